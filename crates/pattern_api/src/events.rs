@@ -93,6 +93,9 @@ pub enum WebSocketEvent {
     Pong {
         timestamp: chrono::DateTime<chrono::Utc>,
     },
+
+    /// Reply to a subscribe or unsubscribe command
+    Subscription(SubscriptionConfirmation),
 }
 
 /// WebSocket command types (client to server)
@@ -78,6 +78,9 @@ pub struct AccessTokenClaims {
     /// Optional permissions (for API keys)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub permissions: Option<Vec<requests::ApiPermission>>,
+    /// API key the token was exchanged for, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
 }
 
 /// JWT claims for refresh tokens
@@ -369,7 +369,7 @@ pub struct CreateApiKeyRequest {
 }
 
 /// API permissions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ApiPermission {
     // Read permissions
@@ -391,6 +391,59 @@ pub enum ApiPermission {
     ManageApiKeys,
 }
 
+impl ApiPermission {
+    /// Every permission, in declaration order
+    pub const ALL: [ApiPermission; 12] = [
+        ApiPermission::ReadAgents,
+        ApiPermission::ReadGroups,
+        ApiPermission::ReadMessages,
+        ApiPermission::ReadMemory,
+        ApiPermission::WriteAgents,
+        ApiPermission::WriteGroups,
+        ApiPermission::SendMessages,
+        ApiPermission::UpdateMemory,
+        ApiPermission::ManageUsers,
+        ApiPermission::ManageMcpServers,
+        ApiPermission::ManageModelProviders,
+        ApiPermission::ManageApiKeys,
+    ];
+
+    /// The snake_case name used on the wire and in storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiPermission::ReadAgents => "read_agents",
+            ApiPermission::ReadGroups => "read_groups",
+            ApiPermission::ReadMessages => "read_messages",
+            ApiPermission::ReadMemory => "read_memory",
+            ApiPermission::WriteAgents => "write_agents",
+            ApiPermission::WriteGroups => "write_groups",
+            ApiPermission::SendMessages => "send_messages",
+            ApiPermission::UpdateMemory => "update_memory",
+            ApiPermission::ManageUsers => "manage_users",
+            ApiPermission::ManageMcpServers => "manage_mcp_servers",
+            ApiPermission::ManageModelProviders => "manage_model_providers",
+            ApiPermission::ManageApiKeys => "manage_api_keys",
+        }
+    }
+}
+
+impl std::fmt::Display for ApiPermission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ApiPermission {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ApiPermission::ALL
+            .into_iter()
+            .find(|p| p.as_str() == s)
+            .ok_or_else(|| format!("unknown permission: {}", s))
+    }
+}
+
 /// Update API key
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateApiKeyRequest {
@@ -2,10 +2,13 @@ use miette::{IntoDiagnostic, Result};
 use owo_colors::OwoColorize;
 use pattern_core::{
     Agent, ModelProvider,
-    agent::{AgentRecord, AgentType, DatabaseAgent},
+    agent::{AgentRecord, AgentType, DatabaseAgent, tool_rules::ToolRule},
     config::PatternConfig,
-    context::heartbeat,
-    data_source::{BlueskyFilter, DataSourceBuilder},
+    context::{AgentHandle, heartbeat},
+    data_source::{
+        BlueskyFilter, DataSourceBuilder, HomeAssistantClient, add_homeassistant_source,
+        create_coordinator_with_agent_info,
+    },
     db::{
         client::DB,
         ops::{self},
@@ -13,11 +16,16 @@ use pattern_core::{
     embeddings::{EmbeddingProvider, cloud::GeminiEmbedder},
     id::{AgentId, RelationId},
     memory::{Memory, MemoryBlock},
-    model::{GenAiClient, ResponseOptions},
+    model::{
+        GenAiClient, ResponseOptions,
+        local::{LocalModelProvider, LocalServerKind},
+    },
+    skill::SkillLibrary,
     tool::{
         ToolRegistry,
-        builtin::{DataSourceTool, MessageTarget},
+        builtin::{DataSourceTool, HomeTool, MessageTarget, SkillTool},
     },
+    usage::Budgets,
 };
 use std::sync::Arc;
 use surrealdb::RecordId;
@@ -25,7 +33,9 @@ use tokio::sync::RwLock;
 use tracing::info;
 
 use crate::{
-    data_sources::get_bluesky_credentials, endpoints::setup_bluesky_endpoint, output::Output,
+    data_sources::{get_bluesky_credentials, get_notification_templates},
+    endpoints::setup_bluesky_endpoint,
+    output::Output,
 };
 
 /// Build a ContextConfig and CompressionStrategy from an AgentConfig with optional overrides
@@ -227,12 +237,12 @@ pub async fn load_model_embedding_providers(
     // Create model provider - use OAuth if available
     let model_provider = {
         #[cfg(feature = "oauth")]
-        {
+        let genai_client = {
             use pattern_core::oauth::resolver::OAuthClientBuilder;
             let oauth_client =
                 OAuthClientBuilder::new(Arc::new(DB.clone()), config.user.id.clone()).build()?;
             // Wrap in GenAiClient with all endpoints available
-            let genai_client = GenAiClient::with_endpoints(
+            GenAiClient::with_endpoints(
                 oauth_client,
                 vec![
                     genai::adapter::AdapterKind::Anthropic,
@@ -241,13 +251,17 @@ pub async fn load_model_embedding_providers(
                     genai::adapter::AdapterKind::Groq,
                     genai::adapter::AdapterKind::Cohere,
                 ],
-            );
-            Arc::new(RwLock::new(genai_client))
-        }
+            )
+        };
         #[cfg(not(feature = "oauth"))]
-        {
-            Arc::new(RwLock::new(GenAiClient::new().await?))
-        }
+        let genai_client = GenAiClient::new().await?;
+
+        // Local server (Ollama, llama.cpp, vLLM) named by the model config or its fallbacks
+        let genai_client = match LocalModelProvider::from_model_config(&config.model)? {
+            Some(local) => genai_client.with_local(local),
+            None => genai_client,
+        };
+        Arc::new(RwLock::new(genai_client))
     };
 
     // Get available models and select the one to use
@@ -281,6 +295,12 @@ pub async fn load_model_embedding_providers(
         {
             // Fall back to the agent's stored model preference
             models.iter().find(|m| &m.id == stored_model).cloned()
+        } else if let Some(kind) = LocalServerKind::from_provider_string(&config.model.provider) {
+            // Whatever the local server has loaded
+            models
+                .iter()
+                .find(|m| LocalServerKind::from_provider_string(&m.provider) == Some(kind))
+                .cloned()
         } else {
             // Default to Gemini models with free tier
             models
@@ -380,6 +400,13 @@ pub async fn load_model_embedding_providers(
         normalize_reasoning_content: Some(true),
         reasoning_effort: Some(genai::chat::ReasoningEffort::Medium),
         custom_headers: None,
+        // An agent's own model config overrides the global fallback chain
+        fallback: config
+            .agent
+            .model
+            .as_ref()
+            .and_then(|m| m.fallback.clone())
+            .or_else(|| config.model.fallback.clone()),
     };
 
     // Enable reasoning mode if the model supports it
@@ -410,7 +437,7 @@ pub async fn create_agent_from_record(
     let tools = ToolRegistry::new();
 
     // Create agent from the record
-    let agent = DatabaseAgent::from_record(
+    let mut agent = DatabaseAgent::from_record(
         record,
         DB.clone(),
         model_provider,
@@ -419,6 +446,7 @@ pub async fn create_agent_from_record(
         heartbeat_sender,
     )
     .await?;
+    agent.set_budgets(Arc::new(Budgets::from_config(config)?));
 
     // Set the chat options with our selected model (do not overwrite max_context_tokens here;
     // DatabaseAgent::from_record already set an appropriate value from provider data)
@@ -429,6 +457,8 @@ pub async fn create_agent_from_record(
 
     // Wrap in Arc before calling monitoring methods
     let agent = Arc::new(agent);
+    register_skill_tool(agent.handle().await, &config.agent, &tools);
+    register_home_assistant(&agent, config, &tools, embedding_provider.clone()).await;
     agent.clone().start_stats_sync().await?;
     agent.clone().start_memory_sync().await?;
     agent.clone().start_message_monitoring().await?;
@@ -469,7 +499,7 @@ pub async fn create_agent_from_record_with_tracker(
     let tools = shared_tools.unwrap_or_else(ToolRegistry::new);
 
     // Create agent from the record
-    let agent = DatabaseAgent::from_record(
+    let mut agent = DatabaseAgent::from_record(
         record.clone(),
         DB.clone(),
         model_provider,
@@ -478,6 +508,7 @@ pub async fn create_agent_from_record_with_tracker(
         heartbeat_sender,
     )
     .await?;
+    agent.set_budgets(Arc::new(Budgets::from_config(config)?));
 
     // Apply context config from the passed PatternConfig
     let (context_config, compression_strategy) =
@@ -669,6 +700,8 @@ pub async fn create_agent_from_record_with_tracker(
 
     // Wrap in Arc before calling monitoring methods
     let agent = Arc::new(agent);
+    register_skill_tool(agent.handle().await, &config.agent, &tools);
+    register_home_assistant(&agent, config, &tools, embedding_provider).await;
 
     // Start all monitoring tasks in parallel
     let agent_stats = agent.clone();
@@ -712,6 +745,92 @@ pub async fn create_agent_from_record_with_tracker(
     Ok(agent_dyn)
 }
 
+/// Register the skill tool when the agent's own config declares skill directories
+fn register_skill_tool(
+    handle: AgentHandle,
+    agent_config: &pattern_core::config::AgentConfig,
+    tools: &ToolRegistry,
+) {
+    if agent_config.skills.is_empty() {
+        return;
+    }
+    let library = SkillLibrary::new(agent_config.skills.clone());
+    tools.register(SkillTool::new(handle, library).with_registry(tools.clone()));
+}
+
+/// Give the agent the `home` tool and HomeAssistant notifications when
+/// `[homeassistant]` is configured for it
+///
+/// The source gets its own coordinator without a `data_source` tool, so it
+/// never replaces the Bluesky one; the `home` tool covers on-demand reads.
+async fn register_home_assistant<M, E>(
+    agent: &Arc<DatabaseAgent<M, E>>,
+    config: &PatternConfig,
+    tools: &ToolRegistry,
+    embedding_provider: Option<Arc<E>>,
+) where
+    E: EmbeddingProvider + Clone + 'static,
+    M: ModelProvider + 'static,
+{
+    let Some(ha_config) = config
+        .homeassistant
+        .as_ref()
+        .filter(|ha| ha.applies_to(&agent.name()))
+    else {
+        return;
+    };
+
+    let base_url = match ha_config.base_url() {
+        Ok(url) => url,
+        Err(e) => {
+            tracing::warn!(
+                "Invalid HomeAssistant base_url '{}': {}",
+                ha_config.base_url,
+                e
+            );
+            return;
+        }
+    };
+    let Ok(token) = std::env::var(&ha_config.token_env) else {
+        tracing::warn!(
+            "HomeAssistant is configured but {} is not set, skipping",
+            ha_config.token_env
+        );
+        return;
+    };
+    let client = HomeAssistantClient::new(base_url, token);
+
+    tools.register(
+        HomeTool::new(client.clone()).with_service_domains(ha_config.service_domains.clone()),
+    );
+    if ha_config.require_consent {
+        agent
+            .add_tool_rule(ToolRule::requires_consent(
+                "home".to_string(),
+                Some("homeassistant".to_string()),
+            ))
+            .await;
+    }
+
+    let mut coordinator = create_coordinator_with_agent_info(
+        agent.id(),
+        agent.name(),
+        DB.clone(),
+        embedding_provider,
+    );
+    if let Some(templates) = get_notification_templates(&config.agent) {
+        coordinator.set_templates(templates);
+    }
+    let filter = ha_config.filter();
+    let owner_id = config.user.id.clone();
+    // Connecting can take a while if the instance is unreachable
+    tokio::spawn(async move {
+        if let Err(e) = add_homeassistant_source(&mut coordinator, client, filter, owner_id).await {
+            tracing::error!("Failed to connect to HomeAssistant: {}", e);
+        }
+    });
+}
+
 pub async fn register_data_sources<M, E>(
     agent: Arc<DatabaseAgent<M, E>>,
     config: &PatternConfig,
@@ -753,6 +872,7 @@ pub async fn register_data_sources_with_target<M, E>(
             tracing::info!("filter: {:?}", filter);
             let data_sources = if let Some(target) = target {
                 DataSourceBuilder::new()
+                    .with_templates(get_notification_templates(&config.agent))
                     .with_custom_bluesky_source(
                         "bluesky_jetstream".to_string(),
                         config
@@ -778,6 +898,7 @@ pub async fn register_data_sources_with_target<M, E>(
                     .unwrap()
             } else {
                 DataSourceBuilder::new()
+                    .with_templates(get_notification_templates(&config.agent))
                     .with_custom_bluesky_source(
                         "bluesky_jetstream".to_string(),
                         config
@@ -904,7 +1025,7 @@ pub async fn create_agent(
     }
 
     // Create agent
-    let agent = DatabaseAgent::new(
+    let mut agent = DatabaseAgent::new(
         agent_id.clone(),
         user_id,
         AgentType::Generic,
@@ -918,6 +1039,7 @@ pub async fn create_agent(
         heartbeat_sender,
         tool_rules,
     );
+    agent.set_budgets(Arc::new(Budgets::from_config(config)?));
 
     // Update the agent with the full context config
     agent
@@ -948,6 +1070,8 @@ pub async fn create_agent(
 
     // Wrap in Arc before calling monitoring methods
     let agent = Arc::new(agent);
+    register_skill_tool(agent.handle().await, &config.agent, &tools);
+    register_home_assistant(&agent, config, &tools, embedding_provider.clone()).await;
     agent.clone().start_stats_sync().await?;
     agent.clone().start_memory_sync().await?;
     agent.clone().start_message_monitoring().await?;
@@ -1277,6 +1401,9 @@ pub async fn load_or_create_agent_from_member(
                                     .and_then(|cfg| cfg.agent.bluesky_handle.clone()),
                                 tool_rules: Vec::new(),
                                 tools: Vec::new(),
+                                skills: Vec::new(),
+                                notification_templates: Default::default(),
+                                notification_templates_dir: None,
                                 model: None,
                                 context: None,
                             }
@@ -1304,6 +1431,9 @@ pub async fn load_or_create_agent_from_member(
                             .and_then(|cfg| cfg.agent.bluesky_handle.clone()),
                         tool_rules: Vec::new(),
                         tools: Vec::new(),
+                        skills: Vec::new(),
+                        notification_templates: Default::default(),
+                        notification_templates_dir: None,
                         model: None,
                         context: None,
                     }
@@ -1323,6 +1453,7 @@ pub async fn load_or_create_agent_from_member(
                         model: model_name.clone(),
                         temperature: None,
                         settings: Default::default(),
+                        fallback: None,
                     }
                 };
 
@@ -1339,6 +1470,11 @@ pub async fn load_or_create_agent_from_member(
                     groups: vec![],
                     bluesky: None,
                     discord: None,
+                    homeassistant: main_config.and_then(|cfg| cfg.homeassistant.clone()),
+                    policy: None,
+                    budgets: main_config
+                        .map(|cfg| cfg.budgets.clone())
+                        .unwrap_or_default(),
                 };
 
                 // Create the agent with the specified ID
@@ -1419,6 +1555,9 @@ pub async fn load_or_create_agent_from_member(
                                     .and_then(|cfg| cfg.agent.bluesky_handle.clone()),
                                 tool_rules: Vec::new(),
                                 tools: Vec::new(),
+                                skills: Vec::new(),
+                                notification_templates: Default::default(),
+                                notification_templates_dir: None,
                                 model: None,
                                 context: None,
                             }
@@ -1446,6 +1585,9 @@ pub async fn load_or_create_agent_from_member(
                             .and_then(|cfg| cfg.agent.bluesky_handle.clone()),
                         tool_rules: Vec::new(),
                         tools: Vec::new(),
+                        skills: Vec::new(),
+                        notification_templates: Default::default(),
+                        notification_templates_dir: None,
                         model: None,
                         context: None,
                     }
@@ -1465,6 +1607,7 @@ pub async fn load_or_create_agent_from_member(
                         model: model_name.clone(),
                         temperature: None,
                         settings: Default::default(),
+                        fallback: None,
                     }
                 };
 
@@ -1481,6 +1624,11 @@ pub async fn load_or_create_agent_from_member(
                     groups: vec![],
                     bluesky: None,
                     discord: None,
+                    homeassistant: main_config.and_then(|cfg| cfg.homeassistant.clone()),
+                    policy: None,
+                    budgets: main_config
+                        .map(|cfg| cfg.budgets.clone())
+                        .unwrap_or_default(),
                 };
 
                 // Create the agent with the specified ID
@@ -1547,11 +1695,25 @@ pub async fn load_or_create_agent_from_member(
                         bluesky_handle: bluesky_handle.clone(),
                         tool_rules: Vec::new(),
                         tools: Vec::new(),
+                        skills: Vec::new(),
+                        notification_templates: Default::default(),
+                        notification_templates_dir: None,
                         model: None,
                         context: None,
                     }
                 }
             }
+        } else if let Some(inline) = &member.agent_config {
+            // Inline config carries the member's own skills and templates
+            let mut cfg = inline.clone();
+            cfg.id = Some(agent_id.clone());
+            if cfg.name.is_empty() {
+                cfg.name = agent_record.name.clone();
+            }
+            if cfg.bluesky_handle.is_none() {
+                cfg.bluesky_handle = bluesky_handle.clone();
+            }
+            cfg
         } else {
             // No config_path, use minimal config
             pattern_core::config::AgentConfig {
@@ -1566,6 +1728,9 @@ pub async fn load_or_create_agent_from_member(
                 bluesky_handle: bluesky_handle.clone(),
                 tool_rules: Vec::new(),
                 tools: Vec::new(),
+                skills: Vec::new(),
+                notification_templates: Default::default(),
+                notification_templates_dir: None,
                 model: None,
                 context: None,
             }
@@ -1583,11 +1748,17 @@ pub async fn load_or_create_agent_from_member(
                 model: model_name,
                 temperature: None,
                 settings: Default::default(),
+                fallback: None,
             },
             database: Default::default(),
             groups: vec![],
             bluesky: None,
             discord: None,
+            homeassistant: main_config.and_then(|cfg| cfg.homeassistant.clone()),
+            policy: None,
+            budgets: main_config
+                .map(|cfg| cfg.budgets.clone())
+                .unwrap_or_default(),
         };
 
         return create_agent_from_record(
@@ -1621,6 +1792,7 @@ pub async fn load_or_create_agent_from_member(
                 model: model_name,
                 temperature: None,
                 settings: Default::default(),
+                fallback: None,
             }
         };
 
@@ -1636,6 +1808,11 @@ pub async fn load_or_create_agent_from_member(
             groups: vec![],
             bluesky: None,
             discord: None,
+            homeassistant: main_config.and_then(|cfg| cfg.homeassistant.clone()),
+            policy: None,
+            budgets: main_config
+                .map(|cfg| cfg.budgets.clone())
+                .unwrap_or_default(),
         };
 
         // Use the agent name from config, or fall back to member name
@@ -1679,6 +1856,7 @@ pub async fn load_or_create_agent_from_member(
                 model: model_name,
                 temperature: None,
                 settings: Default::default(),
+                fallback: None,
             }
         };
 
@@ -1702,6 +1880,11 @@ pub async fn load_or_create_agent_from_member(
             groups: vec![],
             bluesky: None,
             discord: None,
+            homeassistant: main_config.and_then(|cfg| cfg.homeassistant.clone()),
+            policy: None,
+            budgets: main_config
+                .map(|cfg| cfg.budgets.clone())
+                .unwrap_or_default(),
         };
 
         // Use the agent name from config, or fall back to member name
@@ -1750,6 +1933,9 @@ pub async fn load_or_create_agent_from_member(
             bluesky_handle: main_config.and_then(|cfg| cfg.agent.bluesky_handle.clone()),
             tool_rules: Vec::new(),
             tools: Vec::new(),
+            skills: Vec::new(),
+            notification_templates: Default::default(),
+            notification_templates_dir: None,
             model: None,
             context: None,
         },
@@ -1758,11 +1944,17 @@ pub async fn load_or_create_agent_from_member(
             model: model_name,
             temperature: None,
             settings: Default::default(),
+            fallback: None,
         },
         database: Default::default(),
         groups: vec![],
         bluesky: None,
         discord: None,
+        homeassistant: main_config.and_then(|cfg| cfg.homeassistant.clone()),
+        policy: None,
+        budgets: main_config
+            .map(|cfg| cfg.budgets.clone())
+            .unwrap_or_default(),
     };
 
     create_agent(&member.name, None, enable_tools, &config, heartbeat_sender).await
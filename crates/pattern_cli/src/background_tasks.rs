@@ -58,9 +58,10 @@ pub async fn start_context_sync_monitoring(
 
             // Create a generic trigger check message
             // The sleeptime manager will customize it for the specific agent being activated
-            let trigger_message = Message::user(MessageContent::from_text(
+            let mut trigger_message = Message::user(MessageContent::from_text(
                 "Context sync check: Review your domain and report any notable patterns or concerns. Provide brief status updates only if intervention is needed.",
             ));
+            trigger_message.metadata.group_id = Some(group.id.clone());
 
             // Route the message through the group manager
             match manager
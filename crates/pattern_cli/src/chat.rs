@@ -23,7 +23,7 @@ use crate::{
         create_agent_from_record_with_tracker, load_agent_memories_and_messages,
         load_model_embedding_providers,
     },
-    data_sources::get_bluesky_credentials,
+    data_sources::{get_bluesky_credentials, get_notification_templates, owning_agent_config},
     endpoints::CliEndpoint,
     output::Output,
     slash_commands::handle_slash_command,
@@ -78,6 +78,21 @@ pub async fn chat_with_agent(
         },
     ));
 
+    // Deliver scheduled wakeups
+    let output_clone = output.clone();
+    tokio::spawn(pattern_core::context::scheduler::process_wakeups(
+        DB.clone(),
+        vec![agent.clone()],
+        pattern_core::context::scheduler::DEFAULT_WAKEUP_POLL_INTERVAL,
+        move |event, _agent_id, agent_name| {
+            let output = output_clone.clone();
+            async move {
+                output.status(&format!("⏰ Scheduled wakeup for {}:", agent_name));
+                print_response_event(event, &output);
+            }
+        },
+    ));
+
     // Spawn CLI permission listener
     let _perm_task = crate::permission_sink::spawn_cli_permission_listener(output.clone());
 
@@ -605,6 +620,9 @@ pub async fn setup_group(
                         None
                     }
                 }
+            } else if let Some(inline) = &member.agent_config {
+                output.info("ℹ", "Using member's inline agent config");
+                Some(inline.clone())
             } else {
                 output.info("ℹ", "Member has no config_path");
                 None
@@ -632,10 +650,23 @@ pub async fn setup_group(
                 groups: config.groups.clone(),
                 bluesky: config.bluesky.clone(),
                 discord: config.discord.clone(),
+                homeassistant: config.homeassistant.clone(),
+                policy: config.policy.clone(),
+                budgets: config.budgets.clone(),
             }
         } else {
             output.info("📋", "Using default config (no persona)");
-            config.clone()
+            let mut member_cfg = config.clone();
+            // Skills and templates declared for the main agent don't carry over
+            // to other members
+            let is_main_agent = config.agent.id.as_ref() == Some(&agent_record.id)
+                || config.agent.name == agent_record.name;
+            if !is_main_agent {
+                member_cfg.agent.skills.clear();
+                member_cfg.agent.notification_templates.clear();
+                member_cfg.agent.notification_templates_dir = None;
+            }
+            member_cfg
         };
 
         // Decide special tools based on role/domain instead of hardcoded names
@@ -764,6 +795,7 @@ pub async fn setup_group(
 
     // Create the appropriate pattern manager based on the group's coordination pattern
     use pattern_core::coordination::selectors::DefaultSelectorRegistry;
+    use pattern_core::coordination::triggers::DefaultTriggerEvaluatorRegistry;
     use pattern_core::coordination::types::CoordinationPattern;
     use pattern_core::coordination::{
         DynamicManager, PipelineManager, RoundRobinManager, SleeptimeManager, SupervisorManager,
@@ -778,9 +810,36 @@ pub async fn setup_group(
         CoordinationPattern::Pipeline { .. } => Arc::new(PipelineManager),
         CoordinationPattern::Supervisor { .. } => Arc::new(SupervisorManager),
         CoordinationPattern::Voting { .. } => Arc::new(VotingManager),
-        CoordinationPattern::Sleeptime { .. } => Arc::new(SleeptimeManager),
+        CoordinationPattern::Sleeptime { .. } => Arc::new(
+            SleeptimeManager::new(Arc::new(DefaultTriggerEvaluatorRegistry::new()))
+                .with_activity_tracker(constellation_tracker.clone()),
+        ),
     };
 
+    // Metrics recorded here are what sleeptime threshold triggers watch
+    if !no_tools {
+        use pattern_core::tool::builtin::RecordMetricTool;
+        for (agent, tools) in agents.iter().zip(&agent_tools) {
+            tools.register(RecordMetricTool::new(
+                agent.handle().await,
+                constellation_tracker.clone(),
+            ));
+        }
+    }
+
+    // Voting members cast their ballots through the vote tool
+    if matches!(
+        group.coordination_pattern,
+        CoordinationPattern::Voting { .. }
+    ) && !no_tools
+    {
+        use pattern_core::tool::builtin::VoteTool;
+        for tools in &agent_tools {
+            tools.register(VoteTool::new());
+        }
+        output.success("Vote tool registered for all group members");
+    }
+
     // Initialize group chat (registers CLI and Group endpoints)
     let agents_with_membership =
         init_group_chat(&group, agents.clone(), &pattern_manager, output).await?;
@@ -876,8 +935,10 @@ pub async fn setup_group(
 
                         if sleeptime_agents.len() == agents.len() {
                             // Start background monitoring with a new sleeptime manager
-                            let sleeptime_manager: Arc<dyn GroupManager + Send + Sync> =
-                                Arc::new(pattern_core::coordination::SleeptimeManager);
+                            let sleeptime_manager: Arc<dyn GroupManager + Send + Sync> = Arc::new(
+                                pattern_core::coordination::SleeptimeManager::default()
+                                    .with_activity_tracker(constellation_tracker.clone()),
+                            );
                             let monitoring_handle =
                                 crate::background_tasks::start_context_sync_monitoring(
                                     sleeptime_group.clone(),
@@ -1003,7 +1064,7 @@ pub async fn run_group_chat_loop(
     let output_clone = output.clone();
     tokio::spawn(pattern_core::context::heartbeat::process_heartbeats(
         heartbeat_receiver,
-        agents_for_heartbeat,
+        agents_for_heartbeat.clone(),
         move |event, _agent_id, agent_name| {
             let output = output_clone.clone();
             async move {
@@ -1013,6 +1074,21 @@ pub async fn run_group_chat_loop(
         },
     ));
 
+    // Deliver scheduled wakeups
+    let output_clone = output.clone();
+    tokio::spawn(pattern_core::context::scheduler::process_wakeups(
+        DB.clone(),
+        agents_for_heartbeat,
+        pattern_core::context::scheduler::DEFAULT_WAKEUP_POLL_INTERVAL,
+        move |event, _agent_id, agent_name| {
+            let output = output_clone.clone();
+            async move {
+                output.status(&format!("⏰ Scheduled wakeup for {}:", agent_name));
+                print_response_event(event, &output);
+            }
+        },
+    ));
+
     loop {
         let event = rl.readline().await;
         match event {
@@ -1060,11 +1136,12 @@ pub async fn run_group_chat_loop(
                 rl.add_history_entry(line.clone());
 
                 // Create a message
-                let message = Message {
+                let mut message = Message {
                     content: MessageContent::Text(line.clone()),
                     word_count: line.split_whitespace().count() as u32,
                     ..Default::default()
                 };
+                message.metadata.group_id = Some(group.id.clone());
 
                 // Route through the group
                 output.status("Routing message through group...");
@@ -1357,7 +1434,10 @@ pub async fn chat_with_group_and_jetstream(
 
                 tracing::info!("filter: {:?}", filter);
 
+                let owner_config =
+                    owning_agent_config(config, &pattern_agent.id(), &pattern_agent.name()).await;
                 let data_sources = DataSourceBuilder::new()
+                    .with_templates(get_notification_templates(&owner_config))
                     .with_bluesky_source("bluesky_jetstream".to_string(), filter, true)
                     .build_with_target(
                         pattern_agent.id(),
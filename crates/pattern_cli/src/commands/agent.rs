@@ -340,6 +340,9 @@ pub async fn export(name: &str, output_path: Option<&Path>) -> Result<()> {
             memory: HashMap::new(), // Will be populated from memory blocks
             tool_rules: Vec::new(),
             tools: Vec::new(),
+            skills: Vec::new(),
+            notification_templates: HashMap::new(),
+            notification_templates_dir: None,
             model: None,
             context: None,
         };
@@ -0,0 +1,188 @@
+use std::path::Path;
+
+use chrono::{Duration, NaiveDate, Utc};
+use miette::{IntoDiagnostic, Result};
+use owo_colors::OwoColorize;
+use pattern_core::{
+    config::PatternConfig,
+    context::scheduler,
+    db::{
+        client::DB,
+        ops::{self, EventFilter},
+    },
+    ical,
+    id::AgentId,
+    tool::builtin::render_event_list,
+};
+
+use crate::{commands::export::get_agent_by_name, output::Output};
+
+/// List upcoming events
+pub async fn list(days: i64, agent: Option<&str>, config: &PatternConfig) -> Result<()> {
+    let output = Output::new();
+
+    let now = Utc::now();
+    let mut filter = EventFilter {
+        creator_id: Some(config.user.id.clone()),
+        from: Some(now),
+        to: Some(now + Duration::days(days.max(1))),
+        ..Default::default()
+    };
+    if let Some(agent) = agent {
+        filter.agent_id = Some(resolve_agent(agent, config).await?);
+        output.section(&format!(
+            "Next {} day(s) for {}",
+            days.max(1),
+            agent.bright_cyan()
+        ));
+    } else {
+        output.section(&format!("Next {} day(s)", days.max(1)));
+    }
+
+    let events = ops::list_events(&DB, &filter).await?;
+    if events.is_empty() {
+        output.info("Nothing scheduled", "");
+        output.info(
+            "Hint:",
+            "Import a calendar with: pattern-cli calendar import <file.ics>",
+        );
+        return Ok(());
+    }
+
+    for line in render_event_list(&events).lines() {
+        output.print(&format!("  {}", line));
+    }
+    println!();
+    output.kv("Total", &events.len().to_string());
+
+    Ok(())
+}
+
+/// Import events from an .ics file
+///
+/// Events are matched to earlier imports by their UID, so importing an
+/// updated export of the same calendar moves events rather than duplicating
+/// them. With an agent, events go on its schedule and it is woken
+/// `remind_minutes` before each upcoming one.
+pub async fn import(
+    file: &Path,
+    agent: Option<&str>,
+    remind_minutes: i64,
+    config: &PatternConfig,
+) -> Result<()> {
+    let output = Output::new();
+
+    let contents = tokio::fs::read_to_string(file).await.into_diagnostic()?;
+    let events = ical::parse_ics(&contents, &config.user.id)?;
+    let agent_id = match agent {
+        Some(agent) => Some(resolve_agent(agent, config).await?),
+        None => None,
+    };
+
+    output.section(&format!(
+        "Importing {} event(s) from {}",
+        events.len(),
+        file.display()
+    ));
+
+    let (mut created, mut updated, mut reminders) = (0, 0, 0);
+    for mut event in events {
+        let existing = match &event.ical_uid {
+            Some(uid) => ops::get_event_by_ical_uid(&DB, &config.user.id, uid).await?,
+            None => None,
+        };
+
+        let stored = match existing {
+            Some(existing) => {
+                event.id = existing.id;
+                event.created_at = existing.created_at;
+                let stored = ops::update_event(&DB, &event).await?;
+                scheduler::reschedule_event_reminders(&DB, &stored).await?;
+                if let Some(agent_id) = &agent_id {
+                    ops::schedule_event_for_agent(&DB, &stored.id, agent_id).await?;
+                }
+                updated += 1;
+                stored
+            }
+            None => {
+                created += 1;
+                ops::create_event(&DB, &event, agent_id.as_ref()).await?
+            }
+        };
+
+        if let Some(agent_id) = &agent_id {
+            if remind_minutes >= 0
+                && scheduler::sync_event_reminder(&DB, agent_id, &stored, remind_minutes)
+                    .await?
+                    .is_some()
+            {
+                reminders += 1;
+            }
+        }
+    }
+
+    output.success(&format!(
+        "Imported {} new and {} updated event(s)",
+        created, updated
+    ));
+    if let Some(agent) = agent {
+        output.kv(
+            "Reminders",
+            &format!("{} upcoming event(s) will wake {}", reminders, agent),
+        );
+    }
+
+    Ok(())
+}
+
+/// Export events to an .ics file
+pub async fn export(
+    file: &Path,
+    from: Option<&str>,
+    to: Option<&str>,
+    agent: Option<&str>,
+    config: &PatternConfig,
+) -> Result<()> {
+    let output = Output::new();
+
+    let filter = EventFilter {
+        creator_id: Some(config.user.id.clone()),
+        agent_id: match agent {
+            Some(agent) => Some(resolve_agent(agent, config).await?),
+            None => None,
+        },
+        from: from
+            .map(parse_date)
+            .transpose()?
+            .map(|d| d.and_hms_opt(0, 0, 0).expect("midnight is valid").and_utc()),
+        to: to
+            .map(parse_date)
+            .transpose()?
+            .map(|d| d.and_hms_opt(0, 0, 0).expect("midnight is valid").and_utc()),
+    };
+
+    let events = ops::list_events(&DB, &filter).await?;
+    tokio::fs::write(file, ical::to_ics(&events))
+        .await
+        .into_diagnostic()?;
+
+    output.success(&format!(
+        "Exported {} event(s) to {}",
+        events.len(),
+        file.display().bright_green()
+    ));
+
+    Ok(())
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| miette::miette!("Invalid date '{}': use YYYY-MM-DD", date))
+}
+
+async fn resolve_agent(name: &str, config: &PatternConfig) -> Result<AgentId> {
+    get_agent_by_name(&DB, &config.user.id, name)
+        .await?
+        .map(|agent| agent.id)
+        .ok_or_else(|| miette::miette!("Agent '{}' not found", name))
+}
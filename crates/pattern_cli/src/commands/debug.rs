@@ -6,12 +6,14 @@ use pattern_core::{
     config::PatternConfig,
     context::AgentHandle,
     db::{DatabaseError, DbEntity, client::DB, ops},
+    id::{IdType, MemoryRevisionId},
     memory::{Memory, MemoryBlock, MemoryType},
+    memory_history::{MemoryRevision, RevisionChange, unified_diff},
     message::ChatRole,
 };
 use surrealdb::RecordId;
 
-use crate::output::Output;
+use crate::{commands::export::get_agent_by_name, output::Output};
 
 /// Search conversation history for an agent
 pub async fn search_conversations(
@@ -1098,7 +1100,12 @@ pub async fn show_context(agent_name: &str, config: &PatternConfig) -> Result<()
 }
 
 /// Edit a memory block by exporting to file and reimporting after edits
-pub async fn edit_memory(agent_name: &str, label: &str, file_path: Option<&str>) -> Result<()> {
+pub async fn edit_memory(
+    agent_name: &str,
+    label: &str,
+    file_path: Option<&str>,
+    config: &PatternConfig,
+) -> Result<()> {
     let output = Output::new();
 
     output.section(&format!(
@@ -1171,24 +1178,15 @@ pub async fn edit_memory(agent_name: &str, label: &str, file_path: Option<&str>)
             // Read the edited content
             let new_content = std::fs::read_to_string(&file_path).into_diagnostic()?;
 
-            // Update the memory block
-            let update_sql = r#"
-                UPDATE mem SET value = $value WHERE id = $id
-            "#;
-
-            let mut response = DB
-                .query(update_sql)
-                .bind(("value", new_content.clone()))
-                .bind(("id", RecordId::from(&memory_id)))
-                .await
-                .into_diagnostic()?;
-
-            let memory_models: Vec<<MemoryBlock as DbEntity>::DbModel> =
-                response.take(0).into_diagnostic()?;
-            let _memories: Vec<MemoryBlock> = memory_models
-                .into_iter()
-                .filter_map(|m| MemoryBlock::from_db_model(m).ok())
-                .collect();
+            // Update the memory block, keeping a revision of the old value
+            ops::set_memory_value(
+                &DB,
+                &memory_id,
+                new_content.clone(),
+                Some(&config.user.id),
+                Some("edited with pattern-cli debug edit-memory".to_string()),
+            )
+            .await?;
 
             output.success(&format!(
                 "Memory block '{}' updated successfully!",
@@ -1246,6 +1244,205 @@ Available memory blocks:"
     Ok(())
 }
 
+/// List the revision history of an agent's memory block, newest first
+pub async fn memory_history(
+    agent_name: &str,
+    label: &str,
+    limit: usize,
+    config: &PatternConfig,
+) -> Result<()> {
+    let output = Output::new();
+
+    let block = load_agent_block(agent_name, label, config).await?;
+    output.section(&format!(
+        "History of '{}' for {}",
+        label.bright_cyan(),
+        agent_name.bright_cyan()
+    ));
+
+    let revisions = ops::list_memory_revisions(&DB, &block.id, limit).await?;
+    if revisions.is_empty() {
+        output.info("No revisions recorded", "");
+        return Ok(());
+    }
+
+    for revision in &revisions {
+        let delta = revision.size_delta();
+        let delta = if delta >= 0 {
+            format!("+{}", delta).green().to_string()
+        } else {
+            delta.to_string().red().to_string()
+        };
+        output.print(&format!(
+            "  {} {} {} chars by {}",
+            revision.id.to_key().bright_yellow(),
+            revision
+                .created_at
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string()
+                .dimmed(),
+            delta,
+            revision_author(revision).await
+        ));
+        if revision.change == RevisionChange::Delete {
+            output.kv("    Change", &"removed".red().to_string());
+        }
+        if let Some(call_id) = &revision.tool_call_id {
+            output.kv("    Tool call", &call_id.to_key());
+        }
+        if let Some(note) = &revision.note {
+            output.kv("    Note", note);
+        }
+    }
+    println!();
+    output.info(
+        "Hint:",
+        "Show a change with: pattern-cli debug memory-diff <agent> <label> <revision>",
+    );
+
+    Ok(())
+}
+
+/// Show what a revision changed, or compare the block after two revisions
+///
+/// `to` may be another revision ID or "current".
+pub async fn memory_diff(
+    agent_name: &str,
+    label: &str,
+    revision: &str,
+    to: Option<&str>,
+    config: &PatternConfig,
+) -> Result<()> {
+    let output = Output::new();
+
+    let block = load_agent_block(agent_name, label, config).await?;
+    let from = load_revision(&block, revision).await?;
+
+    let (old, new, title) = match to {
+        None => (
+            from.previous_value.clone(),
+            from.value.clone(),
+            format!("Changes made by {}", from.id.to_key()),
+        ),
+        Some(to) if to.eq_ignore_ascii_case("current") => (
+            from.value.clone(),
+            block.value.clone(),
+            format!("{} → current", from.id.to_key()),
+        ),
+        Some(to) => {
+            let to = load_revision(&block, to).await?;
+            (
+                from.value.clone(),
+                to.value.clone(),
+                format!("{} → {}", from.id.to_key(), to.id.to_key()),
+            )
+        }
+    };
+
+    output.section(&title);
+    let diff = unified_diff(&old, &new, 3);
+    if diff.is_empty() {
+        output.info("No differences", "");
+        return Ok(());
+    }
+    for line in diff.lines() {
+        let line = if line.starts_with('+') {
+            line.green().to_string()
+        } else if line.starts_with('-') {
+            line.red().to_string()
+        } else if line.starts_with("@@") {
+            line.dimmed().to_string()
+        } else {
+            line.to_string()
+        };
+        output.print(&format!("  {}", line));
+    }
+
+    Ok(())
+}
+
+/// Undo a revision, restoring the block to its value from just before it
+pub async fn memory_rollback(
+    agent_name: &str,
+    label: &str,
+    revision: &str,
+    config: &PatternConfig,
+) -> Result<()> {
+    let output = Output::new();
+
+    let block = load_agent_block(agent_name, label, config).await?;
+    let revision = load_revision(&block, revision).await?;
+
+    // Undoing a removal restores the block even if its value never changed
+    let diff = unified_diff(&block.value, &revision.previous_value, 3);
+    if diff.is_empty() && revision.change != RevisionChange::Delete {
+        output.info(
+            "Nothing to do:",
+            &format!("'{}' already has that value", label),
+        );
+        return Ok(());
+    }
+
+    let restored = ops::rollback_memory_revision(&DB, &revision.id, Some(&config.user.id)).await?;
+    output.success(&format!(
+        "Rolled '{}' back to before {}",
+        label.bright_cyan(),
+        revision.id.to_key().bright_yellow()
+    ));
+    output.info(
+        "New length:",
+        &format!("{} chars", restored.value.chars().count()),
+    );
+    output.info(
+        "Note:",
+        "The rollback is recorded as a revision too; see memory-history to undo it",
+    );
+
+    Ok(())
+}
+
+/// Find one of an agent's memory blocks by label
+async fn load_agent_block(
+    agent_name: &str,
+    label: &str,
+    config: &PatternConfig,
+) -> Result<MemoryBlock> {
+    let agent = get_agent_by_name(&DB, &config.user.id, agent_name)
+        .await?
+        .ok_or_else(|| miette::miette!("Agent '{}' not found", agent_name))?;
+    ops::get_memory_by_label(&DB, agent.id, label)
+        .await?
+        .ok_or_else(|| {
+            miette::miette!(
+                "Memory block '{}' not found for agent '{}'",
+                label,
+                agent_name
+            )
+        })
+}
+
+/// Load a revision of `block` by ID ("mem_rev:" prefix optional)
+async fn load_revision(block: &MemoryBlock, id: &str) -> Result<MemoryRevision> {
+    let key = id.trim().strip_prefix("mem_rev:").unwrap_or(id.trim());
+    ops::get_memory_revision(&DB, &MemoryRevisionId(key.to_string()))
+        .await?
+        .filter(|r| r.memory_id == block.id)
+        .ok_or_else(|| miette::miette!("No revision {} of '{}'", key, block.label))
+}
+
+async fn revision_author(revision: &MemoryRevision) -> String {
+    if let Some(agent_id) = &revision.author_agent_id {
+        return match ops::get_entity::<AgentRecord, _>(&DB, agent_id).await {
+            Ok(Some(agent)) => agent.name,
+            _ => agent_id.to_string(),
+        };
+    }
+    if revision.author_user_id.is_some() {
+        return "user".to_string();
+    }
+    "unknown".to_string()
+}
+
 pub async fn modify_memory(
     agent: &String,
     label: &String,
@@ -1,16 +1,26 @@
 //! Export and import commands for agents, groups, and constellations
 
+use indicatif::{HumanBytes, ProgressBar, ProgressStyle};
 use miette::{IntoDiagnostic, Result};
 use owo_colors::OwoColorize;
-use std::path::PathBuf;
-use tokio::fs::File;
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+use tokio::{
+    fs::File,
+    sync::mpsc::{self, UnboundedSender},
+};
 
 use pattern_core::{
-    UserId,
+    CoreError, UserId,
     agent::AgentRecord,
     config::PatternConfig,
     db::{client::DB, ops},
-    export::{AgentExporter, AgentImporter, ExportOptions, ImportOptions},
+    export::{
+        AgentExporter, AgentImporter, CancellationToken, ExportManifest, ExportOptions,
+        ExportProgress, ImportOptions, ImportProgress,
+    },
 };
 
 use crate::output::Output;
@@ -58,10 +68,11 @@ pub async fn export_agent(
 
     let file = File::create(&output_path).await.into_diagnostic()?;
 
-    let manifest = exporter
-        .export_to_car(agent.id, file, options)
-        .await
-        .into_diagnostic()?;
+    let manifest = with_progress(render_export_progress, |progress, cancel| {
+        exporter.export_to_car_with_progress(agent.id, file, options, progress, cancel)
+    })
+    .await;
+    let manifest = finish_export(manifest, &output_path).await?;
 
     output_handler.success(&format!("Export complete!"));
     output_handler.kv("Manifest CID", &manifest.data_cid.to_string());
@@ -76,6 +87,46 @@ pub async fn export_agent(
     Ok(())
 }
 
+/// Export an agent to a Letta agent file
+pub async fn export_agent_letta(
+    name: &str,
+    output: Option<PathBuf>,
+    config: &PatternConfig,
+) -> Result<()> {
+    let output_handler = Output::new();
+
+    let agent = get_agent_by_name(&DB, &config.user.id, name)
+        .await?
+        .ok_or_else(|| miette::miette!("Agent '{}' not found", name))?;
+
+    let output_path =
+        output.unwrap_or_else(|| PathBuf::from(format!("{}.af", name.replace(' ', "-"))));
+
+    output_handler.info(
+        "Exporting",
+        &format!(
+            "agent '{}' to Letta agent file {}",
+            name.bright_cyan(),
+            output_path.display()
+        ),
+    );
+
+    let exporter = AgentExporter::new(DB.clone());
+    let file = File::create(&output_path).await.into_diagnostic()?;
+
+    let agent_file = exporter
+        .export_to_letta(agent.id, file)
+        .await
+        .into_diagnostic()?;
+
+    output_handler.success("Export complete!");
+    output_handler.kv("Messages", &agent_file.messages.len().to_string());
+    output_handler.kv("Memory blocks", &agent_file.core_memory.len().to_string());
+    output_handler.kv("Tool rules", &agent_file.tool_rules.len().to_string());
+
+    Ok(())
+}
+
 /// Export a group to a CAR file
 pub async fn export_group(
     name: &str,
@@ -120,10 +171,17 @@ pub async fn export_group(
 
     let file = File::create(&output_path).await.into_diagnostic()?;
 
-    let manifest = exporter
-        .export_group_to_car(group.id, file, options)
-        .await
-        .into_diagnostic()?;
+    let manifest = with_progress(render_export_progress, |progress, cancel| {
+        exporter.export_group_to_car_with_progress(
+            group.id.clone(),
+            file,
+            options,
+            progress,
+            cancel,
+        )
+    })
+    .await;
+    let manifest = finish_export(manifest, &output_path).await?;
 
     output_handler.success(&format!("Export complete!"));
     output_handler.kv("Manifest CID", &manifest.data_cid.to_string());
@@ -171,10 +229,17 @@ pub async fn export_constellation(
 
     let file = File::create(&output_path).await.into_diagnostic()?;
 
-    let manifest = exporter
-        .export_constellation_to_car(constellation.id, file, options)
-        .await
-        .into_diagnostic()?;
+    let manifest = with_progress(render_export_progress, |progress, cancel| {
+        exporter.export_constellation_to_car_with_progress(
+            constellation.id.clone(),
+            file,
+            options,
+            progress,
+            cancel,
+        )
+    })
+    .await;
+    let manifest = finish_export(manifest, &output_path).await?;
 
     output_handler.success(&format!("Export complete!"));
     output_handler.kv("Manifest CID", &manifest.data_cid.to_string());
@@ -212,30 +277,43 @@ pub async fn import(
         import_memories: true,
     };
 
+    // Detect the type of export from the manifest at the start of the file
     let file = File::open(&file_path).await.into_diagnostic()?;
-
-    // Detect the type of export
-    let (export_type, buffer) = AgentImporter::<surrealdb::engine::any::Any>::detect_type(file)
+    let export_type = AgentImporter::<surrealdb::engine::any::Any>::detect_export_type(file)
         .await
         .into_diagnostic()?;
 
-    // Create a cursor from the buffer for importing
-    let cursor = std::io::Cursor::new(buffer);
+    // Stream the file into the import so reading reports progress and can be cancelled
+    let file = File::open(&file_path).await.into_diagnostic()?;
 
     // Import based on detected type
-    let result = match export_type {
-        pattern_core::export::ExportType::Agent => importer
-            .import_agent_from_car(cursor, options)
-            .await
-            .into_diagnostic()?,
-        pattern_core::export::ExportType::Group => importer
-            .import_group_from_car(cursor, options)
-            .await
-            .into_diagnostic()?,
-        pattern_core::export::ExportType::Constellation => importer
-            .import_constellation_from_car(cursor, options)
-            .await
-            .into_diagnostic()?,
+    let result = with_progress(render_import_progress, |progress, cancel| async move {
+        match export_type {
+            pattern_core::export::ExportType::Agent => {
+                importer
+                    .import_agent_from_car_with_progress(file, options, progress, cancel)
+                    .await
+            }
+            pattern_core::export::ExportType::Group => {
+                importer
+                    .import_group_from_car_with_progress(file, options, progress, cancel)
+                    .await
+            }
+            pattern_core::export::ExportType::Constellation => {
+                importer
+                    .import_constellation_from_car_with_progress(file, options, progress, cancel)
+                    .await
+            }
+        }
+    })
+    .await;
+    let result = match result {
+        Err(CoreError::Cancelled { .. }) => {
+            return Err(miette::miette!(
+                "Import cancelled, agents stored before cancelling were kept"
+            ));
+        }
+        result => result.into_diagnostic()?,
     };
 
     output_handler.success(&format!("Import complete!"));
@@ -258,6 +336,153 @@ pub async fn import(
     Ok(())
 }
 
+/// Import an agent from a Letta agent file
+pub async fn import_letta(
+    file_path: PathBuf,
+    rename_to: Option<String>,
+    config: &PatternConfig,
+) -> Result<()> {
+    let output_handler = Output::new();
+
+    output_handler.info(
+        "Importing",
+        &format!("Letta agent file {}", file_path.display()),
+    );
+
+    let importer = AgentImporter::new(DB.clone());
+
+    // Letta ids don't carry over, so the agent always gets a fresh id
+    let options = ImportOptions {
+        rename_to,
+        merge_existing: false,
+        preserve_ids: false,
+        owner_id: config.user.id.clone(),
+        preserve_timestamps: true,
+        import_messages: true,
+        import_memories: true,
+    };
+
+    let file = File::open(&file_path).await.into_diagnostic()?;
+    let result = importer
+        .import_agent_from_letta(file, options)
+        .await
+        .into_diagnostic()?;
+
+    output_handler.success("Import complete!");
+    output_handler.kv("Messages imported", &result.messages_imported.to_string());
+    output_handler.kv("Memories imported", &result.memories_imported.to_string());
+    for new_id in result.agent_id_map.values() {
+        output_handler.kv("Agent ID", &new_id.to_string());
+    }
+
+    Ok(())
+}
+
+/// Run an export or import while drawing its progress, cancelling it on Ctrl-C
+async fn with_progress<P, T, F, Fut>(render: fn(&ProgressBar, P), run: F) -> pattern_core::Result<T>
+where
+    P: Send + 'static,
+    F: FnOnce(UnboundedSender<P>, CancellationToken) -> Fut,
+    Fut: Future<Output = pattern_core::Result<T>>,
+{
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let cancel = CancellationToken::new();
+
+    let ctrl_c = tokio::spawn({
+        let cancel = cancel.clone();
+        async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancel.cancel();
+            }
+        }
+    });
+
+    // Runs until the sender is dropped along with the finished export/import
+    let renderer = tokio::spawn(async move {
+        let bar = ProgressBar::new(0).with_style(
+            ProgressStyle::with_template("  {prefix:.cyan} [{bar:30}] {pos}/{len} {msg}")
+                .expect("valid progress template")
+                .progress_chars("=> "),
+        );
+        while let Some(event) = receiver.recv().await {
+            render(&bar, event);
+        }
+        bar.finish_and_clear();
+    });
+
+    let result = run(sender, cancel).await;
+    ctrl_c.abort();
+    let _ = renderer.await;
+    result
+}
+
+fn set_bar(bar: &ProgressBar, current: u64, total: u64, message: impl Into<Cow<'static, str>>) {
+    bar.set_length(total.max(current));
+    bar.set_position(current);
+    bar.set_message(message);
+}
+
+fn render_export_progress(bar: &ProgressBar, event: ExportProgress) {
+    match event {
+        ExportProgress::Agent {
+            name,
+            current,
+            total,
+        } => bar.set_prefix(format!("[{}/{}] {}", current, total, name)),
+        ExportProgress::Memories { current, total } => set_bar(bar, current, total, "memories"),
+        ExportProgress::Messages { current, total } => set_bar(bar, current, total, "messages"),
+        ExportProgress::Writing {
+            blocks,
+            total_blocks,
+            bytes,
+        } => {
+            bar.set_prefix("writing");
+            set_bar(
+                bar,
+                blocks,
+                total_blocks,
+                format!("blocks ({})", HumanBytes(bytes)),
+            );
+        }
+    }
+}
+
+fn render_import_progress(bar: &ProgressBar, event: ImportProgress) {
+    match event {
+        ImportProgress::Reading { blocks, bytes } => {
+            bar.set_prefix("reading");
+            set_bar(
+                bar,
+                blocks,
+                blocks,
+                format!("blocks ({})", HumanBytes(bytes)),
+            );
+        }
+        ImportProgress::Agent {
+            name,
+            current,
+            total,
+        } => bar.set_prefix(format!("[{}/{}] {}", current, total, name)),
+        ImportProgress::Memories { current, total } => set_bar(bar, current, total, "memories"),
+        ImportProgress::Messages { current, total } => set_bar(bar, current, total, "messages"),
+    }
+}
+
+/// Turn an export result into the CLI's, removing the partial file of a cancelled export
+async fn finish_export(
+    result: pattern_core::Result<ExportManifest>,
+    output_path: &Path,
+) -> Result<ExportManifest> {
+    if let Err(CoreError::Cancelled { .. }) = &result {
+        let _ = tokio::fs::remove_file(output_path).await;
+        return Err(miette::miette!(
+            "Export cancelled, removed partial file {}",
+            output_path.display()
+        ));
+    }
+    result.into_diagnostic()
+}
+
 // Helper function to get agent by name
 pub async fn get_agent_by_name<C: surrealdb::Connection>(
     db: &surrealdb::Surreal<C>,
@@ -4,11 +4,11 @@ use owo_colors::OwoColorize;
 use pattern_core::{
     config::{
         AgentConfig, GroupConfig, GroupMemberConfig, GroupMemberRoleConfig, GroupPatternConfig,
-        MemoryBlockConfig, ModelConfig, PatternConfig, UserConfig,
+        MemoryBlockConfig, ModelConfig, PatternConfig, PipelineStageConfig, UserConfig,
     },
     coordination::{
         groups::{AgentGroup, GroupMembership},
-        types::{CoordinationPattern, GroupMemberRole, GroupState},
+        types::{CoordinationPattern, GroupMemberRole, GroupState, StageMerge},
     },
     db::{DatabaseConfig, client::DB, ops, ops::get_group_by_name},
     id::{AgentId, GroupId, RelationId, UserId},
@@ -518,10 +518,14 @@ pub async fn convert_pattern_config(
             current_index: 0,
             skip_unavailable: *skip_unavailable,
         },
-        GroupPatternConfig::Pipeline { stages } => {
+        GroupPatternConfig::Pipeline {
+            stages,
+            parallel_stages,
+        } => {
             // Convert stage names to PipelineStage structs
             let mut pipeline_stages = Vec::new();
-            for stage_name in stages {
+            for stage in stages {
+                let stage_name = stage.name();
                 // Find the member with this stage name
                 let agent_ids = if let Some(member) = members.iter().find(|m| &m.name == stage_name)
                 {
@@ -543,7 +547,7 @@ pub async fn convert_pattern_config(
                     // Stage name might be a role or capability, find all matching agents
                     let matching: Vec<AgentId> = members
                         .iter()
-                        .filter(|m| m.capabilities.contains(stage_name))
+                        .filter(|m| m.capabilities.iter().any(|c| c == stage_name))
                         .filter_map(|m| m.agent_id.clone())
                         .collect();
 
@@ -557,16 +561,18 @@ pub async fn convert_pattern_config(
                 };
 
                 pipeline_stages.push(PipelineStage {
-                    name: stage_name.clone(),
+                    name: stage_name.to_string(),
                     agent_ids,
                     timeout: std::time::Duration::from_secs(300), // 5 minute default
                     on_failure: StageFailureAction::Skip,
+                    merge: stage.merge(),
+                    depends_on: stage.depends_on().to_vec(),
                 });
             }
 
             CoordinationPattern::Pipeline {
                 stages: pipeline_stages,
-                parallel_stages: false,
+                parallel_stages: *parallel_stages,
             }
         }
         GroupPatternConfig::Dynamic {
@@ -715,6 +721,9 @@ pub async fn export(name: &str, output_path: Option<&Path>, config: &PatternConf
             memory: HashMap::new(), // Will be populated from memory blocks
             tool_rules: Vec::new(),
             tools: Vec::new(),
+            skills: Vec::new(),
+            notification_templates: HashMap::new(),
+            notification_templates_dir: None,
             model: None,
             context: None,
         };
@@ -779,6 +788,9 @@ pub async fn export(name: &str, output_path: Option<&Path>, config: &PatternConf
             memory: HashMap::new(),
             tool_rules: Vec::new(),
             tools: Vec::new(),
+            skills: Vec::new(),
+            notification_templates: HashMap::new(),
+            notification_templates_dir: None,
             model: None,
             context: None,
         },
@@ -786,7 +798,10 @@ pub async fn export(name: &str, output_path: Option<&Path>, config: &PatternConf
         database: DatabaseConfig::default(),
         bluesky: None,
         discord: None,
+        homeassistant: None,
         groups: vec![group_config.clone()],
+        policy: None,
+        budgets: Vec::new(),
     };
 
     // Debug: try serializing step by step
@@ -847,10 +862,27 @@ fn convert_pattern_to_config(pattern: &CoordinationPattern) -> GroupPatternConfi
                 leader: String::new(), // Default empty string
             }
         }
-        CoordinationPattern::Pipeline { .. } => {
-            // Similar issue - stages are IDs, not names
-            GroupPatternConfig::Pipeline { stages: vec![] }
-        }
+        CoordinationPattern::Pipeline {
+            stages,
+            parallel_stages,
+        } => GroupPatternConfig::Pipeline {
+            // Stages keep the member or capability name they were configured with
+            stages: stages
+                .iter()
+                .map(|stage| {
+                    if stage.merge == StageMerge::default() && stage.depends_on.is_empty() {
+                        PipelineStageConfig::Name(stage.name.clone())
+                    } else {
+                        PipelineStageConfig::Detailed {
+                            name: stage.name.clone(),
+                            merge: stage.merge,
+                            depends_on: stage.depends_on.clone(),
+                        }
+                    }
+                })
+                .collect(),
+            parallel_stages: *parallel_stages,
+        },
         CoordinationPattern::Dynamic {
             selector_name,
             selector_config,
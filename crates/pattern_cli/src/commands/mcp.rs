@@ -0,0 +1,97 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use miette::Result;
+use owo_colors::OwoColorize;
+use pattern_core::{Agent, config::PatternConfig, context::heartbeat, tool::DynamicToolAdapter};
+use pattern_mcp::{DEFAULT_TOOLS, McpServer, MessageAgentTool, ToolRegistry, TransportType};
+
+use crate::output::Output;
+
+/// Serve an agent's or group's tools to MCP clients
+///
+/// The builtin tools come from the named agent, or from the group's supervisor
+/// (falling back to its first member). Every loaded agent can be messaged
+/// through `message_agent`.
+pub async fn serve(
+    agent_name: &str,
+    group_name: Option<&str>,
+    model: Option<String>,
+    http: Option<SocketAddr>,
+    config: &PatternConfig,
+) -> Result<()> {
+    // stdout belongs to the MCP protocol when serving over stdio
+    let output = Output::new().on_stderr();
+
+    let (primary, agents) = if let Some(group_name) = group_name {
+        output.info("Group:", &group_name.bright_cyan().to_string());
+        let setup = crate::chat::setup_group(group_name, model, false, config, &output).await?;
+        let agents: Vec<Arc<dyn Agent>> = setup
+            .agents_with_membership
+            .iter()
+            .map(|awm| awm.agent.clone())
+            .collect();
+        let primary = setup
+            .supervisor_agent
+            .or_else(|| agents.first().cloned())
+            .ok_or_else(|| miette::miette!("Group '{}' has no members", group_name))?;
+        (primary, agents)
+    } else {
+        output.info("Agent:", &agent_name.bright_cyan().to_string());
+        let agent = crate::agent_ops::load_or_create_agent(
+            agent_name,
+            model,
+            true,
+            config,
+            heartbeat::heartbeat_channel().0,
+            &output,
+        )
+        .await?;
+        (agent.clone(), vec![agent])
+    };
+
+    let registry = primary
+        .available_tools()
+        .await
+        .into_iter()
+        .fold(ToolRegistry::builder(), |builder, tool| {
+            builder.with_tool(tool)
+        })
+        .only(DEFAULT_TOOLS)
+        .build();
+    registry.register(Box::new(DynamicToolAdapter::new(MessageAgentTool::new(
+        agents,
+    ))));
+
+    let mut builder = McpServer::builder()
+        .with_name("pattern")
+        .with_instructions(format!(
+            "Tools from the Pattern agent '{}'. Use message_agent to talk to agents directly.",
+            primary.name()
+        ))
+        .with_registry(registry);
+    if let Some(address) = http {
+        builder = builder
+            .with_transport(TransportType::Http)
+            .with_bind_address(address);
+    }
+    let server = builder.build()?;
+
+    output.success(&format!(
+        "Publishing {} tools over {}",
+        server.registry().read().await.len(),
+        match http {
+            Some(address) => format!("http://{}/mcp", address),
+            None => "stdio".to_string(),
+        }
+    ));
+
+    let shutdown = server.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let _ = shutdown.stop().await;
+        }
+    });
+
+    server.start().await?;
+    Ok(())
+}
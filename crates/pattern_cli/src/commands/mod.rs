@@ -2,9 +2,14 @@ pub mod agent;
 pub mod atproto;
 #[cfg(feature = "oauth")]
 pub mod auth;
+pub mod calendar;
 pub mod config;
 pub mod db;
 pub mod debug;
 pub mod export;
 pub mod firehose;
 pub mod group;
+pub mod mcp;
+pub mod permissions;
+pub mod stats;
+pub mod task;
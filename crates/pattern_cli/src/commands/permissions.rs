@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use miette::Result;
+use owo_colors::OwoColorize;
+use pattern_core::{
+    agent::AgentRecord,
+    config::PatternConfig,
+    db::{client::DB, ops},
+    id::{AgentId, IdType, PermissionGrantId},
+    permission::{PermissionAuditAction, broker},
+};
+
+use crate::{commands::export::get_agent_by_name, output::Output};
+
+/// List standing permission grants
+pub async fn list(agent: Option<&str>, all: bool, config: &PatternConfig) -> Result<()> {
+    let output = Output::new();
+
+    let agent_id = match agent {
+        Some(agent) => Some(resolve_agent(agent, config).await?),
+        None => None,
+    };
+    match agent {
+        Some(agent) => output.section(&format!("Permission grants for {}", agent.bright_cyan())),
+        None => output.section("Permission grants"),
+    }
+
+    let grants = broker().list_grants(agent_id.as_ref(), all).await?;
+    if grants.is_empty() {
+        output.info("No standing grants", "");
+        output.info(
+            "Hint:",
+            "Grants are created by approving a request with 'always' or 'ttl=<secs>'",
+        );
+        return Ok(());
+    }
+
+    let mut names = AgentNames::default();
+    let now = chrono::Utc::now();
+    for grant in &grants {
+        let state = if grant.revoked_at.is_some() {
+            "revoked".red().to_string()
+        } else if !grant.is_active_at(now) {
+            "expired".dimmed().to_string()
+        } else {
+            "active".green().to_string()
+        };
+        output.print(&format!(
+            "  {} {} [{}]",
+            grant.id.to_key().bright_yellow(),
+            grant.scope.to_string().bright_cyan(),
+            state
+        ));
+        output.kv("    Agent", &names.get(&grant.agent_id).await);
+        output.kv(
+            "    Granted",
+            &format!(
+                "{} by {}",
+                grant.granted_at.format("%Y-%m-%d %H:%M UTC"),
+                grant.granted_by.as_deref().unwrap_or("unknown")
+            ),
+        );
+        if let Some(expires) = grant.expires_at {
+            output.kv(
+                "    Expires",
+                &expires.format("%Y-%m-%d %H:%M UTC").to_string(),
+            );
+        }
+    }
+    println!();
+    output.kv("Total", &grants.len().to_string());
+
+    Ok(())
+}
+
+/// Revoke a standing grant so the agent has to ask again
+pub async fn revoke(id: &str) -> Result<()> {
+    let output = Output::new();
+
+    let key = id
+        .trim()
+        .strip_prefix("permission_grant:")
+        .unwrap_or(id.trim());
+    match broker()
+        .revoke(&PermissionGrantId(key.to_string()), Some("cli".to_string()))
+        .await?
+    {
+        Some(grant) => output.success(&format!(
+            "Revoked grant {} for {}",
+            key,
+            grant.scope.to_string().bright_cyan()
+        )),
+        None => return Err(miette::miette!("No active grant with id {}", key)),
+    }
+
+    Ok(())
+}
+
+/// Show the most recent permission requests and decisions
+pub async fn audit(agent: Option<&str>, limit: usize, config: &PatternConfig) -> Result<()> {
+    let output = Output::new();
+
+    let agent_id = match agent {
+        Some(agent) => Some(resolve_agent(agent, config).await?),
+        None => None,
+    };
+    output.section("Permission audit log");
+
+    let entries = broker().audit_log(agent_id.as_ref(), limit).await?;
+    if entries.is_empty() {
+        output.info("No permission activity recorded", "");
+        return Ok(());
+    }
+
+    let mut names = AgentNames::default();
+    // Oldest first so it reads like a log
+    for entry in entries.iter().rev() {
+        let action = match entry.action {
+            PermissionAuditAction::Approved | PermissionAuditAction::GrantUsed => {
+                entry.action.to_string().green().to_string()
+            }
+            PermissionAuditAction::Denied
+            | PermissionAuditAction::TimedOut
+            | PermissionAuditAction::Revoked => entry.action.to_string().red().to_string(),
+            PermissionAuditAction::Requested => entry.action.to_string().yellow().to_string(),
+        };
+        let mut line = format!(
+            "  {} {} {} {} via {}",
+            entry.created_at.format("%Y-%m-%d %H:%M:%S").dimmed(),
+            names.get(&entry.agent_id).await.bright_cyan(),
+            action,
+            entry.scope,
+            entry.tool_name
+        );
+        if let Some(decision) = &entry.decision {
+            line.push_str(&format!(" ({})", decision));
+        }
+        if let Some(actor) = &entry.actor {
+            line.push_str(&format!(" by {}", actor));
+        }
+        output.print(&line);
+    }
+
+    Ok(())
+}
+
+/// Agent names looked up once per listing
+#[derive(Default)]
+struct AgentNames(HashMap<AgentId, String>);
+
+impl AgentNames {
+    async fn get(&mut self, agent_id: &AgentId) -> String {
+        if let Some(name) = self.0.get(agent_id) {
+            return name.clone();
+        }
+        let name = match ops::get_entity::<AgentRecord, _>(&DB, agent_id).await {
+            Ok(Some(agent)) => agent.name,
+            _ => agent_id.to_string(),
+        };
+        self.0.insert(agent_id.clone(), name.clone());
+        name
+    }
+}
+
+async fn resolve_agent(name: &str, config: &PatternConfig) -> Result<AgentId> {
+    get_agent_by_name(&DB, &config.user.id, name)
+        .await?
+        .map(|agent| agent.id)
+        .ok_or_else(|| miette::miette!("Agent '{}' not found", name))
+}
@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use miette::{IntoDiagnostic, Result};
+use owo_colors::OwoColorize;
+use pattern_core::{
+    agent::AgentRecord,
+    config::PatternConfig,
+    coordination::groups::AgentGroup,
+    db::{client::DB, ops},
+    id::{AgentId, GroupId},
+    tool::stats::{ToolUsage, tool_stats},
+    usage::{UsageGrouping, UsageTotals, usage_report},
+};
+
+use crate::{commands::export::get_agent_by_name, output::Output};
+
+/// Options for `pattern-cli stats tools`
+pub struct ToolStatsArgs<'a> {
+    pub agent: Option<&'a str>,
+    pub tool: Option<&'a str>,
+    pub since: &'a str,
+    pub until: Option<&'a str>,
+    pub top_errors: usize,
+    pub json: bool,
+}
+
+/// Report tool call counts, error rates, latency and common failures
+pub async fn tools(args: ToolStatsArgs<'_>, config: &PatternConfig) -> Result<()> {
+    let now = Utc::now();
+    let agent_id = match args.agent {
+        Some(name) => Some(resolve_agent(name, config).await?),
+        None => None,
+    };
+    let filter = ops::ToolCallFilter {
+        agent_id,
+        tool_name: args.tool.map(str::to_string),
+        since: parse_time(args.since, now)?,
+        until: args
+            .until
+            .map(|u| parse_time(u, now))
+            .transpose()?
+            .flatten(),
+    };
+
+    let stats = tool_stats(&DB, &filter, args.top_errors).await?;
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&stats).into_diagnostic()?
+        );
+        return Ok(());
+    }
+
+    let output = Output::new();
+    output.section("Tool usage");
+    print_window(&output, stats.since, stats.until);
+    if stats.total_calls == 0 {
+        output.info("No tool calls recorded in this window", "");
+        return Ok(());
+    }
+    output.kv("Calls", &stats.total_calls.to_string());
+    output.kv(
+        "Errors",
+        &format!("{} ({})", stats.total_errors, percent(stats.error_rate())),
+    );
+
+    output.section("By tool");
+    print_tool_table(&output, &stats.by_tool);
+    for usage in stats.by_tool.iter().filter(|u| !u.top_errors.is_empty()) {
+        println!();
+        output.print(&format!("  {} failures:", usage.tool_name.bright_cyan()));
+        for error in &usage.top_errors {
+            output.print(&format!("    {:>4}× {}", error.count, error.message.red()));
+        }
+    }
+
+    output.section("By agent");
+    let mut names = HashMap::new();
+    for agent in &stats.by_agent {
+        let name = agent_name(&mut names, &agent.agent_id).await;
+        output.print(&format!(
+            "  {} — {} calls, {} errors ({})",
+            name.bright_cyan(),
+            agent.calls,
+            agent.errors,
+            percent(agent.error_rate)
+        ));
+        print_tool_table(&output, &agent.tools);
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Options for `pattern-cli stats usage`
+pub struct UsageArgs<'a> {
+    pub by: &'a str,
+    pub agent: Option<&'a str>,
+    pub group: Option<&'a str>,
+    pub source: Option<&'a str>,
+    pub since: &'a str,
+    pub until: Option<&'a str>,
+    pub json: bool,
+}
+
+/// Report model tokens and cost, broken down one way
+pub async fn usage(args: UsageArgs<'_>, config: &PatternConfig) -> Result<()> {
+    let now = Utc::now();
+    let grouping: UsageGrouping = args
+        .by
+        .parse()
+        .map_err(|e: String| miette::miette!("{}", e))?;
+    let agent_id = match args.agent {
+        Some(name) => Some(resolve_agent(name, config).await?),
+        None => None,
+    };
+    let group_id = match args.group {
+        Some(name) => Some(
+            ops::get_group_by_name(&DB, &config.user.id, name)
+                .await?
+                .map(|group| group.id)
+                .ok_or_else(|| miette::miette!("Group '{}' not found", name))?,
+        ),
+        None => None,
+    };
+    let filter = ops::UsageFilter {
+        agent_id,
+        group_id,
+        source: args.source.map(str::to_string),
+        since: parse_time(args.since, now)?,
+        until: args
+            .until
+            .map(|u| parse_time(u, now))
+            .transpose()?
+            .flatten(),
+    };
+
+    let report = usage_report(&DB, &filter, grouping).await?;
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).into_diagnostic()?
+        );
+        return Ok(());
+    }
+
+    let output = Output::new();
+    output.section("Model usage");
+    print_window(&output, report.since, report.until);
+    if report.totals.calls == 0 {
+        output.info("No model calls recorded in this window", "");
+        return Ok(());
+    }
+    output.kv("Calls", &report.totals.calls.to_string());
+    output.kv("Tokens", &token_summary(&report.totals));
+    output.kv("Cost", &cost_summary(&report.totals));
+
+    let heading = match grouping {
+        UsageGrouping::Agent => "By agent",
+        UsageGrouping::Group => "By group",
+        UsageGrouping::Day => "By day",
+        UsageGrouping::Source => "By source",
+        UsageGrouping::Model => "By model",
+    };
+    output.section(heading);
+    output.print(&format!(
+        "  {:<32} {:>7} {:>12} {:>12} {:>10} {:>10}",
+        "".dimmed(),
+        "calls".dimmed(),
+        "prompt".dimmed(),
+        "completion".dimmed(),
+        "cached".dimmed(),
+        "cost $".dimmed()
+    ));
+    let mut agent_names = HashMap::new();
+    for row in &report.rows {
+        let label = match grouping {
+            UsageGrouping::Agent => {
+                agent_name(&mut agent_names, &AgentId(id_key(&row.key).to_string())).await
+            }
+            UsageGrouping::Group => group_name(&row.key).await,
+            _ => row.key.clone(),
+        };
+        output.print(&format!(
+            "  {:<32} {:>7} {:>12} {:>12} {:>10} {:>10}",
+            label.bright_cyan(),
+            row.totals.calls,
+            row.totals.prompt_tokens,
+            row.totals.completion_tokens,
+            row.totals.cached_tokens,
+            format!("{:.4}", row.totals.cost_usd)
+        ));
+    }
+    if report.totals.unpriced_calls > 0 {
+        println!();
+        output.warning(&format!(
+            "{} call(s) used models without known prices and are not in the cost",
+            report.totals.unpriced_calls
+        ));
+    }
+
+    Ok(())
+}
+
+fn token_summary(totals: &UsageTotals) -> String {
+    format!(
+        "{} prompt ({} cached), {} completion ({} reasoning)",
+        totals.prompt_tokens,
+        totals.cached_tokens,
+        totals.completion_tokens,
+        totals.reasoning_tokens
+    )
+}
+
+fn cost_summary(totals: &UsageTotals) -> String {
+    if totals.unpriced_calls == totals.calls {
+        "unknown (no model prices)".to_string()
+    } else {
+        format!("${:.4}", totals.cost_usd)
+    }
+}
+
+/// The key of a "table:key" record ID
+fn id_key(id: &str) -> &str {
+    id.split_once(':').map(|(_, key)| key).unwrap_or(id)
+}
+
+async fn group_name(key: &str) -> String {
+    if !key.starts_with("group:") {
+        return key.to_string();
+    }
+    match ops::get_entity::<AgentGroup, _>(&DB, &GroupId(id_key(key).to_string())).await {
+        Ok(Some(group)) => group.name,
+        _ => key.to_string(),
+    }
+}
+
+async fn agent_name(cache: &mut HashMap<AgentId, String>, agent_id: &AgentId) -> String {
+    if let Some(name) = cache.get(agent_id) {
+        return name.clone();
+    }
+    let name = match ops::get_entity::<AgentRecord, _>(&DB, agent_id).await {
+        Ok(Some(agent)) => agent.name,
+        _ => agent_id.to_string(),
+    };
+    cache.insert(agent_id.clone(), name.clone());
+    name
+}
+
+async fn resolve_agent(name: &str, config: &PatternConfig) -> Result<AgentId> {
+    get_agent_by_name(&DB, &config.user.id, name)
+        .await?
+        .map(|agent| agent.id)
+        .ok_or_else(|| miette::miette!("Agent '{}' not found", name))
+}
+
+fn print_window(output: &Output, since: Option<DateTime<Utc>>, until: Option<DateTime<Utc>>) {
+    output.kv(
+        "Window",
+        &format!(
+            "{} → {}",
+            since
+                .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_else(|| "beginning".to_string()),
+            until
+                .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_else(|| "now".to_string())
+        ),
+    );
+}
+
+fn print_tool_table(output: &Output, usage: &[ToolUsage]) {
+    output.print(&format!(
+        "  {:<28} {:>7} {:>7} {:>7} {:>8} {:>8} {:>8}",
+        "tool".dimmed(),
+        "calls".dimmed(),
+        "errors".dimmed(),
+        "err%".dimmed(),
+        "p50 ms".dimmed(),
+        "p90 ms".dimmed(),
+        "p99 ms".dimmed()
+    ));
+    for tool in usage {
+        let rate = format!("{:>7}", percent(tool.error_rate));
+        let rate = if tool.error_rate >= 0.25 {
+            rate.red().to_string()
+        } else if tool.error_rate > 0.0 {
+            rate.yellow().to_string()
+        } else {
+            rate
+        };
+        output.print(&format!(
+            "  {:<28} {:>7} {:>7} {:>7} {:>8} {:>8} {:>8}",
+            tool.tool_name,
+            tool.calls,
+            tool.errors,
+            rate,
+            tool.latency.p50_ms,
+            tool.latency.p90_ms,
+            tool.latency.p99_ms
+        ));
+    }
+}
+
+fn percent(rate: f64) -> String {
+    format!("{:.1}%", rate * 100.0)
+}
+
+/// Accept "all", a relative age like "30m", "24h", "7d" or "2w", a plain date
+/// or an RFC 3339 timestamp
+fn parse_time(value: &str, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("all") {
+        return Ok(None);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(Some(dt.with_timezone(&Utc)));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(Some(
+            date.and_hms_opt(0, 0, 0)
+                .expect("midnight is a valid time")
+                .and_utc(),
+        ));
+    }
+
+    let invalid = || {
+        miette::miette!(
+            "Invalid time '{}': use an age like 24h or 7d, YYYY-MM-DD, an RFC 3339 timestamp or 'all'",
+            value
+        )
+    };
+    let (split, _) = value.char_indices().last().ok_or_else(invalid)?;
+    let amount: i64 = value
+        .get(..split)
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(invalid)?;
+    let age = match &value[split..] {
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => None,
+    }
+    .ok_or_else(invalid)?;
+    now.checked_sub_signed(age).map(Some).ok_or_else(invalid)
+}
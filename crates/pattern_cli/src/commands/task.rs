@@ -0,0 +1,227 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use miette::Result;
+use owo_colors::OwoColorize;
+use pattern_core::{
+    config::PatternConfig,
+    db::{
+        BaseTask, BaseTaskPriority, BaseTaskStatus,
+        client::DB,
+        ops::{self, TaskFilter},
+    },
+    id::{AgentId, IdType, TaskId},
+    tool::builtin::render_task_list,
+};
+
+use crate::{commands::export::get_agent_by_name, output::Output};
+
+/// List tasks, most pressing first
+pub async fn list(agent: Option<&str>, all: bool, config: &PatternConfig) -> Result<()> {
+    let output = Output::new();
+
+    let mut filter = if all {
+        TaskFilter::default()
+    } else {
+        TaskFilter::open()
+    };
+    filter.creator_id = Some(config.user.id.clone());
+    if let Some(agent) = agent {
+        filter.assigned_agent_id = Some(resolve_agent(agent, config).await?);
+        output.section(&format!("Tasks for {}", agent.bright_cyan()));
+    } else {
+        output.section("Tasks");
+    }
+
+    let tasks = ops::list_tasks(&DB, &filter).await?;
+    if tasks.is_empty() {
+        output.info("No tasks found", "");
+        output.info("Hint:", "Add one with: pattern-cli task add <title>");
+        return Ok(());
+    }
+
+    for line in render_task_list(&tasks).lines() {
+        output.print(&format!("  {}", colorize_status(line)));
+    }
+    println!();
+    output.kv(
+        "Total",
+        &format!(
+            "{} ({} open)",
+            tasks.len(),
+            tasks.iter().filter(|t| t.status.is_open()).count()
+        ),
+    );
+
+    Ok(())
+}
+
+/// Add a task, optionally under a parent and assigned to an agent
+pub async fn add(
+    title: &str,
+    description: Option<&str>,
+    priority: &str,
+    due: Option<&str>,
+    parent: Option<&str>,
+    agent: Option<&str>,
+    config: &PatternConfig,
+) -> Result<()> {
+    let output = Output::new();
+
+    let priority: BaseTaskPriority = priority.parse().map_err(|e| miette::miette!("{}", e))?;
+    let due_date = due.map(parse_due).transpose()?;
+    let parent = match parent {
+        Some(id) => Some(load_task(id, config).await?),
+        None => None,
+    };
+    let assigned_agent_id = match agent {
+        Some(agent) => Some(resolve_agent(agent, config).await?),
+        None => parent.as_ref().and_then(|p| p.assigned_agent_id.clone()),
+    };
+
+    let task = BaseTask {
+        title: title.to_string(),
+        description: description.map(str::to_string),
+        priority,
+        due_date: due_date.or(parent.as_ref().and_then(|p| p.due_date)),
+        creator_id: config.user.id.clone(),
+        assigned_agent_id,
+        parent_task_id: parent.as_ref().map(|p| p.id.clone()),
+        ..Default::default()
+    };
+    let created = ops::create_task(&DB, &task).await?;
+
+    output.success(&format!("Created task '{}'", created.title));
+    print_task(&output, &created);
+
+    Ok(())
+}
+
+/// Change a task's status
+pub async fn set_status(id: &str, status: &str, config: &PatternConfig) -> Result<()> {
+    let output = Output::new();
+
+    let status: BaseTaskStatus = status.parse().map_err(|e| miette::miette!("{}", e))?;
+    let mut task = load_task(id, config).await?;
+    task.status = status;
+    let updated = ops::update_task(&DB, &task).await?;
+
+    output.success(&format!(
+        "Task '{}' is now {}",
+        updated.title,
+        updated.status.to_string().bright_cyan()
+    ));
+
+    Ok(())
+}
+
+/// Assign a task to an agent, or unassign it with "none"
+pub async fn assign(id: &str, agent: &str, config: &PatternConfig) -> Result<()> {
+    let output = Output::new();
+
+    let task = load_task(id, config).await?;
+    let agent_id = if agent.eq_ignore_ascii_case("none") {
+        None
+    } else {
+        Some(resolve_agent(agent, config).await?)
+    };
+    let updated = ops::assign_task(&DB, &task.id, agent_id.as_ref()).await?;
+
+    match agent_id {
+        Some(_) => output.success(&format!(
+            "Assigned '{}' to {}",
+            updated.title,
+            agent.bright_cyan()
+        )),
+        None => output.success(&format!("Unassigned '{}'", updated.title)),
+    }
+
+    Ok(())
+}
+
+/// Show a task with its subtasks
+pub async fn show(id: &str, config: &PatternConfig) -> Result<()> {
+    let output = Output::new();
+
+    let task = load_task(id, config).await?;
+    output.section(&task.title);
+    print_task(&output, &task);
+    if let Some(description) = &task.description {
+        output.kv("Description", description);
+    }
+
+    let subtasks = ops::list_tasks(
+        &DB,
+        &TaskFilter {
+            parent_task_id: Some(task.id.clone()),
+            ..Default::default()
+        },
+    )
+    .await?;
+    if !subtasks.is_empty() {
+        println!();
+        output.info("Subtasks:", "");
+        for line in render_task_list(&subtasks).lines() {
+            output.print(&format!("    {}", colorize_status(line)));
+        }
+    }
+
+    Ok(())
+}
+
+fn print_task(output: &Output, task: &BaseTask) {
+    output.kv("ID", &task.id.to_key());
+    output.kv("Status", &task.status.to_string());
+    output.kv("Priority", &task.priority.to_string());
+    if let Some(due) = task.due_date {
+        output.kv("Due", &due.format("%Y-%m-%d %H:%M UTC").to_string());
+    }
+    if let Some(parent) = &task.parent_task_id {
+        output.kv("Parent", &parent.to_key());
+    }
+    if let Some(agent) = &task.assigned_agent_id {
+        output.kv("Assigned", &agent.to_string());
+    }
+}
+
+/// Dim finished tasks so open ones stand out
+fn colorize_status(line: &str) -> String {
+    if line.contains("[completed]") || line.contains("[cancelled]") {
+        line.dimmed().to_string()
+    } else if line.contains("[in_progress]") {
+        line.bright_yellow().to_string()
+    } else {
+        line.to_string()
+    }
+}
+
+/// Load one of the current user's tasks by id ("task:" prefix optional)
+async fn load_task(id: &str, config: &PatternConfig) -> Result<BaseTask> {
+    let key = id.trim().strip_prefix("task:").unwrap_or(id.trim());
+    ops::get_task(&DB, &TaskId(key.to_string()))
+        .await?
+        .filter(|t| t.creator_id == config.user.id)
+        .ok_or_else(|| miette::miette!("No task with id {}", key))
+}
+
+async fn resolve_agent(name: &str, config: &PatternConfig) -> Result<AgentId> {
+    get_agent_by_name(&DB, &config.user.id, name)
+        .await?
+        .map(|agent| agent.id)
+        .ok_or_else(|| miette::miette!("Agent '{}' not found", name))
+}
+
+/// Accept an RFC 3339 timestamp or a plain date (due at the end of that day, UTC)
+fn parse_due(due: &str) -> Result<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(due) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(due, "%Y-%m-%d").map_err(|_| {
+        miette::miette!(
+            "Invalid due date '{}': use YYYY-MM-DD or an RFC 3339 timestamp",
+            due
+        )
+    })?;
+    Ok(date
+        .and_hms_opt(23, 59, 59)
+        .expect("end of day is a valid time")
+        .and_utc())
+}
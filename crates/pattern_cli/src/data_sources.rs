@@ -1,10 +1,15 @@
 //! Data source setup and configuration
 //!
-//! This module handles credential management for data sources like Bluesky/ATProto.
+//! This module handles credential management for data sources like Bluesky/ATProto
+//! and the agent's notification templates.
+
+use std::sync::Arc;
 
 use pattern_core::{
-    config::PatternConfig,
+    config::{AgentConfig, PatternConfig},
     db::{client::DB, ops::atproto::get_user_atproto_identities},
+    id::AgentId,
+    prompt_template::TemplateRegistry,
 };
 
 /// Get Bluesky credentials from configuration
@@ -40,3 +45,61 @@ pub async fn get_bluesky_credentials(
     }
     None
 }
+
+/// Get the agent's notification templates, if it overrides any
+pub fn get_notification_templates(agent_config: &AgentConfig) -> Option<Arc<TemplateRegistry>> {
+    match agent_config.get_notification_templates() {
+        Ok(templates) => templates.map(Arc::new),
+        Err(e) => {
+            tracing::warn!(
+                "Failed to load notification templates for {}, using the defaults: {}",
+                agent_config.name,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Find the config of the agent that owns a data source
+///
+/// The main agent uses `config.agent`; group members use their own
+/// `config_path` or inline config. Members without either get an empty config
+/// rather than inheriting the main agent's templates.
+pub async fn owning_agent_config(
+    config: &PatternConfig,
+    agent_id: &AgentId,
+    agent_name: &str,
+) -> AgentConfig {
+    if config.agent.id.as_ref() == Some(agent_id) || config.agent.name == agent_name {
+        return config.agent.clone();
+    }
+
+    let member = config
+        .groups
+        .iter()
+        .flat_map(|group| &group.members)
+        .find(|m| m.agent_id.as_ref() == Some(agent_id) || m.name == agent_name);
+    if let Some(member) = member {
+        if let Some(config_path) = &member.config_path {
+            match AgentConfig::load_from_file(config_path).await {
+                Ok(cfg) => return cfg,
+                Err(e) => tracing::warn!(
+                    "Failed to load config for {} from {}: {}",
+                    agent_name,
+                    config_path.display(),
+                    e
+                ),
+            }
+        }
+        if let Some(inline) = &member.agent_config {
+            return inline.clone();
+        }
+    }
+
+    AgentConfig {
+        id: Some(agent_id.clone()),
+        name: agent_name.to_string(),
+        ..Default::default()
+    }
+}
@@ -8,7 +8,7 @@
 use crate::{
     agent_ops::load_model_embedding_providers,
     chat::{GroupSetup, run_group_chat_loop, setup_group},
-    data_sources::get_bluesky_credentials,
+    data_sources::{get_bluesky_credentials, get_notification_templates, owning_agent_config},
     endpoints::CliEndpoint,
     output::Output,
 };
@@ -185,7 +185,10 @@ pub async fn run_discord_bot_with_group(
                 .clone();
 
             tracing::info!("Discord: Building data sources with DataSourceBuilder");
+            let owner_config =
+                owning_agent_config(config, &pattern_agent.id(), &pattern_agent.name()).await;
             let data_sources = DataSourceBuilder::new()
+                .with_templates(get_notification_templates(&owner_config))
                 .with_bluesky_source("bluesky_jetstream".to_string(), filter, true)
                 .build_with_target(
                     pattern_agent.id(),
@@ -392,7 +395,7 @@ pub async fn run_discord_bot_with_group(
             let heartbeat_handle =
                 tokio::spawn(pattern_core::context::heartbeat::process_heartbeats(
                     heartbeat_receiver,
-                    agents_for_heartbeat,
+                    agents_for_heartbeat.clone(),
                     move |event, _agent_id, agent_name| {
                         let output = output_clone.clone();
                         async move {
@@ -403,15 +406,32 @@ pub async fn run_discord_bot_with_group(
                     },
                 ));
 
+            // Deliver scheduled wakeups
+            let output_clone = output.clone();
+            let wakeup_handle = tokio::spawn(pattern_core::context::scheduler::process_wakeups(
+                DB.clone(),
+                agents_for_heartbeat,
+                pattern_core::context::scheduler::DEFAULT_WAKEUP_POLL_INTERVAL,
+                move |event, _agent_id, agent_name| {
+                    let output = output_clone.clone();
+                    async move {
+                        output.status(&format!("⏰ Scheduled wakeup for {}:", agent_name));
+                        crate::chat::print_response_event(event, &output);
+                    }
+                },
+            ));
+
             // Run Discord bot
             if let Err(why) = client.start().await {
                 output.error(&format!("Discord bot error: {:?}", why));
                 heartbeat_handle.abort(); // Clean up heartbeat task
+                wakeup_handle.abort();
                 return Err(miette::miette!("Failed to run Discord bot"));
             }
 
-            // Clean up heartbeat task when Discord bot exits
+            // Clean up background tasks when Discord bot exits
             heartbeat_handle.abort();
+            wakeup_handle.abort();
         }
     }
 
@@ -130,6 +130,8 @@ impl MessageEndpoint for GroupCliEndpoint {
             }
         }
 
+        message.metadata.group_id = Some(self.group.id.clone());
+
         let stream = self
             .manager
             .route_message(&self.group, &self.agents, message)
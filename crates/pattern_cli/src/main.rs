@@ -120,11 +120,15 @@ enum Commands {
         #[command(subcommand)]
         cmd: ExportCommands,
     },
-    /// Import from CAR files
+    /// Import from CAR files or Letta agent files
     Import {
-        /// Path to CAR file to import
+        /// Path to the file to import
         file: PathBuf,
 
+        /// Input format (car, letta)
+        #[arg(long, default_value = "car")]
+        format: String,
+
         /// Rename imported entity to this name
         #[arg(long)]
         rename_to: Option<String>,
@@ -133,6 +137,31 @@ enum Commands {
         #[arg(long, default_value_t = true)]
         preserve_ids: bool,
     },
+    /// Serve Pattern tools and agents to MCP clients
+    Mcp {
+        #[command(subcommand)]
+        cmd: McpCommands,
+    },
+    /// Task management
+    Task {
+        #[command(subcommand)]
+        cmd: TaskCommands,
+    },
+    /// Calendar events and iCalendar import/export
+    Calendar {
+        #[command(subcommand)]
+        cmd: CalendarCommands,
+    },
+    /// Standing permission grants and the permission audit log
+    Permissions {
+        #[command(subcommand)]
+        cmd: PermissionCommands,
+    },
+    /// Usage reports
+    Stats {
+        #[command(subcommand)]
+        cmd: StatsCommands,
+    },
 }
 
 #[derive(Subcommand)]
@@ -286,16 +315,19 @@ enum GroupCommands {
 
 #[derive(Subcommand)]
 enum ExportCommands {
-    /// Export an agent to a CAR file
+    /// Export an agent to a CAR file or Letta agent file
     Agent {
         /// Agent name to export
         name: String,
-        /// Output file path (defaults to <name>.car)
+        /// Output file path (defaults to <name>.car, or <name>.af for letta)
         #[arg(short = 'o', long)]
         output: Option<PathBuf>,
         /// Exclude embeddings from export to reduce file size
         #[arg(long)]
         exclude_embeddings: bool,
+        /// Output format (car, letta)
+        #[arg(long, default_value = "car")]
+        format: String,
     },
     /// Export a group with all member agents to a CAR file
     Group {
@@ -389,6 +421,221 @@ enum AtprotoCommands {
     Test,
 }
 
+#[derive(Subcommand)]
+enum McpCommands {
+    /// Serve over stdio, or over streamable HTTP with --http
+    Serve {
+        /// Agent whose tools to publish
+        #[arg(long, default_value = "Lasa", conflicts_with = "group")]
+        agent: String,
+
+        /// Group whose agents to publish
+        #[arg(long, conflicts_with = "agent")]
+        group: Option<String>,
+
+        /// Model to use (e.g. gpt-4o, claude-3-haiku)
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Listen for streamable HTTP clients on this address instead of stdio
+        #[arg(long, value_name = "ADDR")]
+        http: Option<std::net::SocketAddr>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TaskCommands {
+    /// List open tasks (or all with --all)
+    List {
+        /// Only tasks assigned to this agent
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Include completed and cancelled tasks
+        #[arg(long)]
+        all: bool,
+    },
+    /// Add a new task
+    Add {
+        /// Task title
+        title: String,
+
+        /// Longer description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Priority (low, medium, high, critical)
+        #[arg(long, default_value = "medium")]
+        priority: String,
+
+        /// Due date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        due: Option<String>,
+
+        /// Make this a subtask of another task
+        #[arg(long, value_name = "TASK_ID")]
+        parent: Option<String>,
+
+        /// Assign to this agent
+        #[arg(long)]
+        agent: Option<String>,
+    },
+    /// Change a task's status
+    Status {
+        /// Task ID
+        id: String,
+
+        /// New status (pending, in_progress, completed, cancelled)
+        status: String,
+    },
+    /// Assign a task to an agent ("none" to unassign)
+    Assign {
+        /// Task ID
+        id: String,
+
+        /// Agent name
+        agent: String,
+    },
+    /// Show a task and its subtasks
+    Show {
+        /// Task ID
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PermissionCommands {
+    /// List standing permission grants
+    List {
+        /// Only grants for this agent
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Include expired and revoked grants
+        #[arg(long)]
+        all: bool,
+    },
+    /// Revoke a standing permission grant
+    Revoke {
+        /// Grant ID
+        id: String,
+    },
+    /// Show recent permission requests and decisions
+    Audit {
+        /// Only entries for this agent
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Maximum number of entries to show
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum StatsCommands {
+    /// Tool call counts, error rates, latency and common failures
+    Tools {
+        /// Only calls made by this agent
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Only calls to this tool
+        #[arg(long)]
+        tool: Option<String>,
+
+        /// Start of the window: an age like 24h or 7d, a date, a timestamp or "all"
+        #[arg(long, default_value = "7d")]
+        since: String,
+
+        /// End of the window (defaults to now)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Failure messages to show per tool
+        #[arg(long, default_value_t = 3)]
+        top_errors: usize,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Model tokens and cost by agent, group, day, data source or model
+    Usage {
+        /// Break down by agent, group, day, source or model
+        #[arg(long, default_value = "agent")]
+        by: String,
+
+        /// Only calls made by this agent
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Only calls routed through this group
+        #[arg(long)]
+        group: Option<String>,
+
+        /// Only calls triggered by this data source
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Start of the window: an age like 24h or 7d, a date, a timestamp or "all"
+        #[arg(long, default_value = "7d")]
+        since: String,
+
+        /// End of the window (defaults to now)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CalendarCommands {
+    /// List upcoming events
+    List {
+        /// Number of days to look ahead
+        #[arg(long, default_value_t = 7)]
+        days: i64,
+
+        /// Only events on this agent's schedule
+        #[arg(long)]
+        agent: Option<String>,
+    },
+    /// Import events from an .ics file
+    Import {
+        /// Path to the .ics file
+        file: PathBuf,
+
+        /// Put events on this agent's schedule and wake it before each one
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Minutes before each event to wake the agent
+        #[arg(long, default_value_t = 15)]
+        remind: i64,
+    },
+    /// Export events to an .ics file
+    Export {
+        /// Output file path
+        file: PathBuf,
+
+        /// Only events running on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Only events starting before this date (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Only events on this agent's schedule
+        #[arg(long)]
+        agent: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum DebugCommands {
     /// Search archival memory as if you were an agent
@@ -467,6 +714,37 @@ enum DebugCommands {
         #[arg(long)]
         memory_type: Option<String>,
     },
+    /// List the revision history of a memory block
+    MemoryHistory {
+        /// Agent name
+        agent: String,
+        /// Memory block label
+        label: String,
+        /// Maximum number of revisions to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+    /// Show what a memory revision changed, or compare two revisions
+    MemoryDiff {
+        /// Agent name
+        agent: String,
+        /// Memory block label
+        label: String,
+        /// Revision ID
+        revision: String,
+        /// Compare against this revision ID or "current" instead
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// Undo a memory revision, restoring the value from before it
+    MemoryRollback {
+        /// Agent name
+        agent: String,
+        /// Memory block label
+        label: String,
+        /// Revision ID to undo
+        revision: String,
+    },
     /// Clean up message context by removing unpaired/out-of-order messages
     ContextCleanup {
         /// Agent name
@@ -633,6 +911,11 @@ async fn main() -> Result<()> {
         client::init_db(config.database.clone()).await?;
     }
 
+    // Load the permission policy before any agent can call a tool
+    pattern_core::policy::install_from_config(&config).await?;
+    // and reject invalid daily budgets before any agent is built with them
+    pattern_core::usage::Budgets::from_config(&config)?;
+
     // Initialize groups from configuration (skip for auth/atproto/config commands to avoid API key issues)
     let _skip_group_init = matches!(
         &cli.command,
@@ -889,7 +1172,7 @@ async fn main() -> Result<()> {
                 commands::debug::list_all_memory(&agent).await?;
             }
             DebugCommands::EditMemory { agent, label, file } => {
-                commands::debug::edit_memory(&agent, &label, file.as_deref()).await?;
+                commands::debug::edit_memory(&agent, &label, file.as_deref(), &config).await?;
             }
             DebugCommands::SearchConversations {
                 agent,
@@ -930,6 +1213,29 @@ async fn main() -> Result<()> {
             } => {
                 commands::debug::context_cleanup(agent, *interactive, *dry_run, *limit).await?;
             }
+            DebugCommands::MemoryHistory {
+                agent,
+                label,
+                limit,
+            } => {
+                commands::debug::memory_history(agent, label, *limit, &config).await?;
+            }
+            DebugCommands::MemoryDiff {
+                agent,
+                label,
+                revision,
+                to,
+            } => {
+                commands::debug::memory_diff(agent, label, revision, to.as_deref(), &config)
+                    .await?;
+            }
+            DebugCommands::MemoryRollback {
+                agent,
+                label,
+                revision,
+            } => {
+                commands::debug::memory_rollback(agent, label, revision, &config).await?;
+            }
         },
         Commands::Config { cmd } => {
             let output = crate::output::Output::new();
@@ -1017,10 +1323,22 @@ async fn main() -> Result<()> {
                 name,
                 output,
                 exclude_embeddings,
-            } => {
-                commands::export::export_agent(name, output.clone(), *exclude_embeddings, &config)
+                format,
+            } => match format.as_str() {
+                "letta" => {
+                    commands::export::export_agent_letta(name, output.clone(), &config).await?
+                }
+                "car" => {
+                    commands::export::export_agent(
+                        name,
+                        output.clone(),
+                        *exclude_embeddings,
+                        &config,
+                    )
                     .await?
-            }
+                }
+                other => miette::bail!("Unknown export format '{}' (expected car or letta)", other),
+            },
             ExportCommands::Group {
                 name,
                 output,
@@ -1039,12 +1357,140 @@ async fn main() -> Result<()> {
         },
         Commands::Import {
             file,
+            format,
             rename_to,
             preserve_ids,
-        } => {
-            commands::export::import(file.clone(), rename_to.clone(), *preserve_ids, &config)
+        } => match format.as_str() {
+            "letta" => {
+                commands::export::import_letta(file.clone(), rename_to.clone(), &config).await?
+            }
+            "car" => {
+                commands::export::import(file.clone(), rename_to.clone(), *preserve_ids, &config)
+                    .await?
+            }
+            other => miette::bail!("Unknown import format '{}' (expected car or letta)", other),
+        },
+        Commands::Mcp { cmd } => match cmd {
+            McpCommands::Serve {
+                agent,
+                group,
+                model,
+                http,
+            } => {
+                commands::mcp::serve(agent, group.as_deref(), model.clone(), *http, &config).await?
+            }
+        },
+        Commands::Task { cmd } => match cmd {
+            TaskCommands::List { agent, all } => {
+                commands::task::list(agent.as_deref(), *all, &config).await?
+            }
+            TaskCommands::Add {
+                title,
+                description,
+                priority,
+                due,
+                parent,
+                agent,
+            } => {
+                commands::task::add(
+                    title,
+                    description.as_deref(),
+                    priority,
+                    due.as_deref(),
+                    parent.as_deref(),
+                    agent.as_deref(),
+                    &config,
+                )
                 .await?
-        }
+            }
+            TaskCommands::Status { id, status } => {
+                commands::task::set_status(id, status, &config).await?
+            }
+            TaskCommands::Assign { id, agent } => {
+                commands::task::assign(id, agent, &config).await?
+            }
+            TaskCommands::Show { id } => commands::task::show(id, &config).await?,
+        },
+        Commands::Calendar { cmd } => match cmd {
+            CalendarCommands::List { days, agent } => {
+                commands::calendar::list(*days, agent.as_deref(), &config).await?
+            }
+            CalendarCommands::Import {
+                file,
+                agent,
+                remind,
+            } => commands::calendar::import(file, agent.as_deref(), *remind, &config).await?,
+            CalendarCommands::Export {
+                file,
+                from,
+                to,
+                agent,
+            } => {
+                commands::calendar::export(
+                    file,
+                    from.as_deref(),
+                    to.as_deref(),
+                    agent.as_deref(),
+                    &config,
+                )
+                .await?
+            }
+        },
+        Commands::Permissions { cmd } => match cmd {
+            PermissionCommands::List { agent, all } => {
+                commands::permissions::list(agent.as_deref(), *all, &config).await?
+            }
+            PermissionCommands::Revoke { id } => commands::permissions::revoke(id).await?,
+            PermissionCommands::Audit { agent, limit } => {
+                commands::permissions::audit(agent.as_deref(), *limit, &config).await?
+            }
+        },
+        Commands::Stats { cmd } => match cmd {
+            StatsCommands::Tools {
+                agent,
+                tool,
+                since,
+                until,
+                top_errors,
+                json,
+            } => {
+                commands::stats::tools(
+                    commands::stats::ToolStatsArgs {
+                        agent: agent.as_deref(),
+                        tool: tool.as_deref(),
+                        since,
+                        until: until.as_deref(),
+                        top_errors: *top_errors,
+                        json: *json,
+                    },
+                    &config,
+                )
+                .await?
+            }
+            StatsCommands::Usage {
+                by,
+                agent,
+                group,
+                source,
+                since,
+                until,
+                json,
+            } => {
+                commands::stats::usage(
+                    commands::stats::UsageArgs {
+                        by,
+                        agent: agent.as_deref(),
+                        group: group.as_deref(),
+                        source: source.as_deref(),
+                        since,
+                        until: until.as_deref(),
+                        json: *json,
+                    },
+                    &config,
+                )
+                .await?
+            }
+        },
     }
 
     // Flush any remaining logs before exit
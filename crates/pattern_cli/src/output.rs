@@ -9,6 +9,8 @@ pub struct Output {
     skin: MadSkin,
     reasoning_skin: MadSkin,
     writer: Option<SharedWriter>,
+    /// Write to stderr instead of stdout, for when stdout carries a protocol
+    stderr: bool,
 }
 
 impl Output {
@@ -52,12 +54,14 @@ impl Output {
                 skin,
                 reasoning_skin,
                 writer: Some(shared),
+                stderr: false,
             }
         } else {
             Self {
                 skin,
                 reasoning_skin,
                 writer: None,
+                stderr: false,
             }
         }
     }
@@ -67,6 +71,15 @@ impl Output {
             skin: self.skin,
             reasoning_skin: self.reasoning_skin,
             writer: Some(writer),
+            stderr: self.stderr,
+        }
+    }
+
+    /// Send all output to stderr
+    pub fn on_stderr(self) -> Self {
+        Self {
+            stderr: true,
+            ..self
         }
     }
 
@@ -79,6 +92,8 @@ impl Output {
             let _ = writeln!(writer, "{}", content);
             // Force flush to ensure immediate output
             let _ = writer.flush();
+        } else if self.stderr {
+            eprintln!("{}", content);
         } else {
             // Fallback to regular println
             println!("{}", content);
@@ -94,7 +109,7 @@ impl Output {
             // Clone the writer to get a mutable version
             let mut writer = writer.clone();
             let _ = writer.flush();
-        } else {
+        } else if !self.stderr {
             use std::io::{self, Write};
             let _ = io::stdout().flush();
         }
@@ -107,6 +122,8 @@ impl Output {
             let mut writer = writer.clone();
             // When using SharedWriter, it handles the synchronization
             let _ = writeln!(writer, "{}", content);
+        } else if self.stderr {
+            eprintln!("{}", content);
         } else {
             // Fallback to regular println
             println!("{}", content);
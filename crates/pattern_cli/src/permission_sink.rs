@@ -18,7 +18,7 @@ pub fn spawn_cli_permission_listener(output: Output) -> JoinHandle<()> {
                     output.section("Permission Requested");
                     output.kv("Request ID", &id);
                     output.kv("Tool", &tool_name);
-                    output.kv("Scope", &format!("{:?}", scope));
+                    output.kv("Scope", &scope.to_string());
                     output.status("Type /permit <id> [once|always|ttl=600] or /deny <id>");
                 }
                 Err(_) => break,
@@ -37,7 +37,9 @@ pub async fn cli_permit(id: &str, mode: Option<&str>) -> miette::Result<()> {
         }
         _ => PermissionDecisionKind::ApproveOnce,
     };
-    let ok = broker().resolve(id, decision).await;
+    let ok = broker()
+        .resolve_as(id, decision, Some("cli".to_string()))
+        .await;
     if ok {
         Ok(())
     } else {
@@ -46,7 +48,9 @@ pub async fn cli_permit(id: &str, mode: Option<&str>) -> miette::Result<()> {
 }
 
 pub async fn cli_deny(id: &str) -> miette::Result<()> {
-    let ok = broker().resolve(id, PermissionDecisionKind::Deny).await;
+    let ok = broker()
+        .resolve_as(id, PermissionDecisionKind::Deny, Some("cli".to_string()))
+        .await;
     if ok {
         Ok(())
     } else {
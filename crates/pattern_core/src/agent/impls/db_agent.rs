@@ -109,6 +109,9 @@ pub struct DatabaseAgent<M, E> {
     /// Tool execution rules for this agent
     tool_rules: Arc<RwLock<crate::agent::tool_rules::ToolRuleEngine>>,
 
+    /// Daily budgets checked before each model call
+    budgets: Arc<crate::usage::Budgets>,
+
     // Cached values to avoid deadlock from block_on
     cached_id: AgentId,
     cached_name: String,
@@ -171,12 +174,167 @@ where
         }
     }
 
+    /// Persist the tokens and cost of a model call
+    async fn record_model_usage(
+        &self,
+        options: &ResponseOptions,
+        response: &Response,
+        origin: &crate::usage::UsageOrigin,
+    ) {
+        let Some(tokens) = &response.metadata.tokens_used else {
+            return;
+        };
+        // Charge the model that actually answered
+        let answered_by = response.metadata.fallback.as_ref().map(|f| f.model_info());
+        let usage = crate::usage::ModelUsage::new(
+            self.cached_id.clone(),
+            answered_by.as_ref().unwrap_or(&options.model_info),
+            crate::usage::TokenCounts::from_usage(tokens),
+            origin,
+        );
+        if let Err(e) = ops::create_model_usage(&self.db, &usage).await {
+            crate::log_error!("Failed to record model usage", e);
+        }
+    }
+
+    /// Hold the agent to its daily budget before calling the model
+    ///
+    /// Returns the options to call the model with, switched to the cheaper
+    /// model if the budget says so, or the reason and end of the pause.
+    async fn apply_budget(
+        &self,
+        mut options: ResponseOptions,
+    ) -> std::result::Result<ResponseOptions, (String, chrono::DateTime<Utc>)> {
+        let budgets = &self.budgets;
+        if budgets.is_empty() {
+            return Ok(options);
+        }
+
+        let mut subject =
+            crate::policy::PolicySubject::new(self.cached_id.clone(), self.cached_name.clone());
+        if budgets.uses_groups() {
+            match ops::get_agent_group_names(&self.db, &self.cached_id).await {
+                Ok(groups) => subject = subject.with_groups(groups),
+                Err(e) => crate::log_error!("Failed to look up groups for budget check", e),
+            }
+        }
+        let Some(budget) = budgets.budget_for(&subject) else {
+            return Ok(options);
+        };
+
+        let now = Utc::now();
+        // An unreadable usage table shouldn't take the agent down with it
+        let spent =
+            match ops::agent_usage_since(&self.db, &self.cached_id, budget.day_start(now)).await {
+                Ok(spent) => spent,
+                Err(e) => {
+                    crate::log_error!("Failed to read model usage for budget check", e);
+                    return Ok(options);
+                }
+            };
+
+        match budget.verdict(&spent, now) {
+            None => Ok(options),
+            Some(crate::usage::BudgetVerdict::Pause { reason, until }) => {
+                tracing::warn!("{}: pausing {} until {}", reason, self.cached_name, until);
+                Err((reason, until))
+            }
+            Some(crate::usage::BudgetVerdict::Downgrade { model, reason }) => {
+                if options.model_info.id != model {
+                    tracing::warn!("{}: switching {} to {}", reason, self.cached_name, model);
+                    options.model_info =
+                        crate::model::defaults::enhance_model_info(crate::model::ModelInfo {
+                            id: model.clone(),
+                            name: model,
+                            provider: options.model_info.provider.clone(),
+                            capabilities: Vec::new(),
+                            context_window: 0,
+                            max_output_tokens: None,
+                            cost_per_1k_prompt_tokens: None,
+                            cost_per_1k_completion_tokens: None,
+                        });
+                    options.max_tokens = Some(crate::model::defaults::calculate_max_tokens(
+                        &options.model_info,
+                        options.max_tokens,
+                    ));
+                }
+                Ok(options)
+            }
+        }
+    }
+
+    /// Complete with the configured model, failing over along its fallback
+    /// chain when the error is one the chain is set to fail over on
+    async fn complete_with_fallback(
+        model: &M,
+        options: &ResponseOptions,
+        request: Request,
+        max_retries: u32,
+    ) -> Result<Response> {
+        let Some(fallback) = options.fallback.as_ref().filter(|f| !f.models.is_empty()) else {
+            return Self::complete_with_retry(model, options, request, max_retries, None).await;
+        };
+
+        let chain: Vec<ResponseOptions> = std::iter::once(options.clone())
+            .chain(
+                fallback
+                    .models
+                    .iter()
+                    .map(|m| fallback.options_for(options, m)),
+            )
+            .collect();
+        let last = chain.len() - 1;
+        let mut reason = None;
+
+        for (i, attempt) in chain.iter().enumerate() {
+            let mut request = request.clone();
+            if i > 0 {
+                crate::model::fallback::adapt_request(&mut request, &attempt.model_info);
+            }
+            // The last model in the chain has nowhere to fail over to
+            let failover = (i < last).then_some(fallback);
+
+            match Self::complete_with_retry(model, attempt, request, max_retries, failover).await {
+                Ok(mut response) => {
+                    if let Some(reason) = reason {
+                        response.metadata.fallback = Some(crate::model::fallback::FallbackNote {
+                            requested_model: options.model_info.id.clone(),
+                            answered_by: attempt.model_info.id.clone(),
+                            provider: attempt.model_info.provider.clone(),
+                            reason,
+                        });
+                    }
+                    return Ok(response);
+                }
+                Err(e) if i < last => {
+                    let Some(trigger) = fallback.trigger_for(&e) else {
+                        return Err(e);
+                    };
+                    tracing::warn!(
+                        "Model {} failed ({}), failing over to {}: {}",
+                        attempt.model_info.id,
+                        trigger,
+                        chain[i + 1].model_info.id,
+                        e
+                    );
+                    reason = Some(trigger);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!("fallback chain always has the primary model")
+    }
+
     /// Retry model completion with exponential backoff for rate limit errors
+    ///
+    /// With a fallback chain, errors the chain fails over on are returned once
+    /// the chain's retry allowance is used up rather than retried here.
     async fn complete_with_retry(
         model: &M,
         options: &ResponseOptions,
         mut request: Request,
         max_retries: u32,
+        failover: Option<&crate::model::fallback::FallbackConfig>,
     ) -> Result<Response> {
         let mut retries = 0;
         let mut backoff_ms = 10000; // Start with 10 seconds
@@ -187,6 +345,10 @@ where
                     return Ok(response);
                 }
                 Err(e) => {
+                    if failover.is_some_and(|f| f.fail_over_now(&e, retries)) {
+                        return Err(e);
+                    }
+
                     // Check if this is the Gemini empty candidates error (match structured error)
                     let is_gemini_empty_candidates = match &e {
                         CoreError::ModelProviderError { cause, .. } => {
@@ -775,6 +937,11 @@ where
         // Add router to handle - endpoints will be registered by the consumer
         context.handle = context.handle.with_message_router(router);
 
+        // Let search tools embed queries for hybrid BM25 + vector retrieval
+        if let Some(embeddings) = &embeddings {
+            context.handle = context.handle.with_embedding_provider(embeddings.clone());
+        }
+
         // Register built-in tools
         let builtin = BuiltinTools::default_for_agent(context.handle());
         builtin.register_all(&context.tools);
@@ -788,6 +955,7 @@ where
             embeddings,
             heartbeat_sender,
             tool_rules: Arc::new(RwLock::new(ToolRuleEngine::new(tool_rules))),
+            budgets: Arc::default(),
             cached_id: agent_id,
             cached_name: name,
             cached_agent_type: agent_type,
@@ -856,6 +1024,11 @@ where
         self.heartbeat_sender = sender;
     }
 
+    /// Set the daily budgets this agent is held to
+    pub fn set_budgets(&mut self, budgets: Arc<crate::usage::Budgets>) {
+        self.budgets = budgets;
+    }
+
     /// Get the embedding provider for this agent
     pub fn embedding_provider(&self) -> Option<Arc<E>> {
         self.embeddings.clone()
@@ -902,6 +1075,7 @@ where
                 block.description = memory_block.description.clone();
                 block.metadata = memory_block.metadata.clone();
                 block.embedding_model = memory_block.embedding_model.clone();
+                block.embedding = memory_block.embedding.clone();
                 block.created_at = memory_block.created_at;
                 block.updated_at = memory_block.updated_at;
                 block.is_active = memory_block.is_active;
@@ -1096,6 +1270,16 @@ where
             metadata.compression_events = record.compression_events;
         }
 
+        // Embed archival memories and archived messages stored without one
+        if agent.embeddings.is_some() {
+            let handle = agent.handle().await;
+            tokio::spawn(async move {
+                if let Err(e) = handle.backfill_embeddings().await {
+                    crate::log_error!("Failed to back-fill embeddings", e);
+                }
+            });
+        }
+
         Ok(agent)
     }
 
@@ -1124,6 +1308,18 @@ where
         Ok(())
     }
 
+    /// Add a tool rule at runtime unless an equivalent rule is already present
+    pub async fn add_tool_rule(&self, rule: ToolRule) {
+        let mut engine = self.tool_rules.write().await;
+        let exists = engine.get_rules().iter().any(|r| {
+            r.tool_name == rule.tool_name
+                && std::mem::discriminant(&r.rule_type) == std::mem::discriminant(&rule.rule_type)
+        });
+        if !exists {
+            engine.add_rule(rule);
+        }
+    }
+
     /// Store the current agent state to the database
     pub async fn store(&self) -> Result<()> {
         // Create an AgentRecord from the current state
@@ -1277,6 +1473,7 @@ where
                             block.description = memory_block.description;
                             block.metadata = memory_block.metadata;
                             block.embedding_model = memory_block.embedding_model;
+                            block.embedding = memory_block.embedding;
                             block.created_at = memory_block.created_at;
                             block.updated_at = memory_block.updated_at;
                             block.is_active = memory_block.is_active;
@@ -1292,9 +1489,9 @@ where
                                 memory_block.label,
                                 memory_block.value
                             );
-                            // Update existing block
+                            // Update existing block; the writer already recorded the revision
                             if let Err(e) =
-                                memory.update_block_value(&memory_block.label, &memory_block.value)
+                                memory.sync_block_value(&memory_block.label, &memory_block.value)
                             {
                                 crate::log_error!(
                                     format!("Failed to update memory block {}", memory_block.label),
@@ -1325,6 +1522,7 @@ where
                             block.description = memory_block.description;
                             block.metadata = memory_block.metadata;
                             block.embedding_model = memory_block.embedding_model;
+                            block.embedding = memory_block.embedding;
                             block.created_at = memory_block.created_at;
                             block.updated_at = memory_block.updated_at;
                             block.is_active = memory_block.is_active;
@@ -1336,7 +1534,7 @@ where
                             agent_id,
                             memory_block.label
                         );
-                        memory.remove_block(&memory_block.label);
+                        memory.sync_block_removal(&memory_block.label);
                     }
                     _ => {
                         tracing::debug!("Ignoring action {:?} for memory block", action);
@@ -1976,6 +2174,20 @@ where
             }
         }
 
+        // Record what changed; revision history is best-effort and never blocks persistence
+        for revision in memory.take_revisions() {
+            let revision = revision.by_agent(context.handle.agent_id.clone());
+            if let Err(e) = ops::create_memory_revision(&self.db, &revision).await {
+                crate::log_error!(
+                    format!(
+                        "Failed to record revision of memory block {}",
+                        revision.label
+                    ),
+                    e
+                );
+            }
+        }
+
         tracing::debug!(
             "Completed memory persistence attempt for {} - blocks marked as persisted individually",
             context.handle.agent_id
@@ -2012,13 +2224,13 @@ where
                 );
 
                 // Add the message to context and get the updated version with sequence numbers
-                let updated_message = {
+                let (updated_message, handle) = {
                     let context = self.context.read().await;
-                    context.add_message(message).await
+                    (context.add_message(message).await, context.handle.clone())
                 };
 
                 // Persist the updated message to DB (now with sequence numbers!)
-                let _ = crate::db::ops::persist_agent_message(
+                let persisted = crate::db::ops::persist_agent_message(
                     &self.db,
                     agent_id,
                     &updated_message,
@@ -2028,6 +2240,13 @@ where
                 .inspect_err(|e| {
                     crate::log_error!("Failed to persist response message", e);
                 });
+
+                // Embed in the background so vector search finds it once archived
+                if persisted.is_ok() && handle.embedding_provider().is_some() {
+                    tokio::spawn(async move {
+                        handle.embed_messages(&[updated_message]).await;
+                    });
+                }
             }
         }
 
@@ -2444,6 +2663,9 @@ where
                 None
             };
 
+            // Group and data source the model calls below are charged to
+            let usage_origin = crate::usage::UsageOrigin::from_metadata(&message.metadata);
+
             // Accumulate agent's response text for constellation logging
             let mut agent_response_text = String::new();
 
@@ -2526,7 +2748,7 @@ where
                 let updated_message = ctx.add_message(message).await;
 
                 // Now persist with the sequence numbers
-                let _ = crate::db::ops::persist_agent_message(
+                let persisted = crate::db::ops::persist_agent_message(
                     &db,
                     &agent_id,
                     &updated_message,
@@ -2536,6 +2758,14 @@ where
                 .inspect_err(|e| {
                     crate::log_error!("Failed to persist incoming message", e);
                 });
+
+                // Embed in the background so vector search finds it once archived
+                if persisted.is_ok() && ctx.handle.embedding_provider().is_some() {
+                    let handle = ctx.handle.clone();
+                    tokio::spawn(async move {
+                        handle.embed_messages(&[updated_message]).await;
+                    });
+                }
             }
 
             // Add tool rules from rule engine to context before building
@@ -2652,6 +2882,31 @@ where
                 .clone()
                 .expect("should have options or default");
 
+            // Over its daily budget the agent either drops to a cheaper model or stops here
+            let options = match self_clone.apply_budget(options).await {
+                Ok(options) => options,
+                Err((reason, until)) => {
+                    context
+                        .read()
+                        .await
+                        .cleanup_errors(current_batch_id, &reason)
+                        .await;
+                    self_clone
+                        .cleanup_temporary_memory_blocks(&loaded_memory_labels)
+                        .await;
+                    // Later messages check the budget again, so the agent
+                    // stays available rather than sitting in a cooldown
+                    release_batch(&context, current_batch_id).await;
+
+                    send_event(ResponseEvent::Error {
+                        message: format!("{}; try again after {}", reason, until),
+                        recoverable: true,
+                    })
+                    .await;
+                    return;
+                }
+            };
+
             // Wait for any pending tool responses with timeout
             if let Some(batch_id) = current_batch_id {
                 let notifier = {
@@ -2712,7 +2967,7 @@ where
             // Get response from model with retry logic
             let response = {
                 let model = model.read().await;
-                match Self::complete_with_retry(&*model, &options, request, 10).await {
+                match Self::complete_with_fallback(&*model, &options, request, 10).await {
                     Ok(resp) => resp,
                     Err(e) => {
                         // Parse error to determine recovery type using reusable function
@@ -2757,6 +3012,10 @@ where
                 }
             };
 
+            self_clone
+                .record_model_usage(&options, &response, &usage_origin)
+                .await;
+
             let mut current_response = response;
             let mut should_continue_after_tools = false;
 
@@ -3395,8 +3654,13 @@ where
 
                     current_response = {
                         let model = model.read().await;
-                        match Self::complete_with_retry(&*model, &options, request_with_tools, 10)
-                            .await
+                        match Self::complete_with_fallback(
+                            &*model,
+                            &options,
+                            request_with_tools,
+                            10,
+                        )
+                        .await
                         {
                             Ok(resp) => resp,
                             Err(e) => {
@@ -3427,6 +3691,9 @@ where
                             }
                         }
                     };
+                    self_clone
+                        .record_model_usage(&options, &current_response, &usage_origin)
+                        .await;
 
                     if current_response.num_tool_calls() == 0 {
                         // Persist any memory changes from tool execution
@@ -3681,27 +3948,7 @@ where
             }
 
             // Remove batch from active set and reset state if no other batches
-            {
-                let mut ctx = context.write().await;
-
-                // Remove current batch from active set
-                if let AgentState::Processing {
-                    ref mut active_batches,
-                } = ctx.handle.state
-                {
-                    if let Some(batch_id) = current_batch_id {
-                        active_batches.remove(&batch_id);
-                    }
-
-                    // If no more active batches, set to Ready
-                    if active_batches.is_empty() {
-                        ctx.handle.state = AgentState::Ready;
-                    }
-                } else {
-                    // Fallback to Ready if not in Processing state
-                    ctx.handle.state = AgentState::Ready;
-                }
-            }
+            release_batch(&context, current_batch_id).await;
 
             // Log to constellation activity tracker if present
             if let Some(tracker) = &context.read().await.constellation_tracker {
@@ -3762,6 +4009,29 @@ where
     }
 }
 
+/// Remove a finished batch from the active set, returning to Ready once no
+/// other batches are in flight
+async fn release_batch(context: &RwLock<AgentContext>, batch_id: Option<crate::SnowflakePosition>) {
+    let mut ctx = context.write().await;
+
+    if let AgentState::Processing {
+        ref mut active_batches,
+    } = ctx.handle.state
+    {
+        if let Some(batch_id) = batch_id {
+            active_batches.remove(&batch_id);
+        }
+
+        // If no more active batches, set to Ready
+        if active_batches.is_empty() {
+            ctx.handle.state = AgentState::Ready;
+        }
+    } else {
+        // Fallback to Ready if not in Processing state
+        ctx.handle.state = AgentState::Ready;
+    }
+}
+
 #[cfg(test)]
 mod tool_rules_integration_tests {
     use crate::agent::tool_rules::ToolRule;
@@ -466,4 +466,117 @@ mod tests {
         let (state, _watch) = agent.state().await;
         assert_eq!(state, AgentState::Ready);
     }
+
+    #[tokio::test]
+    async fn test_agent_accepts_messages_after_budget_pause() {
+        use crate::agent::ResponseEvent;
+        use crate::message::Message;
+        use crate::model::{ModelProvider, ResponseOptions};
+        use crate::usage::{BudgetConfig, Budgets, ModelUsage, TokenCounts, UsageOrigin};
+        use futures::StreamExt;
+
+        let db = client::create_test_db().await.unwrap();
+        let model = Arc::new(RwLock::new(MockModelProvider {
+            response: "Test response".to_string(),
+        }));
+        let model_info = model.read().await.list_models().await.unwrap().remove(0);
+
+        let user = User {
+            id: UserId::generate(),
+            ..Default::default()
+        };
+        let user = create_entity::<User, _>(&db, &user).await.unwrap();
+        let agent_record = AgentRecord {
+            id: AgentId::generate(),
+            name: "BudgetAgent".to_string(),
+            agent_type: AgentType::Generic,
+            owner_id: user.id.clone(),
+            ..Default::default()
+        };
+        let agent_record = create_entity::<AgentRecord, _>(&db, &agent_record)
+            .await
+            .unwrap();
+
+        let (heartbeat_sender, _heartbeat_receiver) =
+            crate::context::heartbeat::heartbeat_channel();
+        let mut agent = DatabaseAgent::new(
+            agent_record.id.clone(),
+            user.id.clone(),
+            AgentType::Generic,
+            "BudgetAgent".to_string(),
+            "I am a budget test agent".to_string(),
+            Memory::with_owner(&user.id),
+            db.clone(),
+            model,
+            ToolRegistry::new(),
+            None::<Arc<MockEmbeddingProvider>>,
+            heartbeat_sender,
+            vec![],
+        );
+        agent.set_budgets(Arc::new(
+            Budgets::new(vec![BudgetConfig {
+                name: Some("test".to_string()),
+                agents: vec!["BudgetAgent".to_string()],
+                groups: vec![],
+                daily_usd: None,
+                daily_tokens: Some(1000),
+                action: Default::default(),
+                downgrade_model: None,
+                timezone: None,
+            }])
+            .unwrap(),
+        ));
+        *agent.chat_options.write().await = Some(ResponseOptions::new(model_info.clone()));
+        let agent = Arc::new(agent);
+
+        // Today's spend is over the limit
+        let mut usage = ModelUsage::new(
+            agent_record.id.clone(),
+            &model_info,
+            TokenCounts {
+                prompt_tokens: 2000,
+                ..Default::default()
+            },
+            &UsageOrigin::default(),
+        );
+        crate::db::ops::create_model_usage(&db, &usage)
+            .await
+            .unwrap();
+
+        let events: Vec<_> = agent
+            .clone()
+            .process_message_stream(Message::user("hello"))
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert!(
+            events
+                .iter()
+                .any(|e| matches!(e, ResponseEvent::Error { .. }))
+        );
+        let (state, _) = agent.state().await;
+        assert_eq!(state, AgentState::Ready);
+
+        // Past the reset, yesterday's spend no longer counts
+        usage.created_at = chrono::Utc::now() - chrono::Duration::days(1);
+        crate::db::ops::update_entity(&db, &usage).await.unwrap();
+
+        let events: Vec<_> = tokio::time::timeout(
+            Duration::from_secs(30),
+            agent
+                .clone()
+                .process_message_stream(Message::user("hello again"))
+                .await
+                .unwrap()
+                .collect(),
+        )
+        .await
+        .expect("agent should not wait for a cooldown");
+        assert!(
+            !events
+                .iter()
+                .any(|e| matches!(e, ResponseEvent::Error { .. }))
+        );
+    }
 }
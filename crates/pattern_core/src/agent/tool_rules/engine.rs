@@ -246,6 +246,16 @@ impl ToolRuleEngine {
         &self.rules
     }
 
+    /// Add a rule, keeping rules ordered by priority
+    pub fn add_rule(&mut self, rule: ToolRule) {
+        let index = self
+            .rules
+            .iter()
+            .position(|r| r.priority < rule.priority)
+            .unwrap_or(self.rules.len());
+        self.rules.insert(index, rule);
+    }
+
     /// Check if a tool can be executed given current state
     pub fn can_execute_tool(&self, tool_name: &str) -> Result<bool, ToolRuleViolation> {
         // First, check if start constraints are satisfied
@@ -661,6 +671,11 @@ impl ToolRule {
     pub fn cooldown(tool_name: String, duration: Duration) -> Self {
         Self::new(tool_name, ToolRuleType::Cooldown(duration)).with_priority(4)
     }
+
+    /// Create a rule requiring user consent before each call
+    pub fn requires_consent(tool_name: String, scope: Option<String>) -> Self {
+        Self::new(tool_name, ToolRuleType::RequiresConsent { scope }).with_priority(8)
+    }
 }
 
 #[cfg(test)]
@@ -841,6 +856,26 @@ mod tests {
         assert_eq!(engine.rules[2].priority, 1);
     }
 
+    #[test]
+    fn test_add_rule_keeps_priority_order() {
+        let mut engine = ToolRuleEngine::new(vec![
+            ToolRule::continue_loop("search".to_string()),
+            ToolRule::max_calls("web".to_string(), 3),
+        ]);
+
+        engine.add_rule(ToolRule::requires_consent(
+            "home".to_string(),
+            Some("homeassistant".to_string()),
+        ));
+
+        assert_eq!(engine.rules[0].tool_name, "home");
+        assert!(matches!(
+            engine.rules[0].rule_type,
+            ToolRuleType::RequiresConsent { .. }
+        ));
+        assert_eq!(engine.rules.len(), 3);
+    }
+
     #[test]
     fn test_reset_engine_state() {
         let rules = vec![ToolRule::max_calls("test_tool".to_string(), 1)];
@@ -13,10 +13,12 @@ use crate::{
     Result,
     agent::tool_rules::ToolRule,
     context::compression::CompressionStrategy,
-    data_source::bluesky::BlueskyFilter,
+    coordination::types::StageMerge,
+    data_source::{bluesky::BlueskyFilter, homeassistant::HomeAssistantFilter},
     db::DatabaseConfig,
     id::{AgentId, GroupId, MemoryId, UserId},
     memory::{MemoryPermission, MemoryType},
+    prompt_template::TemplateRegistry,
 };
 
 /// Resolve a path relative to a base directory
@@ -57,6 +59,19 @@ pub struct PatternConfig {
     /// Discord configuration (non-sensitive options)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub discord: Option<DiscordAppConfig>,
+
+    /// HomeAssistant configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub homeassistant: Option<HomeAssistantConfig>,
+
+    /// Permission policy file (see [`crate::policy`]); defaults to
+    /// `policy.toml` next to the config file when one exists
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<PathBuf>,
+
+    /// Daily spend limits per agent (see [`crate::usage`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budgets: Vec<crate::usage::BudgetConfig>,
 }
 
 /// Discord options in pattern.toml (non-sensitive)
@@ -132,6 +147,20 @@ pub struct AgentConfig {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tools: Vec<String>,
 
+    /// Directories of skill documents the agent can load with the `skill` tool
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<PathBuf>,
+
+    /// Notification templates replacing the built-in ones, keyed by template
+    /// name (e.g. `bluesky_post`, `file_modified`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub notification_templates: HashMap<String, String>,
+
+    /// Directory of `<template name>.j2` files replacing built-in notification
+    /// templates; inline `notification_templates` take precedence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_templates_dir: Option<PathBuf>,
+
     /// Optional model configuration (overrides global model config)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<ModelConfig>,
@@ -312,6 +341,22 @@ impl AgentConfig {
     pub fn set_tool_rules(&mut self, rules: &[ToolRule]) {
         self.tool_rules = rules.iter().map(ToolRuleConfig::from_tool_rule).collect();
     }
+
+    /// Notification templates with this agent's overrides applied, or `None`
+    /// if it doesn't override any and the built-in templates should be used
+    pub fn get_notification_templates(&self) -> Result<Option<TemplateRegistry>> {
+        if self.notification_templates.is_empty() && self.notification_templates_dir.is_none() {
+            return Ok(None);
+        }
+
+        let mut templates = TemplateRegistry::new().with_defaults()?;
+        if let Some(ref templates_dir) = self.notification_templates_dir {
+            templates.load_dir(templates_dir)?;
+        }
+        templates
+            .with_overrides(&self.notification_templates)
+            .map(Some)
+    }
 }
 
 impl AgentConfig {
@@ -392,6 +437,15 @@ impl AgentConfig {
             }
         }
 
+        // Resolve skill directories
+        for skill_dir in config.skills.iter_mut() {
+            *skill_dir = resolve_path(base_dir, skill_dir);
+        }
+
+        if let Some(ref templates_dir) = config.notification_templates_dir {
+            config.notification_templates_dir = Some(resolve_path(base_dir, templates_dir));
+        }
+
         Ok(config)
     }
 }
@@ -582,8 +636,11 @@ pub enum GroupPatternConfig {
     },
     /// Sequential processing pipeline
     Pipeline {
-        /// Ordered list of member names for each stage
-        stages: Vec<String>,
+        /// Ordered list of stages, each a member name or a table with options
+        stages: Vec<PipelineStageConfig>,
+        /// Run all stages at once instead of feeding each into the next
+        #[serde(default)]
+        parallel_stages: bool,
     },
     /// Dynamic selection based on context
     Dynamic {
@@ -609,6 +666,53 @@ fn default_skip_unavailable() -> bool {
     true
 }
 
+/// Configuration for a single pipeline stage
+///
+/// Either a bare member (or capability) name, or a table that also sets how
+/// the stage's outputs are merged and which stages it waits for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PipelineStageConfig {
+    /// Stage with default options
+    Name(String),
+    /// Stage with explicit options
+    Detailed {
+        /// Member or capability name
+        name: String,
+        /// How the outputs of the stage's agents are combined
+        #[serde(default)]
+        merge: StageMerge,
+        /// Names of stages whose output this stage needs (with `parallel_stages`)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        depends_on: Vec<String>,
+    },
+}
+
+impl PipelineStageConfig {
+    /// Member or capability name of the stage
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Name(name) | Self::Detailed { name, .. } => name,
+        }
+    }
+
+    /// How the stage's outputs are merged
+    pub fn merge(&self) -> StageMerge {
+        match self {
+            Self::Name(_) => StageMerge::default(),
+            Self::Detailed { merge, .. } => *merge,
+        }
+    }
+
+    /// Stages this one depends on
+    pub fn depends_on(&self) -> &[String] {
+        match self {
+            Self::Name(_) => &[],
+            Self::Detailed { depends_on, .. } => depends_on,
+        }
+    }
+}
+
 /// Bluesky/ATProto configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlueskyConfig {
@@ -629,6 +733,96 @@ fn default_jetstream_endpoint() -> String {
     "wss://jetstream1.us-east.fire.hose.cam/subscribe".to_string()
 }
 
+/// HomeAssistant configuration
+///
+/// Agents it applies to receive entity state notifications and get the `home`
+/// tool for reading states and calling services. The access token is read from
+/// the environment rather than the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HomeAssistantConfig {
+    /// Base URL of the instance (e.g. "http://homeassistant.local:8123")
+    pub base_url: String,
+
+    /// Environment variable holding a long-lived access token
+    #[serde(default = "default_homeassistant_token_env")]
+    pub token_env: String,
+
+    /// Agents that get HomeAssistant access (all agents if empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<String>,
+
+    /// Entity domains to be notified about (e.g. "sensor", "binary_sensor")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub domains: Vec<String>,
+
+    /// Specific entities to be notified about
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entity_ids: Vec<String>,
+
+    /// Areas to be notified about
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub areas: Vec<String>,
+
+    /// Event types to subscribe to (defaults to "state_changed")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub event_types: Vec<String>,
+
+    /// Minimum seconds between notifications for the same entity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_update_interval: Option<u64>,
+
+    /// Service domains the `home` tool may call
+    #[serde(default = "default_homeassistant_service_domains")]
+    pub service_domains: Vec<String>,
+
+    /// Whether each `home` tool call needs the user's consent
+    #[serde(default = "default_homeassistant_require_consent")]
+    pub require_consent: bool,
+}
+
+fn default_homeassistant_token_env() -> String {
+    "HOMEASSISTANT_TOKEN".to_string()
+}
+
+fn default_homeassistant_service_domains() -> Vec<String> {
+    vec![
+        "light".to_string(),
+        "scene".to_string(),
+        "notify".to_string(),
+    ]
+}
+
+fn default_homeassistant_require_consent() -> bool {
+    true
+}
+
+impl HomeAssistantConfig {
+    /// Whether the agent named `agent_name` gets HomeAssistant access
+    pub fn applies_to(&self, agent_name: &str) -> bool {
+        self.agents.is_empty() || self.agents.iter().any(|a| a == agent_name)
+    }
+
+    /// Parsed base URL of the instance
+    pub fn base_url(&self) -> std::result::Result<url::Url, url::ParseError> {
+        url::Url::parse(&self.base_url)
+    }
+
+    /// Notification filter built from this configuration
+    pub fn filter(&self) -> HomeAssistantFilter {
+        fn non_empty(values: &[String]) -> Option<Vec<String>> {
+            (!values.is_empty()).then(|| values.to_vec())
+        }
+
+        HomeAssistantFilter {
+            domains: non_empty(&self.domains),
+            entity_ids: non_empty(&self.entity_ids),
+            event_types: non_empty(&self.event_types),
+            areas: non_empty(&self.areas),
+            min_update_interval: self.min_update_interval.map(std::time::Duration::from_secs),
+        }
+    }
+}
+
 /// Model provider configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelConfig {
@@ -646,6 +840,10 @@ pub struct ModelConfig {
     /// Additional provider-specific settings
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub settings: HashMap<String, toml::Value>,
+
+    /// Models to fail over to when this one is rate limited or unavailable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<crate::model::fallback::FallbackConfig>,
 }
 
 // Default implementations
@@ -659,6 +857,9 @@ impl Default for PatternConfig {
             groups: Vec::new(),
             bluesky: None,
             discord: None,
+            homeassistant: None,
+            policy: None,
+            budgets: Vec::new(),
         }
     }
 }
@@ -687,6 +888,9 @@ impl Default for AgentConfig {
             bluesky_handle: None,
             tool_rules: Vec::new(),
             tools: Vec::new(),
+            skills: Vec::new(),
+            notification_templates: HashMap::new(),
+            notification_templates_dir: None,
             model: None,
             context: None,
         }
@@ -700,6 +904,7 @@ impl Default for ModelConfig {
             model: None,
             temperature: None,
             settings: HashMap::new(),
+            fallback: None,
         }
     }
 }
@@ -747,6 +952,15 @@ pub async fn load_config(path: &Path) -> Result<PatternConfig> {
         }
     }
 
+    // Resolve the main agent's skill directories
+    for skill_dir in config.agent.skills.iter_mut() {
+        *skill_dir = resolve_path(base_dir, skill_dir);
+    }
+
+    if let Some(ref templates_dir) = config.agent.notification_templates_dir {
+        config.agent.notification_templates_dir = Some(resolve_path(base_dir, templates_dir));
+    }
+
     // Resolve paths in group members
     for group in config.groups.iter_mut() {
         for member in group.members.iter_mut() {
@@ -756,6 +970,12 @@ pub async fn load_config(path: &Path) -> Result<PatternConfig> {
         }
     }
 
+    // Resolve the policy file, picking up policy.toml beside the config by default
+    config.policy = match config.policy.take() {
+        Some(policy_path) => Some(resolve_path(base_dir, &policy_path)),
+        None => Some(base_dir.join(crate::policy::DEFAULT_POLICY_FILE)).filter(|p| p.exists()),
+    };
+
     // Ensure a stable user id:
     // - If the config explicitly specified a user.id, sync the stable-id file to it.
     // - If not specified, load (or create) a stable id and set it on the config, then persist the config back.
@@ -812,6 +1032,9 @@ pub fn merge_configs(base: PatternConfig, overlay: PartialConfig) -> PatternConf
         groups: overlay.groups.unwrap_or(base.groups),
         bluesky: overlay.bluesky.or(base.bluesky),
         discord: base.discord,
+        homeassistant: overlay.homeassistant.or(base.homeassistant),
+        policy: base.policy,
+        budgets: overlay.budgets.unwrap_or(base.budgets),
     }
 }
 
@@ -835,6 +1058,12 @@ pub struct PartialConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bluesky: Option<BlueskyConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub homeassistant: Option<HomeAssistantConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budgets: Option<Vec<crate::usage::BudgetConfig>>,
 }
 
 /// Partial agent configuration for overlaying
@@ -867,6 +1096,15 @@ pub struct PartialAgentConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<String>>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skills: Option<Vec<PathBuf>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_templates: Option<HashMap<String, String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_templates_dir: Option<PathBuf>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<ModelConfig>,
 }
@@ -891,6 +1129,17 @@ fn merge_agent_configs(base: AgentConfig, overlay: PartialAgentConfig) -> AgentC
         bluesky_handle: overlay.bluesky_handle.or(base.bluesky_handle),
         tool_rules: overlay.tool_rules.unwrap_or(base.tool_rules),
         tools: overlay.tools.unwrap_or(base.tools),
+        skills: overlay.skills.unwrap_or(base.skills),
+        notification_templates: if let Some(overlay_templates) = overlay.notification_templates {
+            let mut merged = base.notification_templates;
+            merged.extend(overlay_templates);
+            merged
+        } else {
+            base.notification_templates
+        },
+        notification_templates_dir: overlay
+            .notification_templates_dir
+            .or(base.notification_templates_dir),
         model: overlay.model.or(base.model),
         context: base.context, // Keep base context config for now (no overlay field yet)
     }
@@ -1259,6 +1508,64 @@ mod tests {
         assert_eq!(merged.agent.persona, None);
     }
 
+    #[test]
+    fn test_notification_templates() {
+        let config = AgentConfig::default();
+        assert!(config.get_notification_templates().unwrap().is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("bluesky_post.j2"), "from dir").unwrap();
+        std::fs::write(dir.path().join("file_deleted.j2"), "from dir").unwrap();
+
+        let config: AgentConfig = toml::from_str(&format!(
+            r#"
+            name = "Templated"
+            notification_templates_dir = "{}"
+
+            [notification_templates]
+            bluesky_post = "@{{{{ handle }}}} posted {{{{ text }}}}"
+            "#,
+            dir.path().display()
+        ))
+        .unwrap();
+
+        let templates = config.get_notification_templates().unwrap().unwrap();
+        assert_eq!(
+            templates.get("bluesky_post").unwrap().template,
+            "@{{ handle }} posted {{ text }}"
+        );
+        assert_eq!(templates.get("file_deleted").unwrap().template, "from dir");
+        // Built-in templates are still there for everything not overridden
+        assert!(templates.get("homeassistant_state").is_some());
+    }
+
+    #[test]
+    fn test_homeassistant_config() {
+        let config: HomeAssistantConfig = toml::from_str(
+            r#"
+            base_url = "http://homeassistant.local:8123"
+            agents = ["Momentum", "Anchor"]
+            domains = ["sensor", "binary_sensor"]
+            min_update_interval = 60
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.token_env, "HOMEASSISTANT_TOKEN");
+        assert_eq!(config.service_domains, vec!["light", "scene", "notify"]);
+        assert!(config.require_consent);
+        assert!(config.applies_to("Anchor"));
+        assert!(!config.applies_to("Archive"));
+
+        let filter = config.filter();
+        assert_eq!(filter.domains.unwrap().len(), 2);
+        assert!(filter.entity_ids.is_none());
+        assert_eq!(
+            filter.min_update_interval,
+            Some(std::time::Duration::from_secs(60))
+        );
+    }
+
     #[test]
     fn test_group_config_serialization() {
         let group = GroupConfig {
@@ -1296,4 +1603,41 @@ mod tests {
         assert!(toml.contains("[[members]]"));
         assert!(toml.contains("name = \"Executive\""));
     }
+
+    #[test]
+    fn test_pipeline_stage_config() {
+        let group: GroupConfig = toml::from_str(
+            r#"
+            name = "Pipeline"
+            description = "Stages with options"
+            pattern = { type = "pipeline", parallel_stages = true, stages = [
+                "Researcher",
+                { name = "review", merge = "first_success" },
+                { name = "Synthesizer", depends_on = ["Researcher", "review"] },
+            ] }
+            members = []
+            "#,
+        )
+        .unwrap();
+
+        let GroupPatternConfig::Pipeline {
+            stages,
+            parallel_stages,
+        } = group.pattern
+        else {
+            panic!("expected a pipeline pattern");
+        };
+        assert!(parallel_stages);
+        assert_eq!(stages.len(), 3);
+
+        assert_eq!(stages[0].name(), "Researcher");
+        assert_eq!(stages[0].merge(), StageMerge::Concatenate);
+        assert!(stages[0].depends_on().is_empty());
+
+        assert_eq!(stages[1].name(), "review");
+        assert_eq!(stages[1].merge(), StageMerge::FirstSuccess);
+
+        assert_eq!(stages[2].merge(), StageMerge::Concatenate);
+        assert_eq!(stages[2].depends_on(), ["Researcher", "review"]);
+    }
 }
@@ -50,6 +50,8 @@ pub enum ConstellationEventType {
         /// Which agent was synced
         synced_agent_id: AgentId,
     },
+    /// Numeric metric recorded by an agent (e.g. minutes since a break)
+    Metric { name: String, value: f64 },
     /// Custom event type for domain-specific tracking
     Custom { category: String },
 }
@@ -163,6 +165,9 @@ impl ConstellationActivityTracker {
                 ConstellationEventType::ContextSync { synced_agent_id } => {
                     content.push_str(&format!("  > Synced with agent: {}\n", synced_agent_id));
                 }
+                ConstellationEventType::Metric { name, value } => {
+                    content.push_str(&format!("  > Metric {} = {}\n", name, value));
+                }
                 ConstellationEventType::Custom { category } => {
                     content.push_str(&format!("  > Category: {}\n", category));
                 }
@@ -190,6 +195,62 @@ impl ConstellationActivityTracker {
         events.iter().filter(|e| e.timestamp > since).count()
     }
 
+    /// Record a numeric metric reported by an agent
+    pub async fn record_metric(
+        &self,
+        agent_id: AgentId,
+        agent_name: String,
+        name: impl Into<String>,
+        value: f64,
+    ) {
+        let name = name.into();
+        self.add_event(ConstellationEvent {
+            timestamp: Utc::now(),
+            agent_id,
+            description: format!("Recorded {} = {}", name, value),
+            agent_name,
+            event_type: ConstellationEventType::Metric { name, value },
+            metadata: None,
+        })
+        .await;
+    }
+
+    /// Most recent value recorded for a metric, with when it was recorded
+    pub async fn latest_metric(&self, name: &str) -> Option<(f64, DateTime<Utc>)> {
+        let events = self.events.read().await;
+        events
+            .iter()
+            .filter_map(|e| match &e.event_type {
+                ConstellationEventType::Metric { name: n, value } if n == name => {
+                    Some((*value, e.timestamp))
+                }
+                _ => None,
+            })
+            .max_by_key(|(_, timestamp)| *timestamp)
+    }
+
+    /// When the constellation last had a context sync, if ever
+    pub async fn last_sync(&self) -> Option<DateTime<Utc>> {
+        let events = self.events.read().await;
+        events
+            .iter()
+            .filter(|e| matches!(e.event_type, ConstellationEventType::ContextSync { .. }))
+            .map(|e| e.timestamp)
+            .max()
+    }
+
+    /// Count activity (anything other than syncs) since a specific timestamp
+    pub async fn activity_count_since(&self, since: DateTime<Utc>) -> usize {
+        let events = self.events.read().await;
+        events
+            .iter()
+            .filter(|e| {
+                e.timestamp > since
+                    && !matches!(e.event_type, ConstellationEventType::ContextSync { .. })
+            })
+            .count()
+    }
+
     /// Create or update the memory block for this tracker
     pub async fn to_memory_block(&self, owner_id: UserId) -> MemoryBlock {
         create_constellation_activity_block(
@@ -267,4 +328,51 @@ mod tests {
         assert_eq!(events[0].agent_name, "Agent5");
         assert_eq!(events[4].agent_name, "Agent9");
     }
+
+    #[tokio::test]
+    async fn test_metrics_and_sync() {
+        let tracker = ConstellationActivityTracker::new(100);
+        let agent_id = AgentId::generate();
+        let before = Utc::now() - chrono::Duration::seconds(1);
+
+        tracker
+            .record_metric(
+                agent_id.clone(),
+                "Anchor".to_string(),
+                "minutes_sitting",
+                30.0,
+            )
+            .await;
+        tracker
+            .record_metric(
+                agent_id.clone(),
+                "Anchor".to_string(),
+                "minutes_sitting",
+                75.0,
+            )
+            .await;
+
+        let (value, _) = tracker.latest_metric("minutes_sitting").await.unwrap();
+        assert_eq!(value, 75.0);
+        assert!(tracker.latest_metric("water").await.is_none());
+        assert_eq!(tracker.activity_count_since(before).await, 2);
+        assert!(tracker.last_sync().await.is_none());
+
+        tracker
+            .add_event(ConstellationEvent {
+                timestamp: Utc::now(),
+                agent_id: agent_id.clone(),
+                agent_name: "Pattern".to_string(),
+                event_type: ConstellationEventType::ContextSync {
+                    synced_agent_id: agent_id,
+                },
+                description: "Context sync".to_string(),
+                metadata: None,
+            })
+            .await;
+
+        let last_sync = tracker.last_sync().await.unwrap();
+        assert_eq!(tracker.activity_count_since(last_sync).await, 0);
+        assert_eq!(tracker.activity_count_since(before).await, 2);
+    }
 }
@@ -41,6 +41,8 @@ impl MessageEndpoint for GroupEndpoint {
             }
         }
 
+        message.metadata.group_id = Some(self.group.id.clone());
+
         let mut stream = self
             .manager
             .route_message(&self.group, &self.agents, message)
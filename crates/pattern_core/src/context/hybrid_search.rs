@@ -0,0 +1,135 @@
+//! Fusing keyword (BM25) and vector rankings into one result list
+//!
+//! Archival and conversation search run a full-text query and, when the agent
+//! has an embedding provider, a vector similarity query over the same records.
+//! The two rankings are merged with reciprocal rank fusion, which only looks
+//! at positions, so BM25 scores and cosine similarities never need to be put
+//! on a common scale.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+
+/// Rank offset from the original RRF paper
+///
+/// Larger values flatten the difference between the top few ranks.
+pub const RRF_K: f32 = 60.0;
+
+/// How much each retriever contributes to a hybrid search
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SearchWeights {
+    /// Weight of the BM25 full-text ranking
+    pub bm25: f32,
+    /// Weight of the vector similarity ranking
+    pub vector: f32,
+}
+
+impl Default for SearchWeights {
+    fn default() -> Self {
+        Self {
+            bm25: 1.0,
+            vector: 1.0,
+        }
+    }
+}
+
+impl SearchWeights {
+    /// Build weights, treating negative or non-finite values as zero
+    pub fn new(bm25: f32, vector: f32) -> Self {
+        let clean = |w: f32| if w.is_finite() { w.max(0.0) } else { 0.0 };
+        Self {
+            bm25: clean(bm25),
+            vector: clean(vector),
+        }
+    }
+
+    /// Keyword search only, as before hybrid search existed
+    pub fn bm25_only() -> Self {
+        Self::new(1.0, 0.0)
+    }
+
+    /// Whether the BM25 ranking counts at all
+    pub fn uses_bm25(&self) -> bool {
+        self.bm25 > 0.0
+    }
+
+    /// Whether the vector ranking counts at all
+    pub fn uses_vector(&self) -> bool {
+        self.vector > 0.0
+    }
+}
+
+/// Merge several rankings with weighted reciprocal rank fusion
+///
+/// Each ranking lists keys best-first alongside its weight. An item at
+/// 0-based position `r` in a ranking earns `weight / (RRF_K + r + 1)`, and
+/// an item's fused score is the sum across rankings. Results come back
+/// best-first; ties keep the order in which keys were first seen.
+pub fn reciprocal_rank_fusion<K>(rankings: &[(Vec<K>, f32)]) -> Vec<(K, f32)>
+where
+    K: Clone + Eq + Hash,
+{
+    let mut scores: HashMap<K, (f32, usize)> = HashMap::new();
+    for (ranking, weight) in rankings {
+        for (rank, key) in ranking.iter().enumerate() {
+            let next = scores.len();
+            let entry = scores.entry(key.clone()).or_insert((0.0, next));
+            entry.0 += weight / (RRF_K + rank as f32 + 1.0);
+        }
+    }
+
+    let mut fused: Vec<(K, f32, usize)> = scores
+        .into_iter()
+        .map(|(key, (score, first_seen))| (key, score, first_seen))
+        .collect();
+    fused.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.2.cmp(&b.2))
+    });
+    fused
+        .into_iter()
+        .map(|(key, score, _)| (key, score))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rrf_rewards_agreement() {
+        let bm25 = vec!["a", "b", "c"];
+        let vector = vec!["c", "b", "d"];
+        let fused = reciprocal_rank_fusion(&[(bm25, 1.0), (vector, 1.0)]);
+
+        let keys: Vec<_> = fused.iter().map(|(k, _)| *k).collect();
+        // "b" is second in both lists and beats items found by only one retriever
+        assert_eq!(keys, vec!["b", "c", "a", "d"]);
+        assert!((fused[0].1 - 2.0 / (RRF_K + 2.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rrf_weights_and_zero_weight() {
+        let bm25 = vec!["a", "b"];
+        let vector = vec!["b", "a"];
+
+        let fused = reciprocal_rank_fusion(&[(bm25.clone(), 1.0), (vector.clone(), 3.0)]);
+        assert_eq!(fused[0].0, "b");
+
+        // A zero-weighted ranking contributes nothing, so ties fall back to first-seen order
+        let fused = reciprocal_rank_fusion(&[(bm25, 1.0), (vector, 0.0)]);
+        assert_eq!(fused[0].0, "a");
+        assert_eq!(fused[1].0, "b");
+    }
+
+    #[test]
+    fn test_weights_are_sanitized() {
+        let weights = SearchWeights::new(-1.0, f32::NAN);
+        assert!(!weights.uses_bm25());
+        assert!(!weights.uses_vector());
+        assert!(SearchWeights::default().uses_vector());
+        assert!(!SearchWeights::bm25_only().uses_vector());
+    }
+}
@@ -282,6 +282,10 @@ impl AgentMessageRouter {
         &self.name
     }
 
+    pub fn db(&self) -> &Surreal<surrealdb::engine::any::Any> {
+        &self.db
+    }
+
     /// Set the default endpoint for user messages (builder pattern)
     pub fn with_default_user_endpoint(self, endpoint: Arc<dyn MessageEndpoint>) -> Self {
         // Can't modify self here since we moved it, so we'll lock and set
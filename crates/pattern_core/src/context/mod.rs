@@ -18,10 +18,13 @@ use regex::Regex;
 pub mod compression;
 pub mod endpoints;
 pub mod heartbeat;
+pub mod hybrid_search;
 pub mod message_router;
+pub mod scheduler;
 pub mod state;
 
 pub use compression::{CompressionResult, CompressionStrategy, MessageCompressor};
+pub use hybrid_search::SearchWeights;
 pub use state::{AgentContext, AgentContextBuilder, AgentHandle, AgentStats, StateCheckpoint};
 
 /// Maximum characters for core memory blocks by default
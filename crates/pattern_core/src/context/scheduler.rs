@@ -0,0 +1,327 @@
+//! Scheduled wakeup delivery for agents
+//!
+//! Agents schedule wakeups for themselves via the `schedule` tool. This module
+//! polls the `wakeup` table and delivers a system-triggered message batch to the
+//! owning agent whenever one comes due. Events on an agent's calendar get a
+//! one-off reminder wakeup shortly before they start, so they arrive the same way.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use futures::StreamExt;
+use surrealdb::{Connection, Surreal};
+
+use crate::{
+    AgentId,
+    agent::{Agent, AgentState, ResponseEvent},
+    context::NON_USER_MESSAGE_PREFIX,
+    db::{BaseEvent, ops},
+    id::IdType,
+    message::{Message, MessageBatch},
+    message_queue::ScheduledWakeup,
+};
+
+/// Default interval between checks of the wakeup table
+pub const DEFAULT_WAKEUP_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Default number of minutes before an event that its reminder fires
+pub const DEFAULT_EVENT_REMINDER_MINUTES: i64 = 15;
+
+/// Build the message an agent receives when a wakeup fires
+pub fn wakeup_message(wakeup: &ScheduledWakeup) -> Message {
+    let now = Utc::now().with_timezone(&chrono::Local);
+    let recurrence = match wakeup.recurring_seconds {
+        Some(seconds) => format!(" (recurring every {}s, id {})", seconds, wakeup.id.0),
+        None => format!(" (id {})", wakeup.id.0),
+    };
+
+    let content = format!(
+        "{}[Scheduled Wakeup] {}{}\nScheduled for {}, current time {}",
+        NON_USER_MESSAGE_PREFIX,
+        wakeup.reason,
+        recurrence,
+        wakeup
+            .scheduled_for
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M %Z"),
+        now.format("%Y-%m-%d %H:%M %Z"),
+    );
+
+    let batch = MessageBatch::new_system_trigger(content);
+    let mut message = batch
+        .messages
+        .into_iter()
+        .next()
+        .expect("system trigger batch always has an initial message");
+
+    message.metadata.custom = serde_json::json!({
+        "wakeup_id": wakeup.id.0,
+        "scheduled_for": wakeup.scheduled_for,
+    });
+    if let Some(event_id) = wakeup.metadata.get("event_id") {
+        message.metadata.custom["event_id"] = event_id.clone();
+    }
+
+    message
+}
+
+/// Build the wakeup that reminds an agent of an upcoming event
+///
+/// The reminder fires `lead_minutes` before the event starts, or right away
+/// if that moment has already passed. Returns `None` once the event has started.
+pub fn event_reminder(
+    agent_id: AgentId,
+    event: &BaseEvent,
+    lead_minutes: i64,
+) -> Option<ScheduledWakeup> {
+    let now = Utc::now();
+    if event.scheduled_for <= now {
+        return None;
+    }
+
+    let lead_minutes = lead_minutes.max(0);
+    let remind_at = (event.scheduled_for - chrono::Duration::minutes(lead_minutes)).max(now);
+    let location = event
+        .location
+        .as_deref()
+        .map(|l| format!(" at {}", l))
+        .unwrap_or_default();
+    let reason = format!(
+        "Upcoming {} event: {}{}, starting {}",
+        event.event_type,
+        event.title,
+        location,
+        event
+            .scheduled_for
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M %Z"),
+    );
+
+    let mut wakeup = ScheduledWakeup::once(agent_id, remind_at, reason);
+    wakeup.metadata = serde_json::json!({
+        "event_id": event.id.to_key(),
+        "lead_minutes": lead_minutes,
+    });
+    Some(wakeup)
+}
+
+/// Replace an agent's pending reminder for an event
+///
+/// Call after an event is created or moved; any earlier reminder for the
+/// same agent is cancelled so moved events don't fire twice.
+pub async fn sync_event_reminder<C: Connection>(
+    db: &Surreal<C>,
+    agent_id: &AgentId,
+    event: &BaseEvent,
+    lead_minutes: i64,
+) -> crate::db::Result<Option<ScheduledWakeup>> {
+    ops::cancel_event_reminders(db, &event.id, Some(agent_id)).await?;
+    match event_reminder(agent_id.clone(), event, lead_minutes) {
+        Some(reminder) => Ok(Some(ops::create_wakeup(db, &reminder).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Move every agent's pending reminder for an event to match its new time
+///
+/// Each agent keeps the lead time it originally asked for. Returns how many
+/// reminders are still pending afterwards.
+pub async fn reschedule_event_reminders<C: Connection>(
+    db: &Surreal<C>,
+    event: &BaseEvent,
+) -> crate::db::Result<usize> {
+    let mut rescheduled = 0;
+    for reminder in ops::list_event_reminders(db, &event.id, None).await? {
+        let lead_minutes = reminder
+            .metadata
+            .get("lead_minutes")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(DEFAULT_EVENT_REMINDER_MINUTES);
+        if sync_event_reminder(db, &reminder.agent_id, event, lead_minutes)
+            .await?
+            .is_some()
+        {
+            rescheduled += 1;
+        }
+    }
+    Ok(rescheduled)
+}
+
+/// Advance a fired wakeup to its next occurrence (or deactivate it)
+///
+/// Recurring wakeups that were missed while the process was down fire once
+/// and then skip ahead to the next future occurrence rather than replaying.
+fn advance_wakeup(wakeup: &mut ScheduledWakeup) {
+    let now = Utc::now();
+    wakeup.update_for_next_recurrence();
+    while wakeup.recurring_seconds.is_some() && wakeup.scheduled_for <= now {
+        wakeup.update_for_next_recurrence();
+    }
+    wakeup.last_triggered = Some(now);
+}
+
+/// Poll for due wakeups and deliver them to one or more agents
+///
+/// Runs until the task is aborted. Each due wakeup is advanced and persisted
+/// before the agent is woken so a slow or failing agent turn can't cause the
+/// same wakeup to fire repeatedly.
+pub async fn process_wakeups<C, F, Fut>(
+    db: Surreal<C>,
+    agents: Vec<Arc<dyn Agent>>,
+    poll_interval: Duration,
+    event_handler: F,
+) where
+    C: Connection,
+    F: Fn(ResponseEvent, AgentId, String) -> Fut + Clone + Send + Sync + 'static,
+    Fut: std::future::Future<Output = ()> + Send,
+{
+    let agent_ids: Vec<AgentId> = agents.iter().map(|a| a.id()).collect();
+    let mut interval = tokio::time::interval(poll_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    tracing::info!(
+        "⏰ Wakeup scheduler started for {} agent(s), polling every {:?}",
+        agent_ids.len(),
+        poll_interval
+    );
+
+    loop {
+        interval.tick().await;
+
+        let due = match ops::get_due_wakeups(&db, &agent_ids).await {
+            Ok(due) => due,
+            Err(e) => {
+                crate::log_error!("Failed to check for due wakeups", e);
+                continue;
+            }
+        };
+
+        for mut wakeup in due {
+            let Some(agent) = agents.iter().find(|a| a.id() == wakeup.agent_id).cloned() else {
+                tracing::warn!("No agent found for wakeup {}", wakeup.id);
+                continue;
+            };
+
+            let message = wakeup_message(&wakeup);
+
+            advance_wakeup(&mut wakeup);
+            if let Err(e) = ops::update_wakeup(&db, &wakeup).await {
+                crate::log_error!("Failed to persist fired wakeup", e);
+                continue;
+            }
+
+            tracing::info!(
+                "⏰ Waking agent {} for scheduled wakeup {}: {}",
+                agent.name(),
+                wakeup.id,
+                wakeup.reason
+            );
+
+            let handler = event_handler.clone();
+            tokio::spawn(async move {
+                let agent_id = agent.id();
+                let agent_name = agent.name();
+
+                // Wait for agent to be ready
+                let (state, maybe_receiver) = agent.state().await;
+                if state != AgentState::Ready {
+                    if let Some(mut receiver) = maybe_receiver {
+                        let _ = tokio::time::timeout(
+                            Duration::from_secs(200),
+                            receiver.wait_for(|s| *s == AgentState::Ready),
+                        )
+                        .await;
+                    }
+                }
+
+                match agent.process_message_stream(message).await {
+                    Ok(mut stream) => {
+                        while let Some(event) = stream.next().await {
+                            handler(event, agent_id.clone(), agent_name.clone()).await;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Error processing scheduled wakeup: {:?}", e);
+                        handler(
+                            ResponseEvent::Error {
+                                message: format!("Scheduled wakeup failed: {:?}", e),
+                                recoverable: true,
+                            },
+                            agent_id,
+                            agent_name,
+                        )
+                        .await;
+                    }
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::BatchType;
+
+    #[test]
+    fn test_wakeup_message_is_system_trigger() {
+        let wakeup = ScheduledWakeup::once(AgentId::generate(), Utc::now(), "take meds".into());
+        let message = wakeup_message(&wakeup);
+
+        assert_eq!(message.batch_type, Some(BatchType::SystemTrigger));
+        assert!(message.batch.is_some());
+        assert!(message.content.text().unwrap().contains("take meds"));
+        assert_eq!(message.metadata.custom["wakeup_id"], wakeup.id.0);
+    }
+
+    #[test]
+    fn test_advance_skips_missed_recurrences() {
+        let start = Utc::now() - chrono::Duration::hours(5);
+        let mut wakeup =
+            ScheduledWakeup::recurring(AgentId::generate(), start, "stretch".into(), 3600);
+
+        advance_wakeup(&mut wakeup);
+
+        assert!(wakeup.active);
+        assert!(wakeup.scheduled_for > Utc::now());
+        assert!(wakeup.scheduled_for <= Utc::now() + chrono::Duration::hours(1));
+    }
+
+    #[test]
+    fn test_event_reminder_lead_time() {
+        let agent_id = AgentId::generate();
+        let event = BaseEvent {
+            title: "Dentist".to_string(),
+            scheduled_for: Utc::now() + chrono::Duration::hours(2),
+            ..Default::default()
+        };
+
+        let reminder = event_reminder(agent_id.clone(), &event, 30).unwrap();
+        assert_eq!(
+            reminder.scheduled_for,
+            event.scheduled_for - chrono::Duration::minutes(30)
+        );
+        assert_eq!(reminder.metadata["event_id"], event.id.to_key());
+        assert!(reminder.reason.contains("Dentist"));
+        assert!(wakeup_message(&reminder).metadata.custom["event_id"].is_string());
+
+        // Too close to the lead time: remind now rather than in the past
+        let reminder = event_reminder(agent_id.clone(), &event, 600).unwrap();
+        assert!(reminder.scheduled_for <= Utc::now());
+
+        let started = BaseEvent {
+            scheduled_for: Utc::now() - chrono::Duration::minutes(1),
+            ..Default::default()
+        };
+        assert!(event_reminder(agent_id, &started, 15).is_none());
+    }
+
+    #[test]
+    fn test_advance_deactivates_one_off() {
+        let mut wakeup = ScheduledWakeup::once(AgentId::generate(), Utc::now(), "once".into());
+        advance_wakeup(&mut wakeup);
+        assert!(!wakeup.active);
+        assert!(wakeup.last_triggered.is_some());
+    }
+}
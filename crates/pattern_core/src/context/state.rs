@@ -13,7 +13,8 @@ use std::sync::Arc;
 use crate::{
     AgentId, AgentState, AgentType, CoreError, IdType, ModelProvider, Result,
     db::{DatabaseError, DbEntity},
-    id::MessageId,
+    embeddings::EmbeddingProvider,
+    id::{MemoryId, MessageId},
     memory::{Memory, MemoryBlock, MemoryPermission, MemoryType},
     message::{Message, MessageContent, MessageRelationType, ToolCall, ToolResponse},
     tool::ToolRegistry,
@@ -41,9 +42,41 @@ pub struct ScoredConstellationMessage {
     pub score: f32,
 }
 
+/// A record's cosine similarity to a search query
+#[derive(Debug, serde::Deserialize)]
+struct SimilarityResult {
+    id: RecordId,
+    similarity: f32,
+}
+
+/// Candidate list size for HNSW KNN queries returning `limit` results
+///
+/// Other agents' rows and other embedding models compete for the same
+/// candidates, so the search looks wider than the results it keeps.
+fn knn_search_breadth(limit: usize) -> usize {
+    (limit * 4).max(40)
+}
+
+/// Merge similarity results into one ranking of distinct ids
+fn rank_by_similarity(mut results: Vec<SimilarityResult>, limit: usize) -> Vec<RecordId> {
+    results.sort_by(|a, b| {
+        b.similarity
+            .partial_cmp(&a.similarity)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let mut seen = std::collections::HashSet::new();
+    results
+        .into_iter()
+        .filter(|r| seen.insert(r.id.clone()))
+        .map(|r| r.id)
+        .take(limit)
+        .collect()
+}
+
 use super::{
     CompressionResult, CompressionStrategy, ContextBuilder, ContextConfig, MemoryContext,
     MessageCompressor,
+    hybrid_search::{SearchWeights, reciprocal_rank_fusion},
 };
 
 /// Cheap handle to agent internals that built-in tools can hold
@@ -72,6 +105,14 @@ pub struct AgentHandle {
     /// Message router for sending messages to various targets
     #[serde(skip)]
     pub(crate) message_router: Option<super::message_router::AgentMessageRouter>,
+
+    /// Embeds search queries for the vector half of hybrid search
+    #[serde(skip)]
+    embedding_provider: Option<Arc<dyn EmbeddingProvider>>,
+
+    /// Whether the vector indexes fit the provider's embeddings, checked once
+    #[serde(skip)]
+    vector_index_ready: Arc<tokio::sync::OnceCell<bool>>,
 }
 
 impl AgentHandle {
@@ -113,6 +154,172 @@ impl AgentHandle {
         self.message_router.as_ref()
     }
 
+    /// Set the embedding provider used for vector search
+    pub fn with_embedding_provider(mut self, provider: Arc<dyn EmbeddingProvider>) -> Self {
+        self.embedding_provider = Some(provider);
+        self
+    }
+
+    /// Get the embedding provider for this handle
+    pub fn embedding_provider(&self) -> Option<&Arc<dyn EmbeddingProvider>> {
+        self.embedding_provider.as_ref()
+    }
+
+    /// Whether embeddings from the current provider can be stored
+    ///
+    /// The vector indexes only take one vector length, so they're checked
+    /// against the provider the first time and redefined if they differ.
+    async fn can_store_embeddings(&self) -> bool {
+        let (Some(db), Some(provider)) = (&self.db, &self.embedding_provider) else {
+            return false;
+        };
+        *self
+            .vector_index_ready
+            .get_or_init(|| async {
+                let dimensions = provider.dimensions();
+                for table in [MemoryId::PREFIX, MessageId::PREFIX] {
+                    if let Err(e) =
+                        crate::db::ops::ensure_vector_index(db, table, dimensions).await
+                    {
+                        tracing::warn!(
+                            "Vector index on {} can't take {}-dimension embeddings, not storing any: {}",
+                            table,
+                            dimensions,
+                            e
+                        );
+                        return false;
+                    }
+                }
+                true
+            })
+            .await
+    }
+
+    /// Embed messages' text and store the embeddings for vector search
+    ///
+    /// Messages without text are skipped. Failures are only logged, since the
+    /// messages themselves are already stored.
+    pub async fn embed_messages(&self, messages: &[Message]) {
+        let (Some(db), Some(provider)) = (&self.db, &self.embedding_provider) else {
+            return;
+        };
+        if !self.can_store_embeddings().await {
+            return;
+        }
+
+        let texts: Vec<(&Message, String)> = messages
+            .iter()
+            .filter_map(|message| Some((message, message.text_content()?)))
+            .filter(|(_, text)| !text.trim().is_empty())
+            .collect();
+        for batch in texts.chunks(provider.max_batch_size().max(1)) {
+            let inputs: Vec<String> = batch.iter().map(|(_, text)| text.clone()).collect();
+            let embeddings = match provider.embed_batch(&inputs).await {
+                Ok(embeddings) => embeddings,
+                Err(e) => {
+                    tracing::warn!("Failed to embed {} messages: {}", batch.len(), e);
+                    continue;
+                }
+            };
+            for ((message, _), embedding) in batch.iter().zip(embeddings) {
+                if let Err(e) = message
+                    .update_embedding(db, embedding.vector, provider.model_id().to_string())
+                    .await
+                {
+                    crate::log_error!("Failed to store message embedding", e);
+                }
+            }
+        }
+    }
+
+    /// Embed this agent's archival memories and archived messages that have
+    /// no embedding from the current provider yet
+    ///
+    /// Rows written before embedding on insert existed, or under another
+    /// embedding model, are otherwise invisible to vector search.
+    pub async fn backfill_embeddings(&self) -> Result<()> {
+        let (Some(db), Some(provider)) = (&self.db, &self.embedding_provider) else {
+            return Ok(());
+        };
+        if !self.can_store_embeddings().await {
+            return Ok(());
+        }
+        let model = provider.model_id().to_string();
+
+        let mut result = db
+            .query(
+                r#"
+                SELECT * FROM $agent_id->agent_memories->mem
+                WHERE memory_type = 'archival'
+                AND (embedding IS NONE OR embedding_model IS NONE OR embedding_model != $model)
+                "#,
+            )
+            .bind(("agent_id", RecordId::from(&self.agent_id)))
+            .bind(("model", model.clone()))
+            .await
+            .map_err(DatabaseError::from)?;
+        let models: Vec<<MemoryBlock as DbEntity>::DbModel> =
+            result.take(0).map_err(DatabaseError::from)?;
+        let blocks = models
+            .into_iter()
+            .map(MemoryBlock::from_db_model)
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(DatabaseError::from)?;
+
+        for batch in blocks.chunks(provider.max_batch_size().max(1)) {
+            let inputs: Vec<String> = batch.iter().map(|block| block.value.clone()).collect();
+            let embeddings = provider
+                .embed_batch(&inputs)
+                .await
+                .map_err(DatabaseError::from)?;
+            for (block, embedding) in batch.iter().zip(embeddings) {
+                db.query("UPDATE $id SET embedding = $embedding, embedding_model = $model")
+                    .bind(("id", RecordId::from(&block.id)))
+                    .bind(("embedding", embedding.vector.clone()))
+                    .bind(("model", model.clone()))
+                    .await
+                    .map_err(DatabaseError::from)?;
+
+                // Keep the loaded copy in step so persisting it doesn't drop the embedding
+                if let Some(mut loaded) = self.memory.get_block_mut(&block.label) {
+                    if loaded.id == block.id {
+                        loaded.embedding = Some(embedding.vector);
+                        loaded.embedding_model = Some(model.clone());
+                    }
+                }
+            }
+        }
+
+        let mut result = db
+            .query(
+                r#"
+                SELECT * FROM $agent_id->(agent_messages WHERE message_type = 'archived')->msg
+                WHERE embedding IS NONE OR embedding_model IS NONE OR embedding_model != $model
+                "#,
+            )
+            .bind(("agent_id", RecordId::from(&self.agent_id)))
+            .bind(("model", model))
+            .await
+            .map_err(DatabaseError::from)?;
+        let models: Vec<<Message as DbEntity>::DbModel> =
+            result.take(0).map_err(DatabaseError::from)?;
+        let messages = models
+            .into_iter()
+            .map(Message::from_db_model)
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(DatabaseError::from)?;
+
+        tracing::info!(
+            "Back-filling embeddings for agent {}: {} archival memories, {} archived messages",
+            self.agent_id,
+            blocks.len(),
+            messages.len()
+        );
+        self.embed_messages(&messages).await;
+
+        Ok(())
+    }
+
     /// Search archival memories directly from the database
     /// This avoids loading all archival memories into RAM
     pub async fn search_archival_memories(
@@ -127,7 +334,53 @@ impl AgentHandle {
     }
 
     /// Search archival memories with fuzzy search options (returns scored results)
+    ///
+    /// Uses hybrid BM25 + vector search with equal weights.
     pub async fn search_archival_memories_with_options(
+        &self,
+        query: &str,
+        limit: usize,
+        fuzzy_level: Option<u8>,
+    ) -> Result<Vec<ScoredMemoryBlock>> {
+        self.search_archival_memories_hybrid(query, limit, fuzzy_level, SearchWeights::default())
+            .await
+    }
+
+    /// Search archival memories, fusing BM25 and vector rankings
+    ///
+    /// Falls back to BM25 alone when the handle has no embedding provider,
+    /// the vector weight is zero, or embedding the query fails. Scores are
+    /// reciprocal rank fusion scores whenever the vector search ran.
+    pub async fn search_archival_memories_hybrid(
+        &self,
+        query: &str,
+        limit: usize,
+        fuzzy_level: Option<u8>,
+        weights: SearchWeights,
+    ) -> Result<Vec<ScoredMemoryBlock>> {
+        let Some((model, embedding)) = self.embed_search_query(query, &weights).await else {
+            return self
+                .keyword_archival_search(query, limit, fuzzy_level)
+                .await;
+        };
+
+        let keyword = if weights.uses_bm25() {
+            self.keyword_archival_search(query, limit, fuzzy_level)
+                .await?
+        } else {
+            Vec::new()
+        };
+        let agents = vec![RecordId::from(&self.agent_id)];
+        let vector = self
+            .vector_archival_search(&agents, &model, &embedding, limit * 2)
+            .await?;
+
+        self.fuse_memory_rankings(keyword, vector, weights, limit)
+            .await
+    }
+
+    /// BM25 full-text search over this agent's archival memories
+    async fn keyword_archival_search(
         &self,
         query: &str,
         limit: usize,
@@ -258,12 +511,57 @@ impl AgentHandle {
     }
 
     /// Search archival memories across all agents in the same groups as this agent
+    ///
+    /// Uses hybrid BM25 + vector search with equal weights.
     pub async fn search_group_archival_memories_with_options(
         &self,
         query: &str,
         limit: usize,
-        _fuzzy_level: Option<u8>,
+        fuzzy_level: Option<u8>,
     ) -> Result<Vec<ScoredMemoryBlock>> {
+        self.search_group_archival_memories_hybrid(
+            query,
+            limit,
+            fuzzy_level,
+            SearchWeights::default(),
+        )
+        .await
+    }
+
+    /// Search group archival memories, fusing BM25 and vector rankings
+    ///
+    /// Falls back to BM25 alone under the same conditions as
+    /// [`Self::search_archival_memories_hybrid`].
+    pub async fn search_group_archival_memories_hybrid(
+        &self,
+        query: &str,
+        limit: usize,
+        fuzzy_level: Option<u8>,
+        weights: SearchWeights,
+    ) -> Result<Vec<ScoredMemoryBlock>> {
+        let Some((model, embedding)) = self.embed_search_query(query, &weights).await else {
+            return self
+                .keyword_group_archival_search(query, limit, fuzzy_level)
+                .await;
+        };
+
+        let keyword = if weights.uses_bm25() {
+            self.keyword_group_archival_search(query, limit * 2, fuzzy_level)
+                .await?
+        } else {
+            Vec::new()
+        };
+        let agents = self.group_agents().await?;
+        let vector = self
+            .vector_archival_search(&agents, &model, &embedding, limit * 2)
+            .await?;
+
+        self.fuse_memory_rankings(keyword, vector, weights, limit)
+            .await
+    }
+
+    /// Every agent sharing a group with this one, itself included
+    async fn group_agents(&self) -> Result<Vec<RecordId>> {
         let db = self.db.as_ref().ok_or_else(|| {
             crate::db::DatabaseError::QueryFailed(surrealdb::Error::Api(
                 surrealdb::error::Api::InvalidParams(
@@ -287,12 +585,32 @@ impl AgentHandle {
         }
 
         let result: Vec<GroupResult> = agents_result.take(0).map_err(DatabaseError::from)?;
-        let agents = result
+        let mut agents = result
             .into_iter()
             .next()
             .map(|r| r.agents)
             .unwrap_or_default();
+        agents.sort_by_key(|agent| agent.to_string());
+        agents.dedup();
+        Ok(agents)
+    }
 
+    /// BM25 full-text search over archival memories of this agent's group members
+    async fn keyword_group_archival_search(
+        &self,
+        query: &str,
+        limit: usize,
+        _fuzzy_level: Option<u8>,
+    ) -> Result<Vec<ScoredMemoryBlock>> {
+        let db = self.db.as_ref().ok_or_else(|| {
+            crate::db::DatabaseError::QueryFailed(surrealdb::Error::Api(
+                surrealdb::error::Api::InvalidParams(
+                    "No database connection available for group archival search".into(),
+                ),
+            ))
+        })?;
+
+        let agents = self.group_agents().await?;
         if agents.is_empty() {
             return Ok(Vec::new());
         }
@@ -424,6 +742,133 @@ impl AgentHandle {
         Ok(scored_blocks)
     }
 
+    /// Embed a search query for the vector half of hybrid search
+    ///
+    /// Returns `None` (meaning BM25 only) when vector search is weighted out,
+    /// the handle has no embedding provider, the vector indexes can't take its
+    /// embeddings, or embedding fails. The model id comes back too, since only
+    /// embeddings from the same model are comparable.
+    async fn embed_search_query(
+        &self,
+        query: &str,
+        weights: &SearchWeights,
+    ) -> Option<(String, Vec<f32>)> {
+        if !weights.uses_vector() || query.trim().is_empty() {
+            return None;
+        }
+        let provider = self.embedding_provider.as_ref()?;
+        if !self.can_store_embeddings().await {
+            return None;
+        }
+        match provider.embed_query(query).await {
+            Ok(embedding) => Some((provider.model_id().to_string(), embedding)),
+            Err(e) => {
+                tracing::warn!("Failed to embed search query, using BM25 only: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Archival blocks of `agents` ranked by cosine similarity, best first
+    ///
+    /// Uses the HNSW index on `mem`, so only the nearest candidates are scored.
+    async fn vector_archival_search(
+        &self,
+        agents: &[RecordId],
+        model: &str,
+        embedding: &[f32],
+        limit: usize,
+    ) -> Result<Vec<RecordId>> {
+        let db = self.db.as_ref().ok_or_else(|| {
+            CoreError::database_query_error(
+                "archival_vector_search",
+                "mem",
+                surrealdb::Error::Api(surrealdb::error::Api::InvalidParams(
+                    "No database connection available for archival search".into(),
+                )),
+            )
+        })?;
+
+        // Blocks from other embedding models can't be compared with this query
+        let sql = format!(
+            r#"
+            SELECT id, 1 - vector::distance::knn() AS similarity
+            FROM mem
+            WHERE embedding <|{},{}|> $embedding
+            AND memory_type = 'archival'
+            AND embedding_model = $model
+            AND <-agent_memories<-agent CONTAINSANY $agents
+            "#,
+            limit,
+            knn_search_breadth(limit)
+        );
+
+        let mut result = db
+            .query(sql)
+            .bind(("agents", agents.to_vec()))
+            .bind(("embedding", embedding.to_vec()))
+            .bind(("model", model.to_string()))
+            .await
+            .map_err(|e| CoreError::database_query_error("archival_vector_search", "mem", e))?;
+        let similar: Vec<SimilarityResult> = result.take(0).map_err(DatabaseError::from)?;
+
+        Ok(rank_by_similarity(similar, limit))
+    }
+
+    /// Fuse BM25-ranked blocks with vector-ranked block ids
+    async fn fuse_memory_rankings(
+        &self,
+        keyword: Vec<ScoredMemoryBlock>,
+        vector: Vec<RecordId>,
+        weights: SearchWeights,
+        limit: usize,
+    ) -> Result<Vec<ScoredMemoryBlock>> {
+        let keyword_ids: Vec<RecordId> = keyword
+            .iter()
+            .map(|sb| RecordId::from(&sb.block.id))
+            .collect();
+        let mut blocks: std::collections::HashMap<RecordId, MemoryBlock> = keyword_ids
+            .iter()
+            .cloned()
+            .zip(keyword.into_iter().map(|sb| sb.block))
+            .collect();
+
+        let mut fused =
+            reciprocal_rank_fusion(&[(keyword_ids, weights.bm25), (vector, weights.vector)]);
+        fused.truncate(limit);
+
+        // Blocks only the vector search found still need loading
+        let missing: Vec<RecordId> = fused
+            .iter()
+            .filter(|(id, _)| !blocks.contains_key(id))
+            .map(|(id, _)| id.clone())
+            .collect();
+        if !missing.is_empty() {
+            if let Some(db) = &self.db {
+                let mut result = db
+                    .query("SELECT * FROM $ids")
+                    .bind(("ids", missing))
+                    .await
+                    .map_err(DatabaseError::from)?;
+                let models: Vec<<MemoryBlock as DbEntity>::DbModel> =
+                    result.take(0).map_err(DatabaseError::from)?;
+                for model in models {
+                    let block = MemoryBlock::from_db_model(model).map_err(DatabaseError::from)?;
+                    blocks.insert(RecordId::from(&block.id), block);
+                }
+            }
+        }
+
+        Ok(fused
+            .into_iter()
+            .filter_map(|(id, score)| {
+                blocks
+                    .remove(&id)
+                    .map(|block| ScoredMemoryBlock { block, score })
+            })
+            .collect())
+    }
+
     /// Insert a new archival memory to in-memory storage
     /// Database persistence happens automatically via persist_memory_changes
     pub async fn insert_archival_memory(&self, label: &str, content: &str) -> Result<MemoryBlock> {
@@ -436,6 +881,23 @@ impl AgentHandle {
             block.permission = MemoryPermission::ReadWrite;
         }
 
+        // Embed it so vector search can find it
+        if let Some(provider) = &self.embedding_provider {
+            if self.can_store_embeddings().await {
+                match provider.embed(content).await {
+                    Ok(embedding) => {
+                        if let Some(mut block) = self.memory.get_block_mut(label) {
+                            block.embedding = Some(embedding.vector);
+                            block.embedding_model = Some(provider.model_id().to_string());
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to embed archival memory {}: {}", label, e);
+                    }
+                }
+            }
+        }
+
         // Get the created block
         let block = self
             .memory
@@ -864,14 +1326,44 @@ impl AgentHandle {
     }
 
     /// Search conversation messages with advanced options including fuzzy search and scoring
+    ///
+    /// Content queries use hybrid BM25 + vector search with equal weights.
     pub async fn search_conversations_with_options(
+        &self,
+        query: Option<&str>,
+        role_filter: Option<crate::message::ChatRole>,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: usize,
+        fuzzy_level: Option<u8>, // 0=exact, 1=fuzzy, 2=very fuzzy
+    ) -> Result<Vec<ScoredMessage>> {
+        self.search_conversations_hybrid(
+            query,
+            role_filter,
+            start_time,
+            end_time,
+            limit,
+            fuzzy_level,
+            SearchWeights::default(),
+        )
+        .await
+    }
+
+    /// Search conversation messages, fusing BM25 and vector rankings
+    ///
+    /// Only content queries are fused; time- and role-only searches return
+    /// messages newest first. Without a usable query embedding this behaves
+    /// like plain BM25 search, including the per-word fallback.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search_conversations_hybrid(
         &self,
         query: Option<&str>,
         role_filter: Option<crate::message::ChatRole>,
         mut start_time: Option<DateTime<Utc>>,
         mut end_time: Option<DateTime<Utc>>,
         limit: usize,
-        _fuzzy_level: Option<u8>, // 0=exact, 1=fuzzy, 2=very fuzzy
+        _fuzzy_level: Option<u8>,
+        weights: SearchWeights,
     ) -> Result<Vec<ScoredMessage>> {
         let db = self.db.as_ref().ok_or_else(|| {
             crate::db::DatabaseError::QueryFailed(surrealdb::Error::Api(
@@ -954,6 +1446,26 @@ impl AgentHandle {
                 agent_msg_id_set.contains(&msg_record_id)
             });
 
+            if let Some((model, embedding)) = self.embed_search_query(search_query, &weights).await
+            {
+                if !weights.uses_bm25() {
+                    scored_msgs.clear();
+                }
+                let vector = self
+                    .vector_message_search(
+                        &model,
+                        &embedding,
+                        role_filter.as_ref(),
+                        start_time,
+                        end_time,
+                        limit * 2,
+                    )
+                    .await?;
+                return self
+                    .fuse_message_rankings(scored_msgs, vector, weights, limit)
+                    .await;
+            }
+
             // Truncate to limit
             scored_msgs.truncate(limit);
 
@@ -1098,6 +1610,130 @@ impl AgentHandle {
         Ok(limited_messages)
     }
 
+    /// This agent's archived messages ranked by cosine similarity, best first
+    ///
+    /// Uses the HNSW index on `msg`, so only the nearest candidates are scored.
+    async fn vector_message_search(
+        &self,
+        model: &str,
+        embedding: &[f32],
+        role_filter: Option<&crate::message::ChatRole>,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: usize,
+    ) -> Result<Vec<RecordId>> {
+        let db = self.db.as_ref().ok_or_else(|| {
+            crate::db::DatabaseError::QueryFailed(surrealdb::Error::Api(
+                surrealdb::error::Api::InvalidParams(
+                    "No database connection available for conversation search".into(),
+                ),
+            ))
+        })?;
+
+        let knn = format!(
+            "embedding <|{},{}|> $embedding",
+            limit,
+            knn_search_breadth(limit)
+        );
+        let mut conditions = vec![
+            knn.as_str(),
+            "embedding_model = $model",
+            "<-(agent_messages WHERE message_type = 'archived')<-agent CONTAINS $agent_id",
+        ];
+        if role_filter.is_some() {
+            conditions.push("role = $role");
+        }
+        if start_time.is_some() {
+            conditions.push("created_at >= $start_time");
+        }
+        if end_time.is_some() {
+            conditions.push("created_at <= $end_time");
+        }
+
+        let sql = format!(
+            "SELECT id, 1 - vector::distance::knn() AS similarity FROM msg WHERE {}",
+            conditions.join(" AND ")
+        );
+
+        let mut query_builder = db
+            .query(&sql)
+            .bind(("agent_id", RecordId::from(&self.agent_id)))
+            .bind(("embedding", embedding.to_vec()))
+            .bind(("model", model.to_string()));
+
+        if let Some(role) = role_filter {
+            query_builder = query_builder.bind(("role", role.to_string()));
+        }
+
+        if let Some(start) = start_time {
+            query_builder =
+                query_builder.bind(("start_time", surrealdb::sql::Datetime::from(start)));
+        }
+
+        if let Some(end) = end_time {
+            query_builder = query_builder.bind(("end_time", surrealdb::sql::Datetime::from(end)));
+        }
+
+        let mut result = query_builder.await.map_err(DatabaseError::from)?;
+        let similar: Vec<SimilarityResult> = result.take(0).map_err(DatabaseError::from)?;
+        Ok(rank_by_similarity(similar, limit))
+    }
+
+    /// Fuse BM25-ranked messages with vector-ranked message ids
+    async fn fuse_message_rankings(
+        &self,
+        keyword: Vec<ScoredMessage>,
+        vector: Vec<RecordId>,
+        weights: SearchWeights,
+        limit: usize,
+    ) -> Result<Vec<ScoredMessage>> {
+        let keyword_ids: Vec<RecordId> = keyword
+            .iter()
+            .map(|sm| RecordId::from((MessageId::PREFIX, sm.message.id.to_key())))
+            .collect();
+        let mut messages: std::collections::HashMap<RecordId, Message> = keyword_ids
+            .iter()
+            .cloned()
+            .zip(keyword.into_iter().map(|sm| sm.message))
+            .collect();
+
+        let mut fused =
+            reciprocal_rank_fusion(&[(keyword_ids, weights.bm25), (vector, weights.vector)]);
+        fused.truncate(limit);
+
+        // Messages only the vector search found still need loading
+        let missing: Vec<RecordId> = fused
+            .iter()
+            .filter(|(id, _)| !messages.contains_key(id))
+            .map(|(id, _)| id.clone())
+            .collect();
+        if !missing.is_empty() {
+            if let Some(db) = &self.db {
+                let mut result = db
+                    .query("SELECT * FROM $ids")
+                    .bind(("ids", missing))
+                    .await
+                    .map_err(DatabaseError::from)?;
+                let models: Vec<<Message as DbEntity>::DbModel> =
+                    result.take(0).map_err(DatabaseError::from)?;
+                for model in models {
+                    let message = Message::from_db_model(model).map_err(DatabaseError::from)?;
+                    let id = RecordId::from((MessageId::PREFIX, message.id.to_key()));
+                    messages.insert(id, message);
+                }
+            }
+        }
+
+        Ok(fused
+            .into_iter()
+            .filter_map(|(id, score)| {
+                messages
+                    .remove(&id)
+                    .map(|message| ScoredMessage { message, score })
+            })
+            .collect())
+    }
+
     /// Search messages from all agents in the same constellation
     pub async fn search_constellation_messages(
         &self,
@@ -1348,6 +1984,205 @@ impl AgentHandle {
 
         Ok(all_messages)
     }
+
+    /// Persist a scheduled wakeup for this agent
+    pub async fn schedule_wakeup(
+        &self,
+        wakeup: crate::message_queue::ScheduledWakeup,
+    ) -> Result<crate::message_queue::ScheduledWakeup> {
+        let db = self.wakeup_db("wakeup_create")?;
+        Ok(crate::db::ops::create_wakeup(db, &wakeup).await?)
+    }
+
+    /// List this agent's active scheduled wakeups, soonest first
+    pub async fn list_wakeups(&self) -> Result<Vec<crate::message_queue::ScheduledWakeup>> {
+        let db = self.wakeup_db("wakeup_list")?;
+        Ok(crate::db::ops::list_agent_wakeups(db, &self.agent_id).await?)
+    }
+
+    /// Cancel one of this agent's scheduled wakeups
+    ///
+    /// Returns `false` if the wakeup doesn't exist, isn't active, or belongs to another agent.
+    pub async fn cancel_wakeup(&self, wakeup_id: &crate::id::WakeupId) -> Result<bool> {
+        let db = self.wakeup_db("wakeup_cancel")?;
+        Ok(crate::db::ops::cancel_wakeup(db, &self.agent_id, wakeup_id).await?)
+    }
+
+    fn wakeup_db(
+        &self,
+        operation: &str,
+    ) -> Result<&surrealdb::Surreal<surrealdb::engine::any::Any>> {
+        self.db.as_ref().ok_or_else(|| {
+            CoreError::database_query_error(
+                operation,
+                "wakeup",
+                surrealdb::Error::Api(surrealdb::error::Api::InvalidParams(
+                    "No database connection available for scheduled wakeups".into(),
+                )),
+            )
+        })
+    }
+
+    /// Create a task, owned by this agent's user
+    pub async fn create_task(&self, task: crate::db::BaseTask) -> Result<crate::db::BaseTask> {
+        let db = self.task_db("task_create")?;
+        Ok(crate::db::ops::create_task(db, &task).await?)
+    }
+
+    /// Get a task by ID
+    pub async fn get_task(
+        &self,
+        task_id: &crate::id::TaskId,
+    ) -> Result<Option<crate::db::BaseTask>> {
+        let db = self.task_db("task_get")?;
+        Ok(crate::db::ops::get_task(db, task_id).await?)
+    }
+
+    /// Save changes to a task
+    pub async fn update_task(&self, task: &crate::db::BaseTask) -> Result<crate::db::BaseTask> {
+        let db = self.task_db("task_update")?;
+        Ok(crate::db::ops::update_task(db, task).await?)
+    }
+
+    /// Assign a task to an agent, or unassign it with `None`
+    pub async fn assign_task(
+        &self,
+        task_id: &crate::id::TaskId,
+        agent_id: Option<&AgentId>,
+    ) -> Result<crate::db::BaseTask> {
+        let db = self.task_db("task_assign")?;
+        Ok(crate::db::ops::assign_task(db, task_id, agent_id).await?)
+    }
+
+    /// List tasks matching a filter, most pressing first
+    pub async fn list_tasks(
+        &self,
+        filter: &crate::db::ops::TaskFilter,
+    ) -> Result<Vec<crate::db::BaseTask>> {
+        let db = self.task_db("task_list")?;
+        Ok(crate::db::ops::list_tasks(db, filter).await?)
+    }
+
+    /// Look up the ID of an agent with the same owner as this one by name
+    pub async fn find_agent_id(&self, name: &str) -> Result<Option<AgentId>> {
+        let db = self.task_db("agent_lookup")?;
+        Ok(crate::db::ops::get_agent_id_by_name(db, &self.memory.owner_id, name).await?)
+    }
+
+    fn task_db(&self, operation: &str) -> Result<&surrealdb::Surreal<surrealdb::engine::any::Any>> {
+        self.db.as_ref().ok_or_else(|| {
+            CoreError::database_query_error(
+                operation,
+                "task",
+                surrealdb::Error::Api(surrealdb::error::Api::InvalidParams(
+                    "No database connection available for tasks".into(),
+                )),
+            )
+        })
+    }
+
+    /// Create an event and put it on this agent's schedule
+    pub async fn create_event(&self, event: crate::db::BaseEvent) -> Result<crate::db::BaseEvent> {
+        let db = self.event_db("event_create")?;
+        Ok(crate::db::ops::create_event(db, &event, Some(&self.agent_id)).await?)
+    }
+
+    /// Get an event by ID
+    pub async fn get_event(
+        &self,
+        event_id: &crate::id::EventId,
+    ) -> Result<Option<crate::db::BaseEvent>> {
+        let db = self.event_db("event_get")?;
+        Ok(crate::db::ops::get_event(db, event_id).await?)
+    }
+
+    /// Save changes to an event
+    pub async fn update_event(&self, event: &crate::db::BaseEvent) -> Result<crate::db::BaseEvent> {
+        let db = self.event_db("event_update")?;
+        Ok(crate::db::ops::update_event(db, event).await?)
+    }
+
+    /// Delete an event and any reminders for it
+    pub async fn delete_event(&self, event_id: &crate::id::EventId) -> Result<()> {
+        let db = self.event_db("event_delete")?;
+        Ok(crate::db::ops::delete_event(db, event_id).await?)
+    }
+
+    /// List events matching a filter, soonest first
+    pub async fn list_events(
+        &self,
+        filter: &crate::db::ops::EventFilter,
+    ) -> Result<Vec<crate::db::BaseEvent>> {
+        let db = self.event_db("event_list")?;
+        Ok(crate::db::ops::list_events(db, filter).await?)
+    }
+
+    /// Schedule (or reschedule) this agent's wakeup ahead of an event
+    pub async fn sync_event_reminder(
+        &self,
+        event: &crate::db::BaseEvent,
+        lead_minutes: i64,
+    ) -> Result<Option<crate::message_queue::ScheduledWakeup>> {
+        let db = self.event_db("event_reminder")?;
+        Ok(
+            crate::context::scheduler::sync_event_reminder(db, &self.agent_id, event, lead_minutes)
+                .await?,
+        )
+    }
+
+    /// Move every agent's pending reminders for an event to its current time
+    pub async fn reschedule_event_reminders(&self, event: &crate::db::BaseEvent) -> Result<usize> {
+        let db = self.event_db("event_reminder")?;
+        Ok(crate::context::scheduler::reschedule_event_reminders(db, event).await?)
+    }
+
+    fn event_db(
+        &self,
+        operation: &str,
+    ) -> Result<&surrealdb::Surreal<surrealdb::engine::any::Any>> {
+        self.db.as_ref().ok_or_else(|| {
+            CoreError::database_query_error(
+                operation,
+                "event",
+                surrealdb::Error::Api(surrealdb::error::Api::InvalidParams(
+                    "No database connection available for events".into(),
+                )),
+            )
+        })
+    }
+
+    /// Who this agent is, for evaluating the permission policy
+    ///
+    /// Group names are only looked up when a policy rule matches on groups.
+    pub async fn policy_subject(&self) -> crate::policy::PolicySubject {
+        let subject = crate::policy::PolicySubject::new(self.agent_id.clone(), self.name.clone());
+        if !crate::policy::policy().uses_groups() {
+            return subject;
+        }
+        let Some(db) = &self.db else {
+            return subject;
+        };
+
+        match crate::db::ops::get_agent_group_names(db, &self.agent_id).await {
+            Ok(groups) => subject.with_groups(groups),
+            Err(e) => {
+                crate::log_error!("Failed to look up groups for policy check", e);
+                subject
+            }
+        }
+    }
+
+    /// Persist a tool execution for `pattern-cli stats tools`
+    ///
+    /// Failures are logged and otherwise ignored; analytics must not break a tool call.
+    pub async fn record_tool_call(&self, tool_call: crate::db::schema::ToolCall) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        if let Err(e) = crate::db::ops::create_entity(db, &tool_call).await {
+            crate::log_error!("Failed to persist tool call", e);
+        }
+    }
 }
 
 impl Default for AgentHandle {
@@ -1363,6 +2198,8 @@ impl Default for AgentHandle {
             state_watch: Some(Arc::new((tx, rx))),
             db: None,
             message_router: None,
+            embedding_provider: None,
+            vector_index_ready: Arc::default(),
         }
     }
 }
@@ -1380,6 +2217,8 @@ impl AgentHandle {
             state_watch: None,
             db: None,
             message_router: None,
+            embedding_provider: None,
+            vector_index_ready: Arc::default(),
         }
     }
 }
@@ -1539,6 +2378,8 @@ impl AgentContext {
             state_watch: Some(Arc::new((tx, rx))),
             db: None,
             message_router: None,
+            embedding_provider: None,
+            vector_index_ready: Arc::default(),
         };
 
         Self {
@@ -1815,12 +2656,31 @@ impl AgentContext {
         // Build execution metadata from the call
         let mut params = call.fn_arguments.clone();
 
-        // Check for a simple consent rule in workflow rules: rule == "requires_consent"
-        let requires_consent = self
-            .context_config
-            .consent_required_tools
-            .iter()
-            .any(|name| name == &call.fn_name);
+        // The permission policy decides first; otherwise fall back to the
+        // tool's own "requires_consent" rule
+        let policy_decision = crate::policy::policy().check_tool(
+            &self.handle.policy_subject().await,
+            &call.fn_name,
+            chrono::Utc::now(),
+        );
+        let (requires_consent, consent_reason) = match policy_decision {
+            Some(crate::policy::PolicyDecision::Deny { reason }) => {
+                return Err(crate::CoreError::ToolExecutionFailed {
+                    tool_name: call.fn_name.clone(),
+                    cause: reason,
+                    parameters: call.fn_arguments.clone(),
+                });
+            }
+            Some(crate::policy::PolicyDecision::RequireConsent { reason }) => (true, reason),
+            Some(crate::policy::PolicyDecision::Allow) => (false, String::new()),
+            None => (
+                self.context_config
+                    .consent_required_tools
+                    .iter()
+                    .any(|name| name == &call.fn_name),
+                "Tool requires consent".to_string(),
+            ),
+        };
         let request_heartbeat = params
             .get("request_heartbeat")
             .and_then(|v| v.as_bool())
@@ -1864,7 +2724,7 @@ impl AgentContext {
                     self.handle.agent_id.clone(),
                     call.fn_name.clone(),
                     scope,
-                    Some("Tool requires consent".to_string()),
+                    Some(consent_reason),
                     route_metadata.clone(),
                     std::time::Duration::from_secs(90),
                 )
@@ -1903,7 +2763,32 @@ impl AgentContext {
             route_metadata: route_metadata.clone(),
         };
 
-        match self.tools.execute(&call.fn_name, params, &meta).await {
+        let started_at = Utc::now();
+        let timer = std::time::Instant::now();
+        let result = self
+            .tools
+            .execute(&call.fn_name, params.clone(), &meta)
+            .await;
+        let duration_ms = timer.elapsed().as_millis() as i64;
+        // Tie any memory writes the tool made to this call
+        self.handle
+            .memory
+            .attribute_revisions(&crate::id::ToolCallId(call.call_id.clone()));
+
+        self.handle
+            .record_tool_call(crate::db::schema::ToolCall {
+                id: crate::id::ToolCallId::generate(),
+                agent_id: self.handle.agent_id.clone(),
+                tool_name: call.fn_name.clone(),
+                parameters: params,
+                result: result.as_ref().cloned().unwrap_or(serde_json::json!({})),
+                error: result.as_ref().err().map(|e| e.to_string()),
+                duration_ms,
+                created_at: started_at,
+            })
+            .await;
+
+        match result {
             Ok(tool_response) => {
                 tracing::debug!("✅ Tool {} executed successfully", call.fn_name);
 
@@ -2365,3 +3250,133 @@ impl AgentContextBuilder {
         Ok(state)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AgentRecord;
+    use crate::db::{client, ops};
+    use crate::embeddings::MockEmbeddingProvider;
+    use crate::id::UserId;
+
+    #[tokio::test]
+    async fn test_hybrid_archival_search_finds_semantic_matches() {
+        let db = client::create_test_db().await.unwrap();
+        let agent = AgentRecord {
+            id: AgentId::generate(),
+            name: "Searcher".to_string(),
+            owner_id: UserId::generate(),
+            ..Default::default()
+        };
+        ops::create_entity::<AgentRecord, _>(&db, &agent)
+            .await
+            .unwrap();
+
+        let provider = MockEmbeddingProvider {
+            dimensions: 1536,
+            ..Default::default()
+        };
+        let archival = |label: &str, value: &str, model: &str| MemoryBlock {
+            owner_id: agent.owner_id.clone(),
+            label: label.into(),
+            value: value.to_string(),
+            memory_type: MemoryType::Archival,
+            embedding_model: Some(model.to_string()),
+            embedding: Some(vec![0.1; 1536]),
+            ..Default::default()
+        };
+        for block in [
+            archival("keyword", "the garden needs watering", "mock-model"),
+            archival(
+                "semantic",
+                "tomatoes and basil on the balcony",
+                "mock-model",
+            ),
+            archival("other_model", "pruning roses", "some-other-model"),
+        ] {
+            ops::persist_agent_memory(&db, agent.id.clone(), &block, MemoryPermission::ReadWrite)
+                .await
+                .unwrap();
+        }
+
+        let handle = AgentHandle {
+            agent_id: agent.id.clone(),
+            ..Default::default()
+        }
+        .with_db(db);
+
+        // Without embeddings only the keyword match is found
+        let results = handle
+            .search_archival_memories_with_options("garden", 10, None)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+
+        let handle = handle.with_embedding_provider(Arc::new(provider));
+        let results = handle
+            .search_archival_memories_with_options("garden", 10, None)
+            .await
+            .unwrap();
+        let labels: Vec<_> = results.iter().map(|r| r.block.label.as_str()).collect();
+        // The keyword match ranks first; blocks from another embedding model never match
+        assert_eq!(labels, vec!["keyword", "semantic"]);
+
+        let results = handle
+            .search_archival_memories_hybrid("garden", 10, None, SearchWeights::bm25_only())
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+    }
+    #[tokio::test]
+    async fn test_archival_memories_are_embedded() {
+        let db = client::create_test_db().await.unwrap();
+        let agent = AgentRecord {
+            id: AgentId::generate(),
+            name: "Embedder".to_string(),
+            owner_id: UserId::generate(),
+            ..Default::default()
+        };
+        ops::create_entity::<AgentRecord, _>(&db, &agent)
+            .await
+            .unwrap();
+
+        let stored = MemoryBlock {
+            owner_id: agent.owner_id.clone(),
+            label: "stored".into(),
+            value: "written before embeddings were on".to_string(),
+            memory_type: MemoryType::Archival,
+            ..Default::default()
+        };
+        ops::persist_agent_memory(&db, agent.id.clone(), &stored, MemoryPermission::ReadWrite)
+            .await
+            .unwrap();
+
+        let provider = MockEmbeddingProvider {
+            dimensions: 8,
+            ..Default::default()
+        };
+        let handle = AgentHandle {
+            agent_id: agent.id.clone(),
+            memory: Memory::with_owner(&agent.owner_id),
+            ..Default::default()
+        }
+        .with_db(db.clone())
+        .with_embedding_provider(Arc::new(provider));
+
+        // New blocks are embedded on insert, and the index follows the provider
+        let inserted = handle
+            .insert_archival_memory("inserted", "fresh note")
+            .await
+            .unwrap();
+        assert_eq!(inserted.embedding.as_ref().map(Vec::len), Some(8));
+        assert_eq!(inserted.embedding_model.as_deref(), Some("mock-model"));
+
+        handle.backfill_embeddings().await.unwrap();
+        let backfilled = ops::get_memory_by_label(&db, agent.id.clone(), "stored")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(backfilled.embedding.as_ref().map(Vec::len), Some(8));
+        assert_eq!(backfilled.embedding_model.as_deref(), Some("mock-model"));
+    }
+}
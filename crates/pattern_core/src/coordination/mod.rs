@@ -6,6 +6,7 @@
 pub mod groups;
 pub mod patterns;
 pub mod selectors;
+pub mod triggers;
 pub mod types;
 pub mod utils;
 
@@ -19,4 +20,7 @@ pub use patterns::{
     VotingManager,
 };
 pub use selectors::{AgentSelector, CapabilitySelector, LoadBalancingSelector, RandomSelector};
+pub use triggers::{
+    DefaultTriggerEvaluatorRegistry, TriggerContext, TriggerEvaluator, TriggerEvaluatorRegistry,
+};
 pub use types::*;
@@ -12,6 +12,7 @@ use crate::{
             AgentResponse, AgentWithMembership, GroupManager, GroupResponse, GroupResponseEvent,
         },
         types::{CoordinationPattern, GroupState, SelectionContext},
+        utils::ResponseAccumulator,
     },
     message::Message,
 };
@@ -230,8 +231,10 @@ impl GroupManager for DynamicManager {
                     // Forward the stream events
                     use tokio_stream::StreamExt;
                     let mut message_id = None;
+                    let mut accumulator = ResponseAccumulator::new();
 
                     while let Some(event) = supervisor_stream.next().await {
+                        accumulator.push(&event);
                         match event {
                             crate::agent::ResponseEvent::TextChunk { text, is_final } => {
                                 let _ = tx
@@ -312,11 +315,7 @@ impl GroupManager for DynamicManager {
                     // Track the response
                     let agent_responses = vec![AgentResponse {
                         agent_id: supervisor_id.clone(),
-                        response: crate::message::Response {
-                            content: vec![], // TODO: We'd need to collect content from the stream
-                            reasoning: None,
-                            metadata: crate::message::ResponseMetadata::default(),
-                        },
+                        response: accumulator.finish(),
                         responded_at: Utc::now(),
                     }];
 
@@ -384,7 +383,10 @@ impl GroupManager for DynamicManager {
                         Ok(mut stream) => {
                             use tokio_stream::StreamExt;
 
+                            let mut accumulator = ResponseAccumulator::new();
                             while let Some(event) = stream.next().await {
+                                accumulator.push(&event);
+
                                 // Convert ResponseEvent to GroupResponseEvent
                                 match event {
                                     crate::agent::ResponseEvent::TextChunk { text, is_final } => {
@@ -470,11 +472,7 @@ impl GroupManager for DynamicManager {
                             let _ = response_tx
                                 .send(AgentResponse {
                                     agent_id: agent_id.clone(),
-                                    response: crate::message::Response {
-                                        content: vec![], // TODO: Collect actual response content
-                                        reasoning: None,
-                                        metadata: crate::message::ResponseMetadata::default(),
-                                    },
+                                    response: accumulator.finish(),
                                     responded_at: Utc::now(),
                                 })
                                 .await;
@@ -710,6 +708,10 @@ mod tests {
         let selected_id = &agent_responses[0].agent_id;
         assert!(selected_id != &agents[2].agent.id());
 
+        // Complete event should carry what the agent actually said
+        let text = agent_responses[0].response.content[0].text().unwrap();
+        assert!(text.ends_with("test response"));
+
         // State should be updated with recent selection
         if let Some(GroupState::Dynamic { recent_selections }) = state_changes {
             assert_eq!(recent_selections.len(), agent_responses.len());
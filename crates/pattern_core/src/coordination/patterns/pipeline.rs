@@ -2,7 +2,13 @@
 
 use async_trait::async_trait;
 use chrono::Utc;
-use std::{sync::Arc, time::Instant};
+use futures::{StreamExt, stream::FuturesUnordered};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
+use tokio::sync::mpsc::Sender;
 
 use crate::{
     AgentId, CoreError, Result,
@@ -11,12 +17,20 @@ use crate::{
         groups::{
             AgentResponse, AgentWithMembership, GroupManager, GroupResponse, GroupResponseEvent,
         },
-        types::{GroupState, PipelineStage, StageFailureAction, StageResult},
-        utils::text_response,
+        types::{
+            CoordinationPattern, GroupState, PipelineExecution, PipelineStage, StageFailureAction,
+            StageMerge, StageResult,
+        },
+        utils::{response_text, text_response},
     },
-    message::Message,
+    message::{Message, Response},
 };
 
+/// Metadata key listing the earlier stage outputs a stage was given
+const STAGE_INPUTS_METADATA_KEY: &str = "pipeline_inputs";
+
+type Member = AgentWithMembership<Arc<dyn Agent>>;
+
 #[derive(Clone)]
 pub struct PipelineManager;
 
@@ -29,38 +43,84 @@ impl GroupManager for PipelineManager {
         message: Message,
     ) -> Result<Box<dyn futures::Stream<Item = GroupResponseEvent> + Send + Unpin>> {
         use tokio_stream::wrappers::ReceiverStream;
-        let (tx, rx) = tokio::sync::mpsc::channel(100);
 
-        let start_time = std::time::Instant::now();
-        let group_id = group.id.clone();
+        // Extract pipeline config
+        let (stages, parallel_stages) = match &group.coordination_pattern {
+            CoordinationPattern::Pipeline {
+                stages,
+                parallel_stages,
+            } => (stages.clone(), *parallel_stages),
+            _ => {
+                return Err(CoreError::AgentGroupError {
+                    group_name: group.name.clone(),
+                    operation: "route_message".to_string(),
+                    cause: "Invalid pattern for PipelineManager".to_string(),
+                });
+            }
+        };
+
+        if parallel_stages {
+            validate_dependencies(&group.name, &stages)?;
+        }
+
+        // Resume the first active execution or start a new one
+        let execution = match &group.state {
+            GroupState::Pipeline { active_executions } => active_executions.first().cloned(),
+            _ => None,
+        }
+        .unwrap_or_else(|| PipelineExecution {
+            id: uuid::Uuid::new_v4(),
+            current_stage: 0,
+            stage_results: Vec::new(),
+            started_at: Utc::now(),
+        });
 
-        // Do the full pipeline operation synchronously first
-        let result = self.do_pipeline(group, agents, message).await;
+        let (tx, rx) = tokio::sync::mpsc::channel(100);
+        let start_time = Instant::now();
+        let group_id = group.id.clone();
+        let run = PipelineRun {
+            stages,
+            agents: agents.to_vec(),
+            original: message,
+            tx: tx.clone(),
+        };
 
-        // Then send the result as a single Complete event
         tokio::spawn(async move {
-            match result {
-                Ok((agent_responses, state_changes)) => {
-                    let _ = tx
-                        .send(GroupResponseEvent::Complete {
-                            group_id,
-                            pattern: "pipeline".to_string(),
-                            execution_time: start_time.elapsed(),
-                            agent_responses,
-                            state_changes,
-                        })
-                        .await;
+            let _ = tx
+                .send(GroupResponseEvent::Started {
+                    group_id: group_id.clone(),
+                    pattern: "pipeline".to_string(),
+                    agent_count: run.agents.len(),
+                })
+                .await;
+
+            let (agent_responses, execution) = if parallel_stages {
+                run.run_parallel(execution).await
+            } else {
+                run.run_sequential(execution).await
+            };
+
+            let state_changes = if execution.current_stage >= run.stages.len() {
+                // Pipeline complete, clear execution
+                GroupState::Pipeline {
+                    active_executions: vec![],
                 }
-                Err(e) => {
-                    let _ = tx
-                        .send(GroupResponseEvent::Error {
-                            agent_id: None,
-                            message: e.to_string(),
-                            recoverable: false,
-                        })
-                        .await;
+            } else {
+                // Pipeline stopped early, keep it around to resume
+                GroupState::Pipeline {
+                    active_executions: vec![execution],
                 }
-            }
+            };
+
+            let _ = tx
+                .send(GroupResponseEvent::Complete {
+                    group_id,
+                    pattern: "pipeline".to_string(),
+                    execution_time: start_time.elapsed(),
+                    agent_responses,
+                    state_changes: Some(state_changes),
+                })
+                .await;
         });
 
         Ok(Box::new(ReceiverStream::new(rx)))
@@ -76,281 +136,872 @@ impl GroupManager for PipelineManager {
     }
 }
 
-impl PipelineManager {
-    async fn do_pipeline(
-        &self,
-        group: &crate::coordination::groups::AgentGroup,
-        agents: &[AgentWithMembership<Arc<dyn Agent>>],
-        message: Message,
-    ) -> Result<(Vec<AgentResponse>, Option<GroupState>)> {
-        use crate::coordination::types::PipelineExecution;
-        use uuid::Uuid;
+/// Check that stage names are unique, every dependency names a stage and there are no cycles
+fn validate_dependencies(group_name: &str, stages: &[PipelineStage]) -> Result<()> {
+    let mut names: HashSet<&str> = HashSet::new();
+    for stage in stages {
+        if !names.insert(stage.name.as_str()) {
+            return Err(CoreError::AgentGroupError {
+                group_name: group_name.to_string(),
+                operation: format!("stage_{}", stage.name),
+                cause: format!(
+                    "More than one stage is named '{}'; dependencies need unique names",
+                    stage.name
+                ),
+            });
+        }
+    }
+    for stage in stages {
+        if let Some(missing) = stage
+            .depends_on
+            .iter()
+            .find(|d| !names.contains(d.as_str()))
+        {
+            return Err(CoreError::AgentGroupError {
+                group_name: group_name.to_string(),
+                operation: format!("stage_{}", stage.name),
+                cause: format!(
+                    "Stage '{}' depends on unknown stage '{}'",
+                    stage.name, missing
+                ),
+            });
+        }
+    }
 
-        // Extract pipeline config
-        let (stages, parallel_stages) = match &group.coordination_pattern {
-            crate::coordination::types::CoordinationPattern::Pipeline {
-                stages,
-                parallel_stages,
-            } => (stages, *parallel_stages),
-            _ => {
-                return Err(CoreError::AgentGroupError {
-                    group_name: group.name.clone(),
-                    operation: "route_message".to_string(),
-                    cause: "Invalid pattern for PipelineManager".to_string(),
-                });
-            }
-        };
+    // Peel off stages whose dependencies are all resolved; anything left is a cycle
+    let mut resolved: HashSet<&str> = HashSet::new();
+    while resolved.len() < stages.len() {
+        let ready: Vec<&str> = stages
+            .iter()
+            .filter(|s| !resolved.contains(s.name.as_str()))
+            .filter(|s| s.depends_on.iter().all(|d| resolved.contains(d.as_str())))
+            .map(|s| s.name.as_str())
+            .collect();
+
+        if ready.is_empty() {
+            return Err(CoreError::AgentGroupError {
+                group_name: group_name.to_string(),
+                operation: "route_message".to_string(),
+                cause: "Pipeline stage dependencies form a cycle".to_string(),
+            });
+        }
+        resolved.extend(ready);
+    }
+
+    Ok(())
+}
+
+/// What a finished stage hands on to the stages after it
+#[derive(Debug, Clone, Default)]
+struct StageOutput {
+    /// (source, text) pairs; one per agent with `AllToNextStage`, otherwise one in total
+    sections: Vec<(String, String)>,
+}
+
+impl StageOutput {
+    fn to_json(&self) -> serde_json::Value {
+        self.sections
+            .iter()
+            .map(|(from, text)| serde_json::json!({ "from": from, "text": text }))
+            .collect()
+    }
 
-        // Get or create pipeline execution
-        let mut execution = match &group.state {
-            GroupState::Pipeline { active_executions } => {
-                // For simplicity, we'll use the first active execution or create new
-                active_executions
-                    .first()
-                    .cloned()
-                    .unwrap_or_else(|| PipelineExecution {
-                        id: Uuid::new_v4(),
-                        current_stage: 0,
-                        stage_results: Vec::new(),
-                        started_at: Utc::now(),
+    /// Rebuild the output of a stage finished during an earlier message
+    fn from_result(result: &StageResult) -> Self {
+        let sections = result
+            .output
+            .get("sections")
+            .and_then(|s| s.as_array())
+            .map(|sections| {
+                sections
+                    .iter()
+                    .filter_map(|s| {
+                        Some((
+                            s.get("from")?.as_str()?.to_string(),
+                            s.get("text")?.as_str()?.to_string(),
+                        ))
                     })
-            }
-            _ => PipelineExecution {
-                id: Uuid::new_v4(),
-                current_stage: 0,
-                stage_results: Vec::new(),
-                started_at: Utc::now(),
-            },
-        };
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { sections }
+    }
+}
+
+/// Whether a recorded stage no longer needs to run
+fn is_settled(result: &StageResult) -> bool {
+    result.success || result.output.get("skipped") == Some(&serde_json::Value::Bool(true))
+}
+
+/// How a stage ended
+enum StageOutcome {
+    /// The stage produced output, possibly through its fallback agent
+    Completed(StageOutput),
+    /// The stage failed and was skipped; later stages see its input instead
+    Skipped,
+    /// The stage failed and the pipeline has to stop
+    Aborted,
+}
+
+struct StageRun {
+    outcome: StageOutcome,
+    responses: Vec<AgentResponse>,
+    result: StageResult,
+}
+
+/// Result of a single agent's attempt at a stage
+struct AgentRun {
+    agent_id: AgentId,
+    agent_name: String,
+    outcome: std::result::Result<Response, String>,
+    timed_out: bool,
+}
+
+impl AgentRun {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "agent_id": self.agent_id,
+            "agent_name": self.agent_name,
+            "success": self.outcome.is_ok(),
+            "timed_out": self.timed_out,
+            "error": self.outcome.as_ref().err(),
+        })
+    }
+}
 
+/// A single pass of a message through the pipeline
+struct PipelineRun {
+    stages: Vec<PipelineStage>,
+    agents: Vec<Member>,
+    original: Message,
+    tx: Sender<GroupResponseEvent>,
+}
+
+impl PipelineRun {
+    /// Run stages one after another, each receiving the previous stage's output
+    async fn run_sequential(
+        &self,
+        mut execution: PipelineExecution,
+    ) -> (Vec<AgentResponse>, PipelineExecution) {
         let mut responses = Vec::new();
-        let mut all_stage_results = execution.stage_results.clone();
 
-        // Process stages
-        if parallel_stages {
-            // TODO: Implement parallel processing
-            // For now, process sequentially
+        // Pick up the output of the last stage finished before a resume
+        let mut previous = execution
+            .stage_results
+            .iter()
+            .rev()
+            .find(|r| r.success)
+            .map(StageOutput::from_result);
+
+        while execution.current_stage < self.stages.len() {
+            let stage = &self.stages[execution.current_stage];
+            let input = self.stage_input(stage, previous.iter());
+            let run = self.run_stage(stage, input).await;
+
+            responses.extend(run.responses);
+            execution.stage_results.push(run.result);
+
+            match run.outcome {
+                StageOutcome::Completed(output) => previous = Some(output),
+                StageOutcome::Skipped => {}
+                StageOutcome::Aborted => break,
+            }
+            execution.current_stage += 1;
         }
 
-        // Sequential processing
-        while execution.current_stage < stages.len() {
-            let stage = &stages[execution.current_stage];
-
-            match self
-                .process_stage(
-                    stage,
-                    execution.current_stage,
-                    &message,
-                    agents,
-                    group.name.clone(),
-                )
-                .await
-            {
-                Ok((response, result)) => {
-                    responses.push(response);
-                    all_stage_results.push(result);
-                    execution.current_stage += 1;
-                }
-                Err(e) => {
-                    // Handle stage failure
-                    let failure_result = self
-                        .handle_stage_failure(stage, execution.current_stage, e, agents)
-                        .await?;
-
-                    if let Some((response, result)) = failure_result {
-                        responses.push(response);
-                        all_stage_results.push(result);
-                        execution.current_stage += 1;
-                    } else {
-                        // Pipeline aborted
-                        break;
+        (responses, execution)
+    }
+
+    /// Run every stage as soon as the stages it depends on have finished
+    async fn run_parallel(
+        &self,
+        mut execution: PipelineExecution,
+    ) -> (Vec<AgentResponse>, PipelineExecution) {
+        let mut responses = Vec::new();
+
+        // Outputs of settled stages, None for skipped ones
+        let mut settled: HashMap<String, Option<StageOutput>> = execution
+            .stage_results
+            .iter()
+            .filter(|r| is_settled(r))
+            .map(|r| {
+                let output = r.success.then(|| StageOutput::from_result(r));
+                (r.stage_name.clone(), output)
+            })
+            .collect();
+        let mut started: HashSet<String> = settled.keys().cloned().collect();
+        let mut running = FuturesUnordered::new();
+        let mut aborted = false;
+
+        loop {
+            if !aborted {
+                for stage in &self.stages {
+                    if started.contains(&stage.name)
+                        || !stage.depends_on.iter().all(|d| settled.contains_key(d))
+                    {
+                        continue;
                     }
+
+                    let inputs = stage
+                        .depends_on
+                        .iter()
+                        .filter_map(|d| settled.get(d).and_then(Option::as_ref));
+                    let input = self.stage_input(stage, inputs);
+                    started.insert(stage.name.clone());
+                    running.push(self.run_stage(stage, input));
                 }
             }
-        }
 
-        // Update execution state
-        execution.stage_results = all_stage_results;
+            // Stages already running finish even after an abort
+            let Some(run) = running.next().await else {
+                break;
+            };
 
-        // Determine if pipeline is complete
-        let new_state = if execution.current_stage >= stages.len() {
-            // Pipeline complete, clear execution
-            Some(GroupState::Pipeline {
-                active_executions: vec![],
-            })
-        } else {
-            // Pipeline still in progress
-            Some(GroupState::Pipeline {
-                active_executions: vec![execution],
-            })
-        };
+            let stage_name = run.result.stage_name.clone();
+            responses.extend(run.responses);
+            execution.stage_results.push(run.result);
+
+            match run.outcome {
+                StageOutcome::Completed(output) => {
+                    settled.insert(stage_name, Some(output));
+                }
+                StageOutcome::Skipped => {
+                    settled.insert(stage_name, None);
+                }
+                StageOutcome::Aborted => aborted = true,
+            }
+        }
 
-        Ok((responses, new_state))
+        execution.current_stage = settled.len();
+        (responses, execution)
     }
 
-    async fn process_stage(
+    /// Build the message a stage receives from the original message and earlier outputs
+    fn stage_input<'a>(
         &self,
         stage: &PipelineStage,
-        _stage_index: usize,
-        message: &Message,
-        agents: &[AgentWithMembership<Arc<dyn Agent>>],
-        group_name: String,
-    ) -> Result<(AgentResponse, StageResult)> {
+        inputs: impl Iterator<Item = &'a StageOutput>,
+    ) -> Message {
+        let sections: Vec<&(String, String)> = inputs.flat_map(|o| o.sections.iter()).collect();
+        if sections.is_empty() {
+            return self.original.clone();
+        }
+
+        let mut text = self.original.text_content().unwrap_or_default();
+        text.push_str(&format!(
+            "\n\n[Pipeline stage '{}'] Output from earlier stages:",
+            stage.name
+        ));
+        for (from, body) in &sections {
+            text.push_str(&format!("\n\n[Output from {}]\n{}", from, body));
+        }
+
+        let mut input = Message::user(text);
+        input.owner_id = self.original.owner_id.clone();
+        input.metadata = self.original.metadata.clone();
+        input.metadata.custom = serde_json::json!({
+            "pipeline_stage": stage.name,
+            STAGE_INPUTS_METADATA_KEY: sections
+                .iter()
+                .map(|(from, text)| serde_json::json!({ "from": from, "text": text }))
+                .collect::<Vec<_>>(),
+        });
+        input
+    }
+
+    async fn run_stage(&self, stage: &PipelineStage, input: Message) -> StageRun {
         let stage_start = Instant::now();
+        // Retries and the fallback agent all share the stage's time budget
+        let deadline = tokio::time::Instant::now() + stage.timeout;
 
-        // Select an agent for this stage
-        let agent_id = stage
+        let members: Vec<&Member> = stage
             .agent_ids
-            .first()
-            .ok_or_else(|| CoreError::AgentGroupError {
-                group_name: group_name.clone(),
-                operation: format!("stage_{}", stage.name),
-                cause: format!("No agents configured for stage '{}'", stage.name),
-            })?;
-
-        // Verify agent exists and is active
-        let awm = agents
             .iter()
-            .find(|awm| &awm.agent.as_ref().id() == agent_id)
-            .ok_or_else(|| CoreError::AgentGroupError {
-                group_name: group_name.clone(),
-                operation: format!("stage_{}", stage.name),
-                cause: format!("Agent '{}' not found", agent_id),
-            })?;
+            .filter_map(|id| self.agents.iter().find(|awm| &awm.agent.id() == id))
+            .filter(|awm| awm.membership.is_active)
+            .collect();
 
-        if !awm.membership.is_active {
-            return Err(CoreError::AgentGroupError {
-                group_name,
-                operation: format!("stage_{}", stage.name),
-                cause: format!("Agent {} is not active", agent_id),
-            });
+        let attempts = match &stage.on_failure {
+            StageFailureAction::Retry { max_attempts } => (*max_attempts).max(1),
+            _ => 1,
+        };
+
+        let mut agent_runs = Vec::new();
+        let mut error = format!("No active agents for stage '{}'", stage.name);
+        for attempt in 1..=attempts {
+            if members.is_empty() {
+                break;
+            }
+
+            agent_runs = self.fan_out(stage, &members, &input, deadline).await;
+            let (successes, failures): (Vec<_>, Vec<_>) =
+                agent_runs.iter().partition(|run| run.outcome.is_ok());
+
+            if !successes.is_empty() {
+                let output = merge_outputs(stage, &successes);
+                let responses = successes
+                    .iter()
+                    .filter_map(|run| {
+                        Some(AgentResponse {
+                            agent_id: run.agent_id.clone(),
+                            response: run.outcome.as_ref().ok()?.clone(),
+                            responded_at: Utc::now(),
+                        })
+                    })
+                    .collect();
+
+                let result = StageResult {
+                    stage_name: stage.name.clone(),
+                    agent_id: successes[0].agent_id.clone(),
+                    success: true,
+                    duration: stage_start.elapsed(),
+                    timed_out: failures.iter().any(|run| run.timed_out),
+                    output: serde_json::json!({
+                        "stage": stage.name,
+                        "merge": stage.merge,
+                        "attempts": attempt,
+                        "sections": output.to_json(),
+                        "agents": agent_runs.iter().map(AgentRun::to_json).collect::<Vec<_>>(),
+                    }),
+                };
+
+                return StageRun {
+                    outcome: StageOutcome::Completed(output),
+                    responses,
+                    result,
+                };
+            }
+
+            error = failures
+                .iter()
+                .filter_map(|run| run.outcome.as_ref().err())
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("; ");
+            if attempt < attempts && tokio::time::Instant::now() >= deadline {
+                tracing::warn!(
+                    "Pipeline stage '{}' ran out of time after {} of {} attempts: {}",
+                    stage.name,
+                    attempt,
+                    attempts,
+                    error
+                );
+                break;
+            }
+            if attempt < attempts {
+                tracing::warn!(
+                    "Pipeline stage '{}' failed (attempt {}/{}): {}",
+                    stage.name,
+                    attempt,
+                    attempts,
+                    error
+                );
+            }
         }
 
-        // Process message with selected agent
-        let agent_response = awm.agent.clone().process_message(message.clone()).await?;
-        let response = AgentResponse {
-            agent_id: awm.agent.as_ref().id(),
-            response: agent_response,
-            responded_at: Utc::now(),
-        };
+        self.handle_stage_failure(stage, &input, stage_start, deadline, agent_runs, error)
+            .await
+    }
 
-        let result = StageResult {
-            stage_name: stage.name.clone(),
-            agent_id: awm.agent.as_ref().id(),
-            success: true,
-            duration: stage_start.elapsed(),
-            output: serde_json::json!({
-                "stage": stage.name,
-                "processed": true,
-                "message_preview": "<message preview>"
-            }),
-        };
+    /// Send the stage input to every member at once, all bound by the stage deadline
+    async fn fan_out(
+        &self,
+        stage: &PipelineStage,
+        members: &[&Member],
+        input: &Message,
+        deadline: tokio::time::Instant,
+    ) -> Vec<AgentRun> {
+        let mut running: FuturesUnordered<_> = members
+            .iter()
+            .map(|awm| self.call_agent(awm, input.clone(), deadline))
+            .collect();
+
+        let mut runs = Vec::with_capacity(members.len());
+        while let Some(run) = running.next().await {
+            let succeeded = run.outcome.is_ok();
+            runs.push(run);
+            // Dropping the remaining futures cancels the slower agents
+            if succeeded && stage.merge == StageMerge::FirstSuccess {
+                break;
+            }
+        }
+        runs
+    }
+
+    async fn call_agent(
+        &self,
+        awm: &Member,
+        input: Message,
+        deadline: tokio::time::Instant,
+    ) -> AgentRun {
+        let agent_id = awm.agent.id();
+        let agent_name = awm.agent.name();
+
+        let _ = self
+            .tx
+            .send(GroupResponseEvent::AgentStarted {
+                agent_id: agent_id.clone(),
+                agent_name: agent_name.clone(),
+                role: awm.membership.role.clone(),
+            })
+            .await;
+
+        let (outcome, timed_out) =
+            match tokio::time::timeout_at(deadline, awm.agent.clone().process_message(input)).await
+            {
+                Ok(Ok(response)) => (Ok(response), false),
+                Ok(Err(e)) => (Err(e.to_string()), false),
+                Err(_) => (
+                    Err(format!(
+                        "{} did not finish before the stage timeout",
+                        agent_name
+                    )),
+                    true,
+                ),
+            };
+
+        match &outcome {
+            Ok(response) => {
+                let text = response_text(response);
+                if !text.is_empty() {
+                    let _ = self
+                        .tx
+                        .send(GroupResponseEvent::TextChunk {
+                            agent_id: agent_id.clone(),
+                            text,
+                            is_final: true,
+                        })
+                        .await;
+                }
+                let _ = self
+                    .tx
+                    .send(GroupResponseEvent::AgentCompleted {
+                        agent_id: agent_id.clone(),
+                        agent_name: agent_name.clone(),
+                        message_id: None,
+                    })
+                    .await;
+            }
+            Err(message) => {
+                let _ = self
+                    .tx
+                    .send(GroupResponseEvent::Error {
+                        agent_id: Some(agent_id.clone()),
+                        message: message.clone(),
+                        recoverable: true,
+                    })
+                    .await;
+            }
+        }
 
-        Ok((response, result))
+        AgentRun {
+            agent_id,
+            agent_name,
+            outcome,
+            timed_out,
+        }
     }
 
     async fn handle_stage_failure(
         &self,
         stage: &PipelineStage,
-        stage_index: usize,
-        error: CoreError,
-        agents: &[AgentWithMembership<Arc<dyn Agent>>],
-    ) -> Result<Option<(AgentResponse, StageResult)>> {
+        input: &Message,
+        stage_start: Instant,
+        deadline: tokio::time::Instant,
+        agent_runs: Vec<AgentRun>,
+        error: String,
+    ) -> StageRun {
+        let agent_id = stage
+            .agent_ids
+            .first()
+            .cloned()
+            .unwrap_or_else(AgentId::generate);
+        let timed_out = agent_runs.iter().any(|run| run.timed_out);
+        let agents = agent_runs.iter().map(AgentRun::to_json).collect::<Vec<_>>();
+
         match &stage.on_failure {
-            StageFailureAction::Skip => {
-                // Skip the stage and continue
-                let response = AgentResponse {
-                    agent_id: stage
-                        .agent_ids
-                        .first()
-                        .cloned()
-                        .unwrap_or_else(AgentId::generate),
+            StageFailureAction::Skip => StageRun {
+                outcome: StageOutcome::Skipped,
+                responses: vec![AgentResponse {
+                    agent_id: agent_id.clone(),
                     response: text_response(format!(
-                        "[Pipeline Stage {}: {} - SKIPPED] Error: {:?}",
-                        stage_index + 1,
-                        stage.name,
-                        error
+                        "[Pipeline Stage: {} - SKIPPED] Error: {}",
+                        stage.name, error
                     )),
                     responded_at: Utc::now(),
-                };
-
-                let result = StageResult {
+                }],
+                result: StageResult {
                     stage_name: stage.name.clone(),
-                    agent_id: stage
-                        .agent_ids
-                        .first()
-                        .cloned()
-                        .unwrap_or_else(AgentId::generate),
+                    agent_id,
                     success: false,
-                    duration: std::time::Duration::from_secs(0),
+                    duration: stage_start.elapsed(),
+                    timed_out,
                     output: serde_json::json!({
                         "stage": stage.name,
                         "skipped": true,
-                        "error": error.to_string()
+                        "error": error,
+                        "agents": agents,
                     }),
-                };
-
-                Ok(Some((response, result)))
-            }
-            StageFailureAction::Retry { max_attempts } => {
-                // In a real implementation, would track retry count
-                // For now, just fail after pretending to retry
-                Err(CoreError::AgentGroupError {
-                    group_name: "pipeline".to_string(),
-                    operation: format!("stage_{}_retry", stage.name),
-                    cause: format!(
-                        "Stage '{}' failed after {} attempts",
-                        stage.name, max_attempts
-                    ),
-                })
-            }
-            StageFailureAction::Abort => {
-                // Abort the entire pipeline
-                Ok(None)
-            }
-            StageFailureAction::Fallback { agent_id } => {
-                // Use fallback agent
-                let awm = agents
+                },
+            },
+            StageFailureAction::Fallback {
+                agent_id: fallback_id,
+            } => {
+                let fallback = self
+                    .agents
                     .iter()
-                    .find(|awm| &awm.agent.as_ref().id() == agent_id)
-                    .ok_or_else(|| CoreError::AgentGroupError {
-                        group_name: "pipeline".to_string(),
-                        operation: format!("stage_{}_fallback", stage.name),
-                        cause: format!("Fallback agent '{}' not found", agent_id),
-                    })?;
-
-                if !awm.membership.is_active {
-                    return Err(CoreError::AgentGroupError {
-                        group_name: "pipeline".to_string(),
-                        operation: format!("stage_{}_fallback", stage.name),
-                        cause: format!("Fallback agent {} is not active", agent_id),
-                    });
-                }
+                    .find(|awm| &awm.agent.id() == fallback_id && awm.membership.is_active);
 
-                let response = AgentResponse {
-                    agent_id: awm.agent.as_ref().id(),
-                    response: text_response(format!(
-                        "[Pipeline Stage {}: {} - FALLBACK] Handling after primary failure",
-                        stage_index + 1,
-                        stage.name
-                    )),
-                    responded_at: Utc::now(),
+                let fallback_run = match fallback {
+                    Some(awm) => Some(self.call_agent(awm, input.clone(), deadline).await),
+                    None => None,
                 };
 
-                let result = StageResult {
+                match fallback_run {
+                    Some(AgentRun {
+                        agent_id,
+                        agent_name,
+                        outcome: Ok(response),
+                        ..
+                    }) => {
+                        let output = StageOutput {
+                            sections: vec![(agent_name, response_text(&response))],
+                        };
+                        StageRun {
+                            result: StageResult {
+                                stage_name: stage.name.clone(),
+                                agent_id: agent_id.clone(),
+                                success: true,
+                                duration: stage_start.elapsed(),
+                                timed_out,
+                                output: serde_json::json!({
+                                    "stage": stage.name,
+                                    "fallback": true,
+                                    "original_error": error,
+                                    "sections": output.to_json(),
+                                    "agents": agents,
+                                }),
+                            },
+                            outcome: StageOutcome::Completed(output),
+                            responses: vec![AgentResponse {
+                                agent_id,
+                                response,
+                                responded_at: Utc::now(),
+                            }],
+                        }
+                    }
+                    other => {
+                        let fallback_error = match other {
+                            Some(run) => run.outcome.err().unwrap_or_default(),
+                            None => format!("Fallback agent '{}' not available", fallback_id),
+                        };
+                        StageRun {
+                            outcome: StageOutcome::Aborted,
+                            responses: vec![],
+                            result: StageResult {
+                                stage_name: stage.name.clone(),
+                                agent_id: fallback_id.clone(),
+                                success: false,
+                                duration: stage_start.elapsed(),
+                                timed_out,
+                                output: serde_json::json!({
+                                    "stage": stage.name,
+                                    "fallback": true,
+                                    "error": fallback_error,
+                                    "original_error": error,
+                                    "agents": agents,
+                                }),
+                            },
+                        }
+                    }
+                }
+            }
+            StageFailureAction::Abort | StageFailureAction::Retry { .. } => StageRun {
+                outcome: StageOutcome::Aborted,
+                responses: vec![],
+                result: StageResult {
                     stage_name: stage.name.clone(),
-                    agent_id: awm.agent.as_ref().id(),
-                    success: true,
-                    duration: std::time::Duration::from_secs(1),
+                    agent_id,
+                    success: false,
+                    duration: stage_start.elapsed(),
+                    timed_out,
                     output: serde_json::json!({
                         "stage": stage.name,
-                        "fallback": true,
-                        "original_error": error.to_string()
+                        "error": error,
+                        "agents": agents,
                     }),
-                };
+                },
+            },
+        }
+    }
+}
+
+/// Combine the successful agent outputs according to the stage's merge setting
+fn merge_outputs(stage: &PipelineStage, successes: &[&AgentRun]) -> StageOutput {
+    let texts = successes.iter().filter_map(|run| {
+        let response = run.outcome.as_ref().ok()?;
+        Some((run.agent_name.clone(), response_text(response)))
+    });
+
+    let sections = match stage.merge {
+        StageMerge::Concatenate => {
+            let joined = texts.map(|(_, text)| text).collect::<Vec<_>>().join("\n\n");
+            vec![(stage.name.clone(), joined)]
+        }
+        StageMerge::FirstSuccess => texts.take(1).collect(),
+        StageMerge::AllToNextStage => texts.collect(),
+    };
+
+    StageOutput { sections }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        coordination::{
+            AgentGroup,
+            groups::GroupMembership,
+            test_utils::test::{collect_complete_event, create_echo_agent, create_test_message},
+            types::GroupMemberRole,
+        },
+        id::{GroupId, RelationId},
+    };
+
+    fn member(agent: Arc<dyn Agent>) -> Member {
+        Member {
+            membership: GroupMembership {
+                id: RelationId::generate(),
+                in_id: agent.id(),
+                out_id: GroupId::generate(),
+                joined_at: Utc::now(),
+                role: GroupMemberRole::Regular,
+                is_active: true,
+                capabilities: vec![],
+            },
+            agent,
+        }
+    }
+
+    fn stage(name: &str, agents: &[&Member], merge: StageMerge) -> PipelineStage {
+        PipelineStage {
+            name: name.to_string(),
+            agent_ids: agents.iter().map(|m| m.agent.id()).collect(),
+            timeout: Duration::from_secs(5),
+            on_failure: StageFailureAction::Abort,
+            merge,
+            depends_on: vec![],
+        }
+    }
 
-                Ok(Some((response, result)))
+    fn pipeline_group(stages: Vec<PipelineStage>, parallel_stages: bool) -> AgentGroup {
+        AgentGroup {
+            id: GroupId::generate(),
+            name: "TestPipeline".to_string(),
+            description: "Test pipeline group".to_string(),
+            coordination_pattern: CoordinationPattern::Pipeline {
+                stages,
+                parallel_stages,
+            },
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            is_active: true,
+            state: GroupState::Pipeline {
+                active_executions: vec![],
+            },
+            members: vec![],
+        }
+    }
+
+    fn text_of(response: &AgentResponse) -> String {
+        response_text(&response.response)
+    }
+
+    #[tokio::test]
+    async fn test_sequential_stages_chain_outputs() {
+        let analyzer = member(Arc::new(create_echo_agent("Analyzer", Duration::ZERO)));
+        let planner = member(Arc::new(create_echo_agent("Planner", Duration::ZERO)));
+        let group = pipeline_group(
+            vec![
+                stage("analyze", &[&analyzer], StageMerge::Concatenate),
+                stage("plan", &[&planner], StageMerge::Concatenate),
+            ],
+            false,
+        );
+
+        let stream = PipelineManager
+            .route_message(
+                &group,
+                &[analyzer.clone(), planner.clone()],
+                create_test_message("Sort my inbox"),
+            )
+            .await
+            .unwrap();
+        let (responses, state) = collect_complete_event(stream).await;
+
+        assert_eq!(responses.len(), 2);
+        assert!(text_of(&responses[1]).contains("[Output from analyze]"));
+        assert!(text_of(&responses[1]).contains("Analyzer saw: Sort my inbox"));
+        assert!(matches!(
+            state,
+            Some(GroupState::Pipeline { active_executions }) if active_executions.is_empty()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fan_out_merges() {
+        let a = member(Arc::new(create_echo_agent("A", Duration::ZERO)));
+        let b = member(Arc::new(create_echo_agent("B", Duration::from_millis(200))));
+        let next = member(Arc::new(create_echo_agent("Next", Duration::ZERO)));
+        let agents = [a.clone(), b.clone(), next.clone()];
+
+        for (merge, expected_sections) in [
+            (StageMerge::Concatenate, 1),
+            (StageMerge::FirstSuccess, 1),
+            (StageMerge::AllToNextStage, 2),
+        ] {
+            let group = pipeline_group(
+                vec![
+                    stage("research", &[&a, &b], merge),
+                    stage("summarize", &[&next], StageMerge::Concatenate),
+                ],
+                false,
+            );
+            let stream = PipelineManager
+                .route_message(&group, &agents, create_test_message("Topic"))
+                .await
+                .unwrap();
+            let (responses, _) = collect_complete_event(stream).await;
+
+            let summary = text_of(responses.last().unwrap());
+            assert_eq!(
+                summary.matches("[Output from").count(),
+                expected_sections,
+                "{:?}",
+                merge
+            );
+            if merge == StageMerge::FirstSuccess {
+                assert_eq!(responses.len(), 2);
+                assert!(summary.contains("A saw"));
+                assert!(!summary.contains("B saw"));
+            } else {
+                assert_eq!(responses.len(), 3);
+                assert!(summary.contains("A saw") && summary.contains("B saw"));
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_independent_stages_run_concurrently() {
+        let delay = Duration::from_millis(300);
+        let analysts: Vec<Member> = ["One", "Two", "Three"]
+            .iter()
+            .map(|name| member(Arc::new(create_echo_agent(name, delay))))
+            .collect();
+        let writer = member(Arc::new(create_echo_agent("Writer", Duration::ZERO)));
+
+        let mut stages: Vec<PipelineStage> = analysts
+            .iter()
+            .enumerate()
+            .map(|(i, m)| stage(&format!("analysis_{}", i), &[m], StageMerge::Concatenate))
+            .collect();
+        let mut report = stage("report", &[&writer], StageMerge::Concatenate);
+        report.depends_on = stages.iter().map(|s| s.name.clone()).collect();
+        stages.push(report);
+
+        let mut agents = analysts.clone();
+        agents.push(writer);
+        let group = pipeline_group(stages, true);
+
+        let started = Instant::now();
+        let stream = PipelineManager
+            .route_message(&group, &agents, create_test_message("Research question"))
+            .await
+            .unwrap();
+        let (responses, _) = collect_complete_event(stream).await;
+
+        assert!(started.elapsed() < delay * 2);
+        assert_eq!(responses.len(), 4);
+        let report = text_of(responses.last().unwrap());
+        for name in ["One", "Two", "Three"] {
+            assert!(report.contains(&format!("{} saw", name)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stage_timeout_is_recorded() {
+        let slow = member(Arc::new(create_echo_agent("Slow", Duration::from_secs(5))));
+        let mut timed = stage("slow", &[&slow], StageMerge::Concatenate);
+        timed.timeout = Duration::from_millis(50);
+        let group = pipeline_group(vec![timed], false);
+
+        let stream = PipelineManager
+            .route_message(&group, &[slow], create_test_message("Hurry"))
+            .await
+            .unwrap();
+        let (responses, state) = collect_complete_event(stream).await;
+
+        assert!(responses.is_empty());
+        let Some(GroupState::Pipeline { active_executions }) = state else {
+            panic!("Expected pipeline state");
+        };
+        let result = &active_executions[0].stage_results[0];
+        assert!(!result.success);
+        assert!(result.timed_out);
+    }
+
+    #[tokio::test]
+    async fn test_retries_share_stage_timeout() {
+        let slow = member(Arc::new(create_echo_agent("Slow", Duration::from_secs(5))));
+        let mut timed = stage("slow", &[&slow], StageMerge::Concatenate);
+        timed.timeout = Duration::from_millis(100);
+        timed.on_failure = StageFailureAction::Retry { max_attempts: 3 };
+        let group = pipeline_group(vec![timed], false);
+
+        let started = Instant::now();
+        let stream = PipelineManager
+            .route_message(&group, &[slow], create_test_message("Hurry"))
+            .await
+            .unwrap();
+        let (responses, _) = collect_complete_event(stream).await;
+
+        assert!(responses.is_empty());
+        assert!(started.elapsed() < Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_duplicate_stage_names_rejected() {
+        let a = member(Arc::new(create_echo_agent("A", Duration::ZERO)));
+        let stages = vec![
+            stage("draft", &[&a], StageMerge::Concatenate),
+            stage("draft", &[&a], StageMerge::Concatenate),
+        ];
+
+        match validate_dependencies("TestPipeline", &stages) {
+            Err(CoreError::AgentGroupError { cause, .. }) => {
+                assert!(cause.contains("'draft'"), "{}", cause);
+                assert!(!cause.contains("cycle"), "{}", cause);
+            }
+            other => panic!("Expected a duplicate name error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dependency_cycle_rejected() {
+        let a = member(Arc::new(create_echo_agent("A", Duration::ZERO)));
+        let mut first = stage("first", &[&a], StageMerge::Concatenate);
+        first.depends_on = vec!["second".to_string()];
+        let mut second = stage("second", &[&a], StageMerge::Concatenate);
+        second.depends_on = vec!["first".to_string()];
+        let group = pipeline_group(vec![first, second], true);
+
+        assert!(
+            PipelineManager
+                .route_message(&group, &[a], create_test_message("Loop"))
+                .await
+                .is_err()
+        );
+    }
 }
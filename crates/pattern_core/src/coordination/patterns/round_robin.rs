@@ -9,8 +9,9 @@ use crate::{
     Result,
     agent::Agent,
     coordination::{
-        groups::{AgentWithMembership, GroupManager, GroupResponse},
+        groups::{AgentResponse, AgentWithMembership, GroupManager, GroupResponse},
         types::{CoordinationPattern, GroupState},
+        utils::ResponseAccumulator,
     },
     message::Message,
 };
@@ -117,6 +118,7 @@ impl GroupManager for RoundRobinManager {
                 .await;
 
             // Process message with streaming
+            let mut agent_responses = Vec::new();
             match awm
                 .agent
                 .clone()
@@ -126,7 +128,10 @@ impl GroupManager for RoundRobinManager {
                 Ok(mut stream) => {
                     use tokio_stream::StreamExt;
 
+                    let mut accumulator = ResponseAccumulator::new();
                     while let Some(event) = stream.next().await {
+                        accumulator.push(&event);
+
                         // Convert ResponseEvent to GroupResponseEvent
                         match event {
                             crate::agent::ResponseEvent::TextChunk { text, is_final } => {
@@ -194,6 +199,12 @@ impl GroupManager for RoundRobinManager {
                             _ => {} // Skip other events
                         }
                     }
+
+                    agent_responses.push(AgentResponse {
+                        agent_id: agent_id.clone(),
+                        response: accumulator.finish(),
+                        responded_at: Utc::now(),
+                    });
                 }
                 Err(e) => {
                     let _ = tx
@@ -227,7 +238,7 @@ impl GroupManager for RoundRobinManager {
                     group_id,
                     pattern: "round_robin".to_string(),
                     execution_time: start_time.elapsed(),
-                    agent_responses: vec![], // TODO: Collect actual responses
+                    agent_responses,
                     state_changes: Some(new_state),
                 })
                 .await;
@@ -255,7 +266,10 @@ mod tests {
         coordination::{
             AgentGroup,
             groups::{AgentWithMembership, GroupMembership},
-            test_utils::test::{collect_agent_responses, create_test_agent, create_test_message},
+            test_utils::test::{
+                collect_agent_responses, collect_complete_event, create_test_agent,
+                create_test_message,
+            },
             types::GroupMemberRole,
         },
         id::{AgentId, GroupId, RelationId},
@@ -430,4 +444,51 @@ mod tests {
         let agent_responses2 = collect_agent_responses(stream2).await;
         assert_eq!(agent_responses2[0].agent_id, agents[1].agent.id());
     }
+
+    #[tokio::test]
+    async fn test_round_robin_complete_carries_response() {
+        let agent = Arc::new(create_test_agent("Agent1")) as Arc<dyn crate::agent::Agent>;
+        let agents = vec![AgentWithMembership {
+            agent: agent.clone(),
+            membership: GroupMembership {
+                id: RelationId::generate(),
+                in_id: agent.id(),
+                out_id: GroupId::generate(),
+                joined_at: Utc::now(),
+                role: GroupMemberRole::Regular,
+                is_active: true,
+                capabilities: vec![],
+            },
+        }];
+
+        let group = AgentGroup {
+            id: GroupId::generate(),
+            name: "TestGroup".to_string(),
+            description: "Test round-robin responses".to_string(),
+            coordination_pattern: CoordinationPattern::RoundRobin {
+                current_index: 0,
+                skip_unavailable: true,
+            },
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            is_active: true,
+            state: GroupState::RoundRobin {
+                current_index: 0,
+                last_rotation: Utc::now(),
+            },
+            members: vec![],
+        };
+
+        let stream = RoundRobinManager
+            .route_message(&group, &agents, create_test_message("Test message"))
+            .await
+            .unwrap();
+        let (agent_responses, _) = collect_complete_event(stream).await;
+
+        assert_eq!(agent_responses.len(), 1);
+        assert_eq!(
+            agent_responses[0].response.content[0].text(),
+            Some("Agent1 test response")
+        );
+    }
 }
@@ -7,22 +7,50 @@ use std::{sync::Arc, time::Duration};
 use crate::{
     Result,
     agent::Agent,
+    constellation_memory::{
+        ConstellationActivityTracker, ConstellationEvent, ConstellationEventType,
+    },
     context::NON_USER_MESSAGE_PREFIX,
     coordination::{
         groups::{
             AgentResponse, AgentWithMembership, GroupManager, GroupResponse, GroupResponseEvent,
         },
-        types::{
-            CoordinationPattern, GroupState, SleeptimeTrigger, TriggerCondition, TriggerEvent,
-            TriggerPriority,
+        triggers::{
+            DefaultTriggerEvaluatorRegistry, TriggerContext, TriggerEvaluatorRegistry,
+            evaluator_name,
         },
-        utils::text_response,
+        types::{CoordinationPattern, GroupState, SleeptimeTrigger, TriggerEvent, TriggerPriority},
+        utils::{ResponseAccumulator, text_response},
     },
     message::{ChatRole, Message},
 };
 
 #[derive(Clone)]
-pub struct SleeptimeManager;
+pub struct SleeptimeManager {
+    evaluators: Arc<dyn TriggerEvaluatorRegistry>,
+    activity: Option<Arc<ConstellationActivityTracker>>,
+}
+
+impl SleeptimeManager {
+    pub fn new(evaluators: Arc<dyn TriggerEvaluatorRegistry>) -> Self {
+        Self {
+            evaluators,
+            activity: None,
+        }
+    }
+
+    /// Let triggers see (and record syncs in) the constellation activity log
+    pub fn with_activity_tracker(mut self, tracker: Arc<ConstellationActivityTracker>) -> Self {
+        self.activity = Some(tracker);
+        self
+    }
+}
+
+impl Default for SleeptimeManager {
+    fn default() -> Self {
+        Self::new(Arc::new(DefaultTriggerEvaluatorRegistry::new()))
+    }
+}
 
 #[async_trait]
 impl GroupManager for SleeptimeManager {
@@ -41,6 +69,8 @@ impl GroupManager for SleeptimeManager {
         let coordination_pattern = group.coordination_pattern.clone();
         let group_state = group.state.clone();
         let agents = agents.to_vec();
+        let evaluators = self.evaluators.clone();
+        let activity = self.activity.clone();
 
         tokio::spawn(async move {
             // Extract sleeptime config
@@ -128,14 +158,28 @@ impl GroupManager for SleeptimeManager {
 
             if should_check {
                 // Evaluate all triggers
+                let mut trigger_context =
+                    TriggerContext::new(message.clone(), trigger_history.clone(), last_check);
+                if let Some(tracker) = &activity {
+                    trigger_context = trigger_context.with_activity(tracker.clone());
+                }
+                if let Some(source) = agents
+                    .iter()
+                    .find(|awm| awm.agent.id() == selected_agent_id)
+                {
+                    trigger_context = trigger_context.with_message_source(source.agent.clone());
+                }
+
                 let mut fired_triggers = Vec::new();
 
                 for trigger in triggers {
-                    if let Ok(fired) =
-                        Self::evaluate_trigger_static(trigger, &message, &trigger_history).await
+                    match Self::evaluate_trigger(evaluators.as_ref(), trigger, &trigger_context)
+                        .await
                     {
-                        if fired {
-                            fired_triggers.push(trigger);
+                        Ok(true) => fired_triggers.push(trigger),
+                        Ok(false) => {}
+                        Err(e) => {
+                            crate::log_error!(format!("Trigger {} failed", trigger.name), e);
                         }
                     }
                 }
@@ -206,8 +250,10 @@ impl GroupManager for SleeptimeManager {
                             Ok(mut stream) => {
                                 use tokio_stream::StreamExt;
 
-                                let mut _message_id = None;
+                                let mut accumulator = ResponseAccumulator::new();
                                 while let Some(event) = stream.next().await {
+                                    accumulator.push(&event);
+
                                     // Convert ResponseEvent to GroupResponseEvent
                                     match event {
                                         crate::agent::ResponseEvent::TextChunk {
@@ -264,7 +310,6 @@ impl GroupManager for SleeptimeManager {
                                             message_id: msg_id,
                                             ..
                                         } => {
-                                            _message_id = Some(msg_id.clone());
                                             let _ = tx
                                                 .send(GroupResponseEvent::AgentCompleted {
                                                     agent_id: agent_id.clone(),
@@ -292,13 +337,28 @@ impl GroupManager for SleeptimeManager {
                                 // Track response for final summary
                                 agent_responses.push(AgentResponse {
                                     agent_id: agent_id.clone(),
-                                    response: crate::message::Response {
-                                        content: vec![], // TODO: Collect actual response content
-                                        reasoning: None,
-                                        metadata: crate::message::ResponseMetadata::default(),
-                                    },
+                                    response: accumulator.finish(),
                                     responded_at: Utc::now(),
                                 });
+
+                                // Mark the sync so activity triggers count from here
+                                if let Some(tracker) = &activity {
+                                    tracker
+                                        .add_event(ConstellationEvent {
+                                            timestamp: Utc::now(),
+                                            agent_id: agent_id.clone(),
+                                            agent_name: agent_name.clone(),
+                                            event_type: ConstellationEventType::ContextSync {
+                                                synced_agent_id: agent_id.clone(),
+                                            },
+                                            description: format!(
+                                                "{} ran a context sync",
+                                                agent_name
+                                            ),
+                                            metadata: None,
+                                        })
+                                        .await;
+                                }
                             }
                             Err(e) => {
                                 let _ = tx
@@ -489,67 +549,23 @@ impl SleeptimeManager {
             .map(|(awm, _)| awm.agent.id())
     }
 
-    async fn evaluate_trigger_static(
+    /// Evaluate a trigger with the evaluator registered for its condition
+    async fn evaluate_trigger(
+        evaluators: &dyn TriggerEvaluatorRegistry,
         trigger: &SleeptimeTrigger,
-        _message: &Message,
-        history: &[TriggerEvent],
+        context: &TriggerContext,
     ) -> Result<bool> {
-        Self::evaluate_trigger_impl(trigger, _message, history).await
-    }
-
-    async fn evaluate_trigger_impl(
-        trigger: &SleeptimeTrigger,
-        _message: &Message,
-        history: &[TriggerEvent],
-    ) -> Result<bool> {
-        match &trigger.condition {
-            TriggerCondition::TimeElapsed { duration } => {
-                // Check if enough time has passed since last trigger
-                let last_fired = history
-                    .iter()
-                    .filter(|e| e.trigger_name == trigger.name && e.intervention_activated)
-                    .max_by_key(|e| e.timestamp);
-
-                if let Some(last) = last_fired {
-                    let elapsed = Utc::now() - last.timestamp;
-                    Ok(elapsed > ChronoDuration::from_std(*duration).unwrap_or_default())
-                } else {
-                    // Never fired before, so it's elapsed
-                    Ok(true)
-                }
-            }
-            TriggerCondition::PatternDetected { pattern_name } => {
-                // In real implementation, would check for specific patterns
-                // For now, simulate pattern detection
-                Ok(pattern_name.contains("hyperfocus") && rand::random::<f32>() > 0.7)
-            }
-            TriggerCondition::ThresholdExceeded { metric, threshold } => {
-                // In real implementation, would check actual metrics
-                // For now, simulate threshold check
-                Ok(metric.contains("sedentary") && *threshold < 60.0)
-            }
-            TriggerCondition::ConstellationActivity {
-                message_threshold: _,
-                time_threshold,
-            } => {
-                // TODO: Check actual constellation activity
-                // For now, simulate based on time elapsed
-                let last_sync = history
-                    .iter()
-                    .filter(|e| e.trigger_name == trigger.name)
-                    .max_by_key(|e| e.timestamp);
-
-                if let Some(last) = last_sync {
-                    let elapsed = Utc::now() - last.timestamp;
-                    Ok(elapsed > ChronoDuration::from_std(*time_threshold).unwrap_or_default())
-                } else {
-                    // Never synced before
-                    Ok(true)
-                }
-            }
-            TriggerCondition::Custom { evaluator } => {
-                // Would call custom evaluator function
-                Ok(evaluator.contains("custom") && rand::random::<f32>() > 0.8)
+        let name = evaluator_name(&trigger.condition);
+        match evaluators.get(name) {
+            Some(evaluator) => evaluator.evaluate(trigger, context).await,
+            None => {
+                tracing::warn!(
+                    "No trigger evaluator named '{}' for trigger {} (available: {})",
+                    name,
+                    trigger.name,
+                    evaluators.list().join(", ")
+                );
+                Ok(false)
             }
         }
     }
@@ -613,14 +629,14 @@ mod tests {
             AgentGroup,
             groups::{AgentWithMembership, GroupMembership},
             test_utils::test::{collect_complete_event, create_test_agent, create_test_message},
-            types::GroupMemberRole,
+            types::{GroupMemberRole, TriggerCondition},
         },
         id::{AgentId, GroupId, RelationId},
     };
 
     #[tokio::test]
     async fn test_sleeptime_trigger_check() {
-        let manager = SleeptimeManager;
+        let manager = SleeptimeManager::default();
         let intervention_agent = create_test_agent("Pattern");
         let intervention_id = intervention_agent.id.clone();
 
@@ -688,8 +704,9 @@ mod tests {
         // Should have at least one response
         assert!(!agent_responses.is_empty());
 
-        // Response should be from intervention agent
+        // Response should be from intervention agent, with its content
         assert_eq!(agent_responses[0].agent_id, intervention_id);
+        assert!(!agent_responses[0].response.content.is_empty());
 
         // State should be updated with new last_check time
         if let Some(GroupState::Sleeptime { last_check, .. }) = state_changes {
@@ -698,4 +715,101 @@ mod tests {
             panic!("Expected Sleeptime state");
         }
     }
+
+    #[tokio::test]
+    async fn test_constellation_activity_trigger_uses_tracker() {
+        let tracker = Arc::new(ConstellationActivityTracker::new(100));
+        let manager = SleeptimeManager::default().with_activity_tracker(tracker.clone());
+        let intervention_agent = create_test_agent("Pattern");
+        let intervention_id = intervention_agent.id.clone();
+
+        let agents: Vec<AgentWithMembership<Arc<dyn crate::agent::Agent>>> =
+            vec![AgentWithMembership {
+                agent: Arc::new(intervention_agent) as Arc<dyn crate::agent::Agent>,
+                membership: GroupMembership {
+                    id: RelationId::generate(),
+                    in_id: AgentId::generate(),
+                    out_id: GroupId::generate(),
+                    joined_at: Utc::now(),
+                    role: GroupMemberRole::Supervisor,
+                    is_active: true,
+                    capabilities: vec![],
+                },
+            }];
+
+        let group = |trigger_history| AgentGroup {
+            id: GroupId::generate(),
+            name: "SleeptimeGroup".to_string(),
+            description: "Background monitoring group".to_string(),
+            coordination_pattern: CoordinationPattern::Sleeptime {
+                check_interval: Duration::from_secs(1),
+                triggers: vec![SleeptimeTrigger {
+                    name: "activity_sync".to_string(),
+                    condition: TriggerCondition::ConstellationActivity {
+                        message_threshold: 2,
+                        time_threshold: Duration::from_secs(3600),
+                    },
+                    priority: TriggerPriority::Medium,
+                }],
+                intervention_agent_id: Some(intervention_id.clone()),
+            },
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            is_active: true,
+            state: GroupState::Sleeptime {
+                last_check: Utc::now() - ChronoDuration::hours(1),
+                trigger_history,
+                current_index: 0,
+            },
+            members: vec![],
+        };
+        let synced_recently = vec![TriggerEvent {
+            trigger_name: "activity_sync".to_string(),
+            timestamp: Utc::now() - ChronoDuration::minutes(5),
+            intervention_activated: true,
+            metadata: Default::default(),
+        }];
+
+        // Nothing has happened since the last sync
+        let stream = manager
+            .route_message(
+                &group(synced_recently.clone()),
+                &agents,
+                create_test_message("Context sync check"),
+            )
+            .await
+            .unwrap();
+        let (_, state) = collect_complete_event(stream).await;
+        let Some(GroupState::Sleeptime {
+            trigger_history, ..
+        }) = state
+        else {
+            panic!("Expected Sleeptime state");
+        };
+        assert_eq!(trigger_history.len(), 1);
+
+        for _ in 0..2 {
+            tracker
+                .record_metric(intervention_id.clone(), "Pattern".into(), "tasks_open", 3.0)
+                .await;
+        }
+
+        let stream = manager
+            .route_message(
+                &group(synced_recently),
+                &agents,
+                create_test_message("Context sync check"),
+            )
+            .await
+            .unwrap();
+        let (_, state) = collect_complete_event(stream).await;
+        let Some(GroupState::Sleeptime {
+            trigger_history, ..
+        }) = state
+        else {
+            panic!("Expected Sleeptime state");
+        };
+        assert_eq!(trigger_history.len(), 2);
+        assert!(tracker.last_sync().await.is_some());
+    }
 }
@@ -12,7 +12,7 @@ use crate::{
             AgentResponse, AgentWithMembership, GroupManager, GroupResponse, GroupResponseEvent,
         },
         types::{CoordinationPattern, DelegationStrategy, FallbackBehavior, GroupState},
-        utils::text_response,
+        utils::{ResponseAccumulator, text_response},
     },
     message::Message,
 };
@@ -142,7 +142,9 @@ impl GroupManager for SupervisorManager {
                         Ok(mut stream) => {
                             use tokio_stream::StreamExt;
 
+                            let mut accumulator = ResponseAccumulator::new();
                             while let Some(event) = stream.next().await {
+                                accumulator.push(&event);
                                 match event {
                                     crate::agent::ResponseEvent::TextChunk { text, is_final } => {
                                         let _ = tx
@@ -223,11 +225,7 @@ impl GroupManager for SupervisorManager {
 
                             agent_responses.push(AgentResponse {
                                 agent_id: agent_id.clone(),
-                                response: crate::message::Response {
-                                    content: vec![],
-                                    reasoning: None,
-                                    metadata: crate::message::ResponseMetadata::default(),
-                                },
+                                response: accumulator.finish(),
                                 responded_at: Utc::now(),
                             });
                         }
@@ -266,7 +264,9 @@ impl GroupManager for SupervisorManager {
                                 Ok(mut stream) => {
                                     use tokio_stream::StreamExt;
 
+                                    let mut accumulator = ResponseAccumulator::new();
                                     while let Some(event) = stream.next().await {
+                                        accumulator.push(&event);
                                         match event {
                                             crate::agent::ResponseEvent::TextChunk {
                                                 text,
@@ -348,11 +348,7 @@ impl GroupManager for SupervisorManager {
 
                                     agent_responses.push(AgentResponse {
                                         agent_id: leader_id.clone(),
-                                        response: crate::message::Response {
-                                            content: vec![],
-                                            reasoning: None,
-                                            metadata: crate::message::ResponseMetadata::default(),
-                                        },
+                                        response: accumulator.finish(),
                                         responded_at: Utc::now(),
                                     });
                                 }
@@ -420,7 +416,9 @@ impl GroupManager for SupervisorManager {
                     Ok(mut stream) => {
                         use tokio_stream::StreamExt;
 
+                        let mut accumulator = ResponseAccumulator::new();
                         while let Some(event) = stream.next().await {
+                            accumulator.push(&event);
                             match event {
                                 crate::agent::ResponseEvent::TextChunk { text, is_final } => {
                                     let _ = tx
@@ -493,11 +491,7 @@ impl GroupManager for SupervisorManager {
 
                         agent_responses.push(AgentResponse {
                             agent_id: leader_id.clone(),
-                            response: crate::message::Response {
-                                content: vec![],
-                                reasoning: None,
-                                metadata: crate::message::ResponseMetadata::default(),
-                            },
+                            response: accumulator.finish(),
                             responded_at: Utc::now(),
                         });
                     }
@@ -1,29 +1,56 @@
 //! Voting coordination pattern implementation
+//!
+//! Every active member is shown the proposal and asked to call the `vote`
+//! tool. Ballots are read straight off each member's response stream until
+//! everyone has answered or the voting timeout expires, then tallied
+//! according to the group's [`VotingRules`].
 
 use async_trait::async_trait;
 use chrono::{Duration, Utc};
+use dashmap::DashMap;
 use std::{collections::HashMap, sync::Arc};
 use uuid::Uuid;
 
 use crate::{
-    CoreError, Result,
-    agent::Agent,
+    AgentId, CoreError, Result,
+    agent::{Agent, ResponseEvent},
+    context::NON_USER_MESSAGE_PREFIX,
     coordination::{
         groups::{
-            AgentResponse, AgentWithMembership, GroupManager, GroupResponse, GroupResponseEvent,
+            AgentResponse, AgentWithMembership, GroupManager, GroupMembership, GroupResponse,
+            GroupResponseEvent,
         },
         types::{
-            CoordinationPattern, GroupState, TieBreaker, Vote, VoteOption, VotingProposal,
-            VotingRules, VotingSession,
+            CoordinationPattern, GroupMemberRole, GroupState, TieBreaker, Vote, VoteOption,
+            VotingProposal, VotingRules, VotingSession,
         },
-        utils::text_response,
+        utils::ResponseAccumulator,
     },
-    message::Message,
+    message::{Message, MessageContent},
+    tool::builtin::VoteInput,
 };
 
+/// Name of the tool members call to cast a ballot
+pub const VOTE_TOOL_NAME: &str = "vote";
+
+/// Message metadata key that may carry a structured [`VotingProposal`]
+pub const PROPOSAL_METADATA_KEY: &str = "voting_proposal";
+
+/// Upper bound on the weight expertise can give a single vote
+const MAX_EXPERTISE_WEIGHT: f32 = 2.0;
+
 #[derive(Clone)]
 pub struct VotingManager;
 
+/// Result of tallying a voting session
+#[derive(Debug, Clone, PartialEq)]
+enum VotingOutcome {
+    /// An option won, possibly after applying the tie-breaker
+    Decided { option_id: String, tie_broken: bool },
+    /// No option could be chosen
+    NoDecision { reason: String },
+}
+
 #[async_trait]
 impl GroupManager for VotingManager {
     async fn route_message(
@@ -37,35 +64,166 @@ impl GroupManager for VotingManager {
 
         let start_time = std::time::Instant::now();
         let group_id = group.id.clone();
-        let _group_name = group.name.clone();
 
-        // Do the full voting operation synchronously first
-        let result = self.do_voting(group, agents, message).await;
+        // Extract voting config
+        let (quorum, voting_rules) = match &group.coordination_pattern {
+            CoordinationPattern::Voting {
+                quorum,
+                voting_rules,
+            } => (*quorum, voting_rules.clone()),
+            _ => {
+                return Err(CoreError::AgentGroupError {
+                    group_name: group.name.clone(),
+                    operation: "route_message".to_string(),
+                    cause: "Invalid pattern for VotingManager".to_string(),
+                });
+            }
+        };
+
+        let voters: Vec<_> = agents
+            .iter()
+            .filter(|awm| awm.membership.is_active)
+            .cloned()
+            .collect();
+
+        let started_at = Utc::now();
+        let session = VotingSession {
+            id: Uuid::new_v4(),
+            proposal: Self::create_proposal_from_message_impl(&message),
+            votes: HashMap::new(),
+            started_at,
+            deadline: started_at
+                + Duration::from_std(voting_rules.voting_timeout).unwrap_or(Duration::seconds(30)),
+        };
+        let ballot = Self::ballot_message(&group.name, &session, &message);
 
-        // Then send the result as a single Complete event
         tokio::spawn(async move {
-            match result {
-                Ok((agent_responses, state_changes)) => {
-                    let _ = tx
-                        .send(GroupResponseEvent::Complete {
-                            group_id,
-                            pattern: "voting".to_string(),
-                            execution_time: start_time.elapsed(),
-                            agent_responses,
-                            state_changes,
-                        })
-                        .await;
-                }
-                Err(e) => {
-                    let _ = tx
-                        .send(GroupResponseEvent::Error {
-                            agent_id: None,
-                            message: e.to_string(),
-                            recoverable: false,
-                        })
-                        .await;
-                }
+            if voters.is_empty() {
+                let _ = tx
+                    .send(GroupResponseEvent::Error {
+                        agent_id: None,
+                        message: "No active agents in group to vote".to_string(),
+                        recoverable: false,
+                    })
+                    .await;
+                return;
+            }
+
+            let _ = tx
+                .send(GroupResponseEvent::Started {
+                    group_id: group_id.clone(),
+                    pattern: "voting".to_string(),
+                    agent_count: voters.len(),
+                })
+                .await;
+
+            let proposal = Arc::new(session.proposal.clone());
+            let votes: Arc<DashMap<AgentId, Vote>> = Arc::new(DashMap::new());
+            let replies: Arc<DashMap<AgentId, ResponseAccumulator>> = Arc::new(DashMap::new());
+
+            let collection = futures::future::join_all(voters.iter().map(|awm| {
+                let weight = if voting_rules.weight_by_expertise {
+                    Self::expertise_weight(&awm.membership, &proposal)
+                } else {
+                    1.0
+                };
+                Self::collect_ballot(
+                    awm.agent.clone(),
+                    awm.membership.role.clone(),
+                    ballot.clone(),
+                    proposal.clone(),
+                    weight,
+                    votes.clone(),
+                    replies.clone(),
+                    tx.clone(),
+                )
+            }));
+
+            if tokio::time::timeout(voting_rules.voting_timeout, collection)
+                .await
+                .is_err()
+            {
+                tracing::info!(
+                    "Voting session {} closed after {:?} with {}/{} ballots",
+                    session.id,
+                    voting_rules.voting_timeout,
+                    votes.len(),
+                    voters.len()
+                );
             }
+
+            let mut session = session;
+            session.votes = votes
+                .iter()
+                .map(|entry| (entry.key().clone(), entry.value().clone()))
+                .collect();
+
+            let outcome = if session.votes.len() < quorum {
+                VotingOutcome::NoDecision {
+                    reason: format!(
+                        "quorum not met ({} of {} required votes)",
+                        session.votes.len(),
+                        quorum
+                    ),
+                }
+            } else {
+                Self::tally_votes_impl(&session, &voting_rules)
+            };
+            let summary = Self::describe_outcome(&session, &outcome);
+            tracing::info!("Voting session {}: {}", session.id, summary);
+
+            let winner = match &outcome {
+                VotingOutcome::Decided { option_id, .. } => Some(option_id.clone()),
+                VotingOutcome::NoDecision { .. } => None,
+            };
+
+            let agent_responses = voters
+                .iter()
+                .map(|awm| {
+                    let agent_id = awm.agent.id();
+                    let vote = session.votes.get(&agent_id);
+                    // Members cut off by the timeout still show what they said so far
+                    let mut response = replies
+                        .remove(&agent_id)
+                        .map(|(_, reply)| reply)
+                        .unwrap_or_default()
+                        .finish();
+                    response
+                        .content
+                        .push(MessageContent::Text(Self::describe_ballot(vote)));
+                    response.metadata.confidence = vote.and_then(|v| v.confidence);
+                    if !response.metadata.custom.is_object() {
+                        response.metadata.custom = serde_json::json!({});
+                    }
+                    for (key, value) in [
+                        ("voting_session", serde_json::json!(session.id)),
+                        ("vote", serde_json::json!(vote)),
+                        ("winner", serde_json::json!(winner)),
+                        ("outcome", serde_json::json!(summary)),
+                    ] {
+                        response.metadata.custom[key] = value;
+                    }
+
+                    AgentResponse {
+                        agent_id,
+                        response,
+                        responded_at: vote.map(|v| v.timestamp).unwrap_or_else(Utc::now),
+                    }
+                })
+                .collect();
+
+            let _ = tx
+                .send(GroupResponseEvent::Complete {
+                    group_id,
+                    pattern: "voting".to_string(),
+                    execution_time: start_time.elapsed(),
+                    agent_responses,
+                    // The session is closed once tallied
+                    state_changes: Some(GroupState::Voting {
+                        active_session: None,
+                    }),
+                })
+                .await;
         });
 
         Ok(Box::new(ReceiverStream::new(rx)))
@@ -82,174 +240,366 @@ impl GroupManager for VotingManager {
 }
 
 impl VotingManager {
-    async fn do_voting(
-        &self,
-        group: &crate::coordination::groups::AgentGroup,
-        agents: &[AgentWithMembership<Arc<dyn Agent>>],
-        message: Message,
-    ) -> Result<(Vec<AgentResponse>, Option<GroupState>)> {
-        // Extract voting config
-        let (quorum, voting_rules) = match &group.coordination_pattern {
-            CoordinationPattern::Voting {
-                quorum,
-                voting_rules,
-            } => (*quorum, voting_rules),
-            _ => {
-                return Err(CoreError::AgentGroupError {
-                    group_name: group.name.clone(),
-                    operation: "route_message".to_string(),
-                    cause: "Invalid pattern for VotingManager".to_string(),
-                });
-            }
-        };
+    /// Ask one member for its vote, forwarding its events and recording any ballot
+    #[allow(clippy::too_many_arguments)]
+    async fn collect_ballot(
+        agent: Arc<dyn Agent>,
+        role: GroupMemberRole,
+        ballot: Message,
+        proposal: Arc<VotingProposal>,
+        weight: f32,
+        votes: Arc<DashMap<AgentId, Vote>>,
+        replies: Arc<DashMap<AgentId, ResponseAccumulator>>,
+        tx: tokio::sync::mpsc::Sender<GroupResponseEvent>,
+    ) {
+        use tokio_stream::StreamExt;
 
-        // Check if we have an active voting session
-        let active_session = match &group.state {
-            GroupState::Voting { active_session } => active_session.clone(),
-            _ => None,
+        let agent_id = agent.id();
+        let agent_name = agent.name();
+
+        let _ = tx
+            .send(GroupResponseEvent::AgentStarted {
+                agent_id: agent_id.clone(),
+                agent_name: agent_name.clone(),
+                role,
+            })
+            .await;
+
+        let mut stream = match agent.process_message_stream(ballot).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                let _ = tx
+                    .send(GroupResponseEvent::Error {
+                        agent_id: Some(agent_id),
+                        message: e.to_string(),
+                        recoverable: true,
+                    })
+                    .await;
+                return;
+            }
         };
 
-        let mut responses = Vec::new();
-        let new_state;
-
-        match active_session {
-            None => {
-                // Create a new voting session
-                let proposal = self.create_proposal_from_message(&message);
-                let session = VotingSession {
-                    id: Uuid::new_v4(),
-                    proposal,
-                    votes: HashMap::new(),
-                    started_at: Utc::now(),
-                    deadline: Utc::now()
-                        + Duration::from_std(voting_rules.voting_timeout)
-                            .unwrap_or(Duration::seconds(30)),
-                };
+        while let Some(event) = stream.next().await {
+            replies.entry(agent_id.clone()).or_default().push(&event);
 
-                // Notify all agents about the new vote
-                for awm in agents {
-                    if awm.membership.is_active {
-                        responses.push(AgentResponse {
-                            agent_id: awm.agent.as_ref().id(),
-                            response: text_response(format!(
-                                "[Voting] New proposal: {}. Options: {:?}",
-                                session.proposal.content,
-                                session
-                                    .proposal
-                                    .options
-                                    .iter()
-                                    .map(|o| &o.description)
-                                    .collect::<Vec<_>>()
-                            )),
-                            responded_at: Utc::now(),
-                        });
+            match event {
+                ResponseEvent::TextChunk { text, is_final } => {
+                    let _ = tx
+                        .send(GroupResponseEvent::TextChunk {
+                            agent_id: agent_id.clone(),
+                            text,
+                            is_final,
+                        })
+                        .await;
+                }
+                ResponseEvent::ReasoningChunk { text, is_final } => {
+                    let _ = tx
+                        .send(GroupResponseEvent::ReasoningChunk {
+                            agent_id: agent_id.clone(),
+                            text,
+                            is_final,
+                        })
+                        .await;
+                }
+                ResponseEvent::ToolCallStarted {
+                    call_id,
+                    fn_name,
+                    args,
+                } => {
+                    if fn_name == VOTE_TOOL_NAME {
+                        Self::record_ballot(&agent_id, &args, &proposal, weight, &votes, &tx).await;
                     }
+                    let _ = tx
+                        .send(GroupResponseEvent::ToolCallStarted {
+                            agent_id: agent_id.clone(),
+                            call_id,
+                            fn_name,
+                            args,
+                        })
+                        .await;
                 }
-
-                new_state = Some(GroupState::Voting {
-                    active_session: Some(session),
-                });
-            }
-            Some(mut session) => {
-                // Collect votes (in a real implementation, this would parse agent responses)
-                let active_agents: Vec<_> = agents
-                    .iter()
-                    .filter(|awm| awm.membership.is_active)
-                    .collect();
-
-                // Simulate vote collection (in reality, parse from message responses)
-                for awm in &active_agents {
-                    let agent_id = awm.agent.as_ref().id();
-                    if !session.votes.contains_key(&agent_id) {
-                        // Simulate a vote
-                        if let Some(option) = session.proposal.options.first() {
-                            let vote = Vote {
-                                option_id: option.id.clone(),
-                                weight: 1.0, // Would calculate based on expertise if enabled
-                                reasoning: Some("Simulated vote".to_string()),
-                                timestamp: Utc::now(),
-                            };
-                            session.votes.insert(agent_id.clone(), vote);
-                        }
+                ResponseEvent::ToolCalls { calls } => {
+                    // Agents that don't stream individual tool calls only report them here
+                    for call in calls.iter().filter(|c| c.fn_name == VOTE_TOOL_NAME) {
+                        Self::record_ballot(
+                            &agent_id,
+                            &call.fn_arguments,
+                            &proposal,
+                            weight,
+                            &votes,
+                            &tx,
+                        )
+                        .await;
                     }
                 }
+                ResponseEvent::ToolCallCompleted { call_id, result } => {
+                    let _ = tx
+                        .send(GroupResponseEvent::ToolCallCompleted {
+                            agent_id: agent_id.clone(),
+                            call_id,
+                            result: result.map_err(|e| e.to_string()),
+                        })
+                        .await;
+                }
+                ResponseEvent::Complete { message_id, .. } => {
+                    let _ = tx
+                        .send(GroupResponseEvent::AgentCompleted {
+                            agent_id: agent_id.clone(),
+                            agent_name: agent_name.clone(),
+                            message_id: Some(message_id),
+                        })
+                        .await;
+                }
+                ResponseEvent::Error {
+                    message,
+                    recoverable,
+                } => {
+                    let _ = tx
+                        .send(GroupResponseEvent::Error {
+                            agent_id: Some(agent_id.clone()),
+                            message,
+                            recoverable,
+                        })
+                        .await;
+                }
+                _ => {} // Skip other events
+            }
+        }
+    }
 
-                // Check if we have quorum or timeout
-                let has_quorum = session.votes.len() >= quorum;
-                let is_timeout = Utc::now() > session.deadline;
-
-                if has_quorum || is_timeout {
-                    // Tally votes and determine winner
-                    let result = self.tally_votes(&session, voting_rules)?;
-
-                    responses.push(AgentResponse {
-                        agent_id: agents[0].agent.as_ref().id(), // Group response
-                        response: text_response(format!(
-                            "[Voting Complete] Winner: {}. Votes: {}/{}",
-                            result,
-                            session.votes.len(),
-                            active_agents.len()
-                        )),
-                        responded_at: Utc::now(),
-                    });
-
-                    // Clear the voting session
-                    new_state = Some(GroupState::Voting {
-                        active_session: None,
-                    });
-                } else {
-                    // Still collecting votes
-                    responses.push(AgentResponse {
-                        agent_id: agents[0].agent.as_ref().id(),
-                        response: text_response(format!(
-                            "[Voting in Progress] {}/{} votes collected",
-                            session.votes.len(),
-                            quorum
-                        )),
-                        responded_at: Utc::now(),
-                    });
-
-                    new_state = Some(GroupState::Voting {
-                        active_session: Some(session),
-                    });
+    /// Validate the arguments of a `vote` call and store the ballot
+    ///
+    /// A later valid ballot from the same agent replaces its earlier one.
+    async fn record_ballot(
+        agent_id: &AgentId,
+        args: &serde_json::Value,
+        proposal: &VotingProposal,
+        weight: f32,
+        votes: &DashMap<AgentId, Vote>,
+        tx: &tokio::sync::mpsc::Sender<GroupResponseEvent>,
+    ) {
+        match Self::parse_vote(args, proposal, weight) {
+            Ok(vote) => {
+                // The same call can be reported both as ToolCalls and ToolCallStarted
+                let duplicate = votes.get(agent_id).is_some_and(|existing| {
+                    existing.option_id == vote.option_id && existing.reasoning == vote.reasoning
+                });
+                if !duplicate {
+                    tracing::debug!("Agent {} voted for {}", agent_id, vote.option_id);
+                    votes.insert(agent_id.clone(), vote);
                 }
             }
+            Err(cause) => {
+                let _ = tx
+                    .send(GroupResponseEvent::Error {
+                        agent_id: Some(agent_id.clone()),
+                        message: format!("Ignoring invalid vote: {}", cause),
+                        recoverable: true,
+                    })
+                    .await;
+            }
         }
+    }
+
+    fn parse_vote(
+        args: &serde_json::Value,
+        proposal: &VotingProposal,
+        weight: f32,
+    ) -> std::result::Result<Vote, String> {
+        let input: VoteInput = serde_json::from_value(args.clone())
+            .map_err(|e| format!("could not read vote arguments: {}", e))?;
 
-        Ok((responses, new_state))
+        let requested = input.option_id.trim();
+        let option = proposal
+            .options
+            .iter()
+            .find(|o| o.id.eq_ignore_ascii_case(requested))
+            .ok_or_else(|| {
+                format!(
+                    "'{}' is not one of the options ({})",
+                    requested,
+                    proposal
+                        .options
+                        .iter()
+                        .map(|o| o.id.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })?;
+
+        let reasoning = input.reasoning.trim();
+        Ok(Vote {
+            option_id: option.id.clone(),
+            weight,
+            reasoning: (!reasoning.is_empty()).then(|| reasoning.to_string()),
+            confidence: input.confidence.map(|c| c.clamp(0.0, 1.0)),
+            timestamp: Utc::now(),
+        })
     }
 
-    fn create_proposal_from_message(&self, message: &Message) -> VotingProposal {
-        Self::create_proposal_from_message_impl(message)
+    /// Weight a member's vote by how well its expertise matches the proposal
+    ///
+    /// Specialists whose domain comes up in the proposal get +0.5, and each
+    /// matching capability adds +0.25, capped at [`MAX_EXPERTISE_WEIGHT`].
+    fn expertise_weight(membership: &GroupMembership, proposal: &VotingProposal) -> f32 {
+        fn normalize(text: &str) -> String {
+            text.to_lowercase().replace(['_', '-'], " ")
+        }
+
+        let mut haystack = normalize(&proposal.content);
+        for option in &proposal.options {
+            haystack.push(' ');
+            haystack.push_str(&normalize(&option.description));
+        }
+        let mentions = |term: &str| -> bool {
+            let term = normalize(term);
+            let term = term.trim();
+            !term.is_empty() && haystack.contains(term)
+        };
+
+        let mut weight = 1.0;
+        if let GroupMemberRole::Specialist { domain } = &membership.role {
+            if mentions(domain) {
+                weight += 0.5;
+            }
+        }
+        let matching = membership
+            .capabilities
+            .iter()
+            .filter(|c| mentions(c))
+            .count();
+        weight += 0.25 * matching as f32;
+
+        weight.min(MAX_EXPERTISE_WEIGHT)
+    }
+
+    /// Build the message each member receives asking for its vote
+    fn ballot_message(group_name: &str, session: &VotingSession, original: &Message) -> Message {
+        let options = session
+            .proposal
+            .options
+            .iter()
+            .map(|o| format!("- `{}`: {}", o.id, o.description))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let prompt = format!(
+            "{}[Vote Requested] Group '{}' is voting on a proposal.\n\n\
+             Proposal: {}\n\nOptions:\n{}\n\n\
+             Cast your vote by calling the `{}` tool with the `option_id` you choose and your `reasoning`. \
+             You may also give a `confidence` between 0.0 and 1.0. Voting closes at {}.",
+            NON_USER_MESSAGE_PREFIX,
+            group_name,
+            session.proposal.content,
+            options,
+            VOTE_TOOL_NAME,
+            session
+                .deadline
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S %Z"),
+        );
+
+        let mut ballot = Message::user(prompt);
+        ballot.owner_id = original.owner_id.clone();
+        ballot.metadata = original.metadata.clone();
+        ballot.metadata.custom = serde_json::json!({
+            "voting_session": session.id,
+        });
+        ballot
     }
 
+    /// Build a proposal from the incoming message
+    ///
+    /// A structured proposal under [`PROPOSAL_METADATA_KEY`] is used as-is.
+    /// Otherwise list items in the text become the options, written either as
+    /// `- id: description` or plain `- description`/`1. description`. Text with
+    /// fewer than two options is treated as a yes/no question.
     fn create_proposal_from_message_impl(message: &Message) -> VotingProposal {
-        // In a real implementation, this would parse the message to create options
+        if let Some(value) = message.metadata.custom.get(PROPOSAL_METADATA_KEY) {
+            match serde_json::from_value::<VotingProposal>(value.clone()) {
+                Ok(proposal) if !proposal.options.is_empty() => return proposal,
+                Ok(_) => tracing::warn!("Structured voting proposal has no options, ignoring"),
+                Err(e) => tracing::warn!("Could not parse structured voting proposal: {}", e),
+            }
+        }
+
+        let text = message.text_content().unwrap_or_default();
+        let mut content_lines = Vec::new();
+        let mut options: Vec<VoteOption> = Vec::new();
+
+        for line in text.lines() {
+            let Some(item) = Self::list_item(line) else {
+                content_lines.push(line);
+                continue;
+            };
+
+            let (id, description) = match item.split_once(':') {
+                Some((id, description))
+                    if !id.trim().is_empty()
+                        && !id.trim().contains(char::is_whitespace)
+                        && !description.trim().is_empty() =>
+                {
+                    (id.trim().to_lowercase(), description.trim().to_string())
+                }
+                _ => (format!("option{}", options.len() + 1), item.to_string()),
+            };
+            let id = if options.iter().any(|o| o.id == id) {
+                format!("{}_{}", id, options.len() + 1)
+            } else {
+                id
+            };
+
+            options.push(VoteOption { id, description });
+        }
+
+        if options.len() < 2 {
+            return VotingProposal {
+                content: text.trim().to_string(),
+                options: vec![
+                    VoteOption {
+                        id: "approve".to_string(),
+                        description: "Approve".to_string(),
+                    },
+                    VoteOption {
+                        id: "reject".to_string(),
+                        description: "Reject".to_string(),
+                    },
+                    VoteOption {
+                        id: "abstain".to_string(),
+                        description: "Abstain".to_string(),
+                    },
+                ],
+                metadata: HashMap::new(),
+            };
+        }
+
         VotingProposal {
-            content: format!("Proposal based on: {:?}", message.content),
-            options: vec![
-                VoteOption {
-                    id: "option1".to_string(),
-                    description: "Approve".to_string(),
-                },
-                VoteOption {
-                    id: "option2".to_string(),
-                    description: "Reject".to_string(),
-                },
-                VoteOption {
-                    id: "option3".to_string(),
-                    description: "Abstain".to_string(),
-                },
-            ],
+            content: content_lines.join("\n").trim().to_string(),
+            options,
             metadata: HashMap::new(),
         }
     }
 
-    fn tally_votes(&self, session: &VotingSession, rules: &VotingRules) -> Result<String> {
-        Self::tally_votes_impl(session, rules)
+    /// Strip a list marker ("- ", "* ", "1. ", "2) ") from a line
+    fn list_item(line: &str) -> Option<&str> {
+        let line = line.trim();
+        let item = if let Some(rest) = line.strip_prefix("- ").or(line.strip_prefix("* ")) {
+            rest
+        } else {
+            let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+            if digits == 0 {
+                return None;
+            }
+            line[digits..]
+                .strip_prefix(". ")
+                .or(line[digits..].strip_prefix(") "))?
+        };
+
+        let item = item.trim();
+        (!item.is_empty()).then_some(item)
     }
 
-    fn tally_votes_impl(session: &VotingSession, rules: &VotingRules) -> Result<String> {
+    fn tally_votes_impl(session: &VotingSession, rules: &VotingRules) -> VotingOutcome {
         // Count votes by option
         let mut vote_counts: HashMap<String, f32> = HashMap::new();
 
@@ -257,70 +607,375 @@ impl VotingManager {
             *vote_counts.entry(vote.option_id.clone()).or_insert(0.0) += vote.weight;
         }
 
+        if vote_counts.is_empty() {
+            return VotingOutcome::NoDecision {
+                reason: "no votes were cast".to_string(),
+            };
+        }
+
         // Find the option(s) with the most votes
         let max_votes = vote_counts.values().cloned().fold(0.0, f32::max);
-        let winners: Vec<_> = vote_counts
+        let mut winners: Vec<_> = vote_counts
             .iter()
-            .filter(|(_, count)| **count == max_votes)
+            .filter(|(_, count)| (max_votes - **count).abs() < f32::EPSILON)
             .map(|(option_id, _)| option_id.clone())
             .collect();
+        // HashMap order is arbitrary; keep tie handling reproducible
+        winners.sort();
 
         if winners.len() == 1 {
             // Clear winner
-            Ok(winners[0].clone())
-        } else {
-            // Tie - use tie breaker
-            match &rules.tie_breaker {
-                TieBreaker::Random => {
-                    let mut rng = rand::rng();
-                    let index = rand::Rng::random_range(&mut rng, 0..winners.len());
-                    winners
-                        .get(index)
-                        .cloned()
-                        .ok_or_else(|| CoreError::AgentGroupError {
-                            group_name: "voting".to_string(),
-                            operation: "tie_breaker".to_string(),
-                            cause: "No winners to choose from".to_string(),
-                        })
-                }
-                TieBreaker::FirstVote => {
-                    // Find which tied option got its first vote earliest
-                    let mut earliest_vote = None;
-                    let mut winning_option = None;
-
-                    for vote in session.votes.values() {
-                        if winners.contains(&vote.option_id) {
-                            if earliest_vote.is_none() || vote.timestamp < earliest_vote.unwrap() {
-                                earliest_vote = Some(vote.timestamp);
-                                winning_option = Some(vote.option_id.clone());
-                            }
-                        }
-                    }
+            return VotingOutcome::Decided {
+                option_id: winners.remove(0),
+                tie_broken: false,
+            };
+        }
 
-                    winning_option.ok_or_else(|| CoreError::AgentGroupError {
-                        group_name: "voting".to_string(),
-                        operation: "tie_breaker".to_string(),
-                        cause: "Could not determine first vote".to_string(),
+        // Tie - use tie breaker
+        let tied = winners.join(", ");
+        let decided = |option_id: String| VotingOutcome::Decided {
+            option_id,
+            tie_broken: true,
+        };
+        match &rules.tie_breaker {
+            TieBreaker::Random => {
+                let mut rng = rand::rng();
+                let index = rand::Rng::random_range(&mut rng, 0..winners.len());
+                decided(winners.swap_remove(index))
+            }
+            TieBreaker::FirstVote => {
+                // The tied option that received its first vote earliest wins
+                session
+                    .votes
+                    .values()
+                    .filter(|vote| winners.contains(&vote.option_id))
+                    .min_by_key(|vote| vote.timestamp)
+                    .map(|vote| decided(vote.option_id.clone()))
+                    .unwrap_or_else(|| VotingOutcome::NoDecision {
+                        reason: format!("tie between {} and no first vote found", tied),
                     })
-                }
-                TieBreaker::SpecificAgent(agent_id) => {
-                    // Find what the specific agent voted for
-                    session
-                        .votes
-                        .get(agent_id)
-                        .map(|vote| vote.option_id.clone())
-                        .ok_or_else(|| CoreError::AgentGroupError {
-                            group_name: "voting".to_string(),
-                            operation: "tie_breaker".to_string(),
-                            cause: format!("Tie-breaker agent {} did not vote", agent_id),
-                        })
-                }
-                TieBreaker::NoDecision => Err(CoreError::AgentGroupError {
-                    group_name: "voting".to_string(),
-                    operation: "tie_breaker".to_string(),
-                    cause: "Voting resulted in a tie with no tie-breaker".to_string(),
-                }),
             }
+            TieBreaker::SpecificAgent(agent_id) => match session.votes.get(agent_id) {
+                Some(vote) if winners.contains(&vote.option_id) => decided(vote.option_id.clone()),
+                Some(vote) => VotingOutcome::NoDecision {
+                    reason: format!(
+                        "tie between {}; tie-breaker agent {} voted for '{}'",
+                        tied, agent_id, vote.option_id
+                    ),
+                },
+                None => VotingOutcome::NoDecision {
+                    reason: format!(
+                        "tie between {}; tie-breaker agent {} did not vote",
+                        tied, agent_id
+                    ),
+                },
+            },
+            TieBreaker::NoDecision => VotingOutcome::NoDecision {
+                reason: format!("tie between {}", tied),
+            },
         }
     }
+
+    fn describe_ballot(vote: Option<&Vote>) -> String {
+        let Some(vote) = vote else {
+            return "[Voting] Did not vote before voting closed".to_string();
+        };
+
+        let confidence = vote
+            .confidence
+            .map(|c| format!(", confidence {:.2}", c))
+            .unwrap_or_default();
+        format!(
+            "[Voting] Voted for '{}' (weight {:.2}{}): {}",
+            vote.option_id,
+            vote.weight,
+            confidence,
+            vote.reasoning.as_deref().unwrap_or("no reasoning given")
+        )
+    }
+
+    fn describe_outcome(session: &VotingSession, outcome: &VotingOutcome) -> String {
+        match outcome {
+            VotingOutcome::Decided {
+                option_id,
+                tie_broken,
+            } => {
+                let description = session
+                    .proposal
+                    .options
+                    .iter()
+                    .find(|o| &o.id == option_id)
+                    .map(|o| o.description.as_str())
+                    .unwrap_or(option_id);
+                format!(
+                    "'{}' ({}) won with {} vote(s){}",
+                    option_id,
+                    description,
+                    session.votes.len(),
+                    if *tie_broken {
+                        " after a tie-break"
+                    } else {
+                        ""
+                    }
+                )
+            }
+            VotingOutcome::NoDecision { reason } => format!("No decision: {}", reason),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        coordination::{
+            AgentGroup,
+            test_utils::test::{VotingTestAgent, create_test_message, create_voting_agent},
+        },
+        id::{GroupId, RelationId},
+    };
+
+    fn member(
+        agent: VotingTestAgent,
+        role: GroupMemberRole,
+    ) -> AgentWithMembership<Arc<dyn Agent>> {
+        AgentWithMembership {
+            membership: GroupMembership {
+                id: RelationId::generate(),
+                in_id: agent.id.clone(),
+                out_id: GroupId::generate(),
+                joined_at: Utc::now(),
+                role,
+                is_active: true,
+                capabilities: vec![],
+            },
+            agent: Arc::new(agent) as Arc<dyn Agent>,
+        }
+    }
+
+    fn voting_group(quorum: usize, voting_rules: VotingRules) -> AgentGroup {
+        AgentGroup {
+            id: GroupId::generate(),
+            name: "Council".to_string(),
+            description: "Test voting group".to_string(),
+            coordination_pattern: CoordinationPattern::Voting {
+                quorum,
+                voting_rules,
+            },
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            is_active: true,
+            state: GroupState::Voting {
+                active_session: None,
+            },
+            members: vec![],
+        }
+    }
+
+    fn rules(tie_breaker: TieBreaker) -> VotingRules {
+        VotingRules {
+            voting_timeout: std::time::Duration::from_secs(5),
+            tie_breaker,
+            weight_by_expertise: false,
+        }
+    }
+
+    fn session_with(votes: Vec<(AgentId, &str, i64)>) -> VotingSession {
+        let now = Utc::now();
+        VotingSession {
+            id: Uuid::new_v4(),
+            proposal: VotingProposal {
+                content: "test".to_string(),
+                options: vec![],
+                metadata: HashMap::new(),
+            },
+            votes: votes
+                .into_iter()
+                .map(|(agent_id, option, offset)| {
+                    (
+                        agent_id,
+                        Vote {
+                            option_id: option.to_string(),
+                            weight: 1.0,
+                            reasoning: None,
+                            confidence: None,
+                            timestamp: now + Duration::seconds(offset),
+                        },
+                    )
+                })
+                .collect(),
+            started_at: now,
+            deadline: now + Duration::seconds(30),
+        }
+    }
+
+    async fn run_vote(
+        group: &AgentGroup,
+        agents: &[AgentWithMembership<Arc<dyn Agent>>],
+        text: &str,
+    ) -> Vec<AgentResponse> {
+        let stream = VotingManager
+            .route_message(group, agents, create_test_message(text))
+            .await
+            .unwrap();
+        let (responses, state) =
+            crate::coordination::test_utils::test::collect_complete_event(stream).await;
+        assert!(matches!(
+            state,
+            Some(GroupState::Voting {
+                active_session: None
+            })
+        ));
+        responses
+    }
+
+    #[test]
+    fn test_proposal_from_list_items() {
+        let message = create_test_message(
+            "Where should we hold the retro?\n- office: In the office\n- Remote on video",
+        );
+        let proposal = VotingManager::create_proposal_from_message_impl(&message);
+
+        assert_eq!(proposal.content, "Where should we hold the retro?");
+        assert_eq!(proposal.options.len(), 2);
+        assert_eq!(proposal.options[0].id, "office");
+        assert_eq!(proposal.options[1].id, "option2");
+        assert_eq!(proposal.options[1].description, "Remote on video");
+
+        let yes_no = VotingManager::create_proposal_from_message_impl(&create_test_message(
+            "Ship it today?",
+        ));
+        assert_eq!(yes_no.options[0].id, "approve");
+    }
+
+    #[tokio::test]
+    async fn test_votes_collected_from_tool_calls() {
+        let agents = vec![
+            member(
+                create_voting_agent("A", Some("reject")),
+                GroupMemberRole::Regular,
+            ),
+            member(
+                create_voting_agent("B", Some("approve")),
+                GroupMemberRole::Regular,
+            ),
+            member(
+                create_voting_agent("C", Some("APPROVE")),
+                GroupMemberRole::Regular,
+            ),
+        ];
+        let group = voting_group(2, rules(TieBreaker::NoDecision));
+
+        let responses = run_vote(&group, &agents, "Adopt the new standup time?").await;
+
+        assert_eq!(responses.len(), 3);
+        for response in &responses {
+            assert_eq!(response.response.metadata.custom["winner"], "approve");
+        }
+        let content = &responses[0].response.content;
+        assert!(
+            matches!(&content[1], MessageContent::ToolCalls(calls) if calls[0].fn_name == VOTE_TOOL_NAME)
+        );
+        let text = content.last().and_then(|c| c.text()).unwrap().to_string();
+        assert!(text.contains("A prefers reject"));
+        assert_eq!(responses[0].response.metadata.confidence, Some(0.75));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_and_quorum() {
+        let mut slow = create_voting_agent("Slow", Some("approve"));
+        slow.delay = std::time::Duration::from_secs(10);
+        let agents = vec![
+            member(
+                create_voting_agent("Fast", Some("approve")),
+                GroupMemberRole::Regular,
+            ),
+            member(slow, GroupMemberRole::Regular),
+            member(
+                create_voting_agent("Silent", None),
+                GroupMemberRole::Regular,
+            ),
+        ];
+        let mut voting_rules = rules(TieBreaker::Random);
+        voting_rules.voting_timeout = std::time::Duration::from_millis(200);
+        let group = voting_group(2, voting_rules);
+
+        let responses = run_vote(&group, &agents, "Adopt the new standup time?").await;
+
+        assert_eq!(responses.len(), 3);
+        assert!(responses[0].response.metadata.custom["winner"].is_null());
+        assert!(
+            responses[0].response.metadata.custom["outcome"]
+                .as_str()
+                .unwrap()
+                .contains("quorum not met")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_weight_by_expertise_breaks_even_split() {
+        let agents = vec![
+            member(
+                create_voting_agent("Planner", Some("approve")),
+                GroupMemberRole::Specialist {
+                    domain: "scheduling".to_string(),
+                },
+            ),
+            member(
+                create_voting_agent("Other", Some("reject")),
+                GroupMemberRole::Regular,
+            ),
+        ];
+        let mut voting_rules = rules(TieBreaker::NoDecision);
+        voting_rules.weight_by_expertise = true;
+        let group = voting_group(1, voting_rules);
+
+        let responses = run_vote(&group, &agents, "Change the scheduling of standups?").await;
+
+        assert_eq!(responses[0].response.metadata.custom["winner"], "approve");
+        assert_eq!(responses[0].response.metadata.custom["vote"]["weight"], 1.5);
+    }
+
+    #[test]
+    fn test_tie_breakers() {
+        let first = AgentId::generate();
+        let second = AgentId::generate();
+        let session = session_with(vec![(first.clone(), "b", 5), (second.clone(), "a", 10)]);
+
+        assert_eq!(
+            VotingManager::tally_votes_impl(&session, &rules(TieBreaker::FirstVote)),
+            VotingOutcome::Decided {
+                option_id: "b".to_string(),
+                tie_broken: true
+            }
+        );
+        assert_eq!(
+            VotingManager::tally_votes_impl(
+                &session,
+                &rules(TieBreaker::SpecificAgent(second.clone()))
+            ),
+            VotingOutcome::Decided {
+                option_id: "a".to_string(),
+                tie_broken: true
+            }
+        );
+        assert!(matches!(
+            VotingManager::tally_votes_impl(
+                &session,
+                &rules(TieBreaker::SpecificAgent(AgentId::generate()))
+            ),
+            VotingOutcome::NoDecision { .. }
+        ));
+        assert!(matches!(
+            VotingManager::tally_votes_impl(&session, &rules(TieBreaker::NoDecision)),
+            VotingOutcome::NoDecision { .. }
+        ));
+        assert!(matches!(
+            VotingManager::tally_votes_impl(&session, &rules(TieBreaker::Random)),
+            VotingOutcome::Decided {
+                tie_broken: true,
+                ..
+            }
+        ));
+    }
 }
@@ -126,6 +126,267 @@ pub(crate) mod test {
         }
     }
 
+    /// Test agent that answers every message by calling the `vote` tool
+    #[derive(Debug)]
+    pub struct VotingTestAgent {
+        pub id: AgentId,
+        pub name: String,
+        /// Option id to vote for, or None to reply without voting
+        pub option_id: Option<String>,
+        /// How long to "think" before answering
+        pub delay: std::time::Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl Agent for VotingTestAgent {
+        fn id(&self) -> AgentId {
+            self.id.clone()
+        }
+
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+
+        fn agent_type(&self) -> AgentType {
+            AgentType::Generic
+        }
+
+        async fn process_message(self: Arc<Self>, _message: Message) -> Result<Response> {
+            use crate::message::{ResponseMetadata, ToolCall};
+
+            tokio::time::sleep(self.delay).await;
+
+            let mut content = vec![MessageContent::Text(format!(
+                "{} has considered the proposal",
+                self.name
+            ))];
+            if let Some(option_id) = &self.option_id {
+                content.push(MessageContent::ToolCalls(vec![ToolCall {
+                    call_id: format!("call_{}", self.name),
+                    fn_name: "vote".to_string(),
+                    fn_arguments: serde_json::json!({
+                        "option_id": option_id,
+                        "reasoning": format!("{} prefers {}", self.name, option_id),
+                        "confidence": 0.75,
+                    }),
+                }]));
+            }
+
+            Ok(Response {
+                content,
+                reasoning: None,
+                metadata: ResponseMetadata::default(),
+            })
+        }
+
+        async fn get_memory(&self, _key: &str) -> Result<Option<MemoryBlock>> {
+            unimplemented!("Test agent")
+        }
+
+        async fn update_memory(&self, _key: &str, _memory: MemoryBlock) -> Result<()> {
+            unimplemented!("Test agent")
+        }
+
+        async fn execute_tool(
+            &self,
+            _tool_name: &str,
+            _params: serde_json::Value,
+        ) -> Result<serde_json::Value> {
+            unimplemented!("Test agent")
+        }
+
+        async fn list_memory_keys(&self) -> Result<Vec<compact_str::CompactString>> {
+            unimplemented!("Test agent")
+        }
+
+        async fn share_memory_with(
+            &self,
+            _memory_key: &str,
+            _target_agent_id: AgentId,
+            _access_level: MemoryPermission,
+        ) -> Result<()> {
+            unimplemented!("Test agent")
+        }
+
+        async fn handle(&self) -> crate::context::state::AgentHandle {
+            unimplemented!("Test agent")
+        }
+
+        async fn last_active(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+            Some(chrono::Utc::now())
+        }
+
+        async fn get_shared_memories(
+            &self,
+        ) -> Result<Vec<(AgentId, compact_str::CompactString, MemoryBlock)>> {
+            unimplemented!("Test agent")
+        }
+
+        async fn system_prompt(&self) -> Vec<String> {
+            vec![]
+        }
+
+        async fn available_tools(&self) -> Vec<Box<dyn DynamicTool>> {
+            vec![]
+        }
+
+        async fn state(&self) -> (AgentState, Option<tokio::sync::watch::Receiver<AgentState>>) {
+            (AgentState::Ready, None)
+        }
+
+        async fn set_state(&self, _state: AgentState) -> Result<()> {
+            unimplemented!("Test agent")
+        }
+
+        async fn register_endpoint(
+            &self,
+            _name: String,
+            _endpoint: Arc<dyn crate::context::message_router::MessageEndpoint>,
+        ) -> Result<()> {
+            unimplemented!("Test agent")
+        }
+
+        async fn set_default_user_endpoint(
+            &self,
+            _endpoint: Arc<dyn crate::context::message_router::MessageEndpoint>,
+        ) -> Result<()> {
+            unimplemented!("Test agent")
+        }
+    }
+
+    /// Create a test agent that votes for the given option
+    pub fn create_voting_agent(name: &str, option_id: Option<&str>) -> VotingTestAgent {
+        VotingTestAgent {
+            id: AgentId::generate(),
+            name: name.to_string(),
+            option_id: option_id.map(str::to_string),
+            delay: std::time::Duration::ZERO,
+        }
+    }
+
+    /// Test agent that echoes the text it was sent after a delay
+    #[derive(Debug)]
+    pub struct EchoTestAgent {
+        pub id: AgentId,
+        pub name: String,
+        /// How long to "think" before answering
+        pub delay: std::time::Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl Agent for EchoTestAgent {
+        fn id(&self) -> AgentId {
+            self.id.clone()
+        }
+
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+
+        fn agent_type(&self) -> AgentType {
+            AgentType::Generic
+        }
+
+        async fn process_message(self: Arc<Self>, message: Message) -> Result<Response> {
+            use crate::message::ResponseMetadata;
+
+            tokio::time::sleep(self.delay).await;
+
+            Ok(Response {
+                content: vec![MessageContent::Text(format!(
+                    "{} saw: {}",
+                    self.name,
+                    message.text_content().unwrap_or_default()
+                ))],
+                reasoning: None,
+                metadata: ResponseMetadata::default(),
+            })
+        }
+
+        async fn get_memory(&self, _key: &str) -> Result<Option<MemoryBlock>> {
+            unimplemented!("Test agent")
+        }
+
+        async fn update_memory(&self, _key: &str, _memory: MemoryBlock) -> Result<()> {
+            unimplemented!("Test agent")
+        }
+
+        async fn execute_tool(
+            &self,
+            _tool_name: &str,
+            _params: serde_json::Value,
+        ) -> Result<serde_json::Value> {
+            unimplemented!("Test agent")
+        }
+
+        async fn list_memory_keys(&self) -> Result<Vec<compact_str::CompactString>> {
+            unimplemented!("Test agent")
+        }
+
+        async fn share_memory_with(
+            &self,
+            _memory_key: &str,
+            _target_agent_id: AgentId,
+            _access_level: MemoryPermission,
+        ) -> Result<()> {
+            unimplemented!("Test agent")
+        }
+
+        async fn handle(&self) -> crate::context::state::AgentHandle {
+            unimplemented!("Test agent")
+        }
+
+        async fn last_active(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+            Some(chrono::Utc::now())
+        }
+
+        async fn get_shared_memories(
+            &self,
+        ) -> Result<Vec<(AgentId, compact_str::CompactString, MemoryBlock)>> {
+            unimplemented!("Test agent")
+        }
+
+        async fn system_prompt(&self) -> Vec<String> {
+            vec![]
+        }
+
+        async fn available_tools(&self) -> Vec<Box<dyn DynamicTool>> {
+            vec![]
+        }
+
+        async fn state(&self) -> (AgentState, Option<tokio::sync::watch::Receiver<AgentState>>) {
+            (AgentState::Ready, None)
+        }
+
+        async fn set_state(&self, _state: AgentState) -> Result<()> {
+            unimplemented!("Test agent")
+        }
+
+        async fn register_endpoint(
+            &self,
+            _name: String,
+            _endpoint: Arc<dyn crate::context::message_router::MessageEndpoint>,
+        ) -> Result<()> {
+            unimplemented!("Test agent")
+        }
+
+        async fn set_default_user_endpoint(
+            &self,
+            _endpoint: Arc<dyn crate::context::message_router::MessageEndpoint>,
+        ) -> Result<()> {
+            unimplemented!("Test agent")
+        }
+    }
+
+    /// Create a test agent that echoes its input after the given delay
+    pub fn create_echo_agent(name: &str, delay: std::time::Duration) -> EchoTestAgent {
+        EchoTestAgent {
+            id: AgentId::generate(),
+            name: name.to_string(),
+            delay,
+        }
+    }
+
     /// Create a test agent with the given name
     pub fn create_test_agent(name: &str) -> TestAgent {
         TestAgent {
@@ -0,0 +1,180 @@
+//! Constellation activity trigger evaluation
+
+use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, Utc};
+
+use super::{TriggerContext, TriggerEvaluator};
+use crate::{
+    Result,
+    coordination::types::{SleeptimeTrigger, TriggerCondition},
+};
+
+/// Fires when enough has happened, or enough time has passed, since the last sync
+///
+/// The last sync is whichever is later: the last time this trigger fired or the
+/// last context sync recorded in the constellation activity tracker. Every
+/// other tracker event since then counts towards `message_threshold`.
+#[derive(Debug, Clone)]
+pub struct ConstellationActivityEvaluator;
+
+#[async_trait]
+impl TriggerEvaluator for ConstellationActivityEvaluator {
+    async fn evaluate(&self, trigger: &SleeptimeTrigger, context: &TriggerContext) -> Result<bool> {
+        let TriggerCondition::ConstellationActivity {
+            message_threshold,
+            time_threshold,
+        } = &trigger.condition
+        else {
+            return Ok(false);
+        };
+
+        let tracker_sync = match &context.activity {
+            Some(tracker) => tracker.last_sync().await,
+            None => None,
+        };
+        let last_sync = match (context.last_fired(&trigger.name), tracker_sync) {
+            (Some(a), Some(b)) => a.max(b),
+            (a, b) => match a.or(b) {
+                Some(last_sync) => last_sync,
+                // Never synced before
+                None => return Ok(true),
+            },
+        };
+
+        if let Some(tracker) = &context.activity {
+            let activity = tracker.activity_count_since(last_sync).await;
+            if *message_threshold > 0 && activity >= *message_threshold {
+                tracing::debug!(
+                    "Trigger {}: {} events since last sync (threshold {})",
+                    trigger.name,
+                    activity,
+                    message_threshold
+                );
+                return Ok(true);
+            }
+        }
+
+        let elapsed = Utc::now() - last_sync;
+        Ok(elapsed > ChronoDuration::from_std(*time_threshold).unwrap_or_default())
+    }
+
+    fn name(&self) -> &str {
+        "constellation_activity"
+    }
+
+    fn description(&self) -> &str {
+        "Fires when enough constellation events have happened, or enough time has passed, since the last sync"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        AgentId,
+        constellation_memory::{
+            ConstellationActivityTracker, ConstellationEvent, ConstellationEventType,
+        },
+        coordination::triggers::test_support::{context, fired, trigger},
+    };
+
+    fn processed(tracker_agent: &AgentId) -> ConstellationEvent {
+        ConstellationEvent {
+            timestamp: Utc::now(),
+            agent_id: tracker_agent.clone(),
+            agent_name: "Entropy".to_string(),
+            event_type: ConstellationEventType::MessageProcessed {
+                summary: "Broke down a task".to_string(),
+            },
+            description: "Completed processing user message".to_string(),
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_counts_events_since_last_sync() {
+        let tracker = Arc::new(ConstellationActivityTracker::new(100));
+        let agent_id = AgentId::generate();
+        let trigger = trigger(
+            "activity_sync",
+            TriggerCondition::ConstellationActivity {
+                message_threshold: 3,
+                time_threshold: std::time::Duration::from_secs(3600),
+            },
+        );
+        let history = vec![fired(
+            "activity_sync",
+            Utc::now() - ChronoDuration::minutes(5),
+        )];
+
+        tracker.add_event(processed(&agent_id)).await;
+        tracker.add_event(processed(&agent_id)).await;
+        let ctx = context(history.clone()).with_activity(tracker.clone());
+        assert!(
+            !ConstellationActivityEvaluator
+                .evaluate(&trigger, &ctx)
+                .await
+                .unwrap()
+        );
+
+        tracker.add_event(processed(&agent_id)).await;
+        assert!(
+            ConstellationActivityEvaluator
+                .evaluate(&trigger, &ctx)
+                .await
+                .unwrap()
+        );
+
+        // A context sync resets the count
+        tracker
+            .add_event(ConstellationEvent {
+                timestamp: Utc::now(),
+                agent_id: agent_id.clone(),
+                agent_name: "Pattern".to_string(),
+                event_type: ConstellationEventType::ContextSync {
+                    synced_agent_id: agent_id.clone(),
+                },
+                description: "Context sync".to_string(),
+                metadata: None,
+            })
+            .await;
+        assert!(
+            !ConstellationActivityEvaluator
+                .evaluate(&trigger, &ctx)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_time_threshold_without_activity() {
+        let trigger = trigger(
+            "activity_sync",
+            TriggerCondition::ConstellationActivity {
+                message_threshold: 10,
+                time_threshold: std::time::Duration::from_secs(600),
+            },
+        );
+
+        let never = context(vec![]);
+        assert!(
+            ConstellationActivityEvaluator
+                .evaluate(&trigger, &never)
+                .await
+                .unwrap()
+        );
+
+        let stale = context(vec![fired(
+            "activity_sync",
+            Utc::now() - ChronoDuration::hours(1),
+        )]);
+        assert!(
+            ConstellationActivityEvaluator
+                .evaluate(&trigger, &stale)
+                .await
+                .unwrap()
+        );
+    }
+}
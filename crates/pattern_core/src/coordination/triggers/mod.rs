@@ -0,0 +1,294 @@
+//! Trigger evaluation for sleeptime coordination
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use tokio::sync::OnceCell;
+
+use super::types::{SleeptimeTrigger, TriggerCondition, TriggerEvent};
+use crate::{
+    Result,
+    agent::Agent,
+    constellation_memory::{ConstellationActivityTracker, ConstellationEvent},
+    message::Message,
+};
+
+mod activity;
+mod pattern;
+mod threshold;
+mod time;
+
+pub use activity::ConstellationActivityEvaluator;
+pub use pattern::PatternDetectedEvaluator;
+pub use threshold::ThresholdExceededEvaluator;
+pub use time::TimeElapsedEvaluator;
+
+/// Maximum number of recent constellation messages loaded for evaluators
+const RECENT_MESSAGE_LIMIT: usize = 50;
+
+/// Everything a trigger evaluator can look at when deciding whether to fire
+pub struct TriggerContext {
+    /// The message that started this check
+    pub message: Message,
+    /// History of previously fired triggers for this group
+    pub history: Vec<TriggerEvent>,
+    /// When the group last ran its checks
+    pub last_check: DateTime<Utc>,
+    /// Shared constellation activity log, if the group has one
+    pub activity: Option<Arc<ConstellationActivityTracker>>,
+    /// Agent whose handle is used to look up constellation messages
+    message_source: Option<Arc<dyn Agent>>,
+    /// Constellation messages since the last check, loaded on first use
+    messages: OnceCell<Vec<(String, Message)>>,
+}
+
+impl TriggerContext {
+    pub fn new(message: Message, history: Vec<TriggerEvent>, last_check: DateTime<Utc>) -> Self {
+        Self {
+            message,
+            history,
+            last_check,
+            activity: None,
+            message_source: None,
+            messages: OnceCell::new(),
+        }
+    }
+
+    /// Attach the constellation activity tracker
+    pub fn with_activity(mut self, tracker: Arc<ConstellationActivityTracker>) -> Self {
+        self.activity = Some(tracker);
+        self
+    }
+
+    /// Look up recent constellation messages through this agent when needed
+    pub fn with_message_source(mut self, agent: Arc<dyn Agent>) -> Self {
+        self.message_source = Some(agent);
+        self
+    }
+
+    /// Supply the recent constellation messages directly as (agent name, message) pairs
+    pub fn with_messages(self, messages: Vec<(String, Message)>) -> Self {
+        let _ = self.messages.set(messages);
+        self
+    }
+
+    /// Constellation messages since the last check, as (agent name, message) pairs
+    ///
+    /// Loaded lazily so checks that only need timing or metrics never hit the database.
+    pub async fn recent_messages(&self) -> &[(String, Message)] {
+        self.messages
+            .get_or_init(|| async {
+                let Some(agent) = &self.message_source else {
+                    return Vec::new();
+                };
+                let handle = agent.handle().await;
+                if !handle.has_db_connection() {
+                    return Vec::new();
+                }
+
+                match handle
+                    .search_constellation_messages(
+                        None,
+                        None,
+                        Some(self.last_check),
+                        None,
+                        RECENT_MESSAGE_LIMIT,
+                    )
+                    .await
+                {
+                    Ok(messages) => messages,
+                    Err(e) => {
+                        crate::log_error!("Failed to load recent constellation messages", e);
+                        Vec::new()
+                    }
+                }
+            })
+            .await
+    }
+
+    /// Constellation activity events since the given time
+    pub async fn activity_since(&self, since: DateTime<Utc>) -> Vec<ConstellationEvent> {
+        match &self.activity {
+            Some(tracker) => tracker.events_since(since).await,
+            None => Vec::new(),
+        }
+    }
+
+    /// When the named trigger last fired, if ever
+    pub fn last_fired(&self, trigger_name: &str) -> Option<DateTime<Utc>> {
+        self.history
+            .iter()
+            .filter(|e| e.trigger_name == trigger_name && e.intervention_activated)
+            .map(|e| e.timestamp)
+            .max()
+    }
+
+    /// Latest value recorded for a metric, with when it was recorded
+    pub async fn latest_metric(&self, name: &str) -> Option<(f64, DateTime<Utc>)> {
+        match &self.activity {
+            Some(tracker) => tracker.latest_metric(name).await,
+            None => None,
+        }
+    }
+}
+
+#[async_trait]
+pub trait TriggerEvaluator: Send + Sync {
+    /// Decide whether the trigger should fire now
+    async fn evaluate(&self, trigger: &SleeptimeTrigger, context: &TriggerContext) -> Result<bool>;
+
+    fn name(&self) -> &str;
+
+    fn description(&self) -> &str;
+}
+
+/// Registry for trigger evaluators
+pub trait TriggerEvaluatorRegistry: Send + Sync {
+    /// Get an evaluator by name
+    fn get(&self, name: &str) -> Option<Arc<dyn TriggerEvaluator>>;
+
+    /// Register a new evaluator
+    fn register(&mut self, name: String, evaluator: Arc<dyn TriggerEvaluator>);
+
+    /// List all available evaluators
+    fn list(&self) -> Vec<String>;
+}
+
+/// Name of the evaluator responsible for a trigger condition
+///
+/// Built-in conditions map to the default evaluators, so registering an
+/// evaluator under the same name replaces the built-in behaviour.
+pub fn evaluator_name(condition: &TriggerCondition) -> &str {
+    match condition {
+        TriggerCondition::TimeElapsed { .. } => "time_elapsed",
+        TriggerCondition::PatternDetected { .. } => "pattern_detected",
+        TriggerCondition::ThresholdExceeded { .. } => "threshold_exceeded",
+        TriggerCondition::ConstellationActivity { .. } => "constellation_activity",
+        TriggerCondition::Custom { evaluator } => evaluator,
+    }
+}
+
+/// Default implementation of TriggerEvaluatorRegistry
+pub struct DefaultTriggerEvaluatorRegistry {
+    evaluators: Arc<DashMap<String, Arc<dyn TriggerEvaluator>>>,
+}
+
+impl DefaultTriggerEvaluatorRegistry {
+    pub fn new() -> Self {
+        let mut registry = Self {
+            evaluators: Arc::new(DashMap::new()),
+        };
+
+        // Register default evaluators
+        registry.register("time_elapsed".to_string(), Arc::new(TimeElapsedEvaluator));
+        registry.register(
+            "pattern_detected".to_string(),
+            Arc::new(PatternDetectedEvaluator),
+        );
+        registry.register(
+            "threshold_exceeded".to_string(),
+            Arc::new(ThresholdExceededEvaluator),
+        );
+        registry.register(
+            "constellation_activity".to_string(),
+            Arc::new(ConstellationActivityEvaluator),
+        );
+
+        registry
+    }
+}
+
+impl TriggerEvaluatorRegistry for DefaultTriggerEvaluatorRegistry {
+    fn get(&self, name: &str) -> Option<Arc<dyn TriggerEvaluator>> {
+        self.evaluators.get(name).map(|r| r.clone())
+    }
+
+    fn register(&mut self, name: String, evaluator: Arc<dyn TriggerEvaluator>) {
+        self.evaluators.insert(name, evaluator);
+    }
+
+    fn list(&self) -> Vec<String> {
+        self.evaluators.iter().map(|e| e.key().clone()).collect()
+    }
+}
+
+impl Default for DefaultTriggerEvaluatorRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use crate::coordination::types::TriggerPriority;
+
+    pub fn trigger(name: &str, condition: TriggerCondition) -> SleeptimeTrigger {
+        SleeptimeTrigger {
+            name: name.to_string(),
+            condition,
+            priority: TriggerPriority::Medium,
+        }
+    }
+
+    pub fn fired(name: &str, timestamp: DateTime<Utc>) -> TriggerEvent {
+        TriggerEvent {
+            trigger_name: name.to_string(),
+            timestamp,
+            intervention_activated: true,
+            metadata: Default::default(),
+        }
+    }
+
+    pub fn context(history: Vec<TriggerEvent>) -> TriggerContext {
+        TriggerContext::new(
+            Message::user("Context sync check"),
+            history,
+            Utc::now() - chrono::Duration::minutes(20),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct AlwaysEvaluator;
+
+    #[async_trait]
+    impl TriggerEvaluator for AlwaysEvaluator {
+        async fn evaluate(&self, _: &SleeptimeTrigger, _: &TriggerContext) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &str {
+            "always"
+        }
+
+        fn description(&self) -> &str {
+            "Always fires"
+        }
+    }
+
+    #[test]
+    fn test_registry_defaults_and_custom() {
+        let mut registry = DefaultTriggerEvaluatorRegistry::new();
+        for name in [
+            "time_elapsed",
+            "pattern_detected",
+            "threshold_exceeded",
+            "constellation_activity",
+        ] {
+            assert!(registry.get(name).is_some(), "missing {}", name);
+        }
+
+        registry.register("always".to_string(), Arc::new(AlwaysEvaluator));
+        let condition = TriggerCondition::Custom {
+            evaluator: "always".to_string(),
+        };
+        assert_eq!(evaluator_name(&condition), "always");
+        assert!(registry.get(evaluator_name(&condition)).is_some());
+    }
+}
@@ -0,0 +1,163 @@
+//! Named pattern trigger evaluation
+
+use async_trait::async_trait;
+
+use super::{TriggerContext, TriggerEvaluator};
+use crate::{
+    Result,
+    constellation_memory::ConstellationEventType,
+    coordination::types::{SleeptimeTrigger, TriggerCondition},
+};
+
+/// Fires when a named pattern shows up in recent constellation activity
+///
+/// A pattern is detected when, since the trigger last fired (or the last
+/// check), an agent recorded a custom activity event whose category is the
+/// pattern name, or the name appears in a constellation message or activity
+/// summary. Underscores and dashes match spaces, so `hyperfocus_session`
+/// matches "hyperfocus session".
+#[derive(Debug, Clone)]
+pub struct PatternDetectedEvaluator;
+
+fn normalize(text: &str) -> String {
+    text.to_lowercase().replace(['_', '-'], " ")
+}
+
+#[async_trait]
+impl TriggerEvaluator for PatternDetectedEvaluator {
+    async fn evaluate(&self, trigger: &SleeptimeTrigger, context: &TriggerContext) -> Result<bool> {
+        let TriggerCondition::PatternDetected { pattern_name } = &trigger.condition else {
+            return Ok(false);
+        };
+
+        let pattern = normalize(pattern_name);
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Ok(false);
+        }
+
+        let since = context
+            .last_fired(&trigger.name)
+            .map_or(context.last_check, |last| last.max(context.last_check));
+
+        for event in context.activity_since(since).await {
+            let detected = match &event.event_type {
+                ConstellationEventType::Custom { category } => {
+                    normalize(category).trim() == pattern
+                }
+                ConstellationEventType::MessageProcessed { summary } => {
+                    normalize(summary).contains(pattern)
+                }
+                _ => normalize(&event.description).contains(pattern),
+            };
+            if detected {
+                tracing::debug!(
+                    "Trigger {}: pattern '{}' detected in activity from {}",
+                    trigger.name,
+                    pattern_name,
+                    event.agent_name
+                );
+                return Ok(true);
+            }
+        }
+
+        for (agent_name, message) in context.recent_messages().await {
+            if message.created_at <= since {
+                continue;
+            }
+            if normalize(&message.display_content()).contains(pattern) {
+                tracing::debug!(
+                    "Trigger {}: pattern '{}' detected in message from {}",
+                    trigger.name,
+                    pattern_name,
+                    agent_name
+                );
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn name(&self) -> &str {
+        "pattern_detected"
+    }
+
+    fn description(&self) -> &str {
+        "Fires when a named pattern appears in recent constellation messages or activity"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use super::*;
+    use crate::{
+        AgentId,
+        constellation_memory::{ConstellationActivityTracker, ConstellationEvent},
+        coordination::triggers::test_support::{context, trigger},
+        message::Message,
+    };
+
+    fn hyperfocus() -> SleeptimeTrigger {
+        trigger(
+            "hyperfocus_check",
+            TriggerCondition::PatternDetected {
+                pattern_name: "hyperfocus".to_string(),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_pattern_from_messages() {
+        let quiet = context(vec![]).with_messages(vec![(
+            "Entropy".to_string(),
+            Message::user("Let's plan groceries"),
+        )]);
+        assert!(
+            !PatternDetectedEvaluator
+                .evaluate(&hyperfocus(), &quiet)
+                .await
+                .unwrap()
+        );
+
+        let busy = context(vec![]).with_messages(vec![(
+            "Entropy".to_string(),
+            Message::user("I've been in hyperfocus on this refactor for 4 hours"),
+        )]);
+        assert!(
+            PatternDetectedEvaluator
+                .evaluate(&hyperfocus(), &busy)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pattern_from_custom_activity() {
+        let tracker = Arc::new(ConstellationActivityTracker::new(100));
+        tracker
+            .add_event(ConstellationEvent {
+                timestamp: Utc::now(),
+                agent_id: AgentId::generate(),
+                agent_name: "Flux".to_string(),
+                event_type: ConstellationEventType::Custom {
+                    category: "Hyperfocus".to_string(),
+                },
+                description: "Partner skipped lunch".to_string(),
+                metadata: None,
+            })
+            .await;
+
+        let ctx = context(vec![]).with_activity(tracker).with_messages(vec![]);
+        assert!(
+            PatternDetectedEvaluator
+                .evaluate(&hyperfocus(), &ctx)
+                .await
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Metric threshold trigger evaluation
+
+use async_trait::async_trait;
+
+use super::{TriggerContext, TriggerEvaluator};
+use crate::{
+    Result,
+    coordination::types::{SleeptimeTrigger, TriggerCondition},
+};
+
+/// Fires when the latest recorded value of a metric exceeds the threshold
+///
+/// Metrics are recorded by agents into the constellation activity tracker.
+/// A reading only fires the trigger once; a fresh reading is needed after that.
+#[derive(Debug, Clone)]
+pub struct ThresholdExceededEvaluator;
+
+#[async_trait]
+impl TriggerEvaluator for ThresholdExceededEvaluator {
+    async fn evaluate(&self, trigger: &SleeptimeTrigger, context: &TriggerContext) -> Result<bool> {
+        let TriggerCondition::ThresholdExceeded { metric, threshold } = &trigger.condition else {
+            return Ok(false);
+        };
+
+        let Some((value, recorded_at)) = context.latest_metric(metric).await else {
+            return Ok(false);
+        };
+
+        let already_handled = context
+            .last_fired(&trigger.name)
+            .is_some_and(|last| last >= recorded_at);
+
+        Ok(value > *threshold && !already_handled)
+    }
+
+    fn name(&self) -> &str {
+        "threshold_exceeded"
+    }
+
+    fn description(&self) -> &str {
+        "Fires when the latest recorded value of a metric exceeds the threshold"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use super::*;
+    use crate::{
+        AgentId,
+        constellation_memory::ConstellationActivityTracker,
+        coordination::triggers::test_support::{context, fired, trigger},
+    };
+
+    #[tokio::test]
+    async fn test_threshold_uses_recorded_metrics() {
+        let tracker = Arc::new(ConstellationActivityTracker::new(100));
+        let trigger = trigger(
+            "sedentary",
+            TriggerCondition::ThresholdExceeded {
+                metric: "minutes_sitting".to_string(),
+                threshold: 60.0,
+            },
+        );
+
+        // No reading yet
+        let ctx = context(vec![]).with_activity(tracker.clone());
+        assert!(
+            !ThresholdExceededEvaluator
+                .evaluate(&trigger, &ctx)
+                .await
+                .unwrap()
+        );
+
+        tracker
+            .record_metric(
+                AgentId::generate(),
+                "Anchor".into(),
+                "minutes_sitting",
+                45.0,
+            )
+            .await;
+        assert!(
+            !ThresholdExceededEvaluator
+                .evaluate(&trigger, &ctx)
+                .await
+                .unwrap()
+        );
+
+        tracker
+            .record_metric(
+                AgentId::generate(),
+                "Anchor".into(),
+                "minutes_sitting",
+                90.0,
+            )
+            .await;
+        assert!(
+            ThresholdExceededEvaluator
+                .evaluate(&trigger, &ctx)
+                .await
+                .unwrap()
+        );
+
+        // Same reading doesn't fire twice
+        let handled = context(vec![fired("sedentary", Utc::now())]).with_activity(tracker.clone());
+        assert!(
+            !ThresholdExceededEvaluator
+                .evaluate(&trigger, &handled)
+                .await
+                .unwrap()
+        );
+    }
+}
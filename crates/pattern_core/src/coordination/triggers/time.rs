@@ -0,0 +1,84 @@
+//! Time-based trigger evaluation
+
+use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, Utc};
+
+use super::{TriggerContext, TriggerEvaluator};
+use crate::{
+    Result,
+    coordination::types::{SleeptimeTrigger, TriggerCondition},
+};
+
+/// Fires once the configured duration has passed since the trigger last fired
+#[derive(Debug, Clone)]
+pub struct TimeElapsedEvaluator;
+
+#[async_trait]
+impl TriggerEvaluator for TimeElapsedEvaluator {
+    async fn evaluate(&self, trigger: &SleeptimeTrigger, context: &TriggerContext) -> Result<bool> {
+        let TriggerCondition::TimeElapsed { duration } = &trigger.condition else {
+            return Ok(false);
+        };
+
+        match context.last_fired(&trigger.name) {
+            Some(last) => {
+                Ok(Utc::now() - last > ChronoDuration::from_std(*duration).unwrap_or_default())
+            }
+            // Never fired before, so it's elapsed
+            None => Ok(true),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "time_elapsed"
+    }
+
+    fn description(&self) -> &str {
+        "Fires when the configured duration has passed since the trigger last fired"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordination::triggers::test_support::{context, fired, trigger};
+
+    #[tokio::test]
+    async fn test_time_elapsed() {
+        let trigger = trigger(
+            "stretch",
+            TriggerCondition::TimeElapsed {
+                duration: std::time::Duration::from_secs(600),
+            },
+        );
+
+        assert!(
+            TimeElapsedEvaluator
+                .evaluate(&trigger, &context(vec![]))
+                .await
+                .unwrap()
+        );
+
+        let recent = context(vec![fired(
+            "stretch",
+            Utc::now() - ChronoDuration::minutes(2),
+        )]);
+        assert!(
+            !TimeElapsedEvaluator
+                .evaluate(&trigger, &recent)
+                .await
+                .unwrap()
+        );
+
+        let stale = context(vec![fired(
+            "stretch",
+            Utc::now() - ChronoDuration::minutes(30),
+        )]);
+        assert!(
+            TimeElapsedEvaluator
+                .evaluate(&trigger, &stale)
+                .await
+                .unwrap()
+        );
+    }
+}
@@ -133,12 +133,34 @@ pub struct PipelineStage {
     pub name: String,
     /// Agents that can process this stage
     pub agent_ids: Vec<AgentId>,
-    /// Maximum time allowed for this stage
+    /// Maximum time allowed for this stage, shared by its retries and fallback agent
     #[serde(with = "crate::utils::serde_duration")]
     #[schemars(with = "u64")]
     pub timeout: Duration,
     /// What to do if this stage fails
     pub on_failure: StageFailureAction,
+    /// How outputs are combined when several agents run this stage
+    #[serde(default)]
+    pub merge: StageMerge,
+    /// Names of stages whose output this stage needs
+    ///
+    /// Only used when `parallel_stages` is set; stages without dependencies
+    /// start immediately with the original message.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
+/// How the outputs of a stage's agents are handed to the following stages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StageMerge {
+    /// Run every agent and join their outputs into one
+    #[default]
+    Concatenate,
+    /// Run every agent and keep the first successful output, cancelling the rest
+    FirstSuccess,
+    /// Run every agent and pass each output on separately
+    AllToNextStage,
 }
 
 /// Actions to take when a pipeline stage fails
@@ -295,6 +317,9 @@ pub struct Vote {
     pub weight: f32,
     /// Optional reasoning provided by the agent
     pub reasoning: Option<String>,
+    /// How confident the agent said it was (0.0 to 1.0), if given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
     /// When the vote was cast
     pub timestamp: DateTime<Utc>,
 }
@@ -324,6 +349,9 @@ pub struct StageResult {
     /// How long it took
     #[serde(with = "crate::utils::serde_duration")]
     pub duration: Duration,
+    /// Whether the stage ran past its timeout
+    #[serde(default)]
+    pub timed_out: bool,
     /// Output data
     pub output: serde_json::Value,
 }
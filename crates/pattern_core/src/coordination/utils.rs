@@ -1,6 +1,12 @@
 //! Utility functions for coordination patterns
 
-use crate::message::{MessageContent, Response, ResponseMetadata};
+use std::collections::HashSet;
+
+use crate::{
+    MessageId,
+    agent::ResponseEvent,
+    message::{MessageContent, Response, ResponseMetadata, ToolCall, ToolResponse},
+};
 use genai::{ModelIden, adapter::AdapterKind};
 
 /// Create a simple text response
@@ -15,6 +21,218 @@ pub fn text_response(text: impl Into<String>) -> Response {
             confidence: None,
             model_iden: ModelIden::new(AdapterKind::Anthropic, "coordination"),
             custom: Default::default(),
+            fallback: None,
         },
     }
 }
+
+/// Concatenate the text content of a response
+pub fn response_text(response: &Response) -> String {
+    response
+        .content
+        .iter()
+        .filter_map(|c| c.text())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Rebuilds a full [`Response`] from the events an agent streams
+///
+/// Text and reasoning chunks are joined, tool calls and results keep their
+/// order relative to the text, and the metadata (including token usage) comes
+/// from the final `Complete` event. Tool calls reported both as a batch and
+/// one at a time are only recorded once.
+#[derive(Debug, Default)]
+pub struct ResponseAccumulator {
+    content: Vec<MessageContent>,
+    text: String,
+    reasoning: String,
+    seen_calls: HashSet<String>,
+    seen_results: HashSet<String>,
+    errors: Vec<String>,
+    metadata: Option<ResponseMetadata>,
+    message_id: Option<MessageId>,
+}
+
+impl ResponseAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a streamed event to the response
+    pub fn push(&mut self, event: &ResponseEvent) {
+        match event {
+            ResponseEvent::TextChunk { text, is_final } => {
+                self.text.push_str(text);
+                if *is_final {
+                    self.flush_text();
+                }
+            }
+            ResponseEvent::ReasoningChunk { text, .. } => self.reasoning.push_str(text),
+            ResponseEvent::ToolCalls { calls } => {
+                for call in calls {
+                    self.push_call(call.clone());
+                }
+            }
+            ResponseEvent::ToolCallStarted {
+                call_id,
+                fn_name,
+                args,
+            } => self.push_call(ToolCall {
+                call_id: call_id.clone(),
+                fn_name: fn_name.clone(),
+                fn_arguments: args.clone(),
+            }),
+            ResponseEvent::ToolResponses { responses } => {
+                for response in responses {
+                    self.push_result(response.clone());
+                }
+            }
+            ResponseEvent::ToolCallCompleted { call_id, result } => {
+                let (content, is_error) = match result {
+                    Ok(content) => (content.clone(), None),
+                    Err(error) => (error.clone(), Some(true)),
+                };
+                self.push_result(ToolResponse {
+                    call_id: call_id.clone(),
+                    content,
+                    is_error,
+                });
+            }
+            ResponseEvent::Complete {
+                message_id,
+                metadata,
+            } => {
+                self.message_id = Some(message_id.clone());
+                self.metadata = Some(metadata.clone());
+            }
+            ResponseEvent::Error { message, .. } => self.errors.push(message.clone()),
+        }
+    }
+
+    /// ID of the message the agent answered, once it has completed
+    pub fn message_id(&self) -> Option<&MessageId> {
+        self.message_id.as_ref()
+    }
+
+    /// Build the response collected so far
+    pub fn finish(mut self) -> Response {
+        self.flush_text();
+
+        let mut metadata = self.metadata.unwrap_or_default();
+        if !self.errors.is_empty() {
+            if !metadata.custom.is_object() {
+                metadata.custom = serde_json::json!({});
+            }
+            metadata.custom["errors"] = serde_json::json!(self.errors);
+        }
+
+        Response {
+            content: self.content,
+            reasoning: (!self.reasoning.is_empty()).then_some(self.reasoning),
+            metadata,
+        }
+    }
+
+    fn flush_text(&mut self) {
+        if !self.text.is_empty() {
+            let text = std::mem::take(&mut self.text);
+            self.content.push(MessageContent::Text(text));
+        }
+    }
+
+    fn push_call(&mut self, call: ToolCall) {
+        if !self.seen_calls.insert(call.call_id.clone()) {
+            return;
+        }
+        self.flush_text();
+        match self.content.last_mut() {
+            Some(MessageContent::ToolCalls(calls)) => calls.push(call),
+            _ => self.content.push(MessageContent::ToolCalls(vec![call])),
+        }
+    }
+
+    fn push_result(&mut self, response: ToolResponse) {
+        if !self.seen_results.insert(response.call_id.clone()) {
+            return;
+        }
+        self.flush_text();
+        match self.content.last_mut() {
+            Some(MessageContent::ToolResponses(responses)) => responses.push(response),
+            _ => self
+                .content
+                .push(MessageContent::ToolResponses(vec![response])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accumulator_rebuilds_response() {
+        let call = ToolCall {
+            call_id: "call_1".to_string(),
+            fn_name: "recall".to_string(),
+            fn_arguments: serde_json::json!({ "query": "tea" }),
+        };
+        let metadata = ResponseMetadata {
+            model_used: Some("test-model".to_string()),
+            ..Default::default()
+        };
+
+        let events = vec![
+            ResponseEvent::ReasoningChunk {
+                text: "Checking memory".to_string(),
+                is_final: true,
+            },
+            ResponseEvent::TextChunk {
+                text: "Let me ".to_string(),
+                is_final: false,
+            },
+            ResponseEvent::TextChunk {
+                text: "look.".to_string(),
+                is_final: true,
+            },
+            ResponseEvent::ToolCalls {
+                calls: vec![call.clone()],
+            },
+            ResponseEvent::ToolCallStarted {
+                call_id: call.call_id.clone(),
+                fn_name: call.fn_name.clone(),
+                args: call.fn_arguments.clone(),
+            },
+            ResponseEvent::ToolCallCompleted {
+                call_id: "call_1".to_string(),
+                result: Ok("green tea".to_string()),
+            },
+            ResponseEvent::ToolResponses {
+                responses: vec![ToolResponse::new("call_1", "green tea")],
+            },
+            ResponseEvent::TextChunk {
+                text: "You like green tea.".to_string(),
+                is_final: true,
+            },
+            ResponseEvent::Complete {
+                message_id: MessageId::generate(),
+                metadata,
+            },
+        ];
+
+        let mut accumulator = ResponseAccumulator::new();
+        for event in &events {
+            accumulator.push(event);
+        }
+        assert!(accumulator.message_id().is_some());
+
+        let response = accumulator.finish();
+        assert_eq!(response.content.len(), 4);
+        assert_eq!(response.content[0].text(), Some("Let me look."));
+        assert!(matches!(&response.content[1], MessageContent::ToolCalls(c) if c.len() == 1));
+        assert!(matches!(&response.content[2], MessageContent::ToolResponses(r) if r.len() == 1));
+        assert_eq!(response.content[3].text(), Some("You like green tea."));
+        assert_eq!(response.reasoning.as_deref(), Some("Checking memory"));
+        assert_eq!(response.metadata.model_used.as_deref(), Some("test-model"));
+    }
+}
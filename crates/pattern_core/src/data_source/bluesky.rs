@@ -10,6 +10,7 @@ use super::{
 use crate::context::AgentHandle;
 use crate::error::Result;
 use crate::memory::MemoryBlock;
+use crate::prompt_template::{TemplateRegistry, item_context};
 use crate::utils::format_duration;
 use async_trait::async_trait;
 use atrium_api::app::bsky::feed::defs::PostViewEmbedRefs;
@@ -335,34 +336,47 @@ impl ThreadContext {
         }
     }
 
-    /// Format reply options for the agent
-    pub fn format_reply_options(&self, buf: &mut String, main_post: &BlueskyPost) {
-        buf.push_str("\n💭 Reply options (choose at most one):\n");
+    /// Posts the agent could reply to: the immediate parent and its siblings,
+    /// the main post, and replies
+    pub fn reply_options<'a>(&'a self, main_post: &'a BlueskyPost) -> Vec<&'a BlueskyPost> {
+        let mut options = Vec::new();
 
-        // Add immediate parent as an option
-        if let Some((parent, _)) = self.parent_chain.first() {
-            buf.push_str(&format!("  • @{} ({})\n", parent.handle, parent.uri));
-        }
-
-        // Add immediate siblings as options (only first level)
-        if let Some((_, siblings)) = self.parent_chain.first() {
-            for sibling in siblings {
-                buf.push_str(&format!("  • @{} ({})\n", sibling.handle, sibling.uri));
-            }
+        // Add immediate parent and its siblings as options (only first level)
+        if let Some((parent, siblings)) = self.parent_chain.first() {
+            options.push(parent);
+            options.extend(siblings);
         }
 
         // Add main post as option
-        buf.push_str(&format!("  • @{} ({})\n", main_post.handle, main_post.uri));
+        options.push(main_post);
 
         // Add replies as options
         for replies in self.replies_map.values() {
-            for reply in replies {
-                buf.push_str(&format!("  • @{} ({})\n", reply.handle, reply.uri));
-            }
+            options.extend(replies);
         }
 
-        buf.push_str("If you choose to reply (by using send_message with target_type bluesky and the target_id set to the uri of the post you want to reply to, from the above options), your response must contain under 300 characters or it will be truncated.\n");
-        buf.push_str("Alternatively, you can 'like' the post by submitting a reply with 'like' as the sole text\n");
+        options
+    }
+
+    /// Template context for this thread shown around `main_post`, with the
+    /// already formatted thread tree as `thread`
+    pub fn template_context(&self, main_post: &BlueskyPost, thread: String) -> serde_json::Value {
+        let reply_options: Vec<_> = self
+            .reply_options(main_post)
+            .into_iter()
+            .map(|post| json!({ "handle": post.handle, "uri": post.uri }))
+            .collect();
+
+        // Image URLs as markers (take last 4)
+        let all_image_urls = self.collect_all_image_urls(main_post);
+        let images: Vec<_> = all_image_urls.iter().rev().take(4).rev().collect();
+
+        json!({
+            "post": main_post,
+            "thread": thread,
+            "reply_options": reply_options,
+            "images": images,
+        })
     }
 }
 
@@ -1584,6 +1598,8 @@ pub struct BlueskyFirehoseSource {
     thread_display_tracker: Arc<DashMap<String, std::time::Instant>>,
     // HTTP client for constellation API calls
     http_client: PatternHttpClient,
+    // Templates for post and thread notifications
+    templates: Arc<TemplateRegistry>,
 }
 
 impl std::fmt::Debug for BlueskyFirehoseSource {
@@ -2400,6 +2416,7 @@ impl BlueskyFirehoseSource {
             thread_cache_ttl: Duration::from_secs(600), // Cache threads for 10 minutes
             thread_display_tracker: Arc::new(DashMap::new()),
             http_client: PatternHttpClient::default(),
+            templates: TemplateRegistry::shared_defaults(),
         }
     }
 
@@ -2418,6 +2435,12 @@ impl BlueskyFirehoseSource {
         self
     }
 
+    /// Render post and thread notifications with these templates
+    pub fn with_templates(mut self, templates: Arc<TemplateRegistry>) -> Self {
+        self.templates = templates;
+        self
+    }
+
     pub fn with_cursor_file(mut self, path: impl Into<std::path::PathBuf>) -> Self {
         self.cursor_file_path = Some(path.into());
         self
@@ -2912,42 +2935,36 @@ impl BlueskyFirehoseSource {
             return None;
         }
 
-        // Format the notification
-        let mut message = String::new();
-
-        // Add batch header
-        message.push_str(&format!(
-            "Thread activity ({} posts in last 30 seconds)\n\n",
-            posts.len()
-        ));
-
         // Determine thread root from the most recent included context
         let main_post = contexts.first().map(|(p, _)| p.clone()).unwrap();
         let thread_root = main_post.thread_root();
+        let recently_shown = self.was_thread_recently_shown(&thread_root);
 
-        // Append each selected branch’s thread view
-        for (idx, (post_ref, ctx)) in contexts.iter().enumerate() {
-            if contexts.len() > 1 {
-                if idx > 0 {
-                    message.push_str("\n───\n");
+        // Render each selected branch’s thread view
+        let thread_contexts: Vec<_> = contexts
+            .iter()
+            .map(|(post_ref, ctx)| {
+                let mut thread = String::new();
+                if recently_shown {
+                    ctx.append_abbreviated_thread_tree(&mut thread, post_ref, agent_did.as_deref());
+                } else {
+                    ctx.append_thread_tree(&mut thread, post_ref, agent_did.as_deref());
                 }
-                message.push_str(&format!("Context {}:\n", idx + 1));
-            }
-
-            if self.was_thread_recently_shown(&thread_root) {
-                ctx.append_abbreviated_thread_tree(&mut message, post_ref, agent_did.as_deref());
-            } else {
-                ctx.append_thread_tree(&mut message, post_ref, agent_did.as_deref());
-            }
-            ctx.format_reply_options(&mut message, post_ref);
+                ctx.template_context(post_ref, thread)
+            })
+            .collect();
 
-            // Collect and append image URLs as markers (take last 4)
-            let all_image_urls = ctx.collect_all_image_urls(post_ref);
-            let selected_urls: Vec<_> = all_image_urls.iter().rev().take(4).rev().collect();
-            for url in selected_urls {
-                message.push_str(&format!("\n[IMAGE: {}]", url));
-            }
-        }
+        // Format the notification
+        let mut context = item_context(&main_post);
+        context.insert("post_count".to_string(), posts.len().into());
+        context.insert(
+            "posts".to_string(),
+            serde_json::to_value(&posts).unwrap_or_default(),
+        );
+        context.insert("contexts".to_string(), thread_contexts.into());
+        let mut message = self
+            .templates
+            .render_notification("bluesky_thread", &context)?;
 
         // Mark this thread as shown
         self.mark_thread_as_shown(&thread_root);
@@ -3210,45 +3227,44 @@ impl BlueskyFirehoseSource {
         }
 
         // Format the notification
-        let mut message = String::new();
-
-        if let Some(ctx) = thread_context {
+        let mut context = item_context(&post);
+        let template = if let Some(ctx) = thread_context {
             // This is a reply - show thread context
-            message.push_str("💬 New reply in thread:\n\n");
-
             let thread_root = post.thread_root();
 
             // Check if we should show abbreviated context for recently-seen threads
+            let mut thread = String::new();
             if self.was_thread_recently_shown(&thread_root) {
-                ctx.append_abbreviated_thread_tree(&mut message, &post, agent_did);
+                ctx.append_abbreviated_thread_tree(&mut thread, &post, agent_did);
             } else {
-                ctx.append_thread_tree(&mut message, &post, agent_did);
+                ctx.append_thread_tree(&mut thread, &post, agent_did);
             }
 
             // Mark this thread as shown
             self.mark_thread_as_shown(&thread_root);
 
-            ctx.format_reply_options(&mut message, &post);
-
-            // Collect and append image URLs as markers (take last 4)
-            let all_image_urls = ctx.collect_all_image_urls(&post);
-            let selected_urls: Vec<_> = all_image_urls.iter().rev().take(4).rev().collect();
-
-            for url in selected_urls {
-                message.push_str(&format!("\n[IMAGE: {}]", url));
+            let thread_context = ctx.template_context(&post, thread);
+            if let serde_json::Value::Object(fields) = &thread_context {
+                context.extend(
+                    ["thread", "reply_options", "images"]
+                        .into_iter()
+                        .filter_map(|key| Some((key.to_string(), fields.get(key)?.clone()))),
+                );
             }
+            context.insert("contexts".to_string(), json!([thread_context]));
+            "bluesky_reply"
         } else {
-            // Standalone post
-            message.push_str("📝 New post:\n\n");
-            post.append_as_standalone(&mut message, agent_did);
-
-            // Simple reply option for standalone posts
-            message.push_str(&format!(
-                "\n💭 Reply option: @{} ({})\n",
-                post.handle, post.uri
-            ));
-            message.push_str("If you choose to reply (by using send_message with target_type bluesky and the target_id set to the uri), your response must contain under 300 graphemes or it won't go through. You can thread a longer reply by requesting a heartbeat when making a reply, posting the first part of the the reply, and then replying to the uri you get back from the first successful reply with the second part\n");
-        }
+            // Standalone post, or one that mentions the agent directly
+            let mut post_text = String::new();
+            post.append_as_standalone(&mut post_text, agent_did);
+            context.insert("post_text".to_string(), post_text.into());
+            if agent_did.is_some_and(|did| post.mentions(did)) {
+                "bluesky_mention"
+            } else {
+                "bluesky_post"
+            }
+        };
+        let mut message = self.templates.render_notification(template, &context)?;
 
         // Collect memory blocks
         let mut memory_blocks = Vec::new();
@@ -4055,6 +4071,10 @@ impl DataSource for BlueskyFirehoseSource {
         self.buffer.as_ref().map(|b| b.lock().stats())
     }
 
+    fn set_templates(&mut self, templates: Arc<TemplateRegistry>) {
+        self.templates = templates;
+    }
+
     fn set_notifications_enabled(&mut self, enabled: bool) {
         self.notifications_enabled = enabled;
     }
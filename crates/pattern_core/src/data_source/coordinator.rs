@@ -10,6 +10,7 @@ use crate::context::message_router::AgentMessageRouter;
 use crate::embeddings::EmbeddingProvider;
 use crate::error::Result;
 use crate::memory::MemoryBlock;
+use crate::prompt_template::TemplateRegistry;
 
 use super::buffer::{BufferConfig, BufferStats};
 use super::traits::{DataSource, StreamEvent};
@@ -35,6 +36,8 @@ pub struct DataIngestionCoordinator<E: EmbeddingProvider + Clone> {
     embedding_provider: Option<Arc<E>>,
     /// Default target for data source notifications
     default_target: Arc<RwLock<crate::tool::builtin::MessageTarget>>,
+    /// Notification templates handed to sources as they are added
+    templates: Option<Arc<TemplateRegistry>>,
 }
 
 /// Type-erased wrapper for concrete data sources
@@ -183,6 +186,10 @@ where
         }
     }
 
+    fn set_templates(&mut self, templates: Arc<TemplateRegistry>) {
+        self.inner.set_templates(templates)
+    }
+
     fn get_buffer_stats(&self) -> Option<BufferStats> {
         self.inner.get_buffer_stats()
     }
@@ -251,6 +258,7 @@ impl<E: EmbeddingProvider + Clone + 'static> DataIngestionCoordinator<E> {
             agent_router,
             embedding_provider,
             default_target: Arc::new(RwLock::new(default_target)),
+            templates: None,
         }
     }
 
@@ -270,6 +278,16 @@ impl<E: EmbeddingProvider + Clone + 'static> DataIngestionCoordinator<E> {
         *default_target = target;
     }
 
+    /// Render notifications of sources added from now on with these templates
+    pub fn set_templates(&mut self, templates: Arc<TemplateRegistry>) {
+        self.templates = Some(templates);
+    }
+
+    /// Notification templates sources are given, if any were set
+    pub fn templates(&self) -> Option<Arc<TemplateRegistry>> {
+        self.templates.clone()
+    }
+
     /// Register a source and start monitoring
     pub async fn add_source<S>(&mut self, source: S) -> Result<()>
     where
@@ -304,6 +322,9 @@ impl<E: EmbeddingProvider + Clone + 'static> DataIngestionCoordinator<E> {
 
         // Create a type-erased wrapper
         let mut erased_source = Box::new(TypeErasedSource::new(source));
+        if let Some(templates) = &self.templates {
+            erased_source.set_templates(templates.clone());
+        }
 
         // Start monitoring if needed
         let monitoring_handle = if buffer_config.notify_changes {
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::SystemTime;
@@ -5,14 +6,17 @@ use std::time::SystemTime;
 use async_trait::async_trait;
 use compact_str::CompactString;
 use futures::Stream;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use tokio::fs;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
 
 use crate::embeddings::EmbeddingProvider;
 use crate::error::Result;
 use crate::memory::MemoryBlock;
+use crate::prompt_template::{PromptTemplate, TemplateRegistry, item_context};
 
+use super::file_index::FileIndex;
 use super::traits::{DataSource, DataSourceMetadata, DataSourceStatus, Searchable, StreamEvent};
 
 /// File-specific implementation
@@ -20,18 +24,28 @@ pub struct FileDataSource {
     pub path: PathBuf,
     pub storage_mode: FileStorageMode,
     pub watch: bool,
+    /// Stream only lines appended to watched files instead of whole files
+    pub tail: bool,
     source_id: String,
     current_cursor: Option<FileCursor>,
-    filter: FileFilter,
+    filter: PathFilter,
+    /// Where chunks go in indexed mode
+    index: Option<Arc<FileIndex>>,
     metadata: DataSourceMetadata,
     notifications_enabled: bool,
+    /// Templates for change notifications
+    templates: Arc<TemplateRegistry>,
+    /// Template used for every notification from this source, ahead of `templates`
+    template: Option<PromptTemplate>,
 }
 
 #[derive(Debug, Clone)]
 pub enum FileStorageMode {
     Ephemeral,
+    /// Chunk, embed and store files as archival memory (needs a [`FileIndex`])
     Indexed {
         embedding_provider: Arc<dyn EmbeddingProvider>,
+        /// Maximum characters per chunk
         chunk_size: i64,
     },
 }
@@ -45,7 +59,9 @@ pub enum FileCursor {
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FileFilter {
+    /// Only include files with one of these extensions
     pub extensions: Option<Vec<String>>,
+    /// Regex the full file path must match
     pub pattern: Option<String>,
     pub max_size_bytes: Option<i64>,
 }
@@ -55,6 +71,20 @@ pub struct FileItem {
     pub path: PathBuf,
     pub content: FileContent,
     pub metadata: FileMetadata,
+    /// What happened to the file, for items from a watch stream
+    #[serde(default)]
+    pub change: Option<FileChange>,
+}
+
+/// Kind of change a watched file went through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChange {
+    Created,
+    Modified,
+    /// New lines were appended (tail mode)
+    Appended,
+    Deleted,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -76,6 +106,53 @@ pub struct FileMetadata {
     pub is_dir: bool,
 }
 
+/// A [`FileFilter`] with its pattern compiled
+#[derive(Debug, Clone, Default)]
+struct PathFilter {
+    filter: FileFilter,
+    pattern: Option<Regex>,
+}
+
+impl PathFilter {
+    fn new(filter: FileFilter) -> Self {
+        let pattern = filter
+            .pattern
+            .as_deref()
+            .and_then(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid file filter pattern '{}': {}", pattern, e);
+                    None
+                }
+            });
+        Self { filter, pattern }
+    }
+
+    /// Whether a file's path passes the extension and pattern filters
+    fn allows(&self, path: &Path) -> bool {
+        if let Some(extensions) = &self.filter.extensions {
+            let Some(ext) = path.extension() else {
+                return false;
+            };
+            let ext = ext.to_string_lossy();
+            if !extensions
+                .iter()
+                .any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(&ext))
+            {
+                return false;
+            }
+        }
+
+        self.pattern
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(&path.to_string_lossy()))
+    }
+
+    fn max_size_bytes(&self) -> Option<i64> {
+        self.filter.max_size_bytes
+    }
+}
+
 impl FileDataSource {
     pub fn new(path: impl AsRef<Path>, storage_mode: FileStorageMode) -> Self {
         let path = path.as_ref().to_path_buf();
@@ -94,11 +171,15 @@ impl FileDataSource {
             path,
             storage_mode,
             watch: false,
+            tail: false,
             source_id,
             current_cursor: None,
-            filter: FileFilter::default(),
+            filter: PathFilter::default(),
+            index: None,
             metadata,
             notifications_enabled: true,
+            templates: TemplateRegistry::shared_defaults(),
+            template: None,
         }
     }
 
@@ -107,119 +188,514 @@ impl FileDataSource {
         self
     }
 
+    /// Watch for appended lines rather than whole-file changes, like `tail -f`
+    pub fn with_tail(mut self) -> Self {
+        self.watch = true;
+        self.tail = true;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: FileFilter) -> Self {
+        self.filter = PathFilter::new(filter);
+        self
+    }
+
+    /// Render change notifications with these templates
+    pub fn with_templates(mut self, templates: Arc<TemplateRegistry>) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    /// Render every notification from this source with one template, whatever
+    /// the change
+    pub fn with_template(mut self, template: PromptTemplate) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    /// Store indexed-mode chunks in this index
+    pub fn with_index(mut self, index: FileIndex) -> Self {
+        self.index = Some(Arc::new(index));
+        self
+    }
+
+    /// Bring the index up to date with the files on disk
+    ///
+    /// Indexes every matching file and drops chunks for files under the source
+    /// path that no longer exist. Returns the number of files indexed.
+    pub async fn reindex(&self) -> Result<usize> {
+        let Some(index) = &self.index else {
+            return Ok(0);
+        };
+
+        let files = if self.path.is_file() {
+            vec![self.path.clone()]
+        } else {
+            walk_files(&self.path, &self.filter).await
+        };
+
+        for stale in index.indexed_paths().await? {
+            if stale.starts_with(&self.path) && !files.contains(&stale) {
+                index.remove(&stale).await?;
+            }
+        }
+
+        let mut indexed = 0;
+        for path in &files {
+            match self.read_file(path).await {
+                Ok(item) => {
+                    update_index(Some(index.as_ref()), &self.storage_mode, &item).await;
+                    indexed += 1;
+                }
+                Err(e) => tracing::warn!("Failed to index {:?}: {}", path, e),
+            }
+        }
+        Ok(indexed)
+    }
+
     async fn read_file(&self, path: &Path) -> Result<FileItem> {
-        let metadata = fs::metadata(path).await.map_err(|e| {
-            crate::CoreError::tool_exec_error(
+        read_file(path, self.filter.max_size_bytes()).await
+    }
+}
+
+/// Keep the archival index in step with a file item, in indexed mode
+///
+/// Failures are logged rather than returned so a bad embedding call doesn't
+/// stop the agent hearing about the change.
+async fn update_index(index: Option<&FileIndex>, storage_mode: &FileStorageMode, item: &FileItem) {
+    let (
+        Some(index),
+        FileStorageMode::Indexed {
+            embedding_provider,
+            chunk_size,
+        },
+    ) = (index, storage_mode)
+    else {
+        return;
+    };
+    if item.metadata.is_dir {
+        return;
+    }
+
+    let result = match (&item.change, &item.content) {
+        (Some(FileChange::Deleted), _) => index.remove(&item.path).await.map(|_| ()),
+        (_, FileContent::Text(text)) => index
+            .index(
+                &item.path,
+                text,
+                embedding_provider.as_ref(),
+                (*chunk_size).max(1) as usize,
+            )
+            .await
+            .map(|_| ()),
+        // Tailed items only carry the new lines, so index the whole file
+        _ => match fs::read_to_string(&item.path).await {
+            Ok(text) => index
+                .index(
+                    &item.path,
+                    &text,
+                    embedding_provider.as_ref(),
+                    (*chunk_size).max(1) as usize,
+                )
+                .await
+                .map(|_| ()),
+            Err(e) => Err(crate::CoreError::tool_exec_error(
                 "file_data_source",
-                serde_json::json!({ "path": path }),
+                serde_json::json!({ "path": &item.path }),
                 e,
-            )
-        })?;
+            )),
+        },
+    };
 
-        let file_metadata = FileMetadata {
-            size_bytes: metadata.len() as i64,
-            modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
-            created: metadata.created().ok(),
-            is_dir: metadata.is_dir(),
-        };
+    if let Err(e) = result {
+        tracing::warn!("Failed to update index for {:?}: {}", item.path, e);
+    }
+}
 
-        if metadata.is_dir() {
-            return Ok(FileItem {
-                path: path.to_path_buf(),
-                content: FileContent::Text(String::new()),
-                metadata: file_metadata,
-            });
+async fn read_file(path: &Path, max_size_bytes: Option<i64>) -> Result<FileItem> {
+    let metadata = fs::metadata(path).await.map_err(|e| {
+        crate::CoreError::tool_exec_error(
+            "file_data_source",
+            serde_json::json!({ "path": path }),
+            e,
+        )
+    })?;
+
+    let file_metadata = file_metadata(&metadata);
+
+    if metadata.is_dir() {
+        return Ok(FileItem {
+            path: path.to_path_buf(),
+            content: FileContent::Text(String::new()),
+            metadata: file_metadata,
+            change: None,
+        });
+    }
+
+    // Check filter
+    if let Some(max_size) = max_size_bytes {
+        if metadata.len() as i64 > max_size {
+            return Err(crate::CoreError::tool_exec_msg(
+                "file_data_source",
+                serde_json::json!({ "path": path, "size": metadata.len() }),
+                format!("File too large: {} bytes", metadata.len()),
+            ));
         }
+    }
 
-        // Check filter
-        if let Some(max_size) = self.filter.max_size_bytes {
-            if metadata.len() as i64 > max_size {
-                return Err(crate::CoreError::tool_exec_msg(
-                    "file_data_source",
-                    serde_json::json!({ "path": path, "size": metadata.len() }),
-                    format!("File too large: {} bytes", metadata.len()),
-                ));
+    // Indexed mode chunks the text when it's indexed, so both modes read it whole
+    let text = fs::read_to_string(path).await.map_err(|e| {
+        crate::CoreError::tool_exec_error(
+            "file_data_source",
+            serde_json::json!({ "path": path }),
+            e,
+        )
+    })?;
+    let content = FileContent::Text(text);
+
+    Ok(FileItem {
+        path: path.to_path_buf(),
+        content,
+        metadata: file_metadata,
+        change: None,
+    })
+}
+
+fn file_metadata(metadata: &std::fs::Metadata) -> FileMetadata {
+    FileMetadata {
+        size_bytes: metadata.len() as i64,
+        modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+        created: metadata.created().ok(),
+        is_dir: metadata.is_dir(),
+    }
+}
+
+/// Hidden entries such as `.git` or `.obsidian` are never watched or searched
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+/// All files under `root` that pass the filter, skipping hidden entries
+async fn walk_files(root: &Path, filter: &PathFilter) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let mut entries = match fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("Failed to read directory {:?}: {}", dir, e);
+                continue;
+            }
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if is_hidden(&path) {
+                continue;
+            }
+            match entry.file_type().await {
+                Ok(file_type) if file_type.is_dir() => dirs.push(path),
+                Ok(file_type) if file_type.is_file() && filter.allows(&path) => files.push(path),
+                _ => {}
             }
         }
+    }
+
+    files
+}
+
+/// Read complete lines appended to a file since `offset`
+///
+/// Returns the lines and the offset just past the last one. A trailing line
+/// without a newline is left for the next read, and a file shorter than
+/// `offset` is assumed truncated and read from the start.
+#[cfg_attr(not(feature = "file-watch"), allow(dead_code))]
+async fn read_appended_lines(path: &Path, offset: u64) -> std::io::Result<(Vec<String>, u64)> {
+    let mut file = fs::File::open(path).await?;
+    let len = file.metadata().await?.len();
+    let offset = if len < offset { 0 } else { offset };
 
-        // Read content
-        let content = match &self.storage_mode {
-            FileStorageMode::Ephemeral => {
-                let text = fs::read_to_string(path).await.map_err(|e| {
-                    crate::CoreError::tool_exec_error(
-                        "file_data_source",
-                        serde_json::json!({ "path": path }),
-                        e,
-                    )
-                })?;
-                FileContent::Text(text)
+    file.seek(std::io::SeekFrom::Start(offset)).await?;
+    let mut bytes = Vec::with_capacity((len - offset) as usize);
+    file.read_to_end(&mut bytes).await?;
+
+    let Some(end) = bytes.iter().rposition(|b| *b == b'\n') else {
+        return Ok((Vec::new(), offset));
+    };
+    let lines = String::from_utf8_lossy(&bytes[..end])
+        .lines()
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .collect();
+
+    Ok((lines, offset + end as u64 + 1))
+}
+
+/// Turns raw filesystem notifications into file items
+///
+/// Notifications are only used as a hint that a path changed: the path's
+/// current state is compared with what was last seen, which handles editors
+/// that save by writing a temp file and renaming it over the original.
+#[cfg_attr(not(feature = "file-watch"), allow(dead_code))]
+struct WatchState {
+    root: PathBuf,
+    single_file: bool,
+    tail: bool,
+    storage_mode: FileStorageMode,
+    filter: PathFilter,
+    index: Option<Arc<FileIndex>>,
+    /// Last seen modification time and size of each known file
+    seen: HashMap<PathBuf, (SystemTime, u64)>,
+    /// Read position of each file in tail mode
+    offsets: HashMap<PathBuf, u64>,
+}
+
+#[cfg_attr(not(feature = "file-watch"), allow(dead_code))]
+impl WatchState {
+    async fn new(source: &FileDataSource, from: Option<FileCursor>) -> Self {
+        let single_file = source.path.is_file();
+        let mut state = Self {
+            root: source.path.clone(),
+            single_file,
+            tail: source.tail,
+            storage_mode: source.storage_mode.clone(),
+            filter: source.filter.clone(),
+            index: source.index.clone(),
+            seen: HashMap::new(),
+            offsets: HashMap::new(),
+        };
+
+        let files = if single_file {
+            vec![source.path.clone()]
+        } else {
+            walk_files(&source.path, &state.filter).await
+        };
+        for path in files {
+            if let Ok(metadata) = fs::metadata(&path).await {
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                state.offsets.insert(path.clone(), metadata.len());
+                state.seen.insert(path, (modified, metadata.len()));
             }
-            FileStorageMode::Indexed { chunk_size, .. } => {
-                // Read in chunks for indexing
-                let file = fs::File::open(path).await.map_err(|e| {
-                    crate::CoreError::tool_exec_error(
-                        "file_data_source",
-                        serde_json::json!({ "path": path }),
-                        e,
-                    )
-                })?;
-
-                let reader = BufReader::new(file);
-                let mut lines = reader.lines();
-                let mut chunks = Vec::new();
-                let mut current_chunk = Vec::new();
-                let mut line_num = 0;
-
-                while let Some(line) = lines.next_line().await.map_err(|e| {
-                    crate::CoreError::tool_exec_error(
-                        "file_data_source",
-                        serde_json::json!({ "path": path, "line": line_num }),
-                        e,
-                    )
-                })? {
-                    current_chunk.push(line);
-                    line_num += 1;
-
-                    if current_chunk.len() as i64 >= *chunk_size {
-                        let text = current_chunk.join("\n");
-                        chunks.push(FileContent::Chunk {
-                            text,
-                            start_line: line_num - current_chunk.len() as i64,
-                            end_line: line_num - 1,
-                        });
-                        current_chunk.clear();
-                    }
+        }
+
+        // Resume a single tailed file from where the last stream left off
+        if let (true, Some(FileCursor::ByteOffset(offset))) = (single_file, from) {
+            state
+                .offsets
+                .insert(source.path.clone(), offset.max(0) as u64);
+        }
+
+        state
+    }
+
+    fn wants(&self, path: &Path) -> bool {
+        if self.single_file {
+            return path == self.root;
+        }
+        path.starts_with(&self.root)
+            && !path
+                .strip_prefix(&self.root)
+                .map(|relative| {
+                    relative
+                        .components()
+                        .any(|c| is_hidden(Path::new(c.as_os_str())))
+                })
+                .unwrap_or(true)
+            && self.filter.allows(path)
+    }
+
+    /// Report changes under `path`, updating the index to match
+    async fn changed(&mut self, path: &Path) -> Vec<Result<StreamEvent<FileItem, FileCursor>>> {
+        let results = self.detect(path).await;
+        for event in results.iter().flatten() {
+            update_index(self.index.as_deref(), &self.storage_mode, &event.item).await;
+        }
+        results
+    }
+
+    /// Work out what happened to `path`, if anything worth reporting
+    ///
+    /// A removed directory reports every file seen under it as deleted.
+    async fn detect(&mut self, path: &Path) -> Vec<Result<StreamEvent<FileItem, FileCursor>>> {
+        let metadata = match fs::metadata(path).await {
+            Ok(metadata) if metadata.is_file() && self.wants(path) => metadata,
+            Ok(_) => return Vec::new(),
+            Err(_) => return self.removed(path),
+        };
+        self.detect_file(path, metadata).await.into_iter().collect()
+    }
+
+    /// Forget every seen file at or under a path that no longer exists
+    fn removed(&mut self, path: &Path) -> Vec<Result<StreamEvent<FileItem, FileCursor>>> {
+        let gone: Vec<PathBuf> = self
+            .seen
+            .keys()
+            .filter(|seen| seen.starts_with(path))
+            .cloned()
+            .collect();
+
+        gone.into_iter()
+            .map(|path| {
+                self.seen.remove(&path);
+                self.offsets.remove(&path);
+                Ok(StreamEvent {
+                    item: FileItem {
+                        path,
+                        content: FileContent::Text(String::new()),
+                        metadata: FileMetadata {
+                            size_bytes: 0,
+                            modified: SystemTime::now(),
+                            created: None,
+                            is_dir: false,
+                        },
+                        change: Some(FileChange::Deleted),
+                    },
+                    cursor: FileCursor::ModTime(SystemTime::now()),
+                    timestamp: chrono::Utc::now(),
+                })
+            })
+            .collect()
+    }
+
+    async fn detect_file(
+        &mut self,
+        path: &Path,
+        metadata: std::fs::Metadata,
+    ) -> Option<Result<StreamEvent<FileItem, FileCursor>>> {
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        let previous = self
+            .seen
+            .insert(path.to_path_buf(), (modified, metadata.len()));
+        if previous == Some((modified, metadata.len())) {
+            return None;
+        }
+
+        if self.tail && previous.is_some() {
+            let offset = self.offsets.get(path).copied().unwrap_or(0);
+            return match read_appended_lines(path, offset).await {
+                Ok((lines, _)) if lines.is_empty() => None,
+                Ok((lines, offset)) => {
+                    self.offsets.insert(path.to_path_buf(), offset);
+                    Some(Ok(StreamEvent {
+                        item: FileItem {
+                            path: path.to_path_buf(),
+                            content: FileContent::Lines(lines),
+                            metadata: file_metadata(&metadata),
+                            change: Some(FileChange::Appended),
+                        },
+                        cursor: FileCursor::ByteOffset(offset as i64),
+                        timestamp: chrono::Utc::now(),
+                    }))
                 }
+                Err(e) => Some(Err(crate::CoreError::tool_exec_error(
+                    "file_data_source",
+                    serde_json::json!({ "path": path, "offset": offset }),
+                    e,
+                ))),
+            };
+        }
+
+        let change = if previous.is_some() {
+            FileChange::Modified
+        } else {
+            FileChange::Created
+        };
+        let result = read_file(path, self.filter.max_size_bytes()).await;
+        if let Ok(item) = &result {
+            self.offsets
+                .insert(path.to_path_buf(), item.metadata.size_bytes as u64);
+        }
+        Some(result.map(|item| StreamEvent {
+            cursor: if self.tail {
+                FileCursor::ByteOffset(item.metadata.size_bytes)
+            } else {
+                FileCursor::ModTime(modified)
+            },
+            item: FileItem {
+                change: Some(change),
+                ..item
+            },
+            timestamp: chrono::Utc::now(),
+        }))
+    }
+}
+
+#[cfg(feature = "file-watch")]
+fn watch_path(
+    state: WatchState,
+) -> Result<tokio::sync::mpsc::Receiver<Result<StreamEvent<FileItem, FileCursor>>>> {
+    use notify::{EventKind, RecursiveMode, Watcher};
+
+    let (raw_tx, mut raw_rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let _ = raw_tx.send(event);
+    })
+    .map_err(|e| {
+        crate::CoreError::tool_exec_error(
+            "file_data_source",
+            serde_json::json!({ "path": &state.root }),
+            e,
+        )
+    })?;
+
+    // Watch a single file through its directory so saves that replace the
+    // file are still seen
+    let (target, mode) = if state.single_file {
+        let parent = state
+            .root
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        (parent.to_path_buf(), RecursiveMode::NonRecursive)
+    } else {
+        (state.root.clone(), RecursiveMode::Recursive)
+    };
+    watcher.watch(&target, mode).map_err(|e| {
+        crate::CoreError::tool_exec_error(
+            "file_data_source",
+            serde_json::json!({ "path": &target }),
+            e,
+        )
+    })?;
+
+    let (tx, rx) = tokio::sync::mpsc::channel(100);
+    tokio::spawn(async move {
+        // Dropping the watcher stops notifications, so it lives as long as the task
+        let _watcher = watcher;
+        let mut state = state;
 
-                // Last chunk
-                if !current_chunk.is_empty() {
-                    let text = current_chunk.join("\n");
-                    chunks.push(FileContent::Chunk {
-                        text,
-                        start_line: line_num - current_chunk.len() as i64,
-                        end_line: line_num - 1,
-                    });
+        loop {
+            let event = tokio::select! {
+                _ = tx.closed() => break,
+                event = raw_rx.recv() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+            };
+
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    tracing::warn!("File watch error for {:?}: {}", state.root, e);
+                    continue;
                 }
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
 
-                // For now, return as lines
-                FileContent::Lines(
-                    chunks
-                        .into_iter()
-                        .map(|c| match c {
-                            FileContent::Chunk { text, .. } => text,
-                            _ => String::new(),
-                        })
-                        .collect(),
-                )
+            for path in event.paths {
+                for result in state.changed(&path).await {
+                    if tx.send(result).await.is_err() {
+                        return;
+                    }
+                }
             }
-        };
+        }
+    });
 
-        Ok(FileItem {
-            path: path.to_path_buf(),
-            content,
-            metadata: file_metadata,
-        })
-    }
+    Ok(rx)
 }
 
 #[async_trait]
@@ -251,6 +727,7 @@ impl DataSource for FileDataSource {
                 }
             }
 
+            update_index(self.index.as_deref(), &self.storage_mode, &item).await;
             items.push(item);
         } else {
             // Directory listing
@@ -279,16 +756,15 @@ impl DataSource for FileDataSource {
                 let path = entry.path();
 
                 // Apply filter
-                if let Some(extensions) = &self.filter.extensions {
-                    if let Some(ext) = path.extension() {
-                        if !extensions.contains(&ext.to_string_lossy().to_string()) {
-                            continue;
-                        }
-                    }
+                if path.is_file() && !self.filter.allows(&path) {
+                    continue;
                 }
 
                 match self.read_file(&path).await {
-                    Ok(item) => items.push(item),
+                    Ok(item) => {
+                        update_index(self.index.as_deref(), &self.storage_mode, &item).await;
+                        items.push(item);
+                    }
                     Err(e) => {
                         tracing::warn!("Failed to read file {:?}: {}", path, e);
                         self.metadata.error_count += 1;
@@ -308,7 +784,7 @@ impl DataSource for FileDataSource {
 
     async fn subscribe(
         &mut self,
-        _from: Option<Self::Cursor>,
+        from: Option<Self::Cursor>,
     ) -> Result<Box<dyn Stream<Item = Result<StreamEvent<Self::Item, Self::Cursor>>> + Send + Unpin>>
     {
         if !self.watch {
@@ -319,14 +795,29 @@ impl DataSource for FileDataSource {
             });
         }
 
-        // TODO: Implement file watching with notify crate
-        // For now, return empty stream
-        let stream = futures::stream::empty();
-        Ok(Box::new(stream))
+        #[cfg(feature = "file-watch")]
+        {
+            let state = WatchState::new(self, from).await;
+            let rx = watch_path(state)?;
+            return Ok(Box::new(tokio_stream::wrappers::ReceiverStream::new(rx))
+                as Box<
+                    dyn Stream<Item = Result<StreamEvent<Self::Item, Self::Cursor>>> + Send + Unpin,
+                >);
+        }
+
+        #[cfg(not(feature = "file-watch"))]
+        {
+            let _ = from;
+            Err(crate::CoreError::ToolExecutionFailed {
+                tool_name: "file_data_source".to_string(),
+                cause: "File watching requires the file-watch feature".to_string(),
+                parameters: serde_json::json!({ "path": &self.path }),
+            })
+        }
     }
 
     fn set_filter(&mut self, filter: Self::Filter) {
-        self.filter = filter;
+        self.filter = PathFilter::new(filter);
     }
 
     fn current_cursor(&self) -> Option<Self::Cursor> {
@@ -352,8 +843,17 @@ impl DataSource for FileDataSource {
         &self,
         item: &Self::Item,
     ) -> Option<(String, Vec<(CompactString, MemoryBlock)>)> {
-        let path_str = item.path.display();
-        let size_str = if item.metadata.size_bytes > 1024 * 1024 {
+        let indexed =
+            self.index.is_some() && matches!(self.storage_mode, FileStorageMode::Indexed { .. });
+        let template = match item.change {
+            Some(FileChange::Created) => "file_created",
+            Some(FileChange::Modified) => "file_modified",
+            Some(FileChange::Appended) => "file_appended",
+            Some(FileChange::Deleted) => "file_deleted",
+            None => "file_changed",
+        };
+
+        let size = if item.metadata.size_bytes > 1024 * 1024 {
             format!(
                 "{:.1}MB",
                 item.metadata.size_bytes as f64 / (1024.0 * 1024.0)
@@ -363,47 +863,63 @@ impl DataSource for FileDataSource {
         } else {
             format!("{} bytes", item.metadata.size_bytes)
         };
+        let (content_kind, text, lines) = match &item.content {
+            FileContent::Text(text) => ("text", text.clone(), text.lines().collect::<Vec<_>>()),
+            FileContent::Lines(lines) => (
+                "lines",
+                lines.join("\n"),
+                lines.iter().map(String::as_str).collect(),
+            ),
+            FileContent::Chunk { text, .. } => ("chunk", text.clone(), text.lines().collect()),
+        };
 
-        let message = match &item.content {
-            FileContent::Text(text) => {
-                let preview = if text.lines().count() > 5 {
-                    let lines: Vec<&str> = text.lines().take(5).collect();
-                    format!(
-                        "\n\nPreview:\n{}\n... ({} more lines)",
-                        lines.join("\n"),
-                        text.lines().count() - 5
-                    )
-                } else {
-                    format!("\n\nContent:\n{}", text)
-                };
-                format!("📄 File: {} ({}){}", path_str, size_str, preview)
-            }
-            FileContent::Lines(lines) => {
-                let preview = if lines.len() > 5 {
-                    format!(
-                        "\n\nFirst 5 lines:\n{}\n... ({} more lines)",
-                        lines[..5].join("\n"),
-                        lines.len() - 5
-                    )
-                } else {
-                    format!("\n\nLines:\n{}", lines.join("\n"))
-                };
-                format!("📋 File: {} ({}){}", path_str, size_str, preview)
+        // Indexed chunks live in archival memory, so point there rather than
+        // attaching them all to the agent's context
+        let chunk_count = match (&self.storage_mode, &item.content) {
+            (FileStorageMode::Indexed { chunk_size, .. }, FileContent::Text(text)) if indexed => {
+                super::file_index::chunk_text(text, (*chunk_size).max(1) as usize).len()
             }
-            FileContent::Chunk {
-                text,
-                start_line,
-                end_line,
-            } => format!(
-                "📄 File: {} (lines {}-{})\n\n{}",
-                path_str, start_line, end_line, text
-            ),
+            _ => 0,
         };
 
-        // No memory blocks from file source
+        let mut context = item_context(item);
+        context.insert("path".to_string(), item.path.display().to_string().into());
+        context.insert("size".to_string(), size.into());
+        context.insert("content_kind".to_string(), content_kind.into());
+        context.insert("line_count".to_string(), lines.len().into());
+        context.insert("lines".to_string(), lines.into());
+        context.insert("text".to_string(), text.into());
+        context.insert("indexed".to_string(), indexed.into());
+        context.insert("chunk_count".to_string(), chunk_count.into());
+        if let FileContent::Chunk {
+            start_line,
+            end_line,
+            ..
+        } = &item.content
+        {
+            context.insert("start_line".to_string(), (*start_line).into());
+            context.insert("end_line".to_string(), (*end_line).into());
+        }
+
+        let rendered = self.template.as_ref().and_then(|custom| {
+            custom
+                .render(&context)
+                .inspect_err(|e| {
+                    tracing::warn!("Failed to render template for {}: {}", self.source_id, e)
+                })
+                .ok()
+        });
+        let message = match rendered {
+            Some(message) => message,
+            None => self.templates.render_notification(template, &context)?,
+        };
         Some((message, vec![]))
     }
 
+    fn set_templates(&mut self, templates: Arc<TemplateRegistry>) {
+        self.templates = templates;
+    }
+
     fn set_notifications_enabled(&mut self, enabled: bool) {
         self.notifications_enabled = enabled;
     }
@@ -412,11 +928,30 @@ impl DataSource for FileDataSource {
         self.notifications_enabled
     }
 
-    async fn search(&self, _query: &str, _limit: usize) -> Result<Vec<Self::Item>> {
-        // For files, we could implement a simple search by reading the file
-        // and searching within it, but for now we'll use the default
-        // TODO: Implement file content search
-        Ok(vec![])
+    /// Search file names and contents under the source path, most relevant first
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<Self::Item>> {
+        let paths = if self.path.is_file() {
+            vec![self.path.clone()]
+        } else {
+            walk_files(&self.path, &self.filter).await
+        };
+
+        let mut matches = Vec::new();
+        for path in paths {
+            match self.read_file(&path).await {
+                Ok(item) if item.matches(query) => matches.push((item.relevance(query), item)),
+                Ok(_) => {}
+                // Binary and oversized files can't be searched
+                Err(e) => tracing::debug!("Skipping {:?} in search: {}", path, e),
+            }
+        }
+
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0));
+        Ok(matches
+            .into_iter()
+            .take(limit)
+            .map(|(_, item)| item)
+            .collect())
     }
 }
 
@@ -498,3 +1033,120 @@ impl Searchable for FileItem {
         (score / 10.0).min(1.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_filter() {
+        let filter = PathFilter::new(FileFilter {
+            extensions: Some(vec![".md".to_string()]),
+            pattern: Some("journal/".to_string()),
+            max_size_bytes: None,
+        });
+
+        assert!(filter.allows(Path::new("/vault/journal/today.MD")));
+        assert!(!filter.allows(Path::new("/vault/ideas/today.md")));
+        assert!(!filter.allows(Path::new("/vault/journal/today.txt")));
+        assert!(!filter.allows(Path::new("/vault/journal/README")));
+    }
+
+    #[tokio::test]
+    async fn test_read_appended_lines_waits_for_newline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        std::fs::write(&path, "first\nsecond\npart").unwrap();
+
+        let (lines, offset) = read_appended_lines(&path, 6).await.unwrap();
+        assert_eq!(lines, vec!["second".to_string()]);
+        assert_eq!(offset, 13);
+
+        std::fs::write(&path, "new\n").unwrap();
+        let (lines, offset) = read_appended_lines(&path, offset).await.unwrap();
+        assert_eq!(lines, vec!["new".to_string()]);
+        assert_eq!(offset, 4);
+    }
+
+    #[tokio::test]
+    async fn test_search_walks_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("notes/daily")).unwrap();
+        std::fs::create_dir_all(dir.path().join(".obsidian")).unwrap();
+        std::fs::write(dir.path().join("notes/daily/today.md"), "Met with Orual").unwrap();
+        std::fs::write(dir.path().join("notes/other.md"), "nothing here").unwrap();
+        std::fs::write(dir.path().join(".obsidian/cache.md"), "Orual").unwrap();
+
+        let source = FileDataSource::new(dir.path(), FileStorageMode::Ephemeral);
+        let results = source.search("orual", 10).await.unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].path.ends_with("notes/daily/today.md"));
+    }
+
+    #[cfg(feature = "file-watch")]
+    #[tokio::test]
+    async fn test_tail_streams_appended_lines() {
+        use futures::StreamExt;
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        std::fs::write(&path, "old line\n").unwrap();
+
+        let mut source = FileDataSource::new(&path, FileStorageMode::Ephemeral).with_tail();
+        let mut stream = source.subscribe(None).await.unwrap();
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        writeln!(file, "new line").unwrap();
+        file.sync_all().unwrap();
+
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), stream.next())
+            .await
+            .expect("no event before timeout")
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.item.change, Some(FileChange::Appended));
+        assert!(matches!(
+            &event.item.content,
+            FileContent::Lines(lines) if lines == &vec!["new line".to_string()]
+        ));
+        assert!(matches!(event.cursor, FileCursor::ByteOffset(18)));
+    }
+
+    #[tokio::test]
+    async fn test_removed_directory_deletes_its_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("notes/daily")).unwrap();
+        std::fs::write(dir.path().join("notes/daily/today.md"), "today").unwrap();
+        std::fs::write(dir.path().join("notes/other.md"), "other").unwrap();
+        std::fs::write(dir.path().join("kept.md"), "kept").unwrap();
+
+        let source = FileDataSource::new(dir.path(), FileStorageMode::Ephemeral);
+        let mut state = WatchState::new(&source, None).await;
+        assert_eq!(state.seen.len(), 3);
+
+        let notes = dir.path().join("notes");
+        std::fs::remove_dir_all(&notes).unwrap();
+        let events = state.detect(&notes).await;
+
+        let mut deleted: Vec<PathBuf> = events
+            .into_iter()
+            .map(|event| event.unwrap().item)
+            .inspect(|item| assert_eq!(item.change, Some(FileChange::Deleted)))
+            .map(|item| item.path)
+            .collect();
+        deleted.sort();
+        assert_eq!(
+            deleted,
+            vec![notes.join("daily/today.md"), notes.join("other.md")]
+        );
+        assert_eq!(
+            state.seen.keys().collect::<Vec<_>>(),
+            vec![&dir.path().join("kept.md")]
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! Archival indexing for files read in [`FileStorageMode::Indexed`]
+//!
+//! Files are split into line-aligned chunks, embedded, and stored as archival
+//! memory blocks attached to an agent, so they turn up in `recall` and
+//! `search` like anything else the agent has archived. Each block's metadata
+//! records the file path and line range it came from.
+//!
+//! [`FileStorageMode::Indexed`]: super::FileStorageMode::Indexed
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use compact_str::format_compact;
+use serde_json::json;
+use surrealdb::{RecordId, Surreal, engine::any::Any};
+use tokio::sync::OnceCell;
+
+use super::file::FileContent;
+use crate::db::{DatabaseError, DbEntity, ops};
+use crate::embeddings::EmbeddingProvider;
+use crate::error::Result;
+use crate::id::{AgentId, UserId};
+use crate::memory::{MemoryBlock, MemoryPermission, MemoryType};
+
+/// Value of `metadata.source` on blocks created from files
+const FILE_SOURCE: &str = "file";
+
+/// Split text into chunks of at most `chunk_size` characters
+///
+/// Chunks break between lines and never start on a blank line, so a single
+/// line longer than `chunk_size` becomes a chunk of its own. Line numbers are
+/// 1-based and inclusive.
+pub fn chunk_text(text: &str, chunk_size: usize) -> Vec<FileContent> {
+    let chunk_size = chunk_size.max(1);
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut start_line = 1;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index as i64 + 1;
+        if !current.is_empty() && current.len() + line.len() + 1 > chunk_size {
+            chunks.push(FileContent::Chunk {
+                text: std::mem::take(&mut current),
+                start_line,
+                end_line: line_number - 1,
+            });
+        }
+        if current.is_empty() {
+            // Don't start a chunk on blank lines
+            if line.trim().is_empty() {
+                continue;
+            }
+            start_line = line_number;
+        } else {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+
+    if !current.is_empty() {
+        chunks.push(FileContent::Chunk {
+            text: current,
+            start_line,
+            end_line: text.lines().count() as i64,
+        });
+    }
+
+    chunks
+}
+
+/// Stores file chunks as one agent's archival memory
+#[derive(Debug)]
+pub struct FileIndex {
+    db: Surreal<Any>,
+    agent_id: AgentId,
+    owner_id: OnceCell<UserId>,
+}
+
+impl FileIndex {
+    pub fn new(db: Surreal<Any>, agent_id: AgentId) -> Self {
+        Self {
+            db,
+            agent_id,
+            owner_id: OnceCell::new(),
+        }
+    }
+
+    /// Index a file's text, replacing whatever was indexed for it before
+    ///
+    /// Chunks whose text hasn't changed keep their embeddings, so editing one
+    /// section of a long document only re-embeds that section. Returns the
+    /// blocks now stored for the file.
+    pub async fn index(
+        &self,
+        path: &Path,
+        text: &str,
+        embedding_provider: &dyn EmbeddingProvider,
+        chunk_size: usize,
+    ) -> Result<Vec<MemoryBlock>> {
+        let chunks = chunk_text(text, chunk_size);
+        let existing = self.indexed_blocks(path).await?;
+
+        let unchanged = existing.len() == chunks.len()
+            && existing.iter().zip(&chunks).all(|(block, chunk)| {
+                matches!(chunk, FileContent::Chunk { text, start_line, end_line }
+                    if &block.value == text
+                        && block.metadata["start_line"] == *start_line
+                        && block.metadata["end_line"] == *end_line)
+            });
+        if unchanged {
+            return Ok(existing);
+        }
+
+        // Reuse embeddings for chunk text we've already embedded with this model
+        let model = embedding_provider.model_id();
+        let reusable: HashMap<&str, &Vec<f32>> = existing
+            .iter()
+            .filter(|block| block.embedding_model.as_deref() == Some(model))
+            .filter_map(|block| Some((block.value.as_str(), block.embedding.as_ref()?)))
+            .collect();
+
+        let to_embed: Vec<String> = chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                FileContent::Chunk { text, .. } if !reusable.contains_key(text.as_str()) => {
+                    Some(text.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        let mut embedded: HashMap<String, Vec<f32>> = HashMap::new();
+        for batch in to_embed.chunks(embedding_provider.max_batch_size().max(1)) {
+            let embeddings = embedding_provider
+                .embed_batch(batch)
+                .await
+                .map_err(DatabaseError::from)?;
+            embedded.extend(
+                batch
+                    .iter()
+                    .cloned()
+                    .zip(embeddings.into_iter().map(|embedding| embedding.vector)),
+            );
+        }
+
+        let owner_id = self.owner_id().await?;
+        let path_str = path.display().to_string();
+        let blocks: Vec<MemoryBlock> = chunks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, chunk)| match chunk {
+                FileContent::Chunk {
+                    text,
+                    start_line,
+                    end_line,
+                } => Some(MemoryBlock {
+                    owner_id: owner_id.clone(),
+                    label: format_compact!("file:{}:{}-{}", path_str, start_line, end_line),
+                    value: text.clone(),
+                    description: Some(format!("Lines {}-{} of {}", start_line, end_line, path_str)),
+                    memory_type: MemoryType::Archival,
+                    permission: MemoryPermission::ReadOnly,
+                    metadata: json!({
+                        "source": FILE_SOURCE,
+                        "path": path_str,
+                        "chunk_index": index,
+                        "start_line": start_line,
+                        "end_line": end_line,
+                    }),
+                    embedding_model: Some(model.to_string()),
+                    embedding: reusable
+                        .get(text.as_str())
+                        .map(|vector| (*vector).clone())
+                        .or_else(|| embedded.get(text).cloned()),
+                    ..Default::default()
+                }),
+                _ => None,
+            })
+            .collect();
+
+        self.delete_blocks(&existing).await?;
+        for block in &blocks {
+            ops::persist_agent_memory(
+                &self.db,
+                self.agent_id.clone(),
+                block,
+                MemoryPermission::ReadOnly,
+            )
+            .await?;
+        }
+
+        tracing::debug!(
+            "Indexed {} as {} chunks ({} newly embedded)",
+            path_str,
+            blocks.len(),
+            embedded.len()
+        );
+        Ok(blocks)
+    }
+
+    /// Remove everything indexed for a file, returning how many chunks went
+    pub async fn remove(&self, path: &Path) -> Result<usize> {
+        let existing = self.indexed_blocks(path).await?;
+        self.delete_blocks(&existing).await?;
+        Ok(existing.len())
+    }
+
+    /// Every file path this agent has chunks for
+    pub async fn indexed_paths(&self) -> Result<Vec<PathBuf>> {
+        let sql = r#"
+            SELECT VALUE metadata.path FROM $agent_id->agent_memories->mem
+            WHERE memory_type = 'archival'
+            AND metadata.source = $source
+        "#;
+
+        let mut response = self
+            .db
+            .query(sql)
+            .bind(("agent_id", RecordId::from(&self.agent_id)))
+            .bind(("source", FILE_SOURCE))
+            .await
+            .map_err(DatabaseError::from)?;
+
+        let mut paths: Vec<String> = response.take(0).map_err(DatabaseError::from)?;
+        paths.sort();
+        paths.dedup();
+        Ok(paths.into_iter().map(PathBuf::from).collect())
+    }
+
+    /// Blocks indexed for a file, in chunk order
+    pub async fn indexed_blocks(&self, path: &Path) -> Result<Vec<MemoryBlock>> {
+        let sql = r#"
+            SELECT * FROM $agent_id->agent_memories->mem
+            WHERE memory_type = 'archival'
+            AND metadata.source = $source
+            AND metadata.path = $path
+            ORDER BY metadata.chunk_index
+        "#;
+
+        let mut response = self
+            .db
+            .query(sql)
+            .bind(("agent_id", RecordId::from(&self.agent_id)))
+            .bind(("source", FILE_SOURCE))
+            .bind(("path", path.display().to_string()))
+            .await
+            .map_err(DatabaseError::from)?;
+
+        let models: Vec<<MemoryBlock as DbEntity>::DbModel> =
+            response.take(0).map_err(DatabaseError::from)?;
+        models
+            .into_iter()
+            .map(|model| {
+                MemoryBlock::from_db_model(model).map_err(|e| DatabaseError::from(e).into())
+            })
+            .collect()
+    }
+
+    async fn delete_blocks(&self, blocks: &[MemoryBlock]) -> Result<()> {
+        if blocks.is_empty() {
+            return Ok(());
+        }
+
+        // Deleting the records also removes their agent_memories edges
+        let ids: Vec<RecordId> = blocks
+            .iter()
+            .map(|block| RecordId::from(&block.id))
+            .collect();
+        self.db
+            .query("DELETE mem WHERE id IN $ids")
+            .bind(("ids", ids))
+            .await
+            .map_err(DatabaseError::from)?;
+        Ok(())
+    }
+
+    /// The agent's owner, who owns the chunk blocks too
+    async fn owner_id(&self) -> Result<UserId> {
+        self.owner_id
+            .get_or_try_init(|| async {
+                let mut response = self
+                    .db
+                    .query("SELECT VALUE owner_id FROM ONLY $agent_id")
+                    .bind(("agent_id", RecordId::from(&self.agent_id)))
+                    .await
+                    .map_err(DatabaseError::from)?;
+                let owner: Option<RecordId> = response.take(0).map_err(DatabaseError::from)?;
+                owner.map(UserId::from_record).ok_or_else(|| {
+                    DatabaseError::NotFound {
+                        entity_type: "agent".to_string(),
+                        id: self.agent_id.to_string(),
+                    }
+                    .into()
+                })
+            })
+            .await
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AgentRecord;
+    use crate::db::{client, ops::create_entity};
+    use crate::embeddings::MockEmbeddingProvider;
+
+    fn chunk_lines(chunks: &[FileContent]) -> Vec<(i64, i64)> {
+        chunks
+            .iter()
+            .map(|chunk| match chunk {
+                FileContent::Chunk {
+                    start_line,
+                    end_line,
+                    ..
+                } => (*start_line, *end_line),
+                _ => panic!("expected a chunk"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chunk_text_breaks_between_lines() {
+        let text = "one\ntwo\nthree\n\n   \nfour";
+        let chunks = chunk_text(text, 8);
+
+        assert_eq!(chunk_lines(&chunks), vec![(1, 2), (3, 4), (6, 6)]);
+        assert!(matches!(&chunks[0], FileContent::Chunk { text, .. } if text == "one\ntwo"));
+
+        // A line longer than the chunk size still gets its own chunk
+        let chunks = chunk_text("short\nthis line is too long\nend", 8);
+        assert_eq!(chunk_lines(&chunks), vec![(1, 1), (2, 2), (3, 3)]);
+    }
+
+    #[tokio::test]
+    async fn test_index_replaces_and_removes_chunks() {
+        let db = client::create_test_db().await.unwrap();
+        let agent = AgentRecord {
+            id: AgentId::generate(),
+            name: "Indexer".to_string(),
+            owner_id: UserId::generate(),
+            ..Default::default()
+        };
+        create_entity::<AgentRecord, _>(&db, &agent).await.unwrap();
+
+        let provider = MockEmbeddingProvider {
+            dimensions: 1536,
+            ..Default::default()
+        };
+        let index = FileIndex::new(db, agent.id.clone());
+        let path = Path::new("/vault/notes.md");
+
+        let blocks = index
+            .index(path, "alpha\nbeta\ngamma", &provider, 10)
+            .await
+            .unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].owner_id, agent.owner_id);
+        assert!(blocks.iter().all(|block| block.embedding.is_some()));
+
+        index.index(path, "alpha", &provider, 10).await.unwrap();
+        let stored = index.indexed_blocks(path).await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].value, "alpha");
+        assert_eq!(stored[0].metadata["path"], "/vault/notes.md");
+
+        assert_eq!(index.remove(path).await.unwrap(), 1);
+        assert!(index.indexed_blocks(path).await.unwrap().is_empty());
+    }
+}
@@ -10,9 +10,12 @@ use crate::{
         bluesky::{BlueskyFilter, BlueskyFirehoseSource},
         coordinator::DataIngestionCoordinator,
         file::{FileDataSource, FileStorageMode},
+        file_index::FileIndex,
+        homeassistant::{HomeAssistantClient, HomeAssistantFilter, HomeAssistantSource},
     },
     embeddings::EmbeddingProvider,
     error::Result,
+    prompt_template::{PromptTemplate, TemplateRegistry},
 };
 use surrealdb::Surreal;
 
@@ -81,12 +84,27 @@ where
         source = source.with_watch();
     }
 
-    // Note: Template path would need to be handled via prompt templates in coordinator
-    // FileDataSource doesn't have built-in template support
-    if template_path.is_some() {
-        tracing::warn!(
-            "Template path specified but FileDataSource doesn't support templates directly. Use prompt templates in coordinator instead."
-        );
+    if matches!(source.storage_mode, FileStorageMode::Indexed { .. }) {
+        let router = coordinator.router();
+        source = source.with_index(FileIndex::new(
+            router.db().clone(),
+            router.agent_id().clone(),
+        ));
+        let indexed = source.reindex().await?;
+        tracing::info!("Indexed {} files from {:?}", indexed, source.path);
+    }
+
+    if let Some(template_path) = template_path {
+        let template = tokio::fs::read_to_string(&template_path)
+            .await
+            .map_err(|e| crate::CoreError::IoError {
+                operation: format!("reading template {}", template_path.display()),
+                cause: e,
+            })?;
+        source = source.with_template(PromptTemplate::new(
+            template_path.display().to_string(),
+            template,
+        )?);
     }
 
     coordinator.add_source(source).await
@@ -126,12 +144,27 @@ where
     coordinator.add_source(source).await
 }
 
+/// Add a HomeAssistant entity state source to an agent's coordinator
+pub async fn add_homeassistant_source<E>(
+    coordinator: &mut DataIngestionCoordinator<E>,
+    client: HomeAssistantClient,
+    filter: HomeAssistantFilter,
+    owner_id: crate::id::UserId,
+) -> Result<()>
+where
+    E: EmbeddingProvider + Clone + 'static,
+{
+    let source = HomeAssistantSource::with_client(client, owner_id).with_filter(filter);
+    coordinator.add_source(source).await
+}
+
 /// Builder for setting up data sources on an agent
 pub struct DataSourceBuilder<E: EmbeddingProvider + Clone> {
     #[allow(dead_code)]
     coordinator: Option<DataIngestionCoordinator<E>>,
     file_sources: Vec<FileSourceConfig>,
     bluesky_sources: Vec<BlueskySourceConfig>,
+    templates: Option<Arc<TemplateRegistry>>,
 }
 
 struct FileSourceConfig {
@@ -154,9 +187,16 @@ impl<E: EmbeddingProvider + Clone> DataSourceBuilder<E> {
             coordinator: None,
             file_sources: Vec::new(),
             bluesky_sources: Vec::new(),
+            templates: None,
         }
     }
 
+    /// Render notifications from every source with these templates
+    pub fn with_templates(mut self, templates: Option<Arc<TemplateRegistry>>) -> Self {
+        self.templates = templates;
+        self
+    }
+
     /// Add a file source configuration
     pub fn with_file_source<P: AsRef<Path>>(mut self, path: P, watch: bool, indexed: bool) -> Self {
         self.file_sources.push(FileSourceConfig {
@@ -233,6 +273,9 @@ impl<E: EmbeddingProvider + Clone> DataSourceBuilder<E> {
     {
         let mut coordinator =
             create_coordinator_with_agent_info(agent_id, agent_name, db, embedding_provider);
+        if let Some(templates) = self.templates {
+            coordinator.set_templates(templates);
+        }
 
         // Add file sources
         for config in self.file_sources {
@@ -285,6 +328,9 @@ impl<E: EmbeddingProvider + Clone> DataSourceBuilder<E> {
         let mut coordinator =
             create_coordinator_with_target(agent_id, agent_name, db, embedding_provider, target)
                 .await;
+        if let Some(templates) = self.templates {
+            coordinator.set_templates(templates);
+        }
 
         // Add file sources
         for config in self.file_sources {
@@ -1,46 +1,246 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use compact_str::CompactString;
-use futures::{SinkExt, Stream, StreamExt};
+use futures::{Sink, SinkExt, Stream, StreamExt};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, mpsc, watch};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use url::Url;
 
+use crate::UserId;
 use crate::error::Result;
 use crate::memory::{MemoryBlock, MemoryPermission, MemoryType};
-use crate::{MemoryId, UserId};
+use crate::prompt_template::{TemplateRegistry, item_context};
 
 use super::BufferConfig;
 use super::traits::{DataSource, DataSourceMetadata, DataSourceStatus, StreamEvent};
 
-/// HomeAssistant data source for real-time entity state tracking
-pub struct HomeAssistantSource {
+type WebSocket =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// REST client for a HomeAssistant instance
+///
+/// Shared by [`HomeAssistantSource`] for reading states and by the `home`
+/// tool for calling services.
+#[derive(Clone)]
+pub struct HomeAssistantClient {
     /// Base URL of HomeAssistant instance (e.g., http://homeassistant.local:8123)
     base_url: Url,
     /// Long-lived access token for authentication
     access_token: String,
+    http: Client,
+}
+
+impl std::fmt::Debug for HomeAssistantClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HomeAssistantClient")
+            .field("base_url", &self.base_url.as_str())
+            .finish_non_exhaustive()
+    }
+}
+
+impl HomeAssistantClient {
+    pub fn new(base_url: Url, access_token: String) -> Self {
+        Self {
+            base_url,
+            access_token,
+            http: Client::new(),
+        }
+    }
+
+    /// Base URL of the instance
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!(
+            "{}/api/{}",
+            self.base_url.as_str().trim_end_matches('/'),
+            path
+        )
+    }
+
+    fn websocket_url(&self) -> String {
+        let base = self.base_url.as_str().trim_end_matches('/');
+        let base = if let Some(rest) = base.strip_prefix("https://") {
+            format!("wss://{}", rest)
+        } else if let Some(rest) = base.strip_prefix("http://") {
+            format!("ws://{}", rest)
+        } else {
+            base.to_string()
+        };
+        format!("{}/api/websocket", base)
+    }
+
+    async fn send(&self, operation: &str, request: reqwest::RequestBuilder) -> Result<Value> {
+        let response = request
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .map_err(|e| crate::CoreError::tool_exec_error(operation, json!({}), e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(crate::CoreError::tool_exec_msg(
+                operation,
+                json!({ "status": status.as_u16() }),
+                format!("API request failed: {} - {}", status, text),
+            ));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| crate::CoreError::tool_exec_error(operation, json!({}), e))
+    }
+
+    /// Fetch all current entity states via REST API
+    pub async fn states(&self) -> Result<Vec<HomeAssistantItem>> {
+        let states = self
+            .send("homeassistant_fetch", self.http.get(self.api_url("states")))
+            .await?;
+
+        Ok(states
+            .as_array()
+            .map(|states| {
+                states
+                    .iter()
+                    .filter_map(HomeAssistantItem::from_state)
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Call a service (e.g. `light.turn_on`), returning the states it changed
+    pub async fn call_service(
+        &self,
+        domain: &str,
+        service: &str,
+        data: Value,
+    ) -> Result<Vec<HomeAssistantItem>> {
+        let changed = self
+            .send(
+                "homeassistant_call_service",
+                self.http
+                    .post(self.api_url(&format!("services/{}/{}", domain, service)))
+                    .json(&data),
+            )
+            .await?;
+
+        Ok(changed
+            .as_array()
+            .map(|states| {
+                states
+                    .iter()
+                    .filter_map(HomeAssistantItem::from_state)
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Open an authenticated WebSocket connection
+    async fn connect_websocket(&self) -> Result<WebSocket> {
+        let ws_url = self.websocket_url();
+
+        let (ws_stream, _) = connect_async(&ws_url).await.map_err(|e| {
+            crate::CoreError::tool_exec_error(
+                "homeassistant_websocket",
+                json!({ "url": ws_url }),
+                e,
+            )
+        })?;
+
+        let (mut write, mut read) = ws_stream.split();
+
+        // Wait for auth_required message
+        if let Some(Ok(Message::Text(text))) = read.next().await {
+            let msg: Value = serde_json::from_str(&text).unwrap_or_default();
+            if msg["type"].as_str() != Some("auth_required") {
+                return Err(crate::CoreError::tool_exec_msg(
+                    "homeassistant_websocket",
+                    json!({ "url": ws_url }),
+                    format!("Expected auth_required, got: {}", msg["type"]),
+                ));
+            }
+        }
+
+        // Send authentication
+        let auth_msg = json!({
+            "type": "auth",
+            "access_token": self.access_token
+        });
+
+        write
+            .send(Message::Text(auth_msg.to_string()))
+            .await
+            .map_err(|e| {
+                crate::CoreError::tool_exec_error(
+                    "homeassistant_websocket",
+                    json!({ "action": "send_auth" }),
+                    e,
+                )
+            })?;
+
+        // Wait for auth response
+        if let Some(Ok(Message::Text(text))) = read.next().await {
+            let msg: Value = serde_json::from_str(&text).unwrap_or_default();
+            if msg["type"].as_str() == Some("auth_invalid") {
+                return Err(crate::CoreError::tool_exec_msg(
+                    "homeassistant_websocket",
+                    json!({ "url": ws_url }),
+                    format!(
+                        "Authentication failed: {}",
+                        msg["message"].as_str().unwrap_or("unknown")
+                    ),
+                ));
+            } else if msg["type"].as_str() != Some("auth_ok") {
+                return Err(crate::CoreError::tool_exec_msg(
+                    "homeassistant_websocket",
+                    json!({ "url": ws_url }),
+                    format!("Expected auth_ok, got: {}", msg["type"]),
+                ));
+            }
+        }
+
+        // Rejoin the stream so the subscription task can own it
+        read.reunite(write).map_err(|_| {
+            crate::CoreError::tool_exec_msg(
+                "homeassistant_websocket",
+                json!({ "url": ws_url }),
+                "Failed to reunite WebSocket stream".to_string(),
+            )
+        })
+    }
+}
+
+/// HomeAssistant data source for real-time entity state tracking
+pub struct HomeAssistantSource {
+    client: HomeAssistantClient,
+    /// User owning the memory blocks created from notifications
+    owner_id: UserId,
     /// Unique identifier for this source
     source_id: String,
     /// Current cursor position
     current_cursor: Option<HomeAssistantCursor>,
     /// Filter configuration
     filter: HomeAssistantFilter,
+    /// Pushes filter changes to the live WebSocket subscription, if any
+    filter_tx: Option<watch::Sender<HomeAssistantFilter>>,
     /// Source metadata
-    metadata: RwLock<DataSourceMetadata>,
+    metadata: Arc<RwLock<DataSourceMetadata>>,
     /// Whether notifications are enabled
     notifications_enabled: bool,
-    /// WebSocket connection (when subscribed)
-    ws_connection: Option<
-        tokio_tungstenite::WebSocketStream<
-            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-        >,
-    >,
+    /// Templates for state change notifications
+    templates: Arc<TemplateRegistry>,
 }
 
 /// Cursor for tracking position in HomeAssistant event stream
@@ -53,7 +253,7 @@ pub struct HomeAssistantCursor {
 }
 
 /// Filter for HomeAssistant entities and events
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct HomeAssistantFilter {
     /// Entity domains to include (e.g., "light", "sensor", "switch")
     pub domains: Option<Vec<String>>,
@@ -67,6 +267,43 @@ pub struct HomeAssistantFilter {
     pub min_update_interval: Option<Duration>,
 }
 
+impl HomeAssistantFilter {
+    /// Event types to subscribe to, defaulting to state changes
+    pub fn subscribed_event_types(&self) -> Vec<String> {
+        match &self.event_types {
+            Some(types) if !types.is_empty() => types.clone(),
+            _ => vec!["state_changed".to_string()],
+        }
+    }
+
+    /// Check if an item passes this filter
+    pub fn matches(&self, item: &HomeAssistantItem) -> bool {
+        // Check domain filter
+        if let Some(domains) = &self.domains {
+            if !domains.contains(&item.domain) {
+                return false;
+            }
+        }
+
+        // Check entity_id filter
+        if let Some(entity_ids) = &self.entity_ids {
+            if !entity_ids.contains(&item.entity_id) {
+                return false;
+            }
+        }
+
+        // Check area filter
+        if let Some(areas) = &self.areas {
+            match &item.area {
+                Some(item_area) if areas.contains(item_area) => {}
+                _ => return false, // No area set but filter requires one
+            }
+        }
+
+        true
+    }
+}
+
 /// HomeAssistant entity state or event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HomeAssistantItem {
@@ -90,75 +327,17 @@ pub struct HomeAssistantItem {
     pub event_type: Option<String>,
 }
 
-impl HomeAssistantSource {
-    pub fn new(base_url: Url, access_token: String) -> Self {
-        let source_id = format!("homeassistant:{}", base_url.host_str().unwrap_or("unknown"));
-
-        let metadata = DataSourceMetadata {
-            source_type: "homeassistant".to_string(),
-            status: DataSourceStatus::Disconnected,
-            items_processed: 0,
-            last_item_time: None,
-            error_count: 0,
-            custom: HashMap::new(),
-        };
-
-        Self {
-            base_url,
-            access_token,
-            source_id,
-            current_cursor: None,
-            filter: HomeAssistantFilter::default(),
-            metadata: RwLock::new(metadata),
-            notifications_enabled: true,
-            ws_connection: None,
-        }
-    }
-
-    /// Fetch all current entity states via REST API
-    async fn fetch_states(&self) -> Result<Vec<HomeAssistantItem>> {
-        let client = Client::new();
-        let url = format!("{}/api/states", self.base_url);
-
-        let response = client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.access_token))
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| {
-                crate::CoreError::tool_exec_error("homeassistant_fetch", json!({ "url": url }), e)
-            })?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(crate::CoreError::tool_exec_msg(
-                "homeassistant_fetch",
-                json!({ "url": url, "status": status.as_u16() }),
-                format!("API request failed: {} - {}", status, text),
-            ));
-        }
-
-        let states: Vec<Value> = response.json().await.map_err(|e| {
-            crate::CoreError::tool_exec_error("homeassistant_fetch", json!({ "url": url }), e)
-        })?;
-
-        let mut items = Vec::new();
-        for state in states {
-            if let Some(item) = self.parse_state_object(state) {
-                // Apply filters
-                if self.should_include_item(&item) {
-                    items.push(item);
-                }
-            }
-        }
-
-        Ok(items)
-    }
+fn parse_timestamp(value: &Value) -> DateTime<Utc> {
+    value
+        .as_str()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now)
+}
 
-    /// Parse a state object from the API into our item format
-    fn parse_state_object(&self, state: Value) -> Option<HomeAssistantItem> {
+impl HomeAssistantItem {
+    /// Parse a state object from the API
+    pub fn from_state(state: &Value) -> Option<Self> {
         let entity_id = state["entity_id"].as_str()?.to_string();
         let state_value = state["state"].as_str()?.to_string();
 
@@ -180,24 +359,12 @@ impl HomeAssistantSource {
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
-        let last_changed = state["last_changed"]
-            .as_str()
-            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-            .map(|dt| dt.with_timezone(&Utc))
-            .unwrap_or_else(Utc::now);
-
-        let last_updated = state["last_updated"]
-            .as_str()
-            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-            .map(|dt| dt.with_timezone(&Utc))
-            .unwrap_or_else(Utc::now);
-
-        Some(HomeAssistantItem {
+        Some(Self {
             entity_id,
             state: state_value,
             attributes,
-            last_changed,
-            last_updated,
+            last_changed: parse_timestamp(&state["last_changed"]),
+            last_updated: parse_timestamp(&state["last_updated"]),
             friendly_name,
             domain,
             area,
@@ -205,162 +372,301 @@ impl HomeAssistantSource {
         })
     }
 
-    /// Check if an item passes our filters
-    fn should_include_item(&self, item: &HomeAssistantItem) -> bool {
-        // Check domain filter
-        if let Some(domains) = &self.filter.domains {
-            if !domains.contains(&item.domain) {
-                return false;
-            }
-        }
-
-        // Check entity_id filter
-        if let Some(entity_ids) = &self.filter.entity_ids {
-            if !entity_ids.contains(&item.entity_id) {
-                return false;
+    /// Parse an event from the WebSocket API
+    ///
+    /// State changes become the entity's new state; other events are keyed by
+    /// the entity or domain in their data, with the event type as the state.
+    pub fn from_event(event: &Value) -> Option<Self> {
+        let event_type = event["event_type"].as_str()?.to_string();
+        let data = &event["data"];
+
+        if event_type == "state_changed" {
+            let new_state = &data["new_state"];
+            if new_state.is_null() {
+                return None;
             }
+            let mut item = Self::from_state(new_state)?;
+            item.event_type = Some(event_type);
+            return Some(item);
         }
 
-        // Check area filter
-        if let Some(areas) = &self.filter.areas {
-            if let Some(item_area) = &item.area {
-                if !areas.contains(item_area) {
-                    return false;
-                }
-            } else {
-                return false; // No area set but filter requires one
-            }
-        }
+        let entity_id = data["entity_id"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("event.{}", event_type));
+        let domain = data["domain"]
+            .as_str()
+            .or_else(|| entity_id.split('.').next())
+            .unwrap_or("event")
+            .to_string();
+        let fired = parse_timestamp(&event["time_fired"]);
 
-        true
+        Some(Self {
+            entity_id,
+            state: event_type.clone(),
+            attributes: data
+                .as_object()
+                .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+                .unwrap_or_default(),
+            last_changed: fired,
+            last_updated: fired,
+            friendly_name: None,
+            domain,
+            area: None,
+            event_type: Some(event_type),
+        })
     }
+}
 
-    /// Connect to WebSocket API for real-time updates
-    async fn connect_websocket(&mut self) -> Result<()> {
-        let ws_url = self.base_url.clone();
-        let ws_url = if ws_url.scheme() == "https" {
-            ws_url.as_str().replace("https://", "wss://")
-        } else {
-            ws_url.as_str().replace("http://", "ws://")
-        };
-        let ws_url = format!("{}/api/websocket", ws_url);
+impl HomeAssistantSource {
+    pub fn new(base_url: Url, access_token: String, owner_id: UserId) -> Self {
+        Self::with_client(HomeAssistantClient::new(base_url, access_token), owner_id)
+    }
 
-        let (ws_stream, _) = connect_async(&ws_url).await.map_err(|e| {
-            crate::CoreError::tool_exec_error(
-                "homeassistant_websocket",
-                json!({ "url": ws_url }),
-                e,
-            )
-        })?;
+    /// Create a source sharing an existing client
+    pub fn with_client(client: HomeAssistantClient, owner_id: UserId) -> Self {
+        let source_id = format!(
+            "homeassistant:{}",
+            client.base_url().host_str().unwrap_or("unknown")
+        );
 
-        let (mut write, mut read) = ws_stream.split();
+        let metadata = DataSourceMetadata {
+            source_type: "homeassistant".to_string(),
+            status: DataSourceStatus::Disconnected,
+            items_processed: 0,
+            last_item_time: None,
+            error_count: 0,
+            custom: HashMap::new(),
+        };
 
-        // Wait for auth_required message
-        if let Some(Ok(Message::Text(text))) = read.next().await {
-            let msg: Value = serde_json::from_str(&text).unwrap_or_default();
-            if msg["type"].as_str() != Some("auth_required") {
-                return Err(crate::CoreError::tool_exec_msg(
-                    "homeassistant_websocket",
-                    json!({ "url": ws_url }),
-                    format!("Expected auth_required, got: {}", msg["type"]),
-                ));
-            }
+        Self {
+            client,
+            owner_id,
+            source_id,
+            current_cursor: None,
+            filter: HomeAssistantFilter::default(),
+            filter_tx: None,
+            metadata: Arc::new(RwLock::new(metadata)),
+            notifications_enabled: true,
+            templates: TemplateRegistry::shared_defaults(),
         }
+    }
 
-        // Send authentication
-        let auth_msg = json!({
-            "type": "auth",
-            "access_token": self.access_token
-        });
-
-        write
-            .send(Message::Text(auth_msg.to_string()))
-            .await
-            .map_err(|e| {
-                crate::CoreError::tool_exec_error(
-                    "homeassistant_websocket",
-                    json!({ "action": "send_auth" }),
-                    e,
-                )
-            })?;
+    /// Set the initial filter
+    pub fn with_filter(mut self, filter: HomeAssistantFilter) -> Self {
+        self.filter = filter;
+        self
+    }
 
-        // Wait for auth response
-        if let Some(Ok(Message::Text(text))) = read.next().await {
-            let msg: Value = serde_json::from_str(&text).unwrap_or_default();
-            if msg["type"].as_str() == Some("auth_invalid") {
-                return Err(crate::CoreError::tool_exec_msg(
-                    "homeassistant_websocket",
-                    json!({ "url": ws_url }),
-                    format!(
-                        "Authentication failed: {}",
-                        msg["message"].as_str().unwrap_or("unknown")
-                    ),
-                ));
-            } else if msg["type"].as_str() != Some("auth_ok") {
-                return Err(crate::CoreError::tool_exec_msg(
-                    "homeassistant_websocket",
-                    json!({ "url": ws_url }),
-                    format!("Expected auth_ok, got: {}", msg["type"]),
-                ));
-            }
-        }
+    /// Render state change notifications with these templates
+    pub fn with_templates(mut self, templates: Arc<TemplateRegistry>) -> Self {
+        self.templates = templates;
+        self
+    }
+}
 
-        // Subscribe to state changes
-        let subscribe_msg = json!({
-            "id": 1,
+/// Build the messages that move a WebSocket subscription onto `event_types`
+///
+/// Unsubscribes every id in `subscriptions` and subscribes to each event type,
+/// replacing `subscriptions` with the new ids.
+fn resubscribe_messages(
+    subscriptions: &mut Vec<u64>,
+    event_types: &[String],
+    next_id: &mut u64,
+) -> Vec<Value> {
+    let mut messages = Vec::new();
+    for subscription in subscriptions.drain(..) {
+        messages.push(json!({
+            "id": *next_id,
+            "type": "unsubscribe_events",
+            "subscription": subscription
+        }));
+        *next_id += 1;
+    }
+    for event_type in event_types {
+        messages.push(json!({
+            "id": *next_id,
             "type": "subscribe_events",
-            "event_type": "state_changed"
-        });
+            "event_type": event_type
+        }));
+        subscriptions.push(*next_id);
+        *next_id += 1;
+    }
+    messages
+}
 
+async fn resubscribe<S>(
+    write: &mut S,
+    subscriptions: &mut Vec<u64>,
+    event_types: &[String],
+    next_id: &mut u64,
+) -> Result<()>
+where
+    S: Sink<Message> + Unpin,
+    S::Error: std::error::Error + Send + Sync + 'static,
+{
+    for message in resubscribe_messages(subscriptions, event_types, next_id) {
         write
-            .send(Message::Text(subscribe_msg.to_string()))
+            .send(Message::Text(message.to_string()))
             .await
             .map_err(|e| {
                 crate::CoreError::tool_exec_error(
                     "homeassistant_websocket",
-                    json!({ "action": "subscribe" }),
+                    json!({ "action": "subscribe", "event_types": event_types }),
                     e,
                 )
             })?;
+    }
+    Ok(())
+}
 
-        // Rejoin the stream for storage
-        let ws_stream = read.reunite(write).map_err(|_| {
-            crate::CoreError::tool_exec_msg(
-                "homeassistant_websocket",
-                json!({ "url": ws_url }),
-                "Failed to reunite WebSocket stream".to_string(),
-            )
-        })?;
-
-        self.ws_connection = Some(ws_stream);
-
-        // Update metadata
-        {
-            let mut metadata = self.metadata.write().await;
-            metadata.status = DataSourceStatus::Active;
+/// Turn a WebSocket text message into an item, if it is an event we want
+fn parse_ws_message(
+    text: &str,
+    filter: &HomeAssistantFilter,
+) -> Option<Result<(HomeAssistantItem, Option<String>)>> {
+    let msg: Value = serde_json::from_str(text).ok()?;
+    match msg["type"].as_str()? {
+        "event" => {
+            let event = &msg["event"];
+            let item = HomeAssistantItem::from_event(event)?;
+            filter
+                .matches(&item)
+                .then(|| Ok((item, event["context"]["id"].as_str().map(|s| s.to_string()))))
         }
+        "result" if msg["success"].as_bool() == Some(false) => {
+            tracing::warn!(
+                "HomeAssistant rejected request {}: {}",
+                msg["id"],
+                msg["error"]["message"].as_str().unwrap_or("unknown error")
+            );
+            None
+        }
+        // Authentication failed during stream
+        "auth_invalid" => Some(Err(crate::CoreError::tool_exec_msg(
+            "homeassistant_subscribe",
+            json!({}),
+            "Authentication invalidated during stream".to_string(),
+        ))),
+        // Other message types we don't handle yet
+        _ => None,
+    }
+}
 
-        Ok(())
+/// Own the WebSocket: forward matching events and follow filter changes
+async fn run_subscription(
+    ws: WebSocket,
+    mut filter_rx: watch::Receiver<HomeAssistantFilter>,
+    from: Option<HomeAssistantCursor>,
+    tx: mpsc::Sender<Result<StreamEvent<HomeAssistantItem, HomeAssistantCursor>>>,
+    metadata: Arc<RwLock<DataSourceMetadata>>,
+) {
+    let (mut write, mut read) = ws.split();
+    let mut filter = filter_rx.borrow_and_update().clone();
+    let mut subscriptions = Vec::new();
+    let mut next_id = 1;
+    let mut last_update_times = HashMap::<String, std::time::Instant>::new();
+
+    if let Err(e) = resubscribe(
+        &mut write,
+        &mut subscriptions,
+        &filter.subscribed_event_types(),
+        &mut next_id,
+    )
+    .await
+    {
+        let _ = tx.send(Err(e)).await;
+        return;
     }
+    metadata.write().await.status = DataSourceStatus::Active;
+
+    loop {
+        tokio::select! {
+            changed = filter_rx.changed() => {
+                // Sender dropped: the source was re-subscribed or dropped
+                if changed.is_err() {
+                    break;
+                }
+                let new_filter = filter_rx.borrow_and_update().clone();
+                let event_types = new_filter.subscribed_event_types();
+                if event_types != filter.subscribed_event_types() {
+                    tracing::info!("HomeAssistant filter changed, subscribing to {:?}", event_types);
+                    if let Err(e) =
+                        resubscribe(&mut write, &mut subscriptions, &event_types, &mut next_id).await
+                    {
+                        let _ = tx.send(Err(e)).await;
+                        break;
+                    }
+                }
+                filter = new_filter;
+            }
+            msg = read.next() => {
+                let (item, event_id) = match msg {
+                    Some(Ok(Message::Text(text))) => match parse_ws_message(&text, &filter) {
+                        Some(Ok(parsed)) => parsed,
+                        Some(Err(e)) => {
+                            let _ = tx.send(Err(e)).await;
+                            break;
+                        }
+                        None => continue,
+                    },
+                    Some(Ok(Message::Close(_))) | None => {
+                        // Connection closed
+                        let _ = tx
+                            .send(Err(crate::CoreError::tool_exec_msg(
+                                "homeassistant_subscribe",
+                                json!({}),
+                                "WebSocket connection closed".to_string(),
+                            )))
+                            .await;
+                        break;
+                    }
+                    Some(Ok(_)) => continue, // Binary, Ping, Pong, etc.
+                    Some(Err(e)) => {
+                        let _ = tx
+                            .send(Err(crate::CoreError::tool_exec_error(
+                                "homeassistant_subscribe",
+                                json!({}),
+                                e,
+                            )))
+                            .await;
+                        break;
+                    }
+                };
 
-    /// Process a state change event from WebSocket
-    fn process_state_change(&self, event: Value) -> Option<HomeAssistantItem> {
-        // Extract the new state from the event
-        let new_state = event["event"]["data"]["new_state"].clone();
-        if new_state.is_null() {
-            return None;
-        }
+                // Apply rate limiting if configured
+                if let Some(interval) = filter.min_update_interval {
+                    let now = std::time::Instant::now();
+                    if let Some(last_time) = last_update_times.get(&item.entity_id) {
+                        if now.duration_since(*last_time) < interval {
+                            continue; // Skip due to rate limiting
+                        }
+                    }
+                    last_update_times.insert(item.entity_id.clone(), now);
+                }
 
-        let mut item = self.parse_state_object(new_state)?;
-        item.event_type = Some("state_changed".to_string());
+                // Apply cursor filtering if provided
+                if from.as_ref().is_some_and(|cursor| item.last_updated <= cursor.timestamp) {
+                    continue;
+                }
 
-        // Apply filters
-        if self.should_include_item(&item) {
-            Some(item)
-        } else {
-            None
+                let cursor = HomeAssistantCursor {
+                    timestamp: item.last_updated,
+                    event_id,
+                };
+                let event = StreamEvent {
+                    item,
+                    cursor,
+                    timestamp: chrono::Utc::now(),
+                };
+                if tx.send(Ok(event)).await.is_err() {
+                    break;
+                }
+            }
         }
     }
+
+    metadata.write().await.status = DataSourceStatus::Disconnected;
 }
 
 #[async_trait]
@@ -375,7 +681,8 @@ impl DataSource for HomeAssistantSource {
 
     async fn pull(&mut self, limit: usize, after: Option<Self::Cursor>) -> Result<Vec<Self::Item>> {
         // Fetch current states via REST API
-        let mut states = self.fetch_states().await?;
+        let mut states = self.client.states().await?;
+        states.retain(|item| self.filter.matches(item));
 
         // Apply cursor filtering if provided
         if let Some(cursor) = after {
@@ -408,148 +715,35 @@ impl DataSource for HomeAssistantSource {
         from: Option<Self::Cursor>,
     ) -> Result<Box<dyn Stream<Item = Result<StreamEvent<Self::Item, Self::Cursor>>> + Send + Unpin>>
     {
-        // Connect to WebSocket if not already connected
-        if self.ws_connection.is_none() {
-            self.connect_websocket().await?;
-        }
-
-        // Take ownership of the WebSocket connection
-        let ws_stream = self.ws_connection.take().ok_or_else(|| {
-            crate::CoreError::tool_exec_msg(
-                "homeassistant_subscribe",
-                json!({}),
-                "WebSocket connection not available".to_string(),
-            )
-        })?;
-
-        // Create a filter for processing events
-        let filter = self.filter.clone();
-        let min_update_interval = filter.min_update_interval.clone();
-        let last_update_times = std::sync::Arc::new(tokio::sync::Mutex::new(HashMap::<
-            String,
-            std::time::Instant,
-        >::new()));
-
-        // Create the stream that processes WebSocket messages
-        let stream = ws_stream
-            .filter_map(move |msg| {
-                let filter = filter.clone();
-                let result = async move {
-                    match msg {
-                        Ok(Message::Text(text)) => {
-                            // Parse the message
-                            let json_msg: Value = serde_json::from_str(&text).ok()?;
-
-                            // Check if it's an event message
-                            if json_msg["type"].as_str() == Some("event") {
-                                let event = json_msg["event"].clone();
-
-                                // Check if it's a state_changed event
-                                if event["event_type"].as_str() == Some("state_changed") {
-                                    // Extract the new state
-                                    let new_state = event["data"]["new_state"].clone();
-                                    if new_state.is_null() {
-                                        return None;
-                                    }
-
-                                    // Parse into our item format
-                                    let item = Self::parse_state_from_json(new_state, &filter)?;
-
-                                    // Create cursor
-                                    let cursor = HomeAssistantCursor {
-                                        timestamp: item.last_updated,
-                                        event_id: event["id"].as_str().map(|s| s.to_string()),
-                                    };
-
-                                    // Create stream event
-                                    Some(Ok(StreamEvent {
-                                        item,
-                                        cursor,
-                                        timestamp: chrono::Utc::now(),
-                                    }))
-                                } else {
-                                    None
-                                }
-                            } else if json_msg["type"].as_str() == Some("auth_invalid") {
-                                // Authentication failed during stream
-                                Some(Err(crate::CoreError::tool_exec_msg(
-                                    "homeassistant_subscribe",
-                                    json!({}),
-                                    "Authentication invalidated during stream".to_string(),
-                                )))
-                            } else {
-                                // Other message types we don't handle yet
-                                None
-                            }
-                        }
-                        Ok(Message::Close(_)) => {
-                            // Connection closed
-                            Some(Err(crate::CoreError::tool_exec_msg(
-                                "homeassistant_subscribe",
-                                json!({}),
-                                "WebSocket connection closed".to_string(),
-                            )))
-                        }
-                        Ok(_) => None, // Binary, Ping, Pong, etc.
-                        Err(e) => Some(Err(crate::CoreError::tool_exec_error(
-                            "homeassistant_subscribe",
-                            json!({}),
-                            e,
-                        ))),
-                    }
-                };
-                result
-            })
-            .filter_map(move |event| {
-                let last_update_times = last_update_times.clone();
-                // Apply rate limiting if configured
-                let result = async move {
-                    match event {
-                        Ok(stream_event) => {
-                            if let Some(interval) = min_update_interval {
-                                let entity_id = stream_event.item.entity_id.clone();
-                                let now = std::time::Instant::now();
-
-                                let mut times = last_update_times.lock().await;
-                                if let Some(last_time) = times.get(&entity_id) {
-                                    if now.duration_since(*last_time) < interval {
-                                        return None; // Skip due to rate limiting
-                                    }
-                                }
-
-                                times.insert(entity_id, now);
-                            }
-                            Some(Ok(stream_event))
-                        }
-                        Err(e) => Some(Err(e)),
-                    }
-                };
-                result
-            })
-            .filter(move |event| {
-                // Apply cursor filtering if provided
-                let keep = if let Some(ref cursor) = from {
-                    if let Ok(stream_event) = event {
-                        stream_event.cursor.timestamp > cursor.timestamp
-                    } else {
-                        true // Keep errors
-                    }
-                } else {
-                    true
-                };
-                futures::future::ready(keep)
-            })
-            .boxed();
+        let ws = self.client.connect_websocket().await?;
+
+        // Replacing the sender ends any previous subscription task
+        let (filter_tx, filter_rx) = watch::channel(self.filter.clone());
+        self.filter_tx = Some(filter_tx);
+
+        let (tx, rx) = mpsc::channel(256);
+        tokio::spawn(run_subscription(
+            ws,
+            filter_rx,
+            from,
+            tx,
+            self.metadata.clone(),
+        ));
 
-        Ok(Box::new(stream)
+        Ok(Box::new(tokio_stream::wrappers::ReceiverStream::new(rx))
             as Box<
                 dyn Stream<Item = Result<StreamEvent<Self::Item, Self::Cursor>>> + Send + Unpin,
             >)
     }
 
     fn set_filter(&mut self, filter: HomeAssistantFilter) {
-        self.filter = filter;
-        // TODO: If connected, update WebSocket subscriptions
+        self.filter = filter.clone();
+        // If connected, the subscription task re-subscribes to the new event types
+        if let Some(filter_tx) = &self.filter_tx {
+            if filter_tx.send(filter).is_err() {
+                self.filter_tx = None;
+            }
+        }
     }
 
     fn current_cursor(&self) -> Option<Self::Cursor> {
@@ -576,26 +770,20 @@ impl DataSource for HomeAssistantSource {
         item: &Self::Item,
     ) -> Option<(String, Vec<(CompactString, MemoryBlock)>)> {
         // Format state change notification
-        let notification = match &item.event_type {
-            Some(event_type) => {
-                format!(
-                    "HomeAssistant Event: {} - {} changed to '{}'",
-                    event_type,
-                    item.friendly_name.as_deref().unwrap_or(&item.entity_id),
-                    item.state
-                )
-            }
-            None => {
-                format!(
-                    "HomeAssistant: {} is now '{}'",
-                    item.friendly_name.as_deref().unwrap_or(&item.entity_id),
-                    item.state
-                )
-            }
+        let template = if item.event_type.is_some() {
+            "homeassistant_event"
+        } else {
+            "homeassistant_state"
         };
-
-        // Create memory block for entity context
-        let mut memory_blocks = Vec::new();
+        let mut context = item_context(item);
+        context.insert(
+            "name".to_string(),
+            item.friendly_name
+                .as_deref()
+                .unwrap_or(&item.entity_id)
+                .into(),
+        );
+        let notification = self.templates.render_notification(template, &context)?;
 
         // Add entity state as a memory block
         let block_name = CompactString::new(format!("ha_{}", item.domain));
@@ -603,28 +791,16 @@ impl DataSource for HomeAssistantSource {
             "Entity: {}\nState: {}\nAttributes: {:?}\nLast Updated: {}",
             item.entity_id, item.state, item.attributes, item.last_updated
         );
+        let block = MemoryBlock::owned(self.owner_id.clone(), block_name.clone(), block_content)
+            .with_description(format!("HomeAssistant {} entities", item.domain))
+            .with_memory_type(MemoryType::Working)
+            .with_permission(MemoryPermission::ReadOnly);
 
-        memory_blocks.push((
-            block_name,
-            MemoryBlock {
-                id: MemoryId::generate(),
-                owner_id: UserId::generate(), // TODO: Get from context
-                label: CompactString::new(format!("ha_{}", item.domain)),
-                value: block_content,
-                memory_type: MemoryType::Working,
-                description: Some(format!("HomeAssistant {} entities", item.domain)),
-                pinned: false,
-                permission: MemoryPermission::ReadOnly,
-                created_at: chrono::Utc::now(),
-                updated_at: chrono::Utc::now(),
-                metadata: serde_json::json!({}),
-                embedding_model: None,
-                embedding: None,
-                is_active: true,
-            },
-        ));
+        Some((notification, vec![(block_name, block)]))
+    }
 
-        Some((notification, memory_blocks))
+    fn set_templates(&mut self, templates: Arc<TemplateRegistry>) {
+        self.templates = templates;
     }
 
     fn set_notifications_enabled(&mut self, enabled: bool) {
@@ -636,80 +812,115 @@ impl DataSource for HomeAssistantSource {
     }
 }
 
-impl HomeAssistantSource {
-    /// Static method to parse state from JSON with filters
-    fn parse_state_from_json(
-        state: Value,
-        filter: &HomeAssistantFilter,
-    ) -> Option<HomeAssistantItem> {
-        let entity_id = state["entity_id"].as_str()?.to_string();
-        let state_value = state["state"].as_str()?.to_string();
-
-        // Extract domain from entity_id
-        let domain = entity_id.split('.').next()?.to_string();
-
-        // Apply domain filter early
-        if let Some(domains) = &filter.domains {
-            if !domains.contains(&domain) {
-                return None;
-            }
-        }
-
-        // Apply entity_id filter early
-        if let Some(entity_ids) = &filter.entity_ids {
-            if !entity_ids.contains(&entity_id) {
-                return None;
-            }
-        }
-
-        let attributes: HashMap<String, Value> = state["attributes"]
-            .as_object()
-            .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
-            .unwrap_or_default();
-
-        let friendly_name = attributes
-            .get("friendly_name")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(entity_id: &str, value: &str, area: Option<&str>) -> Value {
+        json!({
+            "entity_id": entity_id,
+            "state": value,
+            "attributes": { "friendly_name": "Desk", "area": area },
+            "last_changed": "2025-01-01T10:00:00Z",
+            "last_updated": "2025-01-01T10:00:00Z"
+        })
+    }
 
-        let area = attributes
-            .get("area")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+    #[test]
+    fn test_filter_matches() {
+        let item = HomeAssistantItem::from_state(&state(
+            "binary_sensor.desk_occupied",
+            "on",
+            Some("office"),
+        ))
+        .unwrap();
+        assert_eq!(item.domain, "binary_sensor");
+        assert_eq!(item.friendly_name.as_deref(), Some("Desk"));
+
+        assert!(HomeAssistantFilter::default().matches(&item));
+        let by_area = HomeAssistantFilter {
+            areas: Some(vec!["bedroom".to_string()]),
+            ..Default::default()
+        };
+        assert!(!by_area.matches(&item));
+        let by_domain = HomeAssistantFilter {
+            domains: Some(vec!["binary_sensor".to_string()]),
+            ..Default::default()
+        };
+        assert!(by_domain.matches(&item));
+    }
 
-        // Apply area filter
-        if let Some(areas) = &filter.areas {
-            if let Some(ref item_area) = area {
-                if !areas.contains(item_area) {
-                    return None;
-                }
-            } else {
-                return None; // No area set but filter requires one
+    #[test]
+    fn test_parse_events() {
+        let changed = json!({
+            "type": "event",
+            "event": {
+                "event_type": "state_changed",
+                "data": { "new_state": state("light.desk", "off", None) },
+                "context": { "id": "ctx1" }
             }
-        }
-
-        let last_changed = state["last_changed"]
-            .as_str()
-            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-            .map(|dt| dt.with_timezone(&Utc))
-            .unwrap_or_else(Utc::now);
+        })
+        .to_string();
+        let (item, event_id) = parse_ws_message(&changed, &HomeAssistantFilter::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(item.entity_id, "light.desk");
+        assert_eq!(item.event_type.as_deref(), Some("state_changed"));
+        assert_eq!(event_id.as_deref(), Some("ctx1"));
+
+        let lights_only = HomeAssistantFilter {
+            domains: Some(vec!["sensor".to_string()]),
+            ..Default::default()
+        };
+        assert!(parse_ws_message(&changed, &lights_only).is_none());
+
+        let service = HomeAssistantItem::from_event(&json!({
+            "event_type": "call_service",
+            "data": { "domain": "scene", "service": "turn_on" },
+            "time_fired": "2025-01-01T10:00:00Z"
+        }))
+        .unwrap();
+        assert_eq!(service.domain, "scene");
+        assert_eq!(service.state, "call_service");
+    }
 
-        let last_updated = state["last_updated"]
-            .as_str()
-            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-            .map(|dt| dt.with_timezone(&Utc))
-            .unwrap_or_else(Utc::now);
+    #[test]
+    fn test_resubscribe_messages() {
+        let mut subscriptions = Vec::new();
+        let mut next_id = 1;
+        let first = resubscribe_messages(
+            &mut subscriptions,
+            &HomeAssistantFilter::default().subscribed_event_types(),
+            &mut next_id,
+        );
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0]["event_type"], "state_changed");
+        assert_eq!(subscriptions, vec![1]);
+
+        let second = resubscribe_messages(
+            &mut subscriptions,
+            &["state_changed".to_string(), "call_service".to_string()],
+            &mut next_id,
+        );
+        assert_eq!(second[0]["type"], "unsubscribe_events");
+        assert_eq!(second[0]["subscription"], 1);
+        assert_eq!(subscriptions, vec![3, 4]);
+        assert_eq!(next_id, 5);
+    }
 
-        Some(HomeAssistantItem {
-            entity_id,
-            state: state_value,
-            attributes,
-            last_changed,
-            last_updated,
-            friendly_name,
-            domain,
-            area,
-            event_type: Some("state_changed".to_string()),
-        })
+    #[test]
+    fn test_client_urls() {
+        let client = HomeAssistantClient::new(
+            Url::parse("https://ha.example.com:8123/").unwrap(),
+            "token".to_string(),
+        );
+        assert_eq!(
+            client.api_url("states"),
+            "https://ha.example.com:8123/api/states"
+        );
+        assert_eq!(
+            client.websocket_url(),
+            "wss://ha.example.com:8123/api/websocket"
+        );
     }
 }
@@ -3,6 +3,7 @@ pub mod buffer;
 pub mod coordinator;
 pub mod cursor_store;
 pub mod file;
+pub mod file_index;
 pub mod helpers;
 pub mod homeassistant;
 pub mod traits;
@@ -10,12 +11,17 @@ pub mod traits;
 pub use bluesky::{BlueskyFilter, BlueskyFirehoseCursor, BlueskyFirehoseSource, BlueskyPost};
 pub use buffer::{BufferConfig, BufferStats, StreamBuffer};
 pub use coordinator::{DataIngestionCoordinator, DataIngestionEvent};
-pub use file::{FileCursor, FileDataSource, FileStorageMode};
+pub use file::{
+    FileChange, FileContent, FileCursor, FileDataSource, FileFilter, FileItem, FileStorageMode,
+};
+pub use file_index::FileIndex;
 pub use helpers::{
-    DataSourceBuilder, add_bluesky_source, add_file_source, create_coordinator_with_agent_info,
-    create_full_data_pipeline, create_knowledge_base, monitor_bluesky_mentions, monitor_directory,
+    DataSourceBuilder, add_bluesky_source, add_file_source, add_homeassistant_source,
+    create_coordinator_with_agent_info, create_full_data_pipeline, create_knowledge_base,
+    monitor_bluesky_mentions, monitor_directory,
 };
 pub use homeassistant::{
-    HomeAssistantCursor, HomeAssistantFilter, HomeAssistantItem, HomeAssistantSource,
+    HomeAssistantClient, HomeAssistantCursor, HomeAssistantFilter, HomeAssistantItem,
+    HomeAssistantSource,
 };
 pub use traits::{DataSource, DataSourceMetadata, StreamEvent};
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -8,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use super::{BufferConfig, BufferStats};
-use crate::{error::Result, memory::MemoryBlock};
+use crate::{error::Result, memory::MemoryBlock, prompt_template::TemplateRegistry};
 
 /// Core trait for data sources that agents can consume from
 #[async_trait]
@@ -48,6 +49,11 @@ pub trait DataSource: Send + Sync {
         item: &Self::Item,
     ) -> Option<(String, Vec<(CompactString, MemoryBlock)>)>;
 
+    /// Replace the templates notifications are rendered with
+    fn set_templates(&mut self, _templates: Arc<TemplateRegistry>) {
+        // Default: source has no templated notifications
+    }
+
     /// Get buffer statistics if source maintains a buffer
     fn get_buffer_stats(&self) -> Option<BufferStats> {
         None // Default: no buffer
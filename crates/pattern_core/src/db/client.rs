@@ -226,6 +226,9 @@ pub async fn init_db_with_options(config: DatabaseConfig, force_schema_update: b
     // Initialize the schema
     crate::db::migration::MigrationRunner::run_with_options(&DB, force_schema_update).await?;
 
+    // Standing permission grants and the audit log live alongside everything else
+    crate::permission::broker().attach_db(DB.clone()).await;
+
     Ok(())
 }
 
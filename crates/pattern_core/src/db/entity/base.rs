@@ -8,6 +8,8 @@ use crate::users::User;
 use chrono::{DateTime, Utc};
 use pattern_macros::Entity;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 // ============================================================================
 // Base Agent Implementation - REMOVED
@@ -29,8 +31,47 @@ pub enum BaseTaskStatus {
     Cancelled,
 }
 
-/// Base task priority
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+impl BaseTaskStatus {
+    /// Whether the task still needs doing
+    pub fn is_open(&self) -> bool {
+        matches!(self, Self::Pending | Self::InProgress)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::InProgress => "in_progress",
+            Self::Completed => "completed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for BaseTaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BaseTaskStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "pending" | "todo" => Ok(Self::Pending),
+            "in_progress" | "started" => Ok(Self::InProgress),
+            "completed" | "done" => Ok(Self::Completed),
+            "cancelled" | "canceled" => Ok(Self::Cancelled),
+            other => Err(format!(
+                "unknown task status '{}' (expected pending, in_progress, completed or cancelled)",
+                other
+            )),
+        }
+    }
+}
+
+/// Base task priority, ordered from least to most urgent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum BaseTaskPriority {
     Low,
@@ -39,6 +80,40 @@ pub enum BaseTaskPriority {
     Critical,
 }
 
+impl BaseTaskPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+impl fmt::Display for BaseTaskPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BaseTaskPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(Self::Low),
+            "medium" | "normal" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            "critical" | "urgent" => Ok(Self::Critical),
+            other => Err(format!(
+                "unknown task priority '{}' (expected low, medium, high or critical)",
+                other
+            )),
+        }
+    }
+}
+
 /// Base task entity
 #[derive(Debug, Clone, Entity, Serialize, Deserialize)]
 #[entity(entity_type = "task")]
@@ -97,6 +172,10 @@ pub struct BaseEvent {
     pub event_type: String,
     pub scheduled_for: DateTime<Utc>,
     pub duration_minutes: Option<i32>,
+    pub location: Option<String>,
+    /// UID from the iCalendar file this event was imported from, so
+    /// re-importing the same calendar updates events instead of duplicating them
+    pub ical_uid: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 
@@ -104,6 +183,20 @@ pub struct BaseEvent {
     pub creator_id: UserId,
 }
 
+impl BaseEvent {
+    /// When the event ends, if it has a duration
+    pub fn ends_at(&self) -> Option<DateTime<Utc>> {
+        self.duration_minutes
+            .map(|minutes| self.scheduled_for + chrono::Duration::minutes(minutes as i64))
+    }
+
+    /// Whether any part of the event falls within `[from, to)`
+    pub fn overlaps(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> bool {
+        let end = self.ends_at().unwrap_or(self.scheduled_for);
+        self.scheduled_for < to && end >= from
+    }
+}
+
 impl Default for BaseEvent {
     fn default() -> Self {
         let now = Utc::now();
@@ -114,6 +207,8 @@ impl Default for BaseEvent {
             event_type: "general".to_string(),
             scheduled_for: now,
             duration_minutes: None,
+            location: None,
+            ical_uid: None,
             created_at: now,
             updated_at: now,
             creator_id: UserId::nil(),
@@ -224,4 +319,23 @@ mod tests {
         assert_eq!(task.priority, BaseTaskPriority::High);
         assert_eq!(task.status, BaseTaskStatus::Pending);
     }
+
+    #[test]
+    fn test_task_status_and_priority_parsing() {
+        assert_eq!(
+            "in-progress".parse::<BaseTaskStatus>(),
+            Ok(BaseTaskStatus::InProgress)
+        );
+        assert_eq!(
+            "done".parse::<BaseTaskStatus>(),
+            Ok(BaseTaskStatus::Completed)
+        );
+        assert!("later".parse::<BaseTaskStatus>().is_err());
+
+        assert_eq!(
+            "HIGH".parse::<BaseTaskPriority>(),
+            Ok(BaseTaskPriority::High)
+        );
+        assert!(BaseTaskPriority::Critical > BaseTaskPriority::Low);
+    }
 }
@@ -2,7 +2,8 @@
 
 use super::{
     DatabaseError, Result,
-    entity::{AgentMemoryRelation, DbEntity},
+    entity::{AgentMemoryRelation, BaseEvent, BaseTask, BaseTaskStatus, DbEntity},
+    schema::{Schema, ToolCall},
 };
 use serde_json::json;
 use surrealdb::{Connection, Surreal};
@@ -10,9 +11,16 @@ use surrealdb::{Connection, Surreal};
 use crate::agent::{AgentRecord, get_next_message_position_sync};
 use crate::coordination::groups::{AgentGroup, GroupMembership};
 use crate::embeddings::EmbeddingProvider;
-use crate::id::{AgentId, GroupId, IdType, MemoryId, UserId};
+use crate::id::{
+    AgentId, EventId, GroupId, IdType, MemoryId, MemoryRevisionId, PermissionGrantId, TaskId,
+    UserId, WakeupId,
+};
 use crate::memory::MemoryBlock;
+use crate::memory_history::{MemoryRevision, RevisionChange};
 use crate::message::Message;
+use crate::message_queue::ScheduledWakeup;
+use crate::permission::{PermissionAuditEntry, PermissionGrantRecord};
+use crate::usage::{ModelUsage, UsageTotals};
 use crate::utils::debug::ResponseExt;
 use crate::{MessageId, id::RelationId};
 use chrono::Utc;
@@ -512,6 +520,49 @@ impl<C: Connection> VectorSearchExt<C> for Surreal<C> {
     }
 }
 
+/// Make sure `table`'s HNSW index takes embeddings with `dimensions` entries
+///
+/// The index rejects vectors of any other length, so it's redefined when it was
+/// built for a different embedding model. Redefining fails while the table
+/// still holds embeddings of the old length.
+pub async fn ensure_vector_index<C: Connection>(
+    conn: &Surreal<C>,
+    table: &str,
+    dimensions: usize,
+) -> Result<()> {
+    let index_name = format!("{}_vector_idx", table);
+    let info: Option<serde_json::Value> = conn
+        .query(format!("INFO FOR TABLE {}", table))
+        .await
+        .map_err(DatabaseError::QueryFailed)?
+        .take(0)
+        .map_err(DatabaseError::QueryFailed)?;
+
+    let indexed_dimensions = info
+        .as_ref()
+        .and_then(|info| info["indexes"][index_name.as_str()].as_str())
+        .and_then(|definition| {
+            let mut words = definition.split_whitespace();
+            words.find(|word| *word == "DIMENSION")?;
+            words.next()?.parse::<usize>().ok()
+        });
+
+    if indexed_dimensions != Some(dimensions) {
+        tracing::info!(
+            "Redefining {} for {}-dimension embeddings (was {:?})",
+            index_name,
+            dimensions,
+            indexed_dimensions
+        );
+        conn.query(Schema::vector_index(table, "embedding", dimensions))
+            .await
+            .and_then(|response| response.check())
+            .map_err(DatabaseError::QueryFailed)?;
+    }
+
+    Ok(())
+}
+
 // ============================================================================
 // Live Query Operations - Free Functions
 // ============================================================================
@@ -785,12 +836,15 @@ pub async fn get_memory_by_label<C: Connection>(
 }
 
 /// Update memory content with optional re-embedding
+///
+/// A changed value is recorded in the block's revision history.
 pub async fn update_memory_content<C: Connection>(
     conn: &Surreal<C>,
     memory_id: MemoryId,
     content: String,
     embeddings: Option<&impl EmbeddingProvider>,
 ) -> Result<()> {
+    let previous = get_entity::<MemoryBlock, _>(conn, &memory_id).await?;
     // Generate embeddings if provider is available
     let (embedding, model_name) = if let Some(provider) = embeddings {
         let emb = provider.embed_query(&content).await.map_err(|e| {
@@ -806,7 +860,7 @@ pub async fn update_memory_content<C: Connection>(
 
     let _: Option<serde_json::Value> = conn
         .update(RecordId::from(memory_id))
-        .patch(PatchOp::replace("/content", content))
+        .patch(PatchOp::replace("/value", content.clone()))
         .patch(PatchOp::replace("/embedding", embedding))
         .patch(PatchOp::replace("/embedding_model", model_name))
         .patch(PatchOp::replace(
@@ -815,9 +869,197 @@ pub async fn update_memory_content<C: Connection>(
         ))
         .await?;
 
+    if let Some(mut block) = previous {
+        let previous_value = std::mem::replace(&mut block.value, content);
+        if block.value != previous_value {
+            create_memory_revision(conn, &MemoryRevision::new(&block, previous_value)).await?;
+        }
+    }
+
     Ok(())
 }
 
+// ============================================================================
+// Memory Revision Operations
+// ============================================================================
+
+/// Record a write to a memory block
+pub async fn create_memory_revision<C: Connection>(
+    conn: &Surreal<C>,
+    revision: &MemoryRevision,
+) -> Result<MemoryRevision> {
+    create_entity::<MemoryRevision, _>(conn, revision).await
+}
+
+/// Get a memory revision by ID
+pub async fn get_memory_revision<C: Connection>(
+    conn: &Surreal<C>,
+    revision_id: &MemoryRevisionId,
+) -> Result<Option<MemoryRevision>> {
+    get_entity::<MemoryRevision, _>(conn, revision_id).await
+}
+
+/// List a memory block's revisions, newest first
+pub async fn list_memory_revisions<C: Connection>(
+    conn: &Surreal<C>,
+    memory_id: &MemoryId,
+    limit: usize,
+) -> Result<Vec<MemoryRevision>> {
+    let query = r#"
+        SELECT * FROM mem_rev
+        WHERE memory_id = $memory
+        ORDER BY created_at DESC
+        LIMIT $limit
+    "#;
+
+    let mut result = conn
+        .query(query)
+        .bind(("memory", RecordId::from(memory_id)))
+        .bind(("limit", limit))
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "mem_rev"))?;
+
+    let revisions: Vec<<MemoryRevision as DbEntity>::DbModel> = result.take(0)?;
+    revisions
+        .into_iter()
+        .map(|r| MemoryRevision::from_db_model(r).map_err(DatabaseError::from))
+        .collect()
+}
+
+/// Overwrite a memory block's value from outside an agent, recording a revision
+///
+/// Agents holding the block pick up the new value through their live memory
+/// subscription. Returns `None` if the block doesn't exist.
+pub async fn set_memory_value<C: Connection>(
+    conn: &Surreal<C>,
+    memory_id: &MemoryId,
+    value: String,
+    author_user_id: Option<&UserId>,
+    note: Option<String>,
+) -> Result<Option<(MemoryBlock, Option<MemoryRevision>)>> {
+    let Some(mut block) = get_entity::<MemoryBlock, _>(conn, memory_id).await? else {
+        return Ok(None);
+    };
+
+    let previous_value = std::mem::replace(&mut block.value, value);
+    if block.value == previous_value {
+        return Ok(Some((block, None)));
+    }
+    block.updated_at = Utc::now();
+
+    let _: Option<serde_json::Value> = conn
+        .update(RecordId::from(memory_id))
+        .patch(PatchOp::replace("/value", block.value.clone()))
+        .patch(PatchOp::replace(
+            "/updated_at",
+            surrealdb::Datetime::from(block.updated_at),
+        ))
+        .await?;
+
+    let mut revision = MemoryRevision::new(&block, previous_value);
+    if let Some(user_id) = author_user_id {
+        revision = revision.by_user(user_id.clone());
+    }
+    if let Some(note) = note {
+        revision = revision.with_note(note);
+    }
+    let revision = create_memory_revision(conn, &revision).await?;
+
+    Ok(Some((block, Some(revision))))
+}
+
+/// Undo a revision, restoring the block to its value from just before it
+///
+/// Undoing a deletion brings the block back. The rollback is itself recorded
+/// as a revision, so it can be undone too.
+pub async fn rollback_memory_revision<C: Connection>(
+    conn: &Surreal<C>,
+    revision_id: &MemoryRevisionId,
+    author_user_id: Option<&UserId>,
+) -> Result<MemoryBlock> {
+    let revision = get_memory_revision(conn, revision_id)
+        .await?
+        .ok_or_else(|| DatabaseError::NotFound {
+            entity_type: "mem_rev".to_string(),
+            id: revision_id.to_string(),
+        })?;
+
+    if revision.change == RevisionChange::Delete {
+        return restore_deleted_memory(conn, &revision, author_user_id).await;
+    }
+
+    let (block, _) = set_memory_value(
+        conn,
+        &revision.memory_id,
+        revision.previous_value.clone(),
+        author_user_id,
+        Some(format!("rollback of {}", revision.id)),
+    )
+    .await?
+    .ok_or_else(|| DatabaseError::NotFound {
+        entity_type: "mem".to_string(),
+        id: revision.memory_id.to_string(),
+    })?;
+
+    Ok(block)
+}
+
+/// Bring back a block removed by a deletion revision
+///
+/// The block is recreated from the revision's snapshot if its record is gone,
+/// and reattached to the agent that removed it.
+async fn restore_deleted_memory<C: Connection>(
+    conn: &Surreal<C>,
+    revision: &MemoryRevision,
+    author_user_id: Option<&UserId>,
+) -> Result<MemoryBlock> {
+    let not_found = || DatabaseError::NotFound {
+        entity_type: "mem".to_string(),
+        id: revision.memory_id.to_string(),
+    };
+
+    let mut block = match get_entity::<MemoryBlock, _>(conn, &revision.memory_id).await? {
+        Some(block) => block,
+        None => {
+            let snapshot = revision.deleted_block().ok_or_else(not_found)?;
+            create_entity::<MemoryBlock, _>(conn, &snapshot).await?
+        }
+    };
+
+    if let Some(agent_id) = &revision.author_agent_id {
+        let query = "SELECT VALUE id FROM agent_memories WHERE in = $agent_id AND out = $memory_id";
+        let attached: Vec<RecordId> = conn
+            .query(query)
+            .bind(("agent_id", RecordId::from(agent_id)))
+            .bind(("memory_id", RecordId::from(&block.id)))
+            .await
+            .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "agent_memories"))?
+            .take(0)?;
+        if attached.is_empty() {
+            attach_memory_to_agent(conn, agent_id, &block.id, block.permission).await?;
+        }
+    }
+
+    // Touch the block so agents watching memory pick it back up
+    block.updated_at = Utc::now();
+    let _: Option<serde_json::Value> = conn
+        .update(RecordId::from(&block.id))
+        .patch(PatchOp::replace(
+            "/updated_at",
+            surrealdb::Datetime::from(block.updated_at),
+        ))
+        .await?;
+
+    let mut restored = MemoryRevision::new(&block, String::new())
+        .with_note(format!("rollback of {}", revision.id));
+    if let Some(user_id) = author_user_id {
+        restored = restored.by_user(user_id.clone());
+    }
+    create_memory_revision(conn, &restored).await?;
+
+    Ok(block)
+}
+
 // ============================================================================
 // Agent-Message Persistence Operations
 // ============================================================================
@@ -1458,6 +1700,23 @@ pub async fn get_agent_memberships<C: Connection>(
     Ok(memberships)
 }
 
+/// Names of the groups an agent belongs to
+pub async fn get_agent_group_names<C: Connection>(
+    conn: &Surreal<C>,
+    agent_id: &AgentId,
+) -> Result<Vec<String>> {
+    let query = "SELECT VALUE out.name FROM group_members WHERE `in` = $agent_id";
+
+    let mut result = conn
+        .query(query)
+        .bind(("agent_id", RecordId::from(agent_id)))
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "group_members"))?;
+
+    let names: Vec<Option<String>> = result.take(0)?;
+    Ok(names.into_iter().flatten().collect())
+}
+
 /// Update group state
 pub async fn update_group_state<C: Connection>(
     conn: &Surreal<C>,
@@ -1476,6 +1735,784 @@ pub async fn update_group_state<C: Connection>(
     Ok(())
 }
 
+// ============================================================================
+// Scheduled Wakeup Operations
+// ============================================================================
+
+/// Persist a new scheduled wakeup
+pub async fn create_wakeup<C: Connection>(
+    conn: &Surreal<C>,
+    wakeup: &ScheduledWakeup,
+) -> Result<ScheduledWakeup> {
+    create_entity::<ScheduledWakeup, _>(conn, wakeup).await
+}
+
+/// Get a scheduled wakeup by ID
+pub async fn get_wakeup<C: Connection>(
+    conn: &Surreal<C>,
+    wakeup_id: &WakeupId,
+) -> Result<Option<ScheduledWakeup>> {
+    get_entity::<ScheduledWakeup, _>(conn, wakeup_id).await
+}
+
+/// Save changes to an existing scheduled wakeup (e.g. after it fired)
+pub async fn update_wakeup<C: Connection>(
+    conn: &Surreal<C>,
+    wakeup: &ScheduledWakeup,
+) -> Result<ScheduledWakeup> {
+    update_entity::<ScheduledWakeup, _>(conn, wakeup).await
+}
+
+/// List the active wakeups for an agent, soonest first
+pub async fn list_agent_wakeups<C: Connection>(
+    conn: &Surreal<C>,
+    agent_id: &AgentId,
+) -> Result<Vec<ScheduledWakeup>> {
+    let query = r#"
+        SELECT * FROM wakeup
+        WHERE agent_id = $agent AND active = true
+        ORDER BY scheduled_for ASC
+    "#;
+
+    let mut result = conn
+        .query(query)
+        .bind(("agent", RecordId::from(agent_id)))
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "wakeup"))?;
+
+    let wakeups: Vec<<ScheduledWakeup as DbEntity>::DbModel> = result.take(0)?;
+
+    wakeups
+        .into_iter()
+        .map(|w| ScheduledWakeup::from_db_model(w).map_err(DatabaseError::from))
+        .collect()
+}
+
+/// Get all active wakeups for the given agents whose scheduled time has passed
+pub async fn get_due_wakeups<C: Connection>(
+    conn: &Surreal<C>,
+    agent_ids: &[AgentId],
+) -> Result<Vec<ScheduledWakeup>> {
+    let query = r#"
+        SELECT * FROM wakeup
+        WHERE active = true
+        AND scheduled_for <= time::now()
+        AND agent_id IN $agents
+        ORDER BY scheduled_for ASC
+    "#;
+
+    let agents: Vec<RecordId> = agent_ids.iter().map(RecordId::from).collect();
+
+    let mut result = conn
+        .query(query)
+        .bind(("agents", agents))
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "wakeup"))?;
+
+    let wakeups: Vec<<ScheduledWakeup as DbEntity>::DbModel> = result.take(0)?;
+
+    wakeups
+        .into_iter()
+        .map(|w| ScheduledWakeup::from_db_model(w).map_err(DatabaseError::from))
+        .collect()
+}
+
+/// Deactivate a wakeup belonging to the given agent
+///
+/// Returns `false` if no active wakeup with that ID exists for the agent.
+pub async fn cancel_wakeup<C: Connection>(
+    conn: &Surreal<C>,
+    agent_id: &AgentId,
+    wakeup_id: &WakeupId,
+) -> Result<bool> {
+    let Some(mut wakeup) = get_wakeup(conn, wakeup_id).await? else {
+        return Ok(false);
+    };
+
+    if &wakeup.agent_id != agent_id || !wakeup.active {
+        return Ok(false);
+    }
+
+    wakeup.active = false;
+    update_wakeup(conn, &wakeup).await?;
+
+    Ok(true)
+}
+
+// ============================================================================
+// Task Operations
+// ============================================================================
+
+/// Which tasks to return from [`list_tasks`]
+#[derive(Debug, Clone, Default)]
+pub struct TaskFilter {
+    /// Only tasks created by this user
+    pub creator_id: Option<UserId>,
+    /// Only tasks assigned to this agent
+    pub assigned_agent_id: Option<AgentId>,
+    /// Only direct subtasks of this task
+    pub parent_task_id: Option<TaskId>,
+    /// Only tasks in one of these states (all states when empty)
+    pub statuses: Vec<BaseTaskStatus>,
+}
+
+impl TaskFilter {
+    /// Pending and in-progress tasks
+    pub fn open() -> Self {
+        Self {
+            statuses: vec![BaseTaskStatus::Pending, BaseTaskStatus::InProgress],
+            ..Default::default()
+        }
+    }
+}
+
+/// Look up the ID of one of `owner_id`'s agents by its name
+///
+/// Agent names are only unique per user, so other users' agents never match.
+pub async fn get_agent_id_by_name<C: Connection>(
+    conn: &Surreal<C>,
+    owner_id: &UserId,
+    name: &str,
+) -> Result<Option<AgentId>> {
+    let query = "SELECT id FROM agent WHERE owner_id = $owner_id AND name = $name LIMIT 1";
+    let mut result = conn
+        .query(query)
+        .bind(("owner_id", RecordId::from(owner_id)))
+        .bind(("name", name.to_string()))
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "agent"))?;
+
+    let ids: Vec<RecordId> = result.take("id")?;
+    Ok(ids.into_iter().next().map(AgentId::from_record))
+}
+
+/// Persist a new task, linking it to its creator, parent and assignee
+pub async fn create_task<C: Connection>(conn: &Surreal<C>, task: &BaseTask) -> Result<BaseTask> {
+    let created = create_entity::<BaseTask, _>(conn, task).await?;
+    let task_record = RecordId::from(&created.id);
+
+    let query = "RELATE $user->created->$task";
+    conn.query(query)
+        .bind(("user", RecordId::from(&created.creator_id)))
+        .bind(("task", task_record.clone()))
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "created"))?;
+
+    if let Some(parent_id) = &created.parent_task_id {
+        let query = "RELATE $parent->has_subtask->$task";
+        conn.query(query)
+            .bind(("parent", RecordId::from(parent_id)))
+            .bind(("task", task_record.clone()))
+            .await
+            .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "has_subtask"))?;
+    }
+
+    if let Some(agent_id) = &created.assigned_agent_id {
+        let query = "RELATE $agent->assigned->$task";
+        conn.query(query)
+            .bind(("agent", RecordId::from(agent_id)))
+            .bind(("task", task_record))
+            .await
+            .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "assigned"))?;
+    }
+
+    Ok(created)
+}
+
+/// Get a task by ID
+pub async fn get_task<C: Connection>(
+    conn: &Surreal<C>,
+    task_id: &TaskId,
+) -> Result<Option<BaseTask>> {
+    get_entity::<BaseTask, _>(conn, task_id).await
+}
+
+/// Save changes to a task's fields
+///
+/// Keeps `completed_at` in step with the status and bumps `updated_at`.
+/// Use [`assign_task`] to change the assignee so the relation follows.
+pub async fn update_task<C: Connection>(conn: &Surreal<C>, task: &BaseTask) -> Result<BaseTask> {
+    let mut task = task.clone();
+    task.updated_at = Utc::now();
+    match task.status {
+        BaseTaskStatus::Completed => {
+            task.completed_at.get_or_insert(task.updated_at);
+        }
+        _ => task.completed_at = None,
+    }
+    update_entity::<BaseTask, _>(conn, &task).await
+}
+
+/// Assign a task to an agent, or unassign it with `None`
+pub async fn assign_task<C: Connection>(
+    conn: &Surreal<C>,
+    task_id: &TaskId,
+    agent_id: Option<&AgentId>,
+) -> Result<BaseTask> {
+    let mut task = get_task(conn, task_id)
+        .await?
+        .ok_or_else(|| DatabaseError::NotFound {
+            entity_type: "task".to_string(),
+            id: task_id.to_string(),
+        })?;
+
+    let query = "DELETE assigned WHERE out = $task";
+    conn.query(query)
+        .bind(("task", RecordId::from(task_id)))
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "assigned"))?;
+
+    if let Some(agent_id) = agent_id {
+        let query = "RELATE $agent->assigned->$task";
+        conn.query(query)
+            .bind(("agent", RecordId::from(agent_id)))
+            .bind(("task", RecordId::from(task_id)))
+            .await
+            .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "assigned"))?;
+    }
+
+    task.assigned_agent_id = agent_id.cloned();
+    update_task(conn, &task).await
+}
+
+/// List tasks matching a filter, most pressing first
+///
+/// Tasks with a due date come before those without, soonest first; ties
+/// are broken by priority (highest first) and then creation time.
+pub async fn list_tasks<C: Connection>(
+    conn: &Surreal<C>,
+    filter: &TaskFilter,
+) -> Result<Vec<BaseTask>> {
+    let mut conditions = Vec::new();
+    if filter.creator_id.is_some() {
+        conditions.push("creator_id = $creator");
+    }
+    if filter.assigned_agent_id.is_some() {
+        conditions.push("assigned_agent_id = $agent");
+    }
+    if filter.parent_task_id.is_some() {
+        conditions.push("parent_task_id = $parent");
+    }
+    if !filter.statuses.is_empty() {
+        conditions.push("status IN $statuses");
+    }
+
+    let query = if conditions.is_empty() {
+        "SELECT * FROM task".to_string()
+    } else {
+        format!("SELECT * FROM task WHERE {}", conditions.join(" AND "))
+    };
+
+    let mut request = conn.query(&query);
+    if let Some(creator_id) = &filter.creator_id {
+        request = request.bind(("creator", RecordId::from(creator_id)));
+    }
+    if let Some(agent_id) = &filter.assigned_agent_id {
+        request = request.bind(("agent", RecordId::from(agent_id)));
+    }
+    if let Some(parent_id) = &filter.parent_task_id {
+        request = request.bind(("parent", RecordId::from(parent_id)));
+    }
+    if !filter.statuses.is_empty() {
+        request = request.bind(("statuses", filter.statuses.clone()));
+    }
+
+    let mut result = request
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query.clone(), "task"))?;
+    let models: Vec<<BaseTask as DbEntity>::DbModel> = result.take(0)?;
+
+    let mut tasks = models
+        .into_iter()
+        .map(|t| BaseTask::from_db_model(t).map_err(DatabaseError::from))
+        .collect::<Result<Vec<_>>>()?;
+    tasks.sort_by(|a, b| {
+        let due = match (a.due_date, b.due_date) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        };
+        due.then(b.priority.cmp(&a.priority))
+            .then(a.created_at.cmp(&b.created_at))
+    });
+    Ok(tasks)
+}
+
+// ============================================================================
+// Event Operations
+// ============================================================================
+
+/// Which events to return from [`list_events`]
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// Only events created by this user
+    pub creator_id: Option<UserId>,
+    /// Only events on this agent's schedule
+    pub agent_id: Option<AgentId>,
+    /// Only events still running at or after this time
+    pub from: Option<chrono::DateTime<Utc>>,
+    /// Only events starting before this time
+    pub to: Option<chrono::DateTime<Utc>>,
+}
+
+/// Persist a new event, putting it on its creator's schedule and optionally an agent's
+pub async fn create_event<C: Connection>(
+    conn: &Surreal<C>,
+    event: &BaseEvent,
+    agent_id: Option<&AgentId>,
+) -> Result<BaseEvent> {
+    let created = create_entity::<BaseEvent, _>(conn, event).await?;
+
+    let query = "RELATE $user->scheduled->$event";
+    conn.query(query)
+        .bind(("user", RecordId::from(&created.creator_id)))
+        .bind(("event", RecordId::from(&created.id)))
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "scheduled"))?;
+
+    if let Some(agent_id) = agent_id {
+        schedule_event_for_agent(conn, &created.id, agent_id).await?;
+    }
+
+    Ok(created)
+}
+
+/// Put an existing event on an agent's schedule (no-op if it's already there)
+pub async fn schedule_event_for_agent<C: Connection>(
+    conn: &Surreal<C>,
+    event_id: &EventId,
+    agent_id: &AgentId,
+) -> Result<()> {
+    let query = "SELECT VALUE id FROM scheduled WHERE in = $agent AND out = $event";
+    let mut result = conn
+        .query(query)
+        .bind(("agent", RecordId::from(agent_id)))
+        .bind(("event", RecordId::from(event_id)))
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "scheduled"))?;
+    let existing: Vec<RecordId> = result.take(0)?;
+    if !existing.is_empty() {
+        return Ok(());
+    }
+
+    let query = "RELATE $agent->scheduled->$event";
+    conn.query(query)
+        .bind(("agent", RecordId::from(agent_id)))
+        .bind(("event", RecordId::from(event_id)))
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "scheduled"))?;
+    Ok(())
+}
+
+/// Get an event by ID
+pub async fn get_event<C: Connection>(
+    conn: &Surreal<C>,
+    event_id: &EventId,
+) -> Result<Option<BaseEvent>> {
+    get_entity::<BaseEvent, _>(conn, event_id).await
+}
+
+/// Find a user's event by the UID it was imported with
+pub async fn get_event_by_ical_uid<C: Connection>(
+    conn: &Surreal<C>,
+    creator_id: &UserId,
+    ical_uid: &str,
+) -> Result<Option<BaseEvent>> {
+    let query = "SELECT * FROM event WHERE creator_id = $creator AND ical_uid = $uid LIMIT 1";
+    let mut result = conn
+        .query(query)
+        .bind(("creator", RecordId::from(creator_id)))
+        .bind(("uid", ical_uid.to_string()))
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "event"))?;
+
+    let models: Vec<<BaseEvent as DbEntity>::DbModel> = result.take(0)?;
+    models
+        .into_iter()
+        .next()
+        .map(|e| BaseEvent::from_db_model(e).map_err(DatabaseError::from))
+        .transpose()
+}
+
+/// Save changes to an event, bumping `updated_at`
+pub async fn update_event<C: Connection>(
+    conn: &Surreal<C>,
+    event: &BaseEvent,
+) -> Result<BaseEvent> {
+    let mut event = event.clone();
+    event.updated_at = Utc::now();
+    update_entity::<BaseEvent, _>(conn, &event).await
+}
+
+/// Delete an event along with its schedule edges and pending reminders
+pub async fn delete_event<C: Connection>(conn: &Surreal<C>, event_id: &EventId) -> Result<()> {
+    cancel_event_reminders(conn, event_id, None).await?;
+
+    let query = "DELETE scheduled WHERE out = $event; DELETE $event;";
+    conn.query(query)
+        .bind(("event", RecordId::from(event_id)))
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "event"))?;
+    Ok(())
+}
+
+/// List events matching a filter, soonest first
+pub async fn list_events<C: Connection>(
+    conn: &Surreal<C>,
+    filter: &EventFilter,
+) -> Result<Vec<BaseEvent>> {
+    let mut conditions = Vec::new();
+    if filter.creator_id.is_some() {
+        conditions.push("creator_id = $creator");
+    }
+    if filter.agent_id.is_some() {
+        conditions.push("id IN (SELECT VALUE out FROM scheduled WHERE in = $agent)");
+    }
+    if filter.to.is_some() {
+        conditions.push("scheduled_for < $to");
+    }
+
+    let query = if conditions.is_empty() {
+        "SELECT * FROM event ORDER BY scheduled_for ASC".to_string()
+    } else {
+        format!(
+            "SELECT * FROM event WHERE {} ORDER BY scheduled_for ASC",
+            conditions.join(" AND ")
+        )
+    };
+
+    let mut request = conn.query(&query);
+    if let Some(creator_id) = &filter.creator_id {
+        request = request.bind(("creator", RecordId::from(creator_id)));
+    }
+    if let Some(agent_id) = &filter.agent_id {
+        request = request.bind(("agent", RecordId::from(agent_id)));
+    }
+    if let Some(to) = filter.to {
+        request = request.bind(("to", surrealdb::Datetime::from(to)));
+    }
+
+    let mut result = request
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query.clone(), "event"))?;
+    let models: Vec<<BaseEvent as DbEntity>::DbModel> = result.take(0)?;
+
+    let events = models
+        .into_iter()
+        .map(|e| BaseEvent::from_db_model(e).map_err(DatabaseError::from))
+        .collect::<Result<Vec<_>>>()?;
+
+    // The end of an event depends on its duration, so the lower bound is checked here
+    Ok(match filter.from {
+        Some(from) => events
+            .into_iter()
+            .filter(|e| e.ends_at().unwrap_or(e.scheduled_for) >= from)
+            .collect(),
+        None => events,
+    })
+}
+
+/// Active reminder wakeups for an event, optionally only one agent's
+pub async fn list_event_reminders<C: Connection>(
+    conn: &Surreal<C>,
+    event_id: &EventId,
+    agent_id: Option<&AgentId>,
+) -> Result<Vec<ScheduledWakeup>> {
+    let query = if agent_id.is_some() {
+        "SELECT * FROM wakeup WHERE active = true AND metadata.event_id = $event AND agent_id = $agent"
+    } else {
+        "SELECT * FROM wakeup WHERE active = true AND metadata.event_id = $event"
+    };
+
+    let mut request = conn.query(query).bind(("event", event_id.to_key()));
+    if let Some(agent_id) = agent_id {
+        request = request.bind(("agent", RecordId::from(agent_id)));
+    }
+    let mut result = request
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "wakeup"))?;
+
+    let wakeups: Vec<<ScheduledWakeup as DbEntity>::DbModel> = result.take(0)?;
+    wakeups
+        .into_iter()
+        .map(|w| ScheduledWakeup::from_db_model(w).map_err(DatabaseError::from))
+        .collect()
+}
+
+/// Deactivate the pending reminders for an event, returning how many there were
+pub async fn cancel_event_reminders<C: Connection>(
+    conn: &Surreal<C>,
+    event_id: &EventId,
+    agent_id: Option<&AgentId>,
+) -> Result<usize> {
+    let reminders = list_event_reminders(conn, event_id, agent_id).await?;
+    for mut reminder in reminders.iter().cloned() {
+        reminder.active = false;
+        update_wakeup(conn, &reminder).await?;
+    }
+    Ok(reminders.len())
+}
+
+// ============================================================================
+// Tool Call Operations
+// ============================================================================
+
+/// Which executions to return from [`list_tool_calls`]
+#[derive(Debug, Clone, Default)]
+pub struct ToolCallFilter {
+    /// Only calls made by this agent
+    pub agent_id: Option<AgentId>,
+    /// Only calls to this tool
+    pub tool_name: Option<String>,
+    /// Only calls made at or after this time
+    pub since: Option<chrono::DateTime<Utc>>,
+    /// Only calls made before this time
+    pub until: Option<chrono::DateTime<Utc>>,
+}
+
+/// List recorded tool executions matching `filter`, oldest first
+pub async fn list_tool_calls<C: Connection>(
+    conn: &Surreal<C>,
+    filter: &ToolCallFilter,
+) -> Result<Vec<ToolCall>> {
+    let mut conditions = Vec::new();
+    if filter.agent_id.is_some() {
+        conditions.push("agent_id = $agent");
+    }
+    if filter.tool_name.is_some() {
+        conditions.push("tool_name = $tool");
+    }
+    if filter.since.is_some() {
+        conditions.push("created_at >= $since");
+    }
+    if filter.until.is_some() {
+        conditions.push("created_at < $until");
+    }
+
+    let query = if conditions.is_empty() {
+        "SELECT * FROM tool_call ORDER BY created_at ASC".to_string()
+    } else {
+        format!(
+            "SELECT * FROM tool_call WHERE {} ORDER BY created_at ASC",
+            conditions.join(" AND ")
+        )
+    };
+
+    let mut request = conn.query(&query);
+    if let Some(agent_id) = &filter.agent_id {
+        request = request.bind(("agent", RecordId::from(agent_id)));
+    }
+    if let Some(tool_name) = &filter.tool_name {
+        request = request.bind(("tool", tool_name.clone()));
+    }
+    if let Some(since) = filter.since {
+        request = request.bind(("since", surrealdb::Datetime::from(since)));
+    }
+    if let Some(until) = filter.until {
+        request = request.bind(("until", surrealdb::Datetime::from(until)));
+    }
+
+    let mut result = request
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query.clone(), "tool_call"))?;
+    let models: Vec<<ToolCall as DbEntity>::DbModel> = result.take(0)?;
+    models
+        .into_iter()
+        .map(|c| ToolCall::from_db_model(c).map_err(DatabaseError::from))
+        .collect()
+}
+
+// ============================================================================
+// Model Usage Operations
+// ============================================================================
+
+/// Which model calls to return from [`list_model_usage`]
+#[derive(Debug, Clone, Default)]
+pub struct UsageFilter {
+    /// Only calls made by this agent
+    pub agent_id: Option<AgentId>,
+    /// Only calls triggered through this group
+    pub group_id: Option<GroupId>,
+    /// Only calls triggered by this data source
+    pub source: Option<String>,
+    /// Only calls made at or after this time
+    pub since: Option<chrono::DateTime<Utc>>,
+    /// Only calls made before this time
+    pub until: Option<chrono::DateTime<Utc>>,
+}
+
+/// Record the tokens and cost of one model call
+pub async fn create_model_usage<C: Connection>(
+    conn: &Surreal<C>,
+    usage: &ModelUsage,
+) -> Result<ModelUsage> {
+    create_entity::<ModelUsage, _>(conn, usage).await
+}
+
+/// List recorded model calls matching `filter`, oldest first
+pub async fn list_model_usage<C: Connection>(
+    conn: &Surreal<C>,
+    filter: &UsageFilter,
+) -> Result<Vec<ModelUsage>> {
+    let mut conditions = Vec::new();
+    if filter.agent_id.is_some() {
+        conditions.push("agent_id = $agent");
+    }
+    if filter.group_id.is_some() {
+        conditions.push("group_id = $group");
+    }
+    if filter.source.is_some() {
+        conditions.push("source = $source");
+    }
+    if filter.since.is_some() {
+        conditions.push("created_at >= $since");
+    }
+    if filter.until.is_some() {
+        conditions.push("created_at < $until");
+    }
+
+    let query = if conditions.is_empty() {
+        "SELECT * FROM model_usage ORDER BY created_at ASC".to_string()
+    } else {
+        format!(
+            "SELECT * FROM model_usage WHERE {} ORDER BY created_at ASC",
+            conditions.join(" AND ")
+        )
+    };
+
+    let mut request = conn.query(&query);
+    if let Some(agent_id) = &filter.agent_id {
+        request = request.bind(("agent", RecordId::from(agent_id)));
+    }
+    if let Some(group_id) = &filter.group_id {
+        request = request.bind(("group", RecordId::from(group_id)));
+    }
+    if let Some(source) = &filter.source {
+        request = request.bind(("source", source.clone()));
+    }
+    if let Some(since) = filter.since {
+        request = request.bind(("since", surrealdb::Datetime::from(since)));
+    }
+    if let Some(until) = filter.until {
+        request = request.bind(("until", surrealdb::Datetime::from(until)));
+    }
+
+    let mut result = request
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query.clone(), "model_usage"))?;
+    let models: Vec<<ModelUsage as DbEntity>::DbModel> = result.take(0)?;
+    models
+        .into_iter()
+        .map(|u| ModelUsage::from_db_model(u).map_err(DatabaseError::from))
+        .collect()
+}
+
+/// What an agent has spent since `since`
+pub async fn agent_usage_since<C: Connection>(
+    conn: &Surreal<C>,
+    agent_id: &AgentId,
+    since: chrono::DateTime<Utc>,
+) -> Result<UsageTotals> {
+    let filter = UsageFilter {
+        agent_id: Some(agent_id.clone()),
+        since: Some(since),
+        ..Default::default()
+    };
+    Ok(list_model_usage(conn, &filter).await?.iter().collect())
+}
+
+// ============================================================================
+// Permission Operations
+// ============================================================================
+
+/// Persist a standing permission grant
+pub async fn create_permission_grant<C: Connection>(
+    conn: &Surreal<C>,
+    grant: &PermissionGrantRecord,
+) -> Result<PermissionGrantRecord> {
+    create_entity::<PermissionGrantRecord, _>(conn, grant).await
+}
+
+/// Get a permission grant by ID
+pub async fn get_permission_grant<C: Connection>(
+    conn: &Surreal<C>,
+    grant_id: &PermissionGrantId,
+) -> Result<Option<PermissionGrantRecord>> {
+    get_entity::<PermissionGrantRecord, _>(conn, grant_id).await
+}
+
+/// Save changes to a permission grant (e.g. after it was revoked)
+pub async fn update_permission_grant<C: Connection>(
+    conn: &Surreal<C>,
+    grant: &PermissionGrantRecord,
+) -> Result<PermissionGrantRecord> {
+    update_entity::<PermissionGrantRecord, _>(conn, grant).await
+}
+
+/// List permission grants, newest first, optionally for one agent
+///
+/// Includes expired and revoked grants; callers filter with `is_active_at`.
+pub async fn list_permission_grants<C: Connection>(
+    conn: &Surreal<C>,
+    agent_id: Option<&AgentId>,
+) -> Result<Vec<PermissionGrantRecord>> {
+    let query = if agent_id.is_some() {
+        "SELECT * FROM permission_grant WHERE agent_id = $agent ORDER BY granted_at DESC"
+    } else {
+        "SELECT * FROM permission_grant ORDER BY granted_at DESC"
+    };
+
+    let mut request = conn.query(query);
+    if let Some(agent_id) = agent_id {
+        request = request.bind(("agent", RecordId::from(agent_id)));
+    }
+    let mut result = request
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "permission_grant"))?;
+
+    let grants: Vec<<PermissionGrantRecord as DbEntity>::DbModel> = result.take(0)?;
+    grants
+        .into_iter()
+        .map(|g| PermissionGrantRecord::from_db_model(g).map_err(DatabaseError::from))
+        .collect()
+}
+
+/// Append an entry to the permission audit log
+pub async fn append_permission_audit<C: Connection>(
+    conn: &Surreal<C>,
+    entry: &PermissionAuditEntry,
+) -> Result<PermissionAuditEntry> {
+    create_entity::<PermissionAuditEntry, _>(conn, entry).await
+}
+
+/// The most recent permission audit entries, newest first, optionally for one agent
+pub async fn list_permission_audit<C: Connection>(
+    conn: &Surreal<C>,
+    agent_id: Option<&AgentId>,
+    limit: usize,
+) -> Result<Vec<PermissionAuditEntry>> {
+    let query = if agent_id.is_some() {
+        "SELECT * FROM permission_audit WHERE agent_id = $agent ORDER BY created_at DESC LIMIT $limit"
+    } else {
+        "SELECT * FROM permission_audit ORDER BY created_at DESC LIMIT $limit"
+    };
+
+    let mut request = conn.query(query).bind(("limit", limit));
+    if let Some(agent_id) = agent_id {
+        request = request.bind(("agent", RecordId::from(agent_id)));
+    }
+    let mut result = request
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e).with_context(query, "permission_audit"))?;
+
+    let entries: Vec<<PermissionAuditEntry as DbEntity>::DbModel> = result.take(0)?;
+    entries
+        .into_iter()
+        .map(|e| PermissionAuditEntry::from_db_model(e).map_err(DatabaseError::from))
+        .collect()
+}
+
 // ============================================================================
 // OAuth Token Operations
 // ============================================================================
@@ -1794,11 +2831,7 @@ pub async fn create_group_for_user<C: Connection>(
 mod tests {
     use super::*;
     use crate::agent::{AgentRecord, AgentType};
-    use crate::db::{
-        client,
-        entity::{BaseTask, BaseTaskPriority, BaseTaskStatus},
-    };
-    use crate::id::{TaskId, UserId};
+    use crate::db::{client, entity::BaseTaskPriority};
     use crate::users::User;
 
     #[tokio::test]
@@ -1973,4 +3006,29 @@ mod tests {
         let created_user = create_entity::<User, _>(&db, &user).await.unwrap();
         assert_eq!(created_user.id, user.id);
     }
+    #[tokio::test]
+    async fn test_agent_lookup_is_scoped_to_owner() {
+        let db = client::create_test_db().await.unwrap();
+
+        let owner = UserId::generate();
+        let stranger = UserId::generate();
+        let agent = |owner_id: &UserId| AgentRecord {
+            id: AgentId::generate(),
+            name: "Helper".to_string(),
+            owner_id: owner_id.clone(),
+            ..Default::default()
+        };
+        let mine = agent(&owner);
+        let theirs = agent(&stranger);
+        for record in [&theirs, &mine] {
+            create_entity::<AgentRecord, _>(&db, record).await.unwrap();
+        }
+
+        let found = get_agent_id_by_name(&db, &owner, "Helper").await.unwrap();
+        assert_eq!(found, Some(mine.id.clone()));
+
+        let nobody = UserId::generate();
+        let found = get_agent_id_by_name(&db, &nobody, "Helper").await.unwrap();
+        assert_eq!(found, None);
+    }
 }
@@ -251,6 +251,13 @@ pub enum CoreError {
         #[source]
         cause: std::io::Error,
     },
+
+    #[error("{operation} was cancelled")]
+    #[diagnostic(
+        code(pattern_core::cancelled),
+        help("The operation stopped before finishing, so its output may be incomplete")
+    )]
+    Cancelled { operation: String },
 }
 
 pub type Result<T> = std::result::Result<T, CoreError>;
@@ -6,8 +6,12 @@ use iroh_car::{CarHeader, CarWriter};
 use multihash_codetable::Code;
 use multihash_codetable::MultihashDigest;
 use serde_ipld_dagcbor::to_vec as encode_dag_cbor;
+use std::sync::atomic::Ordering;
 use surrealdb::Surreal;
-use tokio::io::AsyncWrite;
+use tokio::{
+    io::{AsyncWrite, AsyncWriteExt},
+    sync::mpsc::UnboundedSender,
+};
 
 use crate::{
     AgentId, CoreError, Result,
@@ -16,6 +20,8 @@ use crate::{
     db::entity::DbEntity,
     export::{
         DEFAULT_CHUNK_SIZE, DEFAULT_MEMORY_CHUNK_SIZE, EXPORT_VERSION, MAX_BLOCK_BYTES,
+        letta::LettaAgentFile,
+        progress::{CancellationToken, CountingWriter, ExportProgress, Progress},
         types::{
             AgentExport, AgentRecordExport, ConstellationExport, ExportManifest, ExportStats,
             ExportType, GroupExport, MemoryChunk, MessageChunk,
@@ -137,17 +143,10 @@ where
         Ok(Cid::new_v1(DAG_CBOR_CODEC, hash))
     }
 
-    /// Export an agent to a CAR file
-    pub async fn export_to_car(
-        &self,
-        agent_id: AgentId,
-        mut output: impl AsyncWrite + Unpin + Send,
-        options: ExportOptions,
-    ) -> Result<ExportManifest> {
-        let start_time = Utc::now();
-
+    /// Load an agent record together with its message history and memory blocks
+    async fn load_agent(&self, agent_id: &AgentId) -> Result<AgentRecord> {
         // Load the agent record
-        let mut agent = AgentRecord::load_with_relations(&self.db, &agent_id)
+        let mut agent = AgentRecord::load_with_relations(&self.db, agent_id)
             .await
             .map_err(|e| {
                 CoreError::from(e).with_db_context(
@@ -199,9 +198,81 @@ where
                 .collect();
         }
 
+        Ok(agent)
+    }
+
+    /// Export an agent to a Letta `.af` agent file
+    pub async fn export_to_letta(
+        &self,
+        agent_id: AgentId,
+        mut output: impl AsyncWrite + Unpin + Send,
+    ) -> Result<LettaAgentFile> {
+        let agent = self.load_agent(&agent_id).await?;
+        let agent_file = LettaAgentFile::from_agent_record(&agent);
+
+        let data = agent_file.to_vec()?;
+        output
+            .write_all(&data)
+            .await
+            .map_err(|e| CoreError::IoError {
+                operation: "writing Letta agent file".to_string(),
+                cause: e,
+            })?;
+        output.flush().await.map_err(|e| CoreError::IoError {
+            operation: "flushing Letta agent file".to_string(),
+            cause: e,
+        })?;
+
+        Ok(agent_file)
+    }
+
+    /// Export an agent to a CAR file
+    pub async fn export_to_car(
+        &self,
+        agent_id: AgentId,
+        output: impl AsyncWrite + Unpin + Send,
+        options: ExportOptions,
+    ) -> Result<ExportManifest> {
+        self.export_agent_car(agent_id, output, options, &Progress::default())
+            .await
+    }
+
+    /// Export an agent to a CAR file, sending progress events to `progress`
+    ///
+    /// Stops with `CoreError::Cancelled` once `cancel` is cancelled, leaving
+    /// the output incomplete.
+    pub async fn export_to_car_with_progress(
+        &self,
+        agent_id: AgentId,
+        output: impl AsyncWrite + Unpin + Send,
+        options: ExportOptions,
+        progress: UnboundedSender<ExportProgress>,
+        cancel: CancellationToken,
+    ) -> Result<ExportManifest> {
+        self.export_agent_car(agent_id, output, options, &Progress::new(progress, cancel))
+            .await
+    }
+
+    async fn export_agent_car(
+        &self,
+        agent_id: AgentId,
+        output: impl AsyncWrite + Unpin + Send,
+        options: ExportOptions,
+        progress: &Progress<ExportProgress>,
+    ) -> Result<ExportManifest> {
+        let start_time = Utc::now();
+
+        let agent = self.load_agent(&agent_id).await?;
+        progress.report(ExportProgress::Agent {
+            name: agent.name.clone(),
+            current: 1,
+            total: 1,
+        });
+
         // First export the agent and collect all blocks
-        let (agent_export, agent_blocks, mut stats) =
-            self.export_agent_to_blocks(&agent, &options).await?;
+        let (agent_export, agent_blocks, mut stats) = self
+            .export_agent_to_blocks(&agent, &options, progress)
+            .await?;
 
         // Create the agent export data
         let agent_export_data =
@@ -243,46 +314,56 @@ where
         }
         let manifest_cid = Self::create_cid(&manifest_data)?;
 
-        // Create CAR writer with manifest as root
-        let header = CarHeader::new_v1(vec![manifest_cid]);
-        let mut car_writer = CarWriter::new(header, &mut output);
+        // Manifest first, then the agent export and its blocks (agent record, memories, messages)
+        Self::write_car(
+            output,
+            (manifest_cid, manifest_data),
+            (agent_export_cid, agent_export_data),
+            agent_blocks,
+            progress,
+        )
+        .await?;
 
-        // Write manifest first
-        car_writer
-            .write(manifest_cid, &manifest_data)
-            .await
-            .map_err(|e| CoreError::CarError {
-                operation: "writing manifest to CAR".to_string(),
-                cause: e,
-            })?;
+        Ok(manifest)
+    }
 
-        // Write agent export
-        car_writer
-            .write(agent_export_cid, &agent_export_data)
-            .await
-            .map_err(|e| CoreError::CarError {
-                operation: "writing agent export to CAR".to_string(),
-                cause: e,
-            })?;
+    /// Write a CAR file rooted at the manifest, reporting each block written
+    async fn write_car(
+        output: impl AsyncWrite + Unpin + Send,
+        manifest: (Cid, Vec<u8>),
+        data: (Cid, Vec<u8>),
+        blocks: Vec<(Cid, Vec<u8>)>,
+        progress: &Progress<ExportProgress>,
+    ) -> Result<()> {
+        let mut output = CountingWriter::new(output);
+        let bytes_written = output.counter();
+        let total_blocks = blocks.len() as u64 + 2;
+
+        let header = CarHeader::new_v1(vec![manifest.0]);
+        let mut car_writer = CarWriter::new(header, &mut output);
 
-        // Write all the agent blocks (agent record, memories, messages)
-        for (cid, data) in agent_blocks {
+        for (index, (cid, block)) in [manifest, data].into_iter().chain(blocks).enumerate() {
+            progress.checkpoint("CAR export")?;
             car_writer
-                .write(cid, &data)
+                .write(cid, &block)
                 .await
                 .map_err(|e| CoreError::CarError {
-                    operation: "writing agent block to CAR".to_string(),
+                    operation: format!("writing block {} to CAR", cid),
                     cause: e,
                 })?;
+            progress.report(ExportProgress::Writing {
+                blocks: index as u64 + 1,
+                total_blocks,
+                bytes: bytes_written.load(Ordering::Relaxed),
+            });
         }
 
-        // Flush the writer
         car_writer.finish().await.map_err(|e| CoreError::CarError {
             operation: "finishing CAR write".to_string(),
             cause: e,
         })?;
 
-        Ok(manifest)
+        Ok(())
     }
 
     /// Export an agent to blocks without writing to CAR file
@@ -290,6 +371,7 @@ where
         &self,
         agent: &AgentRecord,
         options: &ExportOptions,
+        progress: &Progress<ExportProgress>,
     ) -> Result<(AgentExport, Vec<(Cid, Vec<u8>)>, ExportStats)> {
         let mut blocks = Vec::new();
         let mut stats = ExportStats {
@@ -368,7 +450,12 @@ where
             };
 
             let mut chunk_id: u32 = 0;
-            for item in agent.memories.iter().cloned() {
+            for (index, item) in agent.memories.iter().cloned().enumerate() {
+                progress.checkpoint("CAR export")?;
+                progress.report(ExportProgress::Memories {
+                    current: index as u64 + 1,
+                    total: stats.memory_count,
+                });
                 let mut test_vec = current.clone();
                 test_vec.push(item.clone());
                 let est = encode_mem_probe(chunk_id, &test_vec)?;
@@ -499,8 +586,14 @@ where
                         Ok(data.len())
                     };
 
+                let total = source.len() as u64;
                 let mut chunk_id: u32 = 0;
-                for item in source.into_iter() {
+                for (index, item) in source.into_iter().enumerate() {
+                    progress.checkpoint("CAR export")?;
+                    progress.report(ExportProgress::Messages {
+                        current: index as u64 + 1,
+                        total,
+                    });
                     let mut test_vec = current.clone();
                     test_vec.push(item.clone());
                     let est = encode_msg_probe(chunk_id, &test_vec)?;
@@ -611,8 +704,35 @@ where
     pub async fn export_group_to_car(
         &self,
         group_id: GroupId,
-        mut output: impl AsyncWrite + Unpin + Send,
+        output: impl AsyncWrite + Unpin + Send,
+        options: ExportOptions,
+    ) -> Result<ExportManifest> {
+        self.export_group_car(group_id, output, options, &Progress::default())
+            .await
+    }
+
+    /// Export a group to a CAR file, sending progress events to `progress`
+    ///
+    /// Stops with `CoreError::Cancelled` once `cancel` is cancelled, leaving
+    /// the output incomplete.
+    pub async fn export_group_to_car_with_progress(
+        &self,
+        group_id: GroupId,
+        output: impl AsyncWrite + Unpin + Send,
+        options: ExportOptions,
+        progress: UnboundedSender<ExportProgress>,
+        cancel: CancellationToken,
+    ) -> Result<ExportManifest> {
+        self.export_group_car(group_id, output, options, &Progress::new(progress, cancel))
+            .await
+    }
+
+    async fn export_group_car(
+        &self,
+        group_id: GroupId,
+        output: impl AsyncWrite + Unpin + Send,
         options: ExportOptions,
+        progress: &Progress<ExportProgress>,
     ) -> Result<ExportManifest> {
         let start_time = Utc::now();
         let mut total_stats = ExportStats {
@@ -631,9 +751,15 @@ where
         let mut agent_export_cids = Vec::new();
         let mut all_blocks = Vec::new();
 
-        for (agent, _membership) in &group.members {
-            let (agent_export, agent_blocks, stats) =
-                self.export_agent_to_blocks(agent, &options).await?;
+        for (index, (agent, _membership)) in group.members.iter().enumerate() {
+            progress.report(ExportProgress::Agent {
+                name: agent.name.clone(),
+                current: index + 1,
+                total: group.members.len(),
+            });
+            let (agent_export, agent_blocks, stats) = self
+                .export_agent_to_blocks(agent, &options, progress)
+                .await?;
 
             // Serialize the agent export and get its CID
             let agent_export_data =
@@ -709,43 +835,15 @@ where
         }
         let manifest_cid = Self::create_cid(&manifest_data)?;
 
-        // Create CAR file with manifest as root
-        let header = CarHeader::new_v1(vec![manifest_cid]);
-        let mut car_writer = CarWriter::new(header, &mut output);
-
-        // Write manifest first
-        car_writer
-            .write(manifest_cid, &manifest_data)
-            .await
-            .map_err(|e| CoreError::CarError {
-                operation: "writing manifest to CAR".to_string(),
-                cause: e,
-            })?;
-
-        // Write group block
-        car_writer
-            .write(group_cid, &group_data)
-            .await
-            .map_err(|e| CoreError::CarError {
-                operation: "writing group to CAR".to_string(),
-                cause: e,
-            })?;
-
-        // Write all agent blocks
-        for (cid, data) in all_blocks {
-            car_writer
-                .write(cid, &data)
-                .await
-                .map_err(|e| CoreError::CarError {
-                    operation: "writing agent block to CAR".to_string(),
-                    cause: e,
-                })?;
-        }
-
-        car_writer.finish().await.map_err(|e| CoreError::CarError {
-            operation: "finishing group CAR write".to_string(),
-            cause: e,
-        })?;
+        // Manifest first, then the group block and all agent blocks
+        Self::write_car(
+            output,
+            (manifest_cid, manifest_data),
+            (group_cid, group_data),
+            all_blocks,
+            progress,
+        )
+        .await?;
 
         Ok(manifest)
     }
@@ -754,8 +852,40 @@ where
     pub async fn export_constellation_to_car(
         &self,
         constellation_id: ConstellationId,
-        mut output: impl AsyncWrite + Unpin + Send,
+        output: impl AsyncWrite + Unpin + Send,
+        options: ExportOptions,
+    ) -> Result<ExportManifest> {
+        self.export_constellation_car(constellation_id, output, options, &Progress::default())
+            .await
+    }
+
+    /// Export a constellation to a CAR file, sending progress events to `progress`
+    ///
+    /// Stops with `CoreError::Cancelled` once `cancel` is cancelled, leaving
+    /// the output incomplete.
+    pub async fn export_constellation_to_car_with_progress(
+        &self,
+        constellation_id: ConstellationId,
+        output: impl AsyncWrite + Unpin + Send,
         options: ExportOptions,
+        progress: UnboundedSender<ExportProgress>,
+        cancel: CancellationToken,
+    ) -> Result<ExportManifest> {
+        self.export_constellation_car(
+            constellation_id,
+            output,
+            options,
+            &Progress::new(progress, cancel),
+        )
+        .await
+    }
+
+    async fn export_constellation_car(
+        &self,
+        constellation_id: ConstellationId,
+        output: impl AsyncWrite + Unpin + Send,
+        options: ExportOptions,
+        progress: &Progress<ExportProgress>,
     ) -> Result<ExportManifest> {
         let start_time = Utc::now();
         // Load the constellation with all its data (direct agents + groups with their agents)
@@ -775,9 +905,15 @@ where
         };
 
         // Export all agents (from direct membership + groups)
-        for agent in &all_agents {
-            let (agent_export, agent_blocks, stats) =
-                self.export_agent_to_blocks(agent, &options).await?;
+        for (index, agent) in all_agents.iter().enumerate() {
+            progress.report(ExportProgress::Agent {
+                name: agent.name.clone(),
+                current: index + 1,
+                total: all_agents.len(),
+            });
+            let (agent_export, agent_blocks, stats) = self
+                .export_agent_to_blocks(agent, &options, progress)
+                .await?;
 
             // Serialize the agent export and get its CID
             let agent_export_data =
@@ -885,43 +1021,15 @@ where
         }
         let manifest_cid = Self::create_cid(&manifest_data)?;
 
-        // Create CAR file with manifest as root
-        let header = CarHeader::new_v1(vec![manifest_cid]);
-        let mut car_writer = CarWriter::new(header, &mut output);
-
-        // Write manifest first
-        car_writer
-            .write(manifest_cid, &manifest_data)
-            .await
-            .map_err(|e| CoreError::CarError {
-                operation: "writing manifest to CAR".to_string(),
-                cause: e,
-            })?;
-
-        // Write constellation block
-        car_writer
-            .write(constellation_cid, &constellation_data)
-            .await
-            .map_err(|e| CoreError::CarError {
-                operation: "writing constellation to CAR".to_string(),
-                cause: e,
-            })?;
-
-        // Write all collected blocks
-        for (cid, data) in all_blocks {
-            car_writer
-                .write(cid, &data)
-                .await
-                .map_err(|e| CoreError::CarError {
-                    operation: "writing block to CAR".to_string(),
-                    cause: e,
-                })?;
-        }
-
-        car_writer.finish().await.map_err(|e| CoreError::CarError {
-            operation: "finishing constellation CAR write".to_string(),
-            cause: e,
-        })?;
+        // Manifest first, then the constellation block and all collected blocks
+        Self::write_car(
+            output,
+            (manifest_cid, manifest_data),
+            (constellation_cid, constellation_data),
+            all_blocks,
+            progress,
+        )
+        .await?;
 
         Ok(manifest)
     }
@@ -1329,7 +1437,7 @@ mod tests {
         let agent = make_agent_with_data(2500, 250).await;
 
         let (export, blocks, stats) = exporter
-            .export_agent_to_blocks(&agent, &ExportOptions::default())
+            .export_agent_to_blocks(&agent, &ExportOptions::default(), &Progress::default())
             .await
             .unwrap();
 
@@ -1375,4 +1483,79 @@ mod tests {
         assert!(stats.memory_count as usize >= 250);
         assert!(stats.chunk_count >= 3);
     }
+
+    #[tokio::test]
+    async fn export_reports_progress_and_cancels() {
+        type Exporter = AgentExporter<surrealdb::engine::any::Any>;
+
+        let db = client::create_test_db().await.unwrap();
+        let exporter = AgentExporter::new(db);
+        let agent = make_agent_with_data(20, 5).await;
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let progress = Progress::new(tx, CancellationToken::new());
+        let (export, blocks, stats) = exporter
+            .export_agent_to_blocks(&agent, &ExportOptions::default(), &progress)
+            .await
+            .unwrap();
+
+        let export_data = encode_dag_cbor(&export).unwrap();
+        let export_cid = Exporter::create_cid(&export_data).unwrap();
+        let manifest_data = encode_dag_cbor(&ExportManifest {
+            version: EXPORT_VERSION,
+            exported_at: Utc::now(),
+            export_type: ExportType::Agent,
+            stats,
+            data_cid: export_cid,
+        })
+        .unwrap();
+        let manifest_cid = Exporter::create_cid(&manifest_data).unwrap();
+
+        let block_count = blocks.len() as u64;
+        let mut output = Vec::new();
+        Exporter::write_car(
+            &mut output,
+            (manifest_cid, manifest_data),
+            (export_cid, export_data),
+            blocks,
+            &progress,
+        )
+        .await
+        .unwrap();
+        drop(progress);
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        assert!(events.contains(&ExportProgress::Memories {
+            current: 5,
+            total: 5
+        }));
+        assert!(events.contains(&ExportProgress::Messages {
+            current: 20,
+            total: 20
+        }));
+        assert_eq!(
+            events.last(),
+            Some(&ExportProgress::Writing {
+                blocks: block_count + 2,
+                total_blocks: block_count + 2,
+                bytes: output.len() as u64,
+            })
+        );
+
+        // A cancelled token stops the export before any chunk is packed
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let result = exporter
+            .export_agent_to_blocks(
+                &agent,
+                &ExportOptions::default(),
+                &Progress::new(tx, cancel),
+            )
+            .await;
+        assert!(matches!(result, Err(CoreError::Cancelled { .. })));
+    }
 }
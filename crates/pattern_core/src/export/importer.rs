@@ -3,17 +3,114 @@
 use iroh_car::CarReader;
 use serde_ipld_dagcbor::from_slice as decode_dag_cbor;
 use std::collections::HashMap;
-use tokio::io::AsyncRead;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    sync::mpsc::UnboundedSender,
+};
 
 use crate::{
     AgentId, CoreError, Result, UserId,
     agent::AgentRecord,
-    export::types::{
-        AgentExport, AgentRecordExport, ConstellationExport, ExportManifest, ExportType,
-        GroupExport, MemoryChunk, MessageChunk,
+    export::{
+        letta::LettaAgentFile,
+        progress::{CancellationToken, ImportProgress, Progress},
+        types::{
+            AgentExport, AgentRecordExport, ConstellationExport, ExportManifest, ExportType,
+            GroupExport, MemoryChunk, MessageChunk,
+        },
     },
 };
 
+/// Read every block of a CAR file into memory, returning the root CID with the blocks
+async fn read_car_blocks(
+    input: impl AsyncRead + Unpin + Send,
+    progress: &Progress<ImportProgress>,
+) -> Result<(cid::Cid, HashMap<cid::Cid, Vec<u8>>)> {
+    let mut car_reader = CarReader::new(input)
+        .await
+        .map_err(|e| CoreError::CarError {
+            operation: "reading CAR header".to_string(),
+            cause: e,
+        })?;
+
+    // Get the root CID (should be the manifest)
+    let root_cid = {
+        let roots = car_reader.header().roots();
+        if roots.is_empty() {
+            return Err(CoreError::CarError {
+                operation: "reading CAR roots".to_string(),
+                cause: iroh_car::Error::Parsing("No root CID found".to_string()),
+            });
+        }
+        roots[0]
+    };
+
+    let mut blocks = HashMap::new();
+    let mut bytes = 0u64;
+
+    while let Some((cid, data)) =
+        car_reader
+            .next_block()
+            .await
+            .map_err(|e| CoreError::CarError {
+                operation: "reading CAR block".to_string(),
+                cause: e,
+            })?
+    {
+        progress.checkpoint("CAR import")?;
+        bytes += data.len() as u64;
+        blocks.insert(cid, data);
+        progress.report(ImportProgress::Reading {
+            blocks: blocks.len() as u64,
+            bytes,
+        });
+    }
+
+    Ok((root_cid, blocks))
+}
+
+/// Memory and message totals recorded in the manifest, if the root is one
+fn manifest_totals(root_data: &[u8]) -> (u64, u64) {
+    decode_dag_cbor::<ExportManifest>(root_data)
+        .map(|manifest| (manifest.stats.memory_count, manifest.stats.message_count))
+        .unwrap_or_default()
+}
+
+/// Running memory and message counts reported while agents are stored
+struct ImportCounts {
+    memories: u64,
+    messages: u64,
+    total_memories: u64,
+    total_messages: u64,
+}
+
+impl ImportCounts {
+    fn new((total_memories, total_messages): (u64, u64)) -> Self {
+        Self {
+            memories: 0,
+            messages: 0,
+            total_memories,
+            total_messages,
+        }
+    }
+
+    fn memory_stored(&mut self, progress: &Progress<ImportProgress>) {
+        self.memories += 1;
+        progress.report(ImportProgress::Memories {
+            current: self.memories,
+            total: self.total_memories,
+        });
+    }
+
+    fn message_stored(&mut self, progress: &Progress<ImportProgress>) {
+        self.messages += 1;
+        progress.report(ImportProgress::Messages {
+            current: self.messages,
+            total: self.total_messages,
+        });
+    }
+}
+
 fn reconstruct_agent_from_export(
     meta: &AgentRecordExport,
     blocks: &std::collections::HashMap<cid::Cid, Vec<u8>>,
@@ -176,11 +273,16 @@ where
                 cause: e,
             })?;
 
-        // Create a reader from the buffer
-        let mut reader = std::io::Cursor::new(&buffer);
+        let export_type = Self::detect_export_type(std::io::Cursor::new(&buffer)).await?;
+        Ok((export_type, buffer))
+    }
 
-        // Read the CAR header to get root CID
-        let car_reader = CarReader::new(&mut reader)
+    /// Detect the type of export in a CAR file, reading only up to the root block
+    ///
+    /// Exports write their manifest first, so this returns after the first block
+    /// without buffering the rest of the file.
+    pub async fn detect_export_type(input: impl AsyncRead + Unpin + Send) -> Result<ExportType> {
+        let mut car_reader = CarReader::new(input)
             .await
             .map_err(|e| CoreError::CarError {
                 operation: "reading CAR header".to_string(),
@@ -198,16 +300,6 @@ where
             roots[0]
         };
 
-        // Reset reader and read blocks to find the root
-        let mut reader = std::io::Cursor::new(&buffer);
-        let mut car_reader =
-            CarReader::new(&mut reader)
-                .await
-                .map_err(|e| CoreError::CarError {
-                    operation: "reading CAR header".to_string(),
-                    cause: e,
-                })?;
-
         // Find the root block
         while let Some((cid, data)) =
             car_reader
@@ -221,18 +313,18 @@ where
             if cid == root_cid {
                 // First try to decode as ExportManifest (new format)
                 if let Ok(manifest) = decode_dag_cbor::<ExportManifest>(&data) {
-                    return Ok((manifest.export_type, buffer));
+                    return Ok(manifest.export_type);
                 }
 
                 // Fall back to old format detection for backwards compatibility
                 if let Ok(_) = decode_dag_cbor::<AgentRecord>(&data) {
-                    return Ok((ExportType::Agent, buffer));
+                    return Ok(ExportType::Agent);
                 }
                 if let Ok(_) = decode_dag_cbor::<GroupExport>(&data) {
-                    return Ok((ExportType::Group, buffer));
+                    return Ok(ExportType::Group);
                 }
                 if let Ok(_) = decode_dag_cbor::<ConstellationExport>(&data) {
-                    return Ok((ExportType::Constellation, buffer));
+                    return Ok(ExportType::Constellation);
                 }
 
                 return Err(CoreError::CarError {
@@ -251,43 +343,35 @@ where
     /// Import an agent from a CAR file
     pub async fn import_agent_from_car(
         &self,
-        mut input: impl AsyncRead + Unpin + Send,
+        input: impl AsyncRead + Unpin + Send,
         options: ImportOptions,
     ) -> Result<ImportResult> {
-        // Read the CAR file
-        let mut car_reader = CarReader::new(&mut input)
+        self.import_agent_car(input, options, &Progress::default())
             .await
-            .map_err(|e| CoreError::CarError {
-                operation: "reading CAR header".to_string(),
-                cause: e,
-            })?;
+    }
 
-        // Get the root CID (should be the manifest)
-        let root_cid = {
-            let roots = car_reader.header().roots();
-            if roots.is_empty() {
-                return Err(CoreError::CarError {
-                    operation: "reading CAR roots".to_string(),
-                    cause: iroh_car::Error::Parsing("No root CID found".to_string()),
-                });
-            }
-            roots[0]
-        };
+    /// Import an agent from a CAR file, sending progress events to `progress`
+    ///
+    /// Stops with `CoreError::Cancelled` once `cancel` is cancelled.
+    pub async fn import_agent_from_car_with_progress(
+        &self,
+        input: impl AsyncRead + Unpin + Send,
+        options: ImportOptions,
+        progress: UnboundedSender<ImportProgress>,
+        cancel: CancellationToken,
+    ) -> Result<ImportResult> {
+        self.import_agent_car(input, options, &Progress::new(progress, cancel))
+            .await
+    }
 
+    async fn import_agent_car(
+        &self,
+        input: impl AsyncRead + Unpin + Send,
+        options: ImportOptions,
+        progress: &Progress<ImportProgress>,
+    ) -> Result<ImportResult> {
         // Read all blocks into memory
-        let mut blocks = HashMap::new();
-
-        while let Some((cid, data)) =
-            car_reader
-                .next_block()
-                .await
-                .map_err(|e| CoreError::CarError {
-                    operation: "reading CAR block".to_string(),
-                    cause: e,
-                })?
-        {
-            blocks.insert(cid, data);
-        }
+        let (root_cid, blocks) = read_car_blocks(input, progress).await?;
 
         // Get the root block (should be manifest)
         let root_data = blocks.get(&root_cid).ok_or_else(|| CoreError::CarError {
@@ -317,7 +401,7 @@ where
                 })?;
 
         // Decode AgentExport and then the slim AgentRecordExport
-        let mut agent: AgentRecord =
+        let agent: AgentRecord =
             if let Ok(agent_export) = decode_dag_cbor::<AgentExport>(agent_export_data) {
                 let meta_cid = agent_export.agent_cid;
                 let meta_block = blocks.get(&meta_cid).ok_or_else(|| CoreError::CarError {
@@ -341,6 +425,47 @@ where
                 })?
             };
 
+        progress.checkpoint("CAR import")?;
+        progress.report(ImportProgress::Agent {
+            name: agent.name.clone(),
+            current: 1,
+            total: 1,
+        });
+        let mut counts = ImportCounts::new(manifest_totals(root_data));
+        self.store_imported_agent(agent, options, &mut counts, progress)
+            .await
+    }
+
+    /// Import an agent from a Letta `.af` agent file
+    pub async fn import_agent_from_letta(
+        &self,
+        mut input: impl AsyncRead + Unpin + Send,
+        options: ImportOptions,
+    ) -> Result<ImportResult> {
+        let mut data = Vec::new();
+        input
+            .read_to_end(&mut data)
+            .await
+            .map_err(|e| CoreError::IoError {
+                operation: "reading Letta agent file".to_string(),
+                cause: e,
+            })?;
+
+        let agent = LettaAgentFile::from_slice(&data)?.into_agent_record(&options.owner_id);
+        let mut counts =
+            ImportCounts::new((agent.memories.len() as u64, agent.messages.len() as u64));
+        self.store_imported_agent(agent, options, &mut counts, &Progress::default())
+            .await
+    }
+
+    /// Apply import options to a reconstructed agent and store it with its relations
+    async fn store_imported_agent(
+        &self,
+        mut agent: AgentRecord,
+        options: ImportOptions,
+        counts: &mut ImportCounts,
+        progress: &Progress<ImportProgress>,
+    ) -> Result<ImportResult> {
         // Store the original ID for mapping
         let original_id = agent.id.clone();
 
@@ -385,10 +510,7 @@ where
         let message_count = agent.messages.len();
 
         // Store the agent with all its relations individually to avoid payload limits
-        let stored_agent = agent
-            .store_with_relations_individually(&self.db)
-            .await
-            .map_err(|e| CoreError::from(e))?;
+        let stored_agent = self.store_agent_records(agent, counts, progress).await?;
 
         let mut result = ImportResult {
             agents_imported: 1,
@@ -405,45 +527,72 @@ where
         Ok(result)
     }
 
+    /// Store the agent, then each memory and message with its relation one at a
+    /// time, reporting progress after every record and stopping between records
+    /// once the import is cancelled
+    async fn store_agent_records(
+        &self,
+        mut agent: AgentRecord,
+        counts: &mut ImportCounts,
+        progress: &Progress<ImportProgress>,
+    ) -> Result<AgentRecord> {
+        let memories = std::mem::take(&mut agent.memories);
+        let messages = std::mem::take(&mut agent.messages);
+        agent.assigned_task_ids.clear();
+        agent.scheduled_event_ids.clear();
+
+        let stored_agent = agent.store_with_relations(&self.db).await?;
+
+        for (memory, relation) in &memories {
+            progress.checkpoint("CAR import")?;
+            memory.store_with_relations(&self.db).await?;
+            crate::db::ops::create_relation_typed(&self.db, relation).await?;
+            counts.memory_stored(progress);
+        }
+
+        for (message, relation) in &messages {
+            progress.checkpoint("CAR import")?;
+            message.store_with_relations(&self.db).await?;
+            crate::db::ops::create_relation_typed(&self.db, relation).await?;
+            counts.message_stored(progress);
+        }
+
+        Ok(stored_agent)
+    }
+
     /// Import a group from a CAR file
     pub async fn import_group_from_car(
         &self,
-        mut input: impl AsyncRead + Unpin + Send,
+        input: impl AsyncRead + Unpin + Send,
         options: ImportOptions,
     ) -> Result<ImportResult> {
-        // Read the CAR file
-        let mut car_reader = CarReader::new(&mut input)
+        self.import_group_car(input, options, &Progress::default())
             .await
-            .map_err(|e| CoreError::CarError {
-                operation: "reading CAR header".to_string(),
-                cause: e,
-            })?;
+    }
 
-        let root_cid = {
-            let roots = car_reader.header().roots();
-            if roots.is_empty() {
-                return Err(CoreError::CarError {
-                    operation: "reading CAR roots".to_string(),
-                    cause: iroh_car::Error::Parsing("No root CID found".to_string()),
-                });
-            }
-            roots[0]
-        };
+    /// Import a group from a CAR file, sending progress events to `progress`
+    ///
+    /// Stops with `CoreError::Cancelled` once `cancel` is cancelled. Agents
+    /// stored before that point stay in the database.
+    pub async fn import_group_from_car_with_progress(
+        &self,
+        input: impl AsyncRead + Unpin + Send,
+        options: ImportOptions,
+        progress: UnboundedSender<ImportProgress>,
+        cancel: CancellationToken,
+    ) -> Result<ImportResult> {
+        self.import_group_car(input, options, &Progress::new(progress, cancel))
+            .await
+    }
 
+    async fn import_group_car(
+        &self,
+        input: impl AsyncRead + Unpin + Send,
+        options: ImportOptions,
+        progress: &Progress<ImportProgress>,
+    ) -> Result<ImportResult> {
         // Read all blocks
-        let mut blocks = HashMap::new();
-
-        while let Some((cid, data)) =
-            car_reader
-                .next_block()
-                .await
-                .map_err(|e| CoreError::CarError {
-                    operation: "reading CAR block".to_string(),
-                    cause: e,
-                })?
-        {
-            blocks.insert(cid, data);
-        }
+        let (root_cid, blocks) = read_car_blocks(input, progress).await?;
 
         // Get the root block
         let root_data = blocks.get(&root_cid).ok_or_else(|| CoreError::CarError {
@@ -489,8 +638,12 @@ where
 
         // First import all member agents and preserve their membership data
         let mut imported_memberships = Vec::new();
+        let mut counts = ImportCounts::new(manifest_totals(root_data));
+        let total_agents = group_export.member_agent_cids.len();
 
-        for (_old_agent_id, agent_export_cid) in &group_export.member_agent_cids {
+        for (index, (_old_agent_id, agent_export_cid)) in
+            group_export.member_agent_cids.iter().enumerate()
+        {
             if let Some(agent_export_data) = blocks.get(agent_export_cid) {
                 // New format: AgentExport -> AgentRecordExport -> reconstruct
                 let mut agent: AgentRecord =
@@ -548,11 +701,19 @@ where
                     agent.messages.clear();
                 }
 
+                progress.checkpoint("CAR import")?;
+                progress.report(ImportProgress::Agent {
+                    name: agent.name.clone(),
+                    current: index + 1,
+                    total: total_agents,
+                });
+
                 // Store the agent with relations individually to avoid payload limits
-                let stored_agent = agent
-                    .store_with_relations_individually(&self.db)
-                    .await
-                    .map_err(|e| CoreError::from(e))?;
+                let memory_count = agent.memories.len();
+                let message_count = agent.messages.len();
+                let stored_agent = self
+                    .store_agent_records(agent, &mut counts, progress)
+                    .await?;
 
                 // Find and preserve the original membership data for this agent
                 let original_membership = group_export
@@ -565,8 +726,8 @@ where
                     .agent_id_map
                     .insert(original_id, stored_agent.id.clone());
                 result.agents_imported += 1;
-                result.memories_imported += agent.memories.len();
-                result.messages_imported += agent.messages.len();
+                result.memories_imported += memory_count;
+                result.messages_imported += message_count;
 
                 if let Some(membership) = original_membership {
                     imported_memberships.push((stored_agent.id.clone(), membership));
@@ -630,42 +791,36 @@ where
     /// Import a constellation from a CAR file
     pub async fn import_constellation_from_car(
         &self,
-        mut input: impl AsyncRead + Unpin + Send,
+        input: impl AsyncRead + Unpin + Send,
         options: ImportOptions,
     ) -> Result<ImportResult> {
-        // Read the CAR file
-        let mut car_reader = CarReader::new(&mut input)
+        self.import_constellation_car(input, options, &Progress::default())
             .await
-            .map_err(|e| CoreError::CarError {
-                operation: "reading CAR header".to_string(),
-                cause: e,
-            })?;
+    }
 
-        let root_cid = {
-            let roots = car_reader.header().roots();
-            if roots.is_empty() {
-                return Err(CoreError::CarError {
-                    operation: "reading CAR roots".to_string(),
-                    cause: iroh_car::Error::Parsing("No root CID found".to_string()),
-                });
-            }
-            roots[0]
-        };
+    /// Import a constellation from a CAR file, sending progress events to `progress`
+    ///
+    /// Stops with `CoreError::Cancelled` once `cancel` is cancelled. Agents
+    /// stored before that point stay in the database.
+    pub async fn import_constellation_from_car_with_progress(
+        &self,
+        input: impl AsyncRead + Unpin + Send,
+        options: ImportOptions,
+        progress: UnboundedSender<ImportProgress>,
+        cancel: CancellationToken,
+    ) -> Result<ImportResult> {
+        self.import_constellation_car(input, options, &Progress::new(progress, cancel))
+            .await
+    }
 
+    async fn import_constellation_car(
+        &self,
+        input: impl AsyncRead + Unpin + Send,
+        options: ImportOptions,
+        progress: &Progress<ImportProgress>,
+    ) -> Result<ImportResult> {
         // Read all blocks
-        let mut blocks = HashMap::new();
-
-        while let Some((cid, data)) =
-            car_reader
-                .next_block()
-                .await
-                .map_err(|e| CoreError::CarError {
-                    operation: "reading CAR block".to_string(),
-                    cause: e,
-                })?
-        {
-            blocks.insert(cid, data);
-        }
+        let (root_cid, blocks) = read_car_blocks(input, progress).await?;
 
         // Get the root block
         let root_data = blocks.get(&root_cid).ok_or_else(|| CoreError::CarError {
@@ -711,7 +866,12 @@ where
         };
 
         // Import all agents first
-        for (_old_agent_id, agent_export_cid) in &constellation_export.agent_export_cids {
+        let mut counts = ImportCounts::new(manifest_totals(root_data));
+        let total_agents = constellation_export.agent_export_cids.len();
+
+        for (index, (_old_agent_id, agent_export_cid)) in
+            constellation_export.agent_export_cids.iter().enumerate()
+        {
             if let Some(agent_export_data) = blocks.get(agent_export_cid) {
                 let mut agent: AgentRecord =
                     if let Ok(export) = decode_dag_cbor::<AgentExport>(agent_export_data) {
@@ -767,18 +927,26 @@ where
                     agent.messages.clear();
                 }
 
+                progress.checkpoint("CAR import")?;
+                progress.report(ImportProgress::Agent {
+                    name: agent.name.clone(),
+                    current: index + 1,
+                    total: total_agents,
+                });
+
                 // Store the agent with relations individually to avoid payload limits
-                let stored_agent = agent
-                    .store_with_relations_individually(&self.db)
-                    .await
-                    .map_err(|e| CoreError::from(e))?;
+                let memory_count = agent.memories.len();
+                let message_count = agent.messages.len();
+                let stored_agent = self
+                    .store_agent_records(agent, &mut counts, progress)
+                    .await?;
 
                 result
                     .agent_id_map
                     .insert(original_id, stored_agent.id.clone());
                 result.agents_imported += 1;
-                result.memories_imported += agent.memories.len();
-                result.messages_imported += agent.messages.len();
+                result.memories_imported += memory_count;
+                result.messages_imported += message_count;
             }
         }
 
@@ -911,26 +1079,19 @@ mod tests {
     use crate::db::client;
     use crate::export::{AgentExporter, ExportOptions};
 
-    #[tokio::test]
-    async fn import_roundtrip_agent() {
-        // Build a simple agent with enough data to create multiple chunks
-        use crate::agent::AgentRecord;
+    async fn make_agent(msg_count: usize, mem_count: usize) -> AgentRecord {
         use crate::id::RelationId;
         use crate::memory::{MemoryBlock, MemoryPermission, MemoryType};
         use crate::message::{AgentMessageRelation, Message, MessageRelationType};
         use chrono::Utc;
 
-        let db = client::create_test_db().await.unwrap();
-        let exporter = AgentExporter::new(db.clone());
-
-        // Fabricate an agent in-memory
         let mut agent = AgentRecord {
             name: "RoundTrip".to_string(),
             owner_id: crate::UserId::generate(),
             ..Default::default()
         };
         let mut msgs = Vec::new();
-        for i in 0..1200usize {
+        for i in 0..msg_count {
             tokio::time::sleep(std::time::Duration::from_micros(500)).await;
             let m = Message::user(format!("msg{}", i));
             let rel = AgentMessageRelation {
@@ -948,7 +1109,7 @@ mod tests {
         }
         agent.messages = msgs;
         let mut mems = Vec::new();
-        for i in 0..120usize {
+        for i in 0..mem_count {
             let mb = MemoryBlock {
                 owner_id: agent.owner_id.clone(),
                 label: compact_str::format_compact!("mem{}", i),
@@ -967,10 +1128,20 @@ mod tests {
             mems.push((mb, rel));
         }
         agent.memories = mems;
+        agent
+    }
+
+    #[tokio::test]
+    async fn import_roundtrip_agent() {
+        let db = client::create_test_db().await.unwrap();
+        let exporter = AgentExporter::new(db.clone());
+
+        // Fabricate an agent in-memory with enough data to create multiple chunks
+        let agent = make_agent(1200, 120).await;
 
         // Export to blocks (no CAR), then reconstruct via helper
         let (agent_export, blocks, _stats) = exporter
-            .export_agent_to_blocks(&agent, &ExportOptions::default())
+            .export_agent_to_blocks(&agent, &ExportOptions::default(), &Progress::default())
             .await
             .unwrap();
 
@@ -986,4 +1157,55 @@ mod tests {
         assert!(reconstructed.messages.len() >= 1200);
         assert!(reconstructed.memories.len() >= 120);
     }
+
+    #[tokio::test]
+    async fn import_reports_progress_per_record_and_cancels() {
+        use crate::memory::MemoryBlock;
+
+        let db = client::create_test_db().await.unwrap();
+        let importer = AgentImporter::new(db.clone());
+        let agent = make_agent(5, 3).await;
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let progress = Progress::new(tx, CancellationToken::new());
+        let mut counts = ImportCounts::new((3, 5));
+        importer
+            .store_agent_records(agent.clone(), &mut counts, &progress)
+            .await
+            .unwrap();
+        drop(progress);
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        let mut expected: Vec<_> = (1..=3)
+            .map(|current| ImportProgress::Memories { current, total: 3 })
+            .collect();
+        expected.extend((1..=5).map(|current| ImportProgress::Messages { current, total: 5 }));
+        assert_eq!(events, expected);
+
+        // A cancelled import stops before storing the next record
+        let db = client::create_test_db().await.unwrap();
+        let importer = AgentImporter::new(db.clone());
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let result = importer
+            .store_agent_records(
+                agent.clone(),
+                &mut ImportCounts::new((3, 5)),
+                &Progress::new(tx, cancel),
+            )
+            .await;
+        assert!(matches!(result, Err(CoreError::Cancelled { .. })));
+        assert!(rx.recv().await.is_none());
+        let first_memory = &agent.memories[0].0;
+        assert!(
+            crate::db::ops::get_entity::<MemoryBlock, _>(&db, &first_memory.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
 }
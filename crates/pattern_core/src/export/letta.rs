@@ -0,0 +1,951 @@
+//! Letta (formerly MemGPT) agent file support
+//!
+//! Letta serializes agents as `.af` JSON documents holding the compiled system
+//! prompt, core memory blocks, message history, tool definitions and tool rules.
+//! This module maps those documents onto [`AgentRecord`] and back so agents can
+//! be migrated between the two runtimes.
+
+use chrono::{DateTime, Utc};
+use compact_str::CompactString;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{
+    CoreError, Result, UserId,
+    agent::{
+        AgentMemoryRelation, AgentRecord, AgentType, SnowflakePosition,
+        get_next_message_position_sync,
+    },
+    config::{ToolRuleConfig, ToolRuleTypeConfig},
+    id::RelationId,
+    memory::{MemoryBlock, MemoryPermission, MemoryType},
+    message::{
+        AgentMessageRelation, BatchType, ChatRole, ContentBlock, ContentPart, Message,
+        MessageContent, MessageRelationType, ToolCall, ToolResponse,
+    },
+};
+
+/// Agent file version written by the exporter
+pub const LETTA_AGENT_FILE_VERSION: &str = "0.10.0";
+
+/// Key in `AgentRecord::model_config` holding the original Letta `llm_config`
+const LLM_CONFIG_KEY: &str = "letta_llm_config";
+
+/// Key in `AgentRecord::model_config` holding the original Letta tool definitions
+const TOOLS_KEY: &str = "letta_tools";
+
+/// Key in `MemoryBlock::metadata` holding the per-block character limit
+const CHAR_LIMIT_KEY: &str = "char_limit";
+
+/// A Letta agent file (`.af`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LettaAgentFile {
+    pub name: String,
+    #[serde(default)]
+    pub agent_type: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Compiled system prompt, base instructions wrapped in `<base_instructions>`
+    #[serde(default)]
+    pub system: String,
+    #[serde(default)]
+    pub core_memory: Vec<LettaBlock>,
+    #[serde(default)]
+    pub messages: Vec<LettaMessage>,
+    /// Indices into `messages` that are in the agent's context window
+    #[serde(default)]
+    pub in_context_message_indices: Vec<usize>,
+    #[serde(default)]
+    pub message_buffer_autoclear: bool,
+    #[serde(default)]
+    pub tools: Vec<Value>,
+    #[serde(default)]
+    pub tool_rules: Vec<LettaToolRule>,
+    #[serde(default)]
+    pub llm_config: Option<Value>,
+    #[serde(default)]
+    pub embedding_config: Option<Value>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub metadata_: Option<Value>,
+    #[serde(default)]
+    pub multi_agent_group: Option<Value>,
+    #[serde(default)]
+    pub tool_exec_environment_variables: Vec<Value>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// A Letta core memory block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LettaBlock {
+    pub label: String,
+    #[serde(default)]
+    pub value: String,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub read_only: Option<bool>,
+    #[serde(default)]
+    pub is_template: bool,
+    #[serde(default)]
+    pub template_name: Option<String>,
+    #[serde(default)]
+    pub metadata_: Option<Value>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A message in a Letta agent's history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LettaMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Vec<LettaContent>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub group_id: Option<String>,
+    #[serde(default)]
+    pub tool_call_id: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Vec<LettaToolCall>,
+    #[serde(default)]
+    pub tool_returns: Vec<LettaToolReturn>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A content part of a Letta message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LettaContent {
+    Text {
+        text: String,
+    },
+    /// Reasoning, images and other parts Pattern doesn't carry over
+    #[serde(other)]
+    Other,
+}
+
+/// An OpenAI-style tool call recorded on an assistant message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LettaToolCall {
+    pub id: String,
+    #[serde(rename = "type", default = "default_tool_call_type")]
+    pub call_type: String,
+    pub function: LettaFunctionCall,
+}
+
+/// Function name and JSON-encoded arguments of a tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LettaFunctionCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: String,
+}
+
+/// Execution status attached to a tool message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LettaToolReturn {
+    pub status: String,
+    #[serde(default)]
+    pub stdout: Option<Vec<String>>,
+    #[serde(default)]
+    pub stderr: Option<Vec<String>>,
+}
+
+/// A Letta tool rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LettaToolRule {
+    pub tool_name: String,
+    #[serde(rename = "type")]
+    pub rule_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_count_limit: Option<u32>,
+}
+
+fn default_tool_call_type() -> String {
+    "function".to_string()
+}
+
+/// Pattern equivalent for one of Letta's built-in tools
+fn pattern_tool_name(letta_tool: &str) -> &str {
+    match letta_tool {
+        "core_memory_append"
+        | "core_memory_replace"
+        | "memory_insert"
+        | "memory_replace"
+        | "memory_rethink" => "context",
+        "archival_memory_insert" | "archival_memory_search" => "recall",
+        "conversation_search" => "search",
+        other => other,
+    }
+}
+
+/// Strip Letta's `<base_instructions>` wrapper from a compiled system prompt
+fn base_instructions(system: &str) -> &str {
+    match (
+        system.find("<base_instructions>"),
+        system.find("</base_instructions>"),
+    ) {
+        (Some(start), Some(end)) if start < end => {
+            system[start + "<base_instructions>".len()..end].trim()
+        }
+        _ => system.trim(),
+    }
+}
+
+/// Letta wraps user input as `{"type": "user_message", "message": ...}`
+fn unwrap_user_message(text: String) -> String {
+    match serde_json::from_str::<Value>(&text) {
+        Ok(Value::Object(obj))
+            if obj.get("type").and_then(Value::as_str) == Some("user_message") =>
+        {
+            obj.get("message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or(text)
+        }
+        _ => text,
+    }
+}
+
+fn letta_time(at: &DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %I:%M:%S %p UTC%z").to_string()
+}
+
+impl LettaToolRule {
+    fn to_pattern(&self) -> Option<ToolRuleConfig> {
+        let tool_name = pattern_tool_name(&self.tool_name).to_string();
+        let (rule_type, conditions) = match self.rule_type.as_str() {
+            "continue_loop" => (ToolRuleTypeConfig::ContinueLoop, Vec::new()),
+            "exit_loop" => (ToolRuleTypeConfig::ExitLoop, Vec::new()),
+            "run_first" => (ToolRuleTypeConfig::StartConstraint, Vec::new()),
+            "required_before_exit" => (ToolRuleTypeConfig::RequiredBeforeExit, Vec::new()),
+            "max_count_per_step" => (
+                ToolRuleTypeConfig::MaxCalls(self.max_count_limit?),
+                Vec::new(),
+            ),
+            "constrain_child_tools" => (
+                ToolRuleTypeConfig::RequiresFollowingTools,
+                self.children
+                    .iter()
+                    .flatten()
+                    .map(|c| pattern_tool_name(c).to_string())
+                    .collect(),
+            ),
+            other => {
+                tracing::warn!(
+                    "Skipping Letta tool rule '{}' for {}: no Pattern equivalent",
+                    other,
+                    self.tool_name
+                );
+                return None;
+            }
+        };
+
+        Some(ToolRuleConfig {
+            tool_name,
+            rule_type,
+            conditions,
+            priority: 5,
+            metadata: None,
+        })
+    }
+
+    fn from_pattern(rule: &ToolRuleConfig) -> Option<Self> {
+        let (rule_type, children, max_count_limit) = match &rule.rule_type {
+            ToolRuleTypeConfig::ContinueLoop => ("continue_loop", None, None),
+            ToolRuleTypeConfig::ExitLoop => ("exit_loop", None, None),
+            ToolRuleTypeConfig::StartConstraint => ("run_first", None, None),
+            ToolRuleTypeConfig::RequiredBeforeExit => ("required_before_exit", None, None),
+            ToolRuleTypeConfig::MaxCalls(max) => ("max_count_per_step", None, Some(*max)),
+            ToolRuleTypeConfig::RequiresFollowingTools => {
+                ("constrain_child_tools", Some(rule.conditions.clone()), None)
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            tool_name: rule.tool_name.clone(),
+            rule_type: rule_type.to_string(),
+            children,
+            max_count_limit,
+        })
+    }
+}
+
+impl LettaMessage {
+    fn text(&self) -> String {
+        self.content
+            .iter()
+            .filter_map(|c| match c {
+                LettaContent::Text { text } => Some(text.as_str()),
+                LettaContent::Other => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn tool_call(&self) -> Vec<ToolCall> {
+        self.tool_calls
+            .iter()
+            .map(|call| ToolCall {
+                call_id: call.id.clone(),
+                fn_name: pattern_tool_name(&call.function.name).to_string(),
+                fn_arguments: serde_json::from_str(&call.function.arguments)
+                    .unwrap_or_else(|_| Value::String(call.function.arguments.clone())),
+            })
+            .collect()
+    }
+
+    fn new(role: &str, text: Option<String>, created_at: DateTime<Utc>) -> Self {
+        Self {
+            role: role.to_string(),
+            content: text
+                .into_iter()
+                .map(|text| LettaContent::Text { text })
+                .collect(),
+            name: None,
+            model: None,
+            group_id: None,
+            tool_call_id: None,
+            tool_calls: Vec::new(),
+            tool_returns: Vec::new(),
+            created_at: Some(created_at),
+            updated_at: Some(created_at),
+        }
+    }
+}
+
+impl LettaAgentFile {
+    /// Parse an agent file from its JSON bytes
+    pub fn from_slice(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data).map_err(|e| CoreError::SerializationError {
+            data_type: "LettaAgentFile".to_string(),
+            cause: e,
+        })
+    }
+
+    /// Serialize the agent file as pretty-printed JSON
+    pub fn to_vec(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec_pretty(self).map_err(|e| CoreError::SerializationError {
+            data_type: "LettaAgentFile".to_string(),
+            cause: e,
+        })
+    }
+
+    /// Convert into an agent record owned by `owner_id`
+    ///
+    /// Core memory blocks become pinned core memories, the compiled system prompt's
+    /// base instructions become the agent's instructions, and Letta's own memory
+    /// tools are renamed to their Pattern equivalents in tool calls and rules. The
+    /// original `llm_config` and tool definitions are kept in `model_config` so a
+    /// later export can round-trip them.
+    pub fn into_agent_record(self, owner_id: &UserId) -> AgentRecord {
+        let now = Utc::now();
+        let created_at = self.created_at.unwrap_or(now);
+        let mut agent = AgentRecord {
+            name: self.name,
+            agent_type: self
+                .agent_type
+                .map(AgentType::Custom)
+                .unwrap_or(AgentType::Generic),
+            base_instructions: base_instructions(&self.system).to_string(),
+            owner_id: owner_id.clone(),
+            created_at,
+            updated_at: self.updated_at.unwrap_or(created_at),
+            last_active: self.updated_at.unwrap_or(created_at),
+            ..Default::default()
+        };
+
+        if let Some(llm_config) = self.llm_config {
+            agent.model_id = llm_config
+                .get("model")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            for key in ["temperature", "max_tokens", "context_window"] {
+                if let Some(value) = llm_config.get(key).filter(|v| !v.is_null()) {
+                    agent.model_config.insert(key.to_string(), value.clone());
+                }
+            }
+            if let Some(provider) = llm_config.get("provider_name").filter(|v| !v.is_null()) {
+                agent
+                    .model_config
+                    .insert("provider".to_string(), provider.clone());
+            }
+            agent
+                .model_config
+                .insert(LLM_CONFIG_KEY.to_string(), llm_config);
+        }
+        if !self.tools.is_empty() {
+            agent
+                .model_config
+                .insert(TOOLS_KEY.to_string(), Value::Array(self.tools));
+        }
+
+        for rule in self.tool_rules.iter().filter_map(LettaToolRule::to_pattern) {
+            if !agent.tool_rules.iter().any(|r| {
+                r.tool_name == rule.tool_name
+                    && std::mem::discriminant(&r.rule_type)
+                        == std::mem::discriminant(&rule.rule_type)
+                    && r.conditions == rule.conditions
+            }) {
+                agent.tool_rules.push(rule);
+            }
+        }
+
+        if let Some(max_limit) = self.core_memory.iter().filter_map(|b| b.limit).max() {
+            agent.memory_char_limit = max_limit;
+        }
+        for block in self.core_memory {
+            let permission = if block.read_only.unwrap_or(false) {
+                MemoryPermission::ReadOnly
+            } else {
+                MemoryPermission::ReadWrite
+            };
+            let mut metadata = json!({});
+            if let Some(limit) = block.limit {
+                metadata[CHAR_LIMIT_KEY] = json!(limit);
+            }
+            if let Some(extra) = block
+                .metadata_
+                .filter(|m| m.as_object().is_some_and(|o| !o.is_empty()))
+            {
+                metadata["letta"] = extra;
+            }
+            let memory = MemoryBlock {
+                owner_id: owner_id.clone(),
+                label: CompactString::from(block.label),
+                value: block.value,
+                description: block.description,
+                memory_type: MemoryType::Core,
+                pinned: true,
+                permission,
+                metadata,
+                created_at: block.created_at.unwrap_or(created_at),
+                updated_at: block.updated_at.unwrap_or(created_at),
+                ..Default::default()
+            };
+            let relation = AgentMemoryRelation {
+                id: RelationId::nil(),
+                in_id: agent.id.clone(),
+                out_id: memory.id.clone(),
+                access_level: permission,
+                created_at: memory.created_at,
+            };
+            agent.memories.push((memory, relation));
+        }
+
+        // Pattern compiles its own system prompt, so drop Letta's leading copy
+        let skip_leading = self.messages.first().is_some_and(|m| m.role == "system");
+        let mut batch: Option<(SnowflakePosition, BatchType)> = None;
+        let mut sequence_num = 0u32;
+        for (index, letta) in self.messages.iter().enumerate() {
+            if index == 0 && skip_leading {
+                continue;
+            }
+            let text = letta.text();
+            let mut message = match letta.role.as_str() {
+                "user" => Message::user(unwrap_user_message(text)),
+                "system" => Message::system(text),
+                "assistant" => {
+                    let calls = letta.tool_call();
+                    let content = match (text.is_empty(), calls.is_empty()) {
+                        (_, true) => MessageContent::Text(text),
+                        (true, false) => MessageContent::ToolCalls(calls),
+                        (false, false) => {
+                            let mut blocks = vec![ContentBlock::Text {
+                                text,
+                                thought_signature: None,
+                            }];
+                            blocks.extend(calls.into_iter().map(|call| ContentBlock::ToolUse {
+                                id: call.call_id,
+                                name: call.fn_name,
+                                input: call.fn_arguments,
+                                thought_signature: None,
+                            }));
+                            MessageContent::Blocks(blocks)
+                        }
+                    };
+                    Message::agent(content)
+                }
+                "tool" => {
+                    let is_error = letta
+                        .tool_returns
+                        .first()
+                        .map(|r| r.status == "error")
+                        .filter(|e| *e);
+                    Message::tool(vec![ToolResponse {
+                        call_id: letta.tool_call_id.clone().unwrap_or_default(),
+                        content: text,
+                        is_error,
+                    }])
+                }
+                other => {
+                    tracing::warn!("Skipping Letta message with unknown role '{}'", other);
+                    continue;
+                }
+            };
+
+            // User and system messages open a new batch, the rest continue it
+            let position = message
+                .position
+                .unwrap_or_else(get_next_message_position_sync);
+            let (batch_id, batch_type) = match (&message.role, batch) {
+                (ChatRole::User, _) => (position, BatchType::UserRequest),
+                (ChatRole::System, _) => (position, BatchType::SystemTrigger),
+                (_, Some(current)) => current,
+                (_, None) => (position, BatchType::SystemTrigger),
+            };
+            if batch.is_none_or(|(id, _)| id != batch_id) {
+                batch = Some((batch_id, batch_type));
+                sequence_num = 0;
+            }
+            message.position = Some(position);
+            message.batch = Some(batch_id);
+            message.sequence_num = Some(sequence_num);
+            message.batch_type = Some(batch_type);
+            sequence_num += 1;
+            if let Some(at) = letta.created_at {
+                message.created_at = at;
+            }
+            message.owner_id = Some(owner_id.clone());
+
+            let message_type = if self.in_context_message_indices.contains(&index) {
+                MessageRelationType::Active
+            } else {
+                MessageRelationType::Archived
+            };
+            let relation = AgentMessageRelation {
+                id: RelationId::nil(),
+                in_id: agent.id.clone(),
+                out_id: message.id.clone(),
+                message_type,
+                position: message.position,
+                added_at: message.created_at,
+                batch: message.batch,
+                sequence_num: message.sequence_num,
+                batch_type: message.batch_type,
+            };
+            agent.messages.push((message, relation));
+        }
+        agent.total_messages = agent.messages.len();
+
+        agent
+    }
+
+    /// Build an agent file from an agent record with its memories and messages loaded
+    ///
+    /// Core and working memories become core memory blocks; archival memories have
+    /// no `.af` counterpart and are left out. Tool definitions are only written when
+    /// the agent was originally imported from Letta, since Pattern's built-in tools
+    /// have no Python source for Letta to run.
+    pub fn from_agent_record(agent: &AgentRecord) -> Self {
+        let core_memory = agent
+            .memories
+            .iter()
+            .filter(|(block, _)| block.is_active && block.memory_type != MemoryType::Archival)
+            .map(|(block, _)| {
+                let letta_extra = block.metadata.get("letta").cloned();
+                LettaBlock {
+                    label: block.label.to_string(),
+                    value: block.value.clone(),
+                    limit: Some(
+                        block
+                            .metadata
+                            .get(CHAR_LIMIT_KEY)
+                            .and_then(Value::as_u64)
+                            .map(|l| l as usize)
+                            .unwrap_or(agent.memory_char_limit),
+                    ),
+                    description: block.description.clone(),
+                    read_only: Some(matches!(block.permission, MemoryPermission::ReadOnly)),
+                    is_template: false,
+                    template_name: None,
+                    metadata_: Some(letta_extra.unwrap_or_else(|| json!({}))),
+                    created_at: Some(block.created_at),
+                    updated_at: Some(block.updated_at),
+                }
+            })
+            .collect();
+
+        let system = format!(
+            "<base_instructions>\n{}\n</base_instructions>",
+            agent.base_instructions
+        );
+        let mut messages = vec![LettaMessage::new(
+            "system",
+            Some(system.clone()),
+            agent.created_at,
+        )];
+        let mut in_context_message_indices = vec![0];
+
+        let mut history: Vec<_> = agent.messages.iter().collect();
+        history.sort_by(|(a, _), (b, _)| a.position.cmp(&b.position));
+        for (message, relation) in history {
+            let first_index = messages.len();
+            let text = message_text(&message.content);
+            match message.role {
+                ChatRole::System => {
+                    messages.push(LettaMessage::new("system", Some(text), message.created_at))
+                }
+                ChatRole::User => {
+                    let wrapped = json!({
+                        "type": "user_message",
+                        "message": text,
+                        "time": letta_time(&message.created_at),
+                    });
+                    messages.push(LettaMessage::new(
+                        "user",
+                        Some(wrapped.to_string()),
+                        message.created_at,
+                    ));
+                }
+                ChatRole::Assistant => {
+                    let mut letta = LettaMessage::new(
+                        "assistant",
+                        Some(text).filter(|t| !t.is_empty()),
+                        message.created_at,
+                    );
+                    letta.model = agent.model_id.clone();
+                    letta.tool_calls = message_tool_calls(&message.content);
+                    messages.push(letta);
+                }
+                ChatRole::Tool => {
+                    let MessageContent::ToolResponses(responses) = &message.content else {
+                        continue;
+                    };
+                    for response in responses {
+                        let mut letta = LettaMessage::new(
+                            "tool",
+                            Some(response.content.clone()),
+                            message.created_at,
+                        );
+                        letta.tool_call_id = Some(response.call_id.clone());
+                        letta.tool_returns = vec![LettaToolReturn {
+                            status: if response.is_error == Some(true) {
+                                "error".to_string()
+                            } else {
+                                "success".to_string()
+                            },
+                            stdout: None,
+                            stderr: None,
+                        }];
+                        messages.push(letta);
+                    }
+                }
+            }
+            if relation.message_type == MessageRelationType::Active {
+                in_context_message_indices.extend(first_index..messages.len());
+            }
+        }
+
+        let llm_config = agent
+            .model_config
+            .get(LLM_CONFIG_KEY)
+            .cloned()
+            .or_else(|| {
+                let model = agent.model_id.as_ref()?;
+                Some(json!({
+                    "model": model,
+                    "model_endpoint_type": agent.model_config.get("provider").and_then(Value::as_str).map(str::to_lowercase),
+                    "context_window": agent.model_config.get("context_window").cloned().unwrap_or(json!(32000)),
+                    "temperature": agent.model_config.get("temperature").cloned().unwrap_or(json!(0.7)),
+                    "max_tokens": agent.model_config.get("max_tokens").cloned(),
+                }))
+            });
+        let tools = agent
+            .model_config
+            .get(TOOLS_KEY)
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        Self {
+            name: agent.name.clone(),
+            agent_type: Some(match &agent.agent_type {
+                AgentType::Custom(name) => name.clone(),
+                _ => "memgpt_agent".to_string(),
+            }),
+            description: None,
+            system,
+            core_memory,
+            messages,
+            in_context_message_indices,
+            message_buffer_autoclear: false,
+            tools,
+            tool_rules: agent
+                .tool_rules
+                .iter()
+                .filter_map(LettaToolRule::from_pattern)
+                .collect(),
+            llm_config,
+            embedding_config: None,
+            tags: Vec::new(),
+            metadata_: None,
+            multi_agent_group: None,
+            tool_exec_environment_variables: Vec::new(),
+            created_at: Some(agent.created_at),
+            updated_at: Some(agent.updated_at),
+            version: Some(LETTA_AGENT_FILE_VERSION.to_string()),
+        }
+    }
+}
+
+/// Plain text of a message, dropping tool calls and thinking
+fn message_text(content: &MessageContent) -> String {
+    match content {
+        MessageContent::Text(text) => text.clone(),
+        MessageContent::Parts(parts) => parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        MessageContent::Blocks(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        MessageContent::ToolCalls(_) | MessageContent::ToolResponses(_) => String::new(),
+    }
+}
+
+fn message_tool_calls(content: &MessageContent) -> Vec<LettaToolCall> {
+    let to_letta = |id: &str, name: &str, arguments: &Value| LettaToolCall {
+        id: id.to_string(),
+        call_type: default_tool_call_type(),
+        function: LettaFunctionCall {
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+        },
+    };
+    match content {
+        MessageContent::ToolCalls(calls) => calls
+            .iter()
+            .map(|c| to_letta(&c.call_id, &c.fn_name, &c.fn_arguments))
+            .collect(),
+        MessageContent::Blocks(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::ToolUse {
+                    id, name, input, ..
+                } => Some(to_letta(id, name, input)),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> LettaAgentFile {
+        LettaAgentFile::from_slice(
+            json!({
+                "agent_type": "memgpt_agent",
+                "name": "vertical-crimson-antelope",
+                "system": "<base_instructions>\nYou are Letta.\n</base_instructions>",
+                "core_memory": [
+                    {
+                        "label": "human",
+                        "value": "Nothing known yet.",
+                        "limit": 5000,
+                        "description": "The human block",
+                        "is_template": false,
+                        "metadata_": {},
+                        "created_at": "2025-07-04T18:09:11.258469+00:00",
+                        "updated_at": "2025-07-04T22:09:30.634169+00:00"
+                    },
+                    { "label": "persona", "value": "I am Sam.", "limit": 8000 }
+                ],
+                "in_context_message_indices": [0, 1, 2, 3],
+                "messages": [
+                    { "role": "system", "content": [{ "type": "text", "text": "compiled" }] },
+                    {
+                        "role": "user",
+                        "content": [{ "type": "text", "text": "{\"type\": \"user_message\", \"message\": \"hi there\"}" }],
+                        "created_at": "2025-07-04T18:09:11.483460+00:00"
+                    },
+                    {
+                        "role": "assistant",
+                        "content": [{ "type": "text", "text": "Greeting the user." }],
+                        "tool_calls": [{
+                            "id": "call_1",
+                            "type": "function",
+                            "function": { "name": "send_message", "arguments": "{\"message\": \"hello!\"}" }
+                        }]
+                    },
+                    {
+                        "role": "tool",
+                        "tool_call_id": "call_1",
+                        "content": [{ "type": "text", "text": "None" }],
+                        "tool_returns": [{ "status": "success" }]
+                    }
+                ],
+                "tool_rules": [
+                    { "tool_name": "core_memory_replace", "type": "continue_loop" },
+                    { "tool_name": "core_memory_append", "type": "continue_loop" },
+                    { "tool_name": "send_message", "type": "exit_loop" }
+                ],
+                "llm_config": { "model": "gpt-4o-mini", "provider_name": "openai", "context_window": 32000 },
+                "tools": [{ "name": "send_message", "tool_type": "letta_core" }],
+                "version": "0.10.0"
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_import_maps_blocks_messages_and_rules() {
+        let owner = UserId::generate();
+        let agent = sample().into_agent_record(&owner);
+
+        assert_eq!(agent.name, "vertical-crimson-antelope");
+        assert_eq!(agent.base_instructions, "You are Letta.");
+        assert_eq!(agent.model_id.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(agent.memory_char_limit, 8000);
+
+        let (human, _) = &agent.memories[0];
+        assert_eq!(human.label, "human");
+        assert_eq!(human.description.as_deref(), Some("The human block"));
+        assert_eq!(human.memory_type, MemoryType::Core);
+        assert_eq!(human.metadata[CHAR_LIMIT_KEY], 5000);
+
+        // Letta's compiled system message is dropped
+        assert_eq!(agent.messages.len(), 3);
+        let (user, _) = &agent.messages[0];
+        assert_eq!(user.content.text(), Some("hi there"));
+        let (assistant, _) = &agent.messages[1];
+        assert!(assistant.has_tool_calls());
+        assert_eq!(assistant.batch, user.batch);
+        let (tool, rel) = &agent.messages[2];
+        assert!(tool.role.is_tool());
+        assert_eq!(rel.message_type, MessageRelationType::Active);
+
+        // Both core memory tools collapse onto a single `context` rule
+        assert_eq!(agent.tool_rules.len(), 2);
+        assert_eq!(agent.tool_rules[0].tool_name, "context");
+        assert_eq!(agent.tool_rules[1].tool_name, "send_message");
+    }
+
+    #[test]
+    fn test_import_shipped_agent_file() {
+        let data = include_str!("../../../../vertical-crimson-antelope.af");
+        let owner = UserId::generate();
+        let agent = LettaAgentFile::from_slice(data.as_bytes())
+            .unwrap()
+            .into_agent_record(&owner);
+
+        assert_eq!(agent.name, "sa-agent-2baad9e7-23cc-4217-ba2c-e74917007d96");
+        assert_eq!(agent.agent_type, AgentType::Custom("memgpt_agent".into()));
+        assert!(agent.base_instructions.starts_with("You are Letta"));
+        assert!(!agent.base_instructions.contains("<base_instructions>"));
+        assert_eq!(agent.model_id.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(agent.memory_char_limit, 5000);
+
+        let labels: Vec<_> = agent
+            .memories
+            .iter()
+            .map(|(m, _)| m.label.as_str())
+            .collect();
+        assert_eq!(labels, ["human", "persona"]);
+        for (block, relation) in &agent.memories {
+            assert_eq!(block.memory_type, MemoryType::Core);
+            assert_eq!(block.metadata[CHAR_LIMIT_KEY], 5000);
+            assert!(block.description.is_some());
+            assert!(!block.value.is_empty());
+            assert_eq!(relation.out_id, block.id);
+        }
+
+        // Only the login event survives once the compiled system prompt is dropped
+        assert_eq!(agent.messages.len(), 1);
+        let (login, relation) = &agent.messages[0];
+        assert_eq!(login.role, ChatRole::User);
+        assert!(
+            login
+                .content
+                .text()
+                .unwrap()
+                .contains("\"type\": \"login\"")
+        );
+        assert_eq!(login.batch_type, Some(BatchType::UserRequest));
+        assert_eq!(relation.message_type, MessageRelationType::Active);
+
+        // core_memory_replace and core_memory_append share the `context` rule
+        let names: Vec<_> = agent
+            .tool_rules
+            .iter()
+            .map(|r| r.tool_name.as_str())
+            .collect();
+        assert_eq!(names, ["context", "search", "send_message"]);
+        assert!(matches!(
+            agent.tool_rules[1].rule_type,
+            ToolRuleTypeConfig::ContinueLoop
+        ));
+        assert!(matches!(
+            agent.tool_rules[2].rule_type,
+            ToolRuleTypeConfig::ExitLoop
+        ));
+        assert_eq!(agent.model_config[TOOLS_KEY].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let owner = UserId::generate();
+        let agent = sample().into_agent_record(&owner);
+        let exported = LettaAgentFile::from_agent_record(&agent);
+
+        assert_eq!(exported.agent_type.as_deref(), Some("memgpt_agent"));
+        assert_eq!(exported.core_memory.len(), 2);
+        assert_eq!(exported.core_memory[1].limit, Some(8000));
+        assert_eq!(exported.tools.len(), 1);
+        assert_eq!(
+            exported.llm_config.as_ref().unwrap()["model"],
+            "gpt-4o-mini"
+        );
+        // Leading system message plus user, assistant and tool
+        assert_eq!(exported.messages.len(), 4);
+        assert_eq!(exported.in_context_message_indices, vec![0, 1, 2, 3]);
+        assert_eq!(
+            exported.messages[2].tool_calls[0].function.name,
+            "send_message"
+        );
+        assert_eq!(exported.messages[3].tool_call_id.as_deref(), Some("call_1"));
+
+        let bytes = exported.to_vec().unwrap();
+        let reimported = LettaAgentFile::from_slice(&bytes)
+            .unwrap()
+            .into_agent_record(&owner);
+        assert_eq!(reimported.base_instructions, agent.base_instructions);
+        assert_eq!(reimported.memories.len(), agent.memories.len());
+        assert_eq!(reimported.messages.len(), agent.messages.len());
+        assert_eq!(reimported.messages[0].0.content.text(), Some("hi there"));
+    }
+}
@@ -1,14 +1,21 @@
 //! Agent export/import functionality using DAG-CBOR CAR archives
 //!
 //! This module provides tools for exporting agents to portable CAR files
-//! and importing them back, preserving all relationships and data.
+//! and importing them back, preserving all relationships and data. Letta `.af`
+//! agent files are also supported for migrating agents from Letta/MemGPT.
 
 mod exporter;
 mod importer;
+mod letta;
+mod progress;
 mod types;
 
 pub use exporter::{AgentExporter, ExportOptions};
 pub use importer::{AgentImporter, ImportOptions, ImportResult};
+pub use letta::{
+    LETTA_AGENT_FILE_VERSION, LettaAgentFile, LettaBlock, LettaMessage, LettaToolRule,
+};
+pub use progress::{CancellationToken, ExportProgress, ImportProgress};
 pub use types::{
     AgentExport, AgentRecordExport, ConstellationExport, ExportManifest, ExportStats, ExportType,
     GroupExport, MemoryChunk, MessageChunk,
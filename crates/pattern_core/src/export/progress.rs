@@ -0,0 +1,145 @@
+//! Progress reporting and cancellation for CAR export/import
+
+use std::{
+    io,
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Context, Poll},
+};
+
+use tokio::{io::AsyncWrite, sync::mpsc::UnboundedSender};
+pub use tokio_util::sync::CancellationToken;
+
+use crate::{CoreError, Result};
+
+/// Progress event emitted while exporting to a CAR file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportProgress {
+    /// Started exporting an agent
+    Agent {
+        name: String,
+        current: usize,
+        total: usize,
+    },
+
+    /// Memory blocks of the current agent packed into chunks
+    Memories { current: u64, total: u64 },
+
+    /// Messages of the current agent packed into chunks
+    Messages { current: u64, total: u64 },
+
+    /// Blocks written to the output, and the bytes they took
+    Writing {
+        blocks: u64,
+        total_blocks: u64,
+        bytes: u64,
+    },
+}
+
+/// Progress event emitted while importing from a CAR file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportProgress {
+    /// Blocks read from the input, and the bytes they took
+    Reading { blocks: u64, bytes: u64 },
+
+    /// Started importing an agent
+    Agent {
+        name: String,
+        current: usize,
+        total: usize,
+    },
+
+    /// Memory blocks stored so far. `total` comes from the manifest and is 0
+    /// for archives written before manifests existed
+    Memories { current: u64, total: u64 },
+
+    /// Messages stored so far, with `total` taken from the manifest like memories
+    Messages { current: u64, total: u64 },
+}
+
+/// Where progress events go and whether the work should stop
+#[derive(Debug, Clone)]
+pub(crate) struct Progress<P> {
+    sender: Option<UnboundedSender<P>>,
+    cancel: CancellationToken,
+}
+
+impl<P> Default for Progress<P> {
+    fn default() -> Self {
+        Self {
+            sender: None,
+            cancel: CancellationToken::new(),
+        }
+    }
+}
+
+impl<P> Progress<P> {
+    pub(crate) fn new(sender: UnboundedSender<P>, cancel: CancellationToken) -> Self {
+        Self {
+            sender: Some(sender),
+            cancel,
+        }
+    }
+
+    /// Send an event; a receiver that went away is not an error
+    pub(crate) fn report(&self, event: P) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(event);
+        }
+    }
+
+    /// Fail with `CoreError::Cancelled` once the token has been cancelled
+    pub(crate) fn checkpoint(&self, operation: &str) -> Result<()> {
+        if self.cancel.is_cancelled() {
+            return Err(CoreError::Cancelled {
+                operation: operation.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Writer that counts the bytes passed through to the inner writer
+pub(crate) struct CountingWriter<W> {
+    inner: W,
+    written: Arc<AtomicU64>,
+}
+
+impl<W> CountingWriter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        Self {
+            inner,
+            written: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Shared counter that stays readable while the writer is borrowed
+    pub(crate) fn counter(&self) -> Arc<AtomicU64> {
+        self.written.clone()
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for CountingWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = &poll {
+            self.written.fetch_add(*written as u64, Ordering::Relaxed);
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,597 @@
+//! iCalendar (.ics) import and export for [`BaseEvent`]
+//!
+//! Only what Pattern can represent is carried across: each `VEVENT` becomes a
+//! single event with its summary, description, location, first category, start
+//! and duration. Recurrence rules, alarms and attendees are ignored on import,
+//! so a recurring series comes in as its first occurrence.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::db::BaseEvent;
+use crate::id::{IdType, UserId};
+
+/// PRODID written to exported calendars
+const PRODID: &str = "-//Pattern//Pattern Calendar//EN";
+
+/// Lines longer than this many octets are folded (RFC 5545 §3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Errors from parsing an iCalendar file
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+pub enum IcsError {
+    #[error("Not an iCalendar file: no BEGIN:VCALENDAR found")]
+    #[diagnostic(help("Check that the file is a .ics export from a calendar application"))]
+    NotACalendar,
+
+    #[error("Invalid {property} value '{value}' on line {line}")]
+    InvalidValue {
+        property: String,
+        value: String,
+        line: usize,
+    },
+
+    #[error("Event ending on line {line} has no DTSTART")]
+    MissingStart { line: usize },
+
+    #[error("{component} component is never closed")]
+    Unterminated { component: String },
+}
+
+/// Serialize events as an iCalendar document
+pub fn to_ics(events: &[BaseEvent]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for event in events {
+        let uid = event
+            .ical_uid
+            .clone()
+            .unwrap_or_else(|| format!("{}@pattern", event.id.to_key()));
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", uid));
+        lines.push(format!("DTSTAMP:{}", format_utc(event.updated_at)));
+        lines.push(format!("DTSTART:{}", format_utc(event.scheduled_for)));
+        if let Some(end) = event.ends_at() {
+            lines.push(format!("DTEND:{}", format_utc(end)));
+        }
+        lines.push(format!("SUMMARY:{}", escape_text(&event.title)));
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        if let Some(location) = &event.location {
+            lines.push(format!("LOCATION:{}", escape_text(location)));
+        }
+        lines.push(format!("CATEGORIES:{}", escape_text(&event.event_type)));
+        lines.push(format!("CREATED:{}", format_utc(event.created_at)));
+        lines.push(format!("LAST-MODIFIED:{}", format_utc(event.updated_at)));
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold_line(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Parse the events in an iCalendar document
+///
+/// Events get fresh IDs and are owned by `creator_id`; their UID is kept in
+/// [`BaseEvent::ical_uid`] so callers can match them against earlier imports.
+/// Cancelled events are skipped.
+pub fn parse_ics(input: &str, creator_id: &UserId) -> Result<Vec<BaseEvent>, IcsError> {
+    let lines = unfold_lines(input);
+    if !lines
+        .iter()
+        .any(|(_, line)| line.eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err(IcsError::NotACalendar);
+    }
+
+    let mut events = Vec::new();
+    // Component nesting, so properties of a VALARM inside a VEVENT are ignored
+    let mut stack: Vec<String> = Vec::new();
+    let mut current: Option<PendingEvent> = None;
+
+    for (line_number, line) in &lines {
+        let Some(property) = ContentLine::parse(line) else {
+            continue;
+        };
+
+        match property.name.as_str() {
+            "BEGIN" => {
+                let component = property.value.to_ascii_uppercase();
+                if component == "VEVENT" && stack.last().map(String::as_str) == Some("VCALENDAR") {
+                    current = Some(PendingEvent::default());
+                }
+                stack.push(component);
+            }
+            "END" => {
+                let component = property.value.to_ascii_uppercase();
+                if stack.last() == Some(&component) {
+                    stack.pop();
+                }
+                if component == "VEVENT" && stack.last().map(String::as_str) == Some("VCALENDAR") {
+                    if let Some(pending) = current.take() {
+                        if let Some(event) = pending.finish(*line_number, creator_id)? {
+                            events.push(event);
+                        }
+                    }
+                }
+            }
+            _ if stack.last().map(String::as_str) == Some("VEVENT") => {
+                if let Some(pending) = current.as_mut() {
+                    pending.apply(&property, *line_number)?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(component) = stack.pop() {
+        return Err(IcsError::Unterminated { component });
+    }
+
+    Ok(events)
+}
+
+/// One `NAME;PARAM=VALUE:value` line
+struct ContentLine {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl ContentLine {
+    fn parse(line: &str) -> Option<Self> {
+        // The value starts at the first colon outside a quoted parameter value
+        let mut in_quotes = false;
+        let split = line.char_indices().find_map(|(i, c)| match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                None
+            }
+            ':' if !in_quotes => Some(i),
+            _ => None,
+        })?;
+
+        let (head, value) = (&line[..split], &line[split + 1..]);
+        let mut parts = head.split(';');
+        let name = parts.next()?.trim().to_ascii_uppercase();
+        if name.is_empty() {
+            return None;
+        }
+        let params = parts
+            .filter_map(|param| {
+                let (key, value) = param.split_once('=')?;
+                Some((
+                    key.trim().to_ascii_uppercase(),
+                    value.trim().trim_matches('"').to_string(),
+                ))
+            })
+            .collect();
+
+        Some(Self {
+            name,
+            params,
+            value: value.to_string(),
+        })
+    }
+
+    fn param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A date-time value and whether it was a whole day
+struct IcsTime {
+    at: DateTime<Utc>,
+    all_day: bool,
+}
+
+/// Properties collected for a VEVENT until its END line
+#[derive(Default)]
+struct PendingEvent {
+    uid: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    location: Option<String>,
+    category: Option<String>,
+    start: Option<IcsTime>,
+    end: Option<IcsTime>,
+    duration_minutes: Option<i64>,
+    cancelled: bool,
+}
+
+impl PendingEvent {
+    fn apply(&mut self, property: &ContentLine, line: usize) -> Result<(), IcsError> {
+        let invalid = || IcsError::InvalidValue {
+            property: property.name.clone(),
+            value: property.value.clone(),
+            line,
+        };
+
+        match property.name.as_str() {
+            "UID" => self.uid = Some(property.value.trim().to_string()),
+            "SUMMARY" => self.summary = Some(unescape_text(&property.value)),
+            "DESCRIPTION" => self.description = Some(unescape_text(&property.value)),
+            "LOCATION" => self.location = Some(unescape_text(&property.value)),
+            "CATEGORIES" => {
+                self.category = split_list(&property.value)
+                    .into_iter()
+                    .map(|c| c.trim().to_lowercase())
+                    .find(|c| !c.is_empty());
+            }
+            "DTSTART" => self.start = Some(parse_time(property).ok_or_else(invalid)?),
+            "DTEND" => self.end = Some(parse_time(property).ok_or_else(invalid)?),
+            "DURATION" => {
+                self.duration_minutes = Some(parse_duration(&property.value).ok_or_else(invalid)?)
+            }
+            "STATUS" => self.cancelled = property.value.eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn finish(self, line: usize, creator_id: &UserId) -> Result<Option<BaseEvent>, IcsError> {
+        if self.cancelled {
+            return Ok(None);
+        }
+        let start = self.start.ok_or(IcsError::MissingStart { line })?;
+
+        let duration_minutes = match (&self.end, self.duration_minutes) {
+            (Some(end), _) => Some((end.at - start.at).num_minutes()),
+            (None, Some(minutes)) => Some(minutes),
+            // An all-day event without an end lasts the one day
+            (None, None) if start.all_day => Some(24 * 60),
+            (None, None) => None,
+        }
+        .filter(|minutes| *minutes > 0)
+        .map(|minutes| minutes.min(i32::MAX as i64) as i32);
+
+        let now = Utc::now();
+        Ok(Some(BaseEvent {
+            title: self
+                .summary
+                .filter(|s| !s.trim().is_empty())
+                .unwrap_or_else(|| "(untitled)".to_string()),
+            description: self.description.filter(|s| !s.trim().is_empty()),
+            event_type: self.category.unwrap_or_else(|| "general".to_string()),
+            scheduled_for: start.at,
+            duration_minutes,
+            location: self.location.filter(|s| !s.trim().is_empty()),
+            ical_uid: self.uid,
+            created_at: now,
+            updated_at: now,
+            creator_id: creator_id.clone(),
+            ..Default::default()
+        }))
+    }
+}
+
+/// Split the file into logical lines, joining folded continuations
+fn unfold_lines(input: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (index, raw) in input.lines().enumerate() {
+        let raw = raw.trim_end_matches('\r');
+        if let Some(continuation) = raw.strip_prefix([' ', '\t']) {
+            if let Some((_, last)) = lines.last_mut() {
+                last.push_str(continuation);
+                continue;
+            }
+        }
+        if !raw.trim().is_empty() {
+            lines.push((index + 1, raw.to_string()));
+        }
+    }
+    lines
+}
+
+/// Parse DTSTART/DTEND in any of the three RFC 5545 forms
+///
+/// UTC times end in `Z`; times with a `TZID` are resolved in that zone; and
+/// floating times are taken as local time. Dates are midnight local time.
+fn parse_time(property: &ContentLine) -> Option<IcsTime> {
+    let value = property.value.trim();
+    let is_date = property
+        .param("VALUE")
+        .is_some_and(|v| v.eq_ignore_ascii_case("DATE"))
+        || (value.len() == 8 && value.chars().all(|c| c.is_ascii_digit()));
+
+    if is_date {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        let at = resolve_local(property.param("TZID"), date.and_hms_opt(0, 0, 0)?)?;
+        return Some(IcsTime { at, all_day: true });
+    }
+
+    if let Some(utc) = value.strip_suffix(['Z', 'z']) {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(IcsTime {
+            at: naive.and_utc(),
+            all_day: false,
+        });
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Some(IcsTime {
+        at: resolve_local(property.param("TZID"), naive)?,
+        all_day: false,
+    })
+}
+
+/// Resolve a wall-clock time in a named zone, or local time when there isn't one
+fn resolve_local(tzid: Option<&str>, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    if let Some(tzid) = tzid {
+        // Some exporters prefix the zone name with a path, e.g. "/mozilla.org/.../Europe/Berlin"
+        let name = tzid.trim_start_matches('/');
+        let zone = chrono_tz::Tz::from_str(name).ok().or_else(|| {
+            name.match_indices('/')
+                .find_map(|(i, _)| chrono_tz::Tz::from_str(&name[i + 1..]).ok())
+        });
+        match zone {
+            Some(zone) => {
+                return zone
+                    .from_local_datetime(&naive)
+                    .earliest()
+                    .map(|dt| dt.with_timezone(&Utc));
+            }
+            None => tracing::warn!("Unknown TZID '{}', treating time as local", tzid),
+        }
+    }
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Parse an RFC 5545 duration such as `PT1H30M` or `P1D` into whole minutes
+fn parse_duration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (negative, value) = match value.as_bytes().first()? {
+        b'-' => (true, &value[1..]),
+        b'+' => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let value = value.strip_prefix(['P', 'p'])?;
+
+    let mut seconds = 0i64;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in value.chars() {
+        match c.to_ascii_uppercase() {
+            'T' => in_time = true,
+            d if d.is_ascii_digit() => number.push(d),
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                let unit_seconds = match (unit, in_time) {
+                    ('W', false) => 7 * 86400,
+                    ('D', false) => 86400,
+                    ('H', true) => 3600,
+                    ('M', true) => 60,
+                    ('S', true) => 1,
+                    _ => return None,
+                };
+                // The value comes from an imported file, so reject overflow
+                seconds = seconds.checked_add(n.checked_mul(unit_seconds)?)?;
+            }
+        }
+    }
+    if !number.is_empty() {
+        return None;
+    }
+
+    let minutes = seconds / 60;
+    Some(if negative { -minutes } else { minutes })
+}
+
+fn format_utc(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn unescape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n' | 'N') => out.push('\n'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Split a comma-separated list value, respecting escaped commas
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    items.last_mut().unwrap().push('\\');
+                    items.last_mut().unwrap().push(next);
+                }
+            }
+            ',' => items.push(String::new()),
+            _ => items.last_mut().unwrap().push(c),
+        }
+    }
+    items.iter().map(|item| unescape_text(item)).collect()
+}
+
+/// Fold a line so no physical line exceeds [`MAX_LINE_OCTETS`]
+fn fold_line(line: &str) -> String {
+    if line.len() <= MAX_LINE_OCTETS {
+        return line.to_string();
+    }
+
+    let mut out = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts toward the limit
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example//EN\r
+BEGIN:VEVENT\r
+UID:abc-123@example.com\r
+DTSTART:20250301T140000Z\r
+DTEND:20250301T153000Z\r
+SUMMARY:Therapy\\, weekly\r
+DESCRIPTION:Bring the\\nworksheet\r
+LOCATION:Room 4\r
+CATEGORIES:Health,Personal\r
+RRULE:FREQ=WEEKLY\r
+BEGIN:VALARM\r
+TRIGGER:-PT15M\r
+DESCRIPTION:Alarm text\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:berlin@example.com\r
+DTSTART;TZID=Europe/Berlin:20250701T090000\r
+DURATION:PT45M\r
+SUMMARY:Stand\r
+ up\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:gone@example.com\r
+DTSTART;VALUE=DATE:20250302\r
+STATUS:CANCELLED\r
+SUMMARY:Cancelled\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_parse_ics_events() {
+        let creator = UserId::generate();
+        let events = parse_ics(SAMPLE, &creator).unwrap();
+        assert_eq!(events.len(), 2);
+
+        let therapy = &events[0];
+        assert_eq!(therapy.title, "Therapy, weekly");
+        assert_eq!(therapy.description.as_deref(), Some("Bring the\nworksheet"));
+        assert_eq!(therapy.location.as_deref(), Some("Room 4"));
+        assert_eq!(therapy.event_type, "health");
+        assert_eq!(therapy.duration_minutes, Some(90));
+        assert_eq!(therapy.ical_uid.as_deref(), Some("abc-123@example.com"));
+        assert_eq!(therapy.creator_id, creator);
+        assert_eq!(
+            therapy.scheduled_for,
+            Utc.with_ymd_and_hms(2025, 3, 1, 14, 0, 0).unwrap()
+        );
+
+        // Folded summary, TZID resolved (Berlin is UTC+2 in July), DURATION used
+        let standup = &events[1];
+        assert_eq!(standup.title, "Standup");
+        assert_eq!(standup.duration_minutes, Some(45));
+        assert_eq!(
+            standup.scheduled_for,
+            Utc.with_ymd_and_hms(2025, 7, 1, 7, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_ics_round_trip() {
+        let creator = UserId::generate();
+        let event = BaseEvent {
+            title: "Pick up prescription; call pharmacy first, they close early".repeat(2),
+            description: Some("Line one\nLine two".to_string()),
+            event_type: "errand".to_string(),
+            scheduled_for: Utc.with_ymd_and_hms(2025, 5, 6, 16, 30, 0).unwrap(),
+            duration_minutes: Some(20),
+            creator_id: creator.clone(),
+            ..Default::default()
+        };
+
+        let ics = to_ics(std::slice::from_ref(&event));
+        assert!(ics.lines().all(|line| line.len() <= MAX_LINE_OCTETS + 1));
+        assert!(ics.contains(&format!("UID:{}@pattern", event.id.to_key())));
+
+        let parsed = parse_ics(&ics, &creator).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].title, event.title);
+        assert_eq!(parsed[0].description, event.description);
+        assert_eq!(parsed[0].event_type, "errand");
+        assert_eq!(parsed[0].scheduled_for, event.scheduled_for);
+        assert_eq!(parsed[0].duration_minutes, Some(20));
+    }
+
+    #[test]
+    fn test_parse_ics_errors() {
+        let creator = UserId::generate();
+        assert!(matches!(
+            parse_ics("hello", &creator),
+            Err(IcsError::NotACalendar)
+        ));
+
+        let missing_start = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:x\nEND:VEVENT\nEND:VCALENDAR\n";
+        assert!(matches!(
+            parse_ics(missing_start, &creator),
+            Err(IcsError::MissingStart { line: 4 })
+        ));
+
+        let bad_date =
+            "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART:tomorrow\nEND:VEVENT\nEND:VCALENDAR\n";
+        assert!(matches!(
+            parse_ics(bad_date, &creator),
+            Err(IcsError::InvalidValue { line: 3, .. })
+        ));
+
+        let huge_duration = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART:20250701T070000Z\n\
+                             DURATION:P99999999999999999D\nEND:VEVENT\nEND:VCALENDAR\n";
+        assert!(matches!(
+            parse_ics(huge_duration, &creator),
+            Err(IcsError::InvalidValue { line: 4, .. })
+        ));
+
+        assert_eq!(parse_duration("P1DT2H"), Some(26 * 60));
+        assert_eq!(parse_duration("-PT15M"), Some(-15));
+        assert_eq!(parse_duration("PT"), Some(0));
+        assert_eq!(parse_duration("1H"), None);
+        assert_eq!(parse_duration("P99999999999999999D"), None);
+        assert_eq!(parse_duration("P99999999999999999999D"), None);
+    }
+}
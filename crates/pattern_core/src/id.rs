@@ -323,6 +323,10 @@ define_id_type!(ConstellationId, "constellation");
 define_id_type!(OAuthTokenId, "oauth");
 define_id_type!(AtprotoIdentityId, "atproto_identity");
 define_id_type!(DiscordIdentityId, "discord_identity");
+define_id_type!(PermissionGrantId, "permission_grant");
+define_id_type!(PermissionAuditId, "permission_audit");
+define_id_type!(MemoryRevisionId, "mem_rev");
+define_id_type!(ModelUsageId, "model_usage");
 
 #[cfg(test)]
 mod tests {
@@ -18,17 +18,22 @@ pub mod discord_identity;
 pub mod embeddings;
 pub mod error;
 pub mod export;
+pub mod ical;
 pub mod id;
 pub mod memory;
 pub mod memory_acl;
+pub mod memory_history;
 pub mod message;
 pub mod message_queue;
 pub mod model;
 pub mod oauth;
 pub mod permission;
+pub mod policy;
 pub mod prompt_template;
 pub mod realtime;
+pub mod skill;
 pub mod tool;
+pub mod usage;
 pub mod users;
 pub mod utils;
 
@@ -10,6 +10,8 @@ use std::ops::Deref;
 use std::ops::DerefMut;
 use std::sync::Arc;
 
+use crate::id::ToolCallId;
+use crate::memory_history::MemoryRevision;
 use crate::{MemoryId, Result, UserId};
 
 /// Custom deserializer that handles both f32 and f64 values
@@ -219,6 +221,10 @@ pub struct Memory {
     #[serde(skip)]
     dirty_blocks: Arc<DashSet<MemoryId>>,
 
+    /// Value changes not yet written to the revision history
+    #[serde(skip)]
+    pending_revisions: Arc<std::sync::Mutex<Vec<MemoryRevision>>>,
+
     /// Maximum characters per block (soft limit)
     char_limit: usize,
     /// The user (human) who owns this memory collection
@@ -232,6 +238,7 @@ impl Memory {
             blocks: Arc::new(DashMap::new()),
             new_blocks: Arc::new(DashSet::new()),
             dirty_blocks: Arc::new(DashSet::new()),
+            pending_revisions: Arc::default(),
             char_limit: 5000,
             owner_id: UserId::generate(),
         }
@@ -243,6 +250,7 @@ impl Memory {
             blocks: Arc::new(DashMap::new()),
             new_blocks: Arc::new(DashSet::new()),
             dirty_blocks: Arc::new(DashSet::new()),
+            pending_revisions: Arc::default(),
             char_limit: 5000,
             owner_id: owner_id.clone(),
         }
@@ -308,8 +316,9 @@ impl Memory {
     pub fn update_block_value(&self, label: &str, value: impl Into<String>) -> Result<()> {
         if let Some(mut block) = self.blocks.get_mut(label) {
             let block_id = block.id.clone();
-            block.value = value.into();
+            let previous = std::mem::replace(&mut block.value, value.into());
             block.updated_at = Utc::now();
+            self.record_revision(&block, previous);
 
             self.dirty_blocks.insert(block_id);
 
@@ -346,8 +355,20 @@ impl Memory {
         self.blocks.iter().map(|e| e.key().clone()).collect()
     }
 
-    /// Remove a memory block
+    /// Remove a memory block, recording the removal so it can be rolled back
     pub fn remove_block(&self, label: &str) -> Option<MemoryBlock> {
+        let (_, block) = self.blocks.remove(label)?;
+        self.pending_revisions
+            .lock()
+            .expect("revision lock poisoned")
+            .push(MemoryRevision::deletion(&block));
+        Some(block)
+    }
+
+    /// Remove a block deleted from the database without recording a revision
+    ///
+    /// Whoever deleted it from the database already recorded the removal.
+    pub fn sync_block_removal(&self, label: &str) -> Option<MemoryBlock> {
         self.blocks.remove(label).map(|e| e.1)
     }
 
@@ -363,7 +384,9 @@ impl Memory {
     {
         self.blocks.alter(label, |key, block| {
             let block_id = block.id.clone();
+            let previous = block.value.clone();
             let updated_block = f(key, block);
+            self.record_revision(&updated_block, previous);
 
             self.dirty_blocks.insert(block_id);
 
@@ -390,7 +413,11 @@ impl Memory {
             drop(existing_block); // Drop the guard before inserting to avoid deadlock
 
             block.id = existing_id.clone(); // Preserve the existing ID
-            self.blocks.insert(label, block);
+            if let Some(previous) = self.blocks.insert(label, block) {
+                if let Some(current) = self.blocks.get(&previous.label) {
+                    self.record_revision(&current, previous.value);
+                }
+            }
 
             // Mark as dirty if not new
             if !self.new_blocks.contains(&existing_id) {
@@ -433,6 +460,54 @@ impl Memory {
         self.new_blocks.remove(id);
         self.dirty_blocks.remove(id);
     }
+
+    /// Set a block's value from its stored copy without recording a revision
+    ///
+    /// For syncing changes that were already recorded by whoever wrote them
+    /// to the database.
+    pub fn sync_block_value(&self, label: &str, value: impl Into<String>) -> Result<()> {
+        if let Some(mut block) = self.blocks.get_mut(label) {
+            block.value = value.into();
+            block.updated_at = Utc::now();
+            Ok(())
+        } else {
+            Err(crate::CoreError::MemoryNotFound {
+                agent_id: "unknown".to_string(),
+                block_name: label.to_string(),
+                available_blocks: self.list_blocks(),
+            })
+        }
+    }
+
+    /// Attribute value changes not yet tied to a tool call to `call_id`
+    pub fn attribute_revisions(&self, call_id: &ToolCallId) {
+        let mut pending = self
+            .pending_revisions
+            .lock()
+            .expect("revision lock poisoned");
+        for revision in pending.iter_mut().filter(|r| r.tool_call_id.is_none()) {
+            revision.tool_call_id = Some(call_id.clone());
+        }
+    }
+
+    /// Take the value changes made since the last call, oldest first
+    pub fn take_revisions(&self) -> Vec<MemoryRevision> {
+        std::mem::take(
+            &mut *self
+                .pending_revisions
+                .lock()
+                .expect("revision lock poisoned"),
+        )
+    }
+
+    fn record_revision(&self, block: &MemoryBlock, previous: String) {
+        if block.value != previous {
+            self.pending_revisions
+                .lock()
+                .expect("revision lock poisoned")
+                .push(MemoryRevision::new(block, previous));
+        }
+    }
 }
 
 impl Default for Memory {
@@ -1,7 +1,11 @@
+use serde::{Deserialize, Serialize};
+
 use crate::memory::MemoryPermission;
+use crate::policy::PolicySubject;
 
 /// Memory operation types we gate by permission.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MemoryOp {
     Read,
     Append,
@@ -66,6 +70,21 @@ pub fn check(op: MemoryOp, perm: MemoryPermission) -> MemoryGate {
     }
 }
 
+/// Check `op` on the block `label` for `subject`.
+/// A matching rule in the active policy decides first, so it can loosen or
+/// tighten the block's permission; otherwise this is [`check`].
+pub fn check_for(
+    subject: &PolicySubject,
+    label: &str,
+    op: MemoryOp,
+    perm: MemoryPermission,
+) -> MemoryGate {
+    match crate::policy::policy().check_memory(subject, label, op, chrono::Utc::now()) {
+        Some(decision) => decision.into(),
+        None => check(op, perm),
+    }
+}
+
 /// Build a human-friendly reason string for consent prompts.
 pub fn consent_reason(key: &str, op: MemoryOp, current: MemoryPermission) -> String {
     format!(
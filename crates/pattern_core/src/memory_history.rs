@@ -0,0 +1,290 @@
+//! Revision history for memory blocks
+//!
+//! Every change to a block's value is kept as a [`MemoryRevision`] holding the
+//! value before and after the write, so a clobbered block can be inspected
+//! with [`unified_diff`] and restored with
+//! [`rollback_memory_revision`](crate::db::ops::rollback_memory_revision).
+//! Removing a block (deleting it, or archiving it out of context) is recorded
+//! the same way, with a snapshot so rolling back can bring the block back.
+
+use chrono::{DateTime, Utc};
+use pattern_macros::Entity;
+use serde::{Deserialize, Serialize};
+
+use crate::id::{AgentId, MemoryId, MemoryRevisionId, ToolCallId, UserId};
+use crate::memory::MemoryBlock;
+
+/// What a revision did to its block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevisionChange {
+    /// The block's value was rewritten
+    #[default]
+    Write,
+    /// The block was removed; `value` is empty
+    Delete,
+}
+
+/// One write to a memory block
+#[derive(Debug, Clone, Entity, Serialize, Deserialize)]
+#[entity(entity_type = "mem_rev")]
+pub struct MemoryRevision {
+    pub id: MemoryRevisionId,
+    pub memory_id: MemoryId,
+    /// Block label at the time of the write
+    pub label: String,
+    /// Value before the write
+    pub previous_value: String,
+    /// Value after the write
+    pub value: String,
+    /// Agent that made the write, if it came from an agent
+    pub author_agent_id: Option<AgentId>,
+    /// User that made the write, if it came from outside an agent
+    pub author_user_id: Option<UserId>,
+    /// Tool call that made the write
+    pub tool_call_id: Option<ToolCallId>,
+    /// Why the write happened, e.g. "rollback of mem_rev:..."
+    pub note: Option<String>,
+    #[serde(default)]
+    pub change: RevisionChange,
+    /// The removed block, for deletions, so a rollback can recreate it
+    pub snapshot: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MemoryRevision {
+    /// A revision for `block` now holding its current value
+    pub fn new(block: &MemoryBlock, previous_value: impl Into<String>) -> Self {
+        Self {
+            id: MemoryRevisionId::generate(),
+            memory_id: block.id.clone(),
+            label: block.label.to_string(),
+            previous_value: previous_value.into(),
+            value: block.value.clone(),
+            author_agent_id: None,
+            author_user_id: None,
+            tool_call_id: None,
+            note: None,
+            change: RevisionChange::Write,
+            snapshot: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// A revision recording that `block` was removed
+    pub fn deletion(block: &MemoryBlock) -> Self {
+        // The embedding is dropped; it gets back-filled once the block is restored
+        let snapshot = MemoryBlock {
+            embedding: None,
+            ..block.clone()
+        };
+        Self {
+            value: String::new(),
+            change: RevisionChange::Delete,
+            snapshot: serde_json::to_value(&snapshot).ok(),
+            ..Self::new(block, block.value.clone())
+        }
+    }
+
+    /// The block as it was when it was removed, if this is a deletion
+    pub fn deleted_block(&self) -> Option<MemoryBlock> {
+        if self.change != RevisionChange::Delete {
+            return None;
+        }
+        serde_json::from_value(self.snapshot.clone()?).ok()
+    }
+
+    pub fn by_agent(mut self, agent_id: AgentId) -> Self {
+        self.author_agent_id = Some(agent_id);
+        self
+    }
+
+    pub fn by_user(mut self, user_id: UserId) -> Self {
+        self.author_user_id = Some(user_id);
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+
+    /// Change in length, in characters
+    pub fn size_delta(&self) -> i64 {
+        self.value.chars().count() as i64 - self.previous_value.chars().count() as i64
+    }
+}
+
+/// One line of a line-based diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line diff of `old` against `new` (longest common subsequence)
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().copied().map(DiffLine::Removed));
+    lines.extend(new[j..].iter().copied().map(DiffLine::Added));
+    lines
+}
+
+/// Render a diff with `-`/`+` prefixes, keeping `context` unchanged lines
+/// around each change and eliding the rest
+pub fn unified_diff(old: &str, new: &str, context: usize) -> String {
+    let lines = diff_lines(old, new);
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !matches!(l, DiffLine::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    let near_change = |i: usize| {
+        changed
+            .iter()
+            .any(|&c| i + context >= c && i <= c + context)
+    };
+
+    let mut out = String::new();
+    let mut skipped = false;
+    for (i, line) in lines.iter().enumerate() {
+        if !near_change(i) {
+            skipped = true;
+            continue;
+        }
+        if skipped {
+            out.push_str("@@ ... @@\n");
+            skipped = false;
+        }
+        let (prefix, text) = match line {
+            DiffLine::Same(text) => (' ', text),
+            DiffLine::Removed(text) => ('-', text),
+            DiffLine::Added(text) => ('+', text),
+        };
+        out.push(prefix);
+        out.push(' ');
+        out.push_str(text);
+        out.push('\n');
+    }
+    if skipped {
+        out.push_str("@@ ... @@\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let old = "I am Pattern.\nI help with focus.\nI like tea.";
+        let new = "I am Pattern.\nI help with planning.\nI like tea.\nI dislike spam.";
+
+        assert_eq!(
+            diff_lines(old, new),
+            vec![
+                DiffLine::Same("I am Pattern."),
+                DiffLine::Removed("I help with focus."),
+                DiffLine::Added("I help with planning."),
+                DiffLine::Same("I like tea."),
+                DiffLine::Added("I dislike spam."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_elides_unchanged_lines() {
+        let old: String = (1..=10).map(|i| format!("line {}\n", i)).collect();
+        let new = old.replace("line 5\n", "line five\n");
+
+        let diff = unified_diff(&old, &new, 1);
+        assert_eq!(
+            diff,
+            "@@ ... @@\n  line 4\n- line 5\n+ line five\n  line 6\n@@ ... @@\n"
+        );
+        assert!(unified_diff(&old, &old, 3).is_empty());
+    }
+    #[tokio::test]
+    async fn test_rollback_restores_deleted_block() {
+        use crate::agent::AgentRecord;
+        use crate::db::{client, ops};
+        use crate::memory::{Memory, MemoryPermission};
+
+        let db = client::create_test_db().await.unwrap();
+        let agent = AgentRecord {
+            id: AgentId::generate(),
+            owner_id: UserId::generate(),
+            ..Default::default()
+        };
+        ops::create_entity::<AgentRecord, _>(&db, &agent)
+            .await
+            .unwrap();
+
+        let memory = Memory::with_owner(&agent.owner_id);
+        memory.create_block("notes", "keep this").unwrap();
+        let block = memory.get_block("notes").unwrap().clone();
+        ops::persist_agent_memory(&db, agent.id.clone(), &block, MemoryPermission::ReadWrite)
+            .await
+            .unwrap();
+
+        // Removing the block records a deletion carrying the whole block
+        memory.remove_block("notes").unwrap();
+        let revisions = memory.take_revisions();
+        assert_eq!(revisions.len(), 1);
+        let deletion = revisions[0].clone().by_agent(agent.id.clone());
+        assert_eq!(deletion.change, RevisionChange::Delete);
+        assert_eq!(deletion.previous_value, "keep this");
+        assert_eq!(deletion.deleted_block().unwrap().id, block.id);
+        ops::create_memory_revision(&db, &deletion).await.unwrap();
+
+        // Even with the record gone, rolling back recreates and reattaches it
+        ops::delete_entity::<MemoryBlock, _, ()>(&db, &block.id)
+            .await
+            .unwrap();
+        let restored = ops::rollback_memory_revision(&db, &deletion.id, None)
+            .await
+            .unwrap();
+        assert_eq!(restored.value, "keep this");
+        let reattached = ops::get_memory_by_label(&db, agent.id.clone(), "notes")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reattached.id, block.id);
+    }
+}
@@ -893,7 +893,7 @@ impl Message {
     }
 
     /// Estimate word count for content
-    fn estimate_word_count(content: &MessageContent) -> u32 {
+    pub(crate) fn estimate_word_count(content: &MessageContent) -> u32 {
         match content {
             MessageContent::Text(text) => text.split_whitespace().count() as u32,
             MessageContent::Parts(parts) => parts
@@ -1000,6 +1000,9 @@ pub struct MessageMetadata {
     pub channel_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub guild_id: Option<String>,
+    /// Group the message was routed through, for usage accounting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<crate::id::GroupId>,
     #[serde(flatten)]
     pub custom: serde_json::Value,
 }
@@ -1015,6 +1018,27 @@ pub struct Request {
 impl Request {
     /// Convert this request to a genai ChatRequest
     pub fn as_chat_request(&mut self) -> crate::Result<genai::chat::ChatRequest> {
+        self.prepare_messages();
+
+        let messages: Vec<_> = self
+            .messages
+            .iter()
+            .filter(|m| Message::estimate_word_count(&m.content) > 0)
+            .map(|m| m.as_chat_message())
+            .collect();
+
+        Ok(
+            genai::chat::ChatRequest::from_system(self.system.clone().unwrap().join("\n\n"))
+                .append_messages(messages)
+                .with_tools(self.tools.clone().unwrap_or_default()),
+        )
+    }
+
+    /// Stamp user and system messages with their creation time and make sure
+    /// no assistant message ends on a thinking block
+    ///
+    /// Every provider conversion calls this before building its request.
+    pub fn prepare_messages(&mut self) {
         // Fix assistant messages that end with thinking blocks
         for msg in &mut self.messages {
             if msg.role == ChatRole::User || msg.role == ChatRole::System {
@@ -1051,19 +1075,6 @@ impl Request {
                 }
             }
         }
-
-        let messages: Vec<_> = self
-            .messages
-            .iter()
-            .filter(|m| Message::estimate_word_count(&m.content) > 0)
-            .map(|m| m.as_chat_message())
-            .collect();
-
-        Ok(
-            genai::chat::ChatRequest::from_system(self.system.clone().unwrap().join("\n\n"))
-                .append_messages(messages)
-                .with_tools(self.tools.clone().unwrap_or_default()),
-        )
     }
 }
 
@@ -1390,6 +1401,7 @@ impl Response {
             confidence: None,
             model_iden: resp.model_iden.clone(),
             custom: resp.captured_raw_body.clone().unwrap_or_default(),
+            fallback: None,
         };
 
         // Convert genai MessageContent to our MessageContent
@@ -1561,6 +1573,9 @@ pub struct ResponseMetadata {
     pub confidence: Option<f32>,
     pub model_iden: ModelIden,
     pub custom: serde_json::Value,
+    /// Set when a fallback model answered instead of the configured one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<crate::model::fallback::FallbackNote>,
 }
 
 impl Default for ResponseMetadata {
@@ -1572,6 +1587,7 @@ impl Default for ResponseMetadata {
             confidence: None,
             custom: json!({}),
             model_iden: ModelIden::new(genai::adapter::AdapterKind::Ollama, "default_model"),
+            fallback: None,
         }
     }
 }
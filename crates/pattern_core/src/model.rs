@@ -9,6 +9,8 @@ use crate::{
 };
 
 pub mod defaults;
+pub mod fallback;
+pub mod local;
 
 /// A model provider that can generate completions
 #[async_trait]
@@ -78,6 +80,9 @@ pub struct ResponseOptions {
     pub reasoning_effort: Option<genai::chat::ReasoningEffort>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_headers: Option<Vec<(String, String)>>,
+    /// Models to fail over to when this one is unavailable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<fallback::FallbackConfig>,
 }
 
 impl ResponseOptions {
@@ -100,6 +105,7 @@ impl ResponseOptions {
             normalize_reasoning_content: None,
             reasoning_effort: None,
             custom_headers: None,
+            fallback: None,
         }
     }
     /// Convert ResponseOptions to a tuple of (ModelInfo, ChatOptions) for use with genai
@@ -252,6 +258,8 @@ pub enum ModelCapability {
 pub struct GenAiClient {
     client: genai::Client,
     available_endpoints: Vec<AdapterKind>,
+    /// Local OpenAI-compatible server, used for the models it serves
+    local: Option<local::LocalModelProvider>,
 }
 
 impl GenAiClient {
@@ -286,6 +294,7 @@ impl GenAiClient {
         Ok(Self {
             client,
             available_endpoints,
+            local: None,
         })
     }
 
@@ -294,8 +303,15 @@ impl GenAiClient {
         Self {
             client,
             available_endpoints: endpoints,
+            local: None,
         }
     }
+
+    /// Route models served by a local OpenAI-compatible server to it
+    pub fn with_local(mut self, local: local::LocalModelProvider) -> Self {
+        self.local = Some(local);
+        self
+    }
 }
 
 #[async_trait]
@@ -346,11 +362,24 @@ impl ModelProvider for GenAiClient {
             }
         }
 
+        if let Some(local) = &self.local {
+            match local.list_models().await {
+                Ok(models) => model_strings.extend(models),
+                Err(e) => tracing::debug!("Failed to list models for {}: {}", local.name(), e),
+            }
+        }
+
         Ok(model_strings)
     }
 
     /// Generate a completion
     async fn complete(&self, options: &ResponseOptions, mut request: Request) -> Result<Response> {
+        if let Some(local) = &self.local
+            && local.handles(&options.model_info)
+        {
+            return local.complete(options, request).await;
+        }
+
         let (model_info, chat_options) = options.to_chat_options_tuple();
 
         // Validate image URLs are accessible (to avoid anthropic's terrible error handling)
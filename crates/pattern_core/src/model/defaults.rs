@@ -4,13 +4,16 @@
 //! including context windows, max output tokens, and capabilities.
 
 use std::collections::HashMap;
-use std::sync::OnceLock;
+use std::sync::{OnceLock, RwLock};
 
 use super::{ModelCapability, ModelInfo};
 
 /// Static registry of model defaults
 static MODEL_DEFAULTS: OnceLock<HashMap<&'static str, ModelDefaults>> = OnceLock::new();
 
+/// Defaults discovered at runtime, e.g. from a local model server
+static DETECTED_DEFAULTS: OnceLock<RwLock<HashMap<String, ModelDefaults>>> = OnceLock::new();
+
 /// Default configuration for a specific model
 #[derive(Debug, Clone)]
 pub struct ModelDefaults {
@@ -559,6 +562,15 @@ fn init_defaults() -> HashMap<&'static str, ModelDefaults> {
 /// This function takes a ModelInfo (potentially from a provider with incomplete data)
 /// and enriches it with accurate defaults from our registry.
 pub fn enhance_model_info(mut model_info: ModelInfo) -> ModelInfo {
+    // What a server told us about its own model beats anything we guessed
+    if let Some(detected) = DETECTED_DEFAULTS
+        .get()
+        .and_then(|d| d.read().ok()?.get(&model_info.id).cloned())
+    {
+        apply_defaults(&mut model_info, &detected);
+        return model_info;
+    }
+
     let defaults = MODEL_DEFAULTS.get_or_init(init_defaults);
 
     // Try exact match first
@@ -685,6 +697,28 @@ fn apply_provider_defaults(model_info: &mut ModelInfo) {
     }
 }
 
+/// Record the limits a provider reported for one of its models
+///
+/// Later calls to [`enhance_model_info`] for this model ID use these values
+/// instead of the built-in table, so models discovered on a local server get
+/// their real context window rather than a conservative guess.
+pub fn register_detected_model(model_info: &ModelInfo) {
+    if model_info.context_window == 0 {
+        return;
+    }
+    let defaults = ModelDefaults {
+        context_window: model_info.context_window,
+        max_output_tokens: model_info.max_output_tokens,
+        capabilities: model_info.capabilities.clone(),
+        cost_per_1k_prompt: model_info.cost_per_1k_prompt_tokens,
+        cost_per_1k_completion: model_info.cost_per_1k_completion_tokens,
+    };
+    let detected = DETECTED_DEFAULTS.get_or_init(Default::default);
+    if let Ok(mut detected) = detected.write() {
+        detected.insert(model_info.id.clone(), defaults);
+    }
+}
+
 /// Get raw model defaults
 pub fn get_model_defaults(model_id: &str) -> Option<ModelDefaults> {
     let defaults = MODEL_DEFAULTS.get_or_init(init_defaults);
@@ -919,4 +953,34 @@ mod tests {
         // No user preference -> use model's max
         assert_eq!(calculate_max_tokens(&model_info, None), 10_000);
     }
+
+    #[test]
+    fn test_detected_model_overrides_guess() {
+        let local = |context_window| ModelInfo {
+            id: "qwen3:14b-detected-test".to_string(),
+            name: "qwen3:14b".to_string(),
+            provider: "Ollama".to_string(),
+            capabilities: vec![],
+            context_window,
+            max_output_tokens: None,
+            cost_per_1k_prompt_tokens: None,
+            cost_per_1k_completion_tokens: None,
+        };
+
+        // Unknown local model gets the conservative fallback
+        assert_eq!(enhance_model_info(local(0)).context_window, 8_192);
+
+        let mut detected = local(40_960);
+        detected.capabilities = vec![ModelCapability::FunctionCalling];
+        register_detected_model(&detected);
+
+        let enhanced = enhance_model_info(local(0));
+        assert_eq!(enhanced.context_window, 40_960);
+        assert_eq!(enhanced.max_output_tokens, Some(10_240));
+        assert!(
+            enhanced
+                .capabilities
+                .contains(&ModelCapability::FunctionCalling)
+        );
+    }
 }
@@ -0,0 +1,448 @@
+//! Model fallback chains
+//!
+//! A model config can list models to fail over to when the primary model is
+//! rate limited, out of quota or returning nothing, so an always-on agent
+//! answers from a different provider instead of sleeping until the limit
+//! resets. The request is adapted for each provider it is sent to, and the
+//! response notes which model actually answered.
+//!
+//! ```toml
+//! [model]
+//! provider = "Anthropic"
+//! model = "claude-sonnet-4-20250514"
+//!
+//! [model.fallback]
+//! on = ["rate_limit", "usage_limit", "overloaded", "empty_response"]
+//! retries_before_failover = 1
+//!
+//! [[model.fallback.models]]
+//! provider = "Gemini"
+//! model = "gemini-2.5-flash"
+//!
+//! [[model.fallback.models]]
+//! provider = "Ollama"
+//! model = "qwen3:14b"
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::{ModelInfo, ResponseOptions, defaults};
+use crate::CoreError;
+use crate::message::{ContentBlock, MessageContent, Request};
+
+/// Kinds of model error that can move a request to the next model in the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailoverTrigger {
+    /// HTTP 429 from the provider
+    RateLimit,
+    /// Long subscription limits such as Anthropic's 5 hour window
+    UsageLimit,
+    /// HTTP 529 or 503, the provider is busy
+    Overloaded,
+    /// The model returned no candidates (Gemini)
+    EmptyResponse,
+    /// Any other 5xx from the provider
+    ServerError,
+}
+
+impl FailoverTrigger {
+    /// Triggers used when a fallback chain doesn't list its own
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::RateLimit,
+            Self::UsageLimit,
+            Self::Overloaded,
+            Self::EmptyResponse,
+        ]
+    }
+
+    /// Work out which trigger, if any, a model error falls under
+    pub fn classify(error: &CoreError) -> Option<Self> {
+        if let Some((status, headers, _body)) = error.provider_http_parts() {
+            let usage_limit = headers.iter().any(|(k, _)| is_usage_limit_header(k));
+            return Self::from_status(status, usage_limit);
+        }
+
+        let CoreError::ModelProviderError { cause, .. } = error else {
+            return None;
+        };
+        let genai::Error::WebModelCall { webc_error, .. } = cause else {
+            return None;
+        };
+        match webc_error {
+            genai::webc::Error::JsonValueExt(value_ext::JsonValueExtError::PropertyNotFound(
+                path,
+            )) if path == "/candidates/0/content/parts" => Some(Self::EmptyResponse),
+            genai::webc::Error::ResponseFailedStatus {
+                status, headers, ..
+            } => {
+                let usage_limit = headers
+                    .iter()
+                    .any(|(k, _)| is_usage_limit_header(k.as_str()));
+                Self::from_status(status.as_u16(), usage_limit)
+            }
+            _ => None,
+        }
+    }
+
+    fn from_status(status: u16, usage_limit: bool) -> Option<Self> {
+        match status {
+            429 if usage_limit => Some(Self::UsageLimit),
+            429 => Some(Self::RateLimit),
+            503 | 529 => Some(Self::Overloaded),
+            500..=599 => Some(Self::ServerError),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for FailoverTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::RateLimit => "rate_limit",
+            Self::UsageLimit => "usage_limit",
+            Self::Overloaded => "overloaded",
+            Self::EmptyResponse => "empty_response",
+            Self::ServerError => "server_error",
+        };
+        f.write_str(name)
+    }
+}
+
+fn is_usage_limit_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name == "anthropic-ratelimit-unified-5h-reset"
+        || name == "anthropic-ratelimit-unified-5h-status"
+        || name == "anthropic-ratelimit-unified-status"
+}
+
+/// A model to fail over to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackModel {
+    /// Provider name (e.g., "Gemini", "Ollama")
+    pub provider: String,
+    pub model: String,
+    /// Overrides the primary model's temperature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+}
+
+impl FallbackModel {
+    /// Model info for this model, filled in from the known model defaults
+    pub fn model_info(&self) -> ModelInfo {
+        model_info(&self.provider, &self.model)
+    }
+}
+
+/// Ordered list of models to try when the primary model fails
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackConfig {
+    pub models: Vec<FallbackModel>,
+
+    /// Errors that move on to the next model; anything else is returned as is
+    #[serde(default = "FailoverTrigger::defaults")]
+    pub on: Vec<FailoverTrigger>,
+
+    /// Retries on the same model before moving on
+    ///
+    /// Usage limits are never retried, since they can take hours to reset.
+    #[serde(default = "default_retries_before_failover")]
+    pub retries_before_failover: u32,
+}
+
+fn default_retries_before_failover() -> u32 {
+    1
+}
+
+impl FallbackConfig {
+    /// The trigger this error matches, if the chain fails over on it
+    pub fn trigger_for(&self, error: &CoreError) -> Option<FailoverTrigger> {
+        FailoverTrigger::classify(error).filter(|trigger| self.on.contains(trigger))
+    }
+
+    /// Whether a model that has been retried `retries` times should give up
+    /// on this error and hand the request to the next model
+    pub fn fail_over_now(&self, error: &CoreError, retries: u32) -> bool {
+        match self.trigger_for(error) {
+            Some(FailoverTrigger::UsageLimit) => true,
+            Some(_) => retries >= self.retries_before_failover,
+            None => false,
+        }
+    }
+
+    /// Response options for a fallback model, keeping everything else from
+    /// the primary options
+    pub fn options_for(&self, primary: &ResponseOptions, model: &FallbackModel) -> ResponseOptions {
+        let mut options = primary.clone();
+        options.model_info = model.model_info();
+        options.max_tokens = Some(defaults::calculate_max_tokens(
+            &options.model_info,
+            primary.max_tokens,
+        ));
+        if let Some(temperature) = model.temperature {
+            options.temperature = Some(temperature as f64);
+        }
+        if !options.model_info.capabilities.is_empty()
+            && !options
+                .model_info
+                .capabilities
+                .contains(&super::ModelCapability::ExtendedThinking)
+        {
+            options.reasoning_effort = None;
+        }
+        options
+    }
+}
+
+/// Which model answered when the primary model didn't
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackNote {
+    /// The model the agent is configured to use
+    pub requested_model: String,
+    pub answered_by: String,
+    pub provider: String,
+    /// What made the last model before it fail
+    pub reason: FailoverTrigger,
+}
+
+impl FallbackNote {
+    /// Model info for the model that answered
+    pub fn model_info(&self) -> ModelInfo {
+        model_info(&self.provider, &self.answered_by)
+    }
+}
+
+fn model_info(provider: &str, model: &str) -> ModelInfo {
+    defaults::enhance_model_info(ModelInfo {
+        id: model.to_string(),
+        name: model.to_string(),
+        provider: provider.to_string(),
+        capabilities: Vec::new(),
+        context_window: 0,
+        max_output_tokens: None,
+        cost_per_1k_prompt_tokens: None,
+        cost_per_1k_completion_tokens: None,
+    })
+}
+
+/// Adapt a request built for one provider so another provider will accept it
+///
+/// Thinking blocks and thought signatures are only valid for the provider
+/// that produced them, so they are dropped for anyone else. Thinking text is
+/// kept as plain text so the new model still sees the reasoning.
+pub fn adapt_request(request: &mut Request, target: &ModelInfo) {
+    let provider = target.provider.to_lowercase();
+    let id = target.id.to_lowercase();
+    let keeps_thinking = provider.contains("anthropic") || id.contains("claude");
+    let keeps_signatures = provider.contains("gemini") || id.starts_with("gemini");
+
+    for message in &mut request.messages {
+        let MessageContent::Blocks(blocks) = &mut message.content else {
+            continue;
+        };
+        let adapted = std::mem::take(blocks)
+            .into_iter()
+            .filter_map(|block| match block {
+                ContentBlock::Thinking { text, .. } if !keeps_thinking => (!text.trim().is_empty())
+                    .then(|| ContentBlock::Text {
+                        text: format!("<thinking>{}</thinking>", text),
+                        thought_signature: None,
+                    }),
+                ContentBlock::RedactedThinking { .. } if !keeps_thinking => None,
+                ContentBlock::Text {
+                    text,
+                    thought_signature,
+                } => Some(ContentBlock::Text {
+                    text,
+                    thought_signature: thought_signature.filter(|_| keeps_signatures),
+                }),
+                ContentBlock::ToolUse {
+                    id,
+                    name,
+                    input,
+                    thought_signature,
+                } => Some(ContentBlock::ToolUse {
+                    id,
+                    name,
+                    input,
+                    thought_signature: thought_signature.filter(|_| keeps_signatures),
+                }),
+                ContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    is_error,
+                    thought_signature,
+                } => Some(ContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    is_error,
+                    thought_signature: thought_signature.filter(|_| keeps_signatures),
+                }),
+                other => Some(other),
+            })
+            .collect();
+        *blocks = adapted;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Message;
+
+    fn chain() -> FallbackConfig {
+        toml::from_str(
+            r#"
+            [[models]]
+            provider = "Gemini"
+            model = "gemini-2.5-flash"
+
+            [[models]]
+            provider = "Ollama"
+            model = "qwen3:14b"
+            temperature = 0.2
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn http_error(status: u16, headers: Vec<(&str, &str)>) -> CoreError {
+        CoreError::ProviderHttpError {
+            provider: "genai".to_string(),
+            model: "claude-sonnet-4-20250514".to_string(),
+            status,
+            headers: headers
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn parses_chain_with_default_triggers() {
+        let config = chain();
+        assert_eq!(config.models.len(), 2);
+        assert_eq!(config.on, FailoverTrigger::defaults());
+        assert_eq!(config.retries_before_failover, 1);
+        assert_eq!(config.models[1].temperature, Some(0.2));
+    }
+
+    #[test]
+    fn classifies_provider_errors() {
+        assert_eq!(
+            FailoverTrigger::classify(&http_error(429, vec![])),
+            Some(FailoverTrigger::RateLimit)
+        );
+        assert_eq!(
+            FailoverTrigger::classify(&http_error(
+                429,
+                vec![("Anthropic-Ratelimit-Unified-5h-Status", "rejected")]
+            )),
+            Some(FailoverTrigger::UsageLimit)
+        );
+        assert_eq!(
+            FailoverTrigger::classify(&http_error(529, vec![])),
+            Some(FailoverTrigger::Overloaded)
+        );
+        assert_eq!(
+            FailoverTrigger::classify(&http_error(502, vec![])),
+            Some(FailoverTrigger::ServerError)
+        );
+        assert_eq!(FailoverTrigger::classify(&http_error(400, vec![])), None);
+    }
+
+    #[test]
+    fn usage_limits_fail_over_without_retrying() {
+        let config = chain();
+        let usage = http_error(
+            429,
+            vec![("anthropic-ratelimit-unified-status", "rejected")],
+        );
+        assert!(config.fail_over_now(&usage, 0));
+
+        let rate = http_error(429, vec![]);
+        assert!(!config.fail_over_now(&rate, 0));
+        assert!(config.fail_over_now(&rate, 1));
+
+        // Server errors aren't in the default triggers
+        assert!(!config.fail_over_now(&http_error(500, vec![]), 5));
+    }
+
+    #[test]
+    fn fallback_options_switch_model() {
+        let config = chain();
+        let primary = ResponseOptions::new(model_info("Anthropic", "claude-sonnet-4-20250514"));
+        let options = config.options_for(&primary, &config.models[1]);
+        assert_eq!(options.model_info.id, "qwen3:14b");
+        assert_eq!(options.model_info.provider, "Ollama");
+        assert_eq!(options.temperature, Some(0.2f32 as f64));
+    }
+
+    #[test]
+    fn adapting_for_gemini_drops_anthropic_thinking() {
+        let mut message = Message::agent("");
+        message.content = MessageContent::Blocks(vec![
+            ContentBlock::Thinking {
+                text: "They asked about tea".to_string(),
+                signature: Some("sig".to_string()),
+            },
+            ContentBlock::RedactedThinking {
+                data: "opaque".to_string(),
+            },
+            ContentBlock::Text {
+                text: "Green, I think.".to_string(),
+                thought_signature: None,
+            },
+        ]);
+        let mut request = Request {
+            system: None,
+            messages: vec![message],
+            tools: None,
+        };
+
+        adapt_request(&mut request, &model_info("Gemini", "gemini-2.5-flash"));
+
+        let MessageContent::Blocks(blocks) = &request.messages[0].content else {
+            panic!("expected blocks");
+        };
+        assert_eq!(blocks.len(), 2);
+        assert!(matches!(
+            &blocks[0],
+            ContentBlock::Text { text, .. } if text.contains("They asked about tea")
+        ));
+    }
+
+    #[test]
+    fn adapting_for_anthropic_drops_gemini_signatures() {
+        let mut message = Message::agent("");
+        message.content = MessageContent::Blocks(vec![ContentBlock::ToolUse {
+            id: "call_1".to_string(),
+            name: "recall".to_string(),
+            input: serde_json::json!({}),
+            thought_signature: Some("gemini-sig".to_string()),
+        }]);
+        let mut request = Request {
+            system: None,
+            messages: vec![message],
+            tools: None,
+        };
+
+        adapt_request(
+            &mut request,
+            &model_info("Anthropic", "claude-sonnet-4-20250514"),
+        );
+
+        let MessageContent::Blocks(blocks) = &request.messages[0].content else {
+            panic!("expected blocks");
+        };
+        assert!(matches!(
+            &blocks[0],
+            ContentBlock::ToolUse {
+                thought_signature: None,
+                ..
+            }
+        ));
+    }
+}
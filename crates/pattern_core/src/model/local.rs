@@ -0,0 +1,834 @@
+//! Chat completions from local OpenAI-compatible servers
+//!
+//! Ollama, llama.cpp's server and vLLM all speak the OpenAI chat completions
+//! API. [`LocalModelProvider`] talks to one of them directly, discovers the
+//! models it serves and their context windows, and sends tool schemas in a
+//! form these servers accept, so a constellation can run with no hosted
+//! vendor at all.
+//!
+//! ```toml
+//! [model]
+//! provider = "Ollama" # or "LlamaCpp", "vLLM", "Local"
+//! model = "qwen3:14b"
+//!
+//! [model.settings]
+//! base_url = "http://localhost:11434"
+//! # Overrides the detected context window
+//! # context_window = 32768
+//! # Environment variable holding an API key, for servers started with one
+//! # api_key_env = "VLLM_API_KEY"
+//! ```
+//!
+//! Ollama's OpenAI endpoint serves each model with its `num_ctx` parameter
+//! (or `OLLAMA_CONTEXT_LENGTH`), not the model's trained context length, so
+//! set one of those if the detected window is smaller than you expect.
+
+use std::time::Duration;
+
+use genai::{ModelIden, adapter::AdapterKind};
+use serde_json::{Value, json};
+
+use super::{ModelCapability, ModelInfo, ModelProvider, ResponseOptions, defaults};
+use crate::message::{
+    ContentBlock, ContentPart, ImageSource, Message, MessageContent, Request, Response,
+    ResponseMetadata, ToolCall,
+};
+use crate::{CoreError, Result};
+
+/// The kind of server behind an OpenAI-compatible endpoint
+///
+/// The chat API is the same for all of them; the kind decides the default
+/// address and where to look for the served context window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalServerKind {
+    Ollama,
+    LlamaCpp,
+    Vllm,
+    /// Any other server speaking the OpenAI chat completions API
+    OpenAiCompatible,
+}
+
+impl LocalServerKind {
+    /// Parse from a provider string, or `None` for hosted providers
+    pub fn from_provider_string(provider: &str) -> Option<Self> {
+        match provider.to_lowercase().as_str() {
+            "ollama" => Some(Self::Ollama),
+            "llamacpp" | "llama.cpp" | "llama-cpp" => Some(Self::LlamaCpp),
+            "vllm" => Some(Self::Vllm),
+            "local" | "openai-compatible" | "openai_compatible" => Some(Self::OpenAiCompatible),
+            _ => None,
+        }
+    }
+
+    /// Provider name used in model info for models this server serves
+    pub fn provider_name(&self) -> &'static str {
+        match self {
+            Self::Ollama => "Ollama",
+            Self::LlamaCpp => "LlamaCpp",
+            Self::Vllm => "vLLM",
+            Self::OpenAiCompatible => "Local",
+        }
+    }
+
+    fn default_url(&self) -> &'static str {
+        match self {
+            Self::Ollama => "http://localhost:11434",
+            Self::LlamaCpp | Self::OpenAiCompatible => "http://localhost:8080",
+            Self::Vllm => "http://localhost:8000",
+        }
+    }
+}
+
+/// Model provider for a local OpenAI-compatible server
+#[derive(Debug, Clone)]
+pub struct LocalModelProvider {
+    kind: LocalServerKind,
+    /// Server root, without a trailing `/v1`
+    base_url: String,
+    api_key: Option<String>,
+    context_window: Option<usize>,
+    client: reqwest::Client,
+}
+
+impl LocalModelProvider {
+    /// Create a provider for the server at `base_url`
+    pub fn new(kind: LocalServerKind, base_url: impl Into<String>) -> Result<Self> {
+        // Local models on modest hardware can take minutes per response
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(600))
+            .build()
+            .map_err(|e| CoreError::DataSourceError {
+                source_name: kind.provider_name().to_string(),
+                operation: "create_http_client".to_string(),
+                cause: e.to_string(),
+            })?;
+
+        let base_url = base_url.into();
+        let base_url = base_url.trim_end_matches('/');
+        let base_url = base_url.strip_suffix("/v1").unwrap_or(base_url).to_string();
+
+        Ok(Self {
+            kind,
+            base_url,
+            api_key: None,
+            context_window: None,
+            client,
+        })
+    }
+
+    /// Send this key as a bearer token
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Use this context window for every model instead of detecting it
+    pub fn with_context_window(mut self, context_window: usize) -> Self {
+        self.context_window = Some(context_window);
+        self
+    }
+
+    /// Build a provider from a model config, if it or its fallback chain
+    /// names a local server
+    ///
+    /// The address comes from `settings.base_url`, then `LOCAL_MODEL_URL`,
+    /// then the server kind's usual port on localhost.
+    pub fn from_model_config(config: &crate::config::ModelConfig) -> Result<Option<Self>> {
+        let kind = LocalServerKind::from_provider_string(&config.provider).or_else(|| {
+            config.fallback.as_ref().and_then(|f| {
+                f.models
+                    .iter()
+                    .find_map(|m| LocalServerKind::from_provider_string(&m.provider))
+            })
+        });
+        let Some(kind) = kind else {
+            return Ok(None);
+        };
+
+        let setting = |key: &str| config.settings.get(key);
+        let base_url = setting("base_url")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| std::env::var("LOCAL_MODEL_URL").ok())
+            .unwrap_or_else(|| kind.default_url().to_string());
+
+        let mut provider = Self::new(kind, base_url)?;
+        if let Some(key) = setting("api_key_env")
+            .and_then(|v| v.as_str())
+            .and_then(|var| std::env::var(var).ok())
+        {
+            provider = provider.with_api_key(key);
+        }
+        if let Some(window) = setting("context_window").and_then(|v| v.as_integer()) {
+            provider = provider.with_context_window(window.max(0) as usize);
+        }
+        Ok(Some(provider))
+    }
+
+    pub fn kind(&self) -> LocalServerKind {
+        self.kind
+    }
+
+    /// Whether requests for this model should go to this server
+    pub fn handles(&self, model_info: &ModelInfo) -> bool {
+        LocalServerKind::from_provider_string(&model_info.provider) == Some(self.kind)
+            || model_info
+                .provider
+                .eq_ignore_ascii_case(self.kind.provider_name())
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    fn adapter_kind(&self) -> AdapterKind {
+        match self.kind {
+            LocalServerKind::Ollama => AdapterKind::Ollama,
+            _ => AdapterKind::OpenAI,
+        }
+    }
+
+    /// Connection and body-decoding failures, surfaced like any other provider error
+    fn transport_error(
+        &self,
+        model: &str,
+        operation: &str,
+        e: impl std::fmt::Display,
+    ) -> CoreError {
+        CoreError::model_error(
+            self.kind.provider_name(),
+            model,
+            genai::Error::WebStream {
+                model_iden: ModelIden::new(self.adapter_kind(), model),
+                cause: format!("{operation}: {e}"),
+            },
+        )
+    }
+
+    /// Served context window and capabilities reported by Ollama's show API
+    async fn ollama_show(&self, model: &str) -> Option<(Option<usize>, Vec<ModelCapability>)> {
+        let body: Value = self
+            .request(self.client.post(self.url("/api/show")))
+            .json(&json!({ "model": model }))
+            .send()
+            .await
+            .ok()?
+            .json()
+            .await
+            .ok()?;
+
+        // num_ctx is what the server actually serves; the trained length is a ceiling
+        let num_ctx = body["parameters"].as_str().and_then(|params| {
+            params.lines().find_map(|line| {
+                let mut parts = line.split_whitespace();
+                (parts.next() == Some("num_ctx"))
+                    .then(|| parts.next()?.parse::<usize>().ok())
+                    .flatten()
+            })
+        });
+        let trained = body["model_info"].as_object().and_then(|info| {
+            info.iter()
+                .find(|(k, _)| k.ends_with(".context_length"))
+                .and_then(|(_, v)| v.as_u64())
+                .map(|v| v as usize)
+        });
+
+        let capabilities = body["capabilities"]
+            .as_array()
+            .map(|caps| {
+                caps.iter()
+                    .filter_map(Value::as_str)
+                    .filter_map(|cap| match cap {
+                        "tools" => Some(ModelCapability::FunctionCalling),
+                        "vision" => Some(ModelCapability::VisionInput),
+                        "thinking" => Some(ModelCapability::ExtendedThinking),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some((num_ctx.or(trained), capabilities))
+    }
+
+    /// Context window llama.cpp's server was started with
+    async fn llamacpp_context(&self) -> Option<usize> {
+        let body: Value = self
+            .request(self.client.get(self.url("/props")))
+            .send()
+            .await
+            .ok()?
+            .json()
+            .await
+            .ok()?;
+        body["default_generation_settings"]["n_ctx"]
+            .as_u64()
+            .or_else(|| body["n_ctx"].as_u64())
+            .map(|v| v as usize)
+    }
+
+    /// Describe one entry from `/v1/models`
+    async fn describe_model(&self, entry: &Value) -> Option<ModelInfo> {
+        let id = entry["id"].as_str()?.to_string();
+
+        let mut capabilities = vec![
+            ModelCapability::TextGeneration,
+            ModelCapability::SystemPrompt,
+        ];
+        let mut context_window = self.context_window;
+
+        match self.kind {
+            LocalServerKind::Ollama => {
+                if let Some((window, caps)) = self.ollama_show(&id).await {
+                    context_window = context_window.or(window);
+                    // Older Ollama versions don't report capabilities
+                    if caps.is_empty() {
+                        capabilities.push(ModelCapability::FunctionCalling);
+                    }
+                    capabilities.extend(caps);
+                }
+            }
+            LocalServerKind::LlamaCpp => {
+                context_window = context_window.or(self.llamacpp_context().await);
+                capabilities.push(ModelCapability::FunctionCalling);
+            }
+            LocalServerKind::Vllm | LocalServerKind::OpenAiCompatible => {
+                capabilities.push(ModelCapability::FunctionCalling);
+            }
+        }
+
+        // vLLM reports max_model_len, llama.cpp the trained length in meta
+        let context_window = context_window
+            .or_else(|| entry["max_model_len"].as_u64().map(|v| v as usize))
+            .or_else(|| entry["meta"]["n_ctx_train"].as_u64().map(|v| v as usize))
+            .unwrap_or(0);
+
+        let model_info = ModelInfo {
+            name: id.clone(),
+            id,
+            provider: self.kind.provider_name().to_string(),
+            capabilities,
+            context_window,
+            max_output_tokens: None,
+            // Running locally costs nothing per token
+            cost_per_1k_prompt_tokens: Some(0.0),
+            cost_per_1k_completion_tokens: Some(0.0),
+        };
+        defaults::register_detected_model(&model_info);
+        Some(defaults::enhance_model_info(model_info))
+    }
+
+    /// Build the chat completions request body
+    fn request_body(&self, options: &ResponseOptions, request: &Request) -> Value {
+        let mut messages = Vec::new();
+        if let Some(system) = request.system.as_ref().filter(|s| !s.is_empty()) {
+            messages.push(json!({ "role": "system", "content": system.join("\n\n") }));
+        }
+        for message in request
+            .messages
+            .iter()
+            .filter(|m| Message::estimate_word_count(&m.content) > 0)
+        {
+            push_message(&mut messages, message);
+        }
+
+        let mut body = json!({
+            "model": options.model_info.id,
+            "messages": messages,
+            "stream": false,
+        });
+        if let Some(temperature) = options.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(top_p) = options.top_p {
+            body["top_p"] = json!(top_p);
+        }
+        if let Some(max_tokens) = options.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+        if !options.stop_sequences.is_empty() {
+            body["stop"] = json!(options.stop_sequences);
+        }
+
+        let tools: Vec<Value> = request
+            .tools
+            .iter()
+            .flatten()
+            .map(|tool| {
+                let parameters = tool
+                    .schema
+                    .clone()
+                    .map(crate::tool::schema_simplifier::simplify_for_local)
+                    .unwrap_or_else(|| json!({ "type": "object", "properties": {} }));
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": tool.name,
+                        "description": tool.description.clone().unwrap_or_default(),
+                        "parameters": parameters,
+                    }
+                })
+            })
+            .collect();
+        if !tools.is_empty() {
+            body["tools"] = Value::Array(tools);
+        }
+
+        body
+    }
+
+    /// Turn a chat completions response into ours
+    fn parse_response(&self, model: &str, body: Value) -> Result<Response> {
+        let message = &body["choices"][0]["message"];
+        if message.is_null() {
+            return Err(CoreError::ProviderHttpError {
+                provider: self.kind.provider_name().to_string(),
+                model: model.to_string(),
+                status: 502,
+                headers: Vec::new(),
+                body: body.to_string(),
+            });
+        }
+
+        let mut reasoning = message["reasoning_content"]
+            .as_str()
+            .or_else(|| message["reasoning"].as_str())
+            .filter(|r| !r.trim().is_empty())
+            .map(str::to_string);
+
+        let mut text = message["content"].as_str().unwrap_or_default().to_string();
+        // Qwen, DeepSeek and friends put their reasoning inline
+        if let Some((thinking, rest)) = split_think_tags(&text) {
+            reasoning.get_or_insert(thinking);
+            text = rest;
+        }
+
+        let calls: Vec<ToolCall> = message["tool_calls"]
+            .as_array()
+            .map(|calls| calls.iter().filter_map(parse_tool_call).collect())
+            .unwrap_or_default();
+
+        let mut content = Vec::new();
+        if !text.trim().is_empty() {
+            content.push(MessageContent::Text(text));
+        }
+        if !calls.is_empty() {
+            content.push(MessageContent::ToolCalls(calls));
+        }
+
+        let usage = &body["usage"];
+        let count = |key: &str| usage[key].as_i64().map(|n| n as i32);
+        let tokens_used = usage.is_object().then(|| genai::chat::Usage {
+            prompt_tokens: count("prompt_tokens"),
+            completion_tokens: count("completion_tokens"),
+            total_tokens: count("total_tokens"),
+            ..Default::default()
+        });
+
+        Ok(Response {
+            content,
+            reasoning,
+            metadata: ResponseMetadata {
+                tokens_used,
+                model_used: Some(body["model"].as_str().unwrap_or(model).to_string()),
+                model_iden: ModelIden::new(self.adapter_kind(), model),
+                ..Default::default()
+            },
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ModelProvider for LocalModelProvider {
+    fn name(&self) -> &str {
+        self.kind.provider_name()
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let response = self
+            .request(self.client.get(self.url("/v1/models")))
+            .send()
+            .await
+            .map_err(|e| self.transport_error("", "list_models", e))?;
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| self.transport_error("", "list_models", e))?;
+
+        let mut models = Vec::new();
+        for entry in body["data"].as_array().into_iter().flatten() {
+            if let Some(model) = self.describe_model(entry).await {
+                models.push(model);
+            }
+        }
+        Ok(models)
+    }
+
+    async fn complete(&self, options: &ResponseOptions, mut request: Request) -> Result<Response> {
+        request.prepare_messages();
+        let body = self.request_body(options, &request);
+        tracing::debug!("Local chat request:\n{:#}", body);
+
+        let model = &options.model_info.id;
+        let response = self
+            .request(self.client.post(self.url("/v1/chat/completions")))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| self.transport_error(model, "chat_completion", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let headers = response
+                .headers()
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or_default().to_string()))
+                .collect();
+            let body = response.text().await.unwrap_or_default();
+            return Err(CoreError::ProviderHttpError {
+                provider: self.kind.provider_name().to_string(),
+                model: model.clone(),
+                status: status.as_u16(),
+                headers,
+                body,
+            });
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| self.transport_error(model, "chat_completion", e))?;
+        tracing::debug!("Local chat response:\n{:#}", body);
+        self.parse_response(model, body)
+    }
+
+    async fn supports_capability(&self, model: &str, capability: ModelCapability) -> bool {
+        match self.list_models().await {
+            Ok(models) => models
+                .iter()
+                .find(|info| info.id == model)
+                .is_some_and(|info| info.capabilities.contains(&capability)),
+            Err(e) => {
+                tracing::warn!("Could not check capabilities of {}: {}", model, e);
+                false
+            }
+        }
+    }
+
+    async fn count_tokens(&self, _model: &str, content: &str) -> Result<usize> {
+        Ok(content.len() / 4)
+    }
+}
+
+/// Append a message in chat completions form
+///
+/// Tool responses become one `tool` message per call, whatever role they
+/// were stored under.
+fn push_message(messages: &mut Vec<Value>, message: &Message) {
+    let role = message.role.to_string();
+    match &message.content {
+        MessageContent::Text(text) => {
+            messages.push(json!({ "role": role, "content": text }));
+        }
+        MessageContent::Parts(parts) if message.role.is_user() => {
+            let parts: Vec<Value> = parts
+                .iter()
+                .map(|part| match part {
+                    ContentPart::Text(text) => json!({ "type": "text", "text": text }),
+                    ContentPart::Image {
+                        content_type,
+                        source,
+                    } => {
+                        let url = match source {
+                            ImageSource::Url(url) => url.clone(),
+                            ImageSource::Base64(data) => {
+                                format!("data:{};base64,{}", content_type, data)
+                            }
+                        };
+                        json!({ "type": "image_url", "image_url": { "url": url } })
+                    }
+                })
+                .collect();
+            messages.push(json!({ "role": role, "content": parts }));
+        }
+        MessageContent::Parts(parts) => {
+            let text = parts
+                .iter()
+                .filter_map(|part| match part {
+                    ContentPart::Text(text) => Some(text.as_str()),
+                    ContentPart::Image { .. } => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            messages.push(json!({ "role": role, "content": text }));
+        }
+        MessageContent::ToolCalls(calls) => {
+            let calls: Vec<Value> = calls
+                .iter()
+                .map(|c| tool_call_json(&c.call_id, &c.fn_name, &c.fn_arguments))
+                .collect();
+            messages.push(json!({ "role": "assistant", "content": "", "tool_calls": calls }));
+        }
+        MessageContent::ToolResponses(responses) => {
+            for response in responses {
+                messages.push(json!({
+                    "role": "tool",
+                    "tool_call_id": response.call_id,
+                    "content": response.content,
+                }));
+            }
+        }
+        MessageContent::Blocks(blocks) => {
+            let mut text = Vec::new();
+            let mut calls = Vec::new();
+            let mut results = Vec::new();
+            for block in blocks {
+                match block {
+                    ContentBlock::Text { text: t, .. } => text.push(t.as_str()),
+                    ContentBlock::ToolUse {
+                        id, name, input, ..
+                    } => calls.push(tool_call_json(id, name, input)),
+                    ContentBlock::ToolResult {
+                        tool_use_id,
+                        content,
+                        ..
+                    } => results.push(json!({
+                        "role": "tool",
+                        "tool_call_id": tool_use_id,
+                        "content": content,
+                    })),
+                    // Another provider's thinking can't be replayed here
+                    ContentBlock::Thinking { .. } | ContentBlock::RedactedThinking { .. } => {}
+                }
+            }
+            if !text.is_empty() || !calls.is_empty() {
+                let mut entry = json!({ "role": role, "content": text.join("\n") });
+                if !calls.is_empty() {
+                    entry["tool_calls"] = Value::Array(calls);
+                }
+                messages.push(entry);
+            }
+            messages.extend(results);
+        }
+    }
+}
+
+fn tool_call_json(id: &str, name: &str, arguments: &Value) -> Value {
+    json!({
+        "id": id,
+        "type": "function",
+        "function": { "name": name, "arguments": arguments.to_string() },
+    })
+}
+
+fn parse_tool_call(call: &Value) -> Option<ToolCall> {
+    let function = &call["function"];
+    let fn_name = function["name"].as_str()?.to_string();
+    // Arguments should be a JSON string, but some servers send the object
+    let fn_arguments = match &function["arguments"] {
+        Value::String(raw) => serde_json::from_str(raw).unwrap_or_else(|_| json!({ "raw": raw })),
+        Value::Null => json!({}),
+        other => other.clone(),
+    };
+    // Ollama doesn't always send call IDs
+    let call_id = call["id"]
+        .as_str()
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("call_{}", uuid::Uuid::new_v4().simple()));
+    Some(ToolCall {
+        call_id,
+        fn_name,
+        fn_arguments,
+    })
+}
+
+/// Split a leading `<think>...</think>` block from the answer
+fn split_think_tags(text: &str) -> Option<(String, String)> {
+    let rest = text.trim_start().strip_prefix("<think>")?;
+    let (thinking, answer) = rest.split_once("</think>")?;
+    Some((thinking.trim().to_string(), answer.trim_start().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::ToolResponse;
+
+    fn provider() -> LocalModelProvider {
+        LocalModelProvider::new(LocalServerKind::Ollama, "http://localhost:11434/v1/").unwrap()
+    }
+
+    fn options() -> ResponseOptions {
+        ResponseOptions::new(ModelInfo {
+            id: "qwen3:14b".to_string(),
+            name: "qwen3:14b".to_string(),
+            provider: "Ollama".to_string(),
+            capabilities: vec![],
+            context_window: 40_960,
+            max_output_tokens: Some(8_192),
+            cost_per_1k_prompt_tokens: None,
+            cost_per_1k_completion_tokens: None,
+        })
+    }
+
+    #[test]
+    fn test_base_url_is_normalised() {
+        assert_eq!(
+            provider().url("/v1/models"),
+            "http://localhost:11434/v1/models"
+        );
+    }
+
+    #[test]
+    fn test_from_model_config() {
+        let mut config = crate::config::ModelConfig {
+            provider: "ollama".to_string(),
+            ..Default::default()
+        };
+        config.settings.insert(
+            "base_url".to_string(),
+            toml::Value::String("http://gpu-box:11434".to_string()),
+        );
+        config
+            .settings
+            .insert("context_window".to_string(), toml::Value::Integer(32_768));
+
+        let local = LocalModelProvider::from_model_config(&config)
+            .unwrap()
+            .unwrap();
+        assert_eq!(local.kind(), LocalServerKind::Ollama);
+        assert_eq!(local.base_url, "http://gpu-box:11434");
+        assert_eq!(local.context_window, Some(32_768));
+
+        let hosted = crate::config::ModelConfig::default();
+        assert!(
+            LocalModelProvider::from_model_config(&hosted)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_is_a_provider_error() {
+        // Nothing listens on port 9 (discard), so the connection is refused
+        let local =
+            LocalModelProvider::new(LocalServerKind::OpenAiCompatible, "http://127.0.0.1:9")
+                .unwrap();
+
+        let err = local
+            .complete(
+                &options(),
+                Request {
+                    system: None,
+                    messages: vec![Message::user("hello")],
+                    tools: None,
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::ModelProviderError { ref model, .. } if model == "qwen3:14b"
+        ));
+        assert!(
+            !local
+                .supports_capability("qwen3:14b", ModelCapability::TextGeneration)
+                .await
+        );
+    }
+
+    #[test]
+    fn test_handles_only_its_own_models() {
+        let local = provider();
+        let mut info = options().model_info;
+        assert!(local.handles(&info));
+        info.provider = "ollama".to_string();
+        assert!(local.handles(&info));
+        info.provider = "Gemini".to_string();
+        assert!(!local.handles(&info));
+    }
+
+    #[test]
+    fn test_request_body_converts_tool_exchange() {
+        let mut call = Message::agent("");
+        call.content = MessageContent::ToolCalls(vec![ToolCall {
+            call_id: "call_1".to_string(),
+            fn_name: "recall".to_string(),
+            fn_arguments: json!({ "query": "tea" }),
+        }]);
+        let mut result = Message::agent("");
+        result.content = MessageContent::ToolResponses(vec![ToolResponse {
+            call_id: "call_1".to_string(),
+            content: "green".to_string(),
+            is_error: None,
+        }]);
+        let request = Request {
+            system: Some(vec!["You are Pattern.".to_string()]),
+            messages: vec![Message::user("what tea do I like?"), call, result],
+            tools: Some(vec![
+                genai::chat::Tool::new("recall")
+                    .with_description("Search memory")
+                    .with_schema(json!({
+                        "type": "object",
+                        "properties": {
+                            "limit": { "anyOf": [{ "type": "integer" }, { "type": "null" }] }
+                        }
+                    })),
+            ]),
+        };
+
+        let body = provider().request_body(&options(), &request);
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages[0]["role"], "system");
+        assert_eq!(messages[1]["role"], "user");
+        assert_eq!(messages[2]["tool_calls"][0]["function"]["name"], "recall");
+        assert_eq!(
+            messages[2]["tool_calls"][0]["function"]["arguments"],
+            r#"{"query":"tea"}"#
+        );
+        assert_eq!(messages[3]["role"], "tool");
+        assert_eq!(messages[3]["tool_call_id"], "call_1");
+
+        let parameters = &body["tools"][0]["function"]["parameters"];
+        assert_eq!(parameters["properties"]["limit"]["type"], "integer");
+    }
+
+    #[test]
+    fn test_parse_response_with_tool_calls_and_thinking() {
+        let body = json!({
+            "model": "qwen3:14b",
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": "<think>They want their tea.</think>\nLet me check.",
+                    "tool_calls": [{
+                        "function": { "name": "recall", "arguments": { "query": "tea" } }
+                    }]
+                }
+            }],
+            "usage": { "prompt_tokens": 120, "completion_tokens": 30, "total_tokens": 150 }
+        });
+
+        let response = provider().parse_response("qwen3:14b", body).unwrap();
+        assert_eq!(response.reasoning.as_deref(), Some("They want their tea."));
+        assert_eq!(response.content[0].text(), Some("Let me check."));
+        let MessageContent::ToolCalls(calls) = &response.content[1] else {
+            panic!("expected tool calls");
+        };
+        assert_eq!(calls[0].fn_name, "recall");
+        assert_eq!(calls[0].fn_arguments["query"], "tea");
+        assert!(calls[0].call_id.starts_with("call_"));
+        assert_eq!(
+            response.metadata.tokens_used.unwrap().prompt_tokens,
+            Some(120)
+        );
+    }
+}
@@ -1,10 +1,16 @@
+use chrono::{DateTime, Utc};
+use pattern_macros::Entity;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use surrealdb::{Surreal, engine::any::Any};
 use tokio::sync::{RwLock, broadcast, oneshot};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::db::ops;
+use crate::id::{IdType, PermissionAuditId, PermissionGrantId};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PermissionScope {
     MemoryEdit {
         key: String,
@@ -22,6 +28,49 @@ pub enum PermissionScope {
     },
 }
 
+impl PermissionScope {
+    /// Whether a grant for this scope also permits `other`
+    ///
+    /// A batch grant covers every key under its prefix, and a tool grant
+    /// without an argument digest covers every call to that tool.
+    pub fn covers(&self, other: &PermissionScope) -> bool {
+        match (self, other) {
+            (Self::MemoryBatch { prefix }, Self::MemoryEdit { key }) => key.starts_with(prefix),
+            (Self::MemoryBatch { prefix }, Self::MemoryBatch { prefix: other }) => {
+                other.starts_with(prefix)
+            }
+            (
+                Self::ToolExecution {
+                    tool,
+                    args_digest: None,
+                },
+                Self::ToolExecution { tool: other, .. },
+            ) => tool == other,
+            _ => self == other,
+        }
+    }
+}
+
+impl std::fmt::Display for PermissionScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MemoryEdit { key } => write!(f, "memory:{}", key),
+            Self::MemoryBatch { prefix } => write!(f, "memory:{}*", prefix),
+            Self::ToolExecution {
+                tool,
+                args_digest: None,
+            } => write!(f, "tool:{}", tool),
+            Self::ToolExecution {
+                tool,
+                args_digest: Some(digest),
+            } => write!(f, "tool:{}#{}", tool, digest),
+            Self::DataSourceAction { source_id, action } => {
+                write!(f, "source:{}/{}", source_id, action)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PermissionGrant {
     pub id: String,
@@ -50,11 +99,146 @@ pub enum PermissionDecisionKind {
     ApproveForScope,
 }
 
+impl std::fmt::Display for PermissionDecisionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Deny => write!(f, "deny"),
+            Self::ApproveOnce => write!(f, "once"),
+            Self::ApproveForDuration(duration) => write!(f, "ttl={}", duration.as_secs()),
+            Self::ApproveForScope => write!(f, "always"),
+        }
+    }
+}
+
+/// A standing grant from an `always` or `ttl=` approval
+///
+/// Grants are checked before an agent is prompted again for the same scope,
+/// until they expire or are revoked.
+#[derive(Debug, Clone, Entity, Serialize, Deserialize)]
+#[entity(entity_type = "permission_grant")]
+pub struct PermissionGrantRecord {
+    pub id: PermissionGrantId,
+    pub agent_id: crate::AgentId,
+    #[entity(db_type = "object")]
+    pub scope: PermissionScope,
+    /// Tool that made the request the grant was approved from
+    pub tool_name: String,
+    /// Broker request the grant was approved from
+    pub request_id: String,
+    pub granted_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Who approved it, e.g. "cli" or "discord:1234"
+    pub granted_by: Option<String>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revoked_by: Option<String>,
+}
+
+impl PermissionGrantRecord {
+    /// Whether the grant can still be used at `now`
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires| expires > now)
+    }
+
+    fn to_grant(&self) -> PermissionGrant {
+        PermissionGrant {
+            id: self.id.to_key(),
+            scope: self.scope.clone(),
+            expires_at: self.expires_at,
+        }
+    }
+}
+
+/// What happened in a permission audit entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionAuditAction {
+    /// An agent asked for permission
+    Requested,
+    /// A standing grant let the request through without asking
+    GrantUsed,
+    Approved,
+    Denied,
+    /// Nobody answered before the request timed out
+    TimedOut,
+    /// A standing grant was revoked
+    Revoked,
+}
+
+impl std::fmt::Display for PermissionAuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Requested => "requested",
+            Self::GrantUsed => "grant_used",
+            Self::Approved => "approved",
+            Self::Denied => "denied",
+            Self::TimedOut => "timed_out",
+            Self::Revoked => "revoked",
+        };
+        f.write_str(s)
+    }
+}
+
+/// One line of the append-only permission audit log
+#[derive(Debug, Clone, Entity, Serialize, Deserialize)]
+#[entity(entity_type = "permission_audit")]
+pub struct PermissionAuditEntry {
+    pub id: PermissionAuditId,
+    pub agent_id: crate::AgentId,
+    pub action: PermissionAuditAction,
+    pub tool_name: String,
+    #[entity(db_type = "object")]
+    pub scope: PermissionScope,
+    /// Broker request this entry belongs to
+    pub request_id: Option<String>,
+    /// Grant created, used or revoked
+    pub grant_id: Option<PermissionGrantId>,
+    /// Decision as typed by the approver: once, always, ttl=<secs> or deny
+    pub decision: Option<String>,
+    pub reason: Option<String>,
+    /// Who made the decision, e.g. "cli" or "discord:1234"
+    pub actor: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PermissionAuditEntry {
+    fn new(
+        agent_id: &crate::AgentId,
+        action: PermissionAuditAction,
+        tool_name: &str,
+        scope: &PermissionScope,
+    ) -> Self {
+        Self {
+            id: PermissionAuditId::generate(),
+            agent_id: agent_id.clone(),
+            action,
+            tool_name: tool_name.to_string(),
+            scope: scope.clone(),
+            request_id: None,
+            grant_id: None,
+            decision: None,
+            reason: None,
+            actor: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// A decision along with who made it
+struct Resolution {
+    decision: PermissionDecisionKind,
+    actor: Option<String>,
+}
+
 #[derive(Clone)]
 pub struct PermissionBroker {
     tx: broadcast::Sender<PermissionRequest>,
-    pending: Arc<RwLock<HashMap<String, oneshot::Sender<PermissionDecisionKind>>>>,
+    pending: Arc<RwLock<HashMap<String, oneshot::Sender<Resolution>>>>,
     pending_info: Arc<RwLock<HashMap<String, PermissionRequest>>>,
+    /// Where grants and the audit log are persisted, once a database is attached
+    db: Arc<RwLock<Option<Surreal<Any>>>>,
+    /// Grants and audit entries made while no database is attached
+    memory_grants: Arc<RwLock<Vec<PermissionGrantRecord>>>,
+    memory_audit: Arc<RwLock<Vec<PermissionAuditEntry>>>,
 }
 
 impl PermissionBroker {
@@ -64,9 +248,17 @@ impl PermissionBroker {
             tx,
             pending: Arc::new(RwLock::new(HashMap::new())),
             pending_info: Arc::new(RwLock::new(HashMap::new())),
+            db: Arc::new(RwLock::new(None)),
+            memory_grants: Arc::new(RwLock::new(Vec::new())),
+            memory_audit: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
+    /// Persist grants and audit entries to this database from now on
+    pub async fn attach_db(&self, db: Surreal<Any>) {
+        *self.db.write().await = Some(db);
+    }
+
     pub fn subscribe(&self) -> broadcast::Receiver<PermissionRequest> {
         self.tx.subscribe()
     }
@@ -81,6 +273,25 @@ impl PermissionBroker {
         timeout: std::time::Duration,
     ) -> Option<PermissionGrant> {
         tracing::debug!("permission.request tool={} scope={:?}", tool_name, scope);
+
+        if let Some(existing) = self.find_grant(&agent_id, &scope).await {
+            tracing::debug!(
+                "permission.request satisfied by grant {} for scope {}",
+                existing.id,
+                scope
+            );
+            let mut entry = PermissionAuditEntry::new(
+                &agent_id,
+                PermissionAuditAction::GrantUsed,
+                &tool_name,
+                &scope,
+            );
+            entry.grant_id = Some(existing.id.clone());
+            entry.reason = reason;
+            self.record(entry).await;
+            return Some(existing.to_grant());
+        }
+
         let id = Uuid::new_v4().to_string();
         let (tx_decision, rx_decision) = oneshot::channel();
         {
@@ -92,48 +303,94 @@ impl PermissionBroker {
             agent_id: agent_id.clone(),
             tool_name: tool_name.clone(),
             scope: scope.clone(),
-            reason,
+            reason: reason.clone(),
             metadata,
         };
         {
             let mut pi = self.pending_info.write().await;
             pi.insert(id.clone(), req.clone());
         }
+
+        let audit = |action: PermissionAuditAction| {
+            let mut entry = PermissionAuditEntry::new(&agent_id, action, &tool_name, &scope);
+            entry.request_id = Some(id.clone());
+            entry.reason = reason.clone();
+            entry
+        };
+        self.record(audit(PermissionAuditAction::Requested)).await;
         let _ = self.tx.send(req);
 
-        match tokio::time::timeout(timeout, rx_decision).await {
-            Ok(Ok(decision)) => match decision {
-                PermissionDecisionKind::Deny => None,
-                PermissionDecisionKind::ApproveOnce => Some(PermissionGrant {
-                    id,
-                    scope,
-                    expires_at: None,
-                }),
-                PermissionDecisionKind::ApproveForScope => Some(PermissionGrant {
+        let Ok(Ok(Resolution { decision, actor })) =
+            tokio::time::timeout(timeout, rx_decision).await
+        else {
+            tracing::warn!(
+                "permission.request timeout or channel closed: tool={} scope={:?}",
+                tool_name,
+                scope
+            );
+            self.pending.write().await.remove(&id);
+            self.pending_info.write().await.remove(&id);
+            self.record(audit(PermissionAuditAction::TimedOut)).await;
+            return None;
+        };
+
+        let mut entry = audit(match decision {
+            PermissionDecisionKind::Deny => PermissionAuditAction::Denied,
+            _ => PermissionAuditAction::Approved,
+        });
+        entry.decision = Some(decision.to_string());
+        entry.actor = actor.clone();
+
+        let expires_at = match &decision {
+            PermissionDecisionKind::Deny => {
+                self.record(entry).await;
+                return None;
+            }
+            PermissionDecisionKind::ApproveOnce => {
+                self.record(entry).await;
+                return Some(PermissionGrant {
                     id,
                     scope,
                     expires_at: None,
-                }),
-                PermissionDecisionKind::ApproveForDuration(dur) => Some(PermissionGrant {
-                    id,
-                    scope,
-                    expires_at: Some(
-                        chrono::Utc::now() + chrono::Duration::from_std(dur).unwrap_or_default(),
-                    ),
-                }),
-            },
-            _ => {
-                tracing::warn!(
-                    "permission.request timeout or channel closed: tool={} scope={:?}",
-                    tool_name,
-                    scope
-                );
-                None
+                });
             }
-        }
+            PermissionDecisionKind::ApproveForScope => None,
+            // A TTL too large to represent simply never expires
+            PermissionDecisionKind::ApproveForDuration(dur) => chrono::Duration::from_std(*dur)
+                .ok()
+                .and_then(|dur| Utc::now().checked_add_signed(dur)),
+        };
+
+        let record = PermissionGrantRecord {
+            id: PermissionGrantId::generate(),
+            agent_id: agent_id.clone(),
+            scope: scope.clone(),
+            tool_name: tool_name.clone(),
+            request_id: id.clone(),
+            granted_at: Utc::now(),
+            expires_at,
+            granted_by: actor,
+            revoked_at: None,
+            revoked_by: None,
+        };
+        self.store_grant(&record).await;
+        entry.grant_id = Some(record.id.clone());
+        self.record(entry).await;
+
+        Some(record.to_grant())
     }
 
     pub async fn resolve(&self, request_id: &str, decision: PermissionDecisionKind) -> bool {
+        self.resolve_as(request_id, decision, None).await
+    }
+
+    /// Resolve a pending request, noting who decided for the audit log
+    pub async fn resolve_as(
+        &self,
+        request_id: &str,
+        decision: PermissionDecisionKind,
+        actor: Option<String>,
+    ) -> bool {
         let tx_opt = { self.pending.write().await.remove(request_id) };
         {
             let mut pi = self.pending_info.write().await;
@@ -145,7 +402,7 @@ impl PermissionBroker {
                 request_id,
                 decision
             );
-            let _ = tx.send(decision);
+            let _ = tx.send(Resolution { decision, actor });
             true
         } else {
             false
@@ -156,6 +413,159 @@ impl PermissionBroker {
         let pi = self.pending_info.read().await;
         pi.values().cloned().collect()
     }
+
+    /// Standing grants, newest first, optionally for one agent
+    ///
+    /// Expired and revoked grants are only included with `include_inactive`.
+    pub async fn list_grants(
+        &self,
+        agent_id: Option<&crate::AgentId>,
+        include_inactive: bool,
+    ) -> crate::Result<Vec<PermissionGrantRecord>> {
+        let grants = match self.db.read().await.as_ref() {
+            Some(db) => ops::list_permission_grants(db, agent_id).await?,
+            None => {
+                let mut grants: Vec<_> = self
+                    .memory_grants
+                    .read()
+                    .await
+                    .iter()
+                    .filter(|g| agent_id.is_none_or(|a| &g.agent_id == a))
+                    .cloned()
+                    .collect();
+                grants.sort_by(|a, b| b.granted_at.cmp(&a.granted_at));
+                grants
+            }
+        };
+
+        let now = Utc::now();
+        Ok(grants
+            .into_iter()
+            .filter(|g| include_inactive || g.is_active_at(now))
+            .collect())
+    }
+
+    /// Revoke a standing grant so the agent is asked again next time
+    ///
+    /// Returns the revoked grant, or `None` if there's no active grant with that ID.
+    pub async fn revoke(
+        &self,
+        grant_id: &PermissionGrantId,
+        actor: Option<String>,
+    ) -> crate::Result<Option<PermissionGrantRecord>> {
+        let now = Utc::now();
+        let existing = match self.db.read().await.as_ref() {
+            Some(db) => ops::get_permission_grant(db, grant_id).await?,
+            None => self
+                .memory_grants
+                .read()
+                .await
+                .iter()
+                .find(|g| &g.id == grant_id)
+                .cloned(),
+        };
+        let Some(mut grant) = existing.filter(|g| g.is_active_at(now)) else {
+            return Ok(None);
+        };
+
+        grant.revoked_at = Some(now);
+        grant.revoked_by = actor.clone();
+        match self.db.read().await.as_ref() {
+            Some(db) => {
+                ops::update_permission_grant(db, &grant).await?;
+            }
+            None => {
+                if let Some(stored) = self
+                    .memory_grants
+                    .write()
+                    .await
+                    .iter_mut()
+                    .find(|g| g.id == grant.id)
+                {
+                    *stored = grant.clone();
+                }
+            }
+        }
+
+        let mut entry = PermissionAuditEntry::new(
+            &grant.agent_id,
+            PermissionAuditAction::Revoked,
+            &grant.tool_name,
+            &grant.scope,
+        );
+        entry.request_id = Some(grant.request_id.clone());
+        entry.grant_id = Some(grant.id.clone());
+        entry.actor = actor;
+        self.record(entry).await;
+
+        Ok(Some(grant))
+    }
+
+    /// The most recent audit entries, newest first, optionally for one agent
+    pub async fn audit_log(
+        &self,
+        agent_id: Option<&crate::AgentId>,
+        limit: usize,
+    ) -> crate::Result<Vec<PermissionAuditEntry>> {
+        match self.db.read().await.as_ref() {
+            Some(db) => Ok(ops::list_permission_audit(db, agent_id, limit).await?),
+            None => Ok(self
+                .memory_audit
+                .read()
+                .await
+                .iter()
+                .rev()
+                .filter(|e| agent_id.is_none_or(|a| &e.agent_id == a))
+                .take(limit)
+                .cloned()
+                .collect()),
+        }
+    }
+
+    async fn find_grant(
+        &self,
+        agent_id: &crate::AgentId,
+        scope: &PermissionScope,
+    ) -> Option<PermissionGrantRecord> {
+        match self.list_grants(Some(agent_id), false).await {
+            Ok(grants) => grants.into_iter().find(|g| g.scope.covers(scope)),
+            Err(e) => {
+                // Fall through to asking rather than failing the tool call
+                crate::log_error!("Failed to look up permission grants", e);
+                None
+            }
+        }
+    }
+
+    async fn store_grant(&self, grant: &PermissionGrantRecord) {
+        match self.db.read().await.as_ref() {
+            Some(db) => {
+                if let Err(e) = ops::create_permission_grant(db, grant).await {
+                    crate::log_error!("Failed to persist permission grant", e);
+                }
+            }
+            None => self.memory_grants.write().await.push(grant.clone()),
+        }
+    }
+
+    /// Append to the audit log; failures are logged but never block a decision
+    async fn record(&self, entry: PermissionAuditEntry) {
+        tracing::info!(
+            "permission.audit agent={} action={} tool={} scope={}",
+            entry.agent_id,
+            entry.action,
+            entry.tool_name,
+            entry.scope
+        );
+        match self.db.read().await.as_ref() {
+            Some(db) => {
+                if let Err(e) = ops::append_permission_audit(db, &entry).await {
+                    crate::log_error!("Failed to write permission audit entry", e);
+                }
+            }
+            None => self.memory_audit.write().await.push(entry),
+        }
+    }
 }
 
 use std::sync::OnceLock;
@@ -164,3 +574,150 @@ static BROKER: OnceLock<PermissionBroker> = OnceLock::new();
 pub fn broker() -> &'static PermissionBroker {
     BROKER.get_or_init(|| PermissionBroker::new())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AgentId;
+    use crate::db::client::create_test_db;
+    use std::time::Duration;
+
+    /// Approve the next request the broker broadcasts
+    fn approve_next(broker: &PermissionBroker, decision: PermissionDecisionKind) {
+        let mut rx = broker.subscribe();
+        let broker = broker.clone();
+        tokio::spawn(async move {
+            if let Ok(req) = rx.recv().await {
+                broker
+                    .resolve_as(&req.id, decision, Some("test".to_string()))
+                    .await;
+            }
+        });
+    }
+
+    #[test]
+    fn test_scope_covers() {
+        let batch = PermissionScope::MemoryBatch {
+            prefix: "notes_".to_string(),
+        };
+        assert!(batch.covers(&PermissionScope::MemoryEdit {
+            key: "notes_today".to_string()
+        }));
+        assert!(!batch.covers(&PermissionScope::MemoryEdit {
+            key: "human".to_string()
+        }));
+
+        let any_call = PermissionScope::ToolExecution {
+            tool: "web".to_string(),
+            args_digest: None,
+        };
+        let one_call = PermissionScope::ToolExecution {
+            tool: "web".to_string(),
+            args_digest: Some("abc".to_string()),
+        };
+        assert!(any_call.covers(&one_call));
+        assert!(!one_call.covers(&any_call));
+    }
+
+    #[tokio::test]
+    async fn test_scope_grant_persists_and_revokes() {
+        let broker = PermissionBroker::new();
+        broker.attach_db(create_test_db().await.unwrap()).await;
+        let agent_id = AgentId::generate();
+        let scope = PermissionScope::ToolExecution {
+            tool: "web".to_string(),
+            args_digest: None,
+        };
+        let ask = || {
+            broker.request(
+                agent_id.clone(),
+                "web".to_string(),
+                scope.clone(),
+                None,
+                None,
+                Duration::from_millis(200),
+            )
+        };
+
+        approve_next(&broker, PermissionDecisionKind::ApproveForScope);
+        let first = ask().await.unwrap();
+
+        // No one is listening now, so this only succeeds through the stored grant
+        let second = ask().await.unwrap();
+        assert_eq!(first.id, second.id);
+
+        let grants = broker.list_grants(Some(&agent_id), false).await.unwrap();
+        assert_eq!(grants.len(), 1);
+        assert_eq!(grants[0].granted_by.as_deref(), Some("test"));
+
+        let revoked = broker
+            .revoke(&grants[0].id, Some("cli".to_string()))
+            .await
+            .unwrap();
+        assert!(revoked.is_some());
+        assert!(ask().await.is_none());
+
+        let actions: Vec<_> = broker
+            .audit_log(Some(&agent_id), 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.action)
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                PermissionAuditAction::TimedOut,
+                PermissionAuditAction::Requested,
+                PermissionAuditAction::Revoked,
+                PermissionAuditAction::GrantUsed,
+                PermissionAuditAction::Approved,
+                PermissionAuditAction::Requested,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_duration_grant_expires() {
+        let broker = PermissionBroker::new();
+        let agent_id = AgentId::generate();
+        let scope = PermissionScope::MemoryEdit {
+            key: "human".to_string(),
+        };
+
+        approve_next(
+            &broker,
+            PermissionDecisionKind::ApproveForDuration(Duration::from_secs(60)),
+        );
+        let grant = broker
+            .request(
+                agent_id.clone(),
+                "context".to_string(),
+                scope.clone(),
+                None,
+                None,
+                Duration::from_millis(200),
+            )
+            .await
+            .unwrap();
+        assert!(grant.expires_at.is_some());
+
+        // Pretend the minute has passed
+        broker.memory_grants.write().await[0].expires_at = Some(Utc::now());
+        assert!(
+            broker
+                .list_grants(Some(&agent_id), false)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            broker
+                .list_grants(Some(&agent_id), true)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
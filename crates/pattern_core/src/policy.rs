@@ -0,0 +1,513 @@
+//! Declarative permission policy for tools and memory
+//!
+//! A policy file lists rules that are checked in order before the built-in
+//! defaults (the per-tool `RequiresConsent` rules and the [`MemoryPermission`]
+//! matrix in [`crate::memory_acl`]). The first rule that matches decides; if
+//! none match, the defaults apply as before.
+//!
+//! ```toml
+//! # Archive can reorganise its notes without asking
+//! [[rules]]
+//! effect = "allow"
+//! agents = ["Archive"]
+//! memory_prefix = "notes/"
+//! operations = ["append", "overwrite"]
+//!
+//! # Web access after hours needs someone to approve it
+//! [[rules]]
+//! effect = "require_consent"
+//! tools = ["web"]
+//! outside_hours = "09:00-17:00"
+//! timezone = "Europe/London"
+//! reason = "Web access outside working hours needs partner approval"
+//!
+//! [[rules]]
+//! effect = "deny"
+//! tools = ["emergency_halt"]
+//! groups = ["Crisis"]
+//! ```
+//!
+//! [`MemoryPermission`]: crate::memory::MemoryPermission
+
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::id::AgentId;
+use crate::memory_acl::{MemoryGate, MemoryOp};
+
+/// Default policy file name, looked for next to the config file
+pub const DEFAULT_POLICY_FILE: &str = "policy.toml";
+
+/// Errors from loading a policy file
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+pub enum PolicyError {
+    #[error("Failed to read policy file {path}: {cause}")]
+    Io { path: String, cause: String },
+
+    #[error("Invalid policy file: {0}")]
+    #[diagnostic(help("See the policy module docs for the rule format"))]
+    Parse(String),
+
+    #[error("Policy rule {rule} has no tools or memory_prefix, so it would never match")]
+    #[diagnostic(help("Add `tools = [...]` or `memory_prefix = \"...\"` to the rule"))]
+    NoTarget { rule: String },
+
+    #[error("Invalid hours '{value}' in policy rule {rule}: expected HH:MM-HH:MM")]
+    InvalidHours { rule: String, value: String },
+
+    #[error("Unknown timezone '{value}' in policy rule {rule}")]
+    #[diagnostic(help("Use an IANA name such as \"Europe/London\""))]
+    InvalidTimezone { rule: String, value: String },
+}
+
+/// What a matching rule does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyEffect {
+    Allow,
+    RequireConsent,
+    Deny,
+}
+
+/// One rule as written in the policy file
+///
+/// Empty `agents`, `groups` and `operations` lists match anything. A rule
+/// applies to tool calls when `tools` is set and to memory edits when
+/// `memory_prefix` is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyRuleConfig {
+    /// Name shown in reasons and errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub effect: PolicyEffect,
+    /// Agent names or IDs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<String>,
+    /// Group names; the agent must belong to at least one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+    /// Tool names, or "*" for every tool
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    /// Memory block label prefix ("" for every block)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub operations: Vec<MemoryOp>,
+    /// Only match within these hours, e.g. "09:00-17:00"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hours: Option<String>,
+    /// Only match outside these hours
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outside_hours: Option<String>,
+    /// IANA timezone for `hours` and `outside_hours` (defaults to UTC)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Explanation given to the agent and to whoever is asked for consent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Contents of a policy file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicyConfig {
+    #[serde(default)]
+    pub rules: Vec<PolicyRuleConfig>,
+}
+
+/// Outcome of a matching rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyDecision {
+    Allow,
+    RequireConsent { reason: String },
+    Deny { reason: String },
+}
+
+impl From<PolicyDecision> for MemoryGate {
+    fn from(decision: PolicyDecision) -> Self {
+        match decision {
+            PolicyDecision::Allow => MemoryGate::Allow,
+            PolicyDecision::RequireConsent { reason } => MemoryGate::RequireConsent { reason },
+            PolicyDecision::Deny { reason } => MemoryGate::Deny { reason },
+        }
+    }
+}
+
+/// The agent a decision is being made for
+#[derive(Debug, Clone)]
+pub struct PolicySubject {
+    pub agent_id: AgentId,
+    pub agent_name: String,
+    /// Names of the groups the agent belongs to
+    pub groups: Vec<String>,
+}
+
+impl PolicySubject {
+    pub fn new(agent_id: AgentId, agent_name: impl Into<String>) -> Self {
+        Self {
+            agent_id,
+            agent_name: agent_name.into(),
+            groups: Vec::new(),
+        }
+    }
+
+    pub fn with_groups(mut self, groups: Vec<String>) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// Whether the subject is one of `agents` (by name or ID) and belongs to
+    /// one of `groups`; an empty list matches anything
+    pub fn matches(&self, agents: &[String], groups: &[String]) -> bool {
+        let agent_matches = agents.is_empty()
+            || agents.iter().any(|a| {
+                a.eq_ignore_ascii_case(&self.agent_name) || *a == self.agent_id.to_string()
+            });
+        let group_matches = groups.is_empty()
+            || groups
+                .iter()
+                .any(|g| self.groups.iter().any(|sg| sg.eq_ignore_ascii_case(g)));
+        agent_matches && group_matches
+    }
+}
+
+/// A daily time range; ranges like "22:00-06:00" wrap past midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DailyHours {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl DailyHours {
+    fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl FromStr for DailyHours {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_time = |t: &str| {
+            let t = t.trim();
+            NaiveTime::parse_from_str(t, "%H:%M")
+                .ok()
+                .or_else(|| {
+                    t.parse::<u32>()
+                        .ok()
+                        .and_then(|h| NaiveTime::from_hms_opt(h, 0, 0))
+                })
+                // "24:00" and "24" mean the end of the day
+                .or_else(|| (t == "24" || t == "24:00").then_some(NaiveTime::MIN))
+        };
+        let (start, end) = s.split_once('-').ok_or(())?;
+        Ok(Self {
+            start: parse_time(start).ok_or(())?,
+            end: parse_time(end).ok_or(())?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PolicyRule {
+    label: String,
+    config: PolicyRuleConfig,
+    hours: Option<DailyHours>,
+    outside_hours: Option<DailyHours>,
+    timezone: chrono_tz::Tz,
+}
+
+impl PolicyRule {
+    fn compile(index: usize, config: PolicyRuleConfig) -> Result<Self, PolicyError> {
+        let label = config
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("#{}", index + 1));
+        if config.tools.is_empty() && config.memory_prefix.is_none() {
+            return Err(PolicyError::NoTarget { rule: label });
+        }
+
+        let parse_hours = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(|v| {
+                    v.parse::<DailyHours>()
+                        .map_err(|_| PolicyError::InvalidHours {
+                            rule: label.clone(),
+                            value: v.to_string(),
+                        })
+                })
+                .transpose()
+        };
+        let hours = parse_hours(&config.hours)?;
+        let outside_hours = parse_hours(&config.outside_hours)?;
+        let timezone = match &config.timezone {
+            Some(tz) => tz.parse().map_err(|_| PolicyError::InvalidTimezone {
+                rule: label.clone(),
+                value: tz.clone(),
+            })?,
+            None => chrono_tz::UTC,
+        };
+
+        Ok(Self {
+            label,
+            config,
+            hours,
+            outside_hours,
+            timezone,
+        })
+    }
+
+    fn applies_to(&self, subject: &PolicySubject, now: DateTime<Utc>) -> bool {
+        let local = now.with_timezone(&self.timezone).time();
+        let time_matches = self.hours.is_none_or(|h| h.contains(local))
+            && self.outside_hours.is_none_or(|h| !h.contains(local));
+
+        time_matches && subject.matches(&self.config.agents, &self.config.groups)
+    }
+
+    fn decision(&self, default_reason: impl FnOnce() -> String) -> PolicyDecision {
+        let reason = || {
+            self.config
+                .reason
+                .clone()
+                .unwrap_or_else(|| format!("{} (policy rule {})", default_reason(), self.label))
+        };
+        match self.config.effect {
+            PolicyEffect::Allow => PolicyDecision::Allow,
+            PolicyEffect::RequireConsent => PolicyDecision::RequireConsent { reason: reason() },
+            PolicyEffect::Deny => PolicyDecision::Deny { reason: reason() },
+        }
+    }
+}
+
+/// Evaluates policy rules in file order
+#[derive(Debug, Clone, Default)]
+pub struct PolicyEngine {
+    rules: Vec<PolicyRule>,
+}
+
+impl PolicyEngine {
+    pub fn new(config: PolicyConfig) -> Result<Self, PolicyError> {
+        let rules = config
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(i, rule)| PolicyRule::compile(i, rule))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn from_toml(input: &str) -> Result<Self, PolicyError> {
+        let config: PolicyConfig =
+            toml::from_str(input).map_err(|e| PolicyError::Parse(e.to_string()))?;
+        Self::new(config)
+    }
+
+    pub async fn load(path: &Path) -> Result<Self, PolicyError> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| PolicyError::Io {
+                path: path.display().to_string(),
+                cause: e.to_string(),
+            })?;
+        Self::from_toml(&content)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether any rule matches on group membership
+    ///
+    /// Looking up an agent's groups costs a query, so callers skip it when no
+    /// rule needs them.
+    pub fn uses_groups(&self) -> bool {
+        self.rules.iter().any(|r| !r.config.groups.is_empty())
+    }
+
+    /// Decide on a tool call, or `None` to fall back to the tool's own rules
+    pub fn check_tool(
+        &self,
+        subject: &PolicySubject,
+        tool: &str,
+        now: DateTime<Utc>,
+    ) -> Option<PolicyDecision> {
+        self.rules
+            .iter()
+            .find(|r| {
+                r.config.tools.iter().any(|t| t == "*" || t == tool) && r.applies_to(subject, now)
+            })
+            .map(|r| r.decision(|| format!("Tool '{}' is restricted", tool)))
+    }
+
+    /// Decide on a memory edit, or `None` to fall back to the block's permission
+    pub fn check_memory(
+        &self,
+        subject: &PolicySubject,
+        label: &str,
+        op: MemoryOp,
+        now: DateTime<Utc>,
+    ) -> Option<PolicyDecision> {
+        self.rules
+            .iter()
+            .find(|r| {
+                r.config
+                    .memory_prefix
+                    .as_deref()
+                    .is_some_and(|prefix| label.starts_with(prefix))
+                    && (r.config.operations.is_empty() || r.config.operations.contains(&op))
+                    && r.applies_to(subject, now)
+            })
+            .map(|r| r.decision(|| format!("{:?} on memory '{}' is restricted", op, label)))
+    }
+}
+
+static POLICY: OnceLock<RwLock<Arc<PolicyEngine>>> = OnceLock::new();
+
+fn slot() -> &'static RwLock<Arc<PolicyEngine>> {
+    POLICY.get_or_init(|| RwLock::new(Arc::new(PolicyEngine::default())))
+}
+
+/// The active policy (empty until one is installed)
+pub fn policy() -> Arc<PolicyEngine> {
+    slot().read().expect("policy lock poisoned").clone()
+}
+
+/// Replace the active policy
+pub fn set_policy(engine: PolicyEngine) {
+    *slot().write().expect("policy lock poisoned") = Arc::new(engine);
+}
+
+/// Load and install the policy file configured in `config`, if there is one
+///
+/// Returns the number of rules installed.
+pub async fn install_from_config(config: &crate::config::PatternConfig) -> crate::Result<usize> {
+    match &config.policy {
+        Some(path) => install_file(path).await,
+        None => Ok(0),
+    }
+}
+
+/// Load and install a policy file, returning the number of rules installed
+///
+/// Every entry point that runs agents calls this (or
+/// [`install_from_config`]) before any agent can call a tool.
+pub async fn install_file(path: &Path) -> crate::Result<usize> {
+    let engine =
+        PolicyEngine::load(path)
+            .await
+            .map_err(|e| crate::CoreError::ConfigurationError {
+                config_path: path.display().to_string(),
+                field: "rules".to_string(),
+                expected: "valid permission policy".to_string(),
+                cause: crate::error::ConfigError::TomlParse(e.to_string()),
+            })?;
+    let count = engine.rules.len();
+    tracing::info!("Loaded {} policy rule(s) from {}", count, path.display());
+    set_policy(engine);
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const POLICY: &str = r#"
+        [[rules]]
+        effect = "allow"
+        agents = ["Archive"]
+        memory_prefix = "notes/"
+        operations = ["append", "overwrite"]
+
+        [[rules]]
+        effect = "require_consent"
+        tools = ["web"]
+        outside_hours = "09:00-17:00"
+        reason = "after hours"
+
+        [[rules]]
+        name = "crisis-halt"
+        effect = "deny"
+        tools = ["emergency_halt"]
+        groups = ["Crisis"]
+    "#;
+
+    fn subject(name: &str) -> PolicySubject {
+        PolicySubject::new(AgentId::generate(), name)
+    }
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 4, hour, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn test_memory_rules() {
+        let engine = PolicyEngine::from_toml(POLICY).unwrap();
+        let archive = subject("archive");
+
+        assert_eq!(
+            engine.check_memory(&archive, "notes/today", MemoryOp::Overwrite, at(12)),
+            Some(PolicyDecision::Allow)
+        );
+        // Deletes and other agents fall through to the block's permission
+        assert_eq!(
+            engine.check_memory(&archive, "notes/today", MemoryOp::Delete, at(12)),
+            None
+        );
+        assert_eq!(
+            engine.check_memory(&subject("Entropy"), "notes/today", MemoryOp::Append, at(12)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_tool_rules_with_hours_and_groups() {
+        let engine = PolicyEngine::from_toml(POLICY).unwrap();
+        let flux = subject("Flux");
+
+        assert_eq!(engine.check_tool(&flux, "web", at(12)), None);
+        assert_eq!(
+            engine.check_tool(&flux, "web", at(22)),
+            Some(PolicyDecision::RequireConsent {
+                reason: "after hours".to_string()
+            })
+        );
+
+        assert_eq!(engine.check_tool(&flux, "emergency_halt", at(12)), None);
+        let in_crisis = flux.with_groups(vec!["crisis".to_string()]);
+        assert!(matches!(
+            engine.check_tool(&in_crisis, "emergency_halt", at(12)),
+            Some(PolicyDecision::Deny { reason }) if reason.contains("crisis-halt")
+        ));
+        assert!(engine.uses_groups());
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(matches!(
+            PolicyEngine::from_toml("[[rules]]\neffect = \"deny\"\n"),
+            Err(PolicyError::NoTarget { .. })
+        ));
+        assert!(matches!(
+            PolicyEngine::from_toml(
+                "[[rules]]\neffect = \"deny\"\ntools = [\"web\"]\nhours = \"late\"\n"
+            ),
+            Err(PolicyError::InvalidHours { .. })
+        ));
+
+        let overnight: DailyHours = "22:00-06:00".parse().unwrap();
+        assert!(overnight.contains(NaiveTime::from_hms_opt(23, 0, 0).unwrap()));
+        assert!(!overnight.contains(NaiveTime::from_hms_opt(12, 0, 0).unwrap()));
+    }
+}
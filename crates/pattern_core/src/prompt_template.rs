@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
 
 use minijinja::Environment;
 use serde::{Deserialize, Serialize};
@@ -44,6 +46,7 @@ impl PromptTemplate {
     pub fn render(&self, context: &HashMap<String, serde_json::Value>) -> Result<String> {
         // Create a fresh environment for each render
         let mut env = Environment::new();
+        env.set_keep_trailing_newline(true);
         env.add_template(&self.name, &self.template).map_err(|e| {
             crate::CoreError::tool_exec_error(
                 "prompt_template",
@@ -90,8 +93,20 @@ pub struct PromptableEvent {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
+/// File extensions picked up by [`TemplateRegistry::load_dir`]
+const TEMPLATE_EXTENSIONS: &[&str] = &["j2", "jinja", "jinja2"];
+
+/// Built-in templates, shared by every source that has no overrides
+static DEFAULT_TEMPLATES: LazyLock<Arc<TemplateRegistry>> = LazyLock::new(|| {
+    Arc::new(
+        TemplateRegistry::new()
+            .with_defaults()
+            .expect("default templates should compile"),
+    )
+});
+
 /// Registry for reusable templates
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct TemplateRegistry {
     templates: HashMap<String, PromptTemplate>,
 }
@@ -109,6 +124,51 @@ impl TemplateRegistry {
         self.templates.get(name)
     }
 
+    /// The built-in templates, shared rather than rebuilt for every source
+    pub fn shared_defaults() -> Arc<Self> {
+        DEFAULT_TEMPLATES.clone()
+    }
+
+    /// Register each `name = template` pair, replacing any template of the same name
+    pub fn with_overrides<'a>(
+        mut self,
+        overrides: impl IntoIterator<Item = (&'a String, &'a String)>,
+    ) -> Result<Self> {
+        for (name, template) in overrides {
+            self.register(PromptTemplate::new(name.as_str(), template.as_str())?);
+        }
+        Ok(self)
+    }
+
+    /// Register every `<name>.j2` (or `.jinja`, `.jinja2`) file in a directory
+    /// under its file stem, returning how many were loaded
+    pub fn load_dir(&mut self, dir: &Path) -> Result<usize> {
+        let io_error = |e| crate::CoreError::IoError {
+            operation: format!("reading templates from {}", dir.display()),
+            cause: e,
+        };
+
+        let mut loaded = 0;
+        for entry in std::fs::read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            let is_template = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| TEMPLATE_EXTENSIONS.contains(&ext));
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            if !is_template || !path.is_file() {
+                continue;
+            }
+
+            let template = std::fs::read_to_string(&path).map_err(io_error)?;
+            self.register(PromptTemplate::new(name, template)?);
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
     pub fn render(
         &self,
         name: &str,
@@ -123,15 +183,51 @@ impl TemplateRegistry {
             .render(context)
     }
 
+    /// Render a notification template, falling back to the built-in template of
+    /// the same name if an override is missing or fails to render
+    pub fn render_notification(
+        &self,
+        name: &str,
+        context: &HashMap<String, serde_json::Value>,
+    ) -> Option<String> {
+        match self.render(name, context) {
+            Ok(rendered) => Some(rendered),
+            Err(e) => {
+                tracing::warn!("Failed to render notification template '{}': {}", name, e);
+                if std::ptr::eq(self, DEFAULT_TEMPLATES.as_ref()) {
+                    return None;
+                }
+                DEFAULT_TEMPLATES.render(name, context).ok()
+            }
+        }
+    }
+
     /// Register common default templates
     pub fn with_defaults(mut self) -> Result<Self> {
-        // File change template
+        // File change templates
+        for (name, label) in [
+            ("file_created", "File created"),
+            ("file_modified", "File modified"),
+            ("file_changed", "File"),
+        ] {
+            self.register(
+                PromptTemplate::new(name, FILE_CHANGED.replace("{label}", label))?
+                    .with_description("Notify when a watched file changes"),
+            );
+        }
         self.register(
             PromptTemplate::new(
-                "file_changed",
-                "File {{ path }} was modified at {{ timestamp }}:\n{{ preview }}",
+                "file_appended",
+                "📋 New lines in {{ path }}:\n\n{{ lines | join('\\n') }}",
             )?
-            .with_description("Notify when a file changes"),
+            .with_description("Notify when lines are appended to a tailed file"),
+        );
+        self.register(
+            PromptTemplate::new(
+                "file_deleted",
+                "🗑️ File deleted: {{ path }}{% if indexed %}\n\nIts chunks were removed from archival memory.{% endif %}",
+            )?
+            .with_description("Notify when a watched file is deleted"),
         );
 
         // Stream item template
@@ -144,27 +240,56 @@ impl TemplateRegistry {
         self.register(
             PromptTemplate::new(
                 "bluesky_post",
-                "New post from @{{ handle }} on Bluesky:\n{{ text }}\n\n[{{ uri }}]",
+                ["📝 New post:\n\n{{ post_text }}", BLUESKY_POST_OPTIONS].concat(),
+            )?
+            .with_description("Bluesky post outside of any thread"),
+        );
+
+        // Bluesky mention template
+        self.register(
+            PromptTemplate::new(
+                "bluesky_mention",
+                [
+                    "You were mentioned by @{{ handle }}:\n\n{{ post_text }}",
+                    BLUESKY_POST_OPTIONS,
+                ]
+                .concat(),
             )?
-            .with_description("Bluesky post notification"),
+            .with_description("Bluesky mention notification"),
         );
 
         // Bluesky reply template
         self.register(
             PromptTemplate::new(
                 "bluesky_reply",
-                "@{{ handle }} replied to {{ reply_to }}:\n{{ text }}\n\n[{{ uri }}]",
+                [BLUESKY_REPLY, BLUESKY_THREAD_OPTIONS].concat(),
             )?
-            .with_description("Bluesky reply notification"),
+            .with_description("Bluesky reply shown with its thread"),
         );
 
-        // Bluesky mention template
+        // Bluesky thread activity template
         self.register(
             PromptTemplate::new(
-                "bluesky_mention",
-                "You were mentioned by @{{ handle }}:\n{{ text }}\n\n[{{ uri }}]",
+                "bluesky_thread",
+                [BLUESKY_THREAD, BLUESKY_THREAD_OPTIONS, "{% endfor %}"].concat(),
             )?
-            .with_description("Bluesky mention notification"),
+            .with_description("Batch of Bluesky posts in one thread"),
+        );
+
+        // HomeAssistant templates
+        self.register(
+            PromptTemplate::new(
+                "homeassistant_state",
+                "HomeAssistant: {{ name }} is now '{{ state }}'",
+            )?
+            .with_description("HomeAssistant entity state change"),
+        );
+        self.register(
+            PromptTemplate::new(
+                "homeassistant_event",
+                "HomeAssistant Event: {{ event_type }} - {{ name }} changed to '{{ state }}'",
+            )?
+            .with_description("HomeAssistant event"),
         );
 
         // Scheduled task template
@@ -189,6 +314,58 @@ impl TemplateRegistry {
     }
 }
 
+/// Body shared by the created/modified file templates; `{label}` is replaced
+/// with the kind of change
+const FILE_CHANGED: &str = "\
+{% if content_kind == 'chunk' %}📄 {label}: {{ path }} (lines {{ start_line }}-{{ end_line }})\n\n{{ text }}\
+{% elif content_kind == 'lines' %}📋 {label}: {{ path }} ({{ size }})\
+{% if line_count > 5 %}\n\nFirst 5 lines:\n{{ lines[:5] | join('\\n') }}\n... ({{ line_count - 5 }} more lines)\
+{% else %}\n\nLines:\n{{ lines | join('\\n') }}{% endif %}\
+{% else %}📄 {label}: {{ path }} ({{ size }})\
+{% if line_count > 5 %}\n\nPreview:\n{{ lines[:5] | join('\\n') }}\n... ({{ line_count - 5 }} more lines)\
+{% else %}\n\nContent:\n{{ text }}{% endif %}{% endif %}\
+{% if chunk_count %}\n\nIndexed into archival memory as {{ chunk_count }} chunks; use recall or search to look it up.{% endif %}";
+
+/// Reply option for a post shown outside of any thread
+const BLUESKY_POST_OPTIONS: &str = "
+💭 Reply option: @{{ handle }} ({{ uri }})
+If you choose to reply (by using send_message with target_type bluesky and the target_id set to the uri), \
+your response must contain under 300 graphemes or it won't go through. You can thread a longer reply by \
+requesting a heartbeat when making a reply, posting the first part of the the reply, and then replying to \
+the uri you get back from the first successful reply with the second part
+";
+
+const BLUESKY_REPLY: &str = "💬 New reply in thread:\n\n{% set context = contexts[0] %}";
+
+const BLUESKY_THREAD: &str = "\
+Thread activity ({{ post_count }} posts in last 30 seconds)\n\n\
+{% for context in contexts %}{% if contexts | length > 1 %}{% if not loop.first %}\n───\n{% endif %}\
+Context {{ loop.index }}:\n{% endif %}";
+
+/// Thread view, reply options and image markers for one `context`
+const BLUESKY_THREAD_OPTIONS: &str = "\
+{{ context.thread }}
+💭 Reply options (choose at most one):
+{% for option in context.reply_options %}  • @{{ option.handle }} ({{ option.uri }})
+{% endfor %}\
+If you choose to reply (by using send_message with target_type bluesky and the target_id set to the uri of \
+the post you want to reply to, from the above options), your response must contain under 300 characters or \
+it will be truncated.
+Alternatively, you can 'like' the post by submitting a reply with 'like' as the sole text
+{% for url in context.images %}\n[IMAGE: {{ url }}]{% endfor %}";
+
+/// Template context for a serializable item: its top-level fields, plus the
+/// whole item as `item`
+pub fn item_context<T: Serialize>(item: &T) -> HashMap<String, serde_json::Value> {
+    let value = serde_json::to_value(item).unwrap_or_default();
+    let mut context = HashMap::new();
+    if let serde_json::Value::Object(fields) = &value {
+        context.extend(fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    context.insert("item".to_string(), value);
+    context
+}
+
 /// Extract variable names from a template string
 fn extract_template_vars(template: &str) -> Vec<String> {
     let mut vars = Vec::new();
@@ -257,17 +434,94 @@ mod tests {
 
         let mut context = HashMap::new();
         context.insert("path".to_string(), serde_json::json!("/tmp/test.txt"));
-        context.insert(
-            "timestamp".to_string(),
-            serde_json::json!("2024-01-01 12:00"),
+        context.insert("size".to_string(), serde_json::json!("12 bytes"));
+        context.insert("content_kind".to_string(), serde_json::json!("text"));
+        context.insert("text".to_string(), serde_json::json!("First line"));
+        context.insert("lines".to_string(), serde_json::json!(["First line"]));
+        context.insert("line_count".to_string(), serde_json::json!(1));
+
+        let result = registry.render("file_modified", &context).unwrap();
+        assert_eq!(
+            result,
+            "📄 File modified: /tmp/test.txt (12 bytes)\n\nContent:\nFirst line"
         );
-        context.insert(
-            "preview".to_string(),
-            serde_json::json!("First few lines..."),
+    }
+
+    #[test]
+    fn test_item_context_and_overrides() {
+        let item = serde_json::json!({
+            "entity_id": "sensor.desk",
+            "attributes": { "unit": "min" }
+        });
+        let mut context = item_context(&item);
+        context.insert("name".to_string(), serde_json::json!("Desk"));
+        context.insert("state".to_string(), serde_json::json!("45"));
+
+        let overrides = HashMap::from([(
+            "homeassistant_state".to_string(),
+            "{{ name }}: {{ state }} {{ attributes.unit }} ({{ item.entity_id }})".to_string(),
+        )]);
+        let registry = TemplateRegistry::new()
+            .with_defaults()
+            .unwrap()
+            .with_overrides(&overrides)
+            .unwrap();
+        assert_eq!(
+            registry
+                .render_notification("homeassistant_state", &context)
+                .unwrap(),
+            "Desk: 45 min (sensor.desk)"
         );
 
-        let result = registry.render("file_changed", &context).unwrap();
-        assert!(result.contains("/tmp/test.txt"));
-        assert!(result.contains("2024-01-01 12:00"));
+        // A broken override falls back to the built-in template
+        let mut registry = registry;
+        registry.register(
+            PromptTemplate::new("homeassistant_state", "{{ item.missing.field }}").unwrap(),
+        );
+        assert_eq!(
+            registry
+                .render_notification("homeassistant_state", &context)
+                .unwrap(),
+            "HomeAssistant: Desk is now '45'"
+        );
+    }
+
+    #[test]
+    fn test_bluesky_mention_default() {
+        let registry = TemplateRegistry::new().with_defaults().unwrap();
+        let context = item_context(&serde_json::json!({
+            "handle": "alice.bsky.social",
+            "uri": "at://did:plc:alice/app.bsky.feed.post/1",
+            "post_text": "@alice.bsky.social: hey @pattern - 1m ago\n",
+        }));
+
+        let message = registry
+            .render_notification("bluesky_mention", &context)
+            .unwrap();
+        assert!(message.starts_with("You were mentioned by @alice.bsky.social:\n\n"));
+        assert!(message.contains(
+            "💭 Reply option: @alice.bsky.social (at://did:plc:alice/app.bsky.feed.post/1)"
+        ));
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("bluesky_post.j2"),
+            "@{{ handle }}: {{ text }}",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.md"), "not a template").unwrap();
+
+        let mut registry = TemplateRegistry::new();
+        assert_eq!(registry.load_dir(dir.path()).unwrap(), 1);
+        assert!(registry.get("notes").is_none());
+
+        let context = item_context(&serde_json::json!({ "handle": "alice", "text": "hi" }));
+        assert_eq!(
+            registry.render("bluesky_post", &context).unwrap(),
+            "@alice: hi"
+        );
     }
 }
@@ -0,0 +1,284 @@
+//! Skills: loadable instruction packs for agents
+//!
+//! A skill is a markdown document describing a specialised procedure, with an
+//! optional front-matter header:
+//!
+//! ```markdown
+//! ---
+//! name: researching-on-the-internet
+//! description: Use when asked for in-depth information that needs external sources
+//! tools: [web, search]
+//! ---
+//!
+//! # Researching on the Internet
+//! ...
+//! ```
+//!
+//! Agents list the skills in their configured directories and load one into
+//! working memory only while they need it, keeping the system prompt small.
+//! Documents without front matter are named after their file and described by
+//! their first heading.
+
+use std::path::{Path, PathBuf};
+
+use crate::{CoreError, Result};
+
+/// A skill document read from disk
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skill {
+    /// Unique name used to load the skill
+    pub name: String,
+    /// When the skill should be used
+    pub description: String,
+    /// Tools the skill's procedure relies on
+    pub required_tools: Vec<String>,
+    /// The instructions themselves, without front matter
+    pub body: String,
+    /// File the skill was read from
+    pub path: PathBuf,
+}
+
+impl Skill {
+    /// Parse a skill document, falling back to `path` for the name
+    pub fn parse(path: &Path, content: &str) -> Self {
+        let (front_matter, body) = split_front_matter(content);
+
+        let mut name = None;
+        let mut description = None;
+        let mut required_tools = Vec::new();
+        let mut list_key: Option<String> = None;
+        for line in front_matter.lines() {
+            // Continuation of a YAML block list (`tools:` followed by `- web`)
+            if let Some(item) = line.trim().strip_prefix("- ") {
+                if list_key
+                    .as_deref()
+                    .is_some_and(|k| k == "tools" || k == "required_tools")
+                {
+                    required_tools.push(unquote(item).to_string());
+                }
+                continue;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim();
+            let value = value.trim();
+            list_key = Some(key.to_string());
+            match key {
+                "name" => name = Some(unquote(value).to_string()),
+                "description" => description = Some(unquote(value).to_string()),
+                "tools" | "required_tools" => required_tools.extend(
+                    value
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .split(',')
+                        .map(|t| unquote(t.trim()).to_string())
+                        .filter(|t| !t.is_empty()),
+                ),
+                _ => {}
+            }
+        }
+
+        let body = body.trim().to_string();
+        let name = name.filter(|n| !n.is_empty()).unwrap_or_else(|| {
+            path.file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        let description = description
+            .filter(|d| !d.is_empty())
+            .or_else(|| {
+                body.lines()
+                    .find_map(|l| l.strip_prefix('#'))
+                    .map(|h| h.trim_start_matches('#').trim().to_string())
+            })
+            .unwrap_or_default();
+
+        Self {
+            name,
+            description,
+            required_tools,
+            body,
+            path: path.to_path_buf(),
+        }
+    }
+
+    /// Label of the working memory block holding this skill while loaded
+    pub fn block_label(&self) -> String {
+        block_label(&self.name)
+    }
+}
+
+/// Label of the working memory block for skill `name`
+pub fn block_label(name: &str) -> String {
+    format!("skill_{}", name)
+}
+
+fn split_front_matter(content: &str) -> (&str, &str) {
+    let content = content.trim_start_matches('\u{feff}');
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return ("", content);
+    };
+    match rest.find("\n---") {
+        Some(end) => {
+            let after = &rest[end + 4..];
+            (&rest[..end], after.split_once('\n').map_or("", |(_, b)| b))
+        }
+        None => ("", content),
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+/// The skills available to an agent, read from one or more directories
+///
+/// Directories are re-read on every lookup so edits to skill files show up
+/// without restarting the agent. When two directories hold a skill with the
+/// same name, the one listed first wins.
+#[derive(Debug, Clone, Default)]
+pub struct SkillLibrary {
+    dirs: Vec<PathBuf>,
+}
+
+impl SkillLibrary {
+    /// Create a library over the given directories
+    pub fn new(dirs: Vec<PathBuf>) -> Self {
+        Self { dirs }
+    }
+
+    /// Directories searched for skills
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    /// All skills, sorted by name
+    pub async fn list(&self) -> Result<Vec<Skill>> {
+        let mut skills: Vec<Skill> = Vec::new();
+        for dir in &self.dirs {
+            let mut entries = match tokio::fs::read_dir(dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    tracing::warn!("Skill directory {} does not exist", dir.display());
+                    continue;
+                }
+                Err(e) => {
+                    return Err(CoreError::IoError {
+                        operation: format!("reading skill directory {}", dir.display()),
+                        cause: e,
+                    });
+                }
+            };
+
+            let mut paths = Vec::new();
+            while let Some(entry) = entries.next_entry().await.map_err(|e| CoreError::IoError {
+                operation: format!("reading skill directory {}", dir.display()),
+                cause: e,
+            })? {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "md") {
+                    paths.push(path);
+                }
+            }
+            paths.sort();
+
+            for path in paths {
+                let content =
+                    tokio::fs::read_to_string(&path)
+                        .await
+                        .map_err(|e| CoreError::IoError {
+                            operation: format!("reading skill {}", path.display()),
+                            cause: e,
+                        })?;
+                let skill = Skill::parse(&path, &content);
+                if !skills.iter().any(|s| s.name == skill.name) {
+                    skills.push(skill);
+                }
+            }
+        }
+
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(skills)
+    }
+
+    /// Look up a skill by name
+    pub async fn get(&self, name: &str) -> Result<Option<Skill>> {
+        Ok(self.list().await?.into_iter().find(|s| s.name == name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_front_matter() {
+        let skill = Skill::parse(
+            Path::new("skills/research.md"),
+            "---\nname: researching-on-the-internet\ndescription: \"Use for research\"\ntools: [web, search]\n---\n\n# Researching\n\nSteps.",
+        );
+        assert_eq!(skill.name, "researching-on-the-internet");
+        assert_eq!(skill.description, "Use for research");
+        assert_eq!(skill.required_tools, vec!["web", "search"]);
+        assert_eq!(skill.body, "# Researching\n\nSteps.");
+        assert_eq!(skill.block_label(), "skill_researching-on-the-internet");
+    }
+
+    #[test]
+    fn test_parse_block_list_tools() {
+        let skill = Skill::parse(
+            Path::new("a.md"),
+            "---\nname: a\ntools:\n  - web\n  - calculator\ndescription: d\n---\nbody",
+        );
+        assert_eq!(skill.required_tools, vec!["web", "calculator"]);
+        assert_eq!(skill.description, "d");
+    }
+
+    #[test]
+    fn test_parse_without_front_matter() {
+        let skill = Skill::parse(
+            Path::new("skills/deep-background.md"),
+            "Take these as instructions.\n\n# Deep Background: Fact-Checking\n\nMore.",
+        );
+        assert_eq!(skill.name, "deep-background");
+        assert_eq!(skill.description, "Deep Background: Fact-Checking");
+        assert!(skill.required_tools.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_library_first_directory_wins() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        std::fs::write(
+            first.path().join("a.md"),
+            "---\nname: shared\ndescription: first\n---\nA",
+        )
+        .unwrap();
+        std::fs::write(
+            second.path().join("b.md"),
+            "---\nname: shared\ndescription: second\n---\nB",
+        )
+        .unwrap();
+        std::fs::write(second.path().join("other.md"), "# Other\nC").unwrap();
+        std::fs::write(second.path().join("notes.txt"), "ignored").unwrap();
+
+        let library = SkillLibrary::new(vec![
+            first.path().to_path_buf(),
+            second.path().to_path_buf(),
+            first.path().join("missing"),
+        ]);
+        let skills = library.list().await.unwrap();
+        assert_eq!(skills.len(), 2);
+        assert_eq!(skills[0].name, "other");
+        assert_eq!(skills[1].description, "first");
+        assert!(library.get("nope").await.unwrap().is_none());
+    }
+}
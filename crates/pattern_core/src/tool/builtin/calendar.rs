@@ -0,0 +1,613 @@
+//! Calendar tool for agents to keep track of what's on the schedule
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CoreError, Result,
+    context::{AgentHandle, scheduler::DEFAULT_EVENT_REMINDER_MINUTES},
+    db::{BaseEvent, ops::EventFilter},
+    id::{EventId, IdType},
+    tool::{AiTool, ExecutionMeta},
+};
+
+/// How far ahead a query looks when no range is given
+const DEFAULT_QUERY_DAYS: i64 = 7;
+
+/// Operation types for the calendar tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(inline)]
+pub enum CalendarOperationType {
+    Create,
+    Move,
+    Update,
+    Delete,
+    Query,
+}
+
+/// Input for managing calendar events
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CalendarInput {
+    /// The operation to perform
+    pub operation: CalendarOperationType,
+
+    /// For move, update and delete: the event id
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// For create and update: what the event is
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// For create and update: notes about the event
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// For create and update: kind of event, e.g. "appointment", "meeting", "deadline"
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_type: Option<String>,
+
+    /// For create and update: where the event happens
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+
+    /// For create and move: start time as an RFC 3339 timestamp (e.g. "2025-03-01T14:00:00-05:00")
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+
+    /// For create, move and update: how long the event lasts
+    #[schemars(default, with = "i64")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_minutes: Option<i64>,
+
+    /// For create and move: minutes before the start to wake you (default 15, negative for none)
+    #[schemars(default, with = "i64")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remind_minutes: Option<i64>,
+
+    /// For query: start of the range as an RFC 3339 timestamp (default now)
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+
+    /// For query: end of the range as an RFC 3339 timestamp (default 'days' after 'from')
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+
+    /// For query: number of days to look ahead when 'to' isn't given (default 7)
+    #[schemars(default, with = "i64")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days: Option<i64>,
+}
+
+/// Output from calendar operations
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CalendarOutput {
+    /// Whether the operation was successful
+    pub success: bool,
+
+    /// Message about the operation
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Events created, changed or found
+    #[schemars(default)]
+    pub events: Vec<EventSummary>,
+}
+
+/// Summary of an event as shown to the agent
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct EventSummary {
+    /// Event identifier (use with move, update and delete)
+    pub id: String,
+    pub title: String,
+    pub event_type: String,
+    pub start: DateTime<Utc>,
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Utc>>,
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl From<&BaseEvent> for EventSummary {
+    fn from(event: &BaseEvent) -> Self {
+        Self {
+            id: event.id.to_key(),
+            title: event.title.clone(),
+            event_type: event.event_type.clone(),
+            start: event.scheduled_for,
+            end: event.ends_at(),
+            location: event.location.clone(),
+            description: event.description.clone(),
+        }
+    }
+}
+
+/// Render events as a list in local time, one line each
+pub fn render_event_list(events: &[BaseEvent]) -> String {
+    events
+        .iter()
+        .map(|event| {
+            let start = event.scheduled_for.with_timezone(&chrono::Local);
+            let mut line = format!("- {} {}", start.format("%a %Y-%m-%d %H:%M"), event.title);
+            if let Some(end) = event.ends_at() {
+                line.push_str(&format!(
+                    " (until {})",
+                    end.with_timezone(&chrono::Local).format("%H:%M")
+                ));
+            }
+            if let Some(location) = &event.location {
+                line.push_str(&format!(" @ {}", location));
+            }
+            line.push_str(&format!(" [{}] id={}", event.event_type, event.id.to_key()));
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Tool for creating, moving and querying calendar events
+#[derive(Debug, Clone)]
+pub struct CalendarTool {
+    pub(crate) handle: AgentHandle,
+}
+
+impl CalendarTool {
+    /// Create a new calendar tool
+    pub fn new(handle: AgentHandle) -> Self {
+        Self { handle }
+    }
+}
+
+#[async_trait]
+impl AiTool for CalendarTool {
+    type Input = CalendarInput;
+    type Output = CalendarOutput;
+
+    fn name(&self) -> &str {
+        "calendar"
+    }
+
+    fn description(&self) -> &str {
+        "Keep track of scheduled events for your user. Operations: create, move, update, delete, query.
+ - 'create' adds an event with 'title' and 'start' (RFC 3339), plus optional 'duration_minutes', 'event_type', 'location' and 'description'
+ - 'move' changes the 'start' (and optionally 'duration_minutes') of event 'id'
+ - 'update' changes the 'title', 'description', 'event_type', 'location' or 'duration_minutes' of event 'id'
+ - 'delete' removes event 'id'
+ - 'query' lists events between 'from' and 'to', or for the next 'days' (default 7)
+You are woken 'remind_minutes' (default 15) before events you create or move."
+    }
+
+    async fn execute(&self, params: Self::Input, _meta: &ExecutionMeta) -> Result<Self::Output> {
+        if !self.handle.has_db_connection() {
+            return Ok(CalendarOutput {
+                success: false,
+                message: Some("The calendar requires a database connection".to_string()),
+                events: vec![],
+            });
+        }
+
+        match params.operation {
+            CalendarOperationType::Create => self.execute_create(params).await,
+            CalendarOperationType::Move => self.execute_move(params).await,
+            CalendarOperationType::Update => self.execute_update(params).await,
+            CalendarOperationType::Delete => self.execute_delete(params).await,
+            CalendarOperationType::Query => self.execute_query(params).await,
+        }
+    }
+
+    fn usage_rule(&self) -> Option<&'static str> {
+        Some("the conversation will be continued when called")
+    }
+
+    fn examples(&self) -> Vec<crate::tool::ToolExample<Self::Input, Self::Output>> {
+        vec![
+            crate::tool::ToolExample {
+                description: "Add a doctor's appointment with a half-hour heads up".to_string(),
+                parameters: CalendarInput {
+                    operation: CalendarOperationType::Create,
+                    id: None,
+                    title: Some("Doctor's appointment".to_string()),
+                    description: None,
+                    event_type: Some("appointment".to_string()),
+                    location: Some("Elm St clinic".to_string()),
+                    start: Some("2025-03-04T10:30:00-05:00".to_string()),
+                    duration_minutes: Some(45),
+                    remind_minutes: Some(30),
+                    from: None,
+                    to: None,
+                    days: None,
+                },
+                expected_output: Some(CalendarOutput {
+                    success: true,
+                    message: Some("Created event 'Doctor's appointment'".to_string()),
+                    events: vec![],
+                }),
+            },
+            crate::tool::ToolExample {
+                description: "See what's coming up over the next two days".to_string(),
+                parameters: CalendarInput {
+                    operation: CalendarOperationType::Query,
+                    id: None,
+                    title: None,
+                    description: None,
+                    event_type: None,
+                    location: None,
+                    start: None,
+                    duration_minutes: None,
+                    remind_minutes: None,
+                    from: None,
+                    to: None,
+                    days: Some(2),
+                },
+                expected_output: Some(CalendarOutput {
+                    success: true,
+                    message: Some("2 event(s)".to_string()),
+                    events: vec![],
+                }),
+            },
+        ]
+    }
+}
+
+impl CalendarTool {
+    async fn execute_create(&self, params: CalendarInput) -> Result<CalendarOutput> {
+        let title = match params.title.as_deref().map(str::trim) {
+            Some(title) if !title.is_empty() => title.to_string(),
+            _ => return Err(missing("create", "title")),
+        };
+        let start = params
+            .start
+            .as_deref()
+            .ok_or_else(|| missing("create", "start"))?;
+        let scheduled_for = parse_time("start", start)?;
+
+        let event = BaseEvent {
+            title,
+            description: non_empty(params.description),
+            event_type: non_empty(params.event_type).unwrap_or_else(|| "general".to_string()),
+            scheduled_for,
+            duration_minutes: parse_duration(params.duration_minutes)?,
+            location: non_empty(params.location),
+            creator_id: self.handle.memory.owner_id.clone(),
+            ..Default::default()
+        };
+        let created = self.handle.create_event(event).await?;
+
+        let mut message = format!("Created event '{}'", created.title);
+        message.push_str(&self.remind(&created, params.remind_minutes).await?);
+
+        Ok(CalendarOutput {
+            success: true,
+            message: Some(message),
+            events: vec![EventSummary::from(&created)],
+        })
+    }
+
+    async fn execute_move(&self, params: CalendarInput) -> Result<CalendarOutput> {
+        let id = params.id.as_deref().ok_or_else(|| missing("move", "id"))?;
+        let start = params
+            .start
+            .as_deref()
+            .ok_or_else(|| missing("move", "start"))?;
+        let Some(mut event) = self.load_event(id).await? else {
+            return Ok(not_found(id));
+        };
+
+        let previous = event.scheduled_for;
+        event.scheduled_for = parse_time("start", start)?;
+        if params.duration_minutes.is_some() {
+            event.duration_minutes = parse_duration(params.duration_minutes)?;
+        }
+        let moved = self.handle.update_event(&event).await?;
+
+        self.handle.reschedule_event_reminders(&moved).await?;
+        let mut message = format!(
+            "Moved '{}' from {} to {}",
+            moved.title,
+            previous.to_rfc3339(),
+            moved.scheduled_for.to_rfc3339()
+        );
+        if params.remind_minutes.is_some() {
+            message.push_str(&self.remind(&moved, params.remind_minutes).await?);
+        }
+
+        Ok(CalendarOutput {
+            success: true,
+            message: Some(message),
+            events: vec![EventSummary::from(&moved)],
+        })
+    }
+
+    async fn execute_update(&self, params: CalendarInput) -> Result<CalendarOutput> {
+        let id = params
+            .id
+            .as_deref()
+            .ok_or_else(|| missing("update", "id"))?;
+        let Some(mut event) = self.load_event(id).await? else {
+            return Ok(not_found(id));
+        };
+
+        if let Some(title) = params.title.as_deref().map(str::trim) {
+            if !title.is_empty() {
+                event.title = title.to_string();
+            }
+        }
+        if let Some(description) = params.description {
+            event.description = Some(description).filter(|d| !d.trim().is_empty());
+        }
+        if let Some(event_type) = non_empty(params.event_type) {
+            event.event_type = event_type;
+        }
+        if let Some(location) = params.location {
+            event.location = Some(location).filter(|l| !l.trim().is_empty());
+        }
+        if params.duration_minutes.is_some() {
+            event.duration_minutes = parse_duration(params.duration_minutes)?;
+        }
+        let updated = self.handle.update_event(&event).await?;
+
+        // Reminders quote the title, so keep them in step
+        self.handle.reschedule_event_reminders(&updated).await?;
+
+        Ok(CalendarOutput {
+            success: true,
+            message: Some(format!("Updated event '{}'", updated.title)),
+            events: vec![EventSummary::from(&updated)],
+        })
+    }
+
+    async fn execute_delete(&self, params: CalendarInput) -> Result<CalendarOutput> {
+        let id = params
+            .id
+            .as_deref()
+            .ok_or_else(|| missing("delete", "id"))?;
+        let Some(event) = self.load_event(id).await? else {
+            return Ok(not_found(id));
+        };
+
+        self.handle.delete_event(&event.id).await?;
+
+        Ok(CalendarOutput {
+            success: true,
+            message: Some(format!("Deleted event '{}'", event.title)),
+            events: vec![],
+        })
+    }
+
+    async fn execute_query(&self, params: CalendarInput) -> Result<CalendarOutput> {
+        let from = match params.from.as_deref() {
+            Some(from) => parse_time("from", from)?,
+            None => Utc::now(),
+        };
+        let to = match params.to.as_deref() {
+            Some(to) => parse_time("to", to)?,
+            None => from + Duration::days(params.days.unwrap_or(DEFAULT_QUERY_DAYS).max(1)),
+        };
+        if to <= from {
+            return Ok(CalendarOutput {
+                success: false,
+                message: Some("'to' must be after 'from'".to_string()),
+                events: vec![],
+            });
+        }
+
+        let events = self
+            .handle
+            .list_events(&EventFilter {
+                creator_id: Some(self.handle.memory.owner_id.clone()),
+                from: Some(from),
+                to: Some(to),
+                ..Default::default()
+            })
+            .await?;
+
+        Ok(CalendarOutput {
+            success: true,
+            message: Some(if events.is_empty() {
+                format!(
+                    "Nothing scheduled between {} and {}",
+                    from.to_rfc3339(),
+                    to.to_rfc3339()
+                )
+            } else {
+                format!("{} event(s):\n{}", events.len(), render_event_list(&events))
+            }),
+            events: events.iter().map(EventSummary::from).collect(),
+        })
+    }
+
+    /// Set this agent's reminder for an event, describing the result
+    async fn remind(&self, event: &BaseEvent, remind_minutes: Option<i64>) -> Result<String> {
+        let lead = remind_minutes.unwrap_or(DEFAULT_EVENT_REMINDER_MINUTES);
+        if lead < 0 {
+            return Ok(String::new());
+        }
+        Ok(match self.handle.sync_event_reminder(event, lead).await? {
+            Some(reminder) => format!(
+                "; you'll be reminded at {}",
+                reminder.scheduled_for.to_rfc3339()
+            ),
+            None => "; it has already started, so no reminder was set".to_string(),
+        })
+    }
+
+    /// Load an event belonging to this agent's user
+    async fn load_event(&self, id: &str) -> Result<Option<BaseEvent>> {
+        let id = id.trim();
+        let event_id = EventId(id.strip_prefix("event:").unwrap_or(id).to_string());
+        let event = self.handle.get_event(&event_id).await?;
+        Ok(event.filter(|e| e.creator_id == self.handle.memory.owner_id))
+    }
+}
+
+fn parse_time(field: &str, value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| {
+            CoreError::tool_exec_msg(
+                "calendar",
+                serde_json::json!({ field: value }),
+                format!("'{}' must be an RFC 3339 timestamp: {}", field, e),
+            )
+        })
+}
+
+fn parse_duration(minutes: Option<i64>) -> Result<Option<i32>> {
+    match minutes {
+        None | Some(0) => Ok(None),
+        Some(minutes) if (1..=i32::MAX as i64).contains(&minutes) => Ok(Some(minutes as i32)),
+        Some(minutes) => Err(CoreError::tool_exec_msg(
+            "calendar",
+            serde_json::json!({ "duration_minutes": minutes }),
+            "'duration_minutes' must be positive",
+        )),
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn missing(operation: &str, field: &str) -> CoreError {
+    CoreError::tool_exec_msg(
+        "calendar",
+        serde_json::json!({ "operation": operation }),
+        format!("{} operation requires '{}' field", operation, field),
+    )
+}
+
+fn not_found(id: &str) -> CalendarOutput {
+    CalendarOutput {
+        success: false,
+        message: Some(format!("No event with id {}", id)),
+        events: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{UserId, db::client::create_test_db, memory::Memory};
+
+    fn input(operation: CalendarOperationType) -> CalendarInput {
+        CalendarInput {
+            operation,
+            id: None,
+            title: None,
+            description: None,
+            event_type: None,
+            location: None,
+            start: None,
+            duration_minutes: None,
+            remind_minutes: None,
+            from: None,
+            to: None,
+            days: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_move_query_delete() {
+        let db = create_test_db().await.unwrap();
+        let memory = Memory::with_owner(&UserId::generate());
+        let handle = AgentHandle::test_with_memory(memory).with_db(db);
+        let tool = CalendarTool::new(handle.clone());
+        let meta = ExecutionMeta::default();
+
+        let start = Utc::now() + Duration::hours(3);
+        let created = tool
+            .execute(
+                CalendarInput {
+                    title: Some("Therapy".to_string()),
+                    start: Some(start.to_rfc3339()),
+                    duration_minutes: Some(50),
+                    remind_minutes: Some(30),
+                    ..input(CalendarOperationType::Create)
+                },
+                &meta,
+            )
+            .await
+            .unwrap();
+        assert!(created.success);
+        let id = created.events[0].id.clone();
+
+        let wakeups = handle.list_wakeups().await.unwrap();
+        assert_eq!(wakeups.len(), 1);
+        assert_eq!(
+            wakeups[0].scheduled_for.timestamp(),
+            (start - Duration::minutes(30)).timestamp()
+        );
+
+        // Moving the event drags its reminder along
+        let new_start = start + Duration::days(1);
+        let moved = tool
+            .execute(
+                CalendarInput {
+                    id: Some(id.clone()),
+                    start: Some(new_start.to_rfc3339()),
+                    ..input(CalendarOperationType::Move)
+                },
+                &meta,
+            )
+            .await
+            .unwrap();
+        assert!(moved.success);
+        let wakeups = handle.list_wakeups().await.unwrap();
+        assert_eq!(wakeups.len(), 1);
+        assert_eq!(
+            wakeups[0].scheduled_for.timestamp(),
+            (new_start - Duration::minutes(30)).timestamp()
+        );
+
+        let today = tool
+            .execute(
+                CalendarInput {
+                    days: Some(1),
+                    ..input(CalendarOperationType::Query)
+                },
+                &meta,
+            )
+            .await
+            .unwrap();
+        assert!(today.events.is_empty());
+
+        let week = tool
+            .execute(input(CalendarOperationType::Query), &meta)
+            .await
+            .unwrap();
+        assert_eq!(week.events.len(), 1);
+        assert_eq!(week.events[0].title, "Therapy");
+
+        let deleted = tool
+            .execute(
+                CalendarInput {
+                    id: Some(id),
+                    ..input(CalendarOperationType::Delete)
+                },
+                &meta,
+            )
+            .await
+            .unwrap();
+        assert!(deleted.success);
+        assert!(handle.list_wakeups().await.unwrap().is_empty());
+    }
+}
@@ -9,7 +9,7 @@ use serde_json::json;
 use super::search_utils::extract_snippet;
 use crate::{
     Result,
-    context::AgentHandle,
+    context::{AgentHandle, SearchWeights},
     message::ChatRole,
     tool::{AiTool, ExecutionMeta},
 };
@@ -68,9 +68,30 @@ pub struct ConstellationSearchInput {
     /// This will enable typo-tolerant search once SurrealDB fuzzy functions are integrated
     #[serde(default)]
     pub fuzzy: bool,
+
+    /// For archival memory: weight of keyword (BM25) matching when ranking (default: 1.0, 0 disables)
+    #[schemars(default, with = "f32")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bm25_weight: Option<f32>,
+
+    /// For archival memory: weight of semantic (embedding) similarity when ranking (default: 1.0, 0 disables)
+    #[schemars(default, with = "f32")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vector_weight: Option<f32>,
     // request_heartbeat handled via ExecutionMeta injection; field removed
 }
 
+impl ConstellationSearchInput {
+    /// Ranking weights for archival memory search
+    pub fn weights(&self) -> SearchWeights {
+        let defaults = SearchWeights::default();
+        SearchWeights::new(
+            self.bm25_weight.unwrap_or(defaults.bm25),
+            self.vector_weight.unwrap_or(defaults.vector),
+        )
+    }
+}
+
 /// Output from search operations
 #[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct SearchOutput {
@@ -107,7 +128,8 @@ impl AiTool for ConstellationSearchTool {
             - group_archival (recall memory for yourself and other entities in your constellation)
             - constellation_history (message history for the entire constellation)
             - all (all of the above)
-        Returns relevant results ranked by BM25 relevance score. Make regular use of this to ground yourself in past events.
+        Returns relevant results ranked by relevance. Make regular use of this to ground yourself in past events.
+        - Archival results fuse keyword and semantic rankings; set bm25_weight or vector_weight (default 1.0, 0 disables) to favour exact terms or meaning
         - To broaden your search, use a larger limit
         - To narrow your search, you can:
             - use explicit start_time and end_time parameters with rfc3339 datetime parsing
@@ -124,16 +146,17 @@ impl AiTool for ConstellationSearchTool {
 
     async fn execute(&self, params: Self::Input, _meta: &ExecutionMeta) -> Result<Self::Output> {
         let limit = params.limit.max(1).min(100) as usize;
+        let weights = params.weights();
 
         match params.domain {
             ConstellationSearchDomain::LocalArchival => {
                 // Search just this agent's archival
-                self.search_local_archival(&params.query, limit, params.fuzzy)
+                self.search_local_archival(&params.query, limit, params.fuzzy, weights)
                     .await
             }
             ConstellationSearchDomain::GroupArchival => {
                 // Search archival across all group members
-                self.search_group_archival(&params.query, limit, params.fuzzy)
+                self.search_group_archival(&params.query, limit, params.fuzzy, weights)
                     .await
             }
             ConstellationSearchDomain::ConstellationHistory => {
@@ -171,7 +194,8 @@ impl AiTool for ConstellationSearchTool {
             }
             ConstellationSearchDomain::All => {
                 // Search everything - both group archival and constellation history
-                self.search_all(&params.query, limit, params.fuzzy).await
+                self.search_all(&params.query, limit, params.fuzzy, weights)
+                    .await
             }
         }
     }
@@ -192,6 +216,8 @@ impl AiTool for ConstellationSearchTool {
                     start_time: None,
                     end_time: None,
                     fuzzy: false,
+                    bm25_weight: None,
+                    vector_weight: None,
                 },
                 expected_output: Some(SearchOutput {
                     success: true,
@@ -214,6 +240,8 @@ impl AiTool for ConstellationSearchTool {
                     start_time: None,
                     end_time: None,
                     fuzzy: false,
+                    bm25_weight: None,
+                    vector_weight: None,
                 },
                 expected_output: Some(SearchOutput {
                     success: true,
@@ -240,6 +268,7 @@ impl ConstellationSearchTool {
         query: &str,
         limit: usize,
         fuzzy: bool,
+        weights: SearchWeights,
     ) -> Result<SearchOutput> {
         // Try to use database if available
         if self.handle.has_db_connection() {
@@ -248,7 +277,7 @@ impl ConstellationSearchTool {
             let fuzzy_level = if fuzzy { Some(1) } else { None };
             match self
                 .handle
-                .search_archival_memories_with_options(query, limit, fuzzy_level)
+                .search_archival_memories_hybrid(query, limit, fuzzy_level, weights)
                 .await
             {
                 Ok(mut scored_blocks) => {
@@ -349,13 +378,14 @@ impl ConstellationSearchTool {
         query: &str,
         limit: usize,
         fuzzy: bool,
+        weights: SearchWeights,
     ) -> Result<SearchOutput> {
         // Use database search if available
         if self.handle.has_db_connection() {
             let fuzzy_level = if fuzzy { Some(1) } else { None };
             match self
                 .handle
-                .search_group_archival_memories_with_options(query, limit, fuzzy_level)
+                .search_group_archival_memories_hybrid(query, limit, fuzzy_level, weights)
                 .await
             {
                 Ok(mut scored_blocks) => {
@@ -502,9 +532,17 @@ impl ConstellationSearchTool {
         }
     }
 
-    async fn search_all(&self, query: &str, limit: usize, fuzzy: bool) -> Result<SearchOutput> {
+    async fn search_all(
+        &self,
+        query: &str,
+        limit: usize,
+        fuzzy: bool,
+        weights: SearchWeights,
+    ) -> Result<SearchOutput> {
         // Search both domains and combine results
-        let archival_result = self.search_local_archival(query, limit, fuzzy).await?;
+        let archival_result = self
+            .search_local_archival(query, limit, fuzzy, weights)
+            .await?;
         let conv_result = self
             .search_constellation_messages(query, None, None, None, limit, fuzzy)
             .await?;
@@ -564,6 +602,8 @@ mod tests {
                     start_time: None,
                     end_time: None,
                     fuzzy: false,
+                    bm25_weight: None,
+                    vector_weight: None,
                 },
                 &crate::tool::ExecutionMeta::default(),
             )
@@ -578,4 +618,15 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0]["label"], "pref_color");
     }
+    #[test]
+    fn test_constellation_search_weights() {
+        let input: ConstellationSearchInput = serde_json::from_value(json!({
+            "query": "garden",
+            "vector_weight": 0.0
+        }))
+        .unwrap();
+        let weights = input.weights();
+        assert_eq!(weights.bm25, SearchWeights::default().bm25);
+        assert!(!weights.uses_vector());
+    }
 }
@@ -9,7 +9,7 @@ use crate::{
     Result,
     context::AgentHandle,
     memory::{MemoryPermission, MemoryType},
-    memory_acl::{MemoryGate, MemoryOp, check as acl_check, consent_reason},
+    memory_acl::{MemoryGate, MemoryOp, check_for, consent_reason},
     permission::PermissionScope,
     tool::{AiTool, ExecutionMeta},
 };
@@ -255,6 +255,8 @@ impl ContextTool {
         content: String,
         meta: &ExecutionMeta,
     ) -> Result<ContextOutput> {
+        let subject = self.handle.policy_subject().await;
+
         // Fetch and validate target block (copy needed fields, then drop guard)
         let (current_type, current_perm) = {
             let guard = self.handle.memory.get_block(&name).ok_or_else(|| {
@@ -279,38 +281,38 @@ impl ContextTool {
         }
 
         // Gate by permission, offering consent path when applicable
-        if !self.can_bypass(meta, &name) {
-            match acl_check(MemoryOp::Append, current_perm) {
-                MemoryGate::Allow => {}
-                MemoryGate::Deny { reason } => {
+        match check_for(&subject, &name, MemoryOp::Append, current_perm) {
+            MemoryGate::Allow => {}
+            MemoryGate::Deny { reason } => {
+                return Ok(ContextOutput {
+                    success: false,
+                    message: Some(format!("{} — cannot append to '{}'", reason, name)),
+                    content: json!({}),
+                });
+            }
+            // A grant covers consent, but never overrides a denial
+            MemoryGate::RequireConsent { .. } if self.can_bypass(meta, &name) => {}
+            MemoryGate::RequireConsent { .. } => {
+                let grant = crate::permission::broker()
+                    .request(
+                        self.handle.agent_id.clone(),
+                        "context".to_string(),
+                        PermissionScope::MemoryEdit { key: name.clone() },
+                        Some(consent_reason(&name, MemoryOp::Append, current_perm)),
+                        meta.route_metadata.clone(),
+                        std::time::Duration::from_secs(90),
+                    )
+                    .await;
+                if grant.is_none() {
                     return Ok(ContextOutput {
                         success: false,
-                        message: Some(format!("{} — cannot append to '{}'", reason, name)),
+                        message: Some(format!(
+                            "Append to '{}' requires approval; request timed out or was denied",
+                            name
+                        )),
                         content: json!({}),
                     });
                 }
-                MemoryGate::RequireConsent { .. } => {
-                    let grant = crate::permission::broker()
-                        .request(
-                            self.handle.agent_id.clone(),
-                            "context".to_string(),
-                            PermissionScope::MemoryEdit { key: name.clone() },
-                            Some(consent_reason(&name, MemoryOp::Append, current_perm)),
-                            meta.route_metadata.clone(),
-                            std::time::Duration::from_secs(90),
-                        )
-                        .await;
-                    if grant.is_none() {
-                        return Ok(ContextOutput {
-                            success: false,
-                            message: Some(format!(
-                                "Append to '{}' requires approval; request timed out or was denied",
-                                name
-                            )),
-                            content: json!({}),
-                        });
-                    }
-                }
             }
         }
 
@@ -370,6 +372,8 @@ impl ContextTool {
         new_content: String,
         meta: &ExecutionMeta,
     ) -> Result<ContextOutput> {
+        let subject = self.handle.policy_subject().await;
+
         // Fetch and validate target block (copy fields, drop guard)
         let (current_type, current_perm) = match self.handle.memory.get_block(&name) {
             Some(b) => (b.memory_type, b.permission),
@@ -398,56 +402,61 @@ impl ContextTool {
         }
 
         // Gate by permission, offering consent path when applicable
-        if !self.can_bypass(meta, &name) {
-            match acl_check(MemoryOp::Overwrite, current_perm) {
-                MemoryGate::Allow => {}
-                MemoryGate::Deny { reason } => {
+        match check_for(&subject, &name, MemoryOp::Overwrite, current_perm) {
+            MemoryGate::Allow => {}
+            MemoryGate::Deny { reason } => {
+                return Ok(ContextOutput {
+                    success: false,
+                    message: Some(format!("{} — cannot replace in '{}'", reason, name)),
+                    content: json!({}),
+                });
+            }
+            MemoryGate::RequireConsent { .. } if self.can_bypass(meta, &name) => {}
+            MemoryGate::RequireConsent { .. } => {
+                let grant = crate::permission::broker()
+                    .request(
+                        self.handle.agent_id.clone(),
+                        "context".to_string(),
+                        PermissionScope::MemoryEdit { key: name.clone() },
+                        Some(consent_reason(&name, MemoryOp::Overwrite, current_perm)),
+                        meta.route_metadata.clone(),
+                        std::time::Duration::from_secs(90),
+                    )
+                    .await;
+                if grant.is_none() {
                     return Ok(ContextOutput {
                         success: false,
-                        message: Some(format!("{} — cannot replace in '{}'", reason, name)),
+                        message: Some(format!(
+                            "Replace in '{}' requires approval; request timed out or was denied",
+                            name
+                        )),
                         content: json!({}),
                     });
                 }
-                MemoryGate::RequireConsent { .. } => {
-                    let grant = crate::permission::broker()
-                        .request(
-                            self.handle.agent_id.clone(),
-                            "context".to_string(),
-                            PermissionScope::MemoryEdit { key: name.clone() },
-                            Some(consent_reason(&name, MemoryOp::Overwrite, current_perm)),
-                            meta.route_metadata.clone(),
-                            std::time::Duration::from_secs(90),
-                        )
-                        .await;
-                    if grant.is_none() {
-                        return Ok(ContextOutput {
-                            success: false,
-                            message: Some(format!(
-                                "Replace in '{}' requires approval; request timed out or was denied",
-                                name
-                            )),
-                            content: json!({}),
-                        });
-                    }
-                }
             }
         }
 
         // Perform the replacement (and validate old_content presence)
-        if let Some(mut block) = self.handle.memory.get_block_mut(&name) {
-            if !block.value.contains(&old_content) {
-                return Ok(ContextOutput {
-                    success: false,
-                    message: Some(format!(
-                        "Content '{}' not found in context section '{}'",
-                        old_content, name
-                    )),
-                    content: json!({}),
-                });
-            }
+        let found = self
+            .handle
+            .memory
+            .get_block(&name)
+            .is_some_and(|block| block.value.contains(&old_content));
+        if !found {
+            return Ok(ContextOutput {
+                success: false,
+                message: Some(format!(
+                    "Content '{}' not found in context section '{}'",
+                    old_content, name
+                )),
+                content: json!({}),
+            });
+        }
+        self.handle.memory.alter_block(&name, |_k, mut block| {
             block.value = block.value.replace(&old_content, &new_content);
             block.updated_at = chrono::Utc::now();
-        }
+            block
+        });
 
         // Get the updated block to show the new state
         let updated_block = self
@@ -506,6 +515,8 @@ impl ContextTool {
         archival_label: Option<String>,
         meta: &ExecutionMeta,
     ) -> Result<ContextOutput> {
+        let subject = self.handle.policy_subject().await;
+
         // Check if the block exists and is context
         let block = match self.handle.memory.get_block(&name) {
             Some(block) => {
@@ -553,47 +564,52 @@ impl ContextTool {
         // If the archival label already exists, enforce Overwrite ACL (with consent path)
         if self.handle.memory.contains_block(&archival_label) {
             if let Some(existing) = self.handle.memory.get_block(&archival_label) {
-                if !self.can_bypass(meta, &archival_label) {
-                    match acl_check(MemoryOp::Overwrite, existing.permission) {
-                        MemoryGate::Allow => {}
-                        MemoryGate::Deny { reason } => {
+                match check_for(
+                    &subject,
+                    &archival_label,
+                    MemoryOp::Overwrite,
+                    existing.permission,
+                ) {
+                    MemoryGate::Allow => {}
+                    MemoryGate::Deny { reason } => {
+                        return Ok(ContextOutput {
+                            success: false,
+                            message: Some(format!(
+                                "{} — cannot overwrite existing recall memory '{}'",
+                                reason, archival_label
+                            )),
+                            content: json!({}),
+                        });
+                    }
+                    MemoryGate::RequireConsent { .. } if self.can_bypass(meta, &archival_label) => {
+                    }
+                    MemoryGate::RequireConsent { .. } => {
+                        let grant = crate::permission::broker()
+                            .request(
+                                self.handle.agent_id.clone(),
+                                "context".to_string(),
+                                PermissionScope::MemoryEdit {
+                                    key: archival_label.clone(),
+                                },
+                                Some(consent_reason(
+                                    &archival_label,
+                                    MemoryOp::Overwrite,
+                                    existing.permission,
+                                )),
+                                meta.route_metadata.clone(),
+                                std::time::Duration::from_secs(90),
+                            )
+                            .await;
+                        if grant.is_none() {
                             return Ok(ContextOutput {
                                 success: false,
                                 message: Some(format!(
-                                    "{} — cannot overwrite existing recall memory '{}'",
-                                    reason, archival_label
+                                    "Overwriting recall memory '{}' requires approval; request timed out or was denied",
+                                    archival_label
                                 )),
                                 content: json!({}),
                             });
                         }
-                        MemoryGate::RequireConsent { .. } => {
-                            let grant = crate::permission::broker()
-                                .request(
-                                    self.handle.agent_id.clone(),
-                                    "context".to_string(),
-                                    PermissionScope::MemoryEdit {
-                                        key: archival_label.clone(),
-                                    },
-                                    Some(consent_reason(
-                                        &archival_label,
-                                        MemoryOp::Overwrite,
-                                        existing.permission,
-                                    )),
-                                    meta.route_metadata.clone(),
-                                    std::time::Duration::from_secs(90),
-                                )
-                                .await;
-                            if grant.is_none() {
-                                return Ok(ContextOutput {
-                                    success: false,
-                                    message: Some(format!(
-                                        "Overwriting recall memory '{}' requires approval; request timed out or was denied",
-                                        archival_label
-                                    )),
-                                    content: json!({}),
-                                });
-                            }
-                        }
                     }
                 }
             }
@@ -612,7 +628,7 @@ impl ContextTool {
         }
 
         // Enforce delete ACL for the original context block
-        match acl_check(MemoryOp::Delete, block.permission) {
+        match check_for(&subject, &name, MemoryOp::Delete, block.permission) {
             MemoryGate::Allow => {
                 self.handle.memory.remove_block(&name);
             }
@@ -778,6 +794,8 @@ impl ContextTool {
         archival_label: String,
         meta: &ExecutionMeta,
     ) -> Result<ContextOutput> {
+        let subject = self.handle.policy_subject().await;
+
         // First check both blocks exist and have correct types
         let core_block = match self.handle.memory.get_block(&archive_name) {
             Some(block) => {
@@ -849,44 +867,48 @@ impl ContextTool {
         }
 
         // Overwrite requires ACL; allow consent path if configured
-        if !self.can_bypass(meta, &archive_name) {
-            match acl_check(MemoryOp::Overwrite, core_block.permission) {
-                MemoryGate::Allow => {}
-                MemoryGate::Deny { reason } => {
+        match check_for(
+            &subject,
+            &archive_name,
+            MemoryOp::Overwrite,
+            core_block.permission,
+        ) {
+            MemoryGate::Allow => {}
+            MemoryGate::Deny { reason } => {
+                return Ok(ContextOutput {
+                    success: false,
+                    message: Some(format!("{} — cannot swap into '{}'", reason, archive_name)),
+                    content: json!({}),
+                });
+            }
+            MemoryGate::RequireConsent { .. } if self.can_bypass(meta, &archive_name) => {}
+            MemoryGate::RequireConsent { .. } => {
+                let grant = crate::permission::broker()
+                    .request(
+                        self.handle.agent_id.clone(),
+                        "context".to_string(),
+                        PermissionScope::MemoryEdit {
+                            key: archive_name.clone(),
+                        },
+                        Some(consent_reason(
+                            &archive_name,
+                            MemoryOp::Overwrite,
+                            core_block.permission,
+                        )),
+                        meta.route_metadata.clone(),
+                        std::time::Duration::from_secs(90),
+                    )
+                    .await;
+                if grant.is_none() {
                     return Ok(ContextOutput {
                         success: false,
-                        message: Some(format!("{} — cannot swap into '{}'", reason, archive_name)),
+                        message: Some(format!(
+                            "Swap into '{}' requires approval; request timed out or was denied",
+                            archive_name
+                        )),
                         content: json!({}),
                     });
                 }
-                MemoryGate::RequireConsent { .. } => {
-                    let grant = crate::permission::broker()
-                        .request(
-                            self.handle.agent_id.clone(),
-                            "context".to_string(),
-                            PermissionScope::MemoryEdit {
-                                key: archive_name.clone(),
-                            },
-                            Some(consent_reason(
-                                &archive_name,
-                                MemoryOp::Overwrite,
-                                core_block.permission,
-                            )),
-                            meta.route_metadata.clone(),
-                            std::time::Duration::from_secs(90),
-                        )
-                        .await;
-                    if grant.is_none() {
-                        return Ok(ContextOutput {
-                            success: false,
-                            message: Some(format!(
-                                "Swap into '{}' requires approval; request timed out or was denied",
-                                archive_name
-                            )),
-                            content: json!({}),
-                        });
-                    }
-                }
             }
         }
 
@@ -896,7 +918,12 @@ impl ContextTool {
             .update_block_value(&archive_name, archival_block.value.clone())?;
 
         // Remove the original archival block (enforce delete ACL)
-        match acl_check(MemoryOp::Delete, archival_block.permission) {
+        match check_for(
+            &subject,
+            &archival_label,
+            MemoryOp::Delete,
+            archival_block.permission,
+        ) {
             MemoryGate::Allow => {
                 self.handle.memory.remove_block(&archival_label);
             }
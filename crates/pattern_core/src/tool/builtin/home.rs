@@ -0,0 +1,343 @@
+//! Home tool for reading HomeAssistant entity states and calling its services
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    Result,
+    data_source::{HomeAssistantClient, HomeAssistantItem},
+    tool::{AiTool, ExecutionMeta},
+};
+
+/// Most entities returned by a single `states` call
+const MAX_STATES: usize = 50;
+
+/// Operation types for the home tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(inline)]
+pub enum HomeOperationType {
+    States,
+    CallService,
+}
+
+/// Input for the home tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct HomeInput {
+    /// The operation to perform
+    pub operation: HomeOperationType,
+
+    /// For states: only entities in this domain. For call_service: the service
+    /// domain (e.g. "light", "scene", "notify")
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+
+    /// For call_service: the service to call (e.g. "turn_on", "turn_off")
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+
+    /// Entity to read or act on (e.g. "light.desk_lamp")
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity_id: Option<String>,
+
+    /// For call_service: additional service data (e.g. {"brightness_pct": 30})
+    #[schemars(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+/// Output from home operations
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HomeOutput {
+    /// Whether the operation was successful
+    pub success: bool,
+
+    /// Message about the operation
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Entities read or changed
+    #[schemars(default)]
+    pub entities: Vec<EntityState>,
+}
+
+/// State of one entity as shown to the agent
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct EntityState {
+    pub entity_id: String,
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub friendly_name: Option<String>,
+    pub state: String,
+    /// When the state last changed (RFC 3339)
+    pub last_changed: String,
+    /// Entity attributes, included when a single entity is requested
+    #[schemars(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<Value>,
+}
+
+impl EntityState {
+    fn from_item(item: HomeAssistantItem, with_attributes: bool) -> Self {
+        Self {
+            entity_id: item.entity_id,
+            friendly_name: item.friendly_name,
+            state: item.state,
+            last_changed: item.last_changed.to_rfc3339(),
+            attributes: with_attributes
+                .then(|| serde_json::to_value(item.attributes).unwrap_or_default()),
+        }
+    }
+}
+
+/// Tool for checking and controlling the home through HomeAssistant
+#[derive(Debug, Clone)]
+pub struct HomeTool {
+    client: HomeAssistantClient,
+    service_domains: Vec<String>,
+}
+
+impl HomeTool {
+    /// Create a home tool allowed to call light, scene and notify services
+    pub fn new(client: HomeAssistantClient) -> Self {
+        Self {
+            client,
+            service_domains: vec![
+                "light".to_string(),
+                "scene".to_string(),
+                "notify".to_string(),
+            ],
+        }
+    }
+
+    /// Restrict service calls to these domains
+    pub fn with_service_domains(mut self, service_domains: Vec<String>) -> Self {
+        self.service_domains = service_domains;
+        self
+    }
+
+    fn failure(message: String) -> HomeOutput {
+        HomeOutput {
+            success: false,
+            message: Some(message),
+            entities: vec![],
+        }
+    }
+
+    async fn execute_states(
+        &self,
+        domain: Option<&str>,
+        entity_id: Option<&str>,
+    ) -> Result<HomeOutput> {
+        let mut states: Vec<HomeAssistantItem> = self
+            .client
+            .states()
+            .await?
+            .into_iter()
+            .filter(|item| entity_id.is_none_or(|id| item.entity_id == id))
+            .filter(|item| domain.is_none_or(|d| item.domain == d))
+            .collect();
+        states.sort_by(|a, b| a.entity_id.cmp(&b.entity_id));
+
+        let total = states.len();
+        states.truncate(MAX_STATES);
+        let message = if total > MAX_STATES {
+            format!(
+                "Showing {} of {} entities. Narrow down with 'domain' or 'entity_id'",
+                MAX_STATES, total
+            )
+        } else {
+            format!("{} entities", total)
+        };
+
+        Ok(HomeOutput {
+            success: true,
+            message: Some(message),
+            entities: states
+                .into_iter()
+                .map(|item| EntityState::from_item(item, entity_id.is_some()))
+                .collect(),
+        })
+    }
+
+    async fn execute_call_service(
+        &self,
+        domain: &str,
+        service: &str,
+        entity_id: Option<String>,
+        data: Option<Value>,
+    ) -> Result<HomeOutput> {
+        if !self.service_domains.iter().any(|d| d == domain) {
+            return Ok(Self::failure(format!(
+                "Calling '{}' services is not allowed. Allowed domains: {}",
+                domain,
+                self.service_domains.join(", ")
+            )));
+        }
+        if service.is_empty()
+            || !service
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Ok(Self::failure(format!("Invalid service name '{}'", service)));
+        }
+
+        let mut data = match data {
+            Some(Value::Object(map)) => map,
+            Some(Value::Null) | None => serde_json::Map::new(),
+            Some(other) => {
+                return Ok(Self::failure(format!(
+                    "'data' must be an object, got {}",
+                    other
+                )));
+            }
+        };
+        if let Some(entity_id) = entity_id {
+            data.insert("entity_id".to_string(), Value::String(entity_id));
+        }
+
+        let changed = self
+            .client
+            .call_service(domain, service, Value::Object(data))
+            .await?;
+
+        Ok(HomeOutput {
+            success: true,
+            message: Some(format!(
+                "Called {}.{}, {} entities changed",
+                domain,
+                service,
+                changed.len()
+            )),
+            entities: changed
+                .into_iter()
+                .map(|item| EntityState::from_item(item, false))
+                .collect(),
+        })
+    }
+}
+
+#[async_trait]
+impl AiTool for HomeTool {
+    type Input = HomeInput;
+    type Output = HomeOutput;
+
+    fn name(&self) -> &str {
+        "home"
+    }
+
+    fn description(&self) -> &str {
+        "Check and control the home through HomeAssistant. Operations: states, call_service.
+ - 'states' lists entity states, optionally narrowed to a 'domain' (e.g. 'sensor') or one 'entity_id' (which also returns its attributes)
+ - 'call_service' calls 'domain'.'service' on 'entity_id' with optional 'data', e.g. light.turn_on with {\"brightness_pct\": 30}, scene.turn_on on a scene, or notify.notify with {\"message\": \"...\"}"
+    }
+
+    async fn execute(&self, params: Self::Input, _meta: &ExecutionMeta) -> Result<Self::Output> {
+        match params.operation {
+            HomeOperationType::States => {
+                self.execute_states(params.domain.as_deref(), params.entity_id.as_deref())
+                    .await
+            }
+            HomeOperationType::CallService => match (params.domain, params.service) {
+                (Some(domain), Some(service)) => {
+                    self.execute_call_service(&domain, &service, params.entity_id, params.data)
+                        .await
+                }
+                _ => Ok(Self::failure(
+                    "'domain' and 'service' are required for call_service".to_string(),
+                )),
+            },
+        }
+    }
+
+    fn usage_rule(&self) -> Option<&'static str> {
+        Some("the conversation will be continued when called")
+    }
+
+    fn examples(&self) -> Vec<crate::tool::ToolExample<Self::Input, Self::Output>> {
+        vec![crate::tool::ToolExample {
+            description: "Dim the desk lamp for wind-down".to_string(),
+            parameters: HomeInput {
+                operation: HomeOperationType::CallService,
+                domain: Some("light".to_string()),
+                service: Some("turn_on".to_string()),
+                entity_id: Some("light.desk_lamp".to_string()),
+                data: Some(serde_json::json!({ "brightness_pct": 30 })),
+            },
+            expected_output: Some(HomeOutput {
+                success: true,
+                message: Some("Called light.turn_on, 1 entities changed".to_string()),
+                entities: vec![EntityState {
+                    entity_id: "light.desk_lamp".to_string(),
+                    friendly_name: Some("Desk lamp".to_string()),
+                    state: "on".to_string(),
+                    last_changed: "2025-01-01T21:30:00+00:00".to_string(),
+                    attributes: None,
+                }],
+            }),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool() -> HomeTool {
+        // Every case below is rejected before any request is made
+        HomeTool::new(HomeAssistantClient::new(
+            url::Url::parse("http://127.0.0.1:9").unwrap(),
+            "token".to_string(),
+        ))
+    }
+
+    fn call(domain: Option<&str>, service: Option<&str>, data: Option<Value>) -> HomeInput {
+        HomeInput {
+            operation: HomeOperationType::CallService,
+            domain: domain.map(str::to_string),
+            service: service.map(str::to_string),
+            entity_id: Some("lock.front_door".to_string()),
+            data,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_service_validation() {
+        let tool = tool();
+        let meta = ExecutionMeta::default();
+
+        let disallowed = tool
+            .execute(call(Some("lock"), Some("unlock"), None), &meta)
+            .await
+            .unwrap();
+        assert!(!disallowed.success);
+        assert!(disallowed.message.unwrap().contains("not allowed"));
+
+        let missing = tool
+            .execute(call(Some("light"), None, None), &meta)
+            .await
+            .unwrap();
+        assert!(!missing.success);
+
+        let bad_name = tool
+            .execute(call(Some("light"), Some("../states"), None), &meta)
+            .await
+            .unwrap();
+        assert!(!bad_name.success);
+
+        let bad_data = tool
+            .execute(
+                call(Some("light"), Some("turn_on"), Some(Value::from(30))),
+                &meta,
+            )
+            .await
+            .unwrap();
+        assert!(!bad_data.success);
+    }
+}
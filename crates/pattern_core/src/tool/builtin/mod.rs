@@ -4,22 +4,33 @@
 //! including memory management and inter-agent communication.
 
 mod calculator;
+mod calendar;
 mod constellation_search;
 mod context;
 pub mod data_source;
+mod home;
 mod mail;
 mod recall;
+mod record_metric;
+mod schedule;
 mod search;
 pub mod search_utils;
 mod send_message;
+mod skill;
 mod system_integrity;
+mod task;
 #[cfg(test)]
 mod test_schemas;
+mod vote;
 mod web;
 
 use std::fmt::Debug;
 
 pub use calculator::{CalculatorInput, CalculatorOutput, CalculatorTool};
+pub use calendar::{
+    CalendarInput, CalendarOperationType, CalendarOutput, CalendarTool, EventSummary,
+    render_event_list,
+};
 pub use constellation_search::{
     ConstellationSearchDomain, ConstellationSearchInput, ConstellationSearchTool,
 };
@@ -27,15 +38,26 @@ pub use context::{ContextInput, ContextOutput, ContextTool, CoreMemoryOperationT
 pub use data_source::{
     DataSourceInput, DataSourceOutput, DataSourceTool, register_data_source_tool,
 };
+pub use home::{EntityState, HomeInput, HomeOperationType, HomeOutput, HomeTool};
 pub use mail::{MailInput, MailOutput, MailTool};
 pub use recall::{
     ArchivalMemoryOperationType, ArchivalSearchResult, RecallInput, RecallOutput, RecallTool,
 };
+pub use record_metric::{RecordMetricInput, RecordMetricOutput, RecordMetricTool};
+pub use schedule::{
+    ScheduleInput, ScheduleOperationType, ScheduleOutput, ScheduleTool, WakeupSummary,
+};
 use schemars::JsonSchema;
 pub use search::{SearchDomain, SearchInput, SearchOutput, SearchTool};
 pub use send_message::SendMessageTool;
 use serde::{Deserialize, Serialize};
+pub use skill::{SkillInput, SkillOperationType, SkillOutput, SkillSummary, SkillTool};
 pub use system_integrity::{SystemIntegrityInput, SystemIntegrityOutput, SystemIntegrityTool};
+pub use task::{
+    TASKS_BLOCK_LABEL, TaskInput, TaskOperationType, TaskOutput, TaskSummary, TaskTool,
+    render_task_list,
+};
+pub use vote::{VoteInput, VoteOutput, VoteTool};
 pub use web::{WebFormat, WebInput, WebOutput, WebTool};
 
 use crate::{
@@ -50,6 +72,9 @@ pub struct BuiltinTools {
     context_tool: Box<dyn DynamicTool>,
     search_tool: Box<dyn DynamicTool>,
     send_message_tool: Box<dyn DynamicTool>,
+    schedule_tool: Box<dyn DynamicTool>,
+    task_tool: Box<dyn DynamicTool>,
+    calendar_tool: Box<dyn DynamicTool>,
     web_tool: Option<Box<dyn DynamicTool>>,
     calculator_tool: Option<Box<dyn DynamicTool>>,
     mail_tool: Option<Box<dyn DynamicTool>>,
@@ -71,6 +96,9 @@ impl BuiltinTools {
             send_message_tool: Box::new(DynamicToolAdapter::new(SendMessageTool {
                 handle: handle.clone(),
             })),
+            schedule_tool: Box::new(DynamicToolAdapter::new(ScheduleTool::new(handle.clone()))),
+            task_tool: Box::new(DynamicToolAdapter::new(TaskTool::new(handle.clone()))),
+            calendar_tool: Box::new(DynamicToolAdapter::new(CalendarTool::new(handle.clone()))),
             web_tool: Some(Box::new(DynamicToolAdapter::new(WebTool::new(
                 handle.clone(),
             )))),
@@ -87,6 +115,9 @@ impl BuiltinTools {
         registry.register_dynamic(self.context_tool.clone_box());
         registry.register_dynamic(self.search_tool.clone_box());
         registry.register_dynamic(self.send_message_tool.clone_box());
+        registry.register_dynamic(self.schedule_tool.clone_box());
+        registry.register_dynamic(self.task_tool.clone_box());
+        registry.register_dynamic(self.calendar_tool.clone_box());
 
         if let Some(web_tool) = &self.web_tool {
             registry.register_dynamic(web_tool.clone_box());
@@ -117,6 +148,9 @@ pub struct BuiltinToolsBuilder {
     context_tool: Option<Box<dyn DynamicTool>>,
     search_tool: Option<Box<dyn DynamicTool>>,
     send_message_tool: Option<Box<dyn DynamicTool>>,
+    schedule_tool: Option<Box<dyn DynamicTool>>,
+    task_tool: Option<Box<dyn DynamicTool>>,
+    calendar_tool: Option<Box<dyn DynamicTool>>,
     calculator_tool: Option<Box<dyn DynamicTool>>,
     mail_tool: Option<Box<dyn DynamicTool>>,
 }
@@ -146,6 +180,24 @@ impl BuiltinToolsBuilder {
         self
     }
 
+    /// Replace the default schedule tool
+    pub fn with_schedule_tool(mut self, tool: impl DynamicTool + 'static) -> Self {
+        self.schedule_tool = Some(Box::new(tool));
+        self
+    }
+
+    /// Replace the default task tool
+    pub fn with_task_tool(mut self, tool: impl DynamicTool + 'static) -> Self {
+        self.task_tool = Some(Box::new(tool));
+        self
+    }
+
+    /// Replace the default calendar tool
+    pub fn with_calendar_tool(mut self, tool: impl DynamicTool + 'static) -> Self {
+        self.calendar_tool = Some(Box::new(tool));
+        self
+    }
+
     /// Replace the default calculator tool
     pub fn with_calculator_tool(mut self, tool: impl DynamicTool + 'static) -> Self {
         self.calculator_tool = Some(Box::new(tool));
@@ -166,6 +218,9 @@ impl BuiltinToolsBuilder {
             context_tool: self.context_tool.unwrap_or(defaults.context_tool),
             search_tool: self.search_tool.unwrap_or(defaults.search_tool),
             send_message_tool: self.send_message_tool.unwrap_or(defaults.send_message_tool),
+            schedule_tool: self.schedule_tool.unwrap_or(defaults.schedule_tool),
+            task_tool: self.task_tool.unwrap_or(defaults.task_tool),
+            calendar_tool: self.calendar_tool.unwrap_or(defaults.calendar_tool),
             web_tool: defaults.web_tool,
             calculator_tool: self.calculator_tool.or(defaults.calculator_tool),
             mail_tool: self.mail_tool.or(defaults.mail_tool),
@@ -10,7 +10,8 @@ use crate::{
     Result,
     context::AgentHandle,
     memory::{MemoryPermission, MemoryType},
-    memory_acl::{MemoryGate, MemoryOp, check as acl_check, consent_reason},
+    memory_acl::{MemoryGate, MemoryOp, check_for, consent_reason},
+    policy::PolicyDecision,
     tool::{AiTool, ExecutionMeta},
 };
 
@@ -275,10 +276,29 @@ impl RecallTool {
     }
 
     async fn execute_delete(&self, label: String, meta: &ExecutionMeta) -> Result<RecallOutput> {
+        let subject = self.handle.policy_subject().await;
+
         // Check if block exists and get type
         let block_type = if let Some(block) = self.handle.memory.get_block(&label) {
             let memory_type = block.memory_type;
-            if block.permission != MemoryPermission::Admin {
+            let needs_consent = match crate::policy::policy().check_memory(
+                &subject,
+                &label,
+                MemoryOp::Delete,
+                chrono::Utc::now(),
+            ) {
+                Some(PolicyDecision::Allow) => false,
+                Some(PolicyDecision::RequireConsent { .. }) => true,
+                Some(PolicyDecision::Deny { reason }) => {
+                    return Ok(RecallOutput {
+                        success: false,
+                        message: Some(format!("{} — cannot delete '{}'", reason, label)),
+                        results: vec![],
+                    });
+                }
+                None => block.permission != MemoryPermission::Admin,
+            };
+            if needs_consent {
                 // High-risk delete: request explicit consent
                 let grant = crate::permission::broker()
                     .request(
@@ -287,11 +307,7 @@ impl RecallTool {
                         crate::permission::PermissionScope::MemoryEdit { key: label.clone() },
                         Some(format!(
                             "{} — high-risk delete",
-                            crate::memory_acl::consent_reason(
-                                &label,
-                                crate::memory_acl::MemoryOp::Delete,
-                                block.permission
-                            )
+                            consent_reason(&label, MemoryOp::Delete, block.permission)
                         )),
                         meta.route_metadata.clone(),
                         std::time::Duration::from_secs(90),
@@ -379,7 +395,8 @@ impl RecallTool {
             });
         }
 
-        match acl_check(MemoryOp::Append, current_perm) {
+        let subject = self.handle.policy_subject().await;
+        match check_for(&subject, &label, MemoryOp::Append, current_perm) {
             MemoryGate::Allow => {}
             MemoryGate::Deny { reason } => {
                 return Ok(RecallOutput {
@@ -0,0 +1,176 @@
+//! Tool for agents to record numeric metrics into constellation activity
+//!
+//! Recorded metrics are what `threshold_exceeded` sleeptime triggers compare
+//! against their configured thresholds.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CoreError, Result,
+    constellation_memory::ConstellationActivityTracker,
+    context::AgentHandle,
+    tool::{AiTool, ExecutionMeta},
+};
+
+/// Input for recording a metric
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct RecordMetricInput {
+    /// Metric name in snake_case (e.g. "minutes_since_water")
+    pub name: String,
+
+    /// Current value of the metric
+    pub value: f64,
+}
+
+/// Output from recording a metric
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RecordMetricOutput {
+    /// Whether the metric was recorded
+    pub success: bool,
+
+    /// Message about the operation
+    pub message: String,
+
+    /// Previous value of this metric, if one was recorded
+    #[schemars(default, with = "f64")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<f64>,
+}
+
+/// Tool for recording metrics that sleeptime triggers can watch
+#[derive(Clone)]
+pub struct RecordMetricTool {
+    pub(crate) handle: AgentHandle,
+    tracker: Arc<ConstellationActivityTracker>,
+}
+
+impl std::fmt::Debug for RecordMetricTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordMetricTool")
+            .field("agent_id", &self.handle.agent_id)
+            .finish()
+    }
+}
+
+impl RecordMetricTool {
+    /// Create a new metric tool writing to the given activity tracker
+    pub fn new(handle: AgentHandle, tracker: Arc<ConstellationActivityTracker>) -> Self {
+        Self { handle, tracker }
+    }
+}
+
+#[async_trait]
+impl AiTool for RecordMetricTool {
+    type Input = RecordMetricInput;
+    type Output = RecordMetricOutput;
+
+    fn name(&self) -> &str {
+        "record_metric"
+    }
+
+    fn description(&self) -> &str {
+        "Record the current value of a numeric metric (e.g. minutes_sitting, hours_since_meal, open_tasks) in the shared constellation activity log. Background checks compare these against their thresholds."
+    }
+
+    async fn execute(&self, params: Self::Input, _meta: &ExecutionMeta) -> Result<Self::Output> {
+        let name = params.name.trim();
+        if name.is_empty() {
+            return Err(CoreError::tool_exec_msg(
+                "record_metric",
+                serde_json::to_value(&params).unwrap_or_default(),
+                "'name' cannot be empty",
+            ));
+        }
+        if !params.value.is_finite() {
+            return Ok(RecordMetricOutput {
+                success: false,
+                message: "'value' must be a finite number".to_string(),
+                previous: None,
+            });
+        }
+
+        let previous = self.tracker.latest_metric(name).await.map(|(v, _)| v);
+        self.tracker
+            .record_metric(
+                self.handle.agent_id.clone(),
+                self.handle.name.clone(),
+                name,
+                params.value,
+            )
+            .await;
+
+        Ok(RecordMetricOutput {
+            success: true,
+            message: format!("Recorded {} = {}", name, params.value),
+            previous,
+        })
+    }
+
+    fn usage_rule(&self) -> Option<&'static str> {
+        Some("the conversation will be continued when called")
+    }
+
+    fn examples(&self) -> Vec<crate::tool::ToolExample<Self::Input, Self::Output>> {
+        vec![crate::tool::ToolExample {
+            description: "Note how long the partner has been sitting".to_string(),
+            parameters: RecordMetricInput {
+                name: "minutes_sitting".to_string(),
+                value: 75.0,
+            },
+            expected_output: Some(RecordMetricOutput {
+                success: true,
+                message: "Recorded minutes_sitting = 75".to_string(),
+                previous: Some(40.0),
+            }),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{UserId, memory::Memory};
+
+    #[tokio::test]
+    async fn test_record_metric() {
+        let tracker = Arc::new(ConstellationActivityTracker::new(100));
+        let handle = AgentHandle::test_with_memory(Memory::with_owner(&UserId::generate()));
+        let tool = RecordMetricTool::new(handle, tracker.clone());
+
+        let first = tool
+            .execute(
+                RecordMetricInput {
+                    name: "minutes_sitting".to_string(),
+                    value: 40.0,
+                },
+                &ExecutionMeta::default(),
+            )
+            .await
+            .unwrap();
+        assert!(first.success);
+        assert!(first.previous.is_none());
+
+        let second = tool
+            .execute(
+                RecordMetricInput {
+                    name: "minutes_sitting".to_string(),
+                    value: 75.0,
+                },
+                &ExecutionMeta::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(second.previous, Some(40.0));
+        assert_eq!(
+            tracker
+                .latest_metric("minutes_sitting")
+                .await
+                .map(|(v, _)| v),
+            Some(75.0)
+        );
+    }
+}
@@ -0,0 +1,450 @@
+//! Scheduling tool for agents to set reminders and wakeups for themselves
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CoreError, Result,
+    context::AgentHandle,
+    id::{IdType, WakeupId},
+    message_queue::ScheduledWakeup,
+    tool::{AiTool, ExecutionMeta},
+};
+
+/// Shortest interval allowed for recurring wakeups, to avoid runaway loops
+const MIN_RECURRING_SECONDS: i64 = 60;
+
+/// Operation types for the schedule tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(inline)]
+pub enum ScheduleOperationType {
+    Create,
+    List,
+    Cancel,
+}
+
+/// Input for managing scheduled wakeups
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ScheduleInput {
+    /// The operation to perform
+    pub operation: ScheduleOperationType,
+
+    /// For create: what the wakeup is for (shown to you when it fires)
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    /// For create: absolute time to wake up, as an RFC 3339 timestamp (e.g. "2025-03-01T09:00:00-05:00")
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at: Option<String>,
+
+    /// For create: wake up after this many seconds from now (alternative to 'at')
+    #[schemars(default, with = "i64")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_seconds: Option<i64>,
+
+    /// For create: repeat every this many seconds after the first wakeup (minimum 60)
+    #[schemars(default, with = "i64")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub every_seconds: Option<i64>,
+
+    /// For cancel: the id of the wakeup to cancel
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+/// Output from schedule operations
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ScheduleOutput {
+    /// Whether the operation was successful
+    pub success: bool,
+
+    /// Message about the operation
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Wakeups created or currently scheduled
+    #[schemars(default)]
+    pub wakeups: Vec<WakeupSummary>,
+}
+
+/// Summary of a scheduled wakeup as shown to the agent
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct WakeupSummary {
+    /// Wakeup identifier (use with cancel)
+    pub id: String,
+    /// What the wakeup is for
+    pub reason: String,
+    /// When it will next fire
+    pub scheduled_for: DateTime<Utc>,
+    /// Repeat interval in seconds, if recurring
+    #[schemars(default, with = "i64")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub every_seconds: Option<i64>,
+}
+
+impl From<&ScheduledWakeup> for WakeupSummary {
+    fn from(wakeup: &ScheduledWakeup) -> Self {
+        Self {
+            id: wakeup.id.to_key(),
+            reason: wakeup.reason.clone(),
+            scheduled_for: wakeup.scheduled_for,
+            every_seconds: wakeup.recurring_seconds,
+        }
+    }
+}
+
+/// Tool for scheduling one-off and recurring wakeups
+#[derive(Debug, Clone)]
+pub struct ScheduleTool {
+    pub(crate) handle: AgentHandle,
+}
+
+impl ScheduleTool {
+    /// Create a new schedule tool
+    pub fn new(handle: AgentHandle) -> Self {
+        Self { handle }
+    }
+}
+
+#[async_trait]
+impl AiTool for ScheduleTool {
+    type Input = ScheduleInput;
+    type Output = ScheduleOutput;
+
+    fn name(&self) -> &str {
+        "schedule"
+    }
+
+    fn description(&self) -> &str {
+        "Schedule wakeups for yourself. When a wakeup is due you will receive a system message with its reason. Operations: create, list, cancel.
+ - 'create' schedules a wakeup at an absolute time ('at', RFC 3339) or after 'delay_seconds'; add 'every_seconds' to make it recurring
+ - 'list' shows your upcoming wakeups
+ - 'cancel' removes the wakeup with the given 'id'"
+    }
+
+    async fn execute(&self, params: Self::Input, _meta: &ExecutionMeta) -> Result<Self::Output> {
+        if !self.handle.has_db_connection() {
+            return Ok(ScheduleOutput {
+                success: false,
+                message: Some("Scheduling requires a database connection".to_string()),
+                wakeups: vec![],
+            });
+        }
+
+        match params.operation {
+            ScheduleOperationType::Create => self.execute_create(params).await,
+            ScheduleOperationType::List => self.execute_list().await,
+            ScheduleOperationType::Cancel => {
+                let id = params.id.ok_or_else(|| {
+                    CoreError::tool_exec_msg(
+                        "schedule",
+                        serde_json::json!({"operation":"cancel"}),
+                        "cancel operation requires 'id' field",
+                    )
+                })?;
+                self.execute_cancel(id).await
+            }
+        }
+    }
+
+    fn usage_rule(&self) -> Option<&'static str> {
+        Some("the conversation will be continued when called")
+    }
+
+    fn examples(&self) -> Vec<crate::tool::ToolExample<Self::Input, Self::Output>> {
+        vec![
+            crate::tool::ToolExample {
+                description: "Remind yourself to check in about medication at 9am".to_string(),
+                parameters: ScheduleInput {
+                    operation: ScheduleOperationType::Create,
+                    reason: Some("Remind partner to take their morning meds".to_string()),
+                    at: Some("2025-03-01T09:00:00-05:00".to_string()),
+                    delay_seconds: None,
+                    every_seconds: Some(86400),
+                    id: None,
+                },
+                expected_output: Some(ScheduleOutput {
+                    success: true,
+                    message: Some("Scheduled recurring wakeup".to_string()),
+                    wakeups: vec![],
+                }),
+            },
+            crate::tool::ToolExample {
+                description: "Check back on a task in 30 minutes".to_string(),
+                parameters: ScheduleInput {
+                    operation: ScheduleOperationType::Create,
+                    reason: Some("Follow up on whether the email draft got sent".to_string()),
+                    at: None,
+                    delay_seconds: Some(1800),
+                    every_seconds: None,
+                    id: None,
+                },
+                expected_output: Some(ScheduleOutput {
+                    success: true,
+                    message: Some("Scheduled wakeup".to_string()),
+                    wakeups: vec![],
+                }),
+            },
+        ]
+    }
+}
+
+impl ScheduleTool {
+    async fn execute_create(&self, params: ScheduleInput) -> Result<ScheduleOutput> {
+        let reason = params.reason.clone().ok_or_else(|| {
+            CoreError::tool_exec_msg(
+                "schedule",
+                serde_json::json!({"operation":"create"}),
+                "create operation requires 'reason' field",
+            )
+        })?;
+
+        if let Some(every) = params.every_seconds {
+            if every < MIN_RECURRING_SECONDS {
+                return Ok(ScheduleOutput {
+                    success: false,
+                    message: Some(format!(
+                        "Recurring wakeups must be at least {} seconds apart",
+                        MIN_RECURRING_SECONDS
+                    )),
+                    wakeups: vec![],
+                });
+            }
+        }
+
+        let now = Utc::now();
+        let scheduled_for = match (&params.at, params.delay_seconds, params.every_seconds) {
+            (Some(at), None, _) => DateTime::parse_from_rfc3339(at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|e| {
+                    CoreError::tool_exec_msg(
+                        "schedule",
+                        serde_json::to_value(&params).unwrap_or_default(),
+                        format!("'at' must be an RFC 3339 timestamp: {}", e),
+                    )
+                })?,
+            (None, Some(delay), _) if delay >= 0 => now + Duration::seconds(delay),
+            (None, Some(_), _) => {
+                return Ok(ScheduleOutput {
+                    success: false,
+                    message: Some("'delay_seconds' cannot be negative".to_string()),
+                    wakeups: vec![],
+                });
+            }
+            // Recurring with no explicit start: first fire after one interval
+            (None, None, Some(every)) => now + Duration::seconds(every),
+            (Some(_), Some(_), _) => {
+                return Ok(ScheduleOutput {
+                    success: false,
+                    message: Some("Provide either 'at' or 'delay_seconds', not both".to_string()),
+                    wakeups: vec![],
+                });
+            }
+            (None, None, None) => {
+                return Ok(ScheduleOutput {
+                    success: false,
+                    message: Some(
+                        "create requires 'at', 'delay_seconds' or 'every_seconds'".to_string(),
+                    ),
+                    wakeups: vec![],
+                });
+            }
+        };
+
+        if scheduled_for < now && params.every_seconds.is_none() {
+            return Ok(ScheduleOutput {
+                success: false,
+                message: Some(format!(
+                    "{} is in the past (current time is {})",
+                    scheduled_for.to_rfc3339(),
+                    now.to_rfc3339()
+                )),
+                wakeups: vec![],
+            });
+        }
+
+        let wakeup = match params.every_seconds {
+            Some(every) => ScheduledWakeup::recurring(
+                self.handle.agent_id.clone(),
+                scheduled_for,
+                reason,
+                every,
+            ),
+            None => ScheduledWakeup::once(self.handle.agent_id.clone(), scheduled_for, reason),
+        };
+
+        let created = self.handle.schedule_wakeup(wakeup).await?;
+
+        tracing::info!(
+            "Agent {} scheduled wakeup {} for {}",
+            self.handle.agent_id,
+            created.id,
+            created.scheduled_for
+        );
+
+        let message = if created.recurring_seconds.is_some() {
+            format!(
+                "Scheduled recurring wakeup starting {}",
+                created.scheduled_for.to_rfc3339()
+            )
+        } else {
+            format!(
+                "Scheduled wakeup for {}",
+                created.scheduled_for.to_rfc3339()
+            )
+        };
+
+        Ok(ScheduleOutput {
+            success: true,
+            message: Some(message),
+            wakeups: vec![WakeupSummary::from(&created)],
+        })
+    }
+
+    async fn execute_list(&self) -> Result<ScheduleOutput> {
+        let wakeups = self.handle.list_wakeups().await?;
+
+        Ok(ScheduleOutput {
+            success: true,
+            message: Some(format!("{} scheduled wakeup(s)", wakeups.len())),
+            wakeups: wakeups.iter().map(WakeupSummary::from).collect(),
+        })
+    }
+
+    async fn execute_cancel(&self, id: String) -> Result<ScheduleOutput> {
+        // Accept both the bare key and the "wakeup:<key>" display form
+        let key = id.strip_prefix("wakeup:").unwrap_or(&id);
+        let wakeup_id = WakeupId(key.to_string());
+
+        if self.handle.cancel_wakeup(&wakeup_id).await? {
+            Ok(ScheduleOutput {
+                success: true,
+                message: Some(format!("Cancelled wakeup {}", key)),
+                wakeups: vec![],
+            })
+        } else {
+            Ok(ScheduleOutput {
+                success: false,
+                message: Some(format!("No active wakeup with id {}", key)),
+                wakeups: vec![],
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{UserId, db::client::create_test_db, memory::Memory};
+
+    #[tokio::test]
+    async fn test_schedule_create_list_cancel() {
+        let db = create_test_db().await.unwrap();
+
+        let memory = Memory::with_owner(&UserId::generate());
+        let handle = AgentHandle::test_with_memory(memory).with_db(db);
+        let tool = ScheduleTool::new(handle);
+
+        let created = tool
+            .execute(
+                ScheduleInput {
+                    operation: ScheduleOperationType::Create,
+                    reason: Some("take meds".to_string()),
+                    at: None,
+                    delay_seconds: Some(3600),
+                    every_seconds: None,
+                    id: None,
+                },
+                &ExecutionMeta::default(),
+            )
+            .await
+            .unwrap();
+        assert!(created.success);
+        assert_eq!(created.wakeups.len(), 1);
+        let wakeup_id = created.wakeups[0].id.clone();
+
+        let listed = tool
+            .execute(
+                ScheduleInput {
+                    operation: ScheduleOperationType::List,
+                    reason: None,
+                    at: None,
+                    delay_seconds: None,
+                    every_seconds: None,
+                    id: None,
+                },
+                &ExecutionMeta::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(listed.wakeups.len(), 1);
+        assert_eq!(listed.wakeups[0].reason, "take meds");
+
+        let cancelled = tool
+            .execute(
+                ScheduleInput {
+                    operation: ScheduleOperationType::Cancel,
+                    reason: None,
+                    at: None,
+                    delay_seconds: None,
+                    every_seconds: None,
+                    id: Some(wakeup_id),
+                },
+                &ExecutionMeta::default(),
+            )
+            .await
+            .unwrap();
+        assert!(cancelled.success);
+
+        let listed = tool.execute_list().await.unwrap();
+        assert!(listed.wakeups.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_schedule_rejects_past_and_short_intervals() {
+        let db = create_test_db().await.unwrap();
+
+        let memory = Memory::with_owner(&UserId::generate());
+        let handle = AgentHandle::test_with_memory(memory).with_db(db);
+        let tool = ScheduleTool::new(handle);
+
+        let past = tool
+            .execute(
+                ScheduleInput {
+                    operation: ScheduleOperationType::Create,
+                    reason: Some("too late".to_string()),
+                    at: Some("2001-01-01T00:00:00Z".to_string()),
+                    delay_seconds: None,
+                    every_seconds: None,
+                    id: None,
+                },
+                &ExecutionMeta::default(),
+            )
+            .await
+            .unwrap();
+        assert!(!past.success);
+
+        let spammy = tool
+            .execute(
+                ScheduleInput {
+                    operation: ScheduleOperationType::Create,
+                    reason: Some("ping".to_string()),
+                    at: None,
+                    delay_seconds: None,
+                    every_seconds: Some(5),
+                    id: None,
+                },
+                &ExecutionMeta::default(),
+            )
+            .await
+            .unwrap();
+        assert!(!spammy.success);
+    }
+}
@@ -9,7 +9,7 @@ use serde_json::json;
 use super::search_utils::{extract_snippet, process_search_results};
 use crate::{
     Result,
-    context::AgentHandle,
+    context::{AgentHandle, SearchWeights},
     message::ChatRole,
     tool::{AiTool, ExecutionMeta},
 };
@@ -57,9 +57,30 @@ pub struct SearchInput {
     /// This will enable typo-tolerant search once SurrealDB fuzzy functions are integrated
     #[serde(default)]
     pub fuzzy: bool,
+
+    /// Weight of keyword (BM25) matching when ranking results (default: 1.0, 0 disables)
+    #[schemars(default, with = "f32")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bm25_weight: Option<f32>,
+
+    /// Weight of semantic (embedding) similarity when ranking results (default: 1.0, 0 disables)
+    #[schemars(default, with = "f32")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vector_weight: Option<f32>,
     // request_heartbeat handled via ExecutionMeta injection; field removed
 }
 
+impl SearchInput {
+    /// Ranking weights for archival and conversation search
+    pub fn weights(&self) -> SearchWeights {
+        let defaults = SearchWeights::default();
+        SearchWeights::new(
+            self.bm25_weight.unwrap_or(defaults.bm25),
+            self.vector_weight.unwrap_or(defaults.vector),
+        )
+    }
+}
+
 /// Output from search operations
 #[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct SearchOutput {
@@ -91,10 +112,11 @@ impl AiTool for SearchTool {
     }
 
     fn description(&self) -> &str {
-        "Unified search across different domains (archival_memory, conversations, constellation_messages, all). Returns relevant results ranked by BM25 relevance score. Make regular use of this to ground yourself in past events.
+        "Unified search across different domains (archival_memory, conversations, constellation_messages, all). Returns relevant results ranked by combining keyword (BM25) and semantic similarity. Make regular use of this to ground yourself in past events.
         - Use constellation_messages to search messages from all agents in your constellation.
         - archival_memory domain searches your recall memory.
         - To broaden your search, use a larger limit
+        - Archival and conversation results fuse keyword and semantic rankings; set bm25_weight or vector_weight (default 1.0, 0 disables) to favour exact terms or meaning
         - To narrow your search, you can:
             - use explicit start_time and end_time parameters with rfc3339 datetime parsing
             - filter based on role (user, assistant, tool)
@@ -114,9 +136,11 @@ impl AiTool for SearchTool {
             .map(|l| l.max(1).min(100) as usize)
             .unwrap_or(20);
 
+        let weights = params.weights();
+
         match params.domain {
             SearchDomain::ArchivalMemory => {
-                self.search_archival(&params.query, limit, params.fuzzy)
+                self.search_archival(&params.query, limit, params.fuzzy, weights)
                     .await
             }
             SearchDomain::Conversations => {
@@ -149,6 +173,7 @@ impl AiTool for SearchTool {
                     end_time,
                     limit,
                     params.fuzzy,
+                    weights,
                 )
                 .await
             }
@@ -185,7 +210,10 @@ impl AiTool for SearchTool {
                 )
                 .await
             }
-            SearchDomain::All => self.search_all(&params.query, limit, params.fuzzy).await,
+            SearchDomain::All => {
+                self.search_all(&params.query, limit, params.fuzzy, weights)
+                    .await
+            }
         }
     }
 
@@ -205,6 +233,8 @@ impl AiTool for SearchTool {
                     start_time: None,
                     end_time: None,
                     fuzzy: false,
+                    bm25_weight: None,
+                    vector_weight: None,
                 },
                 expected_output: Some(SearchOutput {
                     success: true,
@@ -227,6 +257,8 @@ impl AiTool for SearchTool {
                     start_time: None,
                     end_time: None,
                     fuzzy: false,
+                    bm25_weight: None,
+                    vector_weight: None,
                 },
                 expected_output: Some(SearchOutput {
                     success: true,
@@ -249,6 +281,7 @@ impl SearchTool {
         query: &str,
         limit: usize,
         fuzzy: bool,
+        weights: SearchWeights,
     ) -> Result<SearchOutput> {
         // Try to use database if available
         if self.handle.has_db_connection() {
@@ -257,7 +290,7 @@ impl SearchTool {
             let fuzzy_level = if fuzzy { Some(1) } else { None };
             match self
                 .handle
-                .search_archival_memories_with_options(query, limit, fuzzy_level)
+                .search_archival_memories_hybrid(query, limit, fuzzy_level, weights)
                 .await
             {
                 Ok(mut scored_blocks) => {
@@ -353,6 +386,7 @@ impl SearchTool {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn search_conversations(
         &self,
         query: &str,
@@ -361,6 +395,7 @@ impl SearchTool {
         end_time: Option<DateTime<Utc>>,
         limit: usize,
         fuzzy: bool,
+        weights: SearchWeights,
     ) -> Result<SearchOutput> {
         // Use database search if available
         if self.handle.has_db_connection() {
@@ -369,13 +404,14 @@ impl SearchTool {
             let fuzzy_level = if fuzzy { Some(1) } else { None };
             match self
                 .handle
-                .search_conversations_with_options(
+                .search_conversations_hybrid(
                     Some(query),
                     role,
                     start_time,
                     end_time,
                     limit,
                     fuzzy_level,
+                    weights,
                 )
                 .await
             {
@@ -517,11 +553,17 @@ impl SearchTool {
         }
     }
 
-    async fn search_all(&self, query: &str, limit: usize, fuzzy: bool) -> Result<SearchOutput> {
+    async fn search_all(
+        &self,
+        query: &str,
+        limit: usize,
+        fuzzy: bool,
+        weights: SearchWeights,
+    ) -> Result<SearchOutput> {
         // Search both domains and combine results
-        let archival_result = self.search_archival(query, limit, fuzzy).await?;
+        let archival_result = self.search_archival(query, limit, fuzzy, weights).await?;
         let conv_result = self
-            .search_conversations(query, None, None, None, limit, fuzzy)
+            .search_conversations(query, None, None, None, limit, fuzzy, weights)
             .await?;
 
         let all_results = json!({
@@ -578,6 +620,8 @@ mod tests {
                     start_time: None,
                     end_time: None,
                     fuzzy: false,
+                    bm25_weight: None,
+                    vector_weight: None,
                 },
                 &crate::tool::ExecutionMeta::default(),
             )
@@ -0,0 +1,292 @@
+//! Skill tool for loading instruction packs into working memory on demand
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Result,
+    context::AgentHandle,
+    memory::{MemoryBlock, MemoryPermission, MemoryType},
+    skill::{Skill, SkillLibrary, block_label},
+    tool::{AiTool, ExecutionMeta, ToolRegistry},
+};
+
+/// Operation types for the skill tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(inline)]
+pub enum SkillOperationType {
+    List,
+    Load,
+    Unload,
+}
+
+/// Input for the skill tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct SkillInput {
+    /// The operation to perform
+    pub operation: SkillOperationType,
+
+    /// For load and unload: name of the skill
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Output from skill operations
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SkillOutput {
+    /// Whether the operation was successful
+    pub success: bool,
+
+    /// Message about the operation
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Skills listed, loaded or unloaded
+    #[schemars(default)]
+    pub skills: Vec<SkillSummary>,
+}
+
+/// Summary of a skill as shown to the agent
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SkillSummary {
+    pub name: String,
+    /// When to use the skill
+    pub description: String,
+    /// Tools the skill relies on
+    #[schemars(default)]
+    pub required_tools: Vec<String>,
+    /// Whether the skill is currently in working memory
+    pub loaded: bool,
+}
+
+/// Tool for discovering skills and pulling them into working memory
+#[derive(Debug, Clone)]
+pub struct SkillTool {
+    pub(crate) handle: AgentHandle,
+    library: SkillLibrary,
+    registry: Option<ToolRegistry>,
+}
+
+impl SkillTool {
+    /// Create a new skill tool over a library of skill directories
+    pub fn new(handle: AgentHandle, library: SkillLibrary) -> Self {
+        Self {
+            handle,
+            library,
+            registry: None,
+        }
+    }
+
+    /// Check loaded skills' required tools against this registry
+    pub fn with_registry(mut self, registry: ToolRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    fn summary(&self, skill: &Skill) -> SkillSummary {
+        SkillSummary {
+            name: skill.name.clone(),
+            description: skill.description.clone(),
+            required_tools: skill.required_tools.clone(),
+            loaded: self.handle.memory.contains_block(&skill.block_label()),
+        }
+    }
+
+    fn failure(message: String) -> SkillOutput {
+        SkillOutput {
+            success: false,
+            message: Some(message),
+            skills: vec![],
+        }
+    }
+
+    async fn execute_list(&self) -> Result<SkillOutput> {
+        let skills = self.library.list().await?;
+        Ok(SkillOutput {
+            success: true,
+            message: Some(format!("{} skill(s) available", skills.len())),
+            skills: skills.iter().map(|s| self.summary(s)).collect(),
+        })
+    }
+
+    async fn execute_load(&self, name: &str) -> Result<SkillOutput> {
+        let Some(skill) = self.library.get(name).await? else {
+            return Ok(Self::failure(format!(
+                "No skill named '{}'. Use 'list' to see available skills",
+                name
+            )));
+        };
+
+        let label = skill.block_label();
+        let block = MemoryBlock::new(label.as_str(), skill.body.clone())
+            .with_description(format!("Skill '{}': {}", skill.name, skill.description))
+            .with_memory_type(MemoryType::Working)
+            .with_permission(MemoryPermission::ReadOnly);
+        self.handle.memory.upsert_block(label.as_str(), block)?;
+
+        let mut message = format!(
+            "Loaded skill '{}' into working memory block '{}'. Unload it when the task is done",
+            skill.name, label
+        );
+        if let Some(registry) = &self.registry {
+            let available = registry.list_tools();
+            let missing: Vec<&str> = skill
+                .required_tools
+                .iter()
+                .filter(|t| !available.iter().any(|a| a.as_str() == t.as_str()))
+                .map(String::as_str)
+                .collect();
+            if !missing.is_empty() {
+                message.push_str(&format!(
+                    ". Note: this skill expects tools you don't have: {}",
+                    missing.join(", ")
+                ));
+            }
+        }
+
+        Ok(SkillOutput {
+            success: true,
+            message: Some(message),
+            skills: vec![self.summary(&skill)],
+        })
+    }
+
+    fn execute_unload(&self, name: &str) -> SkillOutput {
+        let label = block_label(name);
+        if self.handle.memory.remove_block(&label).is_some() {
+            SkillOutput {
+                success: true,
+                message: Some(format!("Unloaded skill '{}'", name)),
+                skills: vec![],
+            }
+        } else {
+            Self::failure(format!("Skill '{}' is not loaded", name))
+        }
+    }
+}
+
+#[async_trait]
+impl AiTool for SkillTool {
+    type Input = SkillInput;
+    type Output = SkillOutput;
+
+    fn name(&self) -> &str {
+        "skill"
+    }
+
+    fn description(&self) -> &str {
+        "Load specialised procedures (skills) into working memory when a task calls for them. Operations: list, load, unload.
+ - 'list' shows available skills with when to use them and the tools they need
+ - 'load' puts the skill 'name' into a working memory block so you can follow it
+ - 'unload' removes skill 'name' from working memory once the task is done"
+    }
+
+    async fn execute(&self, params: Self::Input, _meta: &ExecutionMeta) -> Result<Self::Output> {
+        match (params.operation, params.name.as_deref()) {
+            (SkillOperationType::List, _) => self.execute_list().await,
+            (SkillOperationType::Load, Some(name)) => self.execute_load(name).await,
+            (SkillOperationType::Unload, Some(name)) => Ok(self.execute_unload(name)),
+            (_, None) => Ok(Self::failure(
+                "'name' is required for load and unload".to_string(),
+            )),
+        }
+    }
+
+    fn usage_rule(&self) -> Option<&'static str> {
+        Some("the conversation will be continued when called")
+    }
+
+    fn examples(&self) -> Vec<crate::tool::ToolExample<Self::Input, Self::Output>> {
+        vec![crate::tool::ToolExample {
+            description: "Load the research skill before digging into a question".to_string(),
+            parameters: SkillInput {
+                operation: SkillOperationType::Load,
+                name: Some("researching-on-the-internet".to_string()),
+            },
+            expected_output: Some(SkillOutput {
+                success: true,
+                message: Some(
+                    "Loaded skill 'researching-on-the-internet' into working memory block 'skill_researching-on-the-internet'. Unload it when the task is done"
+                        .to_string(),
+                ),
+                skills: vec![SkillSummary {
+                    name: "researching-on-the-internet".to_string(),
+                    description: "Use when asked for in-depth information on a topic".to_string(),
+                    required_tools: vec!["web".to_string()],
+                    loaded: true,
+                }],
+            }),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{UserId, memory::Memory};
+
+    fn input(operation: SkillOperationType, name: Option<&str>) -> SkillInput {
+        SkillInput {
+            operation,
+            name: name.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_load_and_unload() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("research.md"),
+            "---\nname: research\ndescription: Look things up\ntools: [web]\n---\n# Research\nSearch first.",
+        )
+        .unwrap();
+
+        let memory = Memory::with_owner(&UserId::generate());
+        let handle = AgentHandle::test_with_memory(memory);
+        let tool = SkillTool::new(
+            handle.clone(),
+            SkillLibrary::new(vec![dir.path().to_path_buf()]),
+        )
+        .with_registry(ToolRegistry::new());
+        let meta = ExecutionMeta::default();
+
+        let listed = tool
+            .execute(input(SkillOperationType::List, None), &meta)
+            .await
+            .unwrap();
+        assert_eq!(listed.skills.len(), 1);
+        assert!(!listed.skills[0].loaded);
+
+        let loaded = tool
+            .execute(input(SkillOperationType::Load, Some("research")), &meta)
+            .await
+            .unwrap();
+        assert!(loaded.success);
+        assert!(
+            loaded
+                .message
+                .unwrap()
+                .contains("tools you don't have: web")
+        );
+        let block = handle.memory.get_block("skill_research").unwrap().clone();
+        assert_eq!(block.memory_type, MemoryType::Working);
+        assert_eq!(block.value, "# Research\nSearch first.");
+
+        let unloaded = tool
+            .execute(input(SkillOperationType::Unload, Some("research")), &meta)
+            .await
+            .unwrap();
+        assert!(unloaded.success);
+        assert!(!handle.memory.contains_block("skill_research"));
+
+        let missing = tool
+            .execute(input(SkillOperationType::Load, Some("nope")), &meta)
+            .await
+            .unwrap();
+        assert!(!missing.success);
+    }
+}
@@ -0,0 +1,753 @@
+//! Task tool for agents to track work and break it into smaller steps
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CoreError, Result,
+    context::AgentHandle,
+    db::{BaseTask, BaseTaskPriority, ops::TaskFilter},
+    id::{IdType, TaskId},
+    memory::{MemoryPermission, MemoryType},
+    tool::{AiTool, ExecutionMeta},
+};
+
+/// Label of the core memory block that mirrors an agent's open tasks
+pub const TASKS_BLOCK_LABEL: &str = "open_tasks";
+
+/// Operation types for the task tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(inline)]
+pub enum TaskOperationType {
+    Create,
+    BreakDown,
+    Update,
+    List,
+    Assign,
+    Pin,
+    Unpin,
+}
+
+/// Input for managing tasks
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct TaskInput {
+    /// The operation to perform
+    pub operation: TaskOperationType,
+
+    /// For update, assign and break_down: the task id; for create: optional parent task id
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// For create and update: short title of the task
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// For create and update: longer description or notes
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// For create and update: low, medium, high or critical
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+
+    /// For update: pending, in_progress, completed or cancelled
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    /// For create and update: due date as an RFC 3339 timestamp (empty string clears it)
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+
+    /// For break_down: titles of the subtasks to create, in order
+    #[schemars(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<String>,
+
+    /// For assign: name of the agent to assign to (omit to take it yourself, "none" to unassign).
+    /// For list: "all" lists every task for your user instead of just yours
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+
+    /// For list: include completed and cancelled tasks
+    #[serde(default)]
+    pub include_closed: bool,
+}
+
+/// Output from task operations
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TaskOutput {
+    /// Whether the operation was successful
+    pub success: bool,
+
+    /// Message about the operation
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Tasks created, changed or listed
+    #[schemars(default)]
+    pub tasks: Vec<TaskSummary>,
+}
+
+/// Summary of a task as shown to the agent
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TaskSummary {
+    /// Task identifier (use with update, assign and break_down)
+    pub id: String,
+    pub title: String,
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// pending, in_progress, completed or cancelled
+    pub status: String,
+    /// low, medium, high or critical
+    pub priority: String,
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<DateTime<Utc>>,
+    /// Parent task, if this is a subtask
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    /// Agent the task is assigned to
+    #[schemars(default, with = "String")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assigned_to: Option<String>,
+}
+
+impl From<&BaseTask> for TaskSummary {
+    fn from(task: &BaseTask) -> Self {
+        Self {
+            id: task.id.to_key(),
+            title: task.title.clone(),
+            description: task.description.clone(),
+            status: task.status.to_string(),
+            priority: task.priority.to_string(),
+            due: task.due_date,
+            parent_id: task.parent_task_id.as_ref().map(|id| id.to_key()),
+            assigned_to: task.assigned_agent_id.as_ref().map(|id| id.to_string()),
+        }
+    }
+}
+
+/// Render tasks as an indented checklist, subtasks under their parents
+///
+/// Tasks whose parent isn't in the list are shown at the top level. Order
+/// within each level follows the input order.
+pub fn render_task_list(tasks: &[BaseTask]) -> String {
+    fn render(tasks: &[BaseTask], parent: Option<&TaskId>, depth: usize, out: &mut Vec<String>) {
+        let is_root = |task: &BaseTask| {
+            task.parent_task_id
+                .as_ref()
+                .is_none_or(|p| !tasks.iter().any(|t| &t.id == p))
+        };
+        for task in tasks {
+            let matches = match parent {
+                Some(parent) => task.parent_task_id.as_ref() == Some(parent),
+                None => is_root(task),
+            };
+            if !matches {
+                continue;
+            }
+
+            let mut line = format!(
+                "{}- [{}] {} ({}",
+                "  ".repeat(depth),
+                task.status,
+                task.title,
+                task.priority
+            );
+            if let Some(due) = task.due_date {
+                line.push_str(&format!(", due {}", due.format("%Y-%m-%d %H:%M UTC")));
+            }
+            line.push_str(&format!(") id={}", task.id.to_key()));
+            out.push(line);
+
+            render(tasks, Some(&task.id), depth + 1, out);
+        }
+    }
+
+    let mut lines = Vec::new();
+    render(tasks, None, 0, &mut lines);
+    lines.join("\n")
+}
+
+/// Tool for creating, breaking down and tracking tasks
+#[derive(Debug, Clone)]
+pub struct TaskTool {
+    pub(crate) handle: AgentHandle,
+}
+
+impl TaskTool {
+    /// Create a new task tool
+    pub fn new(handle: AgentHandle) -> Self {
+        Self { handle }
+    }
+}
+
+#[async_trait]
+impl AiTool for TaskTool {
+    type Input = TaskInput;
+    type Output = TaskOutput;
+
+    fn name(&self) -> &str {
+        "task"
+    }
+
+    fn description(&self) -> &str {
+        "Track tasks and break them into atomic steps. Operations: create, break_down, update, list, assign, pin, unpin.
+ - 'create' adds a task with 'title' (plus optional 'description', 'priority', 'due', and 'id' of a parent task); it is assigned to you
+ - 'break_down' adds 'subtasks' (a list of titles) under the task 'id'
+ - 'update' changes the 'status', 'priority', 'due', 'title' or 'description' of task 'id'
+ - 'list' shows your open tasks, soonest due and highest priority first; set 'include_closed' for finished ones, or 'agent' to \"all\" for everyone's
+ - 'assign' hands task 'id' to the agent named in 'agent'
+ - 'pin' keeps your open tasks in a core memory block so they stay in view; 'unpin' removes it"
+    }
+
+    async fn execute(&self, params: Self::Input, _meta: &ExecutionMeta) -> Result<Self::Output> {
+        if !self.handle.has_db_connection() {
+            return Ok(TaskOutput {
+                success: false,
+                message: Some("Tasks require a database connection".to_string()),
+                tasks: vec![],
+            });
+        }
+
+        let output = match params.operation {
+            TaskOperationType::Create => self.execute_create(params).await?,
+            TaskOperationType::BreakDown => self.execute_break_down(params).await?,
+            TaskOperationType::Update => self.execute_update(params).await?,
+            TaskOperationType::List => self.execute_list(params).await?,
+            TaskOperationType::Assign => self.execute_assign(params).await?,
+            TaskOperationType::Pin => return self.execute_pin().await,
+            TaskOperationType::Unpin => {
+                let removed = self.handle.memory.remove_block(TASKS_BLOCK_LABEL).is_some();
+                return Ok(TaskOutput {
+                    success: true,
+                    message: Some(if removed {
+                        "Open tasks removed from core memory".to_string()
+                    } else {
+                        "Open tasks were not pinned".to_string()
+                    }),
+                    tasks: vec![],
+                });
+            }
+        };
+
+        // Keep the pinned block current after any change
+        if output.success && self.handle.memory.contains_block(TASKS_BLOCK_LABEL) {
+            self.refresh_pinned_tasks().await?;
+        }
+        Ok(output)
+    }
+
+    fn usage_rule(&self) -> Option<&'static str> {
+        Some("the conversation will be continued when called")
+    }
+
+    fn examples(&self) -> Vec<crate::tool::ToolExample<Self::Input, Self::Output>> {
+        vec![
+            crate::tool::ToolExample {
+                description: "Break a daunting chore into atomic steps".to_string(),
+                parameters: TaskInput {
+                    operation: TaskOperationType::BreakDown,
+                    id: Some("3f1c2a".to_string()),
+                    title: None,
+                    description: None,
+                    priority: None,
+                    status: None,
+                    due: None,
+                    subtasks: vec![
+                        "Find the email address".to_string(),
+                        "Write two sentences".to_string(),
+                        "Press send".to_string(),
+                    ],
+                    agent: None,
+                    include_closed: false,
+                },
+                expected_output: Some(TaskOutput {
+                    success: true,
+                    message: Some("Added 3 subtasks to 'Send the email'".to_string()),
+                    tasks: vec![],
+                }),
+            },
+            crate::tool::ToolExample {
+                description: "Mark a task as done".to_string(),
+                parameters: TaskInput {
+                    operation: TaskOperationType::Update,
+                    id: Some("3f1c2a".to_string()),
+                    title: None,
+                    description: None,
+                    priority: None,
+                    status: Some("completed".to_string()),
+                    due: None,
+                    subtasks: vec![],
+                    agent: None,
+                    include_closed: false,
+                },
+                expected_output: Some(TaskOutput {
+                    success: true,
+                    message: Some("Updated task 'Send the email'".to_string()),
+                    tasks: vec![],
+                }),
+            },
+        ]
+    }
+}
+
+impl TaskTool {
+    async fn execute_create(&self, params: TaskInput) -> Result<TaskOutput> {
+        let title = match params.title.as_deref().map(str::trim) {
+            Some(title) if !title.is_empty() => title.to_string(),
+            _ => {
+                return Err(CoreError::tool_exec_msg(
+                    "task",
+                    serde_json::json!({"operation":"create"}),
+                    "create operation requires 'title' field",
+                ));
+            }
+        };
+
+        let parent = match &params.id {
+            Some(id) => match self.load_task(id).await? {
+                Some(parent) => Some(parent),
+                None => return Ok(not_found(id)),
+            },
+            None => None,
+        };
+
+        let due_date = match params.due.as_deref().map(parse_due).transpose()? {
+            Some(due) => due,
+            None => parent.as_ref().and_then(|p| p.due_date),
+        };
+
+        let priority = params.priority.as_deref().map(parse_priority).transpose()?;
+
+        let task = BaseTask {
+            title,
+            description: params.description.clone(),
+            priority: priority
+                .or(parent.as_ref().map(|p| p.priority))
+                .unwrap_or(BaseTaskPriority::Medium),
+            due_date,
+            creator_id: self.handle.memory.owner_id.clone(),
+            assigned_agent_id: Some(self.handle.agent_id.clone()),
+            parent_task_id: parent.as_ref().map(|p| p.id.clone()),
+            ..Default::default()
+        };
+        let created = self.handle.create_task(task).await?;
+
+        Ok(TaskOutput {
+            success: true,
+            message: Some(format!("Created task '{}'", created.title)),
+            tasks: vec![TaskSummary::from(&created)],
+        })
+    }
+
+    async fn execute_break_down(&self, params: TaskInput) -> Result<TaskOutput> {
+        let id = params.id.as_deref().ok_or_else(|| {
+            CoreError::tool_exec_msg(
+                "task",
+                serde_json::json!({"operation":"break_down"}),
+                "break_down operation requires 'id' field",
+            )
+        })?;
+        let titles: Vec<&str> = params
+            .subtasks
+            .iter()
+            .map(|t| t.trim())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if titles.is_empty() {
+            return Err(CoreError::tool_exec_msg(
+                "task",
+                serde_json::json!({"operation":"break_down", "id": id}),
+                "break_down operation requires a non-empty 'subtasks' list",
+            ));
+        }
+
+        let Some(parent) = self.load_task(id).await? else {
+            return Ok(not_found(id));
+        };
+
+        let mut created = Vec::with_capacity(titles.len());
+        for title in titles {
+            let subtask = BaseTask {
+                title: title.to_string(),
+                priority: parent.priority,
+                due_date: parent.due_date,
+                creator_id: self.handle.memory.owner_id.clone(),
+                assigned_agent_id: parent
+                    .assigned_agent_id
+                    .clone()
+                    .or_else(|| Some(self.handle.agent_id.clone())),
+                parent_task_id: Some(parent.id.clone()),
+                ..Default::default()
+            };
+            created.push(self.handle.create_task(subtask).await?);
+        }
+
+        Ok(TaskOutput {
+            success: true,
+            message: Some(format!(
+                "Added {} subtasks to '{}'",
+                created.len(),
+                parent.title
+            )),
+            tasks: created.iter().map(TaskSummary::from).collect(),
+        })
+    }
+
+    async fn execute_update(&self, params: TaskInput) -> Result<TaskOutput> {
+        let id = params.id.as_deref().ok_or_else(|| {
+            CoreError::tool_exec_msg(
+                "task",
+                serde_json::json!({"operation":"update"}),
+                "update operation requires 'id' field",
+            )
+        })?;
+        let Some(mut task) = self.load_task(id).await? else {
+            return Ok(not_found(id));
+        };
+
+        if let Some(title) = params.title.as_deref().map(str::trim) {
+            if !title.is_empty() {
+                task.title = title.to_string();
+            }
+        }
+        if let Some(description) = &params.description {
+            task.description = Some(description.clone()).filter(|d| !d.is_empty());
+        }
+        if let Some(priority) = params.priority.as_deref() {
+            task.priority = parse_priority(priority)?;
+        }
+        if let Some(status) = params.status.as_deref() {
+            task.status = status.parse().map_err(|e: String| {
+                CoreError::tool_exec_msg("task", serde_json::json!({ "status": status }), e)
+            })?;
+        }
+        if let Some(due) = params.due.as_deref() {
+            task.due_date = parse_due(due)?;
+        }
+
+        let updated = self.handle.update_task(&task).await?;
+
+        // Finishing the last open subtask is worth pointing out
+        let mut message = format!("Updated task '{}'", updated.title);
+        if !updated.status.is_open() {
+            if let Some(parent_id) = &updated.parent_task_id {
+                let siblings = self
+                    .handle
+                    .list_tasks(&TaskFilter {
+                        parent_task_id: Some(parent_id.clone()),
+                        ..TaskFilter::open()
+                    })
+                    .await?;
+                if siblings.is_empty() {
+                    message.push_str(
+                        ". All subtasks of the parent task are now closed; consider completing it",
+                    );
+                }
+            }
+        }
+
+        Ok(TaskOutput {
+            success: true,
+            message: Some(message),
+            tasks: vec![TaskSummary::from(&updated)],
+        })
+    }
+
+    async fn execute_list(&self, params: TaskInput) -> Result<TaskOutput> {
+        let everyone = params
+            .agent
+            .as_deref()
+            .is_some_and(|a| a.eq_ignore_ascii_case("all"));
+        let mut filter = if params.include_closed {
+            TaskFilter::default()
+        } else {
+            TaskFilter::open()
+        };
+        filter.creator_id = Some(self.handle.memory.owner_id.clone());
+        if !everyone {
+            filter.assigned_agent_id = Some(self.handle.agent_id.clone());
+        }
+
+        let tasks = self.handle.list_tasks(&filter).await?;
+        Ok(TaskOutput {
+            success: true,
+            message: Some(if tasks.is_empty() {
+                "No tasks".to_string()
+            } else {
+                format!("{} task(s):\n{}", tasks.len(), render_task_list(&tasks))
+            }),
+            tasks: tasks.iter().map(TaskSummary::from).collect(),
+        })
+    }
+
+    async fn execute_assign(&self, params: TaskInput) -> Result<TaskOutput> {
+        let id = params.id.as_deref().ok_or_else(|| {
+            CoreError::tool_exec_msg(
+                "task",
+                serde_json::json!({"operation":"assign"}),
+                "assign operation requires 'id' field",
+            )
+        })?;
+        if self.load_task(id).await?.is_none() {
+            return Ok(not_found(id));
+        }
+
+        let (agent_id, agent_name) = match params.agent.as_deref().map(str::trim) {
+            None | Some("") | Some("me") => {
+                (Some(self.handle.agent_id.clone()), self.handle.name.clone())
+            }
+            Some(name) if name.eq_ignore_ascii_case("none") => (None, "nobody".to_string()),
+            Some(name) => match self.handle.find_agent_id(name).await? {
+                Some(agent_id) => (Some(agent_id), name.to_string()),
+                None => {
+                    return Ok(TaskOutput {
+                        success: false,
+                        message: Some(format!("No agent named '{}'", name)),
+                        tasks: vec![],
+                    });
+                }
+            },
+        };
+
+        let task = self
+            .handle
+            .assign_task(&task_id_from(id), agent_id.as_ref())
+            .await?;
+        Ok(TaskOutput {
+            success: true,
+            message: Some(format!("Assigned '{}' to {}", task.title, agent_name)),
+            tasks: vec![TaskSummary::from(&task)],
+        })
+    }
+
+    async fn execute_pin(&self) -> Result<TaskOutput> {
+        if !self.handle.memory.contains_block(TASKS_BLOCK_LABEL) {
+            self.handle.memory.create_block(TASKS_BLOCK_LABEL, "")?;
+            if let Some(mut block) = self.handle.memory.get_block_mut(TASKS_BLOCK_LABEL) {
+                block.memory_type = MemoryType::Core;
+                block.permission = MemoryPermission::ReadOnly;
+                block.pinned = true;
+                block.description =
+                    Some("Your open tasks, kept up to date by the task tool".to_string());
+            }
+        }
+        let tasks = self.refresh_pinned_tasks().await?;
+
+        Ok(TaskOutput {
+            success: true,
+            message: Some(format!(
+                "Pinned {} open task(s) to core memory block '{}'",
+                tasks.len(),
+                TASKS_BLOCK_LABEL
+            )),
+            tasks: tasks.iter().map(TaskSummary::from).collect(),
+        })
+    }
+
+    /// Rewrite the pinned block from this agent's open tasks
+    async fn refresh_pinned_tasks(&self) -> Result<Vec<BaseTask>> {
+        let tasks = self
+            .handle
+            .list_tasks(&TaskFilter {
+                assigned_agent_id: Some(self.handle.agent_id.clone()),
+                ..TaskFilter::open()
+            })
+            .await?;
+        let value = if tasks.is_empty() {
+            "No open tasks.".to_string()
+        } else {
+            render_task_list(&tasks)
+        };
+        self.handle
+            .memory
+            .update_block_value(TASKS_BLOCK_LABEL, value)?;
+        Ok(tasks)
+    }
+
+    /// Load a task belonging to this agent's user
+    async fn load_task(&self, id: &str) -> Result<Option<BaseTask>> {
+        let task = self.handle.get_task(&task_id_from(id)).await?;
+        Ok(task.filter(|t| t.creator_id == self.handle.memory.owner_id))
+    }
+}
+
+/// Accept both the bare key and the "task:<key>" display form
+fn task_id_from(id: &str) -> TaskId {
+    let id = id.trim();
+    TaskId(id.strip_prefix("task:").unwrap_or(id).to_string())
+}
+
+fn parse_due(due: &str) -> Result<Option<DateTime<Utc>>> {
+    if due.trim().is_empty() {
+        return Ok(None);
+    }
+    DateTime::parse_from_rfc3339(due.trim())
+        .map(|dt| Some(dt.with_timezone(&Utc)))
+        .map_err(|e| {
+            CoreError::tool_exec_msg(
+                "task",
+                serde_json::json!({ "due": due }),
+                format!("'due' must be an RFC 3339 timestamp: {}", e),
+            )
+        })
+}
+
+fn parse_priority(priority: &str) -> Result<BaseTaskPriority> {
+    priority.parse().map_err(|e: String| {
+        CoreError::tool_exec_msg("task", serde_json::json!({ "priority": priority }), e)
+    })
+}
+
+fn not_found(id: &str) -> TaskOutput {
+    TaskOutput {
+        success: false,
+        message: Some(format!("No task with id {}", id)),
+        tasks: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{UserId, db::client::create_test_db, memory::Memory};
+
+    fn input(operation: TaskOperationType) -> TaskInput {
+        TaskInput {
+            operation,
+            id: None,
+            title: None,
+            description: None,
+            priority: None,
+            status: None,
+            due: None,
+            subtasks: vec![],
+            agent: None,
+            include_closed: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_break_down_and_complete() {
+        let db = create_test_db().await.unwrap();
+        let memory = Memory::with_owner(&UserId::generate());
+        let handle = AgentHandle::test_with_memory(memory).with_db(db);
+        let tool = TaskTool::new(handle.clone());
+        let meta = ExecutionMeta::default();
+
+        let created = tool
+            .execute(
+                TaskInput {
+                    title: Some("Clean the kitchen".to_string()),
+                    priority: Some("high".to_string()),
+                    ..input(TaskOperationType::Create)
+                },
+                &meta,
+            )
+            .await
+            .unwrap();
+        assert!(created.success);
+        let parent_id = created.tasks[0].id.clone();
+
+        let pinned = tool
+            .execute(input(TaskOperationType::Pin), &meta)
+            .await
+            .unwrap();
+        assert!(pinned.success);
+
+        let broken = tool
+            .execute(
+                TaskInput {
+                    id: Some(parent_id.clone()),
+                    subtasks: vec!["Clear the counter".to_string(), "Do dishes".to_string()],
+                    ..input(TaskOperationType::BreakDown)
+                },
+                &meta,
+            )
+            .await
+            .unwrap();
+        assert_eq!(broken.tasks.len(), 2);
+        assert!(
+            broken
+                .tasks
+                .iter()
+                .all(|t| t.parent_id.as_deref() == Some(parent_id.as_str())
+                    && t.priority == "high")
+        );
+
+        // The pinned block shows subtasks nested under their parent
+        let block = handle.memory.get_block(TASKS_BLOCK_LABEL).unwrap().clone();
+        assert_eq!(block.memory_type, MemoryType::Core);
+        assert!(block.value.contains("\n  - [pending] Clear the counter"));
+
+        for subtask in &broken.tasks {
+            tool.execute(
+                TaskInput {
+                    id: Some(subtask.id.clone()),
+                    status: Some("completed".to_string()),
+                    ..input(TaskOperationType::Update)
+                },
+                &meta,
+            )
+            .await
+            .unwrap();
+        }
+
+        let listed = tool
+            .execute(input(TaskOperationType::List), &meta)
+            .await
+            .unwrap();
+        assert_eq!(listed.tasks.len(), 1);
+        assert_eq!(listed.tasks[0].title, "Clean the kitchen");
+
+        let all = tool
+            .execute(
+                TaskInput {
+                    include_closed: true,
+                    ..input(TaskOperationType::List)
+                },
+                &meta,
+            )
+            .await
+            .unwrap();
+        assert_eq!(all.tasks.len(), 3);
+    }
+
+    #[test]
+    fn test_render_orphaned_subtasks_at_top_level() {
+        let parent = BaseTask {
+            title: "Parent".to_string(),
+            ..Default::default()
+        };
+        let child = BaseTask {
+            title: "Child".to_string(),
+            parent_task_id: Some(parent.id.clone()),
+            ..Default::default()
+        };
+        let orphan = BaseTask {
+            title: "Orphan".to_string(),
+            parent_task_id: Some(TaskId::generate()),
+            ..Default::default()
+        };
+
+        let rendered = render_task_list(&[parent, child, orphan]);
+        let lines: Vec<_> = rendered.lines().collect();
+        assert!(lines[0].starts_with("- [pending] Parent (medium)"));
+        assert!(lines[1].starts_with("  - [pending] Child"));
+        assert!(lines[2].starts_with("- [pending] Orphan"));
+    }
+}
@@ -32,6 +32,32 @@ mod tests {
         assert!(tool_names.iter().any(|name| name == "context"));
         assert!(tool_names.iter().any(|name| name == "search"));
         assert!(tool_names.iter().any(|name| name == "send_message"));
+        assert!(tool_names.iter().any(|name| name == "schedule"));
+        assert!(tool_names.iter().any(|name| name == "task"));
+        assert!(tool_names.iter().any(|name| name == "calendar"));
+    }
+
+    #[tokio::test]
+    async fn test_builtin_schemas_simplify_for_local_models() {
+        use crate::tool::schema_simplifier::{simplify_for_local, unsupported_constructs};
+
+        let memory = Memory::with_owner(&UserId::generate());
+        let handle = AgentHandle::test_with_memory(memory);
+        let registry = ToolRegistry::new();
+        BuiltinTools::default_for_agent(handle).register_all(&registry);
+
+        for tool in registry.get_all_as_dynamic() {
+            let schema = simplify_for_local(tool.parameters_schema());
+            let unsupported = unsupported_constructs(&schema);
+            assert!(
+                unsupported.is_empty(),
+                "{} schema still has {:?} after simplifying:\n{:#}",
+                tool.name(),
+                unsupported,
+                schema
+            );
+            assert_eq!(schema["type"], "object", "{} parameters", tool.name());
+        }
     }
 
     #[tokio::test]
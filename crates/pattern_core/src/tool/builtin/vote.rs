@@ -0,0 +1,147 @@
+//! Vote tool for agents taking part in a voting group
+//!
+//! The tool itself only validates and acknowledges the ballot. The voting
+//! coordination pattern watches each member's response stream for `vote`
+//! calls and tallies them.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CoreError, Result,
+    tool::{AiTool, ExecutionMeta},
+};
+
+/// Input for casting a vote on an open proposal
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct VoteInput {
+    /// The id of the option you are voting for, exactly as listed in the proposal
+    pub option_id: String,
+
+    /// Why you are voting for this option (shared with the rest of the group)
+    pub reasoning: String,
+
+    /// How confident you are in this choice, from 0.0 to 1.0 (values outside are clamped)
+    #[schemars(default, with = "f32")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+/// Output from casting a vote
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct VoteOutput {
+    /// Whether the vote was accepted
+    pub success: bool,
+
+    /// Message about the vote
+    pub message: String,
+}
+
+/// Tool for casting a ballot in a voting group
+#[derive(Debug, Clone, Default)]
+pub struct VoteTool;
+
+impl VoteTool {
+    /// Create a new vote tool
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl AiTool for VoteTool {
+    type Input = VoteInput;
+    type Output = VoteOutput;
+
+    fn name(&self) -> &str {
+        "vote"
+    }
+
+    fn description(&self) -> &str {
+        "Cast your vote on a proposal put to your group. Use the option id exactly as it was listed, explain your reasoning, and optionally give a confidence between 0.0 and 1.0. Calling vote again before voting closes replaces your earlier vote."
+    }
+
+    async fn execute(&self, params: Self::Input, _meta: &ExecutionMeta) -> Result<Self::Output> {
+        let option_id = params.option_id.trim();
+        if option_id.is_empty() {
+            return Err(CoreError::tool_exec_msg(
+                "vote",
+                serde_json::to_value(&params).unwrap_or_default(),
+                "'option_id' cannot be empty",
+            ));
+        }
+
+        // The voting pattern tallies the same clamped value
+        let message = match params.confidence {
+            Some(confidence) if !(0.0..=1.0).contains(&confidence) => format!(
+                "Vote for '{}' recorded with confidence {} (clamped from {})",
+                option_id,
+                confidence.clamp(0.0, 1.0),
+                confidence
+            ),
+            _ => format!("Vote for '{}' recorded", option_id),
+        };
+
+        Ok(VoteOutput {
+            success: true,
+            message,
+        })
+    }
+
+    fn usage_rule(&self) -> Option<&'static str> {
+        Some("the conversation will end when called")
+    }
+
+    fn examples(&self) -> Vec<crate::tool::ToolExample<Self::Input, Self::Output>> {
+        vec![crate::tool::ToolExample {
+            description: "Vote to approve a proposal".to_string(),
+            parameters: VoteInput {
+                option_id: "approve".to_string(),
+                reasoning: "Moving the standup later gives everyone time to settle in".to_string(),
+                confidence: Some(0.8),
+            },
+            expected_output: Some(VoteOutput {
+                success: true,
+                message: "Vote for 'approve' recorded".to_string(),
+            }),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_vote_clamps_confidence() {
+        let tool = VoteTool::new();
+
+        let ok = tool
+            .execute(
+                VoteInput {
+                    option_id: "approve".to_string(),
+                    reasoning: "sounds good".to_string(),
+                    confidence: Some(0.9),
+                },
+                &ExecutionMeta::default(),
+            )
+            .await
+            .unwrap();
+        assert!(ok.success);
+
+        let clamped = tool
+            .execute(
+                VoteInput {
+                    option_id: "approve".to_string(),
+                    reasoning: "very sure".to_string(),
+                    confidence: Some(4.0),
+                },
+                &ExecutionMeta::default(),
+            )
+            .await
+            .unwrap();
+        assert!(clamped.success);
+        assert!(clamped.message.contains("confidence 1 "));
+    }
+}
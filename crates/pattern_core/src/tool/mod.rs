@@ -1,5 +1,7 @@
 pub mod builtin;
 mod mod_utils;
+pub mod schema_simplifier;
+pub mod stats;
 
 use async_trait::async_trait;
 use compact_str::{CompactString, ToCompactString};
@@ -277,6 +279,11 @@ impl ToolRegistry {
         self.tools.insert(tool.name().to_compact_string(), tool);
     }
 
+    /// Remove a tool by name, returning whether it was registered
+    pub fn unregister(&self, name: &str) -> bool {
+        self.tools.remove(name).is_some()
+    }
+
     /// Get a tool by name
     pub fn get(
         &self,
@@ -1,33 +1,35 @@
-//! Schema simplifier for Gemini compatibility
+//! Schema simplifier for Gemini and local model compatibility
 //!
-//! Gemini's function calling API only supports a subset of JSON Schema.
-//! This module provides utilities to convert complex schemas to Gemini-compatible ones.
+//! Gemini's function calling API only supports a subset of JSON Schema, and
+//! local servers (Ollama, llama.cpp, vLLM) turn tool schemas into grammars or
+//! prompt text that cope badly with unions. This module provides utilities to
+//! convert complex schemas to ones these providers accept.
 
-use serde_json::{json, Value};
+use serde_json::{Value, json};
 
 /// Simplify a JSON Schema for Gemini compatibility
 pub fn simplify_for_gemini(schema: Value) -> Value {
     match schema {
-        Value::Object(mut obj) => {
-            let mut simplified = obj.clone();
-            
+        Value::Object(obj) => {
+            let mut simplified = Value::Object(obj.clone());
+
             // Simplify type if it's an array (nullable)
             if let Some(v) = simplified.get_mut("type") {
                 *v = simplify_type(v.clone());
             }
-            
+
             // Handle properties recursively
             if let Some(Value::Object(props)) = simplified.get_mut("properties") {
-                for (_key, value) in props.iter_mut() {
+                for value in props.values_mut() {
                     *value = simplify_for_gemini(value.clone());
                 }
             }
-            
+
             // Handle items recursively
             if let Some(v) = simplified.get_mut("items") {
                 *v = simplify_for_gemini(v.clone());
             }
-            
+
             // Handle oneOf by converting to a simpler structure
             if let Some(Value::Array(_one_of)) = obj.get("oneOf") {
                 // For MessageTarget, we'll use a simpler approach
@@ -55,19 +57,270 @@ pub fn simplify_for_gemini(schema: Value) -> Value {
                     },
                     "required": ["type"]
                 });
-                
+
                 // Copy description from original if it exists
                 if let Some(desc) = obj.get("description") {
                     simplified["description"] = desc.clone();
                 }
             }
-            
-            Value::Object(simplified.as_object().unwrap().clone())
+
+            simplified
         }
         other => other,
     }
 }
 
+/// Simplify a JSON Schema for local OpenAI-compatible servers
+///
+/// Unions are collapsed: nullable variants are unwrapped, unions of string
+/// constants become a single `enum`, and unions of objects (tagged enums)
+/// are merged into one object whose tag lists every variant. Only fields
+/// present in every variant stay required.
+pub fn simplify_for_local(schema: Value) -> Value {
+    let Value::Object(mut obj) = schema else {
+        return schema;
+    };
+
+    obj.remove("$schema");
+    obj.remove("$defs");
+    obj.remove("definitions");
+
+    if let Some(v) = obj.remove("type") {
+        obj.insert("type".to_string(), simplify_type(v));
+    }
+
+    if let Some(Value::Object(props)) = obj.get_mut("properties") {
+        for value in props.values_mut() {
+            *value = simplify_for_local(value.take());
+        }
+    }
+
+    if let Some(v) = obj.get_mut("items") {
+        *v = simplify_for_local(v.take());
+    }
+
+    if let Some(v) = obj.get_mut("additionalProperties")
+        && v.is_object()
+    {
+        *v = simplify_for_local(v.take());
+    }
+
+    for key in ["anyOf", "oneOf", "allOf"] {
+        let Some(Value::Array(variants)) = obj.remove(key) else {
+            continue;
+        };
+        let variants: Vec<Value> = variants
+            .into_iter()
+            .map(simplify_for_local)
+            .filter(|v| !is_null_schema(v))
+            .collect();
+        let merged = if key == "allOf" {
+            merge_all_of(variants)
+        } else {
+            merge_union(variants)
+        };
+        // The outer schema's own keywords (description, default) win
+        if let Value::Object(merged) = merged {
+            for (k, v) in merged {
+                obj.entry(k).or_insert(v);
+            }
+        }
+    }
+
+    Value::Object(obj)
+}
+
+/// List the places in a schema that local servers can't handle
+///
+/// Returns JSON pointers to each `anyOf`, `oneOf`, `allOf`, `$ref` or
+/// multi-type `type`; an empty list means the schema is safe to send.
+pub fn unsupported_constructs(schema: &Value) -> Vec<String> {
+    let mut found = Vec::new();
+    collect_unsupported(schema, "", &mut found);
+    found
+}
+
+fn collect_unsupported(schema: &Value, path: &str, found: &mut Vec<String>) {
+    let Value::Object(obj) = schema else {
+        return;
+    };
+    for key in ["anyOf", "oneOf", "allOf", "$ref"] {
+        if obj.contains_key(key) {
+            found.push(format!("{}/{}", path, key));
+        }
+    }
+    if let Some(Value::Array(_)) = obj.get("type") {
+        found.push(format!("{}/type", path));
+    }
+    if let Some(Value::Object(props)) = obj.get("properties") {
+        for (name, value) in props {
+            collect_unsupported(value, &format!("{}/properties/{}", path, name), found);
+        }
+    }
+    if let Some(items) = obj.get("items") {
+        collect_unsupported(items, &format!("{}/items", path), found);
+    }
+    if let Some(extra) = obj.get("additionalProperties") {
+        collect_unsupported(extra, &format!("{}/additionalProperties", path), found);
+    }
+}
+
+fn is_null_schema(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("null")
+        || schema.get("const").is_some_and(Value::is_null)
+}
+
+/// String constants a variant accepts, if it only accepts string constants
+fn string_constants(schema: &Value) -> Option<Vec<Value>> {
+    if let Some(c @ Value::String(_)) = schema.get("const") {
+        return Some(vec![c.clone()]);
+    }
+    match schema.get("enum") {
+        Some(Value::Array(values)) if values.iter().all(Value::is_string) => Some(values.clone()),
+        _ => None,
+    }
+}
+
+fn merge_union(mut variants: Vec<Value>) -> Value {
+    match variants.len() {
+        0 => return json!({}),
+        1 => return variants.remove(0),
+        _ => {}
+    }
+
+    // Unit enum variants: one string enum
+    let constants: Option<Vec<Vec<Value>>> = variants.iter().map(string_constants).collect();
+    if let Some(constants) = constants {
+        let mut values: Vec<Value> = Vec::new();
+        for value in constants.into_iter().flatten() {
+            if !values.contains(&value) {
+                values.push(value);
+            }
+        }
+        let mut merged = json!({ "type": "string", "enum": values });
+        let descriptions = variant_descriptions(&variants);
+        if !descriptions.is_empty() {
+            merged["description"] = json!(descriptions.join("; "));
+        }
+        return merged;
+    }
+
+    // Tagged enum variants: one object with every variant's fields
+    if variants
+        .iter()
+        .all(|v| v.get("type").and_then(Value::as_str) == Some("object"))
+    {
+        return merge_objects(variants, true);
+    }
+
+    // Anything else can't be expressed without a union, so keep the first
+    variants.remove(0)
+}
+
+fn merge_all_of(variants: Vec<Value>) -> Value {
+    if variants.is_empty() {
+        return json!({});
+    }
+    merge_objects(variants, false)
+}
+
+/// Merge object schemas
+///
+/// With `union` set, properties shared by every variant are combined (string
+/// constants across variants become one enum) and only fields required by
+/// every variant stay required. Otherwise every field required anywhere is
+/// required.
+fn merge_objects(variants: Vec<Value>, union: bool) -> Value {
+    let count = variants.len();
+    let mut properties = serde_json::Map::new();
+    let mut required_counts: Vec<(String, usize)> = Vec::new();
+    let mut descriptions = Vec::new();
+
+    for variant in variants {
+        let Value::Object(mut variant) = variant else {
+            continue;
+        };
+        if let Some(Value::String(desc)) = variant.remove("description") {
+            descriptions.push(desc);
+        }
+        if let Some(Value::Object(props)) = variant.remove("properties") {
+            for (name, schema) in props {
+                match properties.get_mut(&name) {
+                    Some(existing) => {
+                        if let (Some(mut a), Some(b)) =
+                            (string_constants(existing), string_constants(&schema))
+                        {
+                            for value in b {
+                                if !a.contains(&value) {
+                                    a.push(value);
+                                }
+                            }
+                            existing["enum"] = Value::Array(a);
+                            existing["type"] = json!("string");
+                            if let Value::Object(existing) = existing {
+                                existing.remove("const");
+                            }
+                        }
+                    }
+                    None => {
+                        properties.insert(name, schema);
+                    }
+                }
+            }
+        }
+        if let Some(Value::Array(required)) = variant.remove("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                match required_counts.iter_mut().find(|(n, _)| n == name) {
+                    Some((_, n)) => *n += 1,
+                    None => required_counts.push((name.to_string(), 1)),
+                }
+            }
+        }
+    }
+
+    // A tag property that is a single constant is just a one-value enum
+    for schema in properties.values_mut() {
+        if let Some(Value::String(c)) = schema.get("const").cloned()
+            && let Value::Object(obj) = schema
+        {
+            obj.remove("const");
+            obj.insert("type".to_string(), json!("string"));
+            obj.insert("enum".to_string(), json!([c]));
+        }
+    }
+
+    let required: Vec<String> = required_counts
+        .into_iter()
+        .filter(|(_, n)| !union || *n == count)
+        .map(|(name, _)| name)
+        .collect();
+
+    let mut merged = json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    });
+    if union && !descriptions.is_empty() {
+        merged["description"] = json!(descriptions.join("; "));
+    }
+    merged
+}
+
+fn variant_descriptions(variants: &[Value]) -> Vec<String> {
+    variants
+        .iter()
+        .filter_map(|v| {
+            let desc = v.get("description")?.as_str()?;
+            let name = string_constants(v)
+                .and_then(|c| c.first().and_then(|c| c.as_str().map(str::to_string)));
+            Some(match name {
+                Some(name) => format!("{}: {}", name, desc),
+                None => desc.to_string(),
+            })
+        })
+        .collect()
+}
+
 /// Simplify type definitions
 fn simplify_type(type_value: Value) -> Value {
     match type_value {
@@ -86,19 +339,19 @@ fn simplify_type(type_value: Value) -> Value {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[test]
     fn test_simplify_nullable_type() {
         let schema = json!({
             "type": ["string", "null"],
             "description": "A nullable string"
         });
-        
+
         let simplified = simplify_for_gemini(schema);
         assert_eq!(simplified["type"], "string");
         assert_eq!(simplified["description"], "A nullable string");
     }
-    
+
     #[test]
     fn test_simplify_oneof() {
         let schema = json!({
@@ -107,9 +360,96 @@ mod tests {
                 {"type": "object", "properties": {"agent_id": {"type": "string"}}}
             ]
         });
-        
+
         let simplified = simplify_for_gemini(schema);
         assert_eq!(simplified["type"], "object");
         assert!(simplified["properties"].is_object());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_local_unwraps_nullable_any_of() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "limit": {
+                    "description": "Maximum results",
+                    "anyOf": [{"type": "integer", "format": "uint"}, {"type": "null"}]
+                }
+            }
+        });
+
+        let simplified = simplify_for_local(schema);
+        assert_eq!(simplified["properties"]["limit"]["type"], "integer");
+        assert_eq!(
+            simplified["properties"]["limit"]["description"],
+            "Maximum results"
+        );
+        assert!(unsupported_constructs(&simplified).is_empty());
+    }
+
+    #[test]
+    fn test_local_merges_unit_variants_into_enum() {
+        let schema = json!({
+            "oneOf": [
+                {"type": "string", "const": "append", "description": "Add to the end"},
+                {"type": "string", "enum": ["replace", "archive"]}
+            ]
+        });
+
+        let simplified = simplify_for_local(schema);
+        assert_eq!(simplified["type"], "string");
+        assert_eq!(simplified["enum"], json!(["append", "replace", "archive"]));
+        assert!(unsupported_constructs(&simplified).is_empty());
+    }
+
+    #[test]
+    fn test_local_merges_tagged_variants() {
+        let schema = json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": {
+                        "type": {"const": "user"}
+                    },
+                    "required": ["type"]
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "type": {"const": "agent"},
+                        "agent_id": {"type": "string"}
+                    },
+                    "required": ["type", "agent_id"]
+                }
+            ]
+        });
+
+        let simplified = simplify_for_local(schema);
+        assert_eq!(simplified["type"], "object");
+        assert_eq!(
+            simplified["properties"]["type"]["enum"],
+            json!(["user", "agent"])
+        );
+        assert!(simplified["properties"]["agent_id"].is_object());
+        assert_eq!(simplified["required"], json!(["type"]));
+        assert!(unsupported_constructs(&simplified).is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_constructs_reports_paths() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "target": {"oneOf": [{"type": "string"}, {"type": "integer"}]},
+                "name": {"type": ["string", "null"]}
+            }
+        });
+
+        let mut found = unsupported_constructs(&schema);
+        found.sort();
+        assert_eq!(
+            found,
+            vec!["/properties/name/type", "/properties/target/oneOf"]
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Usage analytics over recorded tool executions
+//!
+//! Every tool call an agent makes is stored as a [`ToolCall`] row. This module
+//! folds those rows into per-tool and per-agent counts, error rates, latency
+//! percentiles and the most frequent failure messages.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::{Connection, Surreal};
+
+use crate::Result;
+use crate::db::ops::{self, ToolCallFilter};
+use crate::db::schema::ToolCall;
+use crate::id::AgentId;
+
+/// Failure messages longer than this are cut before grouping
+const MAX_ERROR_LEN: usize = 160;
+
+/// How often one failure message was seen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorCount {
+    pub message: String,
+    pub count: usize,
+}
+
+/// Latency distribution in milliseconds (nearest-rank percentiles)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub p50_ms: i64,
+    pub p90_ms: i64,
+    pub p99_ms: i64,
+    pub max_ms: i64,
+    pub mean_ms: f64,
+}
+
+impl LatencyStats {
+    fn from_durations(mut durations: Vec<i64>) -> Self {
+        if durations.is_empty() {
+            return Self::default();
+        }
+        durations.sort_unstable();
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * durations.len() as f64).ceil() as usize;
+            durations[rank.clamp(1, durations.len()) - 1]
+        };
+        Self {
+            p50_ms: percentile(50.0),
+            p90_ms: percentile(90.0),
+            p99_ms: percentile(99.0),
+            max_ms: *durations.last().unwrap_or(&0),
+            mean_ms: durations.iter().sum::<i64>() as f64 / durations.len() as f64,
+        }
+    }
+}
+
+/// Aggregate numbers for one tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolUsage {
+    pub tool_name: String,
+    pub calls: usize,
+    pub errors: usize,
+    /// Fraction of calls that failed, 0.0 to 1.0
+    pub error_rate: f64,
+    pub latency: LatencyStats,
+    /// Most frequent failure messages, most common first
+    pub top_errors: Vec<ErrorCount>,
+}
+
+/// Aggregate numbers for one agent, broken down by tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentToolUsage {
+    pub agent_id: AgentId,
+    pub calls: usize,
+    pub errors: usize,
+    pub error_rate: f64,
+    pub tools: Vec<ToolUsage>,
+}
+
+/// Tool usage report over a time window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolStats {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub total_calls: usize,
+    pub total_errors: usize,
+    /// Sorted by call count, busiest first
+    pub by_tool: Vec<ToolUsage>,
+    /// Sorted by call count, busiest first
+    pub by_agent: Vec<AgentToolUsage>,
+}
+
+impl ToolStats {
+    /// Fold recorded calls into a report, keeping `top_errors` messages per tool
+    pub fn from_calls(calls: &[ToolCall], top_errors: usize) -> Self {
+        let mut by_agent: HashMap<&AgentId, Vec<&ToolCall>> = HashMap::new();
+        for call in calls {
+            by_agent.entry(&call.agent_id).or_default().push(call);
+        }
+
+        let mut by_agent: Vec<AgentToolUsage> = by_agent
+            .into_iter()
+            .map(|(agent_id, calls)| {
+                let errors = calls.iter().filter(|c| c.error.is_some()).count();
+                AgentToolUsage {
+                    agent_id: agent_id.clone(),
+                    calls: calls.len(),
+                    errors,
+                    error_rate: rate(errors, calls.len()),
+                    tools: usage_by_tool(&calls, top_errors),
+                }
+            })
+            .collect();
+        by_agent.sort_by(|a, b| b.calls.cmp(&a.calls).then(a.agent_id.0.cmp(&b.agent_id.0)));
+
+        let all: Vec<&ToolCall> = calls.iter().collect();
+        Self {
+            since: None,
+            until: None,
+            total_calls: calls.len(),
+            total_errors: calls.iter().filter(|c| c.error.is_some()).count(),
+            by_tool: usage_by_tool(&all, top_errors),
+            by_agent,
+        }
+    }
+
+    /// Fraction of all calls that failed
+    pub fn error_rate(&self) -> f64 {
+        rate(self.total_errors, self.total_calls)
+    }
+}
+
+/// Build a tool usage report from the `tool_call` table
+pub async fn tool_stats<C: Connection>(
+    conn: &Surreal<C>,
+    filter: &ToolCallFilter,
+    top_errors: usize,
+) -> Result<ToolStats> {
+    let calls = ops::list_tool_calls(conn, filter).await?;
+    let mut stats = ToolStats::from_calls(&calls, top_errors);
+    stats.since = filter.since;
+    stats.until = filter.until;
+    Ok(stats)
+}
+
+fn usage_by_tool(calls: &[&ToolCall], top_errors: usize) -> Vec<ToolUsage> {
+    let mut grouped: HashMap<&str, Vec<&ToolCall>> = HashMap::new();
+    for call in calls {
+        grouped.entry(&call.tool_name).or_default().push(*call);
+    }
+
+    let mut usage: Vec<ToolUsage> = grouped
+        .into_iter()
+        .map(|(tool_name, calls)| {
+            let mut error_counts: HashMap<String, usize> = HashMap::new();
+            for error in calls.iter().filter_map(|c| c.error.as_deref()) {
+                *error_counts.entry(normalize_error(error)).or_default() += 1;
+            }
+            let errors = error_counts.values().sum();
+            let mut top: Vec<ErrorCount> = error_counts
+                .into_iter()
+                .map(|(message, count)| ErrorCount { message, count })
+                .collect();
+            top.sort_by(|a, b| b.count.cmp(&a.count).then(a.message.cmp(&b.message)));
+            top.truncate(top_errors);
+
+            ToolUsage {
+                tool_name: tool_name.to_string(),
+                calls: calls.len(),
+                errors,
+                error_rate: rate(errors, calls.len()),
+                latency: LatencyStats::from_durations(
+                    calls.iter().map(|c| c.duration_ms).collect(),
+                ),
+                top_errors: top,
+            }
+        })
+        .collect();
+    usage.sort_by(|a, b| b.calls.cmp(&a.calls).then(a.tool_name.cmp(&b.tool_name)));
+    usage
+}
+
+/// First line of an error, shortened, so near-identical failures group together
+fn normalize_error(error: &str) -> String {
+    let line = error.lines().next().unwrap_or("").trim();
+    if line.chars().count() <= MAX_ERROR_LEN {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(MAX_ERROR_LEN).collect();
+    format!("{}…", cut)
+}
+
+fn rate(errors: usize, calls: usize) -> f64 {
+    if calls == 0 {
+        0.0
+    } else {
+        errors as f64 / calls as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::ToolCallId;
+
+    fn call(agent: &AgentId, tool: &str, duration_ms: i64, error: Option<&str>) -> ToolCall {
+        ToolCall {
+            id: ToolCallId::generate(),
+            agent_id: agent.clone(),
+            tool_name: tool.to_string(),
+            parameters: serde_json::json!({}),
+            result: serde_json::json!({}),
+            error: error.map(str::to_string),
+            duration_ms,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let latency = LatencyStats::from_durations((1..=100).rev().collect());
+        assert_eq!(latency.p50_ms, 50);
+        assert_eq!(latency.p90_ms, 90);
+        assert_eq!(latency.p99_ms, 99);
+        assert_eq!(latency.max_ms, 100);
+        assert_eq!(latency.mean_ms, 50.5);
+
+        assert_eq!(
+            LatencyStats::from_durations(vec![]),
+            LatencyStats::default()
+        );
+    }
+
+    #[test]
+    fn test_from_calls() {
+        let alice = AgentId::generate();
+        let bob = AgentId::generate();
+        let calls = vec![
+            call(&alice, "search", 10, None),
+            call(
+                &alice,
+                "search",
+                30,
+                Some("Tool execution failed: no index\ndetails"),
+            ),
+            call(
+                &alice,
+                "search",
+                20,
+                Some("Tool execution failed: no index"),
+            ),
+            call(&alice, "context", 5, None),
+            call(&bob, "search", 40, Some("timeout")),
+        ];
+
+        let stats = ToolStats::from_calls(&calls, 1);
+        assert_eq!(stats.total_calls, 5);
+        assert_eq!(stats.total_errors, 3);
+
+        let search = &stats.by_tool[0];
+        assert_eq!(search.tool_name, "search");
+        assert_eq!(search.calls, 4);
+        assert_eq!(search.errors, 3);
+        assert_eq!(search.error_rate, 0.75);
+        assert_eq!(search.latency.max_ms, 40);
+        assert_eq!(
+            search.top_errors,
+            vec![ErrorCount {
+                message: "Tool execution failed: no index".to_string(),
+                count: 2,
+            }]
+        );
+
+        assert_eq!(stats.by_agent[0].agent_id, alice);
+        assert_eq!(stats.by_agent[0].calls, 4);
+        assert_eq!(stats.by_agent[0].tools.len(), 2);
+        assert_eq!(stats.by_agent[1].error_rate, 1.0);
+    }
+}
@@ -0,0 +1,668 @@
+//! Token and cost accounting for model calls, with daily budgets
+//!
+//! Every completion an agent makes is stored as a [`ModelUsage`] row tagged
+//! with the group and data source that triggered it, so spend can be reported
+//! by agent, group, day, source or model. Budgets in the main config cap what
+//! an agent may spend per day; once over, the agent is paused until the day
+//! resets or switched to a cheaper model.
+//!
+//! ```toml
+//! [[budgets]]
+//! name = "bluesky"
+//! groups = ["Bluesky"]
+//! daily_usd = 5.0
+//! action = "downgrade"
+//! downgrade_model = "claude-3-5-haiku-20241022"
+//!
+//! [[budgets]]
+//! agents = ["Archive"]
+//! daily_tokens = 2000000
+//! timezone = "Europe/London"
+//! ```
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, Days, NaiveTime, TimeZone, Utc};
+use pattern_macros::Entity;
+use serde::{Deserialize, Serialize};
+
+use crate::id::{AgentId, GroupId, ModelUsageId};
+use crate::message::MessageMetadata;
+use crate::model::ModelInfo;
+use crate::policy::PolicySubject;
+
+/// Token counts for one or more model calls
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenCounts {
+    pub prompt_tokens: u64,
+    /// Includes reasoning tokens
+    pub completion_tokens: u64,
+    pub reasoning_tokens: u64,
+    /// Prompt tokens served from the provider's cache
+    pub cached_tokens: u64,
+}
+
+impl TokenCounts {
+    pub fn from_usage(usage: &genai::chat::Usage) -> Self {
+        let count = |n: Option<i32>| n.unwrap_or(0).max(0) as u64;
+        Self {
+            prompt_tokens: count(usage.prompt_tokens),
+            completion_tokens: count(usage.completion_tokens),
+            reasoning_tokens: count(
+                usage
+                    .completion_tokens_details
+                    .as_ref()
+                    .and_then(|d| d.reasoning_tokens),
+            ),
+            cached_tokens: count(
+                usage
+                    .prompt_tokens_details
+                    .as_ref()
+                    .and_then(|d| d.cached_tokens),
+            ),
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// Cost in USD from the model's per-1k prices, or `None` if it has no prices
+///
+/// Cached prompt tokens are charged at the full prompt price, so this is an
+/// upper bound for providers that discount cache hits.
+pub fn estimate_cost(model: &ModelInfo, tokens: &TokenCounts) -> Option<f64> {
+    if model.cost_per_1k_prompt_tokens.is_none() && model.cost_per_1k_completion_tokens.is_none() {
+        return None;
+    }
+    let prompt = model.cost_per_1k_prompt_tokens.unwrap_or(0.0) * tokens.prompt_tokens as f64;
+    let completion =
+        model.cost_per_1k_completion_tokens.unwrap_or(0.0) * tokens.completion_tokens as f64;
+    Some((prompt + completion) / 1000.0)
+}
+
+/// Where the message that led to a model call came from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsageOrigin {
+    pub group_id: Option<GroupId>,
+    /// Data source ID, or "discord" for Discord messages
+    pub source: Option<String>,
+}
+
+impl UsageOrigin {
+    pub fn from_metadata(metadata: &MessageMetadata) -> Self {
+        let source = metadata
+            .custom
+            .get("source_id")
+            .and_then(|s| s.as_str())
+            .map(str::to_string)
+            .or_else(|| {
+                (metadata.custom.get("discord_channel_id").is_some()
+                    || metadata.channel_id.is_some())
+                .then(|| "discord".to_string())
+            });
+        Self {
+            group_id: metadata.group_id.clone(),
+            source,
+        }
+    }
+}
+
+/// One model call
+#[derive(Debug, Clone, Entity, Serialize, Deserialize)]
+#[entity(entity_type = "model_usage")]
+pub struct ModelUsage {
+    pub id: ModelUsageId,
+    pub agent_id: AgentId,
+    /// Group the triggering message was routed through
+    pub group_id: Option<GroupId>,
+    /// Data source the triggering message came from
+    pub source: Option<String>,
+    pub provider: String,
+    pub model: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub reasoning_tokens: u64,
+    pub cached_tokens: u64,
+    /// `None` when the model has no known prices
+    pub cost_usd: Option<f64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ModelUsage {
+    pub fn new(
+        agent_id: AgentId,
+        model: &ModelInfo,
+        tokens: TokenCounts,
+        origin: &UsageOrigin,
+    ) -> Self {
+        Self {
+            id: ModelUsageId::generate(),
+            agent_id,
+            group_id: origin.group_id.clone(),
+            source: origin.source.clone(),
+            provider: model.provider.clone(),
+            model: model.id.clone(),
+            prompt_tokens: tokens.prompt_tokens,
+            completion_tokens: tokens.completion_tokens,
+            reasoning_tokens: tokens.reasoning_tokens,
+            cached_tokens: tokens.cached_tokens,
+            cost_usd: estimate_cost(model, &tokens),
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn tokens(&self) -> TokenCounts {
+        TokenCounts {
+            prompt_tokens: self.prompt_tokens,
+            completion_tokens: self.completion_tokens,
+            reasoning_tokens: self.reasoning_tokens,
+            cached_tokens: self.cached_tokens,
+        }
+    }
+}
+
+/// Summed usage
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub calls: usize,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub reasoning_tokens: u64,
+    pub cached_tokens: u64,
+    pub cost_usd: f64,
+    /// Calls to models without known prices, not included in `cost_usd`
+    pub unpriced_calls: usize,
+}
+
+impl UsageTotals {
+    pub fn add(&mut self, usage: &ModelUsage) {
+        self.calls += 1;
+        self.prompt_tokens += usage.prompt_tokens;
+        self.completion_tokens += usage.completion_tokens;
+        self.reasoning_tokens += usage.reasoning_tokens;
+        self.cached_tokens += usage.cached_tokens;
+        match usage.cost_usd {
+            Some(cost) => self.cost_usd += cost,
+            None => self.unpriced_calls += 1,
+        }
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+impl<'a> FromIterator<&'a ModelUsage> for UsageTotals {
+    fn from_iter<I: IntoIterator<Item = &'a ModelUsage>>(iter: I) -> Self {
+        let mut totals = Self::default();
+        for usage in iter {
+            totals.add(usage);
+        }
+        totals
+    }
+}
+
+/// What a usage report is broken down by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageGrouping {
+    Agent,
+    Group,
+    /// UTC calendar day
+    Day,
+    Source,
+    Model,
+}
+
+impl UsageGrouping {
+    fn key(&self, usage: &ModelUsage) -> String {
+        match self {
+            Self::Agent => usage.agent_id.to_string(),
+            Self::Group => usage
+                .group_id
+                .as_ref()
+                .map(|g| g.to_string())
+                .unwrap_or_else(|| "(none)".to_string()),
+            Self::Day => usage.created_at.format("%Y-%m-%d").to_string(),
+            Self::Source => usage.source.clone().unwrap_or_else(|| "direct".to_string()),
+            Self::Model => usage.model.clone(),
+        }
+    }
+}
+
+impl FromStr for UsageGrouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "agent" => Ok(Self::Agent),
+            "group" => Ok(Self::Group),
+            "day" => Ok(Self::Day),
+            "source" => Ok(Self::Source),
+            "model" => Ok(Self::Model),
+            other => Err(format!(
+                "unknown grouping '{}', expected agent, group, day, source or model",
+                other
+            )),
+        }
+    }
+}
+
+/// One line of a usage report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRow {
+    /// Agent or group ID, day, source or model, depending on the grouping
+    pub key: String,
+    pub totals: UsageTotals,
+}
+
+/// Usage over a time window, broken down one way
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub grouping: UsageGrouping,
+    pub totals: UsageTotals,
+    /// Days in date order; everything else most expensive first
+    pub rows: Vec<UsageRow>,
+}
+
+impl UsageReport {
+    pub fn from_usage(records: &[ModelUsage], grouping: UsageGrouping) -> Self {
+        let mut grouped: BTreeMap<String, UsageTotals> = BTreeMap::new();
+        for usage in records {
+            grouped.entry(grouping.key(usage)).or_default().add(usage);
+        }
+
+        let mut rows: Vec<UsageRow> = grouped
+            .into_iter()
+            .map(|(key, totals)| UsageRow { key, totals })
+            .collect();
+        if grouping != UsageGrouping::Day {
+            rows.sort_by(|a, b| {
+                b.totals
+                    .cost_usd
+                    .total_cmp(&a.totals.cost_usd)
+                    .then(b.totals.total_tokens().cmp(&a.totals.total_tokens()))
+            });
+        }
+
+        Self {
+            since: None,
+            until: None,
+            grouping,
+            totals: records.iter().collect(),
+            rows,
+        }
+    }
+}
+
+/// Build a usage report from the `model_usage` table
+pub async fn usage_report<C: surrealdb::Connection>(
+    conn: &surrealdb::Surreal<C>,
+    filter: &crate::db::ops::UsageFilter,
+    grouping: UsageGrouping,
+) -> crate::Result<UsageReport> {
+    let records = crate::db::ops::list_model_usage(conn, filter).await?;
+    let mut report = UsageReport::from_usage(&records, grouping);
+    report.since = filter.since;
+    report.until = filter.until;
+    Ok(report)
+}
+
+// ============================================================================
+// Budgets
+// ============================================================================
+
+/// Errors from the `budgets` config section
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+pub enum BudgetError {
+    #[error("Budget {budget} sets neither daily_usd nor daily_tokens")]
+    #[diagnostic(help("Add `daily_usd = ...` or `daily_tokens = ...` to the budget"))]
+    NoLimit { budget: String },
+
+    #[error("Budget {budget} downgrades but has no downgrade_model")]
+    #[diagnostic(help("Add `downgrade_model = \"...\"` or use action = \"pause\""))]
+    NoDowngradeModel { budget: String },
+
+    #[error("Unknown timezone '{value}' in budget {budget}")]
+    #[diagnostic(help("Use an IANA name such as \"Europe/London\""))]
+    InvalidTimezone { budget: String, value: String },
+}
+
+/// What happens to an agent that is over budget
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetAction {
+    /// Stop calling the model until the day resets
+    #[default]
+    Pause,
+    /// Keep going on `downgrade_model`
+    Downgrade,
+}
+
+/// One budget as written in the config
+///
+/// Empty `agents` and `groups` lists match every agent. Each matching agent
+/// gets the full allowance; spend is not pooled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BudgetConfig {
+    /// Name shown in reasons and errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Agent names or IDs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<String>,
+    /// Group names; the agent must belong to at least one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_usd: Option<f64>,
+    /// Prompt plus completion tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_tokens: Option<u64>,
+    #[serde(default)]
+    pub action: BudgetAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub downgrade_model: Option<String>,
+    /// IANA timezone whose midnight starts the budget day (defaults to UTC)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+}
+
+/// What to do about an agent that has spent its allowance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetVerdict {
+    Pause {
+        reason: String,
+        until: DateTime<Utc>,
+    },
+    Downgrade {
+        model: String,
+        reason: String,
+    },
+}
+
+/// A validated budget
+#[derive(Debug, Clone)]
+pub struct Budget {
+    label: String,
+    config: BudgetConfig,
+    timezone: chrono_tz::Tz,
+}
+
+impl Budget {
+    fn compile(index: usize, config: BudgetConfig) -> Result<Self, BudgetError> {
+        let label = config
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("#{}", index + 1));
+        if config.daily_usd.is_none() && config.daily_tokens.is_none() {
+            return Err(BudgetError::NoLimit { budget: label });
+        }
+        if config.action == BudgetAction::Downgrade && config.downgrade_model.is_none() {
+            return Err(BudgetError::NoDowngradeModel { budget: label });
+        }
+        let timezone = match &config.timezone {
+            Some(tz) => tz.parse().map_err(|_| BudgetError::InvalidTimezone {
+                budget: label.clone(),
+                value: tz.clone(),
+            })?,
+            None => chrono_tz::UTC,
+        };
+        Ok(Self {
+            label,
+            config,
+            timezone,
+        })
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Start of the budget day containing `now`
+    pub fn day_start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        self.midnight(now.with_timezone(&self.timezone).date_naive())
+    }
+
+    /// When the budget day containing `now` ends
+    pub fn next_reset(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let today = now.with_timezone(&self.timezone).date_naive();
+        self.midnight(today.checked_add_days(Days::new(1)).unwrap_or(today))
+    }
+
+    fn midnight(&self, date: chrono::NaiveDate) -> DateTime<Utc> {
+        let local = date.and_time(NaiveTime::MIN);
+        // Midnight can be skipped by a DST change; count from the UTC instant then
+        self.timezone
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or_else(|| local.and_utc())
+    }
+
+    /// Verdict for an agent that has spent `spent` so far today, or `None` if under budget
+    pub fn verdict(&self, spent: &UsageTotals, now: DateTime<Utc>) -> Option<BudgetVerdict> {
+        let over_usd = self
+            .config
+            .daily_usd
+            .filter(|&limit| spent.cost_usd >= limit);
+        let over_tokens = self
+            .config
+            .daily_tokens
+            .filter(|&limit| spent.total_tokens() >= limit);
+
+        let reason = match (over_usd, over_tokens) {
+            (Some(limit), _) => format!(
+                "Daily budget {} reached: ${:.2} of ${:.2} spent",
+                self.label, spent.cost_usd, limit
+            ),
+            (None, Some(limit)) => format!(
+                "Daily budget {} reached: {} of {} tokens used",
+                self.label,
+                spent.total_tokens(),
+                limit
+            ),
+            (None, None) => return None,
+        };
+
+        Some(match self.config.action {
+            BudgetAction::Pause => BudgetVerdict::Pause {
+                reason,
+                until: self.next_reset(now),
+            },
+            BudgetAction::Downgrade => BudgetVerdict::Downgrade {
+                model: self.config.downgrade_model.clone().unwrap_or_default(),
+                reason,
+            },
+        })
+    }
+}
+
+/// The configured budgets, checked in order
+#[derive(Debug, Clone, Default)]
+pub struct Budgets {
+    budgets: Vec<Budget>,
+}
+
+impl Budgets {
+    pub fn new(configs: Vec<BudgetConfig>) -> Result<Self, BudgetError> {
+        let budgets = configs
+            .into_iter()
+            .enumerate()
+            .map(|(i, config)| Budget::compile(i, config))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { budgets })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.budgets.is_empty()
+    }
+
+    /// Whether any budget matches on group membership
+    pub fn uses_groups(&self) -> bool {
+        self.budgets.iter().any(|b| !b.config.groups.is_empty())
+    }
+
+    /// The first budget that covers `subject`
+    pub fn budget_for(&self, subject: &PolicySubject) -> Option<&Budget> {
+        self.budgets
+            .iter()
+            .find(|b| subject.matches(&b.config.agents, &b.config.groups))
+    }
+
+    /// The budgets declared in `config`
+    pub fn from_config(config: &crate::config::PatternConfig) -> crate::Result<Self> {
+        Self::new(config.budgets.clone()).map_err(|e| crate::CoreError::ConfigurationError {
+            config_path: "budgets".to_string(),
+            field: "budgets".to_string(),
+            expected: "valid daily budgets".to_string(),
+            cause: crate::error::ConfigError::TomlParse(e.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(prompt: Option<f64>, completion: Option<f64>) -> ModelInfo {
+        ModelInfo {
+            id: "test-model".to_string(),
+            name: "Test".to_string(),
+            provider: "anthropic".to_string(),
+            capabilities: vec![],
+            context_window: 200_000,
+            max_output_tokens: None,
+            cost_per_1k_prompt_tokens: prompt,
+            cost_per_1k_completion_tokens: completion,
+        }
+    }
+
+    fn usage(agent: &AgentId, source: Option<&str>, tokens: u64, cost: Option<f64>) -> ModelUsage {
+        ModelUsage {
+            id: ModelUsageId::generate(),
+            agent_id: agent.clone(),
+            group_id: None,
+            source: source.map(str::to_string),
+            provider: "anthropic".to_string(),
+            model: "test-model".to_string(),
+            prompt_tokens: tokens,
+            completion_tokens: 0,
+            reasoning_tokens: 0,
+            cached_tokens: 0,
+            cost_usd: cost,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_estimate_cost() {
+        let tokens = TokenCounts {
+            prompt_tokens: 2000,
+            completion_tokens: 500,
+            ..Default::default()
+        };
+        let cost = estimate_cost(&model(Some(0.003), Some(0.015)), &tokens).unwrap();
+        assert!((cost - 0.0135).abs() < 1e-9);
+        assert_eq!(estimate_cost(&model(None, None), &tokens), None);
+    }
+
+    #[test]
+    fn test_report_by_source() {
+        let agent = AgentId::generate();
+        let records = vec![
+            usage(&agent, Some("bluesky"), 1000, Some(0.5)),
+            usage(&agent, Some("bluesky"), 1000, Some(0.5)),
+            usage(&agent, None, 4000, None),
+        ];
+
+        let report = UsageReport::from_usage(&records, UsageGrouping::Source);
+        assert_eq!(report.totals.calls, 3);
+        assert_eq!(report.totals.unpriced_calls, 1);
+        assert_eq!(report.rows[0].key, "bluesky");
+        assert_eq!(report.rows[0].totals.cost_usd, 1.0);
+        assert_eq!(report.rows[1].key, "direct");
+        assert_eq!(report.rows[1].totals.prompt_tokens, 4000);
+    }
+
+    #[test]
+    fn test_budget_verdicts() {
+        #[derive(Deserialize)]
+        struct Config {
+            budgets: Vec<BudgetConfig>,
+        }
+        let config: Config = toml::from_str(
+            r#"
+            [[budgets]]
+            name = "bluesky"
+            groups = ["Bluesky"]
+            daily_usd = 1.0
+            action = "downgrade"
+            downgrade_model = "claude-3-5-haiku-20241022"
+
+            [[budgets]]
+            daily_tokens = 5000
+            "#,
+        )
+        .unwrap();
+        let budgets = Budgets::new(config.budgets).unwrap();
+        let now = Utc::now();
+
+        let member = PolicySubject::new(AgentId::generate(), "Entropy")
+            .with_groups(vec!["bluesky".to_string()]);
+        let budget = budgets.budget_for(&member).unwrap();
+        assert_eq!(budget.label(), "bluesky");
+        let spent: UsageTotals = [usage(&member.agent_id, None, 100, Some(1.25))]
+            .iter()
+            .collect();
+        assert!(matches!(
+            budget.verdict(&spent, now),
+            Some(BudgetVerdict::Downgrade { model, .. }) if model == "claude-3-5-haiku-20241022"
+        ));
+
+        let other = PolicySubject::new(AgentId::generate(), "Archive");
+        let budget = budgets.budget_for(&other).unwrap();
+        let under: UsageTotals = [usage(&other.agent_id, None, 4000, None)].iter().collect();
+        assert_eq!(budget.verdict(&under, now), None);
+        let over: UsageTotals = [usage(&other.agent_id, None, 6000, None)].iter().collect();
+        match budget.verdict(&over, now) {
+            Some(BudgetVerdict::Pause { until, .. }) => {
+                assert_eq!(until, budget.day_start(now) + chrono::Duration::days(1))
+            }
+            other => panic!("expected pause, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_budgets() {
+        let no_limit = BudgetConfig {
+            name: None,
+            agents: vec![],
+            groups: vec![],
+            daily_usd: None,
+            daily_tokens: None,
+            action: BudgetAction::Pause,
+            downgrade_model: None,
+            timezone: None,
+        };
+        assert!(matches!(
+            Budgets::new(vec![no_limit.clone()]),
+            Err(BudgetError::NoLimit { .. })
+        ));
+        let downgrade = BudgetConfig {
+            daily_usd: Some(1.0),
+            action: BudgetAction::Downgrade,
+            ..no_limit
+        };
+        assert!(matches!(
+            Budgets::new(vec![downgrade]),
+            Err(BudgetError::NoDowngradeModel { .. })
+        ));
+    }
+}
@@ -671,7 +671,9 @@ impl EventHandler for DiscordEventHandler {
                                         let group_clone = group.clone();
                                         let agents_clone = agents_with_membership.clone();
                                         let manager_clone = group_manager.clone();
-                                        let pattern_msg_clone = pattern_msg.clone();
+                                        let mut pattern_msg_clone = pattern_msg.clone();
+                                        pattern_msg_clone.metadata.group_id =
+                                            Some(group.id.clone());
 
                                         // Clone what we need for the async block
                                         let ctx_clone = ctx.clone();
@@ -820,6 +822,18 @@ impl EventHandler for DiscordEventHandler {
                     }
                     Ok(())
                 }
+                "grants" => {
+                    if let Err(e) = crate::slash_commands::handle_grants(&ctx, &command).await {
+                        warn!("Failed to handle grants: {}", e);
+                    }
+                    Ok(())
+                }
+                "revoke" => {
+                    if let Err(e) = crate::slash_commands::handle_revoke(&ctx, &command).await {
+                        warn!("Failed to handle revoke: {}", e);
+                    }
+                    Ok(())
+                }
                 _ => {
                     warn!("Unknown command: {}", command.data.name);
                     Ok(())
@@ -1017,6 +1031,7 @@ impl DiscordBot {
                 &self.agents_with_membership,
                 &self.group_manager,
             ) {
+                pattern_msg.metadata.group_id = Some(group.id.clone());
                 match group_manager
                     .route_message(group, agents_with_membership, pattern_msg)
                     .await
@@ -1143,7 +1158,8 @@ impl DiscordBot {
                 &self.agents_with_membership,
                 &self.group_manager,
             ) {
-                let pattern_msg = PatternMessage::user(notification);
+                let mut pattern_msg = PatternMessage::user(notification);
+                pattern_msg.metadata.group_id = Some(group.id.clone());
 
                 // Fire and forget - don't wait for response
                 let group_clone = group.clone();
@@ -1547,6 +1563,7 @@ impl DiscordBot {
                 );
 
                 // Route through group manager using the real agents with membership
+                pattern_msg.metadata.group_id = Some(group.id.clone());
                 let response_stream = group_manager
                     .route_message(group, agents_with_membership, pattern_msg)
                     .await
@@ -7,6 +7,7 @@ use pattern_core::{
     agent::AgentRecord,
     coordination::groups::{AgentGroup, AgentWithMembership},
     db::{client::DB, ops},
+    id::{IdType, PermissionGrantId},
 };
 use serenity::{
     builder::{
@@ -114,6 +115,16 @@ pub fn create_commands() -> Vec<CreateCommand> {
         CreateCommand::new("permits")
             .description("List pending permission requests (admin only)")
             .dm_permission(true),
+        CreateCommand::new("grants")
+            .description("List standing permission grants (admin only)")
+            .dm_permission(true),
+        CreateCommand::new("revoke")
+            .description("Revoke a standing permission grant (admin only)")
+            .dm_permission(true)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "id", "Grant ID")
+                    .required(true),
+            ),
         CreateCommand::new("restart")
             .description("Restart the runtime")
             .dm_permission(true),
@@ -350,7 +361,7 @@ pub async fn handle_permit(ctx: &Context, command: &CommandInteraction) -> Resul
     };
 
     let ok = pattern_core::permission::broker()
-        .resolve(id, decision)
+        .resolve_as(id, decision, Some(format!("discord:{}", user_id)))
         .await;
     let content = if ok {
         format!("✅ Approved request {}", id)
@@ -399,7 +410,11 @@ pub async fn handle_deny(ctx: &Context, command: &CommandInteraction) -> Result<
         .unwrap_or("");
 
     let ok = pattern_core::permission::broker()
-        .resolve(id, pattern_core::permission::PermissionDecisionKind::Deny)
+        .resolve_as(
+            id,
+            pattern_core::permission::PermissionDecisionKind::Deny,
+            Some(format!("discord:{}", user_id)),
+        )
         .await;
     let content = if ok {
         format!("🚫 Denied request {}", id)
@@ -467,6 +482,126 @@ pub async fn handle_permits(ctx: &Context, command: &CommandInteraction) -> Resu
 
     Ok(())
 }
+
+pub async fn handle_grants(ctx: &Context, command: &CommandInteraction) -> Result<()> {
+    let user_id = command.user.id.get();
+    if !is_authorized_user(user_id) {
+        command
+            .create_response(
+                &ctx.http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("🚫 Not authorized to view grants.")
+                        .ephemeral(true),
+                ),
+            )
+            .await
+            .ok();
+        return Ok(());
+    }
+
+    let content = match pattern_core::permission::broker()
+        .list_grants(None, false)
+        .await
+    {
+        Ok(grants) if grants.is_empty() => "No standing permission grants.".to_string(),
+        Ok(grants) => {
+            let mut lines = Vec::new();
+            for grant in grants.iter().take(25) {
+                let agent_name = match ops::get_entity::<AgentRecord, _>(&DB, &grant.agent_id).await
+                {
+                    Ok(Some(agent)) => agent.name,
+                    _ => grant.agent_id.to_string(),
+                };
+                let expires = grant
+                    .expires_at
+                    .map(|e| format!("until {}", e.format("%Y-%m-%d %H:%M UTC")))
+                    .unwrap_or_else(|| "no expiry".to_string());
+                lines.push(format!(
+                    "• {} — {} — {} ({})",
+                    grant.id.to_key(),
+                    agent_name,
+                    grant.scope,
+                    expires
+                ));
+            }
+            lines.push("Use /revoke <id> to remove one.".to_string());
+            lines.join("\n")
+        }
+        Err(e) => format!("⚠️ Failed to list grants: {}", e),
+    };
+
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await
+        .ok();
+
+    Ok(())
+}
+
+pub async fn handle_revoke(ctx: &Context, command: &CommandInteraction) -> Result<()> {
+    let user_id = command.user.id.get();
+    if !is_authorized_user(user_id) {
+        command
+            .create_response(
+                &ctx.http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("🚫 Not authorized to revoke grants.")
+                        .ephemeral(true),
+                ),
+            )
+            .await
+            .ok();
+        return Ok(());
+    }
+
+    let id = command
+        .data
+        .options
+        .iter()
+        .find(|o| o.name == "id")
+        .and_then(|o| o.value.as_str())
+        .unwrap_or("");
+    let key = id
+        .trim()
+        .strip_prefix("permission_grant:")
+        .unwrap_or(id.trim());
+
+    let content = match pattern_core::permission::broker()
+        .revoke(
+            &PermissionGrantId(key.to_string()),
+            Some(format!("discord:{}", user_id)),
+        )
+        .await
+    {
+        Ok(Some(grant)) => format!("✅ Revoked grant {} for {}", key, grant.scope),
+        Ok(None) => format!("⚠️ No active grant with id {}", key),
+        Err(e) => format!("⚠️ Failed to revoke grant {}: {}", key, e),
+    };
+
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await
+        .ok();
+
+    Ok(())
+}
+
 // ===== Permission approvals =====
 
 fn is_authorized_user(user_id: u64) -> bool {
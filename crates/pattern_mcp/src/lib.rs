@@ -6,14 +6,16 @@
 
 pub mod client;
 pub mod error;
+pub mod message_agent;
 pub mod registry;
 pub mod server;
 pub mod transport;
 
 pub use error::{McpError, Result};
-pub use registry::{ToolRegistry, ToolRegistryBuilder};
+pub use message_agent::MessageAgentTool;
+pub use registry::{DEFAULT_TOOLS, ToolRegistry, ToolRegistryBuilder};
 pub use server::{McpServer, McpServerBuilder};
-pub use transport::{Transport, TransportType};
+pub use transport::TransportType;
 
 // Client exports
 pub use client::{
@@ -32,9 +34,9 @@ pub mod prelude {
         McpServerBuilder,
         McpServerConfig,
         McpToolWrapper,
+        MessageAgentTool,
         ToolRegistry,
         ToolRegistryBuilder,
-        Transport,
         TransportConfig,
         TransportType,
         // Common types
@@ -0,0 +1,151 @@
+//! Tool letting MCP clients talk to Pattern agents
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use pattern_core::{
+    Agent, CoreError,
+    message::{Message, MessageContent, Response},
+    tool::{AiTool, ExecutionMeta},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Input for messaging an agent
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct MessageAgentInput {
+    /// Name of the agent to message
+    pub agent: String,
+
+    /// The message to send
+    pub message: String,
+}
+
+/// An agent's reply
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct MessageAgentOutput {
+    /// Name of the agent that replied
+    pub agent: String,
+
+    /// What the agent said back
+    pub reply: String,
+}
+
+/// Sends a message to one of a set of agents and waits for the reply
+#[derive(Debug, Clone)]
+pub struct MessageAgentTool {
+    agents: Arc<Vec<Arc<dyn Agent>>>,
+    description: String,
+}
+
+impl MessageAgentTool {
+    pub fn new(agents: Vec<Arc<dyn Agent>>) -> Self {
+        let names: Vec<String> = agents.iter().map(|a| a.name()).collect();
+        let description = format!(
+            "Send a message to a Pattern agent and wait for its reply. Available agents: {}",
+            names.join(", ")
+        );
+
+        Self {
+            agents: Arc::new(agents),
+            description,
+        }
+    }
+}
+
+#[async_trait]
+impl AiTool for MessageAgentTool {
+    type Input = MessageAgentInput;
+    type Output = MessageAgentOutput;
+
+    fn name(&self) -> &str {
+        "message_agent"
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    async fn execute(
+        &self,
+        params: Self::Input,
+        _meta: &ExecutionMeta,
+    ) -> pattern_core::Result<Self::Output> {
+        let agent = self
+            .agents
+            .iter()
+            .find(|a| a.name().eq_ignore_ascii_case(params.agent.trim()))
+            .cloned()
+            .ok_or_else(|| {
+                CoreError::tool_execution_error(
+                    self.name(),
+                    format!(
+                        "No agent named '{}'. Available agents: {}",
+                        params.agent,
+                        self.agents
+                            .iter()
+                            .map(|a| a.name())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                )
+            })?;
+
+        let name = agent.name();
+        let response = agent.process_message(Message::user(params.message)).await?;
+
+        Ok(MessageAgentOutput {
+            agent: name,
+            reply: reply_text(&response),
+        })
+    }
+}
+
+/// Text an agent addressed to the sender
+///
+/// Agents usually answer through `send_message` rather than plain text, so
+/// messages sent to the user count as part of the reply.
+fn reply_text(response: &Response) -> String {
+    let mut parts = Vec::new();
+    for content in &response.content {
+        match content {
+            MessageContent::ToolCalls(calls) => {
+                for call in calls.iter().filter(|c| c.fn_name == "send_message") {
+                    let to_user = call.fn_arguments["target"]["target_type"] == "user";
+                    if let (true, Some(text)) = (to_user, call.fn_arguments["content"].as_str()) {
+                        parts.push(text.to_string());
+                    }
+                }
+            }
+            other => {
+                if let Some(text) = other.text() {
+                    parts.push(text.to_string());
+                }
+            }
+        }
+    }
+    parts.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pattern_core::{coordination::utils::text_response, message::ToolCall};
+
+    #[test]
+    fn test_reply_includes_messages_sent_to_user() {
+        let mut response = text_response("thinking out loud");
+        response
+            .content
+            .push(MessageContent::ToolCalls(vec![ToolCall {
+                call_id: "call_1".to_string(),
+                fn_name: "send_message".to_string(),
+                fn_arguments: serde_json::json!({
+                    "target": { "target_type": "user" },
+                    "content": "hello there",
+                }),
+            }]));
+
+        assert_eq!(reply_text(&response), "thinking out loud\nhello there");
+    }
+}
@@ -1,3 +1,239 @@
+//! Tools published to MCP clients
+//!
+//! Wraps Pattern's own [`pattern_core::tool::ToolRegistry`] so any
+//! `DynamicTool` an agent can use can also be offered over MCP with the same
+//! schema.
+
+use std::{sync::Arc, time::Instant};
+
+use pattern_core::tool::{self as core_tool, DynamicTool, ExecutionMeta};
+use rmcp::model::{JsonObject, Tool};
+use serde_json::Value;
+
+use crate::{McpError, Result};
+
+/// Builtin agent tools worth publishing by default
+pub const DEFAULT_TOOLS: [&str; 4] = ["context", "recall", "search", "send_message"];
+
+/// Parameter Pattern injects into agent tool schemas that MCP clients don't use
+const HEARTBEAT_PARAM: &str = "request_heartbeat";
+
+/// Registry of tools an MCP server publishes
+#[derive(Debug, Clone, Default)]
+pub struct ToolRegistry {
+    tools: core_tool::ToolRegistry,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builder() -> ToolRegistryBuilder {
+        ToolRegistryBuilder::new()
+    }
+
+    /// Publish a tool, replacing any tool with the same name
+    pub fn register(&self, tool: Box<dyn DynamicTool>) {
+        self.tools.register_dynamic(tool);
+    }
+
+    /// Names of all published tools, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .tools
+            .list_tools()
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn len(&self) -> usize {
+        self.tools.list_tools().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// MCP descriptions of all published tools, sorted by name
+    pub fn tools(&self) -> Vec<Tool> {
+        let mut tools: Vec<Tool> = self
+            .tools
+            .get_all_as_dynamic()
+            .iter()
+            .map(|tool| to_mcp_tool(tool.as_ref()))
+            .collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        tools
+    }
+
+    /// Run a published tool
+    pub async fn call(&self, name: &str, params: Value) -> Result<Value> {
+        // Clone the tool out so the registry isn't locked while it runs
+        let tool = self
+            .tools
+            .get(name)
+            .map(|tool| tool.clone_box())
+            .ok_or_else(|| McpError::tool_not_found(name, self.names()))?;
+
+        let started = Instant::now();
+        let result = match tool.validate_params(&params) {
+            Ok(()) => tool.execute(params, &ExecutionMeta::default()).await,
+            Err(e) => Err(e),
+        };
+        result.map_err(|cause| McpError::ToolExecutionFailed {
+            tool_name: name.to_string(),
+            cause,
+            execution_time: started.elapsed(),
+            partial_result: None,
+        })
+    }
+}
+
+/// Builder for a [`ToolRegistry`]
 #[derive(Debug, Default)]
-pub struct ToolRegistry;
-pub struct ToolRegistryBuilder;
+pub struct ToolRegistryBuilder {
+    tools: Vec<Box<dyn DynamicTool>>,
+    only: Option<Vec<String>>,
+}
+
+impl ToolRegistryBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publish every tool in a Pattern registry, such as an agent's tools
+    pub fn with_tools(mut self, registry: &core_tool::ToolRegistry) -> Self {
+        self.tools.extend(registry.get_all_as_dynamic());
+        self
+    }
+
+    pub fn with_tool(mut self, tool: Box<dyn DynamicTool>) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
+    /// Only publish tools with these names
+    pub fn only<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.only = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn build(self) -> ToolRegistry {
+        let registry = ToolRegistry::new();
+        for tool in self.tools {
+            let allowed = self
+                .only
+                .as_ref()
+                .is_none_or(|names| names.iter().any(|n| n == tool.name()));
+            if allowed {
+                registry.register(tool);
+            }
+        }
+        registry
+    }
+}
+
+/// Describe a Pattern tool in MCP terms
+fn to_mcp_tool(tool: &dyn DynamicTool) -> Tool {
+    let mut schema = match tool.parameters_schema() {
+        Value::Object(schema) => schema,
+        _ => JsonObject::new(),
+    };
+    schema
+        .entry("type")
+        .or_insert_with(|| Value::String("object".to_string()));
+    if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+        properties.remove(HEARTBEAT_PARAM);
+    }
+    if let Some(Value::Array(required)) = schema.get_mut("required") {
+        required.retain(|name| name != HEARTBEAT_PARAM);
+    }
+
+    let description = match tool.usage_rule() {
+        Some(rule) => format!("{}\n\n{}", tool.description(), rule),
+        None => tool.description().to_string(),
+    };
+
+    Tool::new(tool.name().to_string(), description, Arc::new(schema))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use pattern_core::tool::{AiTool, DynamicToolAdapter};
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+    struct EchoInput {
+        text: String,
+    }
+
+    #[derive(Debug, Clone)]
+    struct EchoTool(&'static str);
+
+    #[async_trait]
+    impl AiTool for EchoTool {
+        type Input = EchoInput;
+        type Output = String;
+
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "Echo the input"
+        }
+
+        async fn execute(
+            &self,
+            params: Self::Input,
+            _meta: &ExecutionMeta,
+        ) -> pattern_core::Result<Self::Output> {
+            Ok(params.text)
+        }
+    }
+
+    #[test]
+    fn test_builder_filters_and_strips_heartbeat() {
+        let registry = ToolRegistry::builder()
+            .with_tool(Box::new(DynamicToolAdapter::new(EchoTool("search"))))
+            .with_tool(Box::new(DynamicToolAdapter::new(EchoTool("shell"))))
+            .only(DEFAULT_TOOLS)
+            .build();
+
+        assert_eq!(registry.names(), vec!["search".to_string()]);
+
+        let tools = registry.tools();
+        let properties = tools[0].input_schema["properties"].as_object().unwrap();
+        assert!(properties.contains_key("text"));
+        assert!(!properties.contains_key(HEARTBEAT_PARAM));
+    }
+
+    #[tokio::test]
+    async fn test_call_reports_unknown_tools() {
+        let registry = ToolRegistry::builder()
+            .with_tool(Box::new(DynamicToolAdapter::new(EchoTool("echo"))))
+            .build();
+
+        let output = registry
+            .call("echo", serde_json::json!({ "text": "hi" }))
+            .await
+            .unwrap();
+        assert_eq!(output, serde_json::json!("hi"));
+
+        assert!(matches!(
+            registry.call("missing", serde_json::json!({})).await,
+            Err(McpError::ToolNotRegistered { .. })
+        ));
+    }
+}
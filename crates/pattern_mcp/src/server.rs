@@ -1,15 +1,31 @@
-use std::sync::Arc;
-use tokio::sync::RwLock;
+//! MCP server publishing Pattern tools over stdio or streamable HTTP
 
-use crate::{Result, ToolRegistry, Transport};
+use std::{net::SocketAddr, sync::Arc};
+
+use rmcp::{
+    ErrorData, ServerHandler, ServiceExt,
+    model::{
+        CallToolRequestParam, CallToolResult, Content, Implementation, ListToolsResult,
+        PaginatedRequestParam, ServerCapabilities, ServerInfo,
+    },
+    service::{RequestContext, RoleServer},
+    transport::streamable_http_server::{
+        StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+    },
+};
+use serde_json::Value;
+use tokio::sync::{Notify, RwLock, Semaphore};
+
+use crate::{McpError, Result, ToolRegistry, TransportType};
 
 /// The main MCP server that handles client connections and tool execution
-#[derive(Debug)]
-#[allow(dead_code)]
+#[derive(Debug, Clone)]
 pub struct McpServer {
     registry: Arc<RwLock<ToolRegistry>>,
-    transport: Arc<dyn Transport>,
     config: McpServerConfig,
+    /// Bounds concurrent tool calls across all sessions
+    limiter: Arc<Semaphore>,
+    shutdown: Arc<Notify>,
 }
 
 /// Configuration for the MCP server
@@ -17,6 +33,13 @@ pub struct McpServer {
 pub struct McpServerConfig {
     pub name: String,
     pub version: String,
+    /// Shown to clients on connect to explain what the server offers
+    pub instructions: Option<String>,
+    pub transport: TransportType,
+    /// Address the HTTP transport listens on
+    pub bind_address: SocketAddr,
+    /// Path the HTTP transport serves MCP on
+    pub http_path: String,
     pub max_concurrent_requests: usize,
     pub request_timeout: std::time::Duration,
 }
@@ -26,6 +49,10 @@ impl Default for McpServerConfig {
         Self {
             name: "pattern_mcp".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
+            instructions: None,
+            transport: TransportType::Stdio,
+            bind_address: SocketAddr::from(([127, 0, 0, 1], 8765)),
+            http_path: "/mcp".to_string(),
             max_concurrent_requests: 100,
             request_timeout: std::time::Duration::from_secs(60),
         }
@@ -37,7 +64,6 @@ impl Default for McpServerConfig {
 pub struct McpServerBuilder {
     config: McpServerConfig,
     registry: Option<ToolRegistry>,
-    transport: Option<Arc<dyn Transport>>,
 }
 
 impl McpServerBuilder {
@@ -45,7 +71,6 @@ impl McpServerBuilder {
         Self {
             config: McpServerConfig::default(),
             registry: None,
-            transport: None,
         }
     }
 
@@ -59,13 +84,28 @@ impl McpServerBuilder {
         self
     }
 
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.config.instructions = Some(instructions.into());
+        self
+    }
+
     pub fn with_registry(mut self, registry: ToolRegistry) -> Self {
         self.registry = Some(registry);
         self
     }
 
-    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
-        self.transport = Some(transport);
+    pub fn with_transport(mut self, transport: TransportType) -> Self {
+        self.config.transport = transport;
+        self
+    }
+
+    pub fn with_bind_address(mut self, address: SocketAddr) -> Self {
+        self.config.bind_address = address;
+        self
+    }
+
+    pub fn with_http_path(mut self, path: impl Into<String>) -> Self {
+        self.config.http_path = path.into();
         self
     }
 
@@ -80,19 +120,36 @@ impl McpServerBuilder {
     }
 
     pub fn build(self) -> Result<McpServer> {
-        let registry = self.registry.unwrap_or_default();
-        let transport = self
-            .transport
-            .ok_or_else(|| crate::McpError::InvalidTransportConfig {
-                transport_type: "none".to_string(),
-                config_errors: vec!["No transport specified".to_string()],
-                example_config: "builder.with_transport(...)".to_string(),
-            })?;
+        match self.config.transport {
+            TransportType::Stdio | TransportType::Http => {}
+            other => {
+                return Err(McpError::InvalidTransportConfig {
+                    transport_type: other.as_str().to_string(),
+                    config_errors: vec![format!(
+                        "The MCP server does not support the {} transport",
+                        other.as_str()
+                    )],
+                    example_config: "builder.with_transport(TransportType::Http)".to_string(),
+                });
+            }
+        }
+        if !self.config.http_path.starts_with('/') {
+            return Err(McpError::InvalidTransportConfig {
+                transport_type: self.config.transport.as_str().to_string(),
+                config_errors: vec![format!(
+                    "HTTP path '{}' must start with '/'",
+                    self.config.http_path
+                )],
+                example_config: "builder.with_http_path(\"/mcp\")".to_string(),
+            });
+        }
 
+        let registry = self.registry.unwrap_or_default();
         Ok(McpServer {
             registry: Arc::new(RwLock::new(registry)),
-            transport,
+            limiter: Arc::new(Semaphore::new(self.config.max_concurrent_requests.max(1))),
             config: self.config,
+            shutdown: Arc::new(Notify::new()),
         })
     }
 }
@@ -104,20 +161,181 @@ impl Default for McpServerBuilder {
 }
 
 impl McpServer {
-    /// Start the MCP server
+    pub fn builder() -> McpServerBuilder {
+        McpServerBuilder::new()
+    }
+
+    /// Serve clients on the configured transport until stopped
+    ///
+    /// Over stdio this also returns when the client disconnects.
     pub async fn start(&self) -> Result<()> {
-        // This would implement the actual server logic
-        todo!("Implement MCP server start")
+        match self.config.transport {
+            TransportType::Http => self.serve_http().await,
+            _ => self.serve_stdio().await,
+        }
     }
 
     /// Stop the MCP server gracefully
     pub async fn stop(&self) -> Result<()> {
-        // This would implement graceful shutdown
-        todo!("Implement MCP server stop")
+        self.shutdown.notify_one();
+        Ok(())
     }
 
     /// Get the tool registry
     pub fn registry(&self) -> Arc<RwLock<ToolRegistry>> {
         Arc::clone(&self.registry)
     }
+
+    /// Streamable HTTP endpoint, for mounting inside another axum app
+    pub fn http_router(&self) -> axum::Router {
+        let server = self.clone();
+        let service = StreamableHttpService::new(
+            move || Ok(server.clone()),
+            Arc::new(LocalSessionManager::default()),
+            StreamableHttpServerConfig::default(),
+        );
+        axum::Router::new().nest_service(&self.config.http_path, service)
+    }
+
+    async fn serve_stdio(&self) -> Result<()> {
+        tracing::info!("Serving MCP on stdio");
+        let service = self
+            .clone()
+            .serve(rmcp::transport::stdio())
+            .await
+            .map_err(|e| McpError::transport_init("stdio", "stdin/stdout", e))?;
+
+        tokio::select! {
+            result = service.waiting() => {
+                if let Err(e) = result {
+                    tracing::warn!("MCP stdio session ended with an error: {}", e);
+                }
+            }
+            _ = self.shutdown.notified() => {}
+        }
+        Ok(())
+    }
+
+    async fn serve_http(&self) -> Result<()> {
+        let address = self.config.bind_address;
+        let listener = tokio::net::TcpListener::bind(address)
+            .await
+            .map_err(|e| McpError::server_bind(address.to_string(), "http", e))?;
+        tracing::info!("Serving MCP on http://{}{}", address, self.config.http_path);
+
+        let shutdown = self.shutdown.clone();
+        axum::serve(listener, self.http_router())
+            .with_graceful_shutdown(async move { shutdown.notified().await })
+            .await
+            .map_err(|e| McpError::server_bind(address.to_string(), "http", e))
+    }
+}
+
+impl ServerHandler for McpServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: Implementation {
+                name: self.config.name.clone(),
+                version: self.config.version.clone(),
+                ..Implementation::from_build_env()
+            },
+            instructions: self.config.instructions.clone(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<ListToolsResult, ErrorData> {
+        Ok(ListToolsResult {
+            tools: self.registry.read().await.tools(),
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<CallToolResult, ErrorData> {
+        let _permit = self
+            .limiter
+            .acquire()
+            .await
+            .map_err(|_| ErrorData::internal_error("Server is shutting down", None))?;
+
+        let registry = self.registry.read().await.clone();
+        let params = Value::Object(request.arguments.unwrap_or_default());
+        let call = registry.call(&request.name, params);
+
+        match tokio::time::timeout(self.config.request_timeout, call).await {
+            Ok(Ok(output)) => Ok(CallToolResult::success(vec![Content::text(output_text(
+                &output,
+            ))])),
+            Ok(Err(McpError::ToolNotRegistered {
+                tool_name,
+                available_tools,
+                ..
+            })) => Err(ErrorData::invalid_params(
+                format!(
+                    "Unknown tool '{}'. Available tools: {}",
+                    tool_name,
+                    available_tools.join(", ")
+                ),
+                None,
+            )),
+            // Failures inside a tool are results the client's model should see
+            Ok(Err(McpError::ToolExecutionFailed { cause, .. })) => Ok(CallToolResult::error(
+                vec![Content::text(cause.to_string())],
+            )),
+            Ok(Err(e)) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+            Err(_) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Tool '{}' timed out after {}s",
+                request.name,
+                self.config.request_timeout.as_secs()
+            ))])),
+        }
+    }
+}
+
+/// Plain strings go out as-is; anything else as pretty JSON
+fn output_text(output: &Value) -> String {
+    match output {
+        Value::String(text) => text.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_else(|_| other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_rejects_unsupported_transport() {
+        let result = McpServer::builder()
+            .with_transport(TransportType::WebSocket)
+            .build();
+        assert!(matches!(
+            result,
+            Err(McpError::InvalidTransportConfig { .. })
+        ));
+
+        let server = McpServer::builder()
+            .with_transport(TransportType::Http)
+            .build()
+            .unwrap();
+        assert_eq!(server.get_info().server_info.name, "pattern_mcp");
+    }
+
+    #[test]
+    fn test_output_text() {
+        assert_eq!(output_text(&Value::String("done".to_string())), "done");
+        assert_eq!(
+            output_text(&serde_json::json!({ "ok": true })),
+            "{\n  \"ok\": true\n}"
+        );
+    }
 }
@@ -4,11 +4,24 @@ use argon2::{
     Argon2,
     password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
 };
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chacha20poly1305::{
+    ChaCha20Poly1305, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng},
+};
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
-use pattern_api::{AccessTokenClaims, RefreshTokenClaims};
-use pattern_core::id::UserId;
+use pattern_api::{AccessTokenClaims, RefreshTokenClaims, requests::ApiPermission};
+use pattern_core::id::{IdType, UserId};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::{ServerError, ServerResult},
+    models::ApiKeyId,
+};
 
-use crate::error::ServerResult;
+/// Prefix that marks a bearer token as a raw API key rather than a JWT
+pub const API_KEY_PREFIX: &str = "pat_";
 
 //pub mod atproto;
 
@@ -35,8 +48,13 @@ pub fn verify_password(password: &str, hash: &str) -> ServerResult<bool> {
 }
 
 /// Generate an access token
+///
+/// `permissions` scopes the token; `None` grants everything the user can do.
+/// Tokens exchanged for an API key name it, so they end with the key.
 pub fn generate_access_token(
     user_id: UserId,
+    permissions: Option<Vec<ApiPermission>>,
+    api_key: Option<&ApiKeyId>,
     encoding_key: &EncodingKey,
     ttl_seconds: u64,
 ) -> ServerResult<String> {
@@ -48,7 +66,8 @@ pub fn generate_access_token(
         exp: now + ttl_seconds as i64,
         jti: uuid::Uuid::new_v4(),
         token_type: "access".to_string(),
-        permissions: None,
+        permissions,
+        api_key: api_key.map(|id| id.to_key()),
     };
 
     Ok(encode(&Header::default(), &claims, encoding_key)?)
@@ -92,3 +111,115 @@ pub fn validate_refresh_token(
     let token_data = decode::<RefreshTokenClaims>(token, decoding_key, &Validation::default())?;
     Ok(token_data.claims)
 }
+
+/// Generate a new API key for the given key ID
+///
+/// Returns the plaintext key (shown to the user once) and its hash for storage.
+pub fn generate_api_key(id: &ApiKeyId) -> ServerResult<(String, String)> {
+    let secret = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let key = format!("{}{}_{}", API_KEY_PREFIX, id.to_key(), secret);
+    let hash = hash_password(&key)?;
+    Ok((key, hash))
+}
+
+/// Extract the key ID from a plaintext API key
+pub fn parse_api_key(key: &str) -> Option<ApiKeyId> {
+    let (id, secret) = key.strip_prefix(API_KEY_PREFIX)?.split_once('_')?;
+    if id.is_empty() || secret.is_empty() {
+        return None;
+    }
+    ApiKeyId::from_key(id).ok()
+}
+
+/// Displayable prefix of an API key, safe to list
+pub fn api_key_prefix(id: &ApiKeyId) -> String {
+    let key = id.to_key();
+    format!("{}{}", API_KEY_PREFIX, &key[..key.len().min(8)])
+}
+
+/// Encrypts secrets the server has to read back, such as model provider keys
+///
+/// Stored values are base64 of a random nonce followed by the ciphertext.
+#[derive(Clone)]
+pub struct SecretCipher(ChaCha20Poly1305);
+
+impl SecretCipher {
+    const NONCE_LEN: usize = 12;
+
+    /// Derive the encryption key from a configured secret
+    pub fn new(secret: &str) -> Self {
+        Self(ChaCha20Poly1305::new(&Sha256::digest(secret.as_bytes())))
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> ServerResult<String> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .0
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| ServerError::Internal)?;
+
+        let mut bytes = nonce.to_vec();
+        bytes.extend(ciphertext);
+        Ok(STANDARD.encode(bytes))
+    }
+
+    /// Fails if the value is malformed or was encrypted with another secret
+    pub fn decrypt(&self, encrypted: &str) -> ServerResult<String> {
+        let invalid = || ServerError::Config("Stored secret cannot be decrypted".to_string());
+        let bytes = STANDARD.decode(encrypted).map_err(|_| invalid())?;
+        if bytes.len() < Self::NONCE_LEN {
+            return Err(invalid());
+        }
+
+        let (nonce, ciphertext) = bytes.split_at(Self::NONCE_LEN);
+        let plaintext = self
+            .0
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| invalid())?;
+        String::from_utf8(plaintext).map_err(|_| invalid())
+    }
+}
+
+impl std::fmt::Debug for SecretCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretCipher").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_round_trip() {
+        let id = ApiKeyId::generate();
+        let (key, hash) = generate_api_key(&id).unwrap();
+
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert_eq!(parse_api_key(&key), Some(id.clone()));
+        assert!(key.starts_with(&api_key_prefix(&id)));
+        assert!(verify_password(&key, &hash).unwrap());
+
+        assert_eq!(parse_api_key("not-a-key"), None);
+        assert_eq!(parse_api_key("pat_onlyid"), None);
+    }
+
+    #[test]
+    fn test_secret_cipher_round_trip() {
+        let cipher = SecretCipher::new("server secret");
+        let encrypted = cipher.encrypt("sk-test").unwrap();
+
+        assert!(!encrypted.contains("sk-test"));
+        assert_ne!(encrypted, cipher.encrypt("sk-test").unwrap());
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), "sk-test");
+        assert!(
+            SecretCipher::new("other secret")
+                .decrypt(&encrypted)
+                .is_err()
+        );
+    }
+}
@@ -1,5 +1,7 @@
 //! Server configuration
 
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +15,9 @@ pub struct ServerConfig {
     /// JWT secret for signing tokens
     pub jwt_secret: String,
 
+    /// Secret for encrypting stored model provider keys
+    pub credentials_secret: String,
+
     /// Token expiration times
     pub access_token_ttl: u64, // seconds
     pub refresh_token_ttl: u64, // seconds
@@ -22,6 +27,12 @@ pub struct ServerConfig {
 
     /// Rate limiting
     pub rate_limit: RateLimitConfig,
+
+    /// Permission policy file for the agents this server runs (see
+    /// [`pattern_core::policy`]); defaults to `policy.toml` in the working
+    /// directory when one exists
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +56,7 @@ impl Default for ServerConfig {
             bind_address: "127.0.0.1:8080".to_string(),
             database_url: "mem://".to_string(),
             jwt_secret: "change-me-in-production".to_string(),
+            credentials_secret: "change-me-in-production".to_string(),
             access_token_ttl: 3600,    // 1 hour
             refresh_token_ttl: 604800, // 7 days
             cors: CorsConfig {
@@ -64,6 +76,8 @@ impl Default for ServerConfig {
                 requests_per_minute: 60,
                 burst_size: 10,
             },
+            policy: Some(PathBuf::from(pattern_core::policy::DEFAULT_POLICY_FILE))
+                .filter(|p| p.exists()),
         }
     }
 }
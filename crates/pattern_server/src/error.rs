@@ -35,10 +35,9 @@ pub enum ServerError {
     Internal,
 }
 
-impl IntoResponse for ServerError {
-    fn into_response(self) -> Response {
-        // Convert to ApiError for consistent error responses
-        let api_error = match self {
+impl From<ServerError> for ApiError {
+    fn from(error: ServerError) -> Self {
+        match error {
             ServerError::Database(e) => ApiError::from(e),
             ServerError::Core(e) => ApiError::from(e),
             ServerError::Api(e) => e,
@@ -52,8 +51,13 @@ impl IntoResponse for ServerError {
             _ => ApiError::ServiceUnavailable {
                 retry_after_seconds: Some(30),
             },
-        };
+        }
+    }
+}
 
-        api_error.into_response()
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        // Convert to ApiError for consistent error responses
+        ApiError::from(self).into_response()
     }
 }
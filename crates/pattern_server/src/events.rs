@@ -0,0 +1,303 @@
+//! Live event fan-out for WebSocket clients
+//!
+//! Agent and group streams are tapped into the [`EventHub`] through the
+//! realtime sink traits, translated into [`WebSocketEvent`]s and broadcast.
+//! Each connection filters the broadcast down to what it has subscribed to.
+
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use pattern_api::events::WebSocketEvent;
+use pattern_core::{
+    agent::{AgentState, ResponseEvent},
+    coordination::groups::GroupResponseEvent,
+    id::{AgentId, GroupId},
+    realtime::{AgentEventContext, AgentEventSink, GroupEventContext, GroupEventSink},
+};
+use tokio::sync::{broadcast, watch};
+
+/// Events buffered per receiver before slow connections start lagging
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// A broadcast event with the agent and group it concerns
+#[derive(Debug, Clone)]
+pub struct LiveEvent {
+    pub agent_id: Option<AgentId>,
+    pub group_id: Option<GroupId>,
+    pub event: WebSocketEvent,
+}
+
+/// Broadcasts live events to every connected client
+#[derive(Debug)]
+pub struct EventHub {
+    sender: broadcast::Sender<Arc<LiveEvent>>,
+}
+
+impl Default for EventHub {
+    fn default() -> Self {
+        Self::new(CHANNEL_CAPACITY)
+    }
+}
+
+impl EventHub {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Receive every event published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<LiveEvent>> {
+        self.sender.subscribe()
+    }
+
+    /// Publish an event; it is dropped if nobody is listening
+    pub fn publish(
+        &self,
+        agent_id: Option<AgentId>,
+        group_id: Option<GroupId>,
+        event: WebSocketEvent,
+    ) {
+        let _ = self.sender.send(Arc::new(LiveEvent {
+            agent_id,
+            group_id,
+            event,
+        }));
+    }
+
+    /// Sink that publishes an agent's streamed events
+    pub fn agent_sink(self: &Arc<Self>, agent_id: AgentId) -> Arc<dyn AgentEventSink> {
+        Arc::new(AgentBridge {
+            hub: self.clone(),
+            agent_id,
+            tool_names: DashMap::new(),
+        })
+    }
+
+    /// Sink that publishes a group's streamed events
+    pub fn group_sink(self: &Arc<Self>, group_id: GroupId) -> Arc<dyn GroupEventSink> {
+        Arc::new(GroupBridge {
+            hub: self.clone(),
+            group_id,
+            tool_names: DashMap::new(),
+        })
+    }
+
+    /// Publish state changes for an agent until its state channel closes
+    pub fn watch_state(
+        self: &Arc<Self>,
+        agent_id: AgentId,
+        mut states: watch::Receiver<AgentState>,
+    ) {
+        let hub = self.clone();
+        tokio::spawn(async move {
+            let mut old_state = states.borrow_and_update().clone();
+            while states.changed().await.is_ok() {
+                let new_state = states.borrow_and_update().clone();
+                if new_state == old_state {
+                    continue;
+                }
+                hub.publish(
+                    Some(agent_id.clone()),
+                    None,
+                    WebSocketEvent::AgentStateChanged {
+                        agent_id: agent_id.clone(),
+                        old_state: std::mem::replace(&mut old_state, new_state.clone()),
+                        new_state,
+                        timestamp: chrono::Utc::now(),
+                    },
+                );
+            }
+        });
+    }
+}
+
+/// Translates one agent's `ResponseEvent`s
+///
+/// Replies themselves are published by whoever assembles the full response;
+/// this only reports activity while the agent works.
+struct AgentBridge {
+    hub: Arc<EventHub>,
+    agent_id: AgentId,
+    /// Tool names by call id, since completions only carry the id
+    tool_names: DashMap<String, String>,
+}
+
+#[async_trait::async_trait]
+impl AgentEventSink for AgentBridge {
+    async fn on_event(&self, event: ResponseEvent, _ctx: AgentEventContext) {
+        let event = match event {
+            ResponseEvent::ToolCallStarted {
+                call_id, fn_name, ..
+            } => tool_started(&self.tool_names, &self.agent_id, call_id, fn_name),
+            ResponseEvent::ToolCallCompleted { call_id, result } => {
+                tool_completed(&self.tool_names, &self.agent_id, call_id, result.is_ok())
+            }
+            ResponseEvent::Complete { .. } => WebSocketEvent::AgentTyping {
+                agent_id: self.agent_id.clone(),
+                is_typing: false,
+            },
+            ResponseEvent::Error { message, .. } => error_event("agent", message),
+            _ => return,
+        };
+        self.hub.publish(Some(self.agent_id.clone()), None, event);
+    }
+}
+
+/// Translates one group's `GroupResponseEvent`s
+struct GroupBridge {
+    hub: Arc<EventHub>,
+    group_id: GroupId,
+    tool_names: DashMap<String, String>,
+}
+
+#[async_trait::async_trait]
+impl GroupEventSink for GroupBridge {
+    async fn on_event(&self, event: GroupResponseEvent, _ctx: GroupEventContext) {
+        let (agent_id, event) = match event {
+            GroupResponseEvent::AgentStarted { agent_id, .. } => (
+                Some(agent_id.clone()),
+                WebSocketEvent::AgentTyping {
+                    agent_id,
+                    is_typing: true,
+                },
+            ),
+            GroupResponseEvent::AgentCompleted { agent_id, .. } => (
+                Some(agent_id.clone()),
+                WebSocketEvent::AgentTyping {
+                    agent_id,
+                    is_typing: false,
+                },
+            ),
+            GroupResponseEvent::ToolCallStarted {
+                agent_id,
+                call_id,
+                fn_name,
+                ..
+            } => (
+                Some(agent_id.clone()),
+                tool_started(&self.tool_names, &agent_id, call_id, fn_name),
+            ),
+            GroupResponseEvent::ToolCallCompleted {
+                agent_id,
+                call_id,
+                result,
+            } => (
+                Some(agent_id.clone()),
+                tool_completed(&self.tool_names, &agent_id, call_id, result.is_ok()),
+            ),
+            GroupResponseEvent::Error {
+                agent_id, message, ..
+            } => (agent_id, error_event("group", message)),
+            _ => return,
+        };
+        self.hub
+            .publish(agent_id, Some(self.group_id.clone()), event);
+    }
+}
+
+fn tool_started(
+    tool_names: &DashMap<String, String>,
+    agent_id: &AgentId,
+    call_id: String,
+    fn_name: String,
+) -> WebSocketEvent {
+    tool_names.insert(call_id.clone(), fn_name.clone());
+    WebSocketEvent::ToolExecutionStarted {
+        agent_id: agent_id.clone(),
+        tool_name: fn_name,
+        tool_call_id: call_id,
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+fn tool_completed(
+    tool_names: &DashMap<String, String>,
+    agent_id: &AgentId,
+    call_id: String,
+    success: bool,
+) -> WebSocketEvent {
+    WebSocketEvent::ToolExecutionCompleted {
+        agent_id: agent_id.clone(),
+        tool_name: tool_names
+            .remove(&call_id)
+            .map(|(_, name)| name)
+            .unwrap_or_default(),
+        tool_call_id: call_id,
+        success,
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+fn error_event(error_type: &str, message: String) -> WebSocketEvent {
+    WebSocketEvent::Error {
+        error_type: error_type.to_string(),
+        message,
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_agent_bridge_names_completed_tools() {
+        let hub = Arc::new(EventHub::default());
+        let mut events = hub.subscribe();
+        let agent_id = AgentId::generate();
+        let sink = hub.agent_sink(agent_id.clone());
+
+        sink.on_event(
+            ResponseEvent::ToolCallStarted {
+                call_id: "call_1".to_string(),
+                fn_name: "context".to_string(),
+                args: serde_json::json!({}),
+            },
+            AgentEventContext::default(),
+        )
+        .await;
+        sink.on_event(
+            ResponseEvent::ToolCallCompleted {
+                call_id: "call_1".to_string(),
+                result: Err("failed".to_string()),
+            },
+            AgentEventContext::default(),
+        )
+        .await;
+
+        let started = events.recv().await.unwrap();
+        assert_eq!(started.agent_id.as_ref(), Some(&agent_id));
+        assert!(matches!(
+            &started.event,
+            WebSocketEvent::ToolExecutionStarted { tool_name, .. } if tool_name == "context"
+        ));
+
+        let completed = events.recv().await.unwrap();
+        assert!(matches!(
+            &completed.event,
+            WebSocketEvent::ToolExecutionCompleted { tool_name, success: false, .. }
+                if tool_name == "context"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_watch_state_publishes_changes() {
+        let hub = Arc::new(EventHub::default());
+        let mut events = hub.subscribe();
+        let agent_id = AgentId::generate();
+        let (sender, receiver) = watch::channel(AgentState::Ready);
+
+        hub.watch_state(agent_id, receiver);
+        sender.send(AgentState::Suspended).unwrap();
+
+        let changed = events.recv().await.unwrap();
+        assert!(matches!(
+            &changed.event,
+            WebSocketEvent::AgentStateChanged {
+                old_state: AgentState::Ready,
+                new_state: AgentState::Suspended,
+                ..
+            }
+        ));
+    }
+}
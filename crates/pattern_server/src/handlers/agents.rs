@@ -0,0 +1,302 @@
+//! Agent management handlers
+
+use axum::{
+    Extension,
+    extract::{Json, Path, State},
+};
+use pattern_api::{
+    ApiError, PaginatedResponse,
+    requests::{
+        CreateAgentRequest, GetAgentRequest, ListAgentsRequest, SortField, UpdateAgentRequest,
+    },
+    responses::AgentResponse,
+};
+use pattern_core::{
+    agent::{AgentRecord, AgentState},
+    db::ops,
+    id::{AgentId, UserId},
+};
+
+use super::{Params, paginate, scope, sort_items};
+use crate::{middleware::AuthContext, runtime::is_known_provider, state::AppState};
+
+/// Keys in `AgentRecord::model_config` holding API-only agent settings
+const DESCRIPTION_KEY: &str = "description";
+const PROVIDER_KEY: &str = "provider";
+const TOOLS_KEY: &str = "tools";
+const METADATA_KEY: &str = "metadata";
+
+/// List the caller's agents
+pub async fn list_agents(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<ListAgentsRequest>,
+) -> Result<Json<PaginatedResponse<AgentResponse>>, ApiError> {
+    // Everything reachable lives in the caller's constellation
+    if request
+        .owner_id
+        .as_ref()
+        .is_some_and(|o| o != &auth.user_id)
+    {
+        return Ok(Json(paginate(vec![], &request.query.pagination)));
+    }
+
+    let mut agents = Vec::new();
+    for id in scope::agent_ids(&state, &auth.user_id).await? {
+        if let Some(record) = ops::get_entity::<AgentRecord, _>(&state.db, &id).await? {
+            agents.push(agent_response(&state, &auth.user_id, &record).await);
+        }
+    }
+
+    if let Some(active) = request.query.is_active {
+        agents.retain(|a| matches!(a.state, AgentState::Suspended) != active);
+    }
+    sort_items(&mut agents, &request.query, |a, b, field| match field {
+        SortField::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+        SortField::Name => a.name.cmp(&b.name),
+        SortField::LastActiveAt => a.last_active_at.cmp(&b.last_active_at),
+        SortField::MessageCount => a.message_count.cmp(&b.message_count),
+        _ => a.created_at.cmp(&b.created_at),
+    });
+
+    Ok(Json(paginate(agents, &request.query.pagination)))
+}
+
+/// Create an agent in the caller's constellation
+pub async fn create_agent(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Json(request): Json<CreateAgentRequest>,
+) -> Result<Json<AgentResponse>, ApiError> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ApiError::validation("Agent name cannot be empty"));
+    }
+    if scope::agent_by_name(&state, &auth.user_id, &name)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::Conflict {
+            message: format!("An agent named '{}' already exists", name),
+        });
+    }
+    validate_model(
+        &state,
+        request.model_provider.as_deref(),
+        request.model_id.as_deref(),
+    )
+    .await?;
+
+    let now = chrono::Utc::now();
+    let mut record = AgentRecord {
+        id: AgentId::generate(),
+        name,
+        agent_type: request.agent_type,
+        model_id: request.model_id,
+        base_instructions: request.system_prompt.unwrap_or_default(),
+        owner_id: auth.user_id.clone(),
+        created_at: now,
+        updated_at: now,
+        last_active: now,
+        ..Default::default()
+    };
+    set_config(&mut record, DESCRIPTION_KEY, request.description);
+    set_config(&mut record, PROVIDER_KEY, request.model_provider);
+    set_config(&mut record, TOOLS_KEY, request.tools);
+    set_config(&mut record, METADATA_KEY, request.metadata);
+
+    let record = record.store_with_relations(&state.db).await?;
+    scope::add_agent(&state, &auth.user_id, &record.id).await?;
+
+    Ok(Json(agent_response(&state, &auth.user_id, &record).await))
+}
+
+/// Get one of the caller's agents
+pub async fn get_agent(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<GetAgentRequest>,
+) -> Result<Json<AgentResponse>, ApiError> {
+    let record = scope::agent_record(&state, &auth.user_id, &request.id).await?;
+    Ok(Json(agent_response(&state, &auth.user_id, &record).await))
+}
+
+/// Update an agent's settings or state
+///
+/// Configuration changes take effect by reloading the agent on its next use;
+/// state changes apply to the running agent directly.
+pub async fn update_agent(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<AgentId>,
+    Json(request): Json<UpdateAgentRequest>,
+) -> Result<Json<AgentResponse>, ApiError> {
+    let mut record = scope::agent_record(&state, &auth.user_id, &id).await?;
+    validate_model(
+        &state,
+        request.model_provider.as_deref(),
+        request.model_id.as_deref(),
+    )
+    .await?;
+
+    let mut reload = false;
+    if let Some(name) = request.name {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(ApiError::validation("Agent name cannot be empty"));
+        }
+        if name != record.name {
+            if scope::agent_by_name(&state, &auth.user_id, &name)
+                .await?
+                .is_some()
+            {
+                return Err(ApiError::Conflict {
+                    message: format!("An agent named '{}' already exists", name),
+                });
+            }
+            record.name = name;
+            reload = true;
+        }
+    }
+    if let Some(system_prompt) = request.system_prompt {
+        record.base_instructions = system_prompt;
+        reload = true;
+    }
+    if let Some(model_id) = request.model_id {
+        record.model_id = Some(model_id).filter(|m| !m.is_empty());
+        reload = true;
+    }
+    if request.model_provider.is_some() {
+        set_config(&mut record, PROVIDER_KEY, request.model_provider);
+    }
+    if request.description.is_some() {
+        set_config(&mut record, DESCRIPTION_KEY, request.description);
+    }
+    if request.tools.is_some() {
+        set_config(&mut record, TOOLS_KEY, request.tools);
+    }
+    if request.metadata.is_some() {
+        set_config(&mut record, METADATA_KEY, request.metadata);
+    }
+    record.updated_at = chrono::Utc::now();
+
+    let record = ops::update_entity::<AgentRecord, _>(&state.db, &record).await?;
+    if reload {
+        state.runtime.evict(&record.id);
+    }
+    if let Some(agent_state) = request.state {
+        state
+            .runtime
+            .agent(&record.id)
+            .await?
+            .set_state(agent_state)
+            .await?;
+    }
+
+    Ok(Json(agent_response(&state, &auth.user_id, &record).await))
+}
+
+/// Build the API view of an agent, using live state when it is running
+pub async fn agent_response(
+    state: &AppState,
+    owner: &UserId,
+    record: &AgentRecord,
+) -> AgentResponse {
+    let (agent_state, live_tools) = match state.runtime.loaded(&record.id) {
+        Some(agent) => {
+            let tools = agent
+                .available_tools()
+                .await
+                .iter()
+                .map(|t| t.name().to_string())
+                .collect();
+            (agent.state().await.0, tools)
+        }
+        None => (AgentState::Ready, Vec::new()),
+    };
+
+    AgentResponse {
+        id: record.id.clone(),
+        name: record.name.clone(),
+        agent_type: record.agent_type.clone(),
+        description: get_config(record, DESCRIPTION_KEY),
+        system_prompt: Some(record.base_instructions.clone()).filter(|p| !p.is_empty()),
+        state: agent_state,
+        model_provider: get_config(record, PROVIDER_KEY).unwrap_or_default(),
+        model_id: record.model_id.clone().unwrap_or_default(),
+        tools: get_config(record, TOOLS_KEY).unwrap_or(live_tools),
+        owner_id: owner.clone(),
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+        last_active_at: Some(record.last_active),
+        message_count: record.total_messages as u64,
+        metadata: get_config(record, METADATA_KEY),
+    }
+}
+
+async fn validate_model(
+    state: &AppState,
+    provider: Option<&str>,
+    model_id: Option<&str>,
+) -> Result<(), ApiError> {
+    if let Some(provider) = provider.filter(|p| !p.is_empty()) {
+        if !is_known_provider(provider) {
+            return Err(ApiError::validation(format!(
+                "Unknown model provider '{}'",
+                provider
+            )));
+        }
+    }
+    if let Some(model_id) = model_id.filter(|m| !m.is_empty()) {
+        let models = state.runtime.list_models().await?;
+        if !models.iter().any(|m| m.id == model_id) {
+            return Err(ApiError::validation(format!(
+                "Model '{}' is not available from any configured provider",
+                model_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn get_config<T: serde::de::DeserializeOwned>(record: &AgentRecord, key: &str) -> Option<T> {
+    record
+        .model_config
+        .get(key)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+}
+
+fn set_config<T: serde::Serialize>(record: &mut AgentRecord, key: &str, value: Option<T>) {
+    match value.and_then(|v| serde_json::to_value(v).ok()) {
+        Some(value) if !value.is_null() => {
+            record.model_config.insert(key.to_string(), value);
+        }
+        _ => {
+            record.model_config.remove(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_config_round_trip() {
+        let mut record = AgentRecord::default();
+        set_config(&mut record, TOOLS_KEY, Some(vec!["context", "search"]));
+        set_config(&mut record, DESCRIPTION_KEY, Some("helper"));
+
+        assert_eq!(
+            get_config::<Vec<String>>(&record, TOOLS_KEY),
+            Some(vec!["context".to_string(), "search".to_string()])
+        );
+        assert_eq!(
+            get_config::<String>(&record, DESCRIPTION_KEY).as_deref(),
+            Some("helper")
+        );
+
+        set_config::<String>(&mut record, DESCRIPTION_KEY, None);
+        assert!(!record.model_config.contains_key(DESCRIPTION_KEY));
+    }
+}
@@ -0,0 +1,243 @@
+//! API key management handlers
+//!
+//! A key can never grant more than the caller holds, so scoped keys can't be
+//! used to mint broader ones.
+
+use axum::{
+    Extension,
+    extract::{Json, Path, State},
+};
+use pattern_api::{
+    ApiError, PaginatedResponse,
+    requests::{
+        ApiPermission, CreateApiKeyRequest, GetApiKeyRequest, ListApiKeysRequest, SortField,
+        UpdateApiKeyRequest,
+    },
+    responses::{ApiKeyInfo, ApiKeyResponse},
+};
+use pattern_core::{
+    db::{DatabaseError, entity::DbEntity, ops},
+    id::{IdType, UserId},
+};
+use surrealdb::RecordId;
+
+use super::{Params, paginate, parse_id, scope::db_error, sort_items};
+use crate::{
+    auth::{api_key_prefix, generate_api_key},
+    middleware::{AuthContext, parse_permissions},
+    models::{ApiKey, ApiKeyId},
+    state::AppState,
+};
+
+/// List the caller's API keys
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<ListApiKeysRequest>,
+) -> Result<Json<PaginatedResponse<ApiKeyInfo>>, ApiError> {
+    let mut keys = user_keys(&state, &auth.user_id).await?;
+
+    if let Some(active) = request.query.is_active {
+        keys.retain(|k| k.is_active == active);
+    }
+    sort_items(&mut keys, &request.query, |a, b, field| match field {
+        SortField::Name => a.name.cmp(&b.name),
+        SortField::LastActiveAt => a.last_used_at.cmp(&b.last_used_at),
+        _ => a.created_at.cmp(&b.created_at),
+    });
+
+    let keys = keys.iter().map(key_info).collect();
+    Ok(Json(paginate(keys, &request.query.pagination)))
+}
+
+/// Create a key; the plaintext is only ever returned here
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> Result<Json<ApiKeyResponse>, ApiError> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ApiError::validation("API key name cannot be empty"));
+    }
+    let permissions = check_grantable(&auth, request.permissions)?;
+    check_expiry(request.expires_at)?;
+    check_rate_limit(request.rate_limit)?;
+
+    let id = ApiKeyId::generate();
+    let (key, key_hash) = generate_api_key(&id)?;
+    let record = ApiKey {
+        id,
+        user_id: auth.user_id.clone(),
+        key_hash,
+        name,
+        permissions: permissions.iter().map(|p| p.as_str().to_string()).collect(),
+        last_used_at: None,
+        expires_at: request.expires_at,
+        created_at: chrono::Utc::now(),
+        is_active: true,
+        rate_limit: request.rate_limit,
+    };
+    let record = ops::create_entity::<ApiKey, _>(&state.db, &record).await?;
+
+    Ok(Json(ApiKeyResponse {
+        id: record.id.to_key(),
+        name: record.name,
+        key,
+        permissions,
+        created_at: record.created_at,
+        expires_at: record.expires_at,
+        rate_limit: record.rate_limit,
+    }))
+}
+
+/// Get one of the caller's API keys
+pub async fn get_api_key(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<GetApiKeyRequest>,
+) -> Result<Json<ApiKeyInfo>, ApiError> {
+    let key = user_key(&state, &auth.user_id, &request.id).await?;
+    Ok(Json(key_info(&key)))
+}
+
+/// Rename, rescope, disable or otherwise change an API key
+pub async fn update_api_key(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateApiKeyRequest>,
+) -> Result<Json<ApiKeyInfo>, ApiError> {
+    let mut key = user_key(&state, &auth.user_id, &id).await?;
+
+    if let Some(name) = request.name {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(ApiError::validation("API key name cannot be empty"));
+        }
+        key.name = name;
+    }
+    if let Some(permissions) = request.permissions {
+        key.permissions = check_grantable(&auth, permissions)?
+            .iter()
+            .map(|p| p.as_str().to_string())
+            .collect();
+    }
+    if let Some(expires_at) = request.expires_at {
+        check_expiry(Some(expires_at))?;
+        key.expires_at = Some(expires_at);
+    }
+    if let Some(rate_limit) = request.rate_limit {
+        check_rate_limit(Some(rate_limit))?;
+        key.rate_limit = Some(rate_limit);
+    }
+    if let Some(enabled) = request.enabled {
+        key.is_active = enabled;
+    }
+
+    let key = ops::update_entity::<ApiKey, _>(&state.db, &key).await?;
+    Ok(Json(key_info(&key)))
+}
+
+/// Deduplicate requested permissions, refusing any the caller lacks
+fn check_grantable(
+    auth: &AuthContext,
+    requested: Vec<ApiPermission>,
+) -> Result<Vec<ApiPermission>, ApiError> {
+    let mut permissions = Vec::new();
+    for permission in requested {
+        auth.require(permission)?;
+        if !permissions.contains(&permission) {
+            permissions.push(permission);
+        }
+    }
+    if permissions.is_empty() {
+        return Err(ApiError::validation(
+            "An API key needs at least one permission",
+        ));
+    }
+    Ok(permissions)
+}
+
+fn check_expiry(expires_at: Option<chrono::DateTime<chrono::Utc>>) -> Result<(), ApiError> {
+    if expires_at.is_some_and(|e| e <= chrono::Utc::now()) {
+        return Err(ApiError::validation("Expiry must be in the future"));
+    }
+    Ok(())
+}
+
+fn check_rate_limit(rate_limit: Option<u32>) -> Result<(), ApiError> {
+    if rate_limit == Some(0) {
+        return Err(ApiError::validation("Rate limit must be at least 1"));
+    }
+    Ok(())
+}
+
+fn key_info(key: &ApiKey) -> ApiKeyInfo {
+    ApiKeyInfo {
+        id: key.id.to_key(),
+        name: key.name.clone(),
+        key_prefix: api_key_prefix(&key.id),
+        permissions: parse_permissions(&key.permissions),
+        created_at: key.created_at,
+        expires_at: key.expires_at,
+        last_used_at: key.last_used_at,
+        rate_limit: key.rate_limit,
+        enabled: key.is_active,
+    }
+}
+
+async fn user_key(state: &AppState, user: &UserId, id: &str) -> Result<ApiKey, ApiError> {
+    let key_id: ApiKeyId = parse_id(id)?;
+    ops::get_entity::<ApiKey, _>(&state.db, &key_id)
+        .await?
+        .filter(|key| &key.user_id == user)
+        .ok_or_else(|| ApiError::not_found("api_key", id))
+}
+
+async fn user_keys(state: &AppState, user: &UserId) -> Result<Vec<ApiKey>, ApiError> {
+    let keys: Vec<<ApiKey as DbEntity>::DbModel> = state
+        .db
+        .query("SELECT * FROM api_key WHERE user_id = $user")
+        .bind(("user", RecordId::from(user)))
+        .await
+        .map_err(db_error)?
+        .take(0)
+        .map_err(db_error)?;
+
+    keys.into_iter()
+        .map(|k| ApiKey::from_db_model(k).map_err(|e| DatabaseError::from(e).into()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_caller_cannot_widen_permissions() {
+        let auth = AuthContext {
+            user_id: UserId::generate(),
+            permissions: Some(vec![
+                ApiPermission::ReadAgents,
+                ApiPermission::ManageApiKeys,
+            ]),
+            api_key: None,
+            rate_limit: None,
+            is_admin: false,
+        };
+
+        let granted = check_grantable(
+            &auth,
+            vec![ApiPermission::ReadAgents, ApiPermission::ReadAgents],
+        )
+        .unwrap();
+        assert_eq!(granted, vec![ApiPermission::ReadAgents]);
+
+        assert!(matches!(
+            check_grantable(&auth, vec![ApiPermission::WriteAgents]),
+            Err(ApiError::Forbidden { .. })
+        ));
+        assert!(check_grantable(&auth, vec![]).is_err());
+    }
+}
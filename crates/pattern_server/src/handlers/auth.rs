@@ -2,11 +2,14 @@
 
 use axum::extract::{Json, State};
 use pattern_api::{ApiError, requests::AuthRequest, responses::AuthResponse};
+use pattern_core::{db::ops, id::UserId};
+use surrealdb::RecordId;
 
+use super::scope::db_error;
 use crate::{
     auth::{generate_access_token, generate_refresh_token, verify_password},
-    middleware::extract_bearer_token,
-    models::ServerUser,
+    middleware::{extract_bearer_token, parse_permissions, verify_api_key},
+    models::{RefreshTokenFamily, RefreshTokenFamilyId, ServerUser},
     state::AppState,
 };
 
@@ -33,9 +36,12 @@ pub async fn login(
                     json: "".to_string(),
                 })?;
 
-            let user = result.ok_or_else(|| ApiError::Unauthorized {
-                message: Some("Invalid username or password".to_string()),
-            })?;
+            let user =
+                result
+                    .filter(|user| user.is_active)
+                    .ok_or_else(|| ApiError::Unauthorized {
+                        message: Some("Invalid username or password".to_string()),
+                    })?;
 
             // Verify password
             if !verify_password(&password, &user.password_hash).map_err(|e| ApiError::Core {
@@ -48,11 +54,13 @@ pub async fn login(
             }
 
             // Generate tokens
-            let user_id = user.id;
-            let token_family = uuid::Uuid::new_v4();
+            let user_id = user.id.clone();
+            let token_family = start_token_family(&state, &user_id).await?;
 
             let access_token = generate_access_token(
                 user_id.clone(),
+                None,
+                None,
                 &state.jwt_encoding_key,
                 state.config.access_token_ttl,
             )
@@ -62,7 +70,7 @@ pub async fn login(
             })?;
 
             let refresh_token = generate_refresh_token(
-                user_id.clone(),
+                user_id,
                 token_family,
                 &state.jwt_encoding_key,
                 state.config.refresh_token_ttl,
@@ -77,22 +85,34 @@ pub async fn login(
                 refresh_token,
                 token_type: "Bearer".to_string(),
                 expires_in: state.config.access_token_ttl,
-                user: pattern_api::responses::UserResponse {
-                    id: user_id,
-                    username: user.username.clone(),
-                    display_name: user.display_name.clone(),
-                    email: user.email.clone(),
-                    created_at: user.created_at,
-                    updated_at: user.updated_at,
-                    is_active: user.is_active,
-                },
+                user: user.to_response(),
             }))
         }
-        AuthRequest::ApiKey { api_key: _ } => {
-            // TODO: Implement API key authentication
-            Err(ApiError::ServiceUnavailable {
-                retry_after_seconds: None,
-            })
+        AuthRequest::ApiKey { api_key } => {
+            let key = verify_api_key(&state, &api_key).await?;
+            let user = load_active_user(&state, &key.user_id).await?;
+
+            // Refresh tokens can't carry a scope, so key logins only get an
+            // access token and log in again with the key when it expires
+            let access_token = generate_access_token(
+                user.id.clone(),
+                Some(parse_permissions(&key.permissions)),
+                Some(&key.id),
+                &state.jwt_encoding_key,
+                state.config.access_token_ttl,
+            )
+            .map_err(|e| ApiError::Core {
+                message: format!("Failed to generate access token: {}", e),
+                json: "".to_string(),
+            })?;
+
+            Ok(Json(AuthResponse {
+                access_token,
+                refresh_token: String::new(),
+                token_type: "Bearer".to_string(),
+                expires_in: state.config.access_token_ttl,
+                user: user.to_response(),
+            }))
         }
     }
 }
@@ -115,12 +135,23 @@ pub async fn refresh_token(
             }
         })?;
 
-    // TODO: Check if token family is still valid (not revoked)
-    // For now, we'll just generate new tokens
+    let user = load_active_user(&state, &claims.sub).await?;
+    let family = ops::get_entity::<RefreshTokenFamily, _>(
+        &state.db,
+        &RefreshTokenFamilyId::from_uuid(claims.family),
+    )
+    .await?;
+    if !family.is_some_and(|f| f.user_id == user.id && f.is_active) {
+        return Err(ApiError::Unauthorized {
+            message: Some("Refresh token has been revoked".to_string()),
+        });
+    }
 
     // Generate new tokens with same family
     let access_token = generate_access_token(
         claims.sub.clone(),
+        None,
+        None,
         &state.jwt_encoding_key,
         state.config.access_token_ttl,
     )
@@ -145,15 +176,126 @@ pub async fn refresh_token(
         refresh_token,
         token_type: "Bearer".to_string(),
         expires_in: state.config.access_token_ttl,
-        user: pattern_api::responses::UserResponse {
-            // TODO: Load user from database
-            id: claims.sub.clone(),
-            username: "TODO".to_string(),
-            display_name: None,
+        user: user.to_response(),
+    }))
+}
+
+/// Record a new refresh token family so it can be revoked later
+async fn start_token_family(state: &AppState, user_id: &UserId) -> Result<uuid::Uuid, ApiError> {
+    let family_id = uuid::Uuid::new_v4();
+    let family = RefreshTokenFamily {
+        id: RefreshTokenFamilyId::from_uuid(family_id),
+        user_id: user_id.clone(),
+        family_id,
+        created_at: chrono::Utc::now(),
+        revoked_at: None,
+        is_active: true,
+    };
+    ops::create_entity::<RefreshTokenFamily, _>(&state.db, &family).await?;
+
+    Ok(family_id)
+}
+
+/// Revoke every refresh token family of a user, signing out their sessions
+/// once the current access tokens expire
+pub async fn revoke_token_families(state: &AppState, user_id: &UserId) -> Result<(), ApiError> {
+    state
+        .db
+        .query(
+            "UPDATE refresh_token_family SET is_active = false, revoked_at = time::now() \
+             WHERE user_id = $user AND is_active = true",
+        )
+        .bind(("user", RecordId::from(user_id)))
+        .await
+        .map_err(db_error)?
+        .check()
+        .map_err(db_error)?;
+
+    Ok(())
+}
+
+/// Load a user who is still allowed to sign in
+async fn load_active_user(state: &AppState, user_id: &UserId) -> Result<ServerUser, ApiError> {
+    ops::get_entity::<ServerUser, _>(&state.db, user_id)
+        .await?
+        .filter(|user| user.is_active)
+        .ok_or_else(|| ApiError::Unauthorized {
+            message: Some("Account not found or disabled".to_string()),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        auth::{generate_api_key, hash_password},
+        config::ServerConfig,
+        middleware::authenticate_token,
+        models::{ApiKey, ApiKeyId},
+    };
+
+    #[tokio::test]
+    async fn test_exchanged_token_keeps_key_and_follows_user() {
+        let state = AppState::new(ServerConfig::default()).await.unwrap();
+        let now = chrono::Utc::now();
+        let mut user = ServerUser {
+            id: UserId::generate(),
+            discord_id: None,
+            created_at: now,
+            updated_at: now,
+            settings: Default::default(),
+            metadata: Default::default(),
+            username: "user".to_string(),
+            password_hash: hash_password("correct horse battery").unwrap(),
             email: None,
-            created_at: chrono::Utc::now(),
-            updated_at: chrono::Utc::now(),
+            display_name: None,
             is_active: true,
-        },
-    }))
+            is_admin: false,
+            owned_agent_ids: vec![],
+            created_task_ids: vec![],
+            memory_ids: vec![],
+            scheduled_event_ids: vec![],
+            api_keys: vec![],
+            refresh_token_families: vec![],
+        };
+        ops::create_entity::<ServerUser, _>(&state.db, &user)
+            .await
+            .unwrap();
+
+        let key_id = ApiKeyId::generate();
+        let (api_key, key_hash) = generate_api_key(&key_id).unwrap();
+        let key = ApiKey {
+            id: key_id.clone(),
+            user_id: user.id.clone(),
+            key_hash,
+            name: "ci".to_string(),
+            permissions: vec!["read_agents".to_string()],
+            last_used_at: None,
+            expires_at: None,
+            created_at: now,
+            is_active: true,
+            rate_limit: Some(5),
+        };
+        ops::create_entity::<ApiKey, _>(&state.db, &key)
+            .await
+            .unwrap();
+
+        let response = login(State(state.clone()), Json(AuthRequest::ApiKey { api_key }))
+            .await
+            .unwrap();
+        let auth = authenticate_token(&state, &response.access_token)
+            .await
+            .unwrap();
+        assert_eq!(auth.api_key, Some(key_id));
+        assert_eq!(auth.rate_limit, Some(5));
+
+        user.is_active = false;
+        ops::update_entity::<ServerUser, _>(&state.db, &user)
+            .await
+            .unwrap();
+        assert!(matches!(
+            authenticate_token(&state, &response.access_token).await,
+            Err(ApiError::Unauthorized { .. })
+        ));
+    }
 }
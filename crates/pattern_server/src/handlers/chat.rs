@@ -0,0 +1,263 @@
+//! Chat handlers
+
+use axum::{
+    Extension,
+    extract::{Json, State},
+};
+use futures::StreamExt;
+use pattern_api::events::WebSocketEvent;
+use pattern_api::{
+    ApiError,
+    requests::SendMessageRequest,
+    responses::{ChatResponse, MessageResponse, UsageInfo},
+};
+use pattern_core::{
+    agent::{AgentRecord, ResponseEvent},
+    coordination::{AgentGroup, groups::GroupResponseEvent, utils::ResponseAccumulator},
+    db::ops,
+    id::{AgentId, GroupId, MessageId},
+    message::{ChatRole, Message, MessageContent, Response},
+    realtime::{AgentEventContext, GroupEventContext, tap_agent_stream, tap_group_stream},
+};
+
+use super::{
+    messages::tool_details,
+    scope::{self, Target},
+};
+use crate::{middleware::AuthContext, state::AppState};
+
+/// Source tag for streams started through the API
+const SOURCE_TAG: &str = "API";
+
+/// Send a message to an agent or group and wait for the full reply
+pub async fn send_message(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Json(request): Json<SendMessageRequest>,
+) -> Result<Json<ChatResponse>, ApiError> {
+    let mut message = Message::user(request.content);
+    message.owner_id = Some(auth.user_id.clone());
+
+    match scope::resolve_target(&state, &auth.user_id, &request.target).await? {
+        Target::Agent(record) => {
+            publish_message(&state, &record.id, None, &message);
+            let response = send_to_agent(&state, &record, message).await?;
+            let messages = response_messages(&record.id, &response);
+            publish_replies(&state, None, &messages);
+
+            Ok(Json(ChatResponse {
+                usage: usage_info(&response),
+                messages,
+            }))
+        }
+        Target::Group(group) => send_to_group(&state, &group, message).await.map(Json),
+    }
+}
+
+/// Run an agent on a message, publishing its activity as it streams
+async fn send_to_agent(
+    state: &AppState,
+    record: &AgentRecord,
+    message: Message,
+) -> Result<Response, ApiError> {
+    let agent = state.runtime.agent(&record.id).await?;
+    state.events.publish(
+        Some(record.id.clone()),
+        None,
+        WebSocketEvent::AgentTyping {
+            agent_id: record.id.clone(),
+            is_typing: true,
+        },
+    );
+
+    let stream = agent.process_message_stream(message).await?;
+    let mut events = tap_agent_stream(
+        stream,
+        vec![state.events.agent_sink(record.id.clone())],
+        AgentEventContext {
+            source_tag: Some(SOURCE_TAG.to_string()),
+            agent_name: Some(record.name.clone()),
+        },
+    );
+
+    let mut accumulator = ResponseAccumulator::new();
+    let mut errors = Vec::new();
+    while let Some(event) = events.next().await {
+        if let ResponseEvent::Error {
+            message,
+            recoverable: false,
+        } = &event
+        {
+            errors.push(message.clone());
+        }
+        accumulator.push(&event);
+    }
+
+    let response = accumulator.finish();
+    if response.content.is_empty() && !errors.is_empty() {
+        return Err(ApiError::Core {
+            message: errors.join("; "),
+            json: String::new(),
+        });
+    }
+    Ok(response)
+}
+
+/// Route through a group and gather every member's reply
+async fn send_to_group(
+    state: &AppState,
+    group: &AgentGroup,
+    message: Message,
+) -> Result<ChatResponse, ApiError> {
+    for (agent, _) in group.members.iter().filter(|(_, m)| m.is_active) {
+        publish_message(state, &agent.id, Some(&group.id), &message);
+    }
+
+    let events = state.runtime.route_group(group, message).await?;
+    let mut events = tap_group_stream(
+        events,
+        vec![state.events.group_sink(group.id.clone())],
+        GroupEventContext {
+            source_tag: Some(SOURCE_TAG.to_string()),
+            group_name: Some(group.name.clone()),
+        },
+    );
+    let mut errors = Vec::new();
+
+    while let Some(event) = events.next().await {
+        match event {
+            GroupResponseEvent::Complete {
+                agent_responses,
+                state_changes,
+                ..
+            } => {
+                if let Some(new_state) = state_changes {
+                    ops::update_group_state(&state.db, &group.id, new_state).await?;
+                }
+
+                let messages: Vec<MessageResponse> = agent_responses
+                    .iter()
+                    .flat_map(|r| response_messages(&r.agent_id, &r.response))
+                    .collect();
+                publish_replies(state, Some(&group.id), &messages);
+                let usage = agent_responses
+                    .iter()
+                    .filter_map(|r| usage_info(&r.response))
+                    .reduce(|total, usage| UsageInfo {
+                        input_tokens: total.input_tokens + usage.input_tokens,
+                        output_tokens: total.output_tokens + usage.output_tokens,
+                        total_tokens: total.total_tokens + usage.total_tokens,
+                        model: total.model,
+                    });
+
+                return Ok(ChatResponse { messages, usage });
+            }
+            GroupResponseEvent::Error {
+                message,
+                recoverable,
+                ..
+            } => {
+                tracing::warn!("Group {} error: {}", group.name, message);
+                if !recoverable {
+                    errors.push(message);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Err(ApiError::Core {
+        message: if errors.is_empty() {
+            format!("Group '{}' finished without a response", group.name)
+        } else {
+            errors.join("; ")
+        },
+        json: String::new(),
+    })
+}
+
+/// Publish a message an agent received to live subscribers
+fn publish_message(
+    state: &AppState,
+    agent_id: &AgentId,
+    group_id: Option<&GroupId>,
+    message: &Message,
+) {
+    state.events.publish(
+        Some(agent_id.clone()),
+        group_id.cloned(),
+        WebSocketEvent::MessageReceived {
+            message_id: message.id.clone(),
+            agent_id: agent_id.clone(),
+            role: message.role.clone(),
+            content: message.content.clone(),
+            timestamp: message.created_at,
+        },
+    );
+}
+
+/// Publish agents' replies to live subscribers
+fn publish_replies(state: &AppState, group_id: Option<&GroupId>, messages: &[MessageResponse]) {
+    for message in messages {
+        state.events.publish(
+            Some(message.agent_id.clone()),
+            group_id.cloned(),
+            WebSocketEvent::MessageReceived {
+                message_id: message.id.clone(),
+                agent_id: message.agent_id.clone(),
+                role: message.role.clone(),
+                content: message.content.clone(),
+                timestamp: message.created_at,
+            },
+        );
+    }
+}
+
+/// Split an agent's response into API messages, one per content item
+fn response_messages(agent_id: &AgentId, response: &Response) -> Vec<MessageResponse> {
+    let now = chrono::Utc::now();
+    response
+        .content
+        .iter()
+        .map(|content| {
+            let (tool_calls, tool_results) = tool_details(content);
+            let role = match content {
+                MessageContent::ToolResponses(_) => ChatRole::Tool,
+                _ => ChatRole::Assistant,
+            };
+            MessageResponse {
+                id: MessageId::generate(),
+                agent_id: agent_id.clone(),
+                role,
+                content: content.clone(),
+                created_at: now,
+                tool_calls,
+                tool_results,
+                metadata: response
+                    .reasoning
+                    .as_ref()
+                    .map(|reasoning| serde_json::json!({ "reasoning": reasoning })),
+            }
+        })
+        .collect()
+}
+
+fn usage_info(response: &Response) -> Option<UsageInfo> {
+    let usage = response.metadata.tokens_used.as_ref()?;
+    let input_tokens = usage.prompt_tokens.unwrap_or(0).max(0) as u32;
+    let output_tokens = usage.completion_tokens.unwrap_or(0).max(0) as u32;
+
+    Some(UsageInfo {
+        input_tokens,
+        output_tokens,
+        total_tokens: usage
+            .total_tokens
+            .map(|t| t.max(0) as u32)
+            .unwrap_or(input_tokens + output_tokens),
+        model: response
+            .metadata
+            .model_used
+            .clone()
+            .unwrap_or_else(|| response.metadata.model_iden.model_name.to_string()),
+    })
+}
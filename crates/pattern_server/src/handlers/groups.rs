@@ -0,0 +1,250 @@
+//! Agent group handlers
+
+use axum::{
+    Extension,
+    extract::{Json, Path, State},
+};
+use chrono::Utc;
+use pattern_api::{
+    ApiError, PaginatedResponse,
+    events::WebSocketEvent,
+    requests::{
+        CreateGroupRequest, GetGroupRequest, ListGroupsRequest, SortField, UpdateGroupRequest,
+    },
+    responses::{GroupMemberResponse, GroupResponse, GroupWithMembersResponse},
+};
+use pattern_core::{
+    coordination::{
+        AgentGroup, CoordinationPattern,
+        groups::GroupMembership,
+        types::{GroupMemberRole, GroupState},
+    },
+    db::ops,
+    id::{GroupId, RelationId, UserId},
+};
+
+use super::{Params, paginate, scope, sort_items};
+use crate::{middleware::AuthContext, state::AppState};
+
+/// List the caller's groups
+pub async fn list_groups(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<ListGroupsRequest>,
+) -> Result<Json<PaginatedResponse<GroupResponse>>, ApiError> {
+    // Everything reachable lives in the caller's constellation
+    if request
+        .owner_id
+        .as_ref()
+        .is_some_and(|o| o != &auth.user_id)
+    {
+        return Ok(Json(paginate(vec![], &request.query.pagination)));
+    }
+
+    let mut groups = Vec::new();
+    for id in scope::group_ids(&state, &auth.user_id).await? {
+        if let Some(group) = AgentGroup::load_with_relations(&state.db, &id).await? {
+            groups.push(group_response(&group, &auth.user_id));
+        }
+    }
+
+    if let Some(active) = request.query.is_active {
+        groups.retain(|g| g.is_active == active);
+    }
+    sort_items(&mut groups, &request.query, |a, b, field| match field {
+        SortField::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+        SortField::Name => a.name.cmp(&b.name),
+        SortField::MemberCount => a.member_count.cmp(&b.member_count),
+        _ => a.created_at.cmp(&b.created_at),
+    });
+
+    Ok(Json(paginate(groups, &request.query.pagination)))
+}
+
+/// Create a group in the caller's constellation, with optional initial members
+pub async fn create_group(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Json(request): Json<CreateGroupRequest>,
+) -> Result<Json<GroupResponse>, ApiError> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ApiError::validation("Group name cannot be empty"));
+    }
+    if scope::group_by_name(&state, &auth.user_id, &name)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::Conflict {
+            message: format!("A group named '{}' already exists", name),
+        });
+    }
+
+    // Check every member before creating anything
+    let members = request.members.unwrap_or_default();
+    for member in &members {
+        scope::agent_record(&state, &auth.user_id, &member.agent_id).await?;
+    }
+
+    let now = Utc::now();
+    let group = AgentGroup {
+        id: GroupId::generate(),
+        name,
+        description: request.description,
+        state: initial_state(&request.coordination_pattern),
+        coordination_pattern: request.coordination_pattern,
+        created_at: now,
+        updated_at: now,
+        is_active: true,
+        members: vec![],
+    };
+    let group = ops::create_group_for_user(&state.db, &auth.user_id, &group).await?;
+
+    for member in members {
+        let membership = GroupMembership {
+            id: RelationId::generate(),
+            in_id: member.agent_id,
+            out_id: group.id.clone(),
+            joined_at: now,
+            role: member.role,
+            is_active: true,
+            capabilities: member.capabilities.unwrap_or_default(),
+        };
+        ops::add_agent_to_group(&state.db, &membership).await?;
+        state.events.publish(
+            Some(membership.in_id.clone()),
+            Some(group.id.clone()),
+            WebSocketEvent::GroupMemberAdded {
+                group_id: group.id.clone(),
+                agent_id: membership.in_id,
+                role: role_name(&membership.role),
+                timestamp: now,
+            },
+        );
+    }
+
+    let group = scope::group(&state, &auth.user_id, &group.id).await?;
+    Ok(Json(group_response(&group, &auth.user_id)))
+}
+
+/// Get a group with its members
+pub async fn get_group(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<GetGroupRequest>,
+) -> Result<Json<GroupWithMembersResponse>, ApiError> {
+    let group = scope::group(&state, &auth.user_id, &request.id).await?;
+
+    let members = group
+        .members
+        .iter()
+        .map(|(agent, membership)| GroupMemberResponse {
+            agent_id: agent.id.clone(),
+            agent_name: agent.name.clone(),
+            role: membership.role.clone(),
+            capabilities: membership.capabilities.clone(),
+            joined_at: membership.joined_at,
+            is_active: membership.is_active,
+            metadata: None,
+        })
+        .collect();
+
+    Ok(Json(GroupWithMembersResponse {
+        group: group_response(&group, &auth.user_id),
+        members,
+    }))
+}
+
+/// Update a group's settings
+///
+/// Changing the coordination pattern resets the pattern's state.
+pub async fn update_group(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<GroupId>,
+    Json(request): Json<UpdateGroupRequest>,
+) -> Result<Json<GroupResponse>, ApiError> {
+    let mut group = scope::group(&state, &auth.user_id, &id).await?;
+
+    if let Some(name) = request.name {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(ApiError::validation("Group name cannot be empty"));
+        }
+        if name != group.name
+            && scope::group_by_name(&state, &auth.user_id, &name)
+                .await?
+                .is_some()
+        {
+            return Err(ApiError::Conflict {
+                message: format!("A group named '{}' already exists", name),
+            });
+        }
+        group.name = name;
+    }
+    if let Some(description) = request.description {
+        group.description = description;
+    }
+    if let Some(pattern) = request.coordination_pattern {
+        group.state = initial_state(&pattern);
+        group.coordination_pattern = pattern;
+    }
+    if let Some(is_active) = request.is_active {
+        group.is_active = is_active;
+    }
+    group.updated_at = Utc::now();
+
+    ops::update_entity::<AgentGroup, _>(&state.db, &group).await?;
+    Ok(Json(group_response(&group, &auth.user_id)))
+}
+
+fn group_response(group: &AgentGroup, owner: &UserId) -> GroupResponse {
+    GroupResponse {
+        id: group.id.clone(),
+        name: group.name.clone(),
+        description: group.description.clone(),
+        coordination_pattern: group.coordination_pattern.clone(),
+        owner_id: owner.clone(),
+        member_count: group.members.len() as u32,
+        is_active: group.is_active,
+        created_at: group.created_at,
+        updated_at: group.updated_at,
+        last_active_at: None,
+    }
+}
+
+fn role_name(role: &GroupMemberRole) -> String {
+    match role {
+        GroupMemberRole::Regular => "regular".to_string(),
+        GroupMemberRole::Supervisor => "supervisor".to_string(),
+        GroupMemberRole::Specialist { domain } => format!("specialist:{}", domain),
+    }
+}
+
+/// Fresh state for a group using the given pattern
+fn initial_state(pattern: &CoordinationPattern) -> GroupState {
+    let now = Utc::now();
+    match pattern {
+        CoordinationPattern::Supervisor { .. } => GroupState::Supervisor {
+            current_delegations: Default::default(),
+        },
+        CoordinationPattern::RoundRobin { .. } => GroupState::RoundRobin {
+            current_index: 0,
+            last_rotation: now,
+        },
+        CoordinationPattern::Voting { .. } => GroupState::Voting {
+            active_session: None,
+        },
+        CoordinationPattern::Pipeline { .. } => GroupState::Pipeline {
+            active_executions: vec![],
+        },
+        CoordinationPattern::Dynamic { .. } => GroupState::Dynamic {
+            recent_selections: vec![],
+        },
+        CoordinationPattern::Sleeptime { .. } => GroupState::Sleeptime {
+            last_check: now,
+            trigger_history: vec![],
+            current_index: 0,
+        },
+    }
+}
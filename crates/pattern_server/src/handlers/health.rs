@@ -1,14 +1,24 @@
 //! Health check endpoint
 
-use axum::Json;
+use axum::{Json, extract::State};
 use pattern_api::responses::{ComponentStatus, HealthResponse, HealthStatus};
 
-pub async fn health_check() -> Json<HealthResponse> {
+use crate::state::AppState;
+
+pub async fn health_check(State(state): State<AppState>) -> Json<HealthResponse> {
+    let (status, database_status) = match state.db.health().await {
+        Ok(()) => (HealthStatus::Healthy, ComponentStatus::Ok),
+        Err(e) => {
+            tracing::warn!("Database health check failed: {}", e);
+            (HealthStatus::Unhealthy, ComponentStatus::Error)
+        }
+    };
+
     Json(HealthResponse {
-        status: HealthStatus::Healthy,
+        status,
         version: pattern_api::API_VERSION.to_string(),
-        uptime_seconds: 0, // TODO: Track actual uptime
-        database_status: ComponentStatus::Ok,
+        uptime_seconds: state.started_at.elapsed().as_secs(),
+        database_status,
         services: vec![],
     })
 }
@@ -0,0 +1,169 @@
+//! MCP server handlers
+
+use axum::{
+    Extension,
+    extract::{Json, Path, State},
+};
+use pattern_api::{
+    ApiError, PaginatedResponse,
+    requests::{
+        ConnectMcpServerRequest, GetMcpServerRequest, ListMcpServersRequest, McpTransportConfig,
+        UpdateMcpServerRequest,
+    },
+    responses::McpServerResponse,
+};
+
+use super::{Params, paginate, parse_id, sort_items};
+use crate::{
+    middleware::AuthContext,
+    models::{McpServerId, McpServerRecord},
+    state::AppState,
+};
+
+/// List the caller's MCP servers
+pub async fn list_servers(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<ListMcpServersRequest>,
+) -> Result<Json<PaginatedResponse<McpServerResponse>>, ApiError> {
+    let mut servers = state.mcp.list(&auth.user_id);
+
+    if let Some(status) = &request.status {
+        servers.retain(|s| std::mem::discriminant(&s.status) == std::mem::discriminant(status));
+    }
+    // Servers only carry a name to sort by
+    sort_items(&mut servers, &request.query, |a, b, _| a.name.cmp(&b.name));
+
+    Ok(Json(paginate(servers, &request.query.pagination)))
+}
+
+/// Register an MCP server and connect to it
+///
+/// The server is saved even if the first connection fails; the error is
+/// reported on the returned server. Only administrators may add stdio
+/// servers, since those run a command on the host.
+pub async fn connect_server(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Json(request): Json<ConnectMcpServerRequest>,
+) -> Result<Json<McpServerResponse>, ApiError> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ApiError::validation("MCP server name cannot be empty"));
+    }
+    check_transport(&auth, &request.transport)?;
+    if state.mcp.list(&auth.user_id).iter().any(|s| s.name == name) {
+        return Err(ApiError::Conflict {
+            message: format!("An MCP server named '{}' already exists", name),
+        });
+    }
+
+    let now = chrono::Utc::now();
+    let record = McpServerRecord {
+        id: McpServerId::generate(),
+        owner_id: auth.user_id.clone(),
+        name,
+        description: request.description,
+        transport: request.transport,
+        auto_reconnect: request.auto_reconnect,
+        enabled: true,
+        created_at: now,
+        updated_at: now,
+    };
+
+    Ok(Json(state.mcp.add(record).await?))
+}
+
+/// Get one MCP server with its connection status and tools
+pub async fn get_server(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<GetMcpServerRequest>,
+) -> Result<Json<McpServerResponse>, ApiError> {
+    let id: McpServerId = parse_id(&request.id)?;
+    Ok(Json(state.mcp.server(&id, &auth.user_id)?))
+}
+
+/// Update an MCP server, connecting or disconnecting when `enabled` changes
+pub async fn update_server(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateMcpServerRequest>,
+) -> Result<Json<McpServerResponse>, ApiError> {
+    let id: McpServerId = parse_id(&id)?;
+    let mut record = state.mcp.record(&id, &auth.user_id)?;
+    check_transport(&auth, &record.transport)?;
+
+    if let Some(name) = request.name {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(ApiError::validation("MCP server name cannot be empty"));
+        }
+        if name != record.name && state.mcp.list(&auth.user_id).iter().any(|s| s.name == name) {
+            return Err(ApiError::Conflict {
+                message: format!("An MCP server named '{}' already exists", name),
+            });
+        }
+        record.name = name;
+    }
+    if let Some(description) = request.description {
+        record.description = Some(description).filter(|d| !d.is_empty());
+    }
+    if let Some(auto_reconnect) = request.auto_reconnect {
+        record.auto_reconnect = auto_reconnect;
+    }
+    if let Some(enabled) = request.enabled {
+        record.enabled = enabled;
+    }
+    record.updated_at = chrono::Utc::now();
+
+    Ok(Json(state.mcp.update(record).await?))
+}
+
+/// Stdio servers are spawned as host processes, so only administrators may
+/// configure them
+fn check_transport(auth: &AuthContext, transport: &McpTransportConfig) -> Result<(), ApiError> {
+    match transport {
+        McpTransportConfig::Stdio { .. } => auth.require_admin(),
+        McpTransportConfig::HttpSse { .. } => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pattern_core::id::UserId;
+
+    #[test]
+    fn test_stdio_transport_requires_admin() {
+        let stdio = McpTransportConfig::Stdio {
+            command: "sh".to_string(),
+            args: vec![],
+            env: None,
+        };
+        let sse = McpTransportConfig::HttpSse {
+            url: "https://mcp.example.com/sse".to_string(),
+            headers: None,
+        };
+        let user = AuthContext {
+            user_id: UserId::generate(),
+            permissions: None,
+            api_key: None,
+            rate_limit: None,
+            is_admin: false,
+        };
+
+        assert!(matches!(
+            check_transport(&user, &stdio),
+            Err(ApiError::Forbidden { .. })
+        ));
+        assert!(check_transport(&user, &sse).is_ok());
+
+        let admin = AuthContext {
+            is_admin: true,
+            ..user
+        };
+        assert!(check_transport(&admin, &stdio).is_ok());
+    }
+}
@@ -0,0 +1,250 @@
+//! Agent memory handlers
+//!
+//! Updates go through the agent's `context` tool so they follow the same
+//! permission and archiving rules as edits the agent makes itself.
+
+use axum::{
+    Extension,
+    extract::{Json, Path, State},
+};
+use pattern_api::{
+    ApiError, PaginatedResponse,
+    requests::{GetMemoryRequest, ListArchivalMemoryRequest, MemoryOperation, UpdateMemoryRequest},
+    responses::{ArchivalMemoryItem, MemoryResponse},
+};
+use pattern_core::{
+    db::ops,
+    id::{AgentId, IdType},
+    memory::{MemoryBlock, MemoryType},
+    tool::builtin::{ContextInput, CoreMemoryOperationType},
+};
+
+use super::{Params, paginate, scope};
+use crate::{middleware::AuthContext, state::AppState};
+
+/// List an agent's memory blocks, optionally of one type
+pub async fn get_memory(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<GetMemoryRequest>,
+) -> Result<Json<Vec<MemoryResponse>>, ApiError> {
+    let record = scope::agent_record(&state, &auth.user_id, &request.agent_id).await?;
+    let memory_type = request
+        .memory_type
+        .as_deref()
+        .map(parse_memory_type)
+        .transpose()?;
+
+    let mut blocks: Vec<MemoryBlock> = ops::get_agent_memories(&state.db, &record.id)
+        .await?
+        .into_iter()
+        .map(|(block, _)| block)
+        .filter(|block| memory_type.is_none_or(|t| block.memory_type == t))
+        .collect();
+    blocks.sort_by(|a, b| a.label.cmp(&b.label));
+
+    Ok(Json(
+        blocks
+            .iter()
+            .map(|block| memory_response(&record.id, block))
+            .collect(),
+    ))
+}
+
+/// Apply a memory operation and return the affected block
+pub async fn update_memory(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(agent_id): Path<AgentId>,
+    Json(request): Json<UpdateMemoryRequest>,
+) -> Result<Json<MemoryResponse>, ApiError> {
+    let record = scope::agent_record(&state, &auth.user_id, &agent_id).await?;
+    let agent = state.runtime.agent(&record.id).await?;
+
+    let (input, result_label) = context_input(request.memory_key, request.operation);
+    let params = serde_json::to_value(&input)?;
+    let output = agent.execute_tool("context", params).await?;
+
+    if output.get("success").and_then(|s| s.as_bool()) == Some(false) {
+        let message = output
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("Memory operation failed");
+        return Err(ApiError::validation(message));
+    }
+
+    let block = agent
+        .get_memory(&result_label)
+        .await?
+        .ok_or_else(|| ApiError::not_found("memory", result_label.clone()))?;
+    Ok(Json(memory_response(&record.id, &block)))
+}
+
+/// List an agent's archival memory, optionally filtered by text
+pub async fn list_archival_memory(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<ListArchivalMemoryRequest>,
+) -> Result<Json<PaginatedResponse<ArchivalMemoryItem>>, ApiError> {
+    let record = scope::agent_record(&state, &auth.user_id, &request.agent_id).await?;
+    let query = request
+        .query
+        .as_deref()
+        .map(|q| q.trim().to_lowercase())
+        .filter(|q| !q.is_empty());
+
+    let mut blocks: Vec<MemoryBlock> = ops::get_agent_memories(&state.db, &record.id)
+        .await?
+        .into_iter()
+        .map(|(block, _)| block)
+        .filter(|block| block.memory_type == MemoryType::Archival)
+        .filter(|block| {
+            query.as_ref().is_none_or(|q| {
+                block.value.to_lowercase().contains(q) || block.label.to_lowercase().contains(q)
+            })
+        })
+        .collect();
+    blocks.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    let items = blocks
+        .into_iter()
+        .map(|block| ArchivalMemoryItem {
+            id: block.id.to_key(),
+            label: block.label.to_string(),
+            content: block.value,
+            created_at: block.created_at,
+            metadata: Some(block.metadata).filter(|m| !m.is_null()),
+        })
+        .collect();
+
+    Ok(Json(paginate(items, &request.pagination)))
+}
+
+/// Translate an API memory operation into `context` tool input
+///
+/// Also returns the label of the block the operation leaves behind.
+fn context_input(memory_key: String, operation: MemoryOperation) -> (ContextInput, String) {
+    let input = ContextInput {
+        operation: CoreMemoryOperationType::Append,
+        name: None,
+        content: None,
+        old_content: None,
+        new_content: None,
+        archival_label: None,
+        archive_name: None,
+    };
+
+    match operation {
+        MemoryOperation::Append { content } => (
+            ContextInput {
+                name: Some(memory_key.clone()),
+                content: Some(content),
+                ..input
+            },
+            memory_key,
+        ),
+        MemoryOperation::Replace {
+            old_content,
+            new_content,
+        } => (
+            ContextInput {
+                operation: CoreMemoryOperationType::Replace,
+                name: Some(memory_key.clone()),
+                old_content: Some(old_content),
+                new_content: Some(new_content),
+                ..input
+            },
+            memory_key,
+        ),
+        MemoryOperation::Archive { label } => {
+            let result_label = label.clone().unwrap_or_else(|| memory_key.clone());
+            (
+                ContextInput {
+                    operation: CoreMemoryOperationType::Archive,
+                    name: Some(memory_key),
+                    archival_label: label,
+                    ..input
+                },
+                result_label,
+            )
+        }
+        MemoryOperation::LoadFromArchival { label } => (
+            ContextInput {
+                operation: CoreMemoryOperationType::Load,
+                name: Some(memory_key.clone()),
+                archival_label: Some(label),
+                ..input
+            },
+            memory_key,
+        ),
+        MemoryOperation::Swap {
+            archive_key,
+            load_label,
+        } => (
+            ContextInput {
+                operation: CoreMemoryOperationType::Swap,
+                archive_name: Some(archive_key),
+                archival_label: Some(load_label.clone()),
+                ..input
+            },
+            load_label,
+        ),
+    }
+}
+
+fn parse_memory_type(name: &str) -> Result<MemoryType, ApiError> {
+    match name.to_lowercase().as_str() {
+        "core" => Ok(MemoryType::Core),
+        "working" => Ok(MemoryType::Working),
+        "archival" | "recall" => Ok(MemoryType::Archival),
+        other => Err(ApiError::validation(format!(
+            "Unknown memory type '{}' (expected core, working or archival)",
+            other
+        ))),
+    }
+}
+
+fn memory_response(agent_id: &AgentId, block: &MemoryBlock) -> MemoryResponse {
+    MemoryResponse {
+        agent_id: agent_id.clone(),
+        memory_type: block.memory_type.to_string(),
+        content: serde_json::json!({
+            "label": block.label,
+            "value": block.value,
+            "description": block.description,
+            "pinned": block.pinned,
+            "permission": block.permission,
+        }),
+        updated_at: block.updated_at,
+        // Blocks aren't versioned yet
+        version: 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_maps_to_context_input() {
+        let (input, label) = context_input(
+            "ignored".to_string(),
+            MemoryOperation::Swap {
+                archive_key: "notes".to_string(),
+                load_label: "project".to_string(),
+            },
+        );
+
+        assert!(matches!(input.operation, CoreMemoryOperationType::Swap));
+        assert_eq!(input.archive_name.as_deref(), Some("notes"));
+        assert_eq!(input.archival_label.as_deref(), Some("project"));
+        assert_eq!(label, "project");
+    }
+
+    #[test]
+    fn test_parse_memory_type() {
+        assert_eq!(parse_memory_type("Core").unwrap(), MemoryType::Core);
+        assert_eq!(parse_memory_type("recall").unwrap(), MemoryType::Archival);
+        assert!(parse_memory_type("episodic").is_err());
+    }
+}
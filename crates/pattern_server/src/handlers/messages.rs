@@ -0,0 +1,261 @@
+//! Message history handlers
+
+use axum::{
+    Extension,
+    extract::{Json, State},
+};
+use chrono::{DateTime, Utc};
+use pattern_api::{
+    ApiError, PaginatedResponse,
+    requests::{GetMessagesRequest, SearchMessagesRequest},
+    responses::{MessageResponse, ToolCallResponse, ToolResultResponse},
+};
+use pattern_core::{
+    db::ops,
+    id::AgentId,
+    message::{ChatRole, ContentBlock, Message, MessageContent},
+};
+use surrealdb::RecordId;
+
+use super::{
+    MAX_PAGE_SIZE, Params, paginate,
+    scope::{self, Target},
+};
+use crate::{middleware::AuthContext, state::AppState};
+
+/// Recent messages for an agent, or for every member of a group
+pub async fn get_messages(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<GetMessagesRequest>,
+) -> Result<Json<PaginatedResponse<MessageResponse>>, ApiError> {
+    let agents = match scope::resolve_target(&state, &auth.user_id, &request.target).await? {
+        Target::Agent(record) => vec![record.id],
+        Target::Group(group) => group.members.into_iter().map(|(a, _)| a.id).collect(),
+    };
+
+    let limit = request.limit.clamp(1, MAX_PAGE_SIZE);
+    let filter = MessageFilter {
+        limit: Some(request.offset + limit),
+        ..Default::default()
+    };
+    let messages = collect_messages(&state, &agents, &filter).await?;
+
+    let total = messages.len() as u64;
+    let items = messages
+        .into_iter()
+        .skip(request.offset as usize)
+        .take(limit as usize)
+        .collect();
+    Ok(Json(PaginatedResponse::new(
+        items,
+        request.offset / limit + 1,
+        limit,
+        total,
+    )))
+}
+
+/// Search message history across the caller's agents
+pub async fn search_messages(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<SearchMessagesRequest>,
+) -> Result<Json<PaginatedResponse<MessageResponse>>, ApiError> {
+    let agents = match request.agent_id {
+        Some(id) => vec![scope::agent_record(&state, &auth.user_id, &id).await?.id],
+        None => scope::agent_ids(&state, &auth.user_id).await?,
+    };
+
+    let filter = MessageFilter {
+        query: request.query.filter(|q| !q.trim().is_empty()),
+        role: request.role,
+        from: request.from_date,
+        to: request.to_date,
+        limit: None,
+    };
+    let messages = collect_messages(&state, &agents, &filter).await?;
+
+    Ok(Json(paginate(messages, &request.pagination)))
+}
+
+#[derive(Debug, Default)]
+struct MessageFilter {
+    query: Option<String>,
+    role: Option<ChatRole>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    /// Most recent messages to take per agent
+    limit: Option<u32>,
+}
+
+/// Matching messages from each agent, newest first
+async fn collect_messages(
+    state: &AppState,
+    agents: &[AgentId],
+    filter: &MessageFilter,
+) -> Result<Vec<MessageResponse>, ApiError> {
+    let mut responses = Vec::new();
+    for agent_id in agents {
+        let query = agent_messages_query(agent_id, filter);
+        let messages = ops::query_messages_raw(&state.db, &query).await?;
+        responses.extend(messages.iter().map(|m| message_response(agent_id, m)));
+    }
+
+    responses.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(responses)
+}
+
+fn agent_messages_query(agent_id: &AgentId, filter: &MessageFilter) -> String {
+    let base = Message::search_complex_query(
+        filter.query.as_deref(),
+        filter.role.as_ref(),
+        None,
+        filter.from,
+        filter.to,
+    );
+    let owned = format!(
+        "id IN (SELECT VALUE out FROM agent_messages WHERE in = {})",
+        RecordId::from(agent_id)
+    );
+    let joiner = if base.contains(" WHERE ") {
+        "AND"
+    } else {
+        "WHERE"
+    };
+
+    let mut query = format!("{} {} {} ORDER BY created_at DESC", base, joiner, owned);
+    if let Some(limit) = filter.limit {
+        query.push_str(&format!(" LIMIT {}", limit));
+    }
+    query
+}
+
+/// The API view of a stored message
+pub fn message_response(agent_id: &AgentId, message: &Message) -> MessageResponse {
+    let (tool_calls, tool_results) = tool_details(&message.content);
+    MessageResponse {
+        id: message.id.clone(),
+        agent_id: agent_id.clone(),
+        role: message.role.clone(),
+        content: message.content.clone(),
+        created_at: message.created_at,
+        tool_calls,
+        tool_results,
+        metadata: serde_json::to_value(&message.metadata).ok(),
+    }
+}
+
+/// Tool calls and results carried by message content, if any
+pub fn tool_details(
+    content: &MessageContent,
+) -> (
+    Option<Vec<ToolCallResponse>>,
+    Option<Vec<ToolResultResponse>>,
+) {
+    let mut calls = Vec::new();
+    let mut results = Vec::new();
+
+    match content {
+        MessageContent::ToolCalls(tool_calls) => {
+            calls.extend(tool_calls.iter().map(|call| ToolCallResponse {
+                id: call.call_id.clone(),
+                name: call.fn_name.clone(),
+                arguments: call.fn_arguments.clone(),
+            }));
+        }
+        MessageContent::ToolResponses(responses) => {
+            results.extend(responses.iter().map(|response| ToolResultResponse {
+                tool_call_id: response.call_id.clone(),
+                result: result_value(&response.content),
+                is_error: response.is_error.unwrap_or(false),
+            }));
+        }
+        MessageContent::Blocks(blocks) => {
+            for block in blocks {
+                match block {
+                    ContentBlock::ToolUse {
+                        id, name, input, ..
+                    } => calls.push(ToolCallResponse {
+                        id: id.clone(),
+                        name: name.clone(),
+                        arguments: input.clone(),
+                    }),
+                    ContentBlock::ToolResult {
+                        tool_use_id,
+                        content,
+                        is_error,
+                        ..
+                    } => results.push(ToolResultResponse {
+                        tool_call_id: tool_use_id.clone(),
+                        result: result_value(content),
+                        is_error: is_error.unwrap_or(false),
+                    }),
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+
+    (
+        Some(calls).filter(|c| !c.is_empty()),
+        Some(results).filter(|r| !r.is_empty()),
+    )
+}
+
+/// Tool output is usually JSON; keep it structured when it is
+fn result_value(content: &str) -> serde_json::Value {
+    serde_json::from_str(content).unwrap_or_else(|_| serde_json::Value::String(content.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pattern_core::message::{ToolCall, ToolResponse};
+
+    #[test]
+    fn test_tool_details() {
+        let calls = MessageContent::ToolCalls(vec![ToolCall {
+            call_id: "call_1".to_string(),
+            fn_name: "context".to_string(),
+            fn_arguments: serde_json::json!({"operation": "append"}),
+        }]);
+        let (tool_calls, tool_results) = tool_details(&calls);
+        assert_eq!(tool_calls.unwrap()[0].name, "context");
+        assert!(tool_results.is_none());
+
+        let responses = MessageContent::ToolResponses(vec![ToolResponse {
+            call_id: "call_1".to_string(),
+            content: r#"{"success": true}"#.to_string(),
+            is_error: None,
+        }]);
+        let (_, tool_results) = tool_details(&responses);
+        let result = &tool_results.unwrap()[0];
+        assert_eq!(result.result["success"], true);
+        assert!(!result.is_error);
+
+        assert!(matches!(
+            tool_details(&MessageContent::from_text("hi")),
+            (None, None)
+        ));
+    }
+
+    #[test]
+    fn test_agent_messages_query_scopes_to_agent() {
+        let agent_id = AgentId::generate();
+        let query = agent_messages_query(
+            &agent_id,
+            &MessageFilter {
+                query: Some("hello".to_string()),
+                limit: Some(10),
+                ..Default::default()
+            },
+        );
+
+        assert!(query.starts_with("SELECT * FROM msg WHERE content @@"));
+        assert!(
+            query.contains("AND id IN (SELECT VALUE out FROM agent_messages WHERE in = agent:")
+        );
+        assert!(query.ends_with("ORDER BY created_at DESC LIMIT 10"));
+    }
+}
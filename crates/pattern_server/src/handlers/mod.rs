@@ -1,18 +1,64 @@
 //! HTTP request handlers
 
 use axum::{
-    Router,
-    routing::{get, post},
+    Router, async_trait,
+    extract::{FromRequestParts, Query, RawPathParams, Request},
+    http::request::Parts,
+    middleware::{self as axum_middleware, Next},
+    routing::{MethodRouter, get, patch, post},
 };
+use pattern_api::{
+    ApiEndpoint, ApiError, PaginatedResponse, PaginationParams,
+    requests::{ApiPermission, ListQueryParams, SortField},
+};
+use pattern_core::id::IdType;
+use serde::de::DeserializeOwned;
+use std::cmp::Ordering;
 
+pub mod agents;
+pub mod api_keys;
 pub mod auth;
+pub mod chat;
+pub mod groups;
 pub mod health;
+pub mod mcp;
+pub mod memory;
+pub mod messages;
+pub mod providers;
+pub mod scope;
+pub mod users;
+pub mod ws;
+
+use crate::{
+    middleware::{require_admin, require_auth, require_permission},
+    state::AppState,
+};
 
-use crate::state::AppState;
+/// Largest page a list endpoint will return
+const MAX_PAGE_SIZE: u32 = 100;
 
 /// Build all API routes
-pub fn routes() -> Router<AppState> {
-    use pattern_api::{ApiEndpoint, requests::*};
+pub fn routes(state: AppState) -> Router<AppState> {
+    use ApiPermission::*;
+    use pattern_api::requests::*;
+
+    let authed = |route: MethodRouter<AppState>| {
+        route.route_layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            require_auth,
+        ))
+    };
+    let scoped = |permission: ApiPermission, route: MethodRouter<AppState>| {
+        authed(route.route_layer(axum_middleware::from_fn(
+            move |request: Request, next: Next| require_permission(permission, request, next),
+        )))
+    };
+    let admin = |permission: ApiPermission, route: MethodRouter<AppState>| {
+        scoped(
+            permission,
+            route.route_layer(axum_middleware::from_fn(require_admin)),
+        )
+    };
 
     Router::new()
         // Health check
@@ -20,6 +66,298 @@ pub fn routes() -> Router<AppState> {
         // Auth endpoints
         .route(AuthRequest::PATH, post(auth::login))
         .route(RefreshTokenRequest::PATH, post(auth::refresh_token))
+        // Users (creation authenticates itself so the first user can register)
+        .route(&path::<CreateUserRequest>(), post(users::create_user))
+        .route(
+            &path::<ListUsersRequest>(),
+            admin(ManageUsers, get(users::list_users)),
+        )
+        .route(&path::<GetUserRequest>(), authed(get(users::get_user)))
+        .route(
+            &path::<UpdateUserRequest>(),
+            authed(patch(users::update_user)),
+        )
+        // Agents
+        .route(
+            &path::<ListAgentsRequest>(),
+            scoped(ReadAgents, get(agents::list_agents)),
+        )
+        .route(
+            &path::<CreateAgentRequest>(),
+            scoped(WriteAgents, post(agents::create_agent)),
+        )
+        .route(
+            &path::<GetAgentRequest>(),
+            scoped(ReadAgents, get(agents::get_agent)),
+        )
+        .route(
+            &path::<UpdateAgentRequest>(),
+            scoped(WriteAgents, patch(agents::update_agent)),
+        )
+        // Groups
+        .route(
+            &path::<ListGroupsRequest>(),
+            scoped(ReadGroups, get(groups::list_groups)),
+        )
+        .route(
+            &path::<CreateGroupRequest>(),
+            scoped(WriteGroups, post(groups::create_group)),
+        )
+        .route(
+            &path::<GetGroupRequest>(),
+            scoped(ReadGroups, get(groups::get_group)),
+        )
+        .route(
+            &path::<UpdateGroupRequest>(),
+            scoped(WriteGroups, patch(groups::update_group)),
+        )
+        // Chat and message history
+        .route(
+            &path::<SendMessageRequest>(),
+            scoped(SendMessages, post(chat::send_message)),
+        )
+        .route(
+            &path::<GetMessagesRequest>(),
+            scoped(ReadMessages, get(messages::get_messages)),
+        )
+        .route(
+            &path::<SearchMessagesRequest>(),
+            scoped(ReadMessages, get(messages::search_messages)),
+        )
+        // Memory
+        .route(
+            &path::<GetMemoryRequest>(),
+            scoped(ReadMemory, get(memory::get_memory)),
+        )
+        .route(
+            &path::<UpdateMemoryRequest>(),
+            scoped(UpdateMemory, post(memory::update_memory)),
+        )
+        .route(
+            &path::<ListArchivalMemoryRequest>(),
+            scoped(ReadMemory, get(memory::list_archival_memory)),
+        )
+        // MCP servers
+        .route(
+            &path::<ListMcpServersRequest>(),
+            scoped(ManageMcpServers, get(mcp::list_servers)),
+        )
+        .route(
+            &path::<ConnectMcpServerRequest>(),
+            scoped(ManageMcpServers, post(mcp::connect_server)),
+        )
+        .route(
+            &path::<GetMcpServerRequest>(),
+            scoped(ManageMcpServers, get(mcp::get_server)),
+        )
+        .route(
+            &path::<UpdateMcpServerRequest>(),
+            scoped(ManageMcpServers, patch(mcp::update_server)),
+        )
+        // Model providers (shared by every user)
+        .route(
+            &path::<ListModelProvidersRequest>(),
+            admin(ManageModelProviders, get(providers::list_providers)),
+        )
+        .route(
+            &path::<ConfigureModelProviderRequest>(),
+            admin(ManageModelProviders, post(providers::configure_provider)),
+        )
+        .route(
+            &path::<GetModelProviderRequest>(),
+            admin(ManageModelProviders, get(providers::get_provider)),
+        )
+        .route(
+            &path::<UpdateModelProviderRequest>(),
+            admin(ManageModelProviders, patch(providers::update_provider)),
+        )
+        // API keys
+        .route(
+            &path::<ListApiKeysRequest>(),
+            scoped(ManageApiKeys, get(api_keys::list_api_keys)),
+        )
+        .route(
+            &path::<CreateApiKeyRequest>(),
+            scoped(ManageApiKeys, post(api_keys::create_api_key)),
+        )
+        .route(
+            &path::<GetApiKeyRequest>(),
+            scoped(ManageApiKeys, get(api_keys::get_api_key)),
+        )
+        .route(
+            &path::<UpdateApiKeyRequest>(),
+            scoped(ManageApiKeys, patch(api_keys::update_api_key)),
+        )
+}
+
+/// Router path for an endpoint
+///
+/// axum's router requires captures at the same position to share a name
+/// (`/agents/{id}` vs `/agents/{agent_id}/memory`), so captures are
+/// registered positionally and [`Params`] maps them back to their names.
+fn path<E: ApiEndpoint>() -> String {
+    router_path(E::PATH)
+}
+
+fn router_path(template: &str) -> String {
+    let mut index = 0;
+    template
+        .split('/')
+        .map(|segment| {
+            if capture_name(segment).is_some() {
+                index += 1;
+                format!(":p{}", index - 1)
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn capture_name(segment: &str) -> Option<&str> {
+    segment.strip_prefix('{').and_then(|s| s.strip_suffix('}'))
+}
+
+/// An endpoint's request type, read from its path and query parameters
+///
+/// Query strings carry everything as text, which `#[serde(flatten)]` fields
+/// can't convert on their own, so numbers and booleans are parsed first.
+pub struct Params<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Params<T>
+where
+    T: ApiEndpoint + DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(mut pairs) = Query::<Vec<(String, String)>>::try_from_uri(&parts.uri)
+            .map_err(|e| ApiError::validation(format!("Invalid query string: {}", e)))?;
+        if let Ok(path) = RawPathParams::from_request_parts(parts, state).await {
+            let names = T::PATH.split('/').filter_map(capture_name);
+            pairs.extend(
+                names
+                    .zip(path.iter())
+                    .map(|(name, (_, value))| (name.to_string(), value.to_string())),
+            );
+        }
+
+        serde_json::from_value(params_to_json(&pairs, true))
+            .or_else(|_| serde_json::from_value(params_to_json(&pairs, false)))
+            .map(Params)
+            .map_err(|e| ApiError::validation(format!("Invalid parameters: {}", e)))
+    }
+}
+
+fn params_to_json(pairs: &[(String, String)], coerce: bool) -> serde_json::Value {
+    let map = pairs
+        .iter()
+        .map(|(key, value)| {
+            let value = match (coerce, value.as_str()) {
+                (true, "true") => serde_json::Value::Bool(true),
+                (true, "false") => serde_json::Value::Bool(false),
+                (true, v) => v
+                    .parse::<i64>()
+                    .map(serde_json::Value::from)
+                    .unwrap_or_else(|_| serde_json::Value::String(value.clone())),
+                (false, _) => serde_json::Value::String(value.clone()),
+            };
+            (key.clone(), value)
+        })
+        .collect();
+    serde_json::Value::Object(map)
+}
+
+/// Parse an ID given either as a bare key or with its table prefix
+pub fn parse_id<T: IdType>(raw: &str) -> Result<T, ApiError> {
+    let key = raw
+        .strip_prefix(T::PREFIX)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .unwrap_or(raw);
+    T::from_key(key).map_err(|e| ApiError::validation(e.to_string()))
+}
+
+/// Sort list items by the requested field, oldest first unless told otherwise
+///
+/// `compare` orders two items by the given field; fields that don't apply to
+/// the resource should fall back to creation time.
+pub fn sort_items<T>(
+    items: &mut [T],
+    query: &ListQueryParams,
+    compare: impl Fn(&T, &T, &SortField) -> Ordering,
+) {
+    let field = query.sort_by.clone().unwrap_or(SortField::CreatedAt);
+    items.sort_by(|a, b| {
+        let ordering = compare(a, b, &field);
+        if query.sort_desc {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+/// Slice an already-sorted list into the requested page
+pub fn paginate<T>(items: Vec<T>, pagination: &PaginationParams) -> PaginatedResponse<T> {
+    let limit = pagination.limit.clamp(1, MAX_PAGE_SIZE);
+    let page = pagination.page.max(1);
+    let total = items.len() as u64;
+    let items = items
+        .into_iter()
+        .skip(((page - 1) * limit) as usize)
+        .take(limit as usize)
+        .collect();
+
+    PaginatedResponse::new(items, page, limit, total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pattern_api::requests::ListAgentsRequest;
+    use pattern_core::id::AgentId;
+
+    #[test]
+    fn test_router_path_translates_captures() {
+        assert_eq!(router_path("/agents"), "/agents");
+        assert_eq!(router_path("/agents/{id}"), "/agents/:p0");
+        assert_eq!(
+            router_path("/agents/{agent_id}/memory/archival"),
+            "/agents/:p0/memory/archival"
+        );
+    }
+
+    #[test]
+    fn test_flattened_params_deserialize() {
+        let pairs = vec![
+            ("page".to_string(), "2".to_string()),
+            ("limit".to_string(), "5".to_string()),
+            ("sort_desc".to_string(), "true".to_string()),
+        ];
+        let request: ListAgentsRequest =
+            serde_json::from_value(params_to_json(&pairs, true)).unwrap();
+
+        assert_eq!(request.query.pagination.page, 2);
+        assert_eq!(request.query.pagination.limit, 5);
+        assert!(request.query.sort_desc);
+    }
+
+    #[test]
+    fn test_parse_id_accepts_prefix() {
+        let id: AgentId = parse_id("agent:abc123").unwrap();
+        assert_eq!(id.0, "abc123");
+        let id: AgentId = parse_id("abc123").unwrap();
+        assert_eq!(id.0, "abc123");
+    }
 
-    // TODO: Add remaining endpoints
+    #[test]
+    fn test_paginate() {
+        let page = paginate((1..=7).collect(), &PaginationParams { page: 2, limit: 3 });
+        assert_eq!(page.items, vec![4, 5, 6]);
+        assert_eq!(page.total, 7);
+        assert_eq!(page.total_pages, 3);
+    }
 }
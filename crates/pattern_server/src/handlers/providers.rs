@@ -0,0 +1,287 @@
+//! Model provider handlers
+//!
+//! Providers are server-wide and identified by their lowercase name. Keys
+//! configured here take precedence over the server's environment, so only
+//! server administrators can reach these endpoints.
+
+use axum::extract::{Json, State};
+use pattern_api::{
+    ApiError, PaginatedResponse,
+    requests::{
+        ConfigureModelProviderRequest, GetModelProviderRequest, ListModelProvidersRequest,
+        ModelProviderConfig, SortField, UpdateModelProviderRequest,
+    },
+    responses::{ModelInfo, ModelProviderResponse, ModelProviderStatus},
+};
+use pattern_core::{db::ops, model};
+
+use super::{Params, paginate, sort_items};
+use crate::{
+    models::{ModelProviderId, ModelProviderRecord},
+    runtime::{has_env_credentials, is_known_provider, provider_names},
+    state::AppState,
+};
+
+/// List providers that are configured here or through the environment
+pub async fn list_providers(
+    State(state): State<AppState>,
+    Params(request): Params<ListModelProvidersRequest>,
+) -> Result<Json<PaginatedResponse<ModelProviderResponse>>, ApiError> {
+    let mut providers = provider_responses(&state).await?;
+
+    if let Some(enabled) = request.enabled {
+        providers.retain(|p| p.enabled == enabled);
+    }
+    sort_items(&mut providers, &request.query, |a, b, field| match field {
+        SortField::UpdatedAt => a.last_validated.cmp(&b.last_validated),
+        _ => a.provider.cmp(&b.provider),
+    });
+
+    Ok(Json(paginate(providers, &request.query.pagination)))
+}
+
+/// Store credentials for a provider and make its models available
+pub async fn configure_provider(
+    State(state): State<AppState>,
+    Json(request): Json<ConfigureModelProviderRequest>,
+) -> Result<Json<ModelProviderResponse>, ApiError> {
+    let provider = request.provider.trim().to_lowercase();
+    let api_key = api_key_from_config(request.config)?;
+
+    let mut record = load_or_new(&state, &provider).await?;
+    record.encrypted_api_key = Some(state.secrets.encrypt(&api_key)?);
+    record.enabled = true;
+    if request.set_as_default {
+        record.is_default = true;
+    }
+
+    save(&state, record).await
+}
+
+/// Get one provider with its models
+pub async fn get_provider(
+    State(state): State<AppState>,
+    Params(request): Params<GetModelProviderRequest>,
+) -> Result<Json<ModelProviderResponse>, ApiError> {
+    let provider = request.id.trim().to_lowercase();
+    provider_responses(&state)
+        .await?
+        .into_iter()
+        .find(|p| p.id == provider)
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("model_provider", request.id))
+}
+
+/// Change a provider's credentials, enable it or make it the default
+///
+/// The update body has no ID, so it's read from the path the get endpoint shares.
+pub async fn update_provider(
+    State(state): State<AppState>,
+    Params(GetModelProviderRequest { id }): Params<GetModelProviderRequest>,
+    Json(request): Json<UpdateModelProviderRequest>,
+) -> Result<Json<ModelProviderResponse>, ApiError> {
+    let provider = id.trim().to_lowercase();
+    let mut record = load_or_new(&state, &provider).await?;
+
+    if let Some(config) = request.config {
+        let api_key = api_key_from_config(config)?;
+        record.encrypted_api_key = Some(state.secrets.encrypt(&api_key)?);
+    }
+    if let Some(enabled) = request.enabled {
+        record.enabled = enabled;
+    }
+    if let Some(is_default) = request.set_as_default {
+        record.is_default = is_default;
+    }
+    if record.encrypted_api_key.is_none() && !has_env_credentials(&provider) {
+        return Err(ApiError::validation(format!(
+            "No API key configured for '{}'",
+            provider
+        )));
+    }
+
+    save(&state, record).await
+}
+
+/// Persist a provider, reapply configuration and report the result
+async fn save(
+    state: &AppState,
+    mut record: ModelProviderRecord,
+) -> Result<Json<ModelProviderResponse>, ApiError> {
+    record.updated_at = chrono::Utc::now();
+    let exists = ops::get_entity::<ModelProviderRecord, _>(&state.db, &record.id)
+        .await?
+        .is_some();
+    let record = if exists {
+        ops::update_entity::<ModelProviderRecord, _>(&state.db, &record).await?
+    } else {
+        ops::create_entity::<ModelProviderRecord, _>(&state.db, &record).await?
+    };
+
+    // Only one default at a time
+    let mut records = ops::list_entities::<ModelProviderRecord, _>(&state.db).await?;
+    if record.is_default {
+        for other in records
+            .iter_mut()
+            .filter(|r| r.id != record.id && r.is_default)
+        {
+            other.is_default = false;
+            ops::update_entity::<ModelProviderRecord, _>(&state.db, &*other).await?;
+        }
+    }
+    state
+        .runtime
+        .apply_providers(&records, &state.secrets)
+        .await;
+
+    // A provider whose models list now is considered validated
+    let validated = state
+        .runtime
+        .list_models()
+        .await
+        .map(|models| {
+            models
+                .iter()
+                .any(|m| m.provider.eq_ignore_ascii_case(&record.provider))
+        })
+        .unwrap_or(false);
+    if validated && record.enabled {
+        let mut record = record.clone();
+        record.last_validated = Some(chrono::Utc::now());
+        ops::update_entity::<ModelProviderRecord, _>(&state.db, &record).await?;
+    }
+
+    provider_responses(state)
+        .await?
+        .into_iter()
+        .find(|p| p.id == record.provider)
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("model_provider", record.provider.clone()))
+}
+
+async fn load_or_new(state: &AppState, provider: &str) -> Result<ModelProviderRecord, ApiError> {
+    if !is_known_provider(provider) {
+        return Err(ApiError::validation(format!(
+            "Unknown model provider '{}' (expected one of: {})",
+            provider,
+            provider_names().collect::<Vec<_>>().join(", ")
+        )));
+    }
+
+    let id = ModelProviderId(provider.to_string());
+    if let Some(record) = ops::get_entity::<ModelProviderRecord, _>(&state.db, &id).await? {
+        return Ok(record);
+    }
+
+    let now = chrono::Utc::now();
+    Ok(ModelProviderRecord {
+        id,
+        provider: provider.to_string(),
+        encrypted_api_key: None,
+        enabled: true,
+        is_default: false,
+        last_validated: None,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+fn api_key_from_config(config: ModelProviderConfig) -> Result<String, ApiError> {
+    match config {
+        ModelProviderConfig::ApiKey {
+            api_key,
+            base_url,
+            org_id,
+        } => {
+            if base_url.is_some() || org_id.is_some() {
+                return Err(ApiError::validation(
+                    "Custom base URLs and organization IDs are not supported yet",
+                ));
+            }
+            if api_key.trim().is_empty() {
+                return Err(ApiError::validation("API key cannot be empty"));
+            }
+            Ok(api_key.trim().to_string())
+        }
+        ModelProviderConfig::OAuth { .. } => Err(ApiError::validation(
+            "OAuth provider configuration is not supported yet",
+        )),
+        ModelProviderConfig::Local { .. } => Err(ApiError::validation(
+            "Local model configuration is not supported yet",
+        )),
+    }
+}
+
+/// Every provider with credentials, with the models it currently offers
+async fn provider_responses(state: &AppState) -> Result<Vec<ModelProviderResponse>, ApiError> {
+    let records = ops::list_entities::<ModelProviderRecord, _>(&state.db).await?;
+    let models = state.runtime.list_models().await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to list models: {}", e);
+        Vec::new()
+    });
+
+    let responses = provider_names()
+        .filter_map(|name| {
+            let record = records.iter().find(|r| r.provider == name);
+            let has_key = record.is_some_and(|r| r.encrypted_api_key.is_some());
+            if !has_key && !has_env_credentials(name) {
+                return None;
+            }
+
+            let available_models: Vec<ModelInfo> = models
+                .iter()
+                .filter(|m| m.provider.eq_ignore_ascii_case(name))
+                .map(model_info)
+                .collect();
+            let enabled = record.is_none_or(|r| r.enabled);
+
+            Some(ModelProviderResponse {
+                id: name.to_string(),
+                provider: name.to_string(),
+                enabled,
+                is_default: record.is_some_and(|r| r.is_default),
+                status: if !enabled || !available_models.is_empty() {
+                    ModelProviderStatus::Active
+                } else {
+                    ModelProviderStatus::Error
+                },
+                available_models,
+                last_validated: record.and_then(|r| r.last_validated),
+            })
+        })
+        .collect();
+
+    Ok(responses)
+}
+
+fn model_info(model: &model::ModelInfo) -> ModelInfo {
+    ModelInfo {
+        id: model.id.clone(),
+        name: model.name.clone(),
+        context_length: model.context_window as u32,
+        input_cost_per_1k: model.cost_per_1k_prompt_tokens.map(|c| c as f32),
+        output_cost_per_1k: model.cost_per_1k_completion_tokens.map(|c| c as f32),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_api_key_configs_accepted() {
+        let key = api_key_from_config(ModelProviderConfig::ApiKey {
+            api_key: " sk-test ".to_string(),
+            base_url: None,
+            org_id: None,
+        });
+        assert_eq!(key.unwrap(), "sk-test");
+
+        let oauth = api_key_from_config(ModelProviderConfig::OAuth {
+            client_id: "id".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_url: None,
+        });
+        assert!(matches!(oauth, Err(ApiError::ValidationError { .. })));
+    }
+}
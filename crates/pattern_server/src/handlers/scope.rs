@@ -0,0 +1,186 @@
+//! Resolving which agents and groups a caller can reach
+//!
+//! Everything a user drives through the API lives in their constellation, so
+//! lookups by ID go through it and report anything outside it as not found.
+
+use pattern_api::{ApiError, requests::ChatTarget};
+use pattern_core::{
+    agent::AgentRecord,
+    coordination::{AgentGroup, Constellation, groups::ConstellationMembership},
+    db::{DatabaseError, entity::DbEntity, ops},
+    id::{AgentId, GroupId, RelationId, UserId},
+};
+use surrealdb::RecordId;
+
+use crate::state::AppState;
+
+/// Convert a raw SurrealDB error into an API error
+pub fn db_error(e: surrealdb::Error) -> ApiError {
+    DatabaseError::QueryFailed(e).into()
+}
+
+/// The caller's constellation, created on first use
+pub async fn constellation(state: &AppState, user: &UserId) -> Result<Constellation, ApiError> {
+    Ok(ops::get_or_create_constellation(&state.db, user).await?)
+}
+
+/// IDs of every agent in the user's constellation
+pub async fn agent_ids(state: &AppState, user: &UserId) -> Result<Vec<AgentId>, ApiError> {
+    let constellation = constellation(state, user).await?;
+    let ids: Vec<RecordId> = state
+        .db
+        .query("SELECT VALUE out FROM constellation_agents WHERE in = $constellation")
+        .bind(("constellation", RecordId::from(&constellation.id)))
+        .await
+        .map_err(db_error)?
+        .take(0)
+        .map_err(db_error)?;
+
+    Ok(ids.into_iter().map(AgentId::from_record).collect())
+}
+
+/// IDs of every group in the user's constellation
+pub async fn group_ids(state: &AppState, user: &UserId) -> Result<Vec<GroupId>, ApiError> {
+    let constellation = constellation(state, user).await?;
+    let ids: Vec<RecordId> = state
+        .db
+        .query("SELECT VALUE out FROM composed_of WHERE in = $constellation")
+        .bind(("constellation", RecordId::from(&constellation.id)))
+        .await
+        .map_err(db_error)?
+        .take(0)
+        .map_err(db_error)?;
+
+    Ok(ids.into_iter().map(GroupId::from_record).collect())
+}
+
+/// Load an agent record the user can reach
+pub async fn agent_record(
+    state: &AppState,
+    user: &UserId,
+    id: &AgentId,
+) -> Result<AgentRecord, ApiError> {
+    let not_found = || ApiError::not_found("agent", id.to_string());
+    if !agent_ids(state, user).await?.contains(id) {
+        return Err(not_found());
+    }
+
+    ops::get_entity::<AgentRecord, _>(&state.db, id)
+        .await?
+        .ok_or_else(not_found)
+}
+
+/// Find an agent in the user's constellation by name
+pub async fn agent_by_name(
+    state: &AppState,
+    user: &UserId,
+    name: &str,
+) -> Result<Option<AgentRecord>, ApiError> {
+    let ids = agent_ids(state, user).await?;
+    let records: Vec<<AgentRecord as DbEntity>::DbModel> = state
+        .db
+        .query("SELECT * FROM agent WHERE name = $name")
+        .bind(("name", name.to_string()))
+        .await
+        .map_err(db_error)?
+        .take(0)
+        .map_err(db_error)?;
+
+    for record in records {
+        let record = AgentRecord::from_db_model(record).map_err(DatabaseError::from)?;
+        if ids.contains(&record.id) {
+            return Ok(Some(record));
+        }
+    }
+    Ok(None)
+}
+
+/// Load a group, with its members, that the user can reach
+pub async fn group(state: &AppState, user: &UserId, id: &GroupId) -> Result<AgentGroup, ApiError> {
+    let not_found = || ApiError::not_found("group", id.to_string());
+    if !group_ids(state, user).await?.contains(id) {
+        return Err(not_found());
+    }
+
+    AgentGroup::load_with_relations(&state.db, id)
+        .await?
+        .ok_or_else(not_found)
+}
+
+/// Find a group in the user's constellation by name
+pub async fn group_by_name(
+    state: &AppState,
+    user: &UserId,
+    name: &str,
+) -> Result<Option<AgentGroup>, ApiError> {
+    for id in group_ids(state, user).await? {
+        if let Some(group) = ops::get_group(&state.db, &id).await? {
+            if group.name == name {
+                return Ok(AgentGroup::load_with_relations(&state.db, &id).await?);
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Make a newly created agent part of the user's constellation
+pub async fn add_agent(
+    state: &AppState,
+    user: &UserId,
+    agent_id: &AgentId,
+) -> Result<(), ApiError> {
+    let constellation = constellation(state, user).await?;
+    let membership = ConstellationMembership {
+        id: RelationId::generate(),
+        in_id: constellation.id,
+        out_id: agent_id.clone(),
+        joined_at: chrono::Utc::now(),
+        is_primary: false,
+    };
+    ops::create_relation_typed(&state.db, &membership).await?;
+
+    Ok(())
+}
+
+/// Where a chat message or history request is aimed
+pub enum Target {
+    Agent(AgentRecord),
+    Group(AgentGroup),
+}
+
+/// Resolve a chat target by agent ID, group ID, agent name, then group name
+///
+/// Any string deserializes as an agent ID, so every variant is treated as
+/// free text and tried against each kind of target in turn.
+pub async fn resolve_target(
+    state: &AppState,
+    user: &UserId,
+    target: &ChatTarget,
+) -> Result<Target, ApiError> {
+    let raw = match target {
+        ChatTarget::Agent(id) => id.0.as_str(),
+        ChatTarget::Group(id) => id.0.as_str(),
+        ChatTarget::Name(name) => name.as_str(),
+    };
+
+    let agent_key = raw.strip_prefix("agent:").unwrap_or(raw);
+    let agent_id = AgentId(agent_key.to_string());
+    if agent_ids(state, user).await?.contains(&agent_id) {
+        return Ok(Target::Agent(agent_record(state, user, &agent_id).await?));
+    }
+
+    let group_key = raw.strip_prefix("group:").unwrap_or(raw);
+    let group_id = GroupId(group_key.to_string());
+    if group_ids(state, user).await?.contains(&group_id) {
+        return Ok(Target::Group(group(state, user, &group_id).await?));
+    }
+
+    if let Some(record) = agent_by_name(state, user, raw).await? {
+        return Ok(Target::Agent(record));
+    }
+    if let Some(group) = group_by_name(state, user, raw).await? {
+        return Ok(Target::Group(group));
+    }
+
+    Err(ApiError::not_found("chat target", raw))
+}
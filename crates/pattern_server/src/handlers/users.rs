@@ -0,0 +1,345 @@
+//! User management handlers
+
+use axum::{
+    Extension,
+    extract::{Json, Path, State},
+    http::HeaderMap,
+};
+use pattern_api::{
+    ApiError, PaginatedResponse,
+    requests::{
+        ApiPermission, CreateUserRequest, GetUserRequest, ListUsersRequest, SortField,
+        UpdateUserRequest,
+    },
+    responses::UserResponse,
+};
+use pattern_core::{
+    db::{DatabaseError, entity::DbEntity, ops},
+    id::UserId,
+};
+use surrealdb::{RecordId, Surreal, engine::any::Any};
+
+use super::{Params, auth::revoke_token_families, paginate, scope::db_error, sort_items};
+use crate::{
+    auth::hash_password,
+    middleware::{AuthContext, authenticate},
+    models::ServerUser,
+    state::AppState,
+};
+
+/// Shortest password accepted for new or changed accounts
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Record claimed by the request registering the first account
+const BOOTSTRAP_RECORD: (&str, &str) = ("server_bootstrap", "first_user");
+
+/// Register a new user
+///
+/// The first account can be created without credentials and becomes the
+/// server administrator; after that only an administrator with
+/// `manage_users` can register accounts.
+pub async fn create_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateUserRequest>,
+) -> Result<Json<UserResponse>, ApiError> {
+    // Concurrent first registrations both see no users, so only the one that
+    // claims the bootstrap record skips authentication
+    let is_first = !has_registered_users(&state).await? && claim_bootstrap(&state.db).await?;
+    if !is_first {
+        let auth = authenticate(&state, &headers).await?;
+        auth.require(ApiPermission::ManageUsers)?;
+        auth.require_admin()?;
+    }
+
+    let result = register(&state, request, is_first).await;
+    if is_first && result.is_err() {
+        // Let the next attempt bootstrap instead
+        release_bootstrap(&state.db).await?;
+    }
+    result
+}
+
+async fn register(
+    state: &AppState,
+    request: CreateUserRequest,
+    is_first: bool,
+) -> Result<Json<UserResponse>, ApiError> {
+    let username = request.username.trim().to_string();
+    if username.is_empty() {
+        return Err(ApiError::validation("Username cannot be empty"));
+    }
+    validate_password(&request.password)?;
+    if find_by_username(state, &username).await?.is_some() {
+        return Err(ApiError::Conflict {
+            message: format!("Username '{}' is already taken", username),
+        });
+    }
+
+    let now = chrono::Utc::now();
+    let user = ServerUser {
+        id: UserId::generate(),
+        discord_id: None,
+        created_at: now,
+        updated_at: now,
+        settings: Default::default(),
+        metadata: Default::default(),
+        username,
+        password_hash: hash_password(&request.password)?,
+        email: request.email,
+        display_name: request.display_name,
+        is_active: true,
+        is_admin: is_first,
+        owned_agent_ids: vec![],
+        created_task_ids: vec![],
+        memory_ids: vec![],
+        scheduled_event_ids: vec![],
+        api_keys: vec![],
+        refresh_token_families: vec![],
+    };
+    let user = ops::create_entity::<ServerUser, _>(&state.db, &user).await?;
+
+    Ok(Json(user.to_response()))
+}
+
+/// Claim the right to register the first account
+///
+/// Creating a record that already exists fails, so exactly one caller wins.
+async fn claim_bootstrap(db: &Surreal<Any>) -> Result<bool, ApiError> {
+    let response = db
+        .query("CREATE type::thing($table, $key) SET claimed_at = time::now()")
+        .bind(("table", BOOTSTRAP_RECORD.0))
+        .bind(("key", BOOTSTRAP_RECORD.1))
+        .await
+        .map_err(db_error)?;
+
+    Ok(response.check().is_ok())
+}
+
+async fn release_bootstrap(db: &Surreal<Any>) -> Result<(), ApiError> {
+    db.query("DELETE type::thing($table, $key)")
+        .bind(("table", BOOTSTRAP_RECORD.0))
+        .bind(("key", BOOTSTRAP_RECORD.1))
+        .await
+        .map_err(db_error)?
+        .check()
+        .map_err(db_error)?;
+
+    Ok(())
+}
+
+/// List registered users
+pub async fn list_users(
+    State(state): State<AppState>,
+    Params(request): Params<ListUsersRequest>,
+) -> Result<Json<PaginatedResponse<UserResponse>>, ApiError> {
+    let mut users: Vec<ServerUser> = registered_users(&state)
+        .await?
+        .into_iter()
+        .filter(|u| {
+            request
+                .query
+                .is_active
+                .is_none_or(|active| u.is_active == active)
+        })
+        .collect();
+
+    sort_items(&mut users, &request.query, |a, b, field| match field {
+        SortField::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+        SortField::Name => a.username.cmp(&b.username),
+        _ => a.created_at.cmp(&b.created_at),
+    });
+
+    let users = users.iter().map(ServerUser::to_response).collect();
+    Ok(Json(paginate(users, &request.query.pagination)))
+}
+
+/// Get a user's profile
+pub async fn get_user(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Params(request): Params<GetUserRequest>,
+) -> Result<Json<UserResponse>, ApiError> {
+    let user = load_visible_user(&state, &auth, &request.id).await?;
+    Ok(Json(user.to_response()))
+}
+
+/// Update a user's profile or password
+///
+/// Changing the password revokes the user's refresh tokens.
+pub async fn update_user(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(id): Path<UserId>,
+    Json(request): Json<UpdateUserRequest>,
+) -> Result<Json<UserResponse>, ApiError> {
+    let mut user = load_visible_user(&state, &auth, &id).await?;
+
+    if let Some(email) = request.email {
+        user.email = Some(email).filter(|e| !e.is_empty());
+    }
+    if let Some(display_name) = request.display_name {
+        user.display_name = Some(display_name).filter(|n| !n.is_empty());
+    }
+    let password_changed = request.password.is_some();
+    if let Some(password) = request.password {
+        validate_password(&password)?;
+        user.password_hash = hash_password(&password)?;
+    }
+    user.updated_at = chrono::Utc::now();
+
+    let user = ops::update_entity::<ServerUser, _>(&state.db, &user).await?;
+    if password_changed {
+        revoke_token_families(&state, &user.id).await?;
+    }
+    Ok(Json(user.to_response()))
+}
+
+/// Users may see themselves; anyone else must be an administrator with
+/// `manage_users`
+async fn load_visible_user(
+    state: &AppState,
+    auth: &AuthContext,
+    id: &UserId,
+) -> Result<ServerUser, ApiError> {
+    if &auth.user_id != id {
+        auth.require(ApiPermission::ManageUsers)?;
+        auth.require_admin()?;
+    }
+
+    ops::get_entity::<ServerUser, _>(&state.db, id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("user", id.to_string()))
+}
+
+fn validate_password(password: &str) -> Result<(), ApiError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Password must be at least {} characters",
+            MIN_PASSWORD_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// Whether any account with login credentials exists
+async fn has_registered_users(state: &AppState) -> Result<bool, ApiError> {
+    let ids: Vec<RecordId> = state
+        .db
+        .query("SELECT VALUE id FROM user WHERE username != NONE LIMIT 1")
+        .await
+        .map_err(db_error)?
+        .take(0)
+        .map_err(db_error)?;
+
+    Ok(!ids.is_empty())
+}
+
+/// Users with login credentials
+///
+/// Users created by the CLI or Discord bot have no username and are skipped.
+async fn registered_users(state: &AppState) -> Result<Vec<ServerUser>, ApiError> {
+    let users: Vec<<ServerUser as DbEntity>::DbModel> = state
+        .db
+        .query("SELECT * FROM user WHERE username != NONE")
+        .await
+        .map_err(db_error)?
+        .take(0)
+        .map_err(db_error)?;
+
+    users
+        .into_iter()
+        .map(|u| ServerUser::from_db_model(u).map_err(|e| DatabaseError::from(e).into()))
+        .collect()
+}
+
+async fn find_by_username(
+    state: &AppState,
+    username: &str,
+) -> Result<Option<ServerUser>, ApiError> {
+    let users: Vec<<ServerUser as DbEntity>::DbModel> = state
+        .db
+        .query("SELECT * FROM user WHERE username = $username LIMIT 1")
+        .bind(("username", username.to_string()))
+        .await
+        .map_err(db_error)?
+        .take(0)
+        .map_err(db_error)?;
+
+    match users.into_iter().next() {
+        Some(user) => Ok(Some(
+            ServerUser::from_db_model(user).map_err(DatabaseError::from)?,
+        )),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bootstrap_claimed_once() {
+        let db = surrealdb::engine::any::connect("mem://").await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+
+        let claims = futures::future::join_all((0..8).map(|_| claim_bootstrap(&db))).await;
+        let won = claims.into_iter().filter(|c| *c.as_ref().unwrap()).count();
+        assert_eq!(won, 1);
+
+        release_bootstrap(&db).await.unwrap();
+        assert!(claim_bootstrap(&db).await.unwrap());
+    }
+
+    fn new_user(username: &str) -> CreateUserRequest {
+        CreateUserRequest {
+            username: username.to_string(),
+            password: "correct horse battery".to_string(),
+            email: None,
+            display_name: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_admin_cannot_update_other_users() {
+        let state = AppState::new(crate::config::ServerConfig::default())
+            .await
+            .unwrap();
+        let admin = register(&state, new_user("admin"), true).await.unwrap().0;
+        let user = register(&state, new_user("user"), false).await.unwrap().0;
+
+        let auth = AuthContext {
+            user_id: user.id.clone(),
+            permissions: None,
+            api_key: None,
+            rate_limit: None,
+            is_admin: false,
+        };
+        let result = update_user(
+            State(state.clone()),
+            Extension(auth.clone()),
+            Path(admin.id.clone()),
+            Json(UpdateUserRequest {
+                email: None,
+                display_name: None,
+                password: Some("taken over by user".to_string()),
+            }),
+        )
+        .await;
+        assert!(matches!(result, Err(ApiError::Forbidden { .. })));
+
+        // Their own account is still theirs to change
+        let own = update_user(
+            State(state),
+            Extension(auth),
+            Path(user.id),
+            Json(UpdateUserRequest {
+                email: None,
+                display_name: Some("User".to_string()),
+                password: None,
+            }),
+        )
+        .await;
+        assert!(own.is_ok());
+    }
+}
@@ -0,0 +1,507 @@
+//! WebSocket live event stream
+//!
+//! Clients connect to `/ws`, then subscribe to agents, groups or everything
+//! in their constellation. Activity comes from the shared [`EventHub`]
+//! broadcast; memory updates and queued messages come from SurrealDB live
+//! queries started for each subscribed agent.
+//!
+//! [`EventHub`]: crate::events::EventHub
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{
+        Query, State,
+        ws::{Message as WsMessage, WebSocket, WebSocketUpgrade},
+    },
+    http::HeaderMap,
+    response::Response,
+};
+use futures::{SinkExt, StreamExt, stream::SplitSink};
+use pattern_api::{
+    ApiError,
+    events::{SubscriptionConfirmation, WebSocketCommand, WebSocketEvent, WebSocketMessage},
+    requests::ApiPermission,
+};
+use pattern_core::{
+    db::ops,
+    id::{AgentId, GroupId, IdType, MessageId},
+    message::{ChatRole, MessageContent},
+};
+use serde::Deserialize;
+use surrealdb::{Action, Surreal, engine::any::Any};
+use tokio::{
+    sync::{broadcast::error::RecvError, mpsc},
+    task::AbortHandle,
+};
+
+use super::scope;
+use crate::{
+    auth::API_KEY_PREFIX,
+    events::LiveEvent,
+    middleware::{AuthContext, authenticate, authenticate_token},
+    state::AppState,
+};
+
+/// How often the server pings an idle connection
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Connections silent for this long are closed
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(90);
+
+#[derive(Debug, Deserialize)]
+pub struct WebSocketParams {
+    /// Access token, for clients that can't set headers
+    ///
+    /// API keys are long-lived, so they are only accepted in the header where
+    /// they stay out of proxy and access logs.
+    pub token: Option<String>,
+}
+
+/// Authenticate and upgrade to a WebSocket connection
+pub async fn websocket(
+    State(state): State<AppState>,
+    Query(params): Query<WebSocketParams>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    // Browsers can't set headers on WebSocket requests, so accept a query token too
+    let auth = match params.token {
+        Some(token) => {
+            check_query_token(&token)?;
+            authenticate_token(&state, &token).await?
+        }
+        None => authenticate(&state, &headers).await?,
+    };
+    if let (Some(key_id), Some(limit)) = (&auth.api_key, auth.rate_limit) {
+        state.rate_limiter.check(key_id, limit)?;
+    }
+
+    Ok(upgrade.on_upgrade(move |socket| run(state, auth, socket)))
+}
+
+/// Refuse raw API keys passed in the URL
+fn check_query_token(token: &str) -> Result<(), ApiError> {
+    if token.starts_with(API_KEY_PREFIX) {
+        return Err(ApiError::Unauthorized {
+            message: Some(
+                "API keys must be sent in the Authorization header, not the query string"
+                    .to_string(),
+            ),
+        });
+    }
+    Ok(())
+}
+
+async fn run(state: AppState, auth: AuthContext, socket: WebSocket) {
+    let (mut sender, mut receiver) = socket.split();
+    let (local_tx, mut local_rx) = mpsc::channel(256);
+    let mut broadcast = state.events.subscribe();
+    let mut connection = Connection {
+        state: state.clone(),
+        auth,
+        local: local_tx,
+        agents: HashMap::new(),
+        groups: HashMap::new(),
+    };
+
+    let connected = WebSocketEvent::Connected {
+        user_id: connection.auth.user_id.clone(),
+        session_id: uuid::Uuid::new_v4().to_string(),
+        timestamp: chrono::Utc::now(),
+    };
+    if send(&mut sender, connected).await.is_err() {
+        return;
+    }
+
+    let mut heartbeat = tokio::time::interval(PING_INTERVAL);
+    heartbeat.tick().await;
+    let mut last_seen = Instant::now();
+
+    loop {
+        let event = tokio::select! {
+            incoming = receiver.next() => {
+                last_seen = Instant::now();
+                match incoming {
+                    Some(Ok(WsMessage::Text(text))) => match parse_command(&text) {
+                        Ok(command) => connection.handle(command).await,
+                        Err(e) => Some(error_event("invalid_command", e.to_string())),
+                    },
+                    Some(Ok(WsMessage::Binary(_))) => Some(error_event(
+                        "invalid_command",
+                        "Commands must be sent as text frames".to_string(),
+                    )),
+                    // Protocol pings are answered by axum
+                    Some(Ok(WsMessage::Ping(_) | WsMessage::Pong(_))) => None,
+                    Some(Ok(WsMessage::Close(_))) | Some(Err(_)) | None => break,
+                }
+            }
+            live = broadcast.recv() => match live {
+                Ok(live) if connection.wants(&live) => Some(live.event.clone()),
+                Ok(_) => None,
+                Err(RecvError::Lagged(skipped)) => Some(error_event(
+                    "lagged",
+                    format!("Connection fell behind; {} events were dropped", skipped),
+                )),
+                Err(RecvError::Closed) => break,
+            },
+            Some(event) = local_rx.recv() => Some(event),
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > CLIENT_TIMEOUT {
+                    tracing::debug!("Closing idle WebSocket for {}", connection.auth.user_id);
+                    break;
+                }
+                Some(WebSocketEvent::Ping {
+                    timestamp: chrono::Utc::now(),
+                })
+            }
+        };
+
+        if let Some(event) = event {
+            if send(&mut sender, event).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    connection.close();
+}
+
+async fn send(
+    sender: &mut SplitSink<WebSocket, WsMessage>,
+    event: WebSocketEvent,
+) -> Result<(), axum::Error> {
+    let text = match serde_json::to_string(&WebSocketMessage::new(event)) {
+        Ok(text) => text,
+        Err(e) => {
+            tracing::warn!("Failed to serialize WebSocket event: {}", e);
+            return Ok(());
+        }
+    };
+    sender.send(WsMessage::Text(text)).await
+}
+
+/// Parse a command, with or without the `WebSocketMessage` envelope
+fn parse_command(text: &str) -> Result<WebSocketCommand, serde_json::Error> {
+    serde_json::from_str::<WebSocketMessage<WebSocketCommand>>(text)
+        .map(|message| message.payload)
+        .or_else(|_| serde_json::from_str(text))
+}
+
+/// One client's subscriptions
+struct Connection {
+    state: AppState,
+    auth: AuthContext,
+    /// Events from this connection's own live queries
+    local: mpsc::Sender<WebSocketEvent>,
+    /// Subscribed agents with the live queries running for them
+    agents: HashMap<AgentId, Vec<AbortHandle>>,
+    /// Subscribed groups with their members at subscription time
+    groups: HashMap<GroupId, HashSet<AgentId>>,
+}
+
+impl Connection {
+    /// Apply a command, returning the reply to send if there is one
+    async fn handle(&mut self, command: WebSocketCommand) -> Option<WebSocketEvent> {
+        match command {
+            WebSocketCommand::SubscribeAgent { agent_id } => {
+                let result = self.subscribe_agent(&agent_id).await;
+                Some(confirmation("agent", agent_id.to_string(), result, None))
+            }
+            WebSocketCommand::UnsubscribeAgent { agent_id } => {
+                if let Some(tasks) = self.agents.remove(&agent_id) {
+                    tasks.iter().for_each(AbortHandle::abort);
+                }
+                Some(unsubscribed("agent", agent_id.to_string()))
+            }
+            WebSocketCommand::SubscribeGroup { group_id } => {
+                let result = self.subscribe_group(&group_id).await;
+                Some(confirmation("group", group_id.to_string(), result, None))
+            }
+            WebSocketCommand::UnsubscribeGroup { group_id } => {
+                self.groups.remove(&group_id);
+                Some(unsubscribed("group", group_id.to_string()))
+            }
+            WebSocketCommand::SubscribeUser { user_id } => {
+                let result = if user_id == self.auth.user_id {
+                    self.subscribe_user().await
+                } else {
+                    Err(ApiError::Forbidden {
+                        required_permission: ApiPermission::ManageUsers.to_string(),
+                    })
+                };
+                Some(confirmation("user", user_id.to_string(), result, None))
+            }
+            WebSocketCommand::SetTyping {
+                agent_id,
+                is_typing,
+            } => match self.set_typing(agent_id, is_typing).await {
+                Ok(()) => None,
+                Err(e) => Some(error_event("command_failed", e.to_string())),
+            },
+            WebSocketCommand::Ping => Some(WebSocketEvent::Pong {
+                timestamp: chrono::Utc::now(),
+            }),
+        }
+    }
+
+    async fn subscribe_agent(&mut self, agent_id: &AgentId) -> Result<(), ApiError> {
+        self.auth.require(ApiPermission::ReadAgents)?;
+        scope::agent_record(&self.state, &self.auth.user_id, agent_id).await?;
+        self.watch_agent(agent_id);
+        Ok(())
+    }
+
+    async fn subscribe_group(&mut self, group_id: &GroupId) -> Result<(), ApiError> {
+        self.auth.require(ApiPermission::ReadGroups)?;
+        let group = scope::group(&self.state, &self.auth.user_id, group_id).await?;
+        self.groups.insert(
+            group.id,
+            group
+                .members
+                .into_iter()
+                .map(|(agent, _)| agent.id)
+                .collect(),
+        );
+        Ok(())
+    }
+
+    /// Subscribe to every agent and group currently in the constellation
+    async fn subscribe_user(&mut self) -> Result<(), ApiError> {
+        if !self.auth.allows(ApiPermission::ReadGroups) {
+            self.auth.require(ApiPermission::ReadAgents)?;
+        }
+
+        if self.auth.allows(ApiPermission::ReadAgents) {
+            for agent_id in scope::agent_ids(&self.state, &self.auth.user_id).await? {
+                self.watch_agent(&agent_id);
+            }
+        }
+        if self.auth.allows(ApiPermission::ReadGroups) {
+            for group_id in scope::group_ids(&self.state, &self.auth.user_id).await? {
+                self.subscribe_group(&group_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn set_typing(&self, agent_id: AgentId, is_typing: bool) -> Result<(), ApiError> {
+        self.auth.require(ApiPermission::SendMessages)?;
+        scope::agent_record(&self.state, &self.auth.user_id, &agent_id).await?;
+        self.state.events.publish(
+            Some(agent_id.clone()),
+            None,
+            WebSocketEvent::AgentTyping {
+                agent_id,
+                is_typing,
+            },
+        );
+        Ok(())
+    }
+
+    /// Start the live queries backing an agent subscription
+    fn watch_agent(&mut self, agent_id: &AgentId) {
+        if self.agents.contains_key(agent_id) {
+            return;
+        }
+
+        let mut tasks = Vec::new();
+        if self.auth.allows(ApiPermission::ReadMessages) {
+            let task = tokio::spawn(forward_queued_messages(
+                self.state.db.clone(),
+                agent_id.clone(),
+                self.local.clone(),
+            ));
+            tasks.push(task.abort_handle());
+        }
+        if self.auth.allows(ApiPermission::ReadMemory) {
+            let task = tokio::spawn(forward_memory_updates(
+                self.state.db.clone(),
+                agent_id.clone(),
+                self.local.clone(),
+            ));
+            tasks.push(task.abort_handle());
+        }
+        self.agents.insert(agent_id.clone(), tasks);
+    }
+
+    /// Whether a broadcast event matches this connection's subscriptions
+    fn wants(&self, live: &LiveEvent) -> bool {
+        let permission = match &live.event {
+            WebSocketEvent::MessageReceived { .. } => Some(ApiPermission::ReadMessages),
+            WebSocketEvent::MemoryUpdated { .. } => Some(ApiPermission::ReadMemory),
+            _ => None,
+        };
+        if permission.is_some_and(|p| !self.auth.allows(p)) {
+            return false;
+        }
+
+        let via_group = live
+            .group_id
+            .as_ref()
+            .is_some_and(|id| self.groups.contains_key(id));
+        let via_agent = live.agent_id.as_ref().is_some_and(|id| {
+            self.agents.contains_key(id) || self.groups.values().any(|m| m.contains(id))
+        });
+        via_group || via_agent
+    }
+
+    /// Stop every live query this connection started
+    fn close(&mut self) {
+        for (_, tasks) in self.agents.drain() {
+            tasks.iter().for_each(AbortHandle::abort);
+        }
+    }
+}
+
+/// Report messages queued for an agent by other agents or users
+async fn forward_queued_messages(
+    db: Surreal<Any>,
+    agent_id: AgentId,
+    events: mpsc::Sender<WebSocketEvent>,
+) {
+    let updates = match ops::subscribe_to_agent_messages(&db, &agent_id).await {
+        Ok(updates) => updates,
+        Err(e) => {
+            tracing::warn!("Failed to watch queued messages for {}: {}", agent_id, e);
+            return;
+        }
+    };
+    futures::pin_mut!(updates);
+
+    while let Some((action, queued)) = updates.next().await {
+        if !matches!(action, Action::Create) {
+            continue;
+        }
+        let event = WebSocketEvent::MessageReceived {
+            // Queued messages keep their queue key so clients can correlate them
+            message_id: MessageId(queued.id.to_key()),
+            agent_id: agent_id.clone(),
+            role: ChatRole::User,
+            content: MessageContent::Text(queued.content),
+            timestamp: queued.created_at,
+        };
+        if events.send(event).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Report changes to the memory blocks an agent had when subscribed
+async fn forward_memory_updates(
+    db: Surreal<Any>,
+    agent_id: AgentId,
+    events: mpsc::Sender<WebSocketEvent>,
+) {
+    let memories = match ops::get_agent_memories(&db, &agent_id).await {
+        Ok(memories) => memories,
+        Err(e) => {
+            tracing::warn!("Failed to load memories for {}: {}", agent_id, e);
+            return;
+        }
+    };
+
+    let mut streams = Vec::new();
+    for (block, _) in memories {
+        match ops::subscribe_to_memory_updates(&db, &block.id).await {
+            Ok(stream) => streams.push(Box::pin(stream)),
+            Err(e) => tracing::warn!("Failed to watch memory block {}: {}", block.label, e),
+        }
+    }
+
+    let mut updates = futures::stream::select_all(streams);
+    while let Some((_, block)) = updates.next().await {
+        let event = WebSocketEvent::MemoryUpdated {
+            agent_id: agent_id.clone(),
+            memory_type: block.memory_type.to_string(),
+            timestamp: chrono::Utc::now(),
+        };
+        if events.send(event).await.is_err() {
+            break;
+        }
+    }
+}
+
+fn confirmation(
+    subscription_type: &str,
+    resource_id: String,
+    result: Result<(), ApiError>,
+    message: Option<String>,
+) -> WebSocketEvent {
+    WebSocketEvent::Subscription(SubscriptionConfirmation {
+        subscription_type: subscription_type.to_string(),
+        resource_id,
+        success: result.is_ok(),
+        message: result.err().map(|e| e.to_string()).or(message),
+    })
+}
+
+fn unsubscribed(subscription_type: &str, resource_id: String) -> WebSocketEvent {
+    confirmation(
+        subscription_type,
+        resource_id,
+        Ok(()),
+        Some("Unsubscribed".to_string()),
+    )
+}
+
+fn error_event(error_type: &str, message: String) -> WebSocketEvent {
+    WebSocketEvent::Error {
+        error_type: error_type.to_string(),
+        message,
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command_with_or_without_envelope() {
+        let bare = parse_command(r#"{"type": "ping"}"#).unwrap();
+        assert!(matches!(bare, WebSocketCommand::Ping));
+
+        let wrapped =
+            serde_json::to_string(&WebSocketMessage::new(WebSocketCommand::SubscribeAgent {
+                agent_id: AgentId::generate(),
+            }))
+            .unwrap();
+        assert!(matches!(
+            parse_command(&wrapped).unwrap(),
+            WebSocketCommand::SubscribeAgent { .. }
+        ));
+
+        assert!(parse_command(r#"{"type": "launch"}"#).is_err());
+    }
+
+    #[test]
+    fn test_query_token_rejects_api_keys() {
+        assert!(matches!(
+            check_query_token("pat_abc_secret"),
+            Err(ApiError::Unauthorized { .. })
+        ));
+        assert!(check_query_token("eyJhbGciOiJIUzI1NiJ9.e30.sig").is_ok());
+    }
+
+    #[test]
+    fn test_failed_subscription_reports_error() {
+        let event = confirmation(
+            "agent",
+            "agent:missing".to_string(),
+            Err(ApiError::not_found("agent", "agent:missing")),
+            None,
+        );
+        assert!(matches!(
+            event,
+            WebSocketEvent::Subscription(SubscriptionConfirmation {
+                success: false,
+                message: Some(_),
+                ..
+            })
+        ));
+    }
+}
@@ -5,9 +5,12 @@
 pub mod auth;
 pub mod config;
 pub mod error;
+pub mod events;
 pub mod handlers;
+pub mod mcp;
 pub mod middleware;
 pub mod models;
+pub mod runtime;
 pub mod state;
 
 pub use config::ServerConfig;
@@ -16,7 +19,7 @@ pub use state::AppState;
 
 /// Start the Pattern API server
 pub async fn start_server(config: ServerConfig) -> ServerResult<()> {
-    use axum::Router;
+    use axum::{Router, routing::get};
     use std::net::SocketAddr;
     use tower_http::cors::CorsLayer;
     use tower_http::trace::TraceLayer;
@@ -28,7 +31,8 @@ pub async fn start_server(config: ServerConfig) -> ServerResult<()> {
 
     // Build router
     let app = Router::new()
-        .nest("/api/v1", handlers::routes())
+        .nest("/api/v1", handlers::routes(state.clone()))
+        .route("/ws", get(handlers::ws::websocket))
         .layer(CorsLayer::permissive()) // TODO: Configure properly
         .layer(TraceLayer::new_for_http())
         .with_state(state);
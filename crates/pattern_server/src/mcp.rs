@@ -0,0 +1,281 @@
+//! Connections to external MCP servers configured through the API
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use pattern_api::{
+    ApiError,
+    requests::McpTransportConfig,
+    responses::{McpServerResponse, McpServerStatus, McpToolInfo},
+};
+use pattern_core::{
+    db::ops,
+    id::{IdType, UserId},
+};
+use pattern_mcp::client::{AuthConfig, ClientTransport, ToolDiscovery, TransportConfig};
+use surrealdb::{Surreal, engine::any::Any};
+
+use crate::models::{McpServerId, McpServerRecord};
+
+/// How long to wait for an MCP server to finish its handshake
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Runtime state of one configured MCP server
+struct McpConnection {
+    record: McpServerRecord,
+    status: McpServerStatus,
+    connected_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    tools: Vec<McpToolInfo>,
+    /// Dropping the transport shuts the connection down
+    transport: Option<Arc<ClientTransport>>,
+}
+
+impl McpConnection {
+    fn new(record: McpServerRecord) -> Self {
+        Self {
+            record,
+            status: McpServerStatus::Disconnected,
+            connected_at: None,
+            last_error: None,
+            tools: Vec::new(),
+            transport: None,
+        }
+    }
+
+    fn to_response(&self) -> McpServerResponse {
+        McpServerResponse {
+            id: self.record.id.to_key(),
+            name: self.record.name.clone(),
+            description: self.record.description.clone(),
+            status: self.status.clone(),
+            transport_type: transport_type(&self.record.transport).to_string(),
+            auto_reconnect: self.record.auto_reconnect,
+            connected_at: self.connected_at,
+            last_error: self.last_error.clone(),
+            available_tools: self.tools.clone(),
+        }
+    }
+}
+
+/// All MCP servers known to this API server
+pub struct McpConnections {
+    db: Surreal<Any>,
+    connections: DashMap<McpServerId, McpConnection>,
+}
+
+impl std::fmt::Debug for McpConnections {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("McpConnections")
+            .field("servers", &self.connections.len())
+            .finish()
+    }
+}
+
+impl McpConnections {
+    pub fn new(db: Surreal<Any>) -> Self {
+        Self {
+            db,
+            connections: DashMap::new(),
+        }
+    }
+
+    /// Load stored servers and reconnect the ones marked for it
+    pub async fn restore(self: Arc<Self>) -> Result<(), ApiError> {
+        let records = ops::list_entities::<McpServerRecord, _>(&self.db).await?;
+        for record in records {
+            let reconnect = record.enabled && record.auto_reconnect;
+            let id = record.id.clone();
+            self.connections
+                .insert(id.clone(), McpConnection::new(record));
+
+            if reconnect {
+                let connections = self.clone();
+                tokio::spawn(async move {
+                    connections.connect(&id).await;
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Store a new server and try to connect to it
+    pub async fn add(&self, record: McpServerRecord) -> Result<McpServerResponse, ApiError> {
+        let record = ops::create_entity::<McpServerRecord, _>(&self.db, &record).await?;
+        let id = record.id.clone();
+        let enabled = record.enabled;
+        self.connections
+            .insert(id.clone(), McpConnection::new(record));
+
+        if enabled {
+            self.connect(&id).await;
+        }
+        self.get(&id)
+    }
+
+    /// Persist changes to a server, reconnecting or disconnecting as needed
+    pub async fn update(&self, record: McpServerRecord) -> Result<McpServerResponse, ApiError> {
+        let record = ops::update_entity::<McpServerRecord, _>(&self.db, &record).await?;
+        let id = record.id.clone();
+        let enabled = record.enabled;
+
+        let was_connected = match self.connections.get_mut(&id) {
+            Some(mut connection) => {
+                connection.record = record;
+                connection.transport.is_some()
+            }
+            None => {
+                self.connections
+                    .insert(id.clone(), McpConnection::new(record));
+                false
+            }
+        };
+
+        if !enabled {
+            self.disconnect(&id);
+        } else if !was_connected {
+            self.connect(&id).await;
+        }
+        self.get(&id)
+    }
+
+    /// Servers owned by a user
+    pub fn list(&self, owner: &UserId) -> Vec<McpServerResponse> {
+        let mut servers: Vec<_> = self
+            .connections
+            .iter()
+            .filter(|c| &c.record.owner_id == owner)
+            .map(|c| c.to_response())
+            .collect();
+        servers.sort_by(|a, b| a.name.cmp(&b.name));
+        servers
+    }
+
+    /// A server's current state, if the user owns it
+    pub fn server(&self, id: &McpServerId, owner: &UserId) -> Result<McpServerResponse, ApiError> {
+        self.connections
+            .get(id)
+            .filter(|c| &c.record.owner_id == owner)
+            .map(|c| c.to_response())
+            .ok_or_else(|| ApiError::not_found("mcp_server", id.to_string()))
+    }
+
+    /// The stored record for a server, if the user owns it
+    pub fn record(&self, id: &McpServerId, owner: &UserId) -> Result<McpServerRecord, ApiError> {
+        self.connections
+            .get(id)
+            .filter(|c| &c.record.owner_id == owner)
+            .map(|c| c.record.clone())
+            .ok_or_else(|| ApiError::not_found("mcp_server", id.to_string()))
+    }
+
+    fn get(&self, id: &McpServerId) -> Result<McpServerResponse, ApiError> {
+        self.connections
+            .get(id)
+            .map(|c| c.to_response())
+            .ok_or_else(|| ApiError::not_found("mcp_server", id.to_string()))
+    }
+
+    /// Open a connection and discover the server's tools
+    ///
+    /// Failures are recorded on the connection rather than returned, so the
+    /// caller can still report the server with its error.
+    pub async fn connect(&self, id: &McpServerId) {
+        let config = match self.connections.get_mut(id) {
+            Some(mut connection) => {
+                connection.status = McpServerStatus::Connecting;
+                transport_config(&connection.record)
+            }
+            None => return,
+        };
+
+        let result = tokio::time::timeout(CONNECT_TIMEOUT, async {
+            let transport = ClientTransport::from_config(config)
+                .await
+                .map_err(|e| e.to_string())?;
+            let tools = ToolDiscovery::discover_tools(transport.peer())
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok::<_, String>((transport, tools))
+        })
+        .await
+        .unwrap_or_else(|_| Err("Timed out connecting to MCP server".to_string()));
+
+        let Some(mut connection) = self.connections.get_mut(id) else {
+            return;
+        };
+        match result {
+            Ok((transport, tools)) => {
+                tracing::info!(
+                    "Connected to MCP server {} with {} tools",
+                    connection.record.name,
+                    tools.len()
+                );
+                connection.status = McpServerStatus::Connected;
+                connection.connected_at = Some(Utc::now());
+                connection.last_error = None;
+                connection.tools = tools
+                    .into_iter()
+                    .map(|tool| McpToolInfo {
+                        name: tool.name,
+                        description: Some(tool.description).filter(|d| !d.is_empty()),
+                        input_schema: Some(tool.input_schema),
+                    })
+                    .collect();
+                connection.transport = Some(Arc::new(transport));
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to connect to MCP server {}: {}",
+                    connection.record.name,
+                    e
+                );
+                connection.status = McpServerStatus::Error;
+                connection.last_error = Some(e);
+                connection.transport = None;
+            }
+        }
+    }
+
+    fn disconnect(&self, id: &McpServerId) {
+        if let Some(mut connection) = self.connections.get_mut(id) {
+            connection.transport = None;
+            connection.status = McpServerStatus::Disconnected;
+            connection.connected_at = None;
+            connection.tools.clear();
+        }
+    }
+}
+
+fn transport_type(transport: &McpTransportConfig) -> &'static str {
+    match transport {
+        McpTransportConfig::Stdio { .. } => "stdio",
+        McpTransportConfig::HttpSse { .. } => "http_sse",
+    }
+}
+
+fn transport_config(record: &McpServerRecord) -> TransportConfig {
+    match &record.transport {
+        McpTransportConfig::Stdio { command, args, env } => {
+            if env.as_ref().is_some_and(|env| !env.is_empty()) {
+                tracing::warn!(
+                    "MCP server {}: custom environment for stdio servers is not supported yet, ignoring",
+                    record.name
+                );
+            }
+            TransportConfig::Stdio {
+                command: command.clone(),
+                args: args.clone(),
+            }
+        }
+        McpTransportConfig::HttpSse { url, headers } => TransportConfig::Sse {
+            url: url.clone(),
+            auth: headers
+                .clone()
+                .filter(|h| !h.is_empty())
+                .map(AuthConfig::Headers)
+                .unwrap_or(AuthConfig::None),
+        },
+    }
+}
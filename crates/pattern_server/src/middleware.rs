@@ -1,14 +1,82 @@
 //! Middleware for authentication, rate limiting, etc.
 
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
 use axum::{
     extract::{Request, State},
     http::HeaderMap,
     middleware::Next,
     response::Response,
 };
-use pattern_api::ApiError;
+use pattern_api::{ApiError, requests::ApiPermission};
+use pattern_core::{
+    db::ops,
+    id::{IdType, UserId},
+};
+
+use crate::{
+    auth::{API_KEY_PREFIX, parse_api_key, validate_access_token, verify_password},
+    models::{ApiKey, ApiKeyId, ServerUser},
+    state::AppState,
+};
+
+/// Who is making a request and what they may do
+///
+/// Inserted into request extensions by [`require_auth`].
+#[derive(Debug, Clone)]
+pub struct AuthContext {
+    pub user_id: UserId,
+    /// Granted permissions; `None` means an unscoped user session
+    pub permissions: Option<Vec<ApiPermission>>,
+    /// The API key used as the bearer token or exchanged for it, if any
+    pub api_key: Option<ApiKeyId>,
+    /// Requests per minute allowed for that API key
+    pub rate_limit: Option<u32>,
+    /// Whether the user is a server administrator
+    pub is_admin: bool,
+}
+
+/// Permissions an unscoped session only holds if the user is an administrator
+const ADMIN_PERMISSIONS: [ApiPermission; 2] = [
+    ApiPermission::ManageUsers,
+    ApiPermission::ManageModelProviders,
+];
+
+impl AuthContext {
+    /// Whether this caller holds the given permission
+    pub fn allows(&self, permission: ApiPermission) -> bool {
+        match &self.permissions {
+            Some(granted) => granted.contains(&permission),
+            None => self.is_admin || !ADMIN_PERMISSIONS.contains(&permission),
+        }
+    }
 
-use crate::state::AppState;
+    /// Fail with `Forbidden` unless this caller holds the given permission
+    pub fn require(&self, permission: ApiPermission) -> Result<(), ApiError> {
+        if self.allows(permission) {
+            Ok(())
+        } else {
+            Err(ApiError::Forbidden {
+                required_permission: permission.to_string(),
+            })
+        }
+    }
+
+    /// Fail with `Forbidden` unless this caller is a server administrator
+    pub fn require_admin(&self) -> Result<(), ApiError> {
+        if self.is_admin {
+            Ok(())
+        } else {
+            Err(ApiError::Forbidden {
+                required_permission: "admin".to_string(),
+            })
+        }
+    }
+}
 
 /// Extract and validate bearer token from Authorization header
 pub fn extract_bearer_token(headers: &HeaderMap) -> Option<&str> {
@@ -18,6 +86,120 @@ pub fn extract_bearer_token(headers: &HeaderMap) -> Option<&str> {
         .and_then(|value| value.strip_prefix("Bearer "))
 }
 
+/// Resolve the caller from a bearer JWT or a raw API key
+pub async fn authenticate(state: &AppState, headers: &HeaderMap) -> Result<AuthContext, ApiError> {
+    let token = extract_bearer_token(headers).ok_or_else(|| ApiError::Unauthorized {
+        message: Some("Missing authorization header".to_string()),
+    })?;
+    authenticate_token(state, token).await
+}
+
+/// Resolve the caller from a JWT or raw API key given directly
+pub async fn authenticate_token(state: &AppState, token: &str) -> Result<AuthContext, ApiError> {
+    let mut auth = if token.starts_with(API_KEY_PREFIX) {
+        let key = verify_api_key(state, token).await?;
+        AuthContext {
+            user_id: key.user_id,
+            permissions: Some(parse_permissions(&key.permissions)),
+            api_key: Some(key.id),
+            rate_limit: key.rate_limit,
+            is_admin: false,
+        }
+    } else {
+        // Validate token
+        let claims = validate_access_token(token, &state.jwt_decoding_key).map_err(|_| {
+            ApiError::Unauthorized {
+                message: Some("Invalid or expired token".to_string()),
+            }
+        })?;
+
+        // A token exchanged for an API key ends with the key and keeps its
+        // rate limit
+        let key = match &claims.api_key {
+            Some(id) => Some(exchanged_api_key(state, &claims.sub, id).await?),
+            None => None,
+        };
+
+        AuthContext {
+            user_id: claims.sub,
+            permissions: claims.permissions,
+            rate_limit: key.as_ref().and_then(|k| k.rate_limit),
+            api_key: key.map(|k| k.id),
+            is_admin: false,
+        }
+    };
+
+    // Read from the user on every request so deactivation and demotion apply
+    // to tokens that were already issued
+    let user = ops::get_entity::<ServerUser, _>(&state.db, &auth.user_id)
+        .await?
+        .filter(|user| user.is_active)
+        .ok_or_else(|| ApiError::Unauthorized {
+            message: Some("Account not found or disabled".to_string()),
+        })?;
+    auth.is_admin = user.is_admin;
+
+    Ok(auth)
+}
+
+/// Look up and check a plaintext API key, recording its use
+pub async fn verify_api_key(state: &AppState, key: &str) -> Result<ApiKey, ApiError> {
+    let invalid = || ApiError::Unauthorized {
+        message: Some("Invalid or expired API key".to_string()),
+    };
+
+    let id = parse_api_key(key).ok_or_else(invalid)?;
+    let mut record = ops::get_entity::<ApiKey, _>(&state.db, &id)
+        .await?
+        .filter(is_usable)
+        .ok_or_else(invalid)?;
+    if !verify_password(key, &record.key_hash).map_err(|_| invalid())? {
+        return Err(invalid());
+    }
+
+    record.last_used_at = Some(chrono::Utc::now());
+    if let Err(e) = ops::update_entity(&state.db, &record).await {
+        tracing::warn!("Failed to record API key use for {}: {}", record.id, e);
+    }
+
+    Ok(record)
+}
+
+/// The still usable API key an access token was exchanged for
+async fn exchanged_api_key(state: &AppState, user: &UserId, id: &str) -> Result<ApiKey, ApiError> {
+    let invalid = || ApiError::Unauthorized {
+        message: Some("Invalid or expired token".to_string()),
+    };
+
+    let id = ApiKeyId::from_key(id).map_err(|_| invalid())?;
+    ops::get_entity::<ApiKey, _>(&state.db, &id)
+        .await?
+        .filter(|key| &key.user_id == user && is_usable(key))
+        .ok_or_else(invalid)
+}
+
+/// Whether a key is enabled and has not expired
+fn is_usable(key: &ApiKey) -> bool {
+    key.is_active
+        && !key
+            .expires_at
+            .is_some_and(|expires| expires <= chrono::Utc::now())
+}
+
+/// Parse stored permission names, skipping any that are no longer known
+pub fn parse_permissions(names: &[String]) -> Vec<ApiPermission> {
+    names
+        .iter()
+        .filter_map(|name| match name.parse() {
+            Ok(permission) => Some(permission),
+            Err(e) => {
+                tracing::warn!("Ignoring stored API key permission: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
 /// Authentication middleware
 pub async fn require_auth(
     State(state): State<AppState>,
@@ -25,20 +207,127 @@ pub async fn require_auth(
     mut request: Request,
     next: Next,
 ) -> Result<Response, ApiError> {
-    let token = extract_bearer_token(&headers).ok_or_else(|| ApiError::Unauthorized {
-        message: Some("Missing authorization header".to_string()),
-    })?;
+    let auth = authenticate(&state, &headers).await?;
 
-    // Validate token
-    let claims =
-        crate::auth::validate_access_token(token, &state.jwt_decoding_key).map_err(|_| {
-            ApiError::Unauthorized {
-                message: Some("Invalid or expired token".to_string()),
-            }
+    if let (Some(key_id), Some(limit)) = (&auth.api_key, auth.rate_limit) {
+        state.rate_limiter.check(key_id, limit)?;
+    }
+
+    // Insert the caller into request extensions for handlers to use
+    request.extensions_mut().insert(auth);
+
+    Ok(next.run(request).await)
+}
+
+/// Permission middleware, layered per route after [`require_auth`]
+pub async fn require_permission(
+    permission: ApiPermission,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let auth = request
+        .extensions()
+        .get::<AuthContext>()
+        .ok_or_else(|| ApiError::Unauthorized {
+            message: Some("Missing authorization header".to_string()),
         })?;
+    auth.require(permission)?;
+
+    Ok(next.run(request).await)
+}
 
-    // Insert user ID into request extensions for handlers to use
-    request.extensions_mut().insert(claims.sub);
+/// Administrator middleware, layered per route after [`require_auth`]
+///
+/// For settings shared by every user; a permission alone is not enough, since
+/// an administrator's keys keep their permissions after a demotion.
+pub async fn require_admin(request: Request, next: Next) -> Result<Response, ApiError> {
+    let auth = request
+        .extensions()
+        .get::<AuthContext>()
+        .ok_or_else(|| ApiError::Unauthorized {
+            message: Some("Missing authorization header".to_string()),
+        })?;
+    auth.require_admin()?;
 
     Ok(next.run(request).await)
 }
+
+/// Fixed-window per-minute request limiter for API keys
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    windows: Mutex<HashMap<ApiKeyId, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    /// Count a request against the key, failing once `limit` is exceeded
+    pub fn check(&self, key: &ApiKeyId, limit: u32) -> Result<(), ApiError> {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let (started, count) = windows.entry(key.clone()).or_insert((now, 0));
+
+        if now.duration_since(*started) >= Self::WINDOW {
+            *started = now;
+            *count = 0;
+        }
+        if *count >= limit {
+            let retry_after = Self::WINDOW.saturating_sub(now.duration_since(*started));
+            return Err(ApiError::RateLimitExceeded {
+                retry_after_seconds: retry_after.as_secs().max(1),
+            });
+        }
+        *count += 1;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_context_requires_permission() {
+        let scoped = AuthContext {
+            user_id: UserId::generate(),
+            permissions: Some(vec![ApiPermission::ReadAgents]),
+            api_key: None,
+            rate_limit: None,
+            is_admin: false,
+        };
+        assert!(scoped.require(ApiPermission::ReadAgents).is_ok());
+        assert!(matches!(
+            scoped.require(ApiPermission::WriteAgents),
+            Err(ApiError::Forbidden { .. })
+        ));
+
+        let session = AuthContext {
+            permissions: None,
+            ..scoped
+        };
+        assert!(session.allows(ApiPermission::ManageApiKeys));
+        assert!(!session.allows(ApiPermission::ManageUsers));
+
+        let admin = AuthContext {
+            is_admin: true,
+            ..session
+        };
+        assert!(admin.allows(ApiPermission::ManageUsers));
+        assert!(admin.require_admin().is_ok());
+    }
+
+    #[test]
+    fn test_rate_limiter_window() {
+        let limiter = RateLimiter::default();
+        let key = ApiKeyId::generate();
+
+        assert!(limiter.check(&key, 2).is_ok());
+        assert!(limiter.check(&key, 2).is_ok());
+        assert!(matches!(
+            limiter.check(&key, 2),
+            Err(ApiError::RateLimitExceeded { .. })
+        ));
+        assert!(limiter.check(&ApiKeyId::generate(), 2).is_ok());
+    }
+}
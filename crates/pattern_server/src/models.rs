@@ -1,6 +1,7 @@
 //! Server-specific data models
 
 use chrono::{DateTime, Utc};
+use pattern_api::{requests::McpTransportConfig, responses::UserResponse};
 use pattern_core::{
     define_id_type,
     id::{AgentId, EventId, MemoryId, TaskId, UserId},
@@ -12,6 +13,8 @@ use std::collections::HashMap;
 // Define ID types for server entities
 define_id_type!(ApiKeyId, "apikey");
 define_id_type!(RefreshTokenFamilyId, "rtfam");
+define_id_type!(McpServerId, "mcp_server");
+define_id_type!(ModelProviderId, "model_provider");
 
 /// Server-side user model with authentication fields
 /// Extends pattern_core::User with auth-specific fields
@@ -34,6 +37,10 @@ pub struct ServerUser {
     pub email: Option<String>,
     pub display_name: Option<String>,
     pub is_active: bool,
+    /// Server administrators manage settings shared by every user, such as
+    /// model providers. The first registered user is one.
+    #[serde(default)]
+    pub is_admin: bool,
 
     // Relations from pattern_core::User
     #[entity(relation = "owns")]
@@ -56,6 +63,21 @@ pub struct ServerUser {
     pub refresh_token_families: Vec<RefreshTokenFamilyId>,
 }
 
+impl ServerUser {
+    /// The public view of this user
+    pub fn to_response(&self) -> UserResponse {
+        UserResponse {
+            id: self.id.clone(),
+            username: self.username.clone(),
+            display_name: self.display_name.clone(),
+            email: self.email.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            is_active: self.is_active,
+        }
+    }
+}
+
 /// Database record for API keys
 #[derive(Debug, Clone, Entity, Serialize, Deserialize)]
 #[entity(entity_type = "api_key", crate_path = "::pattern_core")]
@@ -69,6 +91,9 @@ pub struct ApiKey {
     pub expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub is_active: bool,
+    /// Requests per minute allowed when the key is used directly
+    #[serde(default)]
+    pub rate_limit: Option<u32>,
 }
 
 /// Database record for refresh token families
@@ -82,3 +107,38 @@ pub struct RefreshTokenFamily {
     pub revoked_at: Option<DateTime<Utc>>,
     pub is_active: bool,
 }
+
+/// Database record for an external MCP server connection
+#[derive(Debug, Clone, Entity, Serialize, Deserialize)]
+#[entity(entity_type = "mcp_server", crate_path = "::pattern_core")]
+pub struct McpServerRecord {
+    pub id: McpServerId,
+    pub owner_id: UserId,
+    pub name: String,
+    pub description: Option<String>,
+    #[entity(db_type = "object")]
+    pub transport: McpTransportConfig,
+    pub auto_reconnect: bool,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Database record for a configured model provider
+///
+/// Credentials stored here take precedence over the server environment. They
+/// are shared by every user, so only administrators can change them, and the
+/// key is kept encrypted with [`SecretCipher`](crate::auth::SecretCipher).
+#[derive(Debug, Clone, Entity, Serialize, Deserialize)]
+#[entity(entity_type = "model_provider", crate_path = "::pattern_core")]
+pub struct ModelProviderRecord {
+    pub id: ModelProviderId,
+    /// Lowercase provider name (anthropic, openai, gemini, ...)
+    pub provider: String,
+    pub encrypted_api_key: Option<String>,
+    pub enabled: bool,
+    pub is_default: bool,
+    pub last_validated: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,422 @@
+//! Live agents backing the API
+//!
+//! Agents are loaded from their database records on first use and kept
+//! running so chat requests, memory operations and group routing all act on
+//! the same in-memory state.
+
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use dashmap::DashMap;
+use futures::{Stream, StreamExt};
+use genai::{
+    ModelIden,
+    adapter::AdapterKind,
+    resolver::{AuthData, AuthResolver, Result as ResolverResult},
+};
+use pattern_api::ApiError;
+use pattern_core::{
+    Agent, ModelProvider,
+    agent::{AgentRecord, DatabaseAgent},
+    constellation_memory::ConstellationActivityTracker,
+    context::heartbeat,
+    coordination::{
+        AgentGroup, CoordinationPattern, DynamicManager, GroupManager, PipelineManager,
+        RoundRobinManager, SleeptimeManager, SupervisorManager, VotingManager,
+        groups::{AgentWithMembership, GroupResponseEvent},
+        selectors::DefaultSelectorRegistry,
+        triggers::DefaultTriggerEvaluatorRegistry,
+    },
+    embeddings::cloud::GeminiEmbedder,
+    id::AgentId,
+    message::Message,
+    model::{GenAiClient, ModelInfo, ResponseOptions},
+    tool::{ToolRegistry, builtin::VoteTool},
+};
+use surrealdb::{Surreal, engine::any::Any};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::{auth::SecretCipher, events::EventHub, models::ModelProviderRecord};
+
+/// Providers the server can talk to, with the environment variable holding their key
+const PROVIDERS: [(AdapterKind, &str, &str); 5] = [
+    (AdapterKind::Anthropic, "anthropic", "ANTHROPIC_API_KEY"),
+    (AdapterKind::Gemini, "gemini", "GEMINI_API_KEY"),
+    (AdapterKind::OpenAI, "openai", "OPENAI_API_KEY"),
+    (AdapterKind::Groq, "groq", "GROQ_API_KEY"),
+    (AdapterKind::Cohere, "cohere", "COHERE_API_KEY"),
+];
+
+/// Lowercase provider name for a genai adapter
+pub fn provider_name(kind: AdapterKind) -> Option<&'static str> {
+    PROVIDERS
+        .iter()
+        .find(|(k, _, _)| *k == kind)
+        .map(|(_, name, _)| *name)
+}
+
+/// Whether the server knows how to reach the named provider
+pub fn is_known_provider(name: &str) -> bool {
+    PROVIDERS
+        .iter()
+        .any(|(_, n, _)| n.eq_ignore_ascii_case(name))
+}
+
+/// Names of every provider the server can talk to
+pub fn provider_names() -> impl Iterator<Item = &'static str> {
+    PROVIDERS.iter().map(|(_, name, _)| *name)
+}
+
+/// Whether the server environment holds a key for the named provider
+pub fn has_env_credentials(name: &str) -> bool {
+    PROVIDERS
+        .iter()
+        .find(|(_, n, _)| n.eq_ignore_ascii_case(name))
+        .is_some_and(|(_, _, env)| std::env::var(env).is_ok())
+}
+
+/// Loaded agents plus the shared model and embedding clients they use
+pub struct AgentRuntime {
+    db: Surreal<Any>,
+    agents: DashMap<AgentId, Arc<dyn Agent>>,
+    /// Tool registries of the loaded agents, for tools that depend on their groups
+    tools: DashMap<AgentId, ToolRegistry>,
+    /// Serializes agent loading so concurrent requests don't start duplicates
+    load_lock: Mutex<()>,
+    model: Arc<RwLock<GenAiClient>>,
+    /// API keys configured through the providers endpoints, by provider name
+    credentials: Arc<DashMap<String, String>>,
+    default_provider: RwLock<Option<String>>,
+    embeddings: Option<Arc<GeminiEmbedder>>,
+    activity: Arc<ConstellationActivityTracker>,
+    events: Arc<EventHub>,
+}
+
+impl std::fmt::Debug for AgentRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AgentRuntime")
+            .field("loaded_agents", &self.agents.len())
+            .finish()
+    }
+}
+
+impl AgentRuntime {
+    pub fn new(db: Surreal<Any>, events: Arc<EventHub>) -> Self {
+        let credentials = Arc::new(DashMap::new());
+        let model = GenAiClient::with_endpoints(
+            build_genai_client(credentials.clone()),
+            available_endpoints(&credentials, &[]),
+        );
+
+        // Create embedding provider if API key is available
+        let embeddings = std::env::var("GEMINI_API_KEY").ok().map(|api_key| {
+            Arc::new(GeminiEmbedder::new(
+                "gemini-embedding-001".to_string(),
+                api_key,
+                Some(1536),
+            ))
+        });
+
+        Self {
+            db,
+            agents: DashMap::new(),
+            tools: DashMap::new(),
+            load_lock: Mutex::new(()),
+            model: Arc::new(RwLock::new(model)),
+            credentials,
+            default_provider: RwLock::new(None),
+            embeddings,
+            activity: Arc::new(ConstellationActivityTracker::new(100)),
+            events,
+        }
+    }
+
+    /// Apply stored provider configuration to the shared model client
+    ///
+    /// Loaded agents share the client, so they pick up the change immediately.
+    /// A key that can't be decrypted is skipped, leaving the environment's.
+    pub async fn apply_providers(&self, records: &[ModelProviderRecord], secrets: &SecretCipher) {
+        self.credentials.clear();
+        for record in records.iter().filter(|r| r.enabled) {
+            let Some(encrypted) = &record.encrypted_api_key else {
+                continue;
+            };
+            match secrets.decrypt(encrypted) {
+                Ok(key) => {
+                    self.credentials.insert(record.provider.clone(), key);
+                }
+                Err(e) => {
+                    tracing::warn!("Ignoring stored key for '{}': {}", record.provider, e);
+                }
+            }
+        }
+
+        *self.default_provider.write().await = records
+            .iter()
+            .find(|r| r.enabled && r.is_default)
+            .map(|r| r.provider.clone());
+
+        let disabled: Vec<&str> = records
+            .iter()
+            .filter(|r| !r.enabled)
+            .map(|r| r.provider.as_str())
+            .collect();
+        *self.model.write().await = GenAiClient::with_endpoints(
+            build_genai_client(self.credentials.clone()),
+            available_endpoints(&self.credentials, &disabled),
+        );
+    }
+
+    /// Models reachable with the current provider configuration
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>, ApiError> {
+        Ok(self.model.read().await.list_models().await?)
+    }
+
+    /// Get a running agent, loading it from the database if needed
+    pub async fn agent(&self, id: &AgentId) -> Result<Arc<dyn Agent>, ApiError> {
+        if let Some(agent) = self.agents.get(id) {
+            return Ok(agent.clone());
+        }
+
+        let _guard = self.load_lock.lock().await;
+        if let Some(agent) = self.agents.get(id) {
+            return Ok(agent.clone());
+        }
+
+        let record = AgentRecord::load_with_relations(&self.db, id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("agent", id.to_string()))?;
+        let agent = self.load(record).await?;
+        self.agents.insert(id.clone(), agent.clone());
+
+        Ok(agent)
+    }
+
+    /// Get the agent if it is already running
+    pub fn loaded(&self, id: &AgentId) -> Option<Arc<dyn Agent>> {
+        self.agents.get(id).map(|a| a.clone())
+    }
+
+    /// Drop a running agent so the next request reloads it from its record
+    pub fn evict(&self, id: &AgentId) {
+        self.agents.remove(id);
+        self.tools.remove(id);
+    }
+
+    async fn load(&self, record: AgentRecord) -> Result<Arc<dyn Agent>, ApiError> {
+        let options = self.response_options(record.model_id.as_deref()).await?;
+        let (heartbeat_sender, heartbeat_receiver) = heartbeat::heartbeat_channel();
+        let tools = ToolRegistry::new();
+        self.tools.insert(record.id.clone(), tools.clone());
+
+        let agent = DatabaseAgent::from_record(
+            record,
+            self.db.clone(),
+            self.model.clone(),
+            tools,
+            self.embeddings.clone(),
+            heartbeat_sender,
+        )
+        .await?;
+        *agent.chat_options.write().await = Some(options);
+
+        let agent = Arc::new(agent);
+        agent.clone().start_stats_sync().await?;
+        agent.clone().start_memory_sync().await?;
+        agent.clone().start_message_monitoring().await?;
+
+        let agent: Arc<dyn Agent> = agent;
+        if let (_, Some(states)) = agent.state().await {
+            self.events.watch_state(agent.id(), states);
+        }
+        tokio::spawn(heartbeat::process_heartbeats(
+            heartbeat_receiver,
+            vec![agent.clone()],
+            |event, agent_id, agent_name| async move {
+                tracing::debug!(
+                    "Heartbeat event from {} ({}): {:?}",
+                    agent_name,
+                    agent_id,
+                    event
+                );
+            },
+        ));
+
+        Ok(agent)
+    }
+
+    /// Response options for an agent, preferring its stored model
+    async fn response_options(&self, model_id: Option<&str>) -> Result<ResponseOptions, ApiError> {
+        let models = self.list_models().await?;
+        let default_provider = self.default_provider.read().await.clone();
+
+        let model_info = model_id
+            .and_then(|id| models.iter().find(|m| m.id == id))
+            .or_else(|| {
+                default_provider.and_then(|provider| {
+                    models
+                        .iter()
+                        .find(|m| m.provider.eq_ignore_ascii_case(&provider))
+                })
+            })
+            .or_else(|| models.first())
+            .cloned()
+            .ok_or_else(|| ApiError::ServiceUnavailable {
+                retry_after_seconds: None,
+            })?;
+
+        Ok(ResponseOptions {
+            capture_content: Some(true),
+            capture_tool_calls: Some(true),
+            capture_usage: Some(true),
+            capture_reasoning_content: Some(true),
+            normalize_reasoning_content: Some(true),
+            ..ResponseOptions::new(model_info)
+        })
+    }
+
+    /// Route a message through a group with its active members
+    pub async fn route_group(
+        &self,
+        group: &AgentGroup,
+        mut message: Message,
+    ) -> Result<Box<dyn Stream<Item = GroupResponseEvent> + Send + Unpin>, ApiError> {
+        message.metadata.group_id = Some(group.id.clone());
+        let voting = matches!(
+            group.coordination_pattern,
+            CoordinationPattern::Voting { .. }
+        );
+        let mut agents = Vec::new();
+        let mut vote_tools = VoteToolGuard::default();
+        for (record, membership) in group.members.iter().filter(|(_, m)| m.is_active) {
+            let agent = self.agent(&record.id).await?;
+            // Voting members cast their ballots through the vote tool for this round only
+            if voting {
+                if let Some(tools) = self.tools.get(&record.id) {
+                    vote_tools.register(&tools);
+                }
+            }
+            agents.push(AgentWithMembership {
+                agent,
+                membership: membership.clone(),
+            });
+        }
+        if agents.is_empty() {
+            return Err(ApiError::validation(format!(
+                "Group '{}' has no active members",
+                group.name
+            )));
+        }
+
+        let manager = self.group_manager(&group.coordination_pattern);
+        let stream = manager.route_message(group, &agents, message).await?;
+        if vote_tools.is_empty() {
+            return Ok(stream);
+        }
+        // The guard lives as long as the stream, so the tool goes away when the round ends
+        Ok(Box::new(stream.map(move |event| {
+            let _guard = &vote_tools;
+            event
+        })))
+    }
+
+    fn group_manager(&self, pattern: &CoordinationPattern) -> Arc<dyn GroupManager> {
+        match pattern {
+            CoordinationPattern::RoundRobin { .. } => Arc::new(RoundRobinManager),
+            CoordinationPattern::Dynamic { .. } => Arc::new(DynamicManager::new(Arc::new(
+                DefaultSelectorRegistry::new(),
+            ))),
+            CoordinationPattern::Pipeline { .. } => Arc::new(PipelineManager),
+            CoordinationPattern::Supervisor { .. } => Arc::new(SupervisorManager),
+            CoordinationPattern::Voting { .. } => Arc::new(VotingManager),
+            CoordinationPattern::Sleeptime { .. } => Arc::new(
+                SleeptimeManager::new(Arc::new(DefaultTriggerEvaluatorRegistry::new()))
+                    .with_activity_tracker(self.activity.clone()),
+            ),
+        }
+    }
+}
+
+/// Vote tools registered for one voting round, removed again when dropped
+///
+/// Registries that already had a vote tool are left alone, so an agent's own
+/// tool or an overlapping round keeps it.
+#[derive(Default)]
+struct VoteToolGuard {
+    registries: Vec<ToolRegistry>,
+}
+
+impl VoteToolGuard {
+    fn register(&mut self, tools: &ToolRegistry) {
+        if tools.get(VOTE_TOOL_NAME).is_none() {
+            tools.register(VoteTool::new());
+            self.registries.push(tools.clone());
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.registries.is_empty()
+    }
+}
+
+impl Drop for VoteToolGuard {
+    fn drop(&mut self) {
+        for tools in &self.registries {
+            tools.unregister(VOTE_TOOL_NAME);
+        }
+    }
+}
+
+const VOTE_TOOL_NAME: &str = "vote";
+
+/// Providers with a key in the environment or in stored configuration
+fn available_endpoints(
+    credentials: &DashMap<String, String>,
+    disabled: &[&str],
+) -> Vec<AdapterKind> {
+    PROVIDERS
+        .iter()
+        .filter(|(_, name, env)| {
+            !disabled.contains(name)
+                && (credentials.contains_key(*name) || std::env::var(env).is_ok())
+        })
+        .map(|(kind, _, _)| *kind)
+        .collect()
+}
+
+/// Build a genai client that prefers stored API keys over the environment
+fn build_genai_client(credentials: Arc<DashMap<String, String>>) -> genai::Client {
+    let resolver_fn = move |model_iden: ModelIden| -> Pin<
+        Box<dyn Future<Output = ResolverResult<Option<AuthData>>> + Send>,
+    > {
+        let key = provider_name(model_iden.adapter_kind)
+            .and_then(|name| credentials.get(name).map(|k| k.clone()));
+        // Fall back to None to let genai use its default (environment) resolution
+        Box::pin(async move { Ok(key.map(AuthData::Key)) })
+    };
+
+    genai::Client::builder()
+        .with_auth_resolver(AuthResolver::from_resolver_async_fn(resolver_fn))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vote_tool_only_lasts_for_the_round() {
+        let member = ToolRegistry::new();
+        let voter = ToolRegistry::new();
+        voter.register(VoteTool::new());
+
+        let mut guard = VoteToolGuard::default();
+        guard.register(&member);
+        guard.register(&voter);
+        assert!(member.get(VOTE_TOOL_NAME).is_some());
+
+        drop(guard);
+        assert!(member.get(VOTE_TOOL_NAME).is_none());
+        // A tool the agent already had is left in place
+        assert!(voter.get(VOTE_TOOL_NAME).is_some());
+    }
+}
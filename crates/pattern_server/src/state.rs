@@ -1,6 +1,13 @@
 //! Application state
 
-use crate::{config::ServerConfig, error::ServerResult};
+use std::{sync::Arc, time::Instant};
+
+use crate::{
+    auth::SecretCipher, config::ServerConfig, error::ServerResult, events::EventHub,
+    mcp::McpConnections, middleware::RateLimiter, models::ModelProviderRecord,
+    runtime::AgentRuntime,
+};
+use pattern_core::db::ops;
 use surrealdb::{Surreal, engine::any::Any};
 
 #[derive(Clone)]
@@ -9,6 +16,12 @@ pub struct AppState {
     pub db: Surreal<Any>,
     pub jwt_encoding_key: jsonwebtoken::EncodingKey,
     pub jwt_decoding_key: jsonwebtoken::DecodingKey,
+    pub secrets: SecretCipher,
+    pub runtime: Arc<AgentRuntime>,
+    pub events: Arc<EventHub>,
+    pub mcp: Arc<McpConnections>,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub started_at: Instant,
 }
 
 impl AppState {
@@ -29,15 +42,45 @@ impl AppState {
             ))
         })?;
 
+        // Standing permission grants and the audit log live in the server's
+        // database, not only in memory
+        pattern_core::permission::broker()
+            .attach_db(db.clone())
+            .await;
+
+        // Load the permission policy before any agent can call a tool
+        if let Some(path) = &config.policy {
+            pattern_core::policy::install_file(path).await?;
+        }
+
         // Create JWT keys
         let jwt_encoding_key = jsonwebtoken::EncodingKey::from_secret(config.jwt_secret.as_bytes());
         let jwt_decoding_key = jsonwebtoken::DecodingKey::from_secret(config.jwt_secret.as_bytes());
 
+        let secrets = SecretCipher::new(&config.credentials_secret);
+
+        // Bring up the agent runtime with any stored provider configuration
+        let events = Arc::new(EventHub::default());
+        let runtime = Arc::new(AgentRuntime::new(db.clone(), events.clone()));
+        let providers = ops::list_entities::<ModelProviderRecord, _>(&db).await?;
+        runtime.apply_providers(&providers, &secrets).await;
+
+        let mcp = Arc::new(McpConnections::new(db.clone()));
+        if let Err(e) = mcp.clone().restore().await {
+            tracing::warn!("Failed to restore MCP server connections: {}", e);
+        }
+
         Ok(Self {
             config,
             db,
             jwt_encoding_key,
             jwt_decoding_key,
+            secrets,
+            runtime,
+            events,
+            mcp,
+            rate_limiter: Arc::new(RateLimiter::default()),
+            started_at: Instant::now(),
         })
     }
 }